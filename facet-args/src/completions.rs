@@ -0,0 +1,550 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use facet_core::{Def, Facet, Field, Shape, Type, UserType, Variant};
+
+use crate::help;
+
+/// A shell to generate a completion script for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    /// Bash, with `complete -F`
+    Bash,
+    /// Zsh, as a `#compdef` function
+    Zsh,
+    /// Fish, with `complete -c`
+    Fish,
+}
+
+/// Generates the completion script for `shell` of the program whose arguments are `T`.
+///
+/// It completes the flags of `T` and their short forms, the subcommands and their own flags,
+/// the variants of enum-typed values, and files for `PathBuf` values (and `Utf8PathBuf`, with
+/// the `camino` feature).
+pub fn completions<'a, T: Facet<'a>>(shell: Shell) -> String {
+    generate_completions(T::SHAPE, shell)
+}
+
+/// Generates the completion script for `shell` of the arguments described by `shape`.
+pub(crate) fn generate_completions(shape: &Shape, shell: Shell) -> String {
+    let name = help::program_name(shape);
+    let version = help::generate_version(shape).is_some();
+    let command = Command::new(Vec::from([name]), shape.doc, help::fields(shape), version);
+    match shell {
+        Shell::Bash => bash(&command),
+        Shell::Zsh => zsh(&command),
+        Shell::Fish => fish(&command),
+    }
+}
+
+/// What a value can be completed with.
+#[derive(Debug, PartialEq)]
+enum Value {
+    /// Anything, so nothing is suggested
+    Any,
+    /// A path to a file
+    File,
+    /// One of the names of the variants of an enum
+    Choices(Vec<String>),
+}
+
+impl Value {
+    /// What a value of `shape` (or each value of the option or list `shape`) completes with.
+    fn of(shape: &Shape) -> Self {
+        let shape = match shape.def {
+            Def::Option(option_def) => option_def.t(),
+            Def::List(list_def) => list_def.t(),
+            _ => shape,
+        };
+        if is_path(shape) {
+            return Value::File;
+        }
+        match &shape.ty {
+            Type::User(UserType::Enum(enum_type))
+                if enum_type
+                    .variants
+                    .iter()
+                    .all(|variant| variant.data.fields.is_empty()) =>
+            {
+                Value::Choices(
+                    enum_type
+                        .variants
+                        .iter()
+                        .map(help::subcommand_name)
+                        .collect(),
+                )
+            }
+            _ => Value::Any,
+        }
+    }
+}
+
+/// Whether `shape` is a path, which completes with files.
+fn is_path(shape: &Shape) -> bool {
+    #[cfg(feature = "camino")]
+    if shape.is_type::<camino::Utf8PathBuf>() {
+        return true;
+    }
+    shape.is_type::<std::path::PathBuf>()
+}
+
+/// A flag, as completions see it.
+struct Opt {
+    short: Option<char>,
+    long: String,
+    about: String,
+    /// What follows the flag, if it takes a value
+    value: Option<Value>,
+    /// Whether the flag can be given more than once
+    repeats: bool,
+}
+
+/// A positional argument, as completions see it.
+struct Positional {
+    name: String,
+    value: Value,
+    /// Whether it takes all the arguments that follow
+    many: bool,
+}
+
+/// The program or one of its subcommands, with everything it completes.
+struct Command {
+    /// The names leading to it, from the program's
+    path: Vec<String>,
+    about: String,
+    options: Vec<Opt>,
+    positionals: Vec<Positional>,
+    subcommands: Vec<Command>,
+}
+
+/// The first line of a doc comment.
+fn about(doc: &[&str]) -> String {
+    doc.first()
+        .map(|line| line.trim())
+        .unwrap_or("")
+        .to_string()
+}
+
+/// The fields of a subcommand: those of a struct-like variant, or of the struct a newtype
+/// variant wraps.
+fn variant_fields<'shape>(variant: &Variant<'shape>) -> &'shape [Field<'shape>] {
+    match variant.data.fields {
+        [field] if matches!(field.shape().ty, Type::User(UserType::Struct(_))) => {
+            help::fields(field.shape())
+        }
+        fields => fields,
+    }
+}
+
+impl Command {
+    fn new(path: Vec<String>, doc: &[&str], fields: &[Field], has_version: bool) -> Self {
+        let mut command = Command {
+            path,
+            about: about(doc),
+            options: Vec::new(),
+            positionals: Vec::new(),
+            subcommands: Vec::new(),
+        };
+        for field in fields {
+            if field.should_skip_deserializing() {
+                continue;
+            }
+            if let Some(enum_type) = help::subcommand_enum(field) {
+                for variant in enum_type.variants {
+                    let mut path = command.path.clone();
+                    path.push(help::subcommand_name(variant));
+                    let subcommand =
+                        Command::new(path, variant.doc, variant_fields(variant), false);
+                    command.subcommands.push(subcommand);
+                }
+                continue;
+            }
+            if help::is_positional(field) {
+                command.positionals.push(Positional {
+                    name: field.name.to_string(),
+                    value: Value::of(field.shape()),
+                    many: help::is_list(field.shape()),
+                });
+                continue;
+            }
+            let counted = help::is_counted(field);
+            let flag = field.shape().is_type::<bool>() || counted;
+            command.options.push(Opt {
+                short: help::short_flag(field),
+                long: help::long_flag(field),
+                about: about(field.doc),
+                value: (!flag).then(|| Value::of(field.shape())),
+                repeats: counted || help::is_list(field.shape()),
+            });
+        }
+        let mut builtin = |short: char, long: &str, about: &str| {
+            if help::claims_long(fields, long) {
+                return;
+            }
+            command.options.push(Opt {
+                short: (!help::claims_short(fields, short)).then_some(short),
+                long: long.to_string(),
+                about: about.to_string(),
+                value: None,
+                repeats: false,
+            });
+        };
+        builtin('h', "help", "Print help");
+        if has_version {
+            builtin('V', "version", "Print version");
+        }
+        command
+    }
+
+    /// A name for the command that shell functions and variables can use.
+    fn ident(&self) -> String {
+        self.path.join("__").replace('-', "_")
+    }
+
+    /// The program name.
+    fn program(&self) -> &str {
+        &self.path[0]
+    }
+
+    /// The name the command goes by on the command line.
+    fn name(&self) -> &str {
+        self.path.last().map(String::as_str).unwrap_or_default()
+    }
+
+    /// The command and all its subcommands, depth first.
+    fn all(&self) -> Vec<&Command> {
+        let mut all = Vec::from([self]);
+        for subcommand in &self.subcommands {
+            all.extend(subcommand.all());
+        }
+        all
+    }
+}
+
+fn bash(command: &Command) -> String {
+    let mut out = String::new();
+    let function = format!("_{}", command.ident());
+    writeln!(out, "{function}() {{").unwrap();
+    writeln!(out, "    local cur prev cmd").unwrap();
+    writeln!(out, "    cur=\"${{COMP_WORDS[COMP_CWORD]}}\"").unwrap();
+    writeln!(out, "    prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"").unwrap();
+    writeln!(out, "    cmd=\"{}\"", command.ident()).unwrap();
+
+    // Follow the subcommands given so far
+    let all = command.all();
+    if all.len() > 1 {
+        writeln!(
+            out,
+            "    for word in \"${{COMP_WORDS[@]:1:COMP_CWORD-1}}\"; do"
+        )
+        .unwrap();
+        writeln!(out, "        case \"${{cmd}},${{word}}\" in").unwrap();
+        for parent in &all {
+            for subcommand in &parent.subcommands {
+                writeln!(
+                    out,
+                    "            \"{},{}\") cmd=\"{}\" ;;",
+                    parent.ident(),
+                    subcommand.name(),
+                    subcommand.ident()
+                )
+                .unwrap();
+            }
+        }
+        writeln!(out, "        esac").unwrap();
+        writeln!(out, "    done").unwrap();
+    }
+
+    writeln!(out).unwrap();
+    writeln!(out, "    case \"${{cmd}}\" in").unwrap();
+    for command in &all {
+        writeln!(out, "        {})", command.ident()).unwrap();
+
+        // The value of the flag just before
+        let with_values = command
+            .options
+            .iter()
+            .filter_map(|opt| Some((opt, opt.value.as_ref()?)))
+            .collect::<Vec<_>>();
+        if !with_values.is_empty() {
+            writeln!(out, "            case \"${{prev}}\" in").unwrap();
+            for (opt, value) in with_values {
+                let mut flags = format!("--{}", opt.long);
+                if let Some(short) = opt.short {
+                    flags.push_str(&format!("|-{short}"));
+                }
+                writeln!(out, "                {flags})").unwrap();
+                match value {
+                    Value::Any => writeln!(out, "                    COMPREPLY=()").unwrap(),
+                    Value::File => writeln!(
+                        out,
+                        "                    COMPREPLY=($(compgen -f -- \"${{cur}}\"))"
+                    )
+                    .unwrap(),
+                    Value::Choices(choices) => writeln!(
+                        out,
+                        "                    COMPREPLY=($(compgen -W \"{}\" -- \"${{cur}}\"))",
+                        choices.join(" ")
+                    )
+                    .unwrap(),
+                }
+                writeln!(out, "                    return 0").unwrap();
+                writeln!(out, "                    ;;").unwrap();
+            }
+            writeln!(out, "            esac").unwrap();
+        }
+
+        // Otherwise, its flags, its subcommands and the values of its positional arguments
+        let mut words = Vec::new();
+        for opt in &command.options {
+            if let Some(short) = opt.short {
+                words.push(format!("-{short}"));
+            }
+            words.push(format!("--{}", opt.long));
+        }
+        for positional in &command.positionals {
+            if let Value::Choices(choices) = &positional.value {
+                words.extend(choices.iter().cloned());
+            }
+        }
+        words.extend(command.subcommands.iter().map(|s| s.name().to_string()));
+        writeln!(
+            out,
+            "            COMPREPLY=($(compgen -W \"{}\" -- \"${{cur}}\"))",
+            words.join(" ")
+        )
+        .unwrap();
+        if command.positionals.iter().any(|p| p.value == Value::File) {
+            writeln!(out, "            if [[ \"${{cur}}\" != -* ]]; then").unwrap();
+            writeln!(
+                out,
+                "                COMPREPLY+=($(compgen -f -- \"${{cur}}\"))"
+            )
+            .unwrap();
+            writeln!(out, "            fi").unwrap();
+        }
+        writeln!(out, "            ;;").unwrap();
+    }
+    writeln!(out, "    esac").unwrap();
+    writeln!(out, "}}").unwrap();
+    writeln!(out).unwrap();
+    writeln!(
+        out,
+        "complete -F {function} -o bashdefault -o default {}",
+        command.program()
+    )
+    .unwrap();
+    out
+}
+
+/// Escapes text for a single-quoted zsh string.
+fn zsh_quote(text: &str) -> String {
+    text.replace('\'', "'\\''")
+}
+
+/// Escapes text for the description of a zsh `_arguments` spec, in brackets.
+fn zsh_describe(text: &str) -> String {
+    zsh_quote(
+        &text
+            .replace('\\', "\\\\")
+            .replace('[', "\\[")
+            .replace(']', "\\]")
+            .replace(':', "\\:"),
+    )
+}
+
+/// The zsh action completing `value`.
+fn zsh_action(value: &Value) -> String {
+    match value {
+        Value::Any => " ".to_string(),
+        Value::File => "_files".to_string(),
+        Value::Choices(choices) => format!("({})", choices.join(" ")),
+    }
+}
+
+fn zsh(command: &Command) -> String {
+    let mut out = String::new();
+    writeln!(out, "#compdef {}", command.program()).unwrap();
+    // Subcommands are defined first, so each function only calls ones defined before it
+    let mut all = command.all();
+    all.reverse();
+    for command in all {
+        writeln!(out).unwrap();
+        zsh_function(&mut out, command);
+    }
+    let function = format!("_{}", command.ident());
+    writeln!(out).unwrap();
+    writeln!(out, "if [ \"$funcstack[1]\" = \"{function}\" ]; then").unwrap();
+    writeln!(out, "    {function} \"$@\"").unwrap();
+    writeln!(out, "else").unwrap();
+    writeln!(out, "    compdef {function} {}", command.program()).unwrap();
+    writeln!(out, "fi").unwrap();
+    out
+}
+
+fn zsh_function(out: &mut String, command: &Command) {
+    let mut specs = Vec::new();
+    for opt in &command.options {
+        let about = format!("[{}]", zsh_describe(&opt.about));
+        let (short, long) = match opt.value {
+            Some(_) => (
+                opt.short.map(|c| format!("-{c}+")),
+                format!("--{}=", opt.long),
+            ),
+            None => (
+                opt.short.map(|c| format!("-{c}")),
+                format!("--{}", opt.long),
+            ),
+        };
+        let value = match &opt.value {
+            Some(value) => format!(":{}:{}", opt.long, zsh_quote(&zsh_action(value))),
+            None => String::new(),
+        };
+        let spec = match (short, opt.repeats) {
+            (Some(short), true) => format!("'*'{{{short},{long}}}'{about}{value}'"),
+            (Some(short), false) => {
+                let c = opt.short.unwrap_or_default();
+                format!("'(-{c} --{})'{{{short},{long}}}'{about}{value}'", opt.long)
+            }
+            (None, true) => format!("'*{long}{about}{value}'"),
+            (None, false) => format!("'{long}{about}{value}'"),
+        };
+        specs.push(spec);
+    }
+    for positional in &command.positionals {
+        let many = if positional.many { "*" } else { "" };
+        specs.push(format!(
+            "'{many}:{}:{}'",
+            positional.name,
+            zsh_quote(&zsh_action(&positional.value))
+        ));
+    }
+    if !command.subcommands.is_empty() {
+        specs.push("':command:->command'".to_string());
+        specs.push("'*::arg:->args'".to_string());
+    }
+
+    writeln!(out, "_{}() {{", command.ident()).unwrap();
+    if !command.subcommands.is_empty() {
+        writeln!(out, "    local context state state_descr line").unwrap();
+        writeln!(out, "    typeset -A opt_args").unwrap();
+        writeln!(out).unwrap();
+    }
+    write!(out, "    _arguments -s -S -C").unwrap();
+    for spec in specs {
+        write!(out, " \\\n        {spec}").unwrap();
+    }
+    writeln!(out).unwrap();
+    if !command.subcommands.is_empty() {
+        writeln!(out).unwrap();
+        writeln!(out, "    case $state in").unwrap();
+        writeln!(out, "        command)").unwrap();
+        writeln!(out, "            local -a commands").unwrap();
+        writeln!(out, "            commands=(").unwrap();
+        for subcommand in &command.subcommands {
+            writeln!(
+                out,
+                "                '{}:{}'",
+                subcommand.name(),
+                zsh_quote(&subcommand.about)
+            )
+            .unwrap();
+        }
+        writeln!(out, "            )").unwrap();
+        writeln!(out, "            _describe -t commands 'command' commands").unwrap();
+        writeln!(out, "            ;;").unwrap();
+        writeln!(out, "        args)").unwrap();
+        writeln!(out, "            case $words[1] in").unwrap();
+        for subcommand in &command.subcommands {
+            writeln!(
+                out,
+                "                {}) _{} ;;",
+                subcommand.name(),
+                subcommand.ident()
+            )
+            .unwrap();
+        }
+        writeln!(out, "            esac").unwrap();
+        writeln!(out, "            ;;").unwrap();
+        writeln!(out, "    esac").unwrap();
+    }
+    writeln!(out, "}}").unwrap();
+}
+
+/// Escapes text for a single-quoted fish string.
+fn fish_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// The fish condition under which the completions of `command` apply: its subcommand path was
+/// given, and none of its own subcommands yet.
+fn fish_condition(command: &Command) -> Option<String> {
+    let subcommands = command
+        .subcommands
+        .iter()
+        .map(Command::name)
+        .collect::<Vec<_>>()
+        .join(" ");
+    if command.path.len() == 1 {
+        return (!subcommands.is_empty()).then(|| "__fish_use_subcommand".to_string());
+    }
+    let mut condition = command.path[1..]
+        .iter()
+        .map(|name| format!("__fish_seen_subcommand_from {name}"))
+        .collect::<Vec<_>>()
+        .join("; and ");
+    if !subcommands.is_empty() {
+        condition.push_str(&format!(
+            "; and not __fish_seen_subcommand_from {subcommands}"
+        ));
+    }
+    Some(condition)
+}
+
+fn fish(command: &Command) -> String {
+    let mut out = String::new();
+    for command in command.all() {
+        let mut prefix = format!("complete -c {}", command.program());
+        if let Some(condition) = fish_condition(command) {
+            prefix.push_str(&format!(" -n \"{condition}\""));
+        }
+        for opt in &command.options {
+            let mut line = prefix.clone();
+            if let Some(short) = opt.short {
+                line.push_str(&format!(" -s {short}"));
+            }
+            line.push_str(&format!(" -l {}", opt.long));
+            match &opt.value {
+                None => {}
+                Some(Value::Any) => line.push_str(" -r"),
+                Some(Value::File) => line.push_str(" -r -F"),
+                Some(Value::Choices(choices)) => {
+                    line.push_str(&format!(" -r -f -a \"{}\"", choices.join(" ")))
+                }
+            }
+            if !opt.about.is_empty() {
+                line.push_str(&format!(" -d {}", fish_quote(&opt.about)));
+            }
+            writeln!(out, "{line}").unwrap();
+        }
+        for positional in &command.positionals {
+            match &positional.value {
+                Value::Any => {}
+                Value::File => writeln!(out, "{prefix} -F").unwrap(),
+                Value::Choices(choices) => {
+                    writeln!(out, "{prefix} -f -a \"{}\"", choices.join(" ")).unwrap()
+                }
+            }
+        }
+        for subcommand in &command.subcommands {
+            let mut line = format!("{prefix} -f -a {}", subcommand.name());
+            if !subcommand.about.is_empty() {
+                line.push_str(&format!(" -d {}", fish_quote(&subcommand.about)));
+            }
+            writeln!(out, "{line}").unwrap();
+        }
+    }
+    out
+}
@@ -0,0 +1,63 @@
+use alloc::string::String;
+use core::fmt;
+
+use facet_deserialize::DeserError;
+
+/// Why parsing arguments didn't give a value: they asked for help or for the version, or
+/// they don't make a value of the type.
+#[derive(Debug)]
+pub enum ArgsError<'input, 'shape> {
+    /// `--help` or `-h` was given. Holds the help text, which is all the error displays.
+    HelpRequested(String),
+
+    /// `--version` or `-V` was given. Holds the text to display.
+    VersionRequested(String),
+
+    /// The arguments couldn't be parsed into the type.
+    Deser(DeserError<'input, 'shape>),
+}
+
+impl ArgsError<'_, '_> {
+    /// Whether the arguments asked for help or version text instead of a value, which should
+    /// be shown as is rather than reported as a failure.
+    pub fn is_requested_output(&self) -> bool {
+        matches!(
+            self,
+            ArgsError::HelpRequested(_) | ArgsError::VersionRequested(_)
+        )
+    }
+}
+
+impl<'input, 'shape> From<DeserError<'input, 'shape>> for ArgsError<'input, 'shape> {
+    fn from(err: DeserError<'input, 'shape>) -> Self {
+        ArgsError::Deser(err)
+    }
+}
+
+impl fmt::Display for ArgsError<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgsError::HelpRequested(text) | ArgsError::VersionRequested(text) => {
+                write!(f, "{text}")
+            }
+            ArgsError::Deser(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl core::error::Error for ArgsError<'_, '_> {}
+
+/// What the arguments asked for instead of a value, found while reading them.
+pub(crate) enum Requested {
+    Help(String),
+    Version(String),
+}
+
+impl Requested {
+    pub(crate) fn into_error<'input, 'shape>(self) -> ArgsError<'input, 'shape> {
+        match self {
+            Requested::Help(text) => ArgsError::HelpRequested(text),
+            Requested::Version(text) => ArgsError::VersionRequested(text),
+        }
+    }
+}
@@ -1,14 +1,31 @@
 use alloc::borrow::Cow;
+use alloc::collections::VecDeque;
+use alloc::format;
 use alloc::string::ToString;
+use alloc::vec::Vec;
 use core::fmt;
-use facet_core::{Facet, FieldAttribute, Type, UserType};
+use core::ops::Range;
+use facet_core::{
+    Def, EnumType, Facet, Field, FieldFlags, ScalarAffinity, ScalarDef, Shape, Type, UserType,
+    Variant,
+};
 use facet_deserialize::{
-    DeserError, DeserErrorKind, Expectation, Format, NextData, NextResult, Outcome, Scalar, Span,
-    Spanned,
+    DeserErrorKind, Expectation, Format, NextData, NextResult, Outcome, Scalar, Span, Spanned,
 };
 
+use crate::error::{ArgsError, Requested};
+use crate::help;
+use crate::layers::Layering;
+
 /// Command-line argument format for Facet deserialization
-pub struct Cli;
+///
+/// Flags given several times, counted flags and `--flag=value` need what was read of earlier
+/// arguments, which it keeps track of, so a `Cli` reads a single command line. `--help` and
+/// `--version` stop it with an error: [`from_slice`] returns the text they ask for instead.
+#[derive(Default)]
+pub struct Cli {
+    pub(crate) state: CliState,
+}
 
 impl fmt::Display for Cli {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -74,28 +91,359 @@ impl Cli {
 
         Span::new(effective_pos, len)
     }
+
+    /// Returns the help or version text if `arg` asks for it, unless a field takes that
+    /// flag for itself. In a subcommand, that's the help of the subcommand. The text is only
+    /// built once it's asked for.
+    fn requested_output<'shape>(
+        shape: &'shape Shape<'shape>,
+        variant: Option<Variant<'shape>>,
+        arg: &str,
+    ) -> Option<Requested> {
+        let (fields, subcommand) = match (&shape.ty, variant) {
+            (Type::User(UserType::Struct(st)), _) => (st.fields, None),
+            (Type::User(UserType::Enum(_)), Some(variant)) => (variant.data.fields, Some(variant)),
+            _ => return None,
+        };
+        let help = match arg {
+            "--help" => !help::claims_long(fields, "help"),
+            "-h" => !help::claims_short(fields, 'h'),
+            _ => false,
+        };
+        if help {
+            let text = match subcommand {
+                Some(variant) => help::generate_subcommand_help(&variant),
+                None => help::generate_help(shape),
+            };
+            return Some(Requested::Help(text));
+        }
+        let version = match arg {
+            "--version" => !help::claims_long(fields, "version"),
+            "-V" => !help::claims_short(fields, 'V'),
+            _ => false,
+        };
+        if version && subcommand.is_none() {
+            return help::generate_version(shape).map(Requested::Version);
+        }
+        None
+    }
+
+    /// The fields that flags and positional arguments go to: those of the struct being
+    /// deserialized, or of the subcommand variant that was picked.
+    pub(crate) fn target_fields<'shape>(
+        shape: &'shape Shape<'shape>,
+        variant: Option<Variant<'shape>>,
+    ) -> &'shape [Field<'shape>] {
+        match shape.ty {
+            Type::User(UserType::Struct(st)) => st.fields,
+            Type::User(UserType::Enum(_)) => variant
+                .map(|variant| variant.data.fields)
+                .unwrap_or_default(),
+            _ => &[],
+        }
+    }
+
+    /// Finds the variant of the enum `shape` (or of the enum in the option `shape`) named by
+    /// the argument at `arg_idx`.
+    fn find_variant<'shape>(
+        args: &[&str],
+        arg_idx: usize,
+        shape: &'shape Shape<'shape>,
+        enum_type: &'shape EnumType<'shape>,
+    ) -> Result<&'shape Variant<'shape>, Spanned<DeserErrorKind<'shape>>> {
+        let Some(&arg) = args.get(arg_idx) else {
+            return Err(Spanned {
+                node: DeserErrorKind::MissingValue {
+                    expected: "subcommand",
+                    field: shape.to_string(),
+                },
+                span: Self::char_span(args, arg_idx, Some(0), None),
+            });
+        };
+        enum_type
+            .variants
+            .iter()
+            .find(|variant| variant.name == arg || help::subcommand_name(variant) == arg)
+            .ok_or_else(|| Spanned {
+                node: DeserErrorKind::NoSuchVariant {
+                    name: arg.to_string(),
+                    enum_shape: shape,
+                },
+                span: Self::char_span(args, arg_idx, None, None),
+            })
+    }
+
+    /// The scalar a value given as text stands for, for a field of type `shape`.
+    pub(crate) fn scalar<'input>(shape: &Shape, value: Cow<'input, str>) -> Scalar<'input> {
+        // Strings take the value as is, even if it looks like a number
+        if is_string(shape) {
+            return Scalar::String(value);
+        }
+
+        // Try to parse as appropriate type
+        let shape = match shape.def {
+            Def::Option(option_def) => option_def.t(),
+            _ => shape,
+        };
+        if shape.is_type::<bool>() {
+            if let Ok(v) = value.parse::<bool>() {
+                return Scalar::Bool(v);
+            }
+        }
+        // Handle numeric types
+        if let Ok(v) = value.parse::<u64>() {
+            return Scalar::U64(v);
+        }
+        if let Ok(v) = value.parse::<i64>() {
+            return Scalar::I64(v);
+        }
+        if let Ok(v) = value.parse::<f64>() {
+            return Scalar::F64(v);
+        }
+
+        // Default to string type
+        Scalar::String(value)
+    }
+}
+
+/// Whether `shape` (or the value in the option `shape`) is a string.
+fn is_string(shape: &Shape) -> bool {
+    let shape = match shape.def {
+        Def::Option(option_def) => option_def.t(),
+        _ => shape,
+    };
+    matches!(
+        shape.def,
+        Def::Scalar(ScalarDef {
+            affinity: ScalarAffinity::String(_),
+            ..
+        })
+    )
+}
+
+/// The enum type of `shape`, looking through an option.
+fn enum_type<'shape>(shape: &'shape Shape<'shape>) -> Option<&'shape EnumType<'shape>> {
+    let shape = match shape.def {
+        Def::Option(option_def) => option_def.t(),
+        _ => shape,
+    };
+    match &shape.ty {
+        Type::User(UserType::Enum(enum_type)) => Some(enum_type),
+        _ => None,
+    }
+}
+
+/// What the command line format remembers from one argument to the next, for the flags whose
+/// value isn't simply the argument after them.
+#[derive(Default)]
+pub(crate) struct CliState {
+    /// The value of the `--flag=value` just read, as its argument and where it starts in it
+    inline: Option<(usize, usize)>,
+    /// Whether the flag just read was a `--no-flag`
+    negated: bool,
+    /// How many times the counted flag just read was given
+    count: Option<u64>,
+    /// The values of the list being read
+    list: Option<ListValues>,
+    /// The arguments read already, with the first occurrence of their flag
+    consumed: Vec<usize>,
+    /// What the arguments asked for instead of a value, like the help for `--help`
+    pub(crate) requested: Option<Requested>,
+}
+
+/// Every value given to a list flag, gathered from all its occurrences.
+struct ListValues {
+    /// The values left, as their argument and where they are in it
+    values: VecDeque<(usize, Range<usize>)>,
+    /// The argument to go on from once the list is read: the one after the first occurrence
+    resume: usize,
+}
+
+impl CliState {
+    /// The first argument from `arg_idx` on that wasn't read already.
+    pub(crate) fn first_unread(&self, mut arg_idx: usize) -> usize {
+        while self.consumed.contains(&arg_idx) {
+            arg_idx += 1;
+        }
+        arg_idx
+    }
+}
+
+/// How many times `arg` gives the flag of `field` (`-vvv` is three times for a counted
+/// flag), and where the value starts in it for `--flag=value`.
+fn flag_uses(field: &Field, arg: &str) -> Option<(u64, Option<usize>)> {
+    let (flag, value) = match arg.split_once('=') {
+        Some((flag, _)) => (flag, Some(flag.len() + 1)),
+        None => (arg, None),
+    };
+    if let Some(long) = flag.strip_prefix("--") {
+        return (Cli::kebab_to_snake(long) == field.name).then_some((1, value));
+    }
+    let shorts = flag.strip_prefix('-')?;
+    let c = help::short_flag(field)?;
+    let count = shorts.chars().count();
+    let repeats = count == 1 || (value.is_none() && help::is_counted(field));
+    (count > 0 && repeats && shorts.chars().all(|s| s == c)).then_some((count as u64, value))
+}
+
+/// Where the arguments of the subcommand of `fields` start, looking from `arg_idx` on: at the
+/// first argument naming one of its variants that isn't the value of a flag. The flags before
+/// it are the only ones `fields` can take.
+fn subcommand_start(fields: &[Field], args: &[&str], arg_idx: usize) -> usize {
+    let subcommands: Vec<_> = fields.iter().filter_map(help::subcommand_enum).collect();
+    if subcommands.is_empty() {
+        return args.len();
+    }
+    let mut idx = arg_idx;
+    while idx < args.len() {
+        let arg = args[idx];
+        if arg.starts_with('-') {
+            // The value of a flag isn't a subcommand, even if it's named like one
+            let takes_value = fields.iter().any(|field| {
+                flag_uses(field, arg).is_some_and(|(_, inline)| inline.is_none())
+                    && !field.shape().is_type::<bool>()
+                    && !help::is_counted(field)
+            });
+            idx += if takes_value { 2 } else { 1 };
+            continue;
+        }
+        let names_variant = subcommands.iter().any(|enum_type| {
+            enum_type
+                .variants
+                .iter()
+                .any(|variant| variant.name == arg || help::subcommand_name(variant) == arg)
+        });
+        if names_variant {
+            return idx;
+        }
+        idx += 1;
+    }
+    args.len()
 }
 
 /// Parse command line arguments into a Facet-compatible type
+///
+/// `--help` and `--version` make it return [`ArgsError::HelpRequested`] and
+/// [`ArgsError::VersionRequested`], with the text to print.
 pub fn from_slice<'input, 'facet, 'shape, T: Facet<'facet>>(
     args: &'input [&'input str],
-) -> Result<T, DeserError<'input, 'shape>>
+) -> Result<T, ArgsError<'input, 'shape>>
 where
     'input: 'facet + 'shape,
 {
-    facet_deserialize::deserialize(args, Cli)
+    let mut layering = Layering::new(None);
+    let res = facet_deserialize::deserialize(args, &mut layering);
+    layering.finish(res)
 }
 
-impl Format for Cli {
-    type Input<'input> = [&'input str];
+impl Cli {
+    /// The key of the flag of `field`, one of `fields`, read at `arg_idx`, gathering the other
+    /// occurrences of the flag for counted flags and lists. Those of a subcommand are its own.
+    fn flag_key<'input, 'shape>(
+        state: &mut CliState,
+        args: &[&'input str],
+        arg_idx: usize,
+        fields: &[Field],
+        field: &'shape Field<'shape>,
+    ) -> Result<Spanned<Outcome<'input>>, Spanned<DeserErrorKind<'shape>>>
+    where
+        'shape: 'input,
+    {
+        let (_, inline) = flag_uses(field, args[arg_idx]).unwrap_or((1, None));
+        let end = subcommand_start(fields, args, arg_idx);
+        if help::is_list(field.shape()) {
+            state.list = Some(Self::list_values(state, args, arg_idx..end, field)?);
+        } else if let Some(offset) = inline {
+            state.inline = Some((arg_idx, offset));
+        } else if help::is_counted(field) {
+            state.count = Some(Self::count(state, args, arg_idx..end, field));
+        }
+        Ok(Spanned {
+            node: Outcome::Scalar(Scalar::String(Cow::Borrowed(field.name))),
+            span: Span::new(arg_idx, 1),
+        })
+    }
 
-    fn source(&self) -> &'static str {
-        "args"
+    /// How many times the counted flag of `field` is given in `range`, which starts at its
+    /// first occurrence.
+    fn count(state: &mut CliState, args: &[&str], range: Range<usize>, field: &Field) -> u64 {
+        let arg_idx = range.start;
+        let mut count = 0;
+        for (idx, arg) in args.iter().enumerate().take(range.end).skip(arg_idx) {
+            if state.consumed.contains(&idx) {
+                continue;
+            }
+            if let Some((times, None)) = flag_uses(field, arg) {
+                count += times;
+                if idx != arg_idx {
+                    state.consumed.push(idx);
+                }
+            }
+        }
+        count
     }
 
-    fn next<'input, 'facet, 'shape>(
-        &mut self,
-        nd: NextData<'input, 'facet, 'shape, Self::Input<'input>>,
+    /// The values of every occurrence of the list flag of `field` in `range`, which starts at
+    /// its first occurrence. Each value may hold several, if the field has a delimiter.
+    fn list_values<'shape>(
+        state: &mut CliState,
+        args: &[&str],
+        range: Range<usize>,
+        field: &Field,
+    ) -> Result<ListValues, Spanned<DeserErrorKind<'shape>>> {
+        let arg_idx = range.start;
+        let delimiter = help::delimiter(field);
+        let mut values = VecDeque::new();
+        let mut resume = arg_idx + 1;
+        let mut idx = arg_idx;
+        while idx < range.end {
+            let uses = (!state.consumed.contains(&idx))
+                .then(|| flag_uses(field, args[idx]))
+                .flatten();
+            let Some((_, inline)) = uses else {
+                idx += 1;
+                continue;
+            };
+            let (value_idx, start) = match inline {
+                Some(offset) => (idx, offset),
+                None => (idx + 1, 0),
+            };
+            match args.get(value_idx) {
+                Some(value) if inline.is_some() || !value.starts_with('-') => {}
+                _ => {
+                    return Err(Spanned {
+                        node: DeserErrorKind::MissingValue {
+                            expected: "argument value",
+                            field: args[idx].to_string(),
+                        },
+                        span: Self::char_span(args, value_idx, Some(1), Some(-1)),
+                    });
+                }
+            }
+            let value = &args[value_idx][start..];
+            let parts = match delimiter {
+                Some(delimiter) => value.split(delimiter).collect(),
+                None => Vec::from([value]),
+            };
+            let mut offset = start;
+            for part in parts {
+                values.push_back((value_idx, offset..offset + part.len()));
+                offset += part.len() + delimiter.map_or(0, char::len_utf8);
+            }
+            if idx == arg_idx {
+                resume = value_idx + 1;
+            } else {
+                state.consumed.extend(idx..=value_idx);
+            }
+            idx = value_idx + 1;
+        }
+        Ok(ListValues { values, resume })
+    }
+
+    /// Reads what `expectation` asks for, remembering what later arguments need in `state`.
+    pub(crate) fn next_with<'input, 'facet, 'shape>(
+        state: &mut CliState,
+        nd: NextData<'input, 'facet, 'shape, [&'input str]>,
         expectation: Expectation,
     ) -> NextResult<
         'input,
@@ -103,7 +451,7 @@ impl Format for Cli {
         'shape,
         Spanned<Outcome<'input>>,
         Spanned<DeserErrorKind<'shape>>,
-        Self::Input<'input>,
+        [&'input str],
     >
     where
         'shape: 'input,
@@ -140,104 +488,99 @@ impl Format for Cli {
 
             // Object key (or finished)
             Expectation::ObjectKeyOrObjectClose => {
+                // Occurrences of flags that were read with their first one are passed over
+                let arg_idx = state.first_unread(arg_idx);
+
+                // A subcommand: the variant it names is the only key of its object
+                if let Type::User(UserType::Enum(enum_type)) = &shape.ty {
+                    if nd.wip.selected_variant().is_none() {
+                        let res =
+                            Self::find_variant(args, arg_idx, shape, enum_type).map(|variant| {
+                                Spanned {
+                                    node: Outcome::Scalar(Scalar::String(Cow::Borrowed(
+                                        variant.name,
+                                    ))),
+                                    span: Span::new(arg_idx, 1),
+                                }
+                            });
+                        return (nd, res);
+                    }
+                }
+                let fields = Self::target_fields(shape, nd.wip.selected_variant());
+
                 /* Check if we have more arguments */
                 if arg_idx < args.len() {
                     let arg = args[arg_idx];
-                    let span = Span::new(arg_idx, 1);
-
-                    // Named long argument?
-                    if let Some(key) = arg.strip_prefix("--") {
-                        let key = Self::kebab_to_snake(key);
-
-                        // Check if the field exists in the struct
-                        if let Type::User(UserType::Struct(_)) = shape.ty {
-                            if nd.wip.field_index(&key).is_none() {
-                                return (
-                                    nd,
-                                    Err(Spanned {
-                                        node: DeserErrorKind::UnknownField {
-                                            field_name: key.to_string(),
-                                            shape,
-                                        },
-                                        span: Self::char_span(args, arg_idx, None, None),
-                                    }),
-                                );
-                            }
-                        }
+
+                    if let Some(requested) =
+                        Self::requested_output(shape, nd.wip.selected_variant(), arg)
+                    {
+                        // Reading stops here, and what was asked for is returned instead of
+                        // this error
+                        state.requested = Some(requested);
                         return (
                             nd,
-                            Ok(Spanned {
-                                node: Outcome::Scalar(Scalar::String(key)),
-                                span,
+                            Err(Spanned {
+                                node: DeserErrorKind::InvalidSyntax(format!(
+                                    "`{arg}` asks for output instead of a value"
+                                )),
+                                span: Self::char_span(args, arg_idx, None, None),
                             }),
                         );
                     }
 
-                    // Short flag?
-                    if let Some(key) = arg.strip_prefix('-') {
-                        // Convert short argument to field name via shape
-                        if let Type::User(UserType::Struct(st)) = shape.ty {
-                            for field in st.fields.iter() {
-                                for attr in field.attributes {
-                                    if let FieldAttribute::Arbitrary(a) = attr {
-                                        // Don't require specifying a short key for a single-char key
-                                        if a.contains("short")
-                                            && (a.contains(key)
-                                                || (key.len() == 1 && field.name == key))
-                                        {
-                                            return (
-                                                nd,
-                                                Ok(Spanned {
-                                                    node: Outcome::Scalar(Scalar::String(
-                                                        Cow::Borrowed(field.name),
-                                                    )),
-                                                    span,
-                                                }),
-                                            );
-                                        }
-                                    }
-                                }
-                            }
+                    if let Some(flag) = arg.strip_prefix('-') {
+                        let mut field = fields.iter().find(|f| flag_uses(f, arg).is_some());
+
+                        // `--no-flag` turns a flag off
+                        if field.is_none() {
+                            let negated = arg.strip_prefix("--no-").map(Self::kebab_to_snake);
+                            field = fields.iter().find(|f| {
+                                f.shape().is_type::<bool>() && negated.as_deref() == Some(f.name)
+                            });
+                            state.negated = field.is_some();
                         }
+
+                        let Some(field) = field else {
+                            let name = flag.split('=').next().unwrap_or_default();
+                            let field_name = match name.strip_prefix('-') {
+                                Some(long) => Self::kebab_to_snake(long).to_string(),
+                                None => name.to_string(),
+                            };
+                            return (
+                                nd,
+                                Err(Spanned {
+                                    node: DeserErrorKind::UnknownField { field_name, shape },
+                                    span: Self::char_span(args, arg_idx, None, None),
+                                }),
+                            );
+                        };
+                        let res = Self::flag_key(state, args, arg_idx, fields, field);
+                        return (nd, res);
+                    }
+
+                    // Positional argument: it goes to the first positional field that isn't
+                    // set yet, and once there are none left, to the subcommand
+                    let unset = |idx: usize| !nd.wip.is_field_set(idx).unwrap_or(false);
+                    let positional = fields
+                        .iter()
+                        .enumerate()
+                        .find(|&(idx, field)| help::is_positional(field) && unset(idx))
+                        .or_else(|| {
+                            fields.iter().enumerate().find(|&(idx, field)| {
+                                help::subcommand_enum(field).is_some() && unset(idx)
+                            })
+                        });
+                    if let Some((_, field)) = positional {
                         return (
                             nd,
-                            Err(Spanned {
-                                node: DeserErrorKind::UnknownField {
-                                    field_name: key.to_string(),
-                                    shape,
-                                },
-                                span: Self::char_span(args, arg_idx, None, None),
+                            Ok(Spanned {
+                                node: Outcome::Scalar(Scalar::String(Cow::Borrowed(field.name))),
+                                span: Span::new(arg_idx, 0),
                             }),
                         );
                     }
 
-                    // positional argument
-                    if let Type::User(UserType::Struct(st)) = &shape.ty {
-                        for (idx, field) in st.fields.iter().enumerate() {
-                            for attr in field.attributes.iter() {
-                                if let FieldAttribute::Arbitrary(a) = attr {
-                                    if a.contains("positional") {
-                                        // Check if this field is already set
-                                        let is_set = nd.wip.is_field_set(idx).unwrap_or(false);
-
-                                        if !is_set {
-                                            // Use this positional field
-                                            return (
-                                                nd,
-                                                Ok(Spanned {
-                                                    node: Outcome::Scalar(Scalar::String(
-                                                        Cow::Borrowed(field.name),
-                                                    )),
-                                                    span: Span::new(arg_idx, 0),
-                                                }),
-                                            );
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-
                     // If no positional field was found
                     return (
                         nd,
@@ -251,22 +594,25 @@ impl Format for Cli {
                     );
                 }
 
-                // EOF: inject implicit-false-if-absent bool flags, if there are any
-                if let Type::User(UserType::Struct(st)) = &shape.ty {
-                    for (idx, field) in st.fields.iter().enumerate() {
-                        if !nd.wip.is_field_set(idx).unwrap_or(false)
-                            && field.shape().is_type::<bool>()
-                        {
-                            return (
-                                nd,
-                                Ok(Spanned {
-                                    node: Outcome::Scalar(Scalar::String(Cow::Borrowed(
-                                        field.name,
-                                    ))),
-                                    span: Span::new(arg_idx, 0),
-                                }),
-                            );
-                        }
+                // EOF: inject implicit-false-if-absent bool flags, implicit-none options
+                // (subcommands included), empty lists and zero counts, if there are any and
+                // they have no default of their own. Their keys move past the last argument,
+                // so their values know they weren't given.
+                for (idx, field) in fields.iter().enumerate() {
+                    if !nd.wip.is_field_set(idx).unwrap_or(false)
+                        && !field.flags.contains(FieldFlags::DEFAULT)
+                        && (field.shape().is_type::<bool>()
+                            || matches!(field.shape().def, Def::Option(_))
+                            || help::is_list(field.shape())
+                            || help::is_counted(field))
+                    {
+                        return (
+                            nd,
+                            Ok(Spanned {
+                                node: Outcome::Scalar(Scalar::String(Cow::Borrowed(field.name))),
+                                span: Span::new(arg_idx, 1),
+                            }),
+                        );
                     }
                 }
 
@@ -282,24 +628,104 @@ impl Format for Cli {
 
             // Value for the current key
             Expectation::ObjectVal => {
-                // Synthetic implicit-false
-                if arg_idx >= args.len() && shape.is_type::<bool>() {
+                // Implicit values: implicit keys are read past the last argument, an explicit
+                // flag right at the end is given
+                if arg_idx > args.len() {
+                    let node = if shape.is_type::<bool>() {
+                        Outcome::Scalar(Scalar::Bool(false))
+                    } else if matches!(shape.def, Def::Option(_)) {
+                        Outcome::Scalar(Scalar::Null)
+                    } else if help::is_list(shape) {
+                        // Empty, as there are no arguments left for it
+                        Outcome::ListStarted
+                    } else {
+                        // A counted flag that wasn't given
+                        Outcome::Scalar(Scalar::U64(0))
+                    };
                     return (
                         nd,
                         Ok(Spanned {
-                            node: Outcome::Scalar(Scalar::Bool(false)),
+                            node,
                             span: Span::new(arg_idx, 0),
                         }),
                     );
                 }
 
-                // Explicit boolean true
+                // The value of a `--flag=value`
+                if let Some((idx, offset)) = state.inline.take() {
+                    let value = Cow::Borrowed(&args[idx][offset..]);
+                    return (
+                        nd,
+                        Ok(Spanned {
+                            node: Outcome::Scalar(Self::scalar(shape, value)),
+                            span: Span::new(idx, 1),
+                        }),
+                    );
+                }
+
+                if let Some(count) = state.count.take() {
+                    return (
+                        nd,
+                        Ok(Spanned {
+                            node: Outcome::Scalar(Scalar::U64(count)),
+                            span: Span::new(arg_idx, 0),
+                        }),
+                    );
+                }
+
+                if help::is_list(shape) {
+                    return (
+                        nd,
+                        Ok(Spanned {
+                            node: Outcome::ListStarted,
+                            span: Span::new(arg_idx, 0),
+                        }),
+                    );
+                }
+
+                // A subcommand, or a value picked among the variants of an enum
+                if let Some(enum_type) = enum_type(shape) {
+                    let node = if nd.wip.selected_variant().is_some() {
+                        // The flags of the subcommand
+                        Ok(Outcome::ObjectStarted)
+                    } else {
+                        Self::find_variant(args, arg_idx, shape, enum_type).map(|variant| {
+                            if variant.data.fields.is_empty() {
+                                Outcome::Scalar(Scalar::String(Cow::Borrowed(variant.name)))
+                            } else {
+                                Outcome::ObjectStarted
+                            }
+                        })
+                    };
+                    // Only a unit variant's name is a value in itself: the others are the
+                    // key of an object, read next
+                    let span = match node {
+                        Ok(Outcome::Scalar(Scalar::String(_))) => Span::new(arg_idx, 1),
+                        _ => Span::new(arg_idx, 0),
+                    };
+                    let res = node.map(|node| Spanned { node, span });
+                    return (nd, res);
+                }
+
+                // The arguments of a subcommand that wraps a struct
+                if let Type::User(UserType::Struct(_)) = shape.ty {
+                    return (
+                        nd,
+                        Ok(Spanned {
+                            node: Outcome::ObjectStarted,
+                            span: Span::new(arg_idx, 0),
+                        }),
+                    );
+                }
+
+                // Explicit boolean: true, unless it's a `--no-flag`
                 if shape.is_type::<bool>() {
                     // For boolean fields, we don't need an explicit value
+                    let value = !core::mem::take(&mut state.negated);
                     return (
                         nd,
                         Ok(Spanned {
-                            node: Outcome::Scalar(Scalar::Bool(true)),
+                            node: Outcome::Scalar(Scalar::Bool(value)),
                             span: Span::new(arg_idx, 0),
                         }),
                     );
@@ -338,41 +764,10 @@ impl Format for Cli {
                     );
                 }
 
-                // Try to parse as appropriate type
-                // Handle numeric types
-                if let Ok(v) = arg.parse::<u64>() {
-                    return (
-                        nd,
-                        Ok(Spanned {
-                            node: Outcome::Scalar(Scalar::U64(v)),
-                            span,
-                        }),
-                    );
-                }
-                if let Ok(v) = arg.parse::<i64>() {
-                    return (
-                        nd,
-                        Ok(Spanned {
-                            node: Outcome::Scalar(Scalar::I64(v)),
-                            span,
-                        }),
-                    );
-                }
-                if let Ok(v) = arg.parse::<f64>() {
-                    return (
-                        nd,
-                        Ok(Spanned {
-                            node: Outcome::Scalar(Scalar::F64(v)),
-                            span,
-                        }),
-                    );
-                }
-
-                // Default to string type
                 (
                     nd,
                     Ok(Spanned {
-                        node: Outcome::Scalar(Scalar::String(Cow::Borrowed(arg))),
+                        node: Outcome::Scalar(Self::scalar(shape, Cow::Borrowed(arg))),
                         span,
                     }),
                 )
@@ -380,7 +775,36 @@ impl Format for Cli {
 
             // List items
             Expectation::ListItemOrListClose => {
-                // End the list if we're out of arguments, or if it's a new flag
+                let item_shape = match shape.def {
+                    Def::List(list_def) => list_def.t(),
+                    _ => shape,
+                };
+
+                // The values gathered for a list flag, after which reading goes on right
+                // after its first occurrence
+                if let Some(list) = &mut state.list {
+                    let res = match list.values.pop_front() {
+                        Some((idx, range)) => Spanned {
+                            node: Outcome::Scalar(Self::scalar(
+                                item_shape,
+                                Cow::Borrowed(&args[idx][range]),
+                            )),
+                            span: Span::new(idx, 1),
+                        },
+                        None => {
+                            let resume = list.resume;
+                            state.list = None;
+                            Spanned {
+                                node: Outcome::ListEnded,
+                                span: Span::new(resume, 0),
+                            }
+                        }
+                    };
+                    return (nd, Ok(res));
+                }
+
+                // Otherwise, the arguments that follow. End the list if we're out of
+                // arguments, or if it's a new flag
                 if arg_idx >= args.len() || args[arg_idx].starts_with('-') {
                     return (
                         nd,
@@ -395,14 +819,44 @@ impl Format for Cli {
                 (
                     nd,
                     Ok(Spanned {
-                        node: Outcome::Scalar(Scalar::String(Cow::Borrowed(args[arg_idx]))),
+                        node: Outcome::Scalar(Self::scalar(
+                            item_shape,
+                            Cow::Borrowed(args[arg_idx]),
+                        )),
                         span: Span::new(arg_idx, 1),
                     }),
                 )
             }
         }
     }
+}
+
+impl Format for Cli {
+    type Input<'input> = [&'input str];
+
+    fn source(&self) -> &'static str {
+        "args"
+    }
+
+    fn next<'input, 'facet, 'shape>(
+        &mut self,
+        nd: NextData<'input, 'facet, 'shape, Self::Input<'input>>,
+        expectation: Expectation,
+    ) -> NextResult<
+        'input,
+        'facet,
+        'shape,
+        Spanned<Outcome<'input>>,
+        Spanned<DeserErrorKind<'shape>>,
+        Self::Input<'input>,
+    >
+    where
+        'shape: 'input,
+    {
+        Self::next_with(&mut self.state, nd, expectation)
+    }
 
+    /// Skips the argument at the current position.
     fn skip<'input, 'facet, 'shape>(
         &mut self,
         nd: NextData<'input, 'facet, 'shape, Self::Input<'input>>,
@@ -0,0 +1,404 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use facet_core::{
+    Def, EnumType, Facet, Field, FieldAttribute, FieldFlags, Shape, ShapeAttribute, Type, UserType,
+    Variant,
+};
+use facet_reflect::Wip;
+
+/// Generates the help text of the program whose arguments are `T`, as printed for `--help`.
+///
+/// It's built from the shape of `T`: the program name comes from `#[facet(name = "...")]`
+/// (the type name otherwise), the description from the doc comment of `T`, and each
+/// argument is listed with its flags, type, doc comment and default value.
+pub fn help<'a, T: Facet<'a>>() -> String {
+    generate_help(T::SHAPE)
+}
+
+/// The value of an arbitrary attribute `key = "value"`, or an empty string for a bare `key`.
+pub(crate) fn attr_value<'a>(attr: &'a str, key: &str) -> Option<&'a str> {
+    let rest = attr.strip_prefix(key)?.trim_start();
+    if rest.is_empty() {
+        return Some("");
+    }
+    let value = rest.strip_prefix('=')?.trim();
+    Some(value.trim_matches(|c| c == '"' || c == '\''))
+}
+
+/// The value of the arbitrary attribute `key` on a shape.
+fn shape_attr<'shape>(shape: &Shape<'shape>, key: &str) -> Option<&'shape str> {
+    shape.attributes.iter().find_map(|attr| match attr {
+        ShapeAttribute::Arbitrary(a) => attr_value(a, key),
+        _ => None,
+    })
+}
+
+/// The value of the arbitrary attribute `key` on a field.
+pub(crate) fn field_attr<'shape>(field: &Field<'shape>, key: &str) -> Option<&'shape str> {
+    field.attributes.iter().find_map(|attr| match attr {
+        FieldAttribute::Arbitrary(a) => attr_value(a, key),
+        _ => None,
+    })
+}
+
+/// Whether the field is filled from arguments without a flag.
+pub(crate) fn is_positional(field: &Field) -> bool {
+    field_attr(field, "positional").is_some()
+}
+
+/// The `-x` flag of a field, from `#[facet(short = 'x')]`, or its own name when it's a
+/// single character and `short` has no value.
+pub(crate) fn short_flag(field: &Field) -> Option<char> {
+    let short = field_attr(field, "short")?;
+    if short.is_empty() {
+        field.name.chars().next()
+    } else {
+        short.chars().next()
+    }
+}
+
+/// Whether the flag of a field counts how many times it's given, from `#[facet(count)]`.
+pub(crate) fn is_counted(field: &Field) -> bool {
+    field_attr(field, "count").is_some()
+}
+
+/// The character separating several values in one argument of a list flag, from
+/// `#[facet(delimiter = ',')]`.
+pub(crate) fn delimiter(field: &Field) -> Option<char> {
+    field_attr(field, "delimiter")?.chars().next()
+}
+
+/// Whether `shape` is a list, whose flag can be given several times.
+pub(crate) fn is_list(shape: &Shape) -> bool {
+    matches!(shape.def, Def::List(_))
+}
+
+/// The environment variable a field falls back to, from `#[facet(env = "...")]`.
+pub(crate) fn env_var<'shape>(field: &Field<'shape>) -> Option<&'shape str> {
+    field_attr(field, "env").filter(|var| !var.is_empty())
+}
+
+/// The `--long` flag of a field, without the dashes.
+pub(crate) fn long_flag(field: &Field) -> String {
+    field.name.replace('_', "-")
+}
+
+/// The enum of a `#[facet(subcommand)]` field, which may be optional.
+pub(crate) fn subcommand_enum<'shape>(field: &Field<'shape>) -> Option<&'shape EnumType<'shape>> {
+    field_attr(field, "subcommand")?;
+    let shape = match field.shape().def {
+        Def::Option(option_def) => option_def.t(),
+        _ => field.shape(),
+    };
+    match &shape.ty {
+        Type::User(UserType::Enum(enum_type)) => Some(enum_type),
+        _ => None,
+    }
+}
+
+/// The name a variant goes by on the command line: its own, in kebab-case.
+pub(crate) fn subcommand_name(variant: &Variant) -> String {
+    kebab_case(variant.name)
+}
+
+fn kebab_case(name: &str) -> String {
+    let mut kebab = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c == '_' {
+            kebab.push('-');
+            continue;
+        }
+        if c.is_uppercase() && i > 0 && !kebab.ends_with('-') {
+            kebab.push('-');
+        }
+        kebab.extend(c.to_lowercase());
+    }
+    kebab
+}
+
+/// The name the program goes by in help and version text.
+pub(crate) fn program_name(shape: &Shape) -> String {
+    match shape_attr(shape, "name") {
+        Some(name) if !name.is_empty() => name.to_string(),
+        _ => {
+            let type_name = format!("{shape}");
+            kebab_case(type_name.split('<').next().unwrap_or_default())
+        }
+    }
+}
+
+/// The text printed for `--version`, if the shape has `#[facet(version = "...")]`.
+pub(crate) fn generate_version(shape: &Shape) -> Option<String> {
+    let version = shape_attr(shape, "version").filter(|v| !v.is_empty())?;
+    Some(format!("{} {}", program_name(shape), version))
+}
+
+/// The fields of the struct `shape`, or none if it isn't one.
+pub(crate) fn fields<'shape>(shape: &Shape<'shape>) -> &'shape [Field<'shape>] {
+    match shape.ty {
+        Type::User(UserType::Struct(st)) => st.fields,
+        _ => &[],
+    }
+}
+
+/// The default value of a field, displayed, if it has one that displays.
+fn default_value(field: &Field) -> Option<String> {
+    if !field.flags.contains(FieldFlags::DEFAULT) || field.shape().is_type::<bool>() {
+        return None;
+    }
+    field.shape().vtable.display?;
+    let wip = Wip::alloc_shape(field.shape()).ok()?;
+    let wip = match field.vtable.default_fn {
+        Some(default_fn) => wip.put_from_fn(default_fn),
+        None => wip.put_default(),
+    };
+    let value = wip.ok()?.build().ok()?;
+    Some(value.peek().to_string())
+}
+
+/// What a field's help entry says about it: its doc comment, followed by its environment
+/// variable and its default.
+fn description(field: &Field, style: Style) -> String {
+    let mut description = field
+        .doc
+        .iter()
+        .map(|line| line.trim())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if let Some(var) = env_var(field) {
+        if !description.is_empty() {
+            description.push(' ');
+        }
+        description.push_str(&format!("[env: {}]", style.yellow(var)));
+    }
+    if let Some(default) = default_value(field) {
+        if !description.is_empty() {
+            description.push(' ');
+        }
+        description.push_str(&format!("[default: {}]", style.yellow(&default)));
+    }
+    description
+}
+
+/// One line of the arguments or options table, with the width of its left column once
+/// printed, since colors take no room.
+struct Entry {
+    left: String,
+    width: usize,
+    description: String,
+}
+
+/// How the help text is styled. With the `color` feature, it's in color when standard output
+/// is a terminal and `NO_COLOR` isn't set. Otherwise, it's plain text.
+#[derive(Clone, Copy)]
+struct Style {
+    #[cfg(feature = "color")]
+    colors: bool,
+}
+
+impl Style {
+    fn detect() -> Self {
+        Self {
+            #[cfg(feature = "color")]
+            colors: {
+                use std::io::IsTerminal;
+                std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+                    && std::io::stdout().is_terminal()
+            },
+        }
+    }
+
+    fn bold(self, text: &str) -> String {
+        #[cfg(feature = "color")]
+        if self.colors {
+            return owo_colors::OwoColorize::bold(&text).to_string();
+        }
+        text.to_string()
+    }
+
+    fn green(self, text: &str) -> String {
+        #[cfg(feature = "color")]
+        if self.colors {
+            return owo_colors::OwoColorize::green(&text).to_string();
+        }
+        text.to_string()
+    }
+
+    fn yellow(self, text: &str) -> String {
+        #[cfg(feature = "color")]
+        if self.colors {
+            return owo_colors::OwoColorize::yellow(&text).to_string();
+        }
+        text.to_string()
+    }
+}
+
+/// Generates the help text for the arguments described by `shape`.
+pub(crate) fn generate_help(shape: &Shape) -> String {
+    render_help(
+        &program_name(shape),
+        shape.doc,
+        fields(shape),
+        generate_version(shape).is_some(),
+    )
+}
+
+/// Generates the help text of a subcommand, for the flags of a struct-like variant.
+pub(crate) fn generate_subcommand_help(variant: &Variant) -> String {
+    render_help(
+        &subcommand_name(variant),
+        variant.doc,
+        variant.data.fields,
+        false,
+    )
+}
+
+fn render_help(name: &str, doc: &[&str], fields: &[Field], has_version: bool) -> String {
+    // There's always `--help`, so there are always options
+    let style = Style::detect();
+    let mut usage = format!(
+        "{} {} {}",
+        style.bold("Usage:"),
+        style.green(name),
+        style.green("[OPTIONS]")
+    );
+    let mut arguments = Vec::new();
+    let mut options = Vec::new();
+    let mut commands = Vec::new();
+    for field in fields {
+        if field.should_skip_deserializing() {
+            continue;
+        }
+        if let Some(enum_type) = subcommand_enum(field) {
+            let placeholder = if matches!(field.shape().def, Def::Option(_)) {
+                "[COMMAND]"
+            } else {
+                "<COMMAND>"
+            };
+            usage.push_str(&format!(" {}", style.green(placeholder)));
+            for variant in enum_type.variants {
+                let name = subcommand_name(variant);
+                commands.push(Entry {
+                    width: name.len(),
+                    left: style.green(&name),
+                    description: variant
+                        .doc
+                        .first()
+                        .map(|line| line.trim())
+                        .unwrap_or("")
+                        .to_string(),
+                });
+            }
+            continue;
+        }
+        // Options are described by what they hold, since they can be left out anyway, and
+        // lists by their items, since they're given one or more times
+        let type_name = match field.shape().def {
+            Def::Option(option_def) => format!("<{}>", option_def.t()),
+            Def::List(list_def) => format!("<{}>...", list_def.t()),
+            _ => format!("<{}>", field.shape()),
+        };
+        if is_positional(field) {
+            let placeholder = if is_list(field.shape()) {
+                format!("[{}]...", field.name)
+            } else if field.flags.contains(FieldFlags::DEFAULT) {
+                format!("[{}]", field.name)
+            } else {
+                format!("<{}>", field.name)
+            };
+            usage.push_str(&format!(" {}", style.green(&placeholder)));
+            let mut description = description(field, style);
+            if !description.is_empty() {
+                description.push(' ');
+            }
+            description.push_str(&style.yellow(&type_name));
+            arguments.push(Entry {
+                width: placeholder.len(),
+                left: style.green(&placeholder),
+                description,
+            });
+            continue;
+        }
+
+        let short = match short_flag(field) {
+            Some(c) => format!("-{c}, "),
+            None => "    ".to_string(),
+        };
+        let long = format!("--{}", long_flag(field));
+        let mut width = short.len() + long.len();
+        let mut left = format!("{}{}", style.green(&short), style.green(&long));
+        if !field.shape().is_type::<bool>() && !is_counted(field) {
+            width += 1 + type_name.len();
+            left.push_str(&format!(" {}", style.yellow(&type_name)));
+        }
+        options.push(Entry {
+            left,
+            width,
+            description: description(field, style),
+        });
+    }
+    let builtin = |short: &str, long: &str, description: &str| Entry {
+        left: format!("{}{}", style.green(short), style.green(long)),
+        width: short.len() + long.len(),
+        description: description.to_string(),
+    };
+    if !claims_long(fields, "help") {
+        let help_short = if claims_short(fields, 'h') {
+            "    "
+        } else {
+            "-h, "
+        };
+        options.push(builtin(help_short, "--help", "Print help"));
+    }
+    if has_version && !claims_long(fields, "version") {
+        let version_short = if claims_short(fields, 'V') {
+            "    "
+        } else {
+            "-V, "
+        };
+        options.push(builtin(version_short, "--version", "Print version"));
+    }
+
+    let mut out = String::new();
+    let doc = doc
+        .iter()
+        .map(|line| line.trim())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if !doc.trim().is_empty() {
+        writeln!(out, "{}\n", doc.trim()).unwrap();
+    }
+    writeln!(out, "{usage}").unwrap();
+    write_section(&mut out, style, "Commands:", &commands);
+    write_section(&mut out, style, "Arguments:", &arguments);
+    write_section(&mut out, style, "Options:", &options);
+    out
+}
+
+/// Whether a field of the struct uses `-c` as its short flag.
+pub(crate) fn claims_short(fields: &[Field], c: char) -> bool {
+    fields.iter().any(|field| short_flag(field) == Some(c))
+}
+
+/// Whether a field of the struct uses `--name` as its long flag.
+pub(crate) fn claims_long(fields: &[Field], name: &str) -> bool {
+    fields
+        .iter()
+        .any(|field| !is_positional(field) && long_flag(field) == name)
+}
+
+fn write_section(out: &mut String, style: Style, title: &str, entries: &[Entry]) {
+    if entries.is_empty() {
+        return;
+    }
+    let width = entries.iter().map(|entry| entry.width).max().unwrap_or(0);
+    writeln!(out, "\n{}", style.bold(title)).unwrap();
+    for entry in entries {
+        let padding = " ".repeat(width - entry.width);
+        let line = format!("  {}{}  {}", entry.left, padding, entry.description);
+        writeln!(out, "{}", line.trim_end()).unwrap();
+    }
+}
@@ -0,0 +1,350 @@
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::ops::Deref;
+
+use facet_core::{Facet, Type, UserType};
+use facet_deserialize::{
+    DeserError, DeserErrorKind, Expectation, Format, NextData, NextResult, Outcome, Scalar, Span,
+    Spanned,
+};
+
+use crate::error::ArgsError;
+use crate::format::Cli;
+use crate::help;
+
+/// Where the value of a field came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// Nothing gave the field a value: it has its default, is `false` for a flag, or `None`
+    /// for an option.
+    Default,
+    /// The config file.
+    Config,
+    /// The environment variable of the field, named.
+    Env(String),
+    /// The command line.
+    Cli,
+}
+
+/// Arguments built by [`Layers`], which remember where each of their fields came from.
+///
+/// It dereferences to the arguments themselves.
+#[derive(Debug)]
+pub struct Layered<T> {
+    value: T,
+    sources: Vec<(String, Source)>,
+}
+
+impl<T> Layered<T> {
+    /// Where the field `name` came from, or `None` if there's no such field.
+    pub fn source(&self, name: &str) -> Option<&Source> {
+        self.sources
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, source)| source)
+    }
+
+    /// Every field, in order, with where it came from.
+    pub fn sources(&self) -> impl Iterator<Item = (&str, &Source)> {
+        self.sources
+            .iter()
+            .map(|(field, source)| (field.as_str(), source))
+    }
+
+    /// Returns the arguments themselves.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Layered<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+/// Builds arguments from several sources, each one taking precedence over the ones before:
+/// the defaults of the fields, config files, environment variables (from
+/// `#[facet(env = "...")]`), and the command line.
+///
+/// ```rust
+/// use facet::Facet;
+/// use facet_args::{Layers, Source};
+///
+/// #[derive(Facet)]
+/// struct Args {
+///     #[facet(named, env = "EXAMPLE_HOST")]
+///     host: String,
+///
+///     #[facet(named, default = 8080)]
+///     port: u16,
+/// }
+///
+/// let args = ["--host", "localhost"];
+/// let args = Layers::new(&args).parse::<Args>().unwrap();
+/// assert_eq!(args.host, "localhost");
+/// assert_eq!(args.source("host"), Some(&Source::Cli));
+/// assert_eq!(args.source("port"), Some(&Source::Default));
+/// ```
+pub struct Layers<'input> {
+    args: &'input [&'input str],
+    /// The values from config files, by field, as text
+    config: BTreeMap<String, String>,
+}
+
+impl<'input> Layers<'input> {
+    /// Starts from the command line arguments, which take precedence over everything else.
+    pub fn new(args: &'input [&'input str]) -> Self {
+        Self {
+            args,
+            config: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a config file in JSON, as an object with a member per field. Its members take
+    /// precedence over those of the config files added before it.
+    #[cfg(feature = "json")]
+    pub fn config_json<'json>(
+        mut self,
+        json: &'json str,
+    ) -> Result<Self, DeserError<'json, 'static>> {
+        let config: BTreeMap<String, ConfigValue> = facet_json::from_str(json)?;
+        self.add_config(config);
+        Ok(self)
+    }
+
+    /// Adds a config file in TOML, with a key per field. Its keys take precedence over those
+    /// of the config files added before it.
+    #[cfg(feature = "toml")]
+    pub fn config_toml<'toml>(
+        mut self,
+        toml: &'toml str,
+    ) -> Result<Self, facet_toml::TomlDeError<'toml, 'static>> {
+        let config: BTreeMap<String, ConfigValue> = facet_toml::from_str(toml)?;
+        self.add_config(config);
+        Ok(self)
+    }
+
+    #[cfg(any(feature = "json", feature = "toml"))]
+    fn add_config(&mut self, config: BTreeMap<String, ConfigValue>) {
+        for (key, value) in config {
+            // Keys can be written like flags, in kebab-case
+            self.config.insert(key.replace('-', "_"), value.to_string());
+        }
+    }
+
+    /// Parses the arguments, filling what the command line leaves out from the other sources.
+    pub fn parse<'facet, 'shape, T: Facet<'facet>>(
+        &self,
+    ) -> Result<Layered<T>, ArgsError<'input, 'shape>>
+    where
+        'input: 'facet + 'shape,
+    {
+        let mut layering = Layering::new(Some(&self.config));
+        let res = facet_deserialize::deserialize(self.args, &mut layering);
+        let value = layering.finish(res)?;
+        let fields = match T::SHAPE.ty {
+            Type::User(UserType::Struct(st)) => st.fields,
+            _ => &[],
+        };
+        let sources = fields
+            .iter()
+            .map(|field| {
+                let source = layering
+                    .sources
+                    .iter()
+                    .find(|(name, _)| name == field.name)
+                    .map_or(Source::Default, |(_, source)| source.clone());
+                (field.name.to_string(), source)
+            })
+            .collect();
+        Ok(Layered { value, sources })
+    }
+}
+
+/// A value in a config file. Values are given to fields like command line arguments are, so
+/// they're kept as text.
+#[cfg(any(feature = "json", feature = "toml"))]
+#[derive(facet_derive::Facet)]
+#[facet(untagged)]
+#[repr(u8)]
+#[allow(dead_code)] // Only built by deserialization
+enum ConfigValue {
+    Bool(bool),
+    String(String),
+    // Floats come before integers, which would take them and truncate them. Integers are
+    // taken as floats then, which is exact up to 2^53.
+    Float(f64),
+    Integer(i64),
+}
+
+#[cfg(any(feature = "json", feature = "toml"))]
+impl core::fmt::Display for ConfigValue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ConfigValue::Bool(value) => write!(f, "{value}"),
+            ConfigValue::Integer(value) => write!(f, "{value}"),
+            ConfigValue::Float(value) => write!(f, "{value}"),
+            ConfigValue::String(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+/// The command line format, with the fields it leaves out filled from environment variables
+/// and config values, recording where the fields of the top-level struct came from.
+pub(crate) struct Layering<'a> {
+    config: Option<&'a BTreeMap<String, String>>,
+    /// The value of the field whose key was just given, if it's not from the command line
+    pending: Option<String>,
+    sources: Vec<(String, Source)>,
+    reached_end: bool,
+    cli: Cli,
+}
+
+impl<'a> Layering<'a> {
+    pub(crate) fn new(config: Option<&'a BTreeMap<String, String>>) -> Self {
+        Self {
+            config,
+            pending: None,
+            sources: Vec::new(),
+            reached_end: false,
+            cli: Cli::default(),
+        }
+    }
+
+    /// The value read, or what the arguments asked for instead of one.
+    pub(crate) fn finish<'input, 'shape, T>(
+        &mut self,
+        res: Result<T, DeserError<'input, 'shape>>,
+    ) -> Result<T, ArgsError<'input, 'shape>> {
+        match self.cli.state.requested.take() {
+            Some(requested) => Err(requested.into_error()),
+            None => res.map_err(ArgsError::Deser),
+        }
+    }
+}
+
+impl Format for Layering<'_> {
+    type Input<'input> = [&'input str];
+
+    fn source(&self) -> &'static str {
+        "args"
+    }
+
+    fn next<'input, 'facet, 'shape>(
+        &mut self,
+        nd: NextData<'input, 'facet, 'shape, Self::Input<'input>>,
+        expectation: Expectation,
+    ) -> NextResult<
+        'input,
+        'facet,
+        'shape,
+        Spanned<Outcome<'input>>,
+        Spanned<DeserErrorKind<'shape>>,
+        Self::Input<'input>,
+    >
+    where
+        'shape: 'input,
+    {
+        let start = self.cli.state.first_unread(nd.start());
+        match expectation {
+            // The command line is done with this object: the fields it left out come from
+            // the environment, or from the config for the top-level struct
+            Expectation::ObjectKeyOrObjectClose if start >= nd.input().len() => {
+                let shape = nd.wip.shape();
+                let fields = Cli::target_fields(shape, nd.wip.selected_variant());
+                let top_level = nd.wip.frames_count() == 1;
+                if top_level && !self.reached_end {
+                    self.reached_end = true;
+                    for (idx, field) in fields.iter().enumerate() {
+                        if nd.wip.is_field_set(idx).unwrap_or(false) {
+                            self.sources.push((field.name.to_string(), Source::Cli));
+                        }
+                    }
+                    let unknown_key =
+                        self.config.into_iter().flatten().find(|(key, _)| {
+                            !fields.iter().any(|field| field.name == key.as_str())
+                        });
+                    if let Some((key, _)) = unknown_key {
+                        let err = DeserErrorKind::UnknownField {
+                            field_name: key.clone(),
+                            shape,
+                        };
+                        return (
+                            nd,
+                            Err(Spanned {
+                                node: err,
+                                span: Span::new(start, 0),
+                            }),
+                        );
+                    }
+                }
+
+                for (idx, field) in fields.iter().enumerate() {
+                    if nd.wip.is_field_set(idx).unwrap_or(false) {
+                        continue;
+                    }
+                    let env = help::env_var(field).and_then(|var| {
+                        let value = std::env::var(var).ok()?;
+                        Some((value, Source::Env(var.to_string())))
+                    });
+                    let config = || {
+                        let value = self.config.filter(|_| top_level)?.get(field.name)?;
+                        Some((value.clone(), Source::Config))
+                    };
+                    if let Some((value, source)) = env.or_else(config) {
+                        if top_level {
+                            self.sources.push((field.name.to_string(), source));
+                        }
+                        self.pending = Some(value);
+                        // Like the implicit keys of the command line, this one is past the
+                        // last argument
+                        return (
+                            nd,
+                            Ok(Spanned {
+                                node: Outcome::Scalar(Scalar::String(Cow::Borrowed(field.name))),
+                                span: Span::new(start, 1),
+                            }),
+                        );
+                    }
+                }
+            }
+            Expectation::ObjectVal => {
+                let start = nd.start();
+                if let Some(value) = self.pending.take() {
+                    let scalar = Cli::scalar(nd.wip.shape(), Cow::Owned(value));
+                    return (
+                        nd,
+                        Ok(Spanned {
+                            node: Outcome::Scalar(scalar),
+                            span: Span::new(start, 0),
+                        }),
+                    );
+                }
+            }
+            _ => {}
+        }
+        self.cli.next(nd, expectation)
+    }
+
+    fn skip<'input, 'facet, 'shape>(
+        &mut self,
+        nd: NextData<'input, 'facet, 'shape, Self::Input<'input>>,
+    ) -> NextResult<
+        'input,
+        'facet,
+        'shape,
+        Span,
+        Spanned<DeserErrorKind<'shape>>,
+        Self::Input<'input>,
+    >
+    where
+        'shape: 'input,
+    {
+        self.cli.skip(nd)
+    }
+}
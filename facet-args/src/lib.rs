@@ -5,9 +5,23 @@
 #![doc = include_str!("../README.md")]
 
 extern crate alloc;
+#[cfg(any(feature = "json", feature = "toml"))]
+extern crate facet_core as facet;
 
 /// CLI argument format implementation for facet-deserialize
 pub mod format;
 
+mod error;
+pub use error::ArgsError;
+
+mod help;
+pub use help::help;
+
+mod completions;
+pub use completions::{Shell, completions};
+
+mod layers;
+pub use layers::{Layered, Layers, Source};
+
 #[allow(unused)]
 pub use format::from_slice;
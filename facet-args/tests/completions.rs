@@ -0,0 +1,112 @@
+use std::path::PathBuf;
+
+use facet::Facet;
+use facet_args::Shell;
+
+/// Builds the project
+#[derive(Facet)]
+#[facet(name = "build-tool", version = "1.0.0")]
+struct Args {
+    /// The config file to use
+    #[facet(named, short = 'c')]
+    config: Option<PathBuf>,
+
+    /// How to print what's going on
+    #[facet(named)]
+    format: Format,
+
+    /// More output, each time it's given
+    #[facet(named, short = 'v', count)]
+    verbose: u8,
+
+    /// Features to enable
+    #[facet(named, short = 'F')]
+    features: Vec<String>,
+
+    #[facet(subcommand)]
+    command: Command,
+}
+
+#[derive(Facet)]
+#[repr(u8)]
+#[allow(dead_code)]
+enum Format {
+    Human,
+    Json,
+}
+
+#[derive(Facet)]
+#[repr(u8)]
+#[allow(dead_code)]
+enum Command {
+    /// Compiles files [in order]
+    Compile {
+        /// The files to compile
+        #[facet(positional)]
+        files: Vec<PathBuf>,
+
+        /// Build in release mode
+        #[facet(named, short = 'r')]
+        release: bool,
+    },
+    /// Manages the build cache
+    Cache(CacheArgs),
+}
+
+#[derive(Facet)]
+struct CacheArgs {
+    #[facet(subcommand)]
+    command: CacheCommand,
+}
+
+#[derive(Facet)]
+#[repr(u8)]
+#[allow(dead_code)]
+enum CacheCommand {
+    /// Removes cached artifacts
+    Clean,
+    /// Shows where the cache is
+    Dir,
+}
+
+#[test]
+fn test_bash_completions() {
+    insta::assert_snapshot!(facet_args::completions::<Args>(Shell::Bash));
+}
+
+#[test]
+fn test_zsh_completions() {
+    insta::assert_snapshot!(facet_args::completions::<Args>(Shell::Zsh));
+}
+
+#[test]
+fn test_fish_completions() {
+    insta::assert_snapshot!(facet_args::completions::<Args>(Shell::Fish));
+}
+
+#[test]
+fn test_no_subcommands() {
+    #[derive(Facet)]
+    struct Args {
+        #[facet(positional)]
+        path: PathBuf,
+
+        #[facet(named)]
+        dry_run: bool,
+    }
+
+    insta::assert_snapshot!(facet_args::completions::<Args>(Shell::Fish));
+}
+
+#[cfg(feature = "camino")]
+#[test]
+fn test_utf8_paths() {
+    #[derive(Facet)]
+    struct Args {
+        #[facet(named)]
+        output: camino::Utf8PathBuf,
+    }
+
+    let fish = facet_args::completions::<Args>(Shell::Fish);
+    assert!(fish.contains("-l output -r -F"), "{fish}");
+}
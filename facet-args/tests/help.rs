@@ -0,0 +1,93 @@
+use facet::Facet;
+use facet_args::ArgsError;
+use facet_deserialize::{DeserError, DeserErrorKind};
+use facet_testhelpers::test;
+
+/// Counts the lines of code in a file
+#[derive(Facet, Debug)]
+#[facet(name = "loc", version = "1.2.3")]
+struct Args {
+    /// The file to count lines in
+    #[facet(positional)]
+    path: String,
+
+    /// Only count lines this long
+    #[facet(positional, default = 42)]
+    min_len: usize,
+
+    /// Print each line as it's counted
+    #[facet(named, short = 'v')]
+    verbose: bool,
+
+    /// How many files to read at once
+    #[facet(named, short = 'j', default = 4)]
+    concurrency: usize,
+
+    #[facet(named)]
+    consider_casing: usize,
+}
+
+#[test]
+fn test_help_text() {
+    insta::assert_snapshot!(facet_args::help::<Args>());
+}
+
+#[test]
+fn test_help_flags() {
+    for flag in ["--help", "-h"] {
+        let args = ["file.rs", flag];
+        let err = facet_args::from_slice::<Args>(&args).unwrap_err();
+        assert!(err.is_requested_output());
+        assert!(
+            matches!(err, ArgsError::HelpRequested(ref text) if *text == facet_args::help::<Args>())
+        );
+        // The error displays as just the help text
+        assert_eq!(err.to_string(), facet_args::help::<Args>());
+    }
+}
+
+#[test]
+fn test_version_flags() {
+    for flag in ["--version", "-V"] {
+        let args = [flag];
+        let err = facet_args::from_slice::<Args>(&args).unwrap_err();
+        assert!(matches!(err, ArgsError::VersionRequested(ref text) if text == "loc 1.2.3"));
+        assert_eq!(err.to_string(), "loc 1.2.3");
+    }
+}
+
+#[test]
+fn test_version_needs_attribute() {
+    #[derive(Facet, Debug)]
+    struct Args {
+        #[facet(named, short = 'j')]
+        concurrency: usize,
+    }
+
+    let err = facet_args::from_slice::<Args>(&["--version"]).unwrap_err();
+    assert!(matches!(
+        err,
+        ArgsError::Deser(DeserError {
+            kind: DeserErrorKind::UnknownField { .. },
+            ..
+        })
+    ));
+    assert!(!facet_args::help::<Args>().contains("--version"));
+}
+
+#[test]
+fn test_fields_take_precedence() {
+    #[derive(Facet, Debug)]
+    struct Args {
+        #[facet(named, short = 'h')]
+        host: String,
+    }
+
+    let args: Args = facet_args::from_slice(&["-h", "localhost"])?;
+    assert_eq!(args.host, "localhost");
+
+    let err = facet_args::from_slice::<Args>(&["--help"]).unwrap_err();
+    assert!(err.is_requested_output());
+
+    insta::assert_snapshot!(facet_args::help::<Args>());
+}
@@ -0,0 +1,157 @@
+use facet::Facet;
+use facet_args::{Layers, Source};
+use facet_testhelpers::test;
+
+#[derive(Facet, Debug, PartialEq)]
+struct Args {
+    /// Where to listen
+    #[facet(named, env = "LAYERS_TEST_HOST")]
+    host: String,
+
+    /// The port to listen on
+    #[facet(named, short = 'p', env = "LAYERS_TEST_PORT", default = 8080)]
+    port: u16,
+
+    #[facet(named, env = "LAYERS_TEST_VERBOSE")]
+    verbose: bool,
+
+    #[facet(named)]
+    workers: Option<usize>,
+
+    #[facet(named, default = 1.5)]
+    timeout: f64,
+}
+
+fn set_var(var: &str, value: &str) {
+    // SAFETY: each test runs in its own process with nextest, and only sets its variables once
+    unsafe { std::env::set_var(var, value) };
+}
+
+#[test]
+fn test_env_fallback() {
+    set_var("LAYERS_TEST_HOST", "example.com");
+    set_var("LAYERS_TEST_VERBOSE", "true");
+
+    let parsed = facet_args::from_slice::<Args>(&["-p", "3000"])?;
+    assert_eq!(
+        parsed,
+        Args {
+            host: "example.com".into(),
+            port: 3000,
+            verbose: true,
+            workers: None,
+            timeout: 1.5,
+        }
+    );
+
+    // The command line takes precedence
+    let parsed = facet_args::from_slice::<Args>(&["--host", "localhost"])?;
+    assert_eq!(parsed.host, "localhost");
+}
+
+#[test]
+fn test_env_invalid_value() {
+    set_var("LAYERS_TEST_HOST", "example.com");
+    set_var("LAYERS_TEST_PORT", "not a port");
+
+    assert!(facet_args::from_slice::<Args>(&[]).is_err());
+}
+
+#[test]
+fn test_sources() {
+    set_var("LAYERS_TEST_PORT", "9000");
+
+    let layered = Layers::new(&["--host", "localhost"]).parse::<Args>()?;
+    assert_eq!(layered.port, 9000);
+    assert_eq!(
+        layered.sources().collect::<Vec<_>>(),
+        [
+            ("host", &Source::Cli),
+            ("port", &Source::Env("LAYERS_TEST_PORT".into())),
+            ("verbose", &Source::Default),
+            ("workers", &Source::Default),
+            ("timeout", &Source::Default),
+        ]
+    );
+    assert_eq!(layered.source("nope"), None);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_json_config() {
+    set_var("LAYERS_TEST_PORT", "9000");
+
+    let config = r#"{ "host": "config.example.com", "port": 1234, "workers": 4, "timeout": 2.5 }"#;
+    let layered = Layers::new(&["--verbose"])
+        .config_json(config)?
+        .parse::<Args>()?;
+    assert_eq!(
+        layered.sources().collect::<Vec<_>>(),
+        [
+            ("host", &Source::Config),
+            ("port", &Source::Env("LAYERS_TEST_PORT".into())),
+            ("verbose", &Source::Cli),
+            ("workers", &Source::Config),
+            ("timeout", &Source::Config),
+        ]
+    );
+    assert_eq!(
+        layered.into_inner(),
+        Args {
+            host: "config.example.com".into(),
+            port: 9000,
+            verbose: true,
+            workers: Some(4),
+            timeout: 2.5,
+        }
+    );
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_toml_config() {
+    let base = r#"
+        host = "base.example.com"
+        workers = 2
+    "#;
+    let local = r#"
+        host = "local.example.com"
+        verbose = true
+    "#;
+    let layered = Layers::new(&[])
+        .config_toml(base)?
+        .config_toml(local)?
+        .parse::<Args>()?;
+    assert_eq!(
+        layered.into_inner(),
+        Args {
+            host: "local.example.com".into(),
+            port: 8080,
+            verbose: true,
+            workers: Some(2),
+            timeout: 1.5,
+        }
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_config_unknown_key() {
+    let config = r#"{ "host": "example.com", "hots": "typo" }"#;
+    let err = Layers::new(&[])
+        .config_json(config)?
+        .parse::<Args>()
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        facet_args::ArgsError::Deser(facet_deserialize::DeserError {
+            kind: facet_deserialize::DeserErrorKind::UnknownField { ref field_name, .. },
+            ..
+        }) if field_name == "hots"
+    ));
+}
+
+#[test]
+fn test_env_in_help() {
+    insta::assert_snapshot!(facet_args::help::<Args>());
+}
@@ -0,0 +1,192 @@
+use facet::Facet;
+use facet_testhelpers::test;
+
+#[derive(Facet, Debug, PartialEq)]
+struct Args {
+    /// Files to include
+    #[facet(named, short = 'I', delimiter = ',')]
+    include: Vec<String>,
+
+    /// Ports to listen on
+    #[facet(named, delimiter = ',')]
+    ports: Vec<u16>,
+
+    /// More output, each time it's given
+    #[facet(named, short = 'v', count)]
+    verbose: u8,
+
+    /// Colored output
+    #[facet(named, default = true)]
+    color: bool,
+
+    #[facet(named, short = 'j')]
+    jobs: Option<usize>,
+
+    #[facet(positional)]
+    paths: Vec<String>,
+}
+
+#[test]
+fn test_repeated_flags() {
+    let parsed = facet_args::from_slice::<Args>(&["--include", "a", "--include", "b", "-I", "c"])?;
+    assert_eq!(parsed.include, ["a", "b", "c"]);
+}
+
+#[test]
+fn test_repeated_flags_apart() {
+    let parsed = facet_args::from_slice::<Args>(&[
+        "--include",
+        "a",
+        "-j",
+        "4",
+        "--include",
+        "b",
+        "src",
+        "tests",
+    ])?;
+    assert_eq!(parsed.include, ["a", "b"]);
+    assert_eq!(parsed.jobs, Some(4));
+    assert_eq!(parsed.paths, ["src", "tests"]);
+}
+
+#[test]
+fn test_comma_separated() {
+    let parsed = facet_args::from_slice::<Args>(&["--ports", "80,443", "--ports=8080"])?;
+    assert_eq!(parsed.ports, [80, 443, 8080]);
+
+    let parsed = facet_args::from_slice::<Args>(&["-I", "a,b", "--include=c"])?;
+    assert_eq!(parsed.include, ["a", "b", "c"]);
+}
+
+#[test]
+fn test_commas_kept_without_delimiter() {
+    #[derive(Facet, Debug)]
+    struct Args {
+        #[facet(named)]
+        exclude: Vec<String>,
+    }
+
+    let parsed = facet_args::from_slice::<Args>(&["--exclude", "a,b", "--exclude=c"])?;
+    assert_eq!(parsed.exclude, ["a,b", "c"]);
+}
+
+#[test]
+fn test_lists_left_out() {
+    let parsed = facet_args::from_slice::<Args>(&[])?;
+    assert_eq!(
+        parsed,
+        Args {
+            include: vec![],
+            ports: vec![],
+            verbose: 0,
+            color: true,
+            jobs: None,
+            paths: vec![],
+        }
+    );
+}
+
+#[test]
+fn test_list_missing_value() {
+    assert!(facet_args::from_slice::<Args>(&["--include"]).is_err());
+    assert!(facet_args::from_slice::<Args>(&["--include", "a", "--include", "-v"]).is_err());
+}
+
+#[test]
+fn test_invalid_list_item() {
+    assert!(facet_args::from_slice::<Args>(&["--ports", "80,http"]).is_err());
+}
+
+#[test]
+fn test_counted_flag() {
+    let parsed = facet_args::from_slice::<Args>(&["-vvv"])?;
+    assert_eq!(parsed.verbose, 3);
+
+    let parsed = facet_args::from_slice::<Args>(&["-v", "src", "--verbose", "-vv"])?;
+    assert_eq!(parsed.verbose, 4);
+    assert_eq!(parsed.paths, ["src"]);
+
+    // A count can be given outright
+    let parsed = facet_args::from_slice::<Args>(&["--verbose=2"])?;
+    assert_eq!(parsed.verbose, 2);
+}
+
+#[test]
+fn test_negated_flag() {
+    let parsed = facet_args::from_slice::<Args>(&["--no-color"])?;
+    assert!(!parsed.color);
+
+    let parsed = facet_args::from_slice::<Args>(&["--color"])?;
+    assert!(parsed.color);
+
+    // Only flags can be negated
+    assert!(facet_args::from_slice::<Args>(&["--no-jobs"]).is_err());
+}
+
+#[test]
+fn test_key_equals_value() {
+    let parsed = facet_args::from_slice::<Args>(&["--jobs=8", "--color=false", "src"])?;
+    assert_eq!(parsed.jobs, Some(8));
+    assert!(!parsed.color);
+    assert_eq!(parsed.paths, ["src"]);
+
+    let parsed = facet_args::from_slice::<Args>(&["-j=2"])?;
+    assert_eq!(parsed.jobs, Some(2));
+}
+
+#[test]
+fn test_subcommand_flags_are_its_own() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Tool {
+        #[facet(named, short = 'v', count)]
+        verbose: u8,
+
+        #[facet(named, short = 't')]
+        tag: Vec<String>,
+
+        #[facet(subcommand)]
+        command: Command,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    enum Command {
+        Run {
+            #[facet(named, short = 'v', count)]
+            verbose: u8,
+
+            #[facet(named, short = 't')]
+            tag: Vec<String>,
+        },
+    }
+
+    let parsed = facet_args::from_slice::<Tool>(&[
+        "-v", "--tag", "a", "run", "-vv", "--tag", "b", "-t", "c",
+    ])?;
+    assert_eq!(parsed.verbose, 1);
+    assert_eq!(parsed.tag, ["a"]);
+    assert_eq!(
+        parsed.command,
+        Command::Run {
+            verbose: 2,
+            tag: vec!["b".to_string(), "c".to_string()],
+        }
+    );
+
+    // A value named like the subcommand is still the value of its flag
+    let parsed = facet_args::from_slice::<Tool>(&["-t", "run", "-v", "run", "-v"])?;
+    assert_eq!(parsed.verbose, 1);
+    assert_eq!(parsed.tag, ["run"]);
+    assert_eq!(
+        parsed.command,
+        Command::Run {
+            verbose: 1,
+            tag: vec![],
+        }
+    );
+}
+
+#[test]
+fn test_repeated_flags_in_help() {
+    insta::assert_snapshot!(facet_args::help::<Args>());
+}
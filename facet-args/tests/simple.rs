@@ -5,13 +5,31 @@ use facet_testhelpers::test;
 #[test]
 fn test_cli_display() {
     // Create a Cli instance
-    let cli = Cli;
+    let cli = Cli::default();
 
     // Test the Display implementation
     let formatted = format!("{}", cli);
     assert_eq!(formatted, "Cli");
 }
 
+#[test]
+fn test_cli_format() {
+    #[derive(Facet)]
+    struct Args {
+        #[facet(positional)]
+        path: String,
+
+        #[facet(named, short = 'v', count)]
+        verbose: u8,
+    }
+
+    let args: Args =
+        facet_deserialize::deserialize(&["-v", "example.rs", "-v"][..], &mut Cli::default())
+            .unwrap();
+    assert_eq!(args.path, "example.rs");
+    assert_eq!(args.verbose, 2);
+}
+
 #[test]
 fn test_arg_parse_easy() {
     #[derive(Facet)]
@@ -0,0 +1,204 @@
+use facet::Facet;
+use facet_args::ArgsError;
+use facet_deserialize::{DeserError, DeserErrorKind};
+use facet_testhelpers::test;
+
+/// Manages the packages of a project
+#[derive(Facet, Debug, PartialEq)]
+#[facet(name = "pkg")]
+struct Args {
+    /// Print what's going on
+    #[facet(named, short = 'v')]
+    verbose: bool,
+
+    #[facet(subcommand)]
+    command: Command,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[repr(u8)]
+#[allow(dead_code)]
+enum Command {
+    /// Adds a package to the project
+    Add {
+        /// The package to add
+        #[facet(positional)]
+        name: String,
+
+        /// Add it as a dev-dependency
+        #[facet(named, short = 'd')]
+        dev: bool,
+
+        /// The version to add
+        #[facet(named)]
+        version: Option<String>,
+    },
+    /// Lists the packages of the project
+    List,
+    /// Manages the package cache
+    Cache(CacheArgs),
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct CacheArgs {
+    #[facet(subcommand)]
+    command: CacheCommand,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[repr(u8)]
+enum CacheCommand {
+    /// Removes cached packages
+    Clean {
+        #[facet(named)]
+        all: bool,
+    },
+    /// Shows where the cache is
+    Dir,
+}
+
+#[test]
+fn test_struct_variant() {
+    let args = ["-v", "add", "serde", "--dev", "--version", "1.0"];
+    let parsed = facet_args::from_slice::<Args>(&args).unwrap();
+    assert_eq!(
+        parsed,
+        Args {
+            verbose: true,
+            command: Command::Add {
+                name: "serde".into(),
+                dev: true,
+                version: Some("1.0".into()),
+            },
+        }
+    );
+
+    // Flags of the subcommand have their own defaults
+    let args = ["add", "facet"];
+    let parsed = facet_args::from_slice::<Args>(&args).unwrap();
+    assert_eq!(
+        parsed,
+        Args {
+            verbose: false,
+            command: Command::Add {
+                name: "facet".into(),
+                dev: false,
+                version: None,
+            },
+        }
+    );
+}
+
+#[test]
+fn test_unit_variant() {
+    let args = ["list", "-v"];
+    let parsed = facet_args::from_slice::<Args>(&args).unwrap();
+    assert_eq!(
+        parsed,
+        Args {
+            verbose: true,
+            command: Command::List,
+        }
+    );
+}
+
+#[test]
+fn test_nested_subcommands() {
+    let args = ["cache", "clean", "--all"];
+    let parsed = facet_args::from_slice::<Args>(&args).unwrap();
+    assert_eq!(
+        parsed.command,
+        Command::Cache(CacheArgs {
+            command: CacheCommand::Clean { all: true },
+        })
+    );
+
+    let args = ["cache", "dir"];
+    let parsed = facet_args::from_slice::<Args>(&args).unwrap();
+    assert_eq!(
+        parsed.command,
+        Command::Cache(CacheArgs {
+            command: CacheCommand::Dir,
+        })
+    );
+}
+
+#[test]
+fn test_optional_subcommand() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Args {
+        #[facet(named)]
+        dry_run: bool,
+
+        #[facet(subcommand)]
+        command: Option<CacheCommand>,
+    }
+
+    let args = ["--dry-run"];
+    let parsed = facet_args::from_slice::<Args>(&args).unwrap();
+    assert_eq!(
+        parsed,
+        Args {
+            dry_run: true,
+            command: None,
+        }
+    );
+
+    let args = ["dir"];
+    let parsed = facet_args::from_slice::<Args>(&args).unwrap();
+    assert_eq!(
+        parsed,
+        Args {
+            dry_run: false,
+            command: Some(CacheCommand::Dir),
+        }
+    );
+}
+
+#[test]
+fn test_unknown_subcommand() {
+    let args = ["-v", "remove"];
+    let err = facet_args::from_slice::<Args>(&args).unwrap_err();
+    assert!(matches!(
+        err,
+        ArgsError::Deser(DeserError {
+            kind: DeserErrorKind::NoSuchVariant { ref name, .. },
+            ..
+        }) if name == "remove"
+    ));
+}
+
+#[test]
+fn test_missing_subcommand() {
+    let args = ["-v"];
+    let err = facet_args::from_slice::<Args>(&args).unwrap_err();
+    assert!(!err.is_requested_output());
+}
+
+#[test]
+fn test_subcommand_unknown_flag() {
+    // Flags of a subcommand don't exist outside of it
+    let args = ["--dev", "add", "serde"];
+    assert!(facet_args::from_slice::<Args>(&args).is_err());
+}
+
+#[test]
+fn test_help_lists_subcommands() {
+    insta::assert_snapshot!(facet_args::help::<Args>());
+}
+
+#[test]
+fn test_subcommand_help() {
+    let args = ["add", "--help"];
+    let err = facet_args::from_slice::<Args>(&args).unwrap_err();
+    assert!(err.is_requested_output());
+    insta::assert_snapshot!(err.to_string());
+}
+
+#[test]
+fn test_nested_subcommand_help() {
+    let args = ["cache", "clean", "-h"];
+    let err = facet_args::from_slice::<Args>(&args).unwrap_err();
+    assert!(err.is_requested_output());
+    insta::assert_snapshot!(err.to_string());
+}
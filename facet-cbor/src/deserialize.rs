@@ -0,0 +1,431 @@
+//! Read CBOR into Rust values.
+
+use std::borrow::Cow;
+
+use facet_core::Facet;
+use facet_deserialize::{
+    DeserError, DeserErrorKind, Outcome, Scalar, Span, Spannable, Spanned, Tape,
+};
+
+use crate::half::f16_to_f64;
+use crate::{major, tag};
+
+/// Deserializes a value of type `T` from CBOR bytes.
+///
+/// Arrays and maps can be of definite or indefinite length, and so can byte and text
+/// strings. Byte strings are read into `Vec<u8>` and other lists of bytes. Dates and times
+/// can be tagged RFC 3339 text (tag 0) or seconds since the epoch (tag 1), and UUIDs tagged
+/// bytes (tag 37). Other tags are looked through.
+///
+/// Errors point at the bytes they come from.
+pub fn from_slice<'input, 'facet, 'shape, T: Facet<'facet>>(
+    cbor: &'input [u8],
+) -> Result<T, DeserError<'input, 'shape>>
+where
+    'input: 'facet,
+{
+    let tape = match Decoder::new(cbor).decode() {
+        Ok(tape) => tape,
+        Err(err) => return Err(DeserError::new(err.node, cbor, err.span, "cbor")),
+    };
+    facet_deserialize::deserialize(cbor, tape)
+}
+
+/// An array or a map being decoded.
+struct Container {
+    map: bool,
+    /// How many more data items it holds, keys and values counted apart. `None` for
+    /// indefinite length, which ends with a break byte.
+    remaining: Option<u64>,
+    /// Whether the next data item is a key
+    at_key: bool,
+}
+
+/// The head of a data item: its major type, and the number that follows.
+struct Head {
+    major: u8,
+    /// The low 5 bits of the initial byte
+    info: u8,
+    /// The number, or `None` for indefinite length
+    value: Option<u64>,
+}
+
+type DecodeResult<'shape, T> = Result<T, Spanned<DeserErrorKind<'shape>>>;
+
+/// Decodes a data item into the outcomes the deserializer expects.
+///
+/// Definite-length arrays and maps have nothing marking their end, so the whole input is
+/// decoded ahead of time, with each end put where the last item before it ends.
+struct Decoder<'a> {
+    input: &'a [u8],
+    pos: usize,
+    tape: Tape,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(input: &'a [u8]) -> Self {
+        Self {
+            input,
+            pos: 0,
+            tape: Tape::new("cbor", input.len()),
+        }
+    }
+
+    fn push(&mut self, outcome: Outcome<'static>, start: usize) {
+        let span = Span::new(start, self.pos - start);
+        self.tape.push(outcome, span);
+    }
+
+    /// The span of bytes read from the input as is, which just ended.
+    fn read_span(&self, bytes: &[u8]) -> Span {
+        Span::new(self.pos - bytes.len(), bytes.len())
+    }
+
+    fn eof(&self, wanted: &'static str) -> Spanned<DeserErrorKind<'static>> {
+        DeserErrorKind::UnexpectedEof { wanted }.with_span(Span::new(self.input.len(), 0))
+    }
+
+    fn unexpected(&self, at: usize, wanted: &'static str) -> Spanned<DeserErrorKind<'static>> {
+        DeserErrorKind::UnexpectedByte {
+            got: self.input[at],
+            wanted,
+        }
+        .with_span(Span::new(at, 1))
+    }
+
+    fn take(&mut self, len: u64) -> DecodeResult<'static, &'a [u8]> {
+        let end = usize::try_from(len)
+            .ok()
+            .and_then(|len| self.pos.checked_add(len))
+            .filter(|&end| end <= self.input.len())
+            .ok_or_else(|| self.eof("the rest of the data item"))?;
+        let bytes = &self.input[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn head(&mut self) -> DecodeResult<'static, Head> {
+        let start = self.pos;
+        let initial = *self
+            .input
+            .get(self.pos)
+            .ok_or_else(|| self.eof("a data item"))?;
+        self.pos += 1;
+        let major = initial >> 5;
+        let info = initial & 0x1f;
+        let value = match info {
+            0..=23 => Some(u64::from(info)),
+            24..=27 => {
+                let bytes = self.take(1 << (info - 24))?;
+                Some(bytes.iter().fold(0, |acc, &b| (acc << 8) | u64::from(b)))
+            }
+            31 if matches!(
+                major,
+                major::BYTES | major::TEXT | major::ARRAY | major::MAP | major::SIMPLE
+            ) =>
+            {
+                None
+            }
+            _ => return Err(self.unexpected(start, "a valid initial byte")),
+        };
+        Ok(Head { major, info, value })
+    }
+
+    /// Decodes the whole input, which has to be a single data item.
+    fn decode(mut self) -> DecodeResult<'static, Tape> {
+        let mut stack: Vec<Container> = Vec::new();
+        loop {
+            if let Some(container) = stack.last_mut() {
+                let ended = match container.remaining {
+                    Some(0) => Some(self.pos),
+                    None if self.input.get(self.pos) == Some(&0xff) => {
+                        self.pos += 1;
+                        Some(self.pos - 1)
+                    }
+                    _ => None,
+                };
+                if let Some(start) = ended {
+                    if !container.at_key {
+                        return Err(self.unexpected(start, "the value of the last key"));
+                    }
+                    let outcome = if container.map {
+                        Outcome::ObjectEnded
+                    } else {
+                        Outcome::ListEnded
+                    };
+                    self.push(outcome, start);
+                    stack.pop();
+                    if stack.is_empty() {
+                        break;
+                    }
+                    continue;
+                }
+            }
+
+            let mut key = false;
+            if let Some(container) = stack.last_mut() {
+                if let Some(remaining) = &mut container.remaining {
+                    *remaining -= 1;
+                }
+                if container.map {
+                    key = container.at_key;
+                    container.at_key = !container.at_key;
+                }
+            }
+            if let Some(container) = self.item(key)? {
+                stack.push(container);
+            } else if stack.is_empty() {
+                break;
+            }
+        }
+
+        if self.pos < self.input.len() {
+            return Err(self.unexpected(self.pos, "the end of the input"));
+        }
+        Ok(self.tape)
+    }
+
+    /// Decodes a data item, or the start of it if it's an array or a map, which is then
+    /// returned to decode the rest of.
+    fn item(&mut self, key: bool) -> DecodeResult<'static, Option<Container>> {
+        let start = self.pos;
+
+        // Tags only matter for the data item right after them
+        let mut last_tag = None;
+        let mut content = self.pos;
+        let head = loop {
+            let head = self.head()?;
+            if head.major != major::TAG {
+                break head;
+            }
+            last_tag = head.value;
+            content = self.pos;
+        };
+
+        let scalar = match (head.major, head.value) {
+            (major::ARRAY | major::MAP, _) if key => {
+                return Err(self.unexpected(start, "a map key that's a string or an integer"));
+            }
+            (major::ARRAY, remaining) => {
+                self.push(Outcome::ListStarted, start);
+                return Ok(Some(Container {
+                    map: false,
+                    remaining,
+                    at_key: true,
+                }));
+            }
+            (major::MAP, pairs) => {
+                let remaining = match pairs {
+                    Some(pairs) => Some(
+                        pairs
+                            .checked_mul(2)
+                            .ok_or_else(|| self.eof("the entries of the map"))?,
+                    ),
+                    None => None,
+                };
+                self.push(Outcome::ObjectStarted, start);
+                return Ok(Some(Container {
+                    map: true,
+                    remaining,
+                    at_key: true,
+                }));
+            }
+            (major::UNSIGNED, Some(value)) => Scalar::U64(value),
+            (major::NEGATIVE, Some(value)) => match i64::try_from(value) {
+                Ok(value) => Scalar::I64(-1 - value),
+                Err(_) => {
+                    return Err(DeserErrorKind::NumberOutOfRange(-1.0 - value as f64)
+                        .with_span(Span::new(start, self.pos - start)));
+                }
+            },
+            (major::TEXT, len) => {
+                let bytes = self.string(major::TEXT, len)?;
+                if last_tag == Some(tag::EPOCH) {
+                    return Err(self.unexpected(content, "a number of seconds"));
+                }
+                let span = Span::new(start, self.pos - start);
+                let invalid = |err: std::str::Utf8Error| {
+                    DeserErrorKind::InvalidUtf8(err.to_string()).with_span(span)
+                };
+                match bytes {
+                    // Text of definite length is borrowed from the input
+                    Cow::Borrowed(bytes) => {
+                        std::str::from_utf8(bytes).map_err(invalid)?;
+                        self.tape.push_str(self.read_span(bytes), span);
+                        return Ok(None);
+                    }
+                    Cow::Owned(bytes) => Scalar::String(Cow::Owned(
+                        String::from_utf8(bytes).map_err(|err| invalid(err.utf8_error()))?,
+                    )),
+                }
+            }
+            (major::BYTES, len) => {
+                let bytes = self.string(major::BYTES, len)?;
+                match last_tag {
+                    Some(tag::UUID) if bytes.len() == 16 => {
+                        Scalar::String(Cow::Owned(uuid(&bytes)))
+                    }
+                    Some(tag::POSITIVE_BIGNUM | tag::NEGATIVE_BIGNUM) => {
+                        self.bignum(&bytes, last_tag == Some(tag::NEGATIVE_BIGNUM), start)?
+                    }
+                    _ if key => {
+                        return Err(
+                            self.unexpected(start, "a map key that's a string or an integer")
+                        );
+                    }
+                    _ => match bytes {
+                        Cow::Borrowed(bytes) => {
+                            let bytes = self.read_span(bytes);
+                            self.tape
+                                .push_bytes(bytes, Span::new(start, self.pos - start));
+                            return Ok(None);
+                        }
+                        Cow::Owned(bytes) => Scalar::Bytes(Cow::Owned(bytes)),
+                    },
+                }
+            }
+            (major::SIMPLE, _) => match (head.info, head.value) {
+                (20, _) => Scalar::Bool(false),
+                (21, _) => Scalar::Bool(true),
+                // `null`, and `undefined`
+                (22 | 23, _) => Scalar::Null,
+                (25, Some(bits)) => Scalar::F64(f16_to_f64(bits as u16)),
+                (26, Some(bits)) => Scalar::F64(f64::from(f32::from_bits(bits as u32))),
+                (27, Some(bits)) => Scalar::F64(f64::from_bits(bits)),
+                _ => return Err(self.unexpected(content, "a value")),
+            },
+            _ => return Err(self.unexpected(content, "a value")),
+        };
+
+        let scalar = match (last_tag, scalar) {
+            (Some(tag::EPOCH), Scalar::U64(seconds)) => {
+                Scalar::String(Cow::Owned(epoch_to_rfc3339(i128::from(seconds), 0)))
+            }
+            (Some(tag::EPOCH), Scalar::I64(seconds)) => {
+                Scalar::String(Cow::Owned(epoch_to_rfc3339(i128::from(seconds), 0)))
+            }
+            (Some(tag::EPOCH), Scalar::F64(seconds)) => {
+                if !seconds.is_finite() {
+                    return Err(DeserErrorKind::NumberOutOfRange(seconds)
+                        .with_span(Span::new(start, self.pos - start)));
+                }
+                let whole = seconds.floor();
+                let nanos = ((seconds - whole) * 1e9).round() as u32;
+                let (whole, nanos) = if nanos >= 1_000_000_000 {
+                    (whole + 1.0, 0)
+                } else {
+                    (whole, nanos)
+                };
+                Scalar::String(Cow::Owned(epoch_to_rfc3339(whole as i128, nanos)))
+            }
+            (_, Scalar::U64(value)) if key => Scalar::String(Cow::Owned(value.to_string())),
+            (_, Scalar::I64(value)) if key => Scalar::String(Cow::Owned(value.to_string())),
+            (_, Scalar::String(text)) => Scalar::String(text),
+            (_, _) if key => {
+                return Err(self.unexpected(start, "a map key that's a string or an integer"));
+            }
+            (_, scalar) => scalar,
+        };
+        self.push(Outcome::Scalar(scalar), start);
+        Ok(None)
+    }
+
+    /// Reads the content of a byte or text string, joining its chunks if it's of
+    /// indefinite length.
+    fn string(&mut self, major: u8, len: Option<u64>) -> DecodeResult<'static, Cow<'a, [u8]>> {
+        if let Some(len) = len {
+            return Ok(Cow::Borrowed(self.take(len)?));
+        }
+        let mut joined = Vec::new();
+        loop {
+            if self.input.get(self.pos) == Some(&0xff) {
+                self.pos += 1;
+                return Ok(Cow::Owned(joined));
+            }
+            let chunk = self.pos;
+            let head = self.head()?;
+            match head.value {
+                Some(len) if head.major == major => joined.extend_from_slice(self.take(len)?),
+                _ => return Err(self.unexpected(chunk, "a chunk of the same kind of string")),
+            }
+        }
+    }
+
+    /// Reads a bignum that fits in 64 bits.
+    fn bignum(
+        &self,
+        bytes: &[u8],
+        negative: bool,
+        start: usize,
+    ) -> DecodeResult<'static, Scalar<'static>> {
+        let significant = &bytes[bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len())..];
+        let out_of_range = || {
+            let magnitude = significant
+                .iter()
+                .fold(0.0, |acc, &b| acc * 256.0 + f64::from(b));
+            let number = if negative {
+                -1.0 - magnitude
+            } else {
+                magnitude
+            };
+            DeserErrorKind::NumberOutOfRange(number).with_span(Span::new(start, self.pos - start))
+        };
+        if significant.len() > 8 {
+            return Err(out_of_range());
+        }
+        let value = significant
+            .iter()
+            .fold(0u64, |acc, &b| (acc << 8) | u64::from(b));
+        if !negative {
+            return Ok(Scalar::U64(value));
+        }
+        match i64::try_from(value) {
+            Ok(value) => Ok(Scalar::I64(-1 - value)),
+            Err(_) => Err(out_of_range()),
+        }
+    }
+}
+
+/// Writes the 16 bytes of a UUID as its hyphenated text.
+fn uuid(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(36);
+    for (i, byte) in bytes.iter().enumerate() {
+        if matches!(i, 4 | 6 | 8 | 10) {
+            text.push('-');
+        }
+        text.push_str(&format!("{byte:02x}"));
+    }
+    text
+}
+
+/// Writes seconds since the Unix epoch as RFC 3339 text, in UTC.
+fn epoch_to_rfc3339(seconds: i128, nanos: u32) -> String {
+    let days = seconds.div_euclid(86_400);
+    let time = seconds.rem_euclid(86_400);
+    let (hour, minute, second) = (time / 3600, time % 3600 / 60, time % 60);
+
+    // From the proleptic Gregorian calendar, in eras of 400 years starting on March 1st
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i128::from(month <= 2);
+
+    let mut text = format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}");
+    if nanos > 0 {
+        let fraction = format!("{nanos:09}");
+        text.push('.');
+        text.push_str(fraction.trim_end_matches('0'));
+    }
+    text.push('Z');
+    text
+}
@@ -0,0 +1,58 @@
+//! Half-precision (IEEE 754 binary16) floats, which CBOR has but Rust doesn't.
+
+/// Widens a half-precision float, given its bits. Every one of them is exactly an `f64`.
+pub(crate) fn f16_to_f64(bits: u16) -> f64 {
+    let negative = bits & 0x8000 != 0;
+    let exponent = (bits >> 10) & 0x1f;
+    let mantissa = u64::from(bits & 0x3ff);
+    let magnitude = match exponent {
+        // Subnormal: the mantissa times 2^-24
+        0 => mantissa as f64 / (1u64 << 24) as f64,
+        0x1f if mantissa == 0 => f64::INFINITY,
+        0x1f => f64::NAN,
+        _ => f64::from_bits(((u64::from(exponent) + 1023 - 15) << 52) | (mantissa << 42)),
+    };
+    if negative { -magnitude } else { magnitude }
+}
+
+/// Narrows a float to half precision, if that loses nothing. NaNs all become the same one.
+pub(crate) fn f64_to_f16(value: f64) -> Option<u16> {
+    if value.is_nan() {
+        return Some(0x7e00);
+    }
+    let bits = value.to_bits();
+    let sign = ((bits >> 48) & 0x8000) as u16;
+    if value.is_infinite() {
+        return Some(sign | 0x7c00);
+    }
+    if value == 0.0 {
+        return Some(sign);
+    }
+    let exponent = ((bits >> 52) & 0x7ff) as i32 - 1023;
+    let mantissa = bits & ((1 << 52) - 1);
+    match exponent {
+        -14..=15 => {
+            // Normal: the mantissa has to fit in 10 bits
+            if mantissa & ((1 << 42) - 1) != 0 {
+                return None;
+            }
+            Some(sign | (((exponent + 15) as u16) << 10) | (mantissa >> 42) as u16)
+        }
+        -24..=-15 => {
+            // Subnormal: the significand, leading 1 included, is a multiple of 2^-24
+            let significand = mantissa | (1 << 52);
+            let shift = 52 - (exponent + 24);
+            if significand & ((1 << shift) - 1) != 0 {
+                return None;
+            }
+            Some(sign | (significand >> shift) as u16)
+        }
+        _ => None,
+    }
+}
+
+/// Narrows a float to single precision, if that loses nothing.
+pub(crate) fn f64_to_f32(value: f64) -> Option<f32> {
+    let narrow = value as f32;
+    (value.is_nan() || f64::from(narrow) == value).then_some(narrow)
+}
@@ -0,0 +1,39 @@
+#![warn(missing_docs)]
+#![forbid(unsafe_code)]
+#![doc = include_str!("../README.md")]
+
+pub use facet_deserialize::{DeserError, DeserErrorKind, DeserErrorMessage};
+
+mod half;
+
+mod deserialize;
+pub use deserialize::*;
+
+mod serialize;
+pub use serialize::*;
+
+/// The major types of CBOR data items, from the top 3 bits of their initial byte.
+mod major {
+    pub const UNSIGNED: u8 = 0;
+    pub const NEGATIVE: u8 = 1;
+    pub const BYTES: u8 = 2;
+    pub const TEXT: u8 = 3;
+    pub const ARRAY: u8 = 4;
+    pub const MAP: u8 = 5;
+    pub const TAG: u8 = 6;
+    pub const SIMPLE: u8 = 7;
+}
+
+/// The tags this crate reads and writes.
+mod tag {
+    /// A date and time, as RFC 3339 text
+    pub const DATE_TIME: u64 = 0;
+    /// A date and time, as seconds since the Unix epoch
+    pub const EPOCH: u64 = 1;
+    /// An unsigned integer too large for a head, as big-endian bytes
+    pub const POSITIVE_BIGNUM: u64 = 2;
+    /// A negative integer too large for a head, as the big-endian bytes of `-1 - n`
+    pub const NEGATIVE_BIGNUM: u64 = 3;
+    /// A UUID, as its 16 bytes
+    pub const UUID: u64 = 37;
+}
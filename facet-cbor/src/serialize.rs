@@ -0,0 +1,447 @@
+//! Write Rust values as CBOR.
+
+use core::convert::Infallible;
+use std::io::{self, Write};
+
+use facet_core::{Facet, ScalarAffinity};
+use facet_reflect::Peek;
+use facet_serialize::{Serializer, serialize_iterative};
+use log::trace;
+
+use crate::half::{f64_to_f16, f64_to_f32};
+use crate::{major, tag};
+
+/// Serializes a value to CBOR bytes.
+///
+/// Structs are maps keyed by field name, and lists and tuples are arrays, both of definite
+/// length. `Vec<u8>` is a byte string, dates and times are tagged RFC 3339 text (tag 0) and
+/// UUIDs are tagged bytes (tag 37).
+pub fn to_vec<'a, T: Facet<'a>>(value: &'a T) -> Vec<u8> {
+    CborOptions::new().to_vec(value)
+}
+
+/// Serializes a value to a writer, as CBOR.
+pub fn to_writer<'a, T: Facet<'a>, W: Write>(value: &'a T, writer: &mut W) -> io::Result<()> {
+    CborOptions::new().to_writer(value, writer)
+}
+
+/// How to write CBOR.
+///
+/// ```rust
+/// use facet::Facet;
+/// use facet_cbor::CborOptions;
+///
+/// #[derive(Facet)]
+/// struct Reading {
+///     sensor: u8,
+///     celsius: f64,
+/// }
+///
+/// let bytes = CborOptions::new()
+///     .canonical(true)
+///     .to_vec(&Reading { sensor: 3, celsius: 21.5 });
+/// // Keys sort by their encoded bytes, so the shorter `sensor` comes first, and 21.5 fits
+/// // in a half-precision float
+/// assert_eq!(bytes, b"\xa2\x66sensor\x03\x67celsius\xf9\x4d\x60");
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CborOptions {
+    canonical: bool,
+}
+
+impl CborOptions {
+    /// Writes fields in the order they're declared, and floats as wide as their type.
+    pub fn new() -> Self {
+        Self { canonical: false }
+    }
+
+    /// Sets whether to write the deterministic encoding of RFC 8949 §4.2: the entries of
+    /// maps (and the fields of structs) sorted by their encoded keys, and each float in the
+    /// narrowest width that holds it exactly. The same value is then always the same bytes.
+    pub fn canonical(mut self, canonical: bool) -> Self {
+        self.canonical = canonical;
+        self
+    }
+
+    /// Serializes a value to CBOR bytes.
+    pub fn to_vec<'a, T: Facet<'a>>(&self, value: &'a T) -> Vec<u8> {
+        let mut serializer = CborSerializer {
+            options: *self,
+            output: Vec::new(),
+            stack: Vec::new(),
+        };
+        serialize_iterative(Peek::new(value), &mut serializer)
+            .unwrap_or_else(|never| match never {});
+        serializer.output
+    }
+
+    /// Serializes a value to a writer, as CBOR.
+    pub fn to_writer<'a, T: Facet<'a>, W: Write>(
+        &self,
+        value: &'a T,
+        writer: &mut W,
+    ) -> io::Result<()> {
+        writer.write_all(&self.to_vec(value))
+    }
+}
+
+/// An entry of a map being serialized, by where it is in the output.
+struct Entry {
+    start: usize,
+    key_end: usize,
+}
+
+/// An array or a map being serialized.
+///
+/// Its head, which holds how many items there are, is written first when their number is
+/// known, and put in front of them once they're all written otherwise.
+enum Frame {
+    Array { head: Head, count: u64 },
+    Map { head: Head, entries: Vec<Entry> },
+}
+
+/// Where the head of an array or a map is in the output.
+struct Head {
+    start: usize,
+    /// Where its items start
+    end: usize,
+    /// How many items it says there are, if it was written
+    len: Option<u64>,
+}
+
+struct CborSerializer {
+    options: CborOptions,
+    output: Vec<u8>,
+    /// The arrays and maps being serialized, innermost last
+    stack: Vec<Frame>,
+}
+
+/// Writes the head of a data item: its major type, and a number in the fewest bytes.
+fn write_head(output: &mut Vec<u8>, major: u8, value: u64) {
+    let major = major << 5;
+    match value {
+        0..=23 => output.push(major | value as u8),
+        24..=0xff => output.extend([major | 24, value as u8]),
+        0x100..=0xffff => {
+            output.push(major | 25);
+            output.extend((value as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            output.push(major | 26);
+            output.extend((value as u32).to_be_bytes());
+        }
+        _ => {
+            output.push(major | 27);
+            output.extend(value.to_be_bytes());
+        }
+    }
+}
+
+/// Reads the 16 bytes of a UUID from its text, with or without hyphens.
+fn uuid_bytes(text: &str) -> Option<[u8; 16]> {
+    let mut digits = text.chars().filter(|&c| c != '-').map(|c| c.to_digit(16));
+    let mut bytes = [0; 16];
+    for byte in &mut bytes {
+        let high = digits.next()??;
+        let low = digits.next()??;
+        *byte = (high * 16 + low) as u8;
+    }
+    digits.next().is_none().then_some(bytes)
+}
+
+impl CborSerializer {
+    /// Counts an item of the array it's in, once it's written.
+    fn written(&mut self) {
+        if let Some(Frame::Array { count, .. }) = self.stack.last_mut() {
+            *count += 1;
+        }
+    }
+
+    fn write_text(&mut self, text: &str) {
+        write_head(&mut self.output, major::TEXT, text.len() as u64);
+        self.output.extend_from_slice(text.as_bytes());
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        write_head(&mut self.output, major::BYTES, bytes.len() as u64);
+        self.output.extend_from_slice(bytes);
+    }
+
+    fn write_float(&mut self, value: f64, single: bool) {
+        if self.options.canonical {
+            if let Some(bits) = f64_to_f16(value) {
+                self.output.push(0xf9);
+                self.output.extend(bits.to_be_bytes());
+                return;
+            }
+        }
+        match f64_to_f32(value) {
+            Some(value) if single || self.options.canonical => {
+                self.output.push(0xfa);
+                self.output.extend(value.to_bits().to_be_bytes());
+            }
+            _ => {
+                self.output.push(0xfb);
+                self.output.extend(value.to_bits().to_be_bytes());
+            }
+        }
+    }
+
+    /// Writes an integer too large for a head as a bignum, tagged with whether it's positive.
+    fn write_bignum(&mut self, tag: u64, value: u128) {
+        write_head(&mut self.output, major::TAG, tag);
+        let bytes = value.to_be_bytes();
+        let first = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+        self.write_bytes(&bytes[first..]);
+    }
+
+    fn start_entry(&mut self) {
+        let start = self.output.len();
+        if let Some(Frame::Map { entries, .. }) = self.stack.last_mut() {
+            entries.push(Entry {
+                start,
+                key_end: start,
+            });
+        }
+    }
+
+    fn end_key(&mut self) {
+        let end = self.output.len();
+        if let Some(Frame::Map { entries, .. }) = self.stack.last_mut() {
+            if let Some(entry) = entries.last_mut() {
+                entry.key_end = end;
+            }
+        }
+    }
+
+    /// Writes the head of an array or a map, if it's known how many items it holds.
+    fn start_container(&mut self, major: u8, len: Option<usize>) -> Head {
+        let start = self.output.len();
+        let len = len.map(|len| len as u64);
+        if let Some(len) = len {
+            write_head(&mut self.output, major, len);
+        }
+        Head {
+            start,
+            end: self.output.len(),
+            len,
+        }
+    }
+
+    /// Puts the right head in front of the items of an array or a map, unless it's there.
+    fn finish_head(&mut self, head: Head, major: u8, count: u64) {
+        if head.len == Some(count) {
+            return;
+        }
+        let mut bytes = Vec::new();
+        write_head(&mut bytes, major, count);
+        self.output.splice(head.start..head.end, bytes);
+    }
+
+    fn end_container(&mut self) {
+        let Some(frame) = self.stack.pop() else {
+            return;
+        };
+        match frame {
+            Frame::Array { head, count } => self.finish_head(head, major::ARRAY, count),
+            Frame::Map { head, entries } => {
+                if self.options.canonical {
+                    let end = self.output.len();
+                    let mut spans: Vec<_> = entries
+                        .iter()
+                        .enumerate()
+                        .map(|(i, entry)| {
+                            let next = entries.get(i + 1).map_or(end, |next| next.start);
+                            (entry.start..entry.key_end, entry.start..next)
+                        })
+                        .collect();
+                    spans.sort_by(|(a, _), (b, _)| {
+                        self.output[a.clone()].cmp(&self.output[b.clone()])
+                    });
+                    let mut sorted = Vec::with_capacity(end - head.end);
+                    for (_, entry) in spans {
+                        sorted.extend_from_slice(&self.output[entry]);
+                    }
+                    self.output.truncate(head.end);
+                    self.output.extend(sorted);
+                }
+                self.finish_head(head, major::MAP, entries.len() as u64);
+            }
+        }
+        self.written();
+    }
+}
+
+impl<'shape> Serializer<'shape> for CborSerializer {
+    type Error = Infallible;
+
+    fn serialize_u64(&mut self, value: u64) -> Result<(), Self::Error> {
+        write_head(&mut self.output, major::UNSIGNED, value);
+        self.written();
+        Ok(())
+    }
+
+    fn serialize_u128(&mut self, value: u128) -> Result<(), Self::Error> {
+        match u64::try_from(value) {
+            Ok(value) => write_head(&mut self.output, major::UNSIGNED, value),
+            Err(_) => self.write_bignum(tag::POSITIVE_BIGNUM, value),
+        }
+        self.written();
+        Ok(())
+    }
+
+    fn serialize_i64(&mut self, value: i64) -> Result<(), Self::Error> {
+        if value < 0 {
+            // Negative integers are written as `-1 - n`
+            write_head(&mut self.output, major::NEGATIVE, !(value as u64));
+        } else {
+            write_head(&mut self.output, major::UNSIGNED, value as u64);
+        }
+        self.written();
+        Ok(())
+    }
+
+    fn serialize_i128(&mut self, value: i128) -> Result<(), Self::Error> {
+        if value >= 0 {
+            return self.serialize_u128(value as u128);
+        }
+        let value = !(value as u128);
+        match u64::try_from(value) {
+            Ok(value) => write_head(&mut self.output, major::NEGATIVE, value),
+            Err(_) => self.write_bignum(tag::NEGATIVE_BIGNUM, value),
+        }
+        self.written();
+        Ok(())
+    }
+
+    fn serialize_f32(&mut self, value: f32) -> Result<(), Self::Error> {
+        self.write_float(f64::from(value), true);
+        self.written();
+        Ok(())
+    }
+
+    fn serialize_f64(&mut self, value: f64) -> Result<(), Self::Error> {
+        self.write_float(value, false);
+        self.written();
+        Ok(())
+    }
+
+    fn serialize_bool(&mut self, value: bool) -> Result<(), Self::Error> {
+        self.output.push(if value { 0xf5 } else { 0xf4 });
+        self.written();
+        Ok(())
+    }
+
+    fn serialize_char(&mut self, value: char) -> Result<(), Self::Error> {
+        self.write_text(value.encode_utf8(&mut [0; 4]));
+        self.written();
+        Ok(())
+    }
+
+    fn serialize_str(&mut self, value: &str) -> Result<(), Self::Error> {
+        self.write_text(value);
+        self.written();
+        Ok(())
+    }
+
+    fn serialize_bytes(&mut self, value: &[u8]) -> Result<(), Self::Error> {
+        self.write_bytes(value);
+        self.written();
+        Ok(())
+    }
+
+    fn serialize_affinity_str(
+        &mut self,
+        affinity: &ScalarAffinity<'shape>,
+        value: &str,
+    ) -> Result<(), Self::Error> {
+        match affinity {
+            ScalarAffinity::Time(_) => {
+                trace!("Tagging {value} as a date and time");
+                write_head(&mut self.output, major::TAG, tag::DATE_TIME);
+                self.write_text(value);
+            }
+            ScalarAffinity::UUID(_) => match uuid_bytes(value) {
+                Some(bytes) => {
+                    write_head(&mut self.output, major::TAG, tag::UUID);
+                    self.write_bytes(&bytes);
+                }
+                None => self.write_text(value),
+            },
+            _ => self.write_text(value),
+        }
+        self.written();
+        Ok(())
+    }
+
+    fn serialize_none(&mut self) -> Result<(), Self::Error> {
+        self.output.push(0xf6);
+        self.written();
+        Ok(())
+    }
+
+    fn serialize_unit(&mut self) -> Result<(), Self::Error> {
+        self.output.push(0xf6);
+        self.written();
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        &mut self,
+        _variant_index: usize,
+        variant_name: &'shape str,
+    ) -> Result<(), Self::Error> {
+        self.serialize_str(variant_name)
+    }
+
+    fn start_object(&mut self, len: Option<usize>) -> Result<(), Self::Error> {
+        let head = self.start_container(major::MAP, len);
+        self.stack.push(Frame::Map {
+            head,
+            entries: Vec::new(),
+        });
+        Ok(())
+    }
+
+    fn end_object(&mut self) -> Result<(), Self::Error> {
+        self.end_container();
+        Ok(())
+    }
+
+    fn serialize_field_name(&mut self, name: &'shape str) -> Result<(), Self::Error> {
+        self.start_entry();
+        self.write_text(name);
+        self.end_key();
+        Ok(())
+    }
+
+    fn start_array(&mut self, len: Option<usize>) -> Result<(), Self::Error> {
+        let head = self.start_container(major::ARRAY, len);
+        self.stack.push(Frame::Array { head, count: 0 });
+        Ok(())
+    }
+
+    fn end_array(&mut self) -> Result<(), Self::Error> {
+        self.end_container();
+        Ok(())
+    }
+
+    fn start_map(&mut self, len: Option<usize>) -> Result<(), Self::Error> {
+        self.start_object(len)
+    }
+
+    fn end_map(&mut self) -> Result<(), Self::Error> {
+        self.end_container();
+        Ok(())
+    }
+
+    fn begin_map_key(&mut self) -> Result<(), Self::Error> {
+        self.start_entry();
+        Ok(())
+    }
+
+    fn end_map_key(&mut self) -> Result<(), Self::Error> {
+        self.end_key();
+        Ok(())
+    }
+}
@@ -0,0 +1,301 @@
+use std::collections::HashMap;
+
+use facet::Facet;
+use facet_cbor::{CborOptions, DeserErrorKind, from_slice, to_vec};
+use facet_testhelpers::test;
+
+#[derive(Facet, Debug, PartialEq)]
+struct Reading {
+    sensor: String,
+    celsius: f32,
+    raw: Vec<u8>,
+    #[facet(default)]
+    note: Option<String>,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[repr(u8)]
+enum Command {
+    Stop,
+    Move { speed: u8 },
+    Say(String),
+}
+
+#[test]
+fn test_round_trip() {
+    let readings = vec![
+        Reading {
+            sensor: "porch".to_string(),
+            celsius: 21.5,
+            raw: vec![0x00, 0x7f, 0xff],
+            note: None,
+        },
+        Reading {
+            sensor: "cellar".to_string(),
+            celsius: -3.25,
+            raw: Vec::new(),
+            note: Some("damp".to_string()),
+        },
+    ];
+
+    for options in [CborOptions::new(), CborOptions::new().canonical(true)] {
+        let bytes = options.to_vec(&readings);
+        let back: Vec<Reading> = from_slice(&bytes).map_err(|e| e.into_owned())?;
+        assert_eq!(back, readings);
+    }
+
+    let commands = vec![
+        Command::Stop,
+        Command::Move { speed: 3 },
+        Command::Say("hi".to_string()),
+    ];
+    let bytes = to_vec(&commands);
+    let back: Vec<Command> = from_slice(&bytes).map_err(|e| e.into_owned())?;
+    assert_eq!(back, commands);
+
+    let map = HashMap::from([("one".to_string(), 1u16), ("five hundred".to_string(), 500)]);
+    let bytes = to_vec(&map);
+    let back: HashMap<String, u16> = from_slice(&bytes).map_err(|e| e.into_owned())?;
+    assert_eq!(back, map);
+
+    // Integer keys come back as their decimal text
+    let bytes = to_vec(&HashMap::from([(1u16, true), (500, false)]));
+    let back: HashMap<String, bool> = from_slice(&bytes).map_err(|e| e.into_owned())?;
+    assert_eq!(
+        back,
+        HashMap::from([("1".to_string(), true), ("500".to_string(), false)])
+    );
+}
+
+#[test]
+fn test_numbers() {
+    // From RFC 8949, Appendix A
+    assert_eq!(
+        from_slice::<u64>(&[0x1b, 0, 0, 0, 0xe8, 0xd4, 0xa5, 0x10, 0x00])?,
+        1000000000000
+    );
+    assert_eq!(from_slice::<i32>(&[0x38, 0x63])?, -100);
+    assert_eq!(
+        from_slice::<i64>(&[0x3b, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff])?,
+        i64::MIN
+    );
+    assert_eq!(
+        from_slice::<f64>(&[0xfb, 0x3f, 0xf1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a])?,
+        1.1
+    );
+    assert_eq!(
+        from_slice::<f32>(&[0xfa, 0x47, 0xc3, 0x50, 0x00])?,
+        100000.0
+    );
+
+    // Half-precision floats
+    assert_eq!(from_slice::<f64>(&[0xf9, 0x00, 0x00])?, 0.0);
+    assert_eq!(from_slice::<f64>(&[0xf9, 0x3c, 0x00])?, 1.0);
+    assert_eq!(from_slice::<f64>(&[0xf9, 0x3e, 0x00])?, 1.5);
+    assert_eq!(from_slice::<f64>(&[0xf9, 0x7b, 0xff])?, 65504.0);
+    assert_eq!(
+        from_slice::<f64>(&[0xf9, 0x00, 0x01])?,
+        5.960464477539063e-8
+    );
+    assert_eq!(from_slice::<f64>(&[0xf9, 0x04, 0x00])?, 0.00006103515625);
+    assert_eq!(from_slice::<f64>(&[0xf9, 0xc4, 0x00])?, -4.0);
+    assert_eq!(from_slice::<f64>(&[0xf9, 0x7c, 0x00])?, f64::INFINITY);
+    assert!(from_slice::<f64>(&[0xf9, 0x7e, 0x00])?.is_nan());
+
+    // Bignums that fit
+    assert_eq!(from_slice::<u64>(&[0xc2, 0x42, 0x01, 0x00])?, 256);
+    assert_eq!(from_slice::<i64>(&[0xc3, 0x42, 0x01, 0x00])?, -257);
+    let err = from_slice::<u64>(&[0xc2, 0x49, 1, 0, 0, 0, 0, 0, 0, 0, 0]).unwrap_err();
+    assert!(matches!(err.kind, DeserErrorKind::NumberOutOfRange(_)));
+}
+
+#[test]
+fn test_borrowed_text_and_bytes() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Frame<'a> {
+        name: &'a str,
+        header: [u8; 2],
+        body: Vec<u8>,
+    }
+
+    // {"name": "ping", "header": h'cafe', "body": h'01'}
+    let bytes = b"\xa3\x64name\x64ping\x66header\x42\xca\xfe\x64body\x41\x01";
+    let frame: Frame = from_slice(bytes)?;
+    assert_eq!(
+        frame,
+        Frame {
+            name: "ping",
+            header: [0xca, 0xfe],
+            body: vec![1],
+        }
+    );
+}
+
+#[test]
+fn test_indefinite_lengths() {
+    // [_ 1, [2, 3], [_ 4, 5]]
+    let bytes = b"\x9f\x01\x82\x02\x03\x9f\x04\x05\xff\xff";
+    let back: (u8, Vec<u8>, Vec<u8>) = from_slice(bytes)?;
+    assert_eq!(back, (1, vec![2, 3], vec![4, 5]));
+
+    // {_ "sensor": (_ "po", "rch"), "celsius": 21.5, "raw": (_ h'01', h'0203')}
+    let mut bytes = b"\xbf\x66sensor\x7f\x62po\x63rch\xff".to_vec();
+    bytes.extend(b"\x67celsius\xf9\x4d\x60\x63raw\x5f\x41\x01\x42\x02\x03\xff\xff");
+    let reading: Reading = from_slice(&bytes).map_err(|e| e.into_owned())?;
+    assert_eq!(
+        reading,
+        Reading {
+            sensor: "porch".to_string(),
+            celsius: 21.5,
+            raw: vec![1, 2, 3],
+            note: None,
+        }
+    );
+
+    // Chunks have to be of the same kind of string
+    let bytes = b"\x7f\x62po\x41\x01\xff";
+    let err = from_slice::<String>(bytes).unwrap_err();
+    assert!(matches!(
+        err.kind,
+        DeserErrorKind::UnexpectedByte { got: 0x41, .. }
+    ));
+    assert_eq!(err.span.start(), 4);
+}
+
+#[test]
+fn test_tagged_scalars() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Event {
+        id: uuid::Uuid,
+        at: time::OffsetDateTime,
+    }
+
+    let event = Event {
+        id: uuid::Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8")?,
+        at: time::macros::datetime!(2013-03-21 20:04:00 UTC),
+    };
+    let bytes = to_vec(&event);
+    let back: Event = from_slice(&bytes).map_err(|e| e.into_owned())?;
+    assert_eq!(back, event);
+
+    // Seconds since the epoch, as an integer or a float
+    let at: time::OffsetDateTime = from_slice(&[0xc1, 0x1a, 0x51, 0x4b, 0x67, 0xb0])?;
+    assert_eq!(at, time::macros::datetime!(2013-03-21 20:04:00 UTC));
+    let at: time::OffsetDateTime =
+        from_slice(&[0xc1, 0xfb, 0x41, 0xd4, 0x52, 0xd9, 0xec, 0x20, 0x00, 0x00])?;
+    assert_eq!(at, time::macros::datetime!(2013-03-21 20:04:00.5 UTC));
+    let at: time::OffsetDateTime = from_slice(&[0xc1, 0x3a, 0x00, 0x01, 0x51, 0x7f])?;
+    assert_eq!(at, time::macros::datetime!(1969-12-31 00:00:00 UTC));
+
+    // Tags this crate doesn't know are looked through, like the self-described CBOR one
+    assert_eq!(from_slice::<u8>(&[0xd9, 0xd9, 0xf7, 0x05])?, 5);
+}
+
+#[test]
+fn test_defaults_and_unknown_fields() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Settings {
+        name: String,
+        #[facet(default = 3)]
+        retries: u8,
+    }
+
+    // {"name": "a", "extra": [1, {"x": null}]}
+    let bytes = b"\xa2\x64name\x61a\x65extra\x82\x01\xa1\x61x\xf6";
+    let settings: Settings = from_slice(bytes)?;
+    assert_eq!(
+        settings,
+        Settings {
+            name: "a".to_string(),
+            retries: 3,
+        }
+    );
+
+    #[derive(Facet, Debug)]
+    #[facet(deny_unknown_fields)]
+    struct Strict {
+        #[allow(dead_code)]
+        name: String,
+    }
+
+    let err = from_slice::<Strict>(bytes).unwrap_err();
+    assert!(matches!(
+        err.kind,
+        DeserErrorKind::UnknownField { ref field_name, .. } if field_name == "extra"
+    ));
+    assert_eq!(&bytes[err.span.start()..err.span.end()], b"\x65extra");
+}
+
+#[test]
+fn test_tagged_and_untagged_enums() {
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[facet(tag = "type")]
+    enum Shape {
+        Circle { radius: f64 },
+        Square { side: f64 },
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[facet(untagged)]
+    enum Value {
+        Number(u32),
+        Text(String),
+        Pair { a: u32, b: u32 },
+    }
+
+    // {"side": 2.0, "type": "Square"}, with the tag after the fields
+    let bytes = b"\xa2\x64side\xf9\x40\x00\x64type\x66Square";
+    assert_eq!(from_slice::<Shape>(bytes)?, Shape::Square { side: 2.0 });
+    let circle = Shape::Circle { radius: 1.5 };
+    let bytes = to_vec(&circle);
+    assert_eq!(
+        from_slice::<Shape>(&bytes).map_err(|e| e.into_owned())?,
+        circle
+    );
+
+    let values = vec![
+        Value::Number(3),
+        Value::Text("three".to_string()),
+        Value::Pair { a: 1, b: 2 },
+    ];
+    let bytes = to_vec(&values);
+    let back: Vec<Value> = from_slice(&bytes).map_err(|e| e.into_owned())?;
+    assert_eq!(back, values);
+}
+
+#[test]
+fn test_errors() {
+    // Truncated
+    let err = from_slice::<String>(b"\x65abc").unwrap_err();
+    assert!(matches!(err.kind, DeserErrorKind::UnexpectedEof { .. }));
+
+    // Reserved additional information
+    let err = from_slice::<u8>(&[0x1c]).unwrap_err();
+    assert!(matches!(
+        err.kind,
+        DeserErrorKind::UnexpectedByte { got: 0x1c, .. }
+    ));
+
+    // A break outside of anything of indefinite length
+    let err = from_slice::<Vec<u8>>(&[0x82, 0x01, 0xff]).unwrap_err();
+    assert!(matches!(
+        err.kind,
+        DeserErrorKind::UnexpectedByte { got: 0xff, .. }
+    ));
+    assert_eq!(err.span.start(), 2);
+
+    // More than one data item
+    let err = from_slice::<u8>(&[0x01, 0x02]).unwrap_err();
+    assert_eq!(err.span.start(), 1);
+
+    // Text that isn't UTF-8
+    let err = from_slice::<String>(&[0x62, 0xc3, 0x28]).unwrap_err();
+    assert!(matches!(err.kind, DeserErrorKind::InvalidUtf8(_)));
+
+    // Values of the wrong type point at the value
+    let bytes = b"\xa3\x66sensor\x05\x67celsius\xf9\x4d\x60\x63raw\x40";
+    let err = from_slice::<Reading>(bytes).unwrap_err();
+    assert_eq!(err.span.start(), 8);
+}
@@ -0,0 +1,196 @@
+use std::collections::BTreeMap;
+
+use facet::Facet;
+use facet_cbor::{CborOptions, to_vec};
+use facet_testhelpers::test;
+
+#[test]
+fn test_integers() {
+    // From RFC 8949, Appendix A
+    assert_eq!(to_vec(&0u8), [0x00]);
+    assert_eq!(to_vec(&23u8), [0x17]);
+    assert_eq!(to_vec(&24u8), [0x18, 0x18]);
+    assert_eq!(to_vec(&1000u16), [0x19, 0x03, 0xe8]);
+    assert_eq!(to_vec(&1000000u32), [0x1a, 0x00, 0x0f, 0x42, 0x40]);
+    assert_eq!(
+        to_vec(&18446744073709551615u64),
+        [0x1b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]
+    );
+    assert_eq!(to_vec(&-1i8), [0x20]);
+    assert_eq!(to_vec(&-100i32), [0x38, 0x63]);
+    assert_eq!(to_vec(&-1000i64), [0x39, 0x03, 0xe7]);
+
+    // Too large for a head, so bignums
+    assert_eq!(
+        to_vec(&18446744073709551616u128),
+        [
+            0xc2, 0x49, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+        ]
+    );
+    assert_eq!(
+        to_vec(&-18446744073709551617i128),
+        [
+            0xc3, 0x49, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+        ]
+    );
+    assert_eq!(to_vec(&-18446744073709551616i128), {
+        let mut expected = vec![0x3b];
+        expected.extend([0xff; 8]);
+        expected
+    });
+}
+
+#[test]
+fn test_floats() {
+    assert_eq!(
+        to_vec(&1.1f64),
+        [0xfb, 0x3f, 0xf1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a]
+    );
+    assert_eq!(to_vec(&100000.0f32), [0xfa, 0x47, 0xc3, 0x50, 0x00]);
+    assert_eq!(to_vec(&1.5f64), [0xfb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0]);
+
+    // Canonical floats are as narrow as they can be
+    let canonical = CborOptions::new().canonical(true);
+    assert_eq!(canonical.to_vec(&0.0f64), [0xf9, 0x00, 0x00]);
+    assert_eq!(canonical.to_vec(&-0.0f64), [0xf9, 0x80, 0x00]);
+    assert_eq!(canonical.to_vec(&1.5f64), [0xf9, 0x3e, 0x00]);
+    assert_eq!(canonical.to_vec(&65504.0f64), [0xf9, 0x7b, 0xff]);
+    assert_eq!(
+        canonical.to_vec(&5.960464477539063e-8f64),
+        [0xf9, 0x00, 0x01]
+    );
+    assert_eq!(canonical.to_vec(&-4.0f32), [0xf9, 0xc4, 0x00]);
+    assert_eq!(canonical.to_vec(&f64::INFINITY), [0xf9, 0x7c, 0x00]);
+    assert_eq!(canonical.to_vec(&f64::NAN), [0xf9, 0x7e, 0x00]);
+    assert_eq!(
+        canonical.to_vec(&100000.0f64),
+        [0xfa, 0x47, 0xc3, 0x50, 0x00]
+    );
+    assert_eq!(
+        canonical.to_vec(&1.1f64),
+        [0xfb, 0x3f, 0xf1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a]
+    );
+}
+
+#[test]
+fn test_strings_and_bytes() {
+    assert_eq!(to_vec(&""), [0x60]);
+    assert_eq!(to_vec(&"IETF"), [0x64, 0x49, 0x45, 0x54, 0x46]);
+    assert_eq!(to_vec(&"\u{00fc}".to_string()), [0x62, 0xc3, 0xbc]);
+    assert_eq!(to_vec(&'a'), [0x61, 0x61]);
+    assert_eq!(to_vec(&vec![1u8, 2, 3, 4]), [0x44, 0x01, 0x02, 0x03, 0x04]);
+}
+
+#[test]
+fn test_structs_lists_and_maps() {
+    #[derive(Facet)]
+    struct Point {
+        x: i32,
+        y: i32,
+        label: Option<String>,
+    }
+
+    let point = Point {
+        x: 1,
+        y: -2,
+        label: None,
+    };
+    assert_eq!(
+        to_vec(&point),
+        b"\xa3\x61x\x01\x61y\x21\x65label\xf6".as_slice()
+    );
+
+    assert_eq!(to_vec(&vec![1u32, 2, 3]), [0x83, 0x01, 0x02, 0x03]);
+    assert_eq!(
+        to_vec(&(1u8, vec![2u32, 3], true)),
+        [0x83, 0x01, 0x82, 0x02, 0x03, 0xf5]
+    );
+
+    let mut long = Vec::new();
+    long.extend(1u32..=25);
+    let encoded = to_vec(&long);
+    assert_eq!(&encoded[..2], [0x98, 0x19]);
+    assert_eq!(encoded.len(), 2 + 23 + 2 * 2);
+
+    let map = BTreeMap::from([("a", 1u8), ("b", 2)]);
+    assert_eq!(to_vec(&map), b"\xa2\x61a\x01\x61b\x02".as_slice());
+}
+
+#[test]
+fn test_enums() {
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Command {
+        Stop,
+        Move { speed: u8 },
+        Say(String),
+    }
+
+    assert_eq!(to_vec(&Command::Stop), b"\x64Stop".as_slice());
+    assert_eq!(
+        to_vec(&Command::Move { speed: 3 }),
+        b"\xa1\x64Move\xa1\x65speed\x03".as_slice()
+    );
+    assert_eq!(
+        to_vec(&Command::Say("hi".to_string())),
+        b"\xa1\x63Say\x62hi".as_slice()
+    );
+}
+
+#[test]
+fn test_canonical_key_order() {
+    #[derive(Facet)]
+    struct Config {
+        zeta: u8,
+        b: u8,
+        alpha: BTreeMap<i32, bool>,
+    }
+
+    let config = Config {
+        zeta: 1,
+        b: 2,
+        alpha: BTreeMap::from([(10, true), (-1, false), (100, true)]),
+    };
+
+    // Keys sort by their encoded bytes: shorter first, then bytewise, so 10 comes before
+    // 100, and both before -1
+    assert_eq!(
+        CborOptions::new().canonical(true).to_vec(&config),
+        b"\xa3\x61b\x02\x64zeta\x01\x65alpha\xa3\x0a\xf5\x18\x64\xf5\x20\xf4".as_slice()
+    );
+    // Otherwise, in the order they're declared
+    assert_eq!(
+        to_vec(&config),
+        b"\xa3\x64zeta\x01\x61b\x02\x65alpha\xa3\x20\xf4\x0a\xf5\x18\x64\xf5".as_slice()
+    );
+}
+
+#[test]
+fn test_tagged_scalars() {
+    #[derive(Facet)]
+    struct Event {
+        id: uuid::Uuid,
+        at: time::OffsetDateTime,
+    }
+
+    let event = Event {
+        id: uuid::Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8")?,
+        at: time::macros::datetime!(2013-03-21 20:04:00 UTC),
+    };
+
+    let mut expected = b"\xa2\x62id\xd8\x25\x50".to_vec();
+    expected.extend([
+        0x67, 0xe5, 0x50, 0x44, 0x10, 0xb1, 0x42, 0x6f, 0x92, 0x47, 0xbb, 0x68, 0x0e, 0x5f, 0xe0,
+        0xc8,
+    ]);
+    expected.extend(b"\x62at\xc0\x742013-03-21T20:04:00Z");
+    assert_eq!(to_vec(&event), expected);
+}
+
+#[test]
+fn test_to_writer() {
+    let mut output = Vec::new();
+    facet_cbor::to_writer(&vec!["a", "b"], &mut output)?;
+    assert_eq!(output, b"\x82\x61a\x61b");
+}
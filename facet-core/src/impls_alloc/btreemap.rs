@@ -160,6 +160,10 @@ where
                                     let map = ptr.get::<Self>();
                                     map.get(key.get()).map(|v| PtrConst::new(v as *const _))
                                 })
+                                .remove(|ptr, key| unsafe {
+                                    let map = ptr.as_mut::<Self>();
+                                    map.remove(key.get::<K>()).is_some()
+                                })
                                 .iter_vtable(
                                     IterVTable::builder()
                                         .init_with_value(|ptr| unsafe {
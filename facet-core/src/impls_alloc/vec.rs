@@ -128,6 +128,10 @@ where
                                     let item = vec.get_mut(index)?;
                                     Some(PtrMut::new(item))
                                 })
+                                .remove(|ptr, index| unsafe {
+                                    let vec = ptr.as_mut::<Self>();
+                                    drop(vec.remove(index));
+                                })
                                 .as_ptr(|ptr| unsafe {
                                     let vec = ptr.get::<Self>();
                                     PtrConst::new(vec.as_ptr())
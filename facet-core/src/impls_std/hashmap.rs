@@ -163,6 +163,10 @@ where
                                     let map = ptr.get::<HashMap<K, V>>();
                                     map.get(key.get()).map(|v| PtrConst::new(v))
                                 })
+                                .remove(|ptr, key| unsafe {
+                                    let map = ptr.as_mut::<HashMap<K, V>>();
+                                    map.remove(key.get::<K>()).is_some()
+                                })
                                 .iter_vtable(
                                     IterVTable::builder()
                                         .init_with_value(|ptr| unsafe {
@@ -1,4 +1,4 @@
-use crate::{Facet, Opaque, Shape};
+use crate::{Facet, Opaque, PtrConst, PtrMut, PtrUninit, Shape};
 
 #[doc(hidden)]
 pub const fn shape_of<'a, TStruct, TField: Facet<'a>>(
@@ -17,6 +17,45 @@ where
     Opaque::<TField>::SHAPE
 }
 
+#[doc(hidden)]
+pub const fn shape_of_serialize_with<'a, TField, TProxy: Facet<'a>>(
+    _f: fn(&TField) -> TProxy,
+) -> &'static Shape<'static> {
+    TProxy::SHAPE
+}
+
+#[doc(hidden)]
+pub unsafe fn call_serialize_with<'dst, TField, TProxy>(
+    f: fn(&TField) -> TProxy,
+    field: PtrConst<'_>,
+    target: PtrUninit<'dst>,
+) -> PtrMut<'dst> {
+    unsafe { target.put(f(field.get::<TField>())) }
+}
+
+#[doc(hidden)]
+pub const fn shape_of_deserialize_with<'a, TField, TProxy: Facet<'a>, TError>(
+    _f: fn(TProxy) -> Result<TField, TError>,
+) -> &'static Shape<'static> {
+    TProxy::SHAPE
+}
+
+#[doc(hidden)]
+pub unsafe fn call_deserialize_with<'dst, TField, TProxy, TError: core::fmt::Display>(
+    f: fn(TProxy) -> Result<TField, TError>,
+    source: PtrMut<'_>,
+    target: PtrUninit<'dst>,
+    error: &mut dyn core::fmt::Write,
+) -> Option<PtrMut<'dst>> {
+    match f(unsafe { source.read::<TProxy>() }) {
+        Ok(value) => Some(unsafe { target.put(value) }),
+        Err(err) => {
+            let _ = write!(error, "{err}");
+            None
+        }
+    }
+}
+
 /// Creates a `ValueVTable` for a given type.
 ///
 /// This macro generates a `ValueVTable` with implementations for various traits
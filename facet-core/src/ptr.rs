@@ -363,6 +363,19 @@ impl<'mem> PtrMut<'mem> {
         unsafe { &*(self.0.as_ptr() as *const T) }
     }
 
+    /// Returns a pointer with the given offset added
+    ///
+    /// # Safety
+    ///
+    /// Offset must be within the bounds of the allocated memory,
+    /// and the resulting pointer must be properly aligned.
+    pub const unsafe fn field(self, offset: usize) -> PtrMut<'mem> {
+        PtrMut(
+            unsafe { NonNull::new_unchecked(self.0.as_ptr().byte_add(offset)) },
+            PhantomData,
+        )
+    }
+
     /// Make a const ptr out of this mut ptr
     pub const fn as_const<'borrow: 'mem>(self) -> PtrConst<'borrow> {
         PtrConst(self.0, PhantomData)
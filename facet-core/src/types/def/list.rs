@@ -104,6 +104,15 @@ pub type ListGetFn = unsafe fn(list: PtrConst, index: usize) -> Option<PtrConst>
 /// The `list` parameter must point to aligned, initialized memory of the correct type.
 pub type ListGetMutFn = unsafe fn(list: PtrMut, index: usize) -> Option<PtrMut>;
 
+/// Remove the item at `index` from the list, dropping it and shifting all items after it
+/// to the left.
+///
+/// # Safety
+///
+/// The `list` parameter must point to aligned, initialized memory of the correct type.
+/// `index` must be less than the length of the list.
+pub type ListRemoveFn = unsafe fn(list: PtrMut, index: usize);
+
 /// Get pointer to the data buffer of the list.
 ///
 /// # Safety
@@ -139,6 +148,10 @@ pub struct ListVTable {
     /// cf. [`ListGetMutFn`]
     pub get_mut: ListGetMutFn,
 
+    /// cf. [`ListRemoveFn`]
+    /// Only available for lists that can shrink
+    pub remove: Option<ListRemoveFn>,
+
     /// cf. [`ListAsPtrFn`]
     /// Only available for types that can be accessed as a contiguous array
     pub as_ptr: Option<ListAsPtrFn>,
@@ -165,6 +178,7 @@ pub struct ListVTableBuilder {
     len: Option<ListLenFn>,
     get: Option<ListGetFn>,
     get_mut: Option<ListGetMutFn>,
+    remove: Option<ListRemoveFn>,
     as_ptr: Option<ListAsPtrFn>,
     as_mut_ptr: Option<ListAsMutPtrFn>,
     iter_vtable: Option<IterVTable<PtrConst<'static>>>,
@@ -180,6 +194,7 @@ impl ListVTableBuilder {
             len: None,
             get: None,
             get_mut: None,
+            remove: None,
             as_ptr: None,
             as_mut_ptr: None,
             iter_vtable: None,
@@ -216,6 +231,12 @@ impl ListVTableBuilder {
         self
     }
 
+    /// Sets the remove field
+    pub const fn remove(mut self, f: ListRemoveFn) -> Self {
+        self.remove = Some(f);
+        self
+    }
+
     /// Sets the as_ptr field
     pub const fn as_ptr(mut self, f: ListAsPtrFn) -> Self {
         self.as_ptr = Some(f);
@@ -246,6 +267,7 @@ impl ListVTableBuilder {
             len: self.len.unwrap(),
             get: self.get.unwrap(),
             get_mut: self.get_mut.unwrap(),
+            remove: self.remove,
             as_ptr: self.as_ptr,
             as_mut_ptr: self.as_mut_ptr,
             iter_vtable: self.iter_vtable.unwrap(),
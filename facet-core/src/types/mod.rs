@@ -125,6 +125,16 @@ pub enum ShapeAttribute<'shape> {
     Transparent,
     /// Specifies a case conversion rule for all fields or variants
     RenameAll(&'shape str),
+    /// For enums: the name of the key holding the variant name. On its own this
+    /// means internal tagging, together with [`ShapeAttribute::Content`] it means
+    /// adjacent tagging.
+    Tag(&'shape str),
+    /// For enums: the name of the key holding the variant's content, used together
+    /// with [`ShapeAttribute::Tag`] for adjacent tagging.
+    Content(&'shape str),
+    /// For enums: the variant name is not written at all, and the variant is
+    /// picked by trying each of them in order when deserializing.
+    Untagged,
     /// Custom field attribute containing arbitrary text
     Arbitrary(&'shape str),
 }
@@ -180,6 +190,67 @@ impl<'shape> Shape<'shape> {
             }
         })
     }
+
+    /// See [`ShapeAttribute::Tag`]
+    pub fn get_tag_attr(&self) -> Option<&'shape str> {
+        self.attributes.iter().find_map(|attr| {
+            if let ShapeAttribute::Tag(tag) = attr {
+                Some(*tag)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// See [`ShapeAttribute::Content`]
+    pub fn get_content_attr(&self) -> Option<&'shape str> {
+        self.attributes.iter().find_map(|attr| {
+            if let ShapeAttribute::Content(content) = attr {
+                Some(*content)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// See [`ShapeAttribute::Untagged`]
+    pub fn has_untagged_attr(&self) -> bool {
+        self.attributes.contains(&ShapeAttribute::Untagged)
+    }
+
+    /// Returns how the variants of this shape are represented when (de)serializing,
+    /// as specified by the `tag`, `content` and `untagged` attributes.
+    pub fn enum_tagging(&self) -> EnumTagging<'shape> {
+        if self.has_untagged_attr() {
+            return EnumTagging::Untagged;
+        }
+        match (self.get_tag_attr(), self.get_content_attr()) {
+            (Some(tag), Some(content)) => EnumTagging::Adjacent { tag, content },
+            (Some(tag), None) => EnumTagging::Internal { tag },
+            _ => EnumTagging::External,
+        }
+    }
+}
+
+/// How the variants of an enum are represented in self-describing formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnumTagging<'shape> {
+    /// `{"Variant": content}`, or just `"Variant"` for unit variants (the default)
+    External,
+    /// `{"<tag>": "Variant", ...fields}`
+    Internal {
+        /// The key holding the variant name
+        tag: &'shape str,
+    },
+    /// `{"<tag>": "Variant", "<content>": content}`
+    Adjacent {
+        /// The key holding the variant name
+        tag: &'shape str,
+        /// The key holding the variant content
+        content: &'shape str,
+    },
+    /// Just the content, the variant is found by trying each in order
+    Untagged,
 }
 
 /// Builder for [`Shape`]
@@ -103,6 +103,20 @@ impl<'shape> Variant<'shape> {
         self.attributes
             .contains(&VariantAttribute::Arbitrary(content))
     }
+
+    /// Returns the alternative names this variant accepts when deserializing,
+    /// set via `#[facet(alias = "...")]`
+    pub fn aliases(&self) -> impl Iterator<Item = &'shape str> + '_ {
+        self.attributes.iter().filter_map(|attr| match attr {
+            VariantAttribute::Alias(alias) => Some(*alias),
+            _ => None,
+        })
+    }
+
+    /// Returns true if `name` is the name of this variant or one of its aliases
+    pub fn is_named(&self, name: &str) -> bool {
+        self.name == name || self.aliases().any(|alias| alias == name)
+    }
 }
 
 /// Builder for Variant
@@ -176,6 +190,8 @@ impl<'shape> VariantBuilder<'shape> {
 pub enum VariantAttribute<'shape> {
     /// Custom field attribute containing arbitrary text
     Arbitrary(&'shape str),
+    /// An alternative name accepted when deserializing, `#[facet(alias = "...")]`
+    Alias(&'shape str),
 }
 
 /// All possible representations for Rust enums — ie. the type/size of the discriminant
@@ -1,4 +1,4 @@
-use crate::PtrConst;
+use crate::{Facet, PtrConst, PtrMut, PtrUninit, ShapeLayout};
 
 use super::{DefaultInPlaceFn, Shape};
 use bitflags::bitflags;
@@ -49,6 +49,12 @@ impl Field<'_> {
         }
         false
     }
+
+    /// Returns true if the field is never read when deserializing, and always gets its
+    /// default value instead.
+    pub fn should_skip_deserializing(&self) -> bool {
+        self.flags.contains(FieldFlags::SKIP_DESERIALIZING)
+    }
 }
 
 /// Vtable for field-specific operations
@@ -61,12 +67,135 @@ pub struct FieldVTable {
 
     /// Function to get the default value for this field
     pub default_fn: Option<DefaultInPlaceFn>,
+
+    /// Converts this field to another value, serialized in its place
+    pub serialize_with: Option<SerializeWith>,
+
+    /// Builds this field from another value, deserialized in its place
+    pub deserialize_with: Option<DeserializeWith>,
 }
 
 /// A function that, if present, determines whether field should be included in the serialization
 /// step.
 pub type SkipSerializingIfFn = for<'mem> unsafe fn(value: PtrConst<'mem>) -> bool;
 
+/// Serializes a field as a value of another type, set via `#[facet(serialize_with = ..)]`
+/// or `#[facet(proxy = ..)]`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(C)]
+#[non_exhaustive]
+pub struct SerializeWith {
+    /// Shape of the value that is serialized instead of the field
+    pub shape: &'static Shape<'static>,
+
+    /// Converts the field into that value
+    pub convert_fn: SerializeWithFn,
+}
+
+/// Converts a field into the value that is serialized in its place.
+///
+/// # Safety
+///
+/// `field` must point to an initialized value of the field's type. `target` has the
+/// layout of the [`SerializeWith::shape`] but points to uninitialized memory; it is
+/// returned initialized.
+pub type SerializeWithFn =
+    for<'field, 'dst> unsafe fn(field: PtrConst<'field>, target: PtrUninit<'dst>) -> PtrMut<'dst>;
+
+impl SerializeWith {
+    /// Creates a `SerializeWith` from the shape of the serialized value and a conversion function
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shape is unsized, which fails the build when called in a const context,
+    /// as derived implementations do.
+    pub const fn new(shape: &'static Shape<'static>, convert_fn: SerializeWithFn) -> Self {
+        assert!(
+            matches!(shape.layout, ShapeLayout::Sized(_)),
+            "values serialized in place of fields must be sized"
+        );
+        Self { shape, convert_fn }
+    }
+
+    /// Serializes a field of type `T` as a `P`, converted with `From<&T>`
+    pub const fn proxy<'a, T, P>() -> Self
+    where
+        P: Facet<'a> + for<'t> From<&'t T>,
+    {
+        unsafe fn convert<'field, 'dst, T, P: for<'t> From<&'t T>>(
+            field: PtrConst<'field>,
+            target: PtrUninit<'dst>,
+        ) -> PtrMut<'dst> {
+            unsafe { target.put(P::from(field.get::<T>())) }
+        }
+
+        Self::new(P::SHAPE, convert::<T, P>)
+    }
+}
+
+/// Deserializes a field from a value of another type, set via `#[facet(deserialize_with = ..)]`
+/// or `#[facet(proxy = ..)]`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(C)]
+#[non_exhaustive]
+pub struct DeserializeWith {
+    /// Shape of the value that is deserialized instead of the field
+    pub shape: &'static Shape<'static>,
+
+    /// Builds the field from that value
+    pub convert_fn: DeserializeWithFn,
+}
+
+/// Builds a field from the value that was deserialized in its place, taking ownership of it.
+///
+/// On failure, the reason is written to `error` and `None` is returned.
+///
+/// # Safety
+///
+/// `source` must point to an initialized value of the [`DeserializeWith::shape`], which
+/// must not be used afterwards. `target` has the layout of the field's type but points
+/// to uninitialized memory; it is returned initialized on success.
+pub type DeserializeWithFn = for<'src, 'dst> unsafe fn(
+    source: PtrMut<'src>,
+    target: PtrUninit<'dst>,
+    error: &mut dyn core::fmt::Write,
+) -> Option<PtrMut<'dst>>;
+
+impl DeserializeWith {
+    /// Creates a `DeserializeWith` from the shape of the deserialized value and a conversion function
+    pub const fn new(shape: &'static Shape<'static>, convert_fn: DeserializeWithFn) -> Self {
+        Self { shape, convert_fn }
+    }
+
+    /// Deserializes a field of type `T` from a `P`, converted with `TryFrom<P>`
+    pub const fn proxy<'a, T, P>() -> Self
+    where
+        P: Facet<'a>,
+        T: TryFrom<P>,
+        T::Error: core::fmt::Display,
+    {
+        unsafe fn convert<'src, 'dst, T, P>(
+            source: PtrMut<'src>,
+            target: PtrUninit<'dst>,
+            error: &mut dyn core::fmt::Write,
+        ) -> Option<PtrMut<'dst>>
+        where
+            T: TryFrom<P>,
+            T::Error: core::fmt::Display,
+        {
+            match T::try_from(unsafe { source.read::<P>() }) {
+                Ok(value) => Some(unsafe { target.put(value) }),
+                Err(err) => {
+                    let _ = write!(error, "{err}");
+                    None
+                }
+            }
+        }
+
+        Self::new(P::SHAPE, convert::<T, P>)
+    }
+}
+
 impl<'shape> Field<'shape> {
     /// Returns the shape of the inner type
     pub const fn shape(&self) -> &'shape Shape<'shape> {
@@ -82,6 +211,20 @@ impl<'shape> Field<'shape> {
     pub fn is_sensitive(&'static self) -> bool {
         self.flags.contains(FieldFlags::SENSITIVE)
     }
+
+    /// Returns the alternative names this field accepts when deserializing,
+    /// set via `#[facet(alias = "...")]`
+    pub fn aliases(&self) -> impl Iterator<Item = &'shape str> + '_ {
+        self.attributes.iter().filter_map(|attr| match attr {
+            FieldAttribute::Alias(alias) => Some(*alias),
+            _ => None,
+        })
+    }
+
+    /// Returns true if `name` is the name of this field or one of its aliases
+    pub fn is_named(&self, name: &str) -> bool {
+        self.name == name || self.aliases().any(|alias| alias == name)
+    }
 }
 
 /// An attribute that can be set on a field
@@ -91,12 +234,16 @@ impl<'shape> Field<'shape> {
 pub enum FieldAttribute<'shape> {
     /// Custom field attribute containing arbitrary text
     Arbitrary(&'shape str),
+    /// An alternative name accepted when deserializing, `#[facet(alias = "...")]`
+    Alias(&'shape str),
 }
 
 /// Builder for FieldVTable
 pub struct FieldVTableBuilder {
     skip_serializing_if: Option<SkipSerializingIfFn>,
     default_fn: Option<DefaultInPlaceFn>,
+    serialize_with: Option<SerializeWith>,
+    deserialize_with: Option<DeserializeWith>,
 }
 
 impl FieldVTableBuilder {
@@ -106,6 +253,8 @@ impl FieldVTableBuilder {
         Self {
             skip_serializing_if: None,
             default_fn: None,
+            serialize_with: None,
+            deserialize_with: None,
         }
     }
 
@@ -121,11 +270,25 @@ impl FieldVTableBuilder {
         self
     }
 
+    /// Sets the serialize_with conversion for the FieldVTable
+    pub const fn serialize_with(mut self, serialize_with: SerializeWith) -> Self {
+        self.serialize_with = Some(serialize_with);
+        self
+    }
+
+    /// Sets the deserialize_with conversion for the FieldVTable
+    pub const fn deserialize_with(mut self, deserialize_with: DeserializeWith) -> Self {
+        self.deserialize_with = Some(deserialize_with);
+        self
+    }
+
     /// Builds the FieldVTable
     pub const fn build(self) -> FieldVTable {
         FieldVTable {
             skip_serializing_if: self.skip_serializing_if,
             default_fn: self.default_fn,
+            serialize_with: self.serialize_with,
+            deserialize_with: self.deserialize_with,
         }
     }
 }
@@ -163,6 +326,8 @@ impl<'shape> FieldBuilder<'shape> {
                 FieldVTable {
                     skip_serializing_if: None,
                     default_fn: None,
+                    serialize_with: None,
+                    deserialize_with: None,
                 }
             },
         }
@@ -251,6 +416,10 @@ bitflags! {
         /// When deserializing, if this field is missing, use its default value. If
         /// `FieldVTable::default_fn` is set, use that.
         const DEFAULT = 1 << 4;
+
+        /// Flag indicating this field is never read when deserializing: it always gets its
+        /// default value, from `FieldVTable::default_fn` if set.
+        const SKIP_DESERIALIZING = 1 << 5;
     }
 }
 
@@ -0,0 +1,558 @@
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use facet_core::{Def, Facet, ScalarAffinity, ScalarDef, Shape, Type, UserType};
+use facet_deserialize::{
+    DeserError, DeserErrorKind, Expectation, Format, NextData, NextResult, Outcome, Scalar, Span,
+    Spanned,
+};
+use log::trace;
+
+use crate::CsvOptions;
+
+/// Deserializes CSV with a header row, and cells separated by commas, into one `T` per row
+pub fn from_str<'input, 'facet, T: Facet<'facet>>(
+    input: &'input str,
+) -> Result<Vec<T>, DeserError<'input, 'static>>
+where
+    'input: 'facet,
+{
+    CsvOptions::new().from_str(input)
+}
+
+/// Deserializes CSV with a header row, and cells separated by commas, into one `T` per row,
+/// reading it all from `reader` first.
+///
+/// Strings are always copied out of the input, so `T` can't borrow from it.
+#[cfg(feature = "std")]
+pub fn from_reader<'facet, R: std::io::Read, T: Facet<'facet>>(
+    reader: R,
+) -> Result<Vec<T>, DeserError<'static, 'static>> {
+    CsvOptions::new().from_reader(reader)
+}
+
+/// Iterates over the rows of CSV with a header row, and cells separated by commas, each
+/// deserialized into a `T`
+pub fn rows<'input, T>(input: &'input str) -> Rows<'input, T> {
+    CsvOptions::new().rows(input)
+}
+
+impl CsvOptions {
+    /// Deserializes `input` into one `T` per row.
+    pub fn from_str<'input, 'facet, T: Facet<'facet>>(
+        &self,
+        input: &'input str,
+    ) -> Result<Vec<T>, DeserError<'input, 'static>>
+    where
+        'input: 'facet,
+    {
+        self.rows(input).collect()
+    }
+
+    /// Deserializes CSV read from `reader` into one `T` per row.
+    ///
+    /// Strings are always copied out of the input, so `T` can't borrow from it.
+    #[cfg(feature = "std")]
+    pub fn from_reader<'facet, R: std::io::Read, T: Facet<'facet>>(
+        &self,
+        mut reader: R,
+    ) -> Result<Vec<T>, DeserError<'static, 'static>> {
+        let mut input = Vec::new();
+        if let Err(e) = reader.read_to_end(&mut input) {
+            let kind = DeserErrorKind::Io(e.to_string());
+            return Err(DeserError::new(kind, &b""[..], Span::new(0, 0), "csv"));
+        }
+        let mut walker = RowWalker::new(*self);
+        let mut values = Vec::new();
+        // The deserializer only sees an empty input, and is given copies of the cells
+        while let Some(res) = walker.next_row(&input, &b""[..], true) {
+            match res {
+                Ok(value) => values.push(value),
+                Err(mut err) => {
+                    err.input = Cow::Owned(input);
+                    return Err(err);
+                }
+            }
+        }
+        Ok(values)
+    }
+
+    /// Iterates over the rows of `input`, each deserialized into a `T`. A row that fails to
+    /// deserialize doesn't stop the ones after it.
+    pub fn rows<'input, T>(&self, input: &'input str) -> Rows<'input, T> {
+        Rows {
+            input: input.as_bytes(),
+            walker: RowWalker::new(*self),
+            _t: PhantomData,
+        }
+    }
+}
+
+/// An iterator over the rows of some CSV, each deserialized into a `T`.
+///
+/// Each error points at the row it comes from.
+pub struct Rows<'input, T> {
+    input: &'input [u8],
+    walker: RowWalker,
+    _t: PhantomData<fn() -> T>,
+}
+
+impl<'input, 'facet, T: Facet<'facet>> Iterator for Rows<'input, T>
+where
+    'input: 'facet,
+{
+    type Item = Result<T, DeserError<'input, 'static>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.walker.next_row(self.input, self.input, false)
+    }
+}
+
+/// Goes from one row to the next, reading the header row first.
+struct RowWalker {
+    options: CsvOptions,
+    /// The names of the columns, once read
+    header: Option<Vec<String>>,
+    /// Where the next row starts
+    pos: usize,
+    /// Whether the header was read, or found to be malformed
+    started: bool,
+    /// Whether there's nothing left to read, including after a malformed row
+    done: bool,
+}
+
+impl RowWalker {
+    fn new(options: CsvOptions) -> Self {
+        Self {
+            options,
+            header: None,
+            pos: 0,
+            started: false,
+            done: false,
+        }
+    }
+
+    /// Deserializes the row at `pos` in `data`. The deserializer is given `input`, which is
+    /// either `data` itself or empty, in which case the cells it gets are copies.
+    fn next_row<'input, 'facet, T: Facet<'facet>>(
+        &mut self,
+        data: &[u8],
+        input: &'input [u8],
+        owned: bool,
+    ) -> Option<Result<T, DeserError<'input, 'static>>>
+    where
+        'input: 'facet,
+    {
+        if self.done {
+            return None;
+        }
+        let fail = |walker: &mut Self, err: Spanned<DeserErrorKind<'static>>| {
+            walker.done = true;
+            Some(Err(DeserError::new(err.node, input, err.span, "csv")))
+        };
+
+        if !self.started {
+            self.started = true;
+            // Spreadsheets tend to start their exports with a byte order mark
+            if data.starts_with("\u{feff}".as_bytes()) {
+                self.pos = 3;
+            }
+            if self.options.has_headers {
+                self.pos = skip_blank_lines(data, self.pos);
+                let mut header = Vec::new();
+                loop {
+                    match read_cell(data, self.pos, self.options.delimiter) {
+                        Ok(cell) => {
+                            header.push(cell.value.into_owned());
+                            self.pos = cell.next;
+                            if cell.last {
+                                break;
+                            }
+                        }
+                        Err(err) => return fail(self, err),
+                    }
+                }
+                trace!("CSV header: {:?}", header);
+                self.header = Some(header);
+            }
+        }
+
+        self.pos = skip_blank_lines(data, self.pos);
+        if self.pos >= data.len() {
+            self.done = true;
+            return None;
+        }
+
+        let row_start = self.pos;
+        let mut row = CsvRow {
+            delimiter: self.options.delimiter,
+            header: self.header.as_deref(),
+            data: owned.then_some(data),
+            row_start,
+            column: 0,
+            next: row_start,
+            row_ended: false,
+        };
+        let res = facet_deserialize::deserialize(input, &mut row);
+        self.pos = row.next;
+        if !row.row_ended {
+            // The row failed part way through: the next one starts after its last cell
+            loop {
+                match read_cell(data, self.pos, self.options.delimiter) {
+                    Ok(cell) => {
+                        self.pos = cell.next;
+                        if cell.last {
+                            break;
+                        }
+                    }
+                    Err(err) => {
+                        self.done = true;
+                        if res.is_ok() {
+                            return fail(self, err);
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+        Some(res)
+    }
+}
+
+/// The offset of the first line at or after `pos` that isn't empty.
+fn skip_blank_lines(data: &[u8], mut pos: usize) -> usize {
+    loop {
+        match &data[pos.min(data.len())..] {
+            [b'\n', ..] => pos += 1,
+            [b'\r', b'\n', ..] => pos += 2,
+            _ => return pos,
+        }
+    }
+}
+
+/// A cell read from the input.
+struct Cell<'input> {
+    /// The text of the cell, unquoted
+    value: Cow<'input, str>,
+    /// Where the cell is in the input, quotes included
+    span: Span,
+    /// Where what follows the cell starts: the next cell, or the next row
+    next: usize,
+    /// Whether the cell is the last of its row
+    last: bool,
+}
+
+impl Cell<'_> {
+    fn into_owned(self) -> Cell<'static> {
+        Cell {
+            value: Cow::Owned(self.value.into_owned()),
+            span: self.span,
+            next: self.next,
+            last: self.last,
+        }
+    }
+}
+
+/// Reads the cell starting at `start`, which may be quoted, with `""` standing for a quote.
+fn read_cell(
+    data: &[u8],
+    start: usize,
+    delimiter: u8,
+) -> Result<Cell<'_>, Spanned<DeserErrorKind<'static>>> {
+    let text = |from: usize, to: usize| {
+        core::str::from_utf8(&data[from..to]).map_err(|e| Spanned {
+            node: DeserErrorKind::InvalidUtf8(e.to_string()),
+            span: Span::new(from, to - from),
+        })
+    };
+
+    let (value, end) = if data.get(start) == Some(&b'"') {
+        let mut pos = start + 1;
+        let mut escaped = false;
+        loop {
+            match data[pos.min(data.len())..].iter().position(|&b| b == b'"') {
+                None => {
+                    return Err(Spanned {
+                        node: DeserErrorKind::UnexpectedEof {
+                            wanted: "closing quote",
+                        },
+                        span: Span::new(start, data.len() - start),
+                    });
+                }
+                Some(offset) if data.get(pos + offset + 1) == Some(&b'"') => {
+                    escaped = true;
+                    pos += offset + 2;
+                }
+                Some(offset) => {
+                    pos += offset;
+                    break;
+                }
+            }
+        }
+        let value = text(start + 1, pos)?;
+        let value = if escaped {
+            Cow::Owned(value.replace("\"\"", "\""))
+        } else {
+            Cow::Borrowed(value)
+        };
+        (value, pos + 1)
+    } else {
+        let end = data[start.min(data.len())..]
+            .iter()
+            .position(|&b| b == delimiter || b == b'\n')
+            .map_or(data.len(), |offset| start + offset);
+        // Line breaks may be `\r\n`
+        let text_end = if data.get(end) == Some(&b'\n') && end > start && data[end - 1] == b'\r' {
+            end - 1
+        } else {
+            end
+        };
+        (Cow::Borrowed(text(start, text_end)?), text_end)
+    };
+
+    let span = Span::new(start, end - start);
+    let (next, last) = match &data[end.min(data.len())..] {
+        [] => (end, true),
+        [b'\n', ..] => (end + 1, true),
+        [b'\r', b'\n', ..] => (end + 2, true),
+        [b, ..] if *b == delimiter => (end + 1, false),
+        [b, ..] => {
+            return Err(Spanned {
+                node: DeserErrorKind::UnexpectedByte {
+                    got: *b,
+                    wanted: "delimiter or end of line after quoted cell",
+                },
+                span: Span::new(end, 1),
+            });
+        }
+    };
+    Ok(Cell {
+        value,
+        span,
+        next,
+        last,
+    })
+}
+
+/// Whether `shape` (or the value in the option `shape`) is a string.
+fn is_string(shape: &Shape) -> bool {
+    let shape = match shape.def {
+        Def::Option(option_def) => option_def.t(),
+        _ => shape,
+    };
+    matches!(
+        shape.def,
+        Def::Scalar(ScalarDef {
+            affinity: ScalarAffinity::String(_),
+            ..
+        })
+    )
+}
+
+/// The scalar a cell stands for, for a field of type `shape`. Empty cells are `None` for
+/// options.
+fn scalar<'input>(shape: &Shape, value: Cow<'input, str>) -> Scalar<'input> {
+    if value.is_empty() && matches!(shape.def, Def::Option(_)) {
+        return Scalar::Null;
+    }
+    // Strings take the cell as is, even if it looks like a number
+    if is_string(shape) {
+        return Scalar::String(value);
+    }
+    let shape = match shape.def {
+        Def::Option(option_def) => option_def.t(),
+        _ => shape,
+    };
+    if shape.is_type::<bool>() {
+        if let Ok(v) = value.parse::<bool>() {
+            return Scalar::Bool(v);
+        }
+    }
+    if let Ok(v) = value.parse::<u64>() {
+        return Scalar::U64(v);
+    }
+    if let Ok(v) = value.parse::<i64>() {
+        return Scalar::I64(v);
+    }
+    if let Ok(v) = value.parse::<f64>() {
+        return Scalar::F64(v);
+    }
+    Scalar::String(value)
+}
+
+/// One row of CSV, deserialized as a struct whose fields are named by the header, or taken
+/// in order.
+///
+/// Cells are read from where the last one ended, rather than from where the deserializer
+/// says, so that keys can point at the cell their value comes from.
+struct CsvRow<'a> {
+    delimiter: u8,
+    /// The names of the columns, if there's a header row
+    header: Option<&'a [String]>,
+    /// The input to read cells from, copying them, if the deserializer isn't given it
+    data: Option<&'a [u8]>,
+    row_start: usize,
+    /// The column of the next cell
+    column: usize,
+    /// Where the next cell starts
+    next: usize,
+    /// Whether the last cell of the row was read
+    row_ended: bool,
+}
+
+impl CsvRow<'_> {
+    /// Reads the next cell of the row.
+    fn cell<'input>(
+        &self,
+        input: &'input [u8],
+    ) -> Result<Cell<'input>, Spanned<DeserErrorKind<'static>>> {
+        match self.data {
+            Some(data) => read_cell(data, self.next, self.delimiter).map(Cell::into_owned),
+            None => read_cell(input, self.next, self.delimiter),
+        }
+    }
+}
+
+impl Format for CsvRow<'_> {
+    type Input<'input> = [u8];
+
+    fn source(&self) -> &'static str {
+        "csv"
+    }
+
+    fn next<'input, 'facet, 'shape>(
+        &mut self,
+        nd: NextData<'input, 'facet, 'shape, Self::Input<'input>>,
+        expectation: Expectation,
+    ) -> NextResult<
+        'input,
+        'facet,
+        'shape,
+        Spanned<Outcome<'input>>,
+        Spanned<DeserErrorKind<'shape>>,
+        Self::Input<'input>,
+    >
+    where
+        'shape: 'input,
+    {
+        let shape = nd.wip.shape();
+        match expectation {
+            // A row is a struct
+            Expectation::Value => {
+                let res = match shape.ty {
+                    Type::User(UserType::Struct(_)) => Ok(Spanned {
+                        node: Outcome::ObjectStarted,
+                        span: Span::new(self.row_start, 0),
+                    }),
+                    _ => Err(Spanned {
+                        node: DeserErrorKind::UnsupportedType {
+                            got: shape,
+                            wanted: "struct",
+                        },
+                        span: Span::new(self.row_start, 0),
+                    }),
+                };
+                (nd, res)
+            }
+
+            // The name of the column of the next cell, or the end of the row
+            Expectation::ObjectKeyOrObjectClose => {
+                if self.row_ended {
+                    return (
+                        nd,
+                        Ok(Spanned {
+                            node: Outcome::ObjectEnded,
+                            span: Span::new(self.next, 0),
+                        }),
+                    );
+                }
+                let name = match self.header {
+                    Some(header) => header.get(self.column).cloned().map(Cow::Owned),
+                    None => match shape.ty {
+                        Type::User(UserType::Struct(st)) => st
+                            .fields
+                            .get(self.column)
+                            .map(|field| Cow::Borrowed(field.name)),
+                        _ => None,
+                    },
+                };
+                let res = match name {
+                    Some(name) => Ok(Spanned {
+                        node: Outcome::Scalar(Scalar::String(name)),
+                        span: Span::new(self.next, 0),
+                    }),
+                    // More cells than columns
+                    None => Err(Spanned {
+                        node: DeserErrorKind::UnknownField {
+                            field_name: format!("column {}", self.column + 1),
+                            shape,
+                        },
+                        span: self
+                            .cell(nd.input())
+                            .map_or(Span::new(self.next, 0), |cell| cell.span),
+                    }),
+                };
+                (nd, res)
+            }
+
+            // The next cell
+            Expectation::ObjectVal => match self.cell(nd.input()) {
+                Ok(cell) => {
+                    self.column += 1;
+                    self.next = cell.next;
+                    self.row_ended = cell.last;
+                    let scalar = scalar(shape, cell.value);
+                    (
+                        nd,
+                        Ok(Spanned {
+                            node: Outcome::Scalar(scalar),
+                            span: cell.span,
+                        }),
+                    )
+                }
+                Err(err) => (nd, Err(err)),
+            },
+
+            // Cells only hold scalars
+            Expectation::ListItemOrListClose => (
+                nd,
+                Err(Spanned {
+                    node: DeserErrorKind::UnsupportedType {
+                        got: shape,
+                        wanted: "scalar",
+                    },
+                    span: Span::new(self.next, 0),
+                }),
+            ),
+        }
+    }
+
+    fn skip<'input, 'facet, 'shape>(
+        &mut self,
+        nd: NextData<'input, 'facet, 'shape, Self::Input<'input>>,
+    ) -> NextResult<
+        'input,
+        'facet,
+        'shape,
+        Span,
+        Spanned<DeserErrorKind<'shape>>,
+        Self::Input<'input>,
+    >
+    where
+        'shape: 'input,
+    {
+        // A column no field is named after
+        match self.cell(nd.input()) {
+            Ok(cell) => {
+                self.column += 1;
+                self.next = cell.next;
+                self.row_ended = cell.last;
+                (nd, Ok(cell.span))
+            }
+            Err(err) => (nd, Err(err)),
+        }
+    }
+}
@@ -6,10 +6,16 @@
 #![doc = include_str!("../README.md")]
 extern crate alloc;
 
+mod options;
+pub use options::*;
+
 #[cfg(feature = "std")]
 mod serialize;
 #[cfg(feature = "std")]
 pub use serialize::*;
 
-// mod deserialize;
-// pub use deserialize::*;
+#[cfg(feature = "alloc")]
+mod deserialize;
+#[cfg(feature = "alloc")]
+pub use deserialize::*;
+pub use facet_deserialize::{DeserError, DeserErrorKind};
@@ -0,0 +1,104 @@
+/// How to read and write CSV: what separates cells, whether the first row names the columns,
+/// and for writing, how cells are quoted and how nested values are laid out in columns.
+///
+/// ```rust
+/// use facet::Facet;
+///
+/// #[derive(Facet, Debug, PartialEq)]
+/// struct Payment {
+///     account: String,
+///     amount: f64,
+/// }
+///
+/// let input = "ACC-1;12.5\nACC-2;-3\n";
+/// let payments: Vec<Payment> = facet_csv::CsvOptions::new()
+///     .delimiter(b';')
+///     .has_headers(false)
+///     .from_str(input)
+///     .unwrap();
+/// assert_eq!(payments[1].amount, -3.0);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CsvOptions {
+    pub(crate) delimiter: u8,
+    pub(crate) has_headers: bool,
+    pub(crate) quoting: Quoting,
+    pub(crate) lists: ListStrategy,
+    pub(crate) path_separator: &'static str,
+}
+
+/// When written cells are put in quotes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quoting {
+    /// Only cells holding a delimiter, a quote or a line break, which can't be read back
+    /// otherwise
+    Necessary,
+    /// Every cell
+    Always,
+    /// Every cell that doesn't hold a number
+    NonNumeric,
+    /// No cell, even if it can't be read back
+    Never,
+}
+
+/// How lists are laid out in columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListStrategy {
+    /// In a single cell, with the items separated by the given text
+    Join(&'static str),
+    /// In a column per item, named after the list and the index of the item, like `tags.0`
+    Spread,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CsvOptions {
+    /// Cells separated by commas, and a header row naming the fields of each column. Cells
+    /// are quoted when they need to be, lists are joined with `;`, and the fields of nested
+    /// structs are named like `parent.child`.
+    pub fn new() -> Self {
+        Self {
+            delimiter: b',',
+            has_headers: true,
+            quoting: Quoting::Necessary,
+            lists: ListStrategy::Join(";"),
+            path_separator: ".",
+        }
+    }
+
+    /// Sets the byte that separates cells, like `b';'` or `b'\t'`.
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Sets whether the first row names the field of each column. Without it, the columns
+    /// go to the fields in the order they're declared.
+    pub fn has_headers(mut self, has_headers: bool) -> Self {
+        self.has_headers = has_headers;
+        self
+    }
+
+    /// Sets when written cells are put in quotes.
+    pub fn quoting(mut self, quoting: Quoting) -> Self {
+        self.quoting = quoting;
+        self
+    }
+
+    /// Sets how written lists are laid out in columns.
+    pub fn lists(mut self, lists: ListStrategy) -> Self {
+        self.lists = lists;
+        self
+    }
+
+    /// Sets what goes between the name of a field and the names of the fields it holds, in
+    /// the columns of nested structs.
+    pub fn path_separator(mut self, separator: &'static str) -> Self {
+        self.path_separator = separator;
+        self
+    }
+}
@@ -1,9 +1,15 @@
-use facet_core::Facet;
+use facet_core::{
+    Def, Facet, Field, FieldFlags, SequenceType, Shape, StructKind, StructType, TupleType, Type,
+    UserType,
+};
 use facet_reflect::Peek;
 use facet_serialize::{Serializer, serialize_iterative};
+use std::collections::HashMap;
 use std::io::{self, Write};
 
-/// Serializes a value to CSV
+use crate::{CsvOptions, ListStrategy, Quoting};
+
+/// Serializes a value to CSV, without a header row
 pub fn to_string<'a, T: Facet<'a>>(value: &'a T) -> String {
     let peek = Peek::new(value);
     let mut output = Vec::new();
@@ -12,7 +18,7 @@ pub fn to_string<'a, T: Facet<'a>>(value: &'a T) -> String {
     String::from_utf8(output).unwrap()
 }
 
-/// Serializes a Peek instance to CSV
+/// Serializes a Peek instance to CSV, without a header row
 pub fn peek_to_string<'a>(peek: &'a Peek<'_, 'a, '_>) -> String {
     let mut output = Vec::new();
     let mut serializer = CsvSerializer::new(&mut output);
@@ -20,225 +26,490 @@ pub fn peek_to_string<'a>(peek: &'a Peek<'_, 'a, '_>) -> String {
     String::from_utf8(output).unwrap()
 }
 
-/// Serializes a value to a writer in CSV format
+/// Serializes a value to a writer in CSV format, without a header row
 pub fn to_writer<'a, T: Facet<'a>, W: Write>(value: &'a T, writer: &mut W) -> io::Result<()> {
     let peek = Peek::new(value);
     let mut serializer = CsvSerializer::new(writer);
     serialize_iterative(peek, &mut serializer)
 }
 
-/// Serializes a Peek instance to a writer in CSV format
+/// Serializes a Peek instance to a writer in CSV format, without a header row
 pub fn peek_to_writer<'a, W: Write>(peek: &'a Peek<'_, 'a, '_>, writer: &mut W) -> io::Result<()> {
     let mut serializer = CsvSerializer::new(writer);
     serialize_iterative(*peek, &mut serializer)
 }
 
+impl CsvOptions {
+    /// Serializes a struct as a row, or a list of them as rows, to CSV.
+    pub fn to_string<'a, T: Facet<'a>>(&self, value: &'a T) -> String {
+        let mut output = Vec::new();
+        self.to_writer(value, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    /// Serializes a struct as a row, or a list of them as rows, to a writer in CSV format.
+    pub fn to_writer<'a, T: Facet<'a>, W: Write>(&self, value: &'a T, writer: W) -> io::Result<()> {
+        let mut serializer = CsvSerializer::with_options(writer, *self);
+        serialize_iterative(Peek::new(value), &mut serializer)
+    }
+}
+
+/// A cell of a row, before it's written.
+struct Cell {
+    column: String,
+    text: String,
+    /// Whether the cell holds a number, for [`Quoting::NonNumeric`]
+    numeric: bool,
+}
+
+/// What the value being serialized is in.
+enum Frame {
+    /// The list of rows
+    Rows,
+    /// A struct, or a map. The names of its fields come after the first `base` parts of
+    /// the column names.
+    Object { base: usize, row: bool },
+    /// A list in a row
+    List {
+        base: usize,
+        /// The index of the next item, to name its column when lists are spread
+        next: usize,
+        /// The items so far, when lists are joined
+        items: Vec<String>,
+    },
+}
+
 /// A struct to handle the CSV serializer logic
-pub struct CsvSerializer<W> {
+///
+/// Nested structs take a column per field, named like `parent.child`, and spread lists a
+/// column per item. The columns are those of the first row, which is kept until it ends so
+/// the header row can be written before it. The rows after it are written as they're
+/// serialized.
+pub struct CsvSerializer<'shape, W> {
     /// Owned writer
     writer: W,
 
-    /// The current position in a row
-    pos: usize,
+    options: CsvOptions,
+
+    /// What the value being serialized is in, innermost last
+    stack: Vec<Frame>,
+
+    /// The name of the column of the next value, in parts
+    path: Vec<String>,
+
+    /// Whether the next value is the key of a map, which names a column
+    in_map_key: bool,
+
+    /// The type of the field whose value comes next, if it's the next value
+    field: Option<&'shape Shape<'shape>>,
 
-    /// Initialized by `start_object`
-    n_fields: usize,
+    /// The cells of the row being serialized
+    row: Vec<Cell>,
 
-    /// Delimeter used to separate values
-    delim: &'static [u8],
+    /// The columns of the first row, once it's written, with where each of them is
+    columns: Option<HashMap<String, usize>>,
 
     /// Newline encoding
     newline: &'static [u8],
 }
-impl<W> CsvSerializer<W>
+
+impl<W> CsvSerializer<'_, W>
 where
     W: Write,
 {
-    /// Initializes a new CSV Serializer
+    /// Initializes a new CSV Serializer, which writes no header row
     pub fn new(writer: W) -> Self {
+        Self::with_options(writer, CsvOptions::new().has_headers(false))
+    }
+
+    /// Initializes a new CSV Serializer, writing as `options` say
+    pub fn with_options(writer: W, options: CsvOptions) -> Self {
         Self {
             writer,
-            pos: 0,
-            n_fields: 0,
-            delim: b",",
+            options,
+            stack: Vec::new(),
+            path: Vec::new(),
+            in_map_key: false,
+            field: None,
+            row: Vec::new(),
+            columns: None,
             newline: b"\n",
         }
     }
 
-    fn set_n_fields(&mut self, n_fields: usize) {
-        self.n_fields = n_fields;
+    /// Names the column of an item of a spread list, before its value.
+    fn start_value(&mut self) {
+        if let (Some(Frame::List { base, next, .. }), ListStrategy::Spread) =
+            (self.stack.last_mut(), self.options.lists)
+        {
+            self.path.truncate(*base);
+            self.path.push(next.to_string());
+            *next += 1;
+        }
+    }
+
+    /// Adds a value to the row, or to the list it's an item of.
+    fn write_value(&mut self, text: String, numeric: bool) -> io::Result<()> {
+        self.field = None;
+        if self.in_map_key {
+            // The key of a map names the column of its value
+            if let Some(Frame::Object { base, .. }) = self.stack.last() {
+                self.path.truncate(*base);
+            }
+            self.path.push(text);
+            return Ok(());
+        }
+        self.start_value();
+        if let (Some(Frame::List { items, .. }), ListStrategy::Join(_)) =
+            (self.stack.last_mut(), self.options.lists)
+        {
+            items.push(text);
+            return Ok(());
+        }
+        self.row.push(Cell {
+            column: self.path.join(self.options.path_separator),
+            text,
+            numeric,
+        });
+        if self.stack.is_empty() {
+            // A lone value is a row of its own
+            self.end_row()?;
+        }
+        Ok(())
     }
 
-    /// Conditionally prefix the value with the required delimeter
-    fn start_value(&mut self) -> Result<(), io::Error> {
-        if self.pos == 0 {
-            // no prefix
-            Ok(())
-        } else {
-            self.writer.write_all(self.delim)
+    /// Writes the row, and the header row before it if it's the first one.
+    fn end_row(&mut self) -> io::Result<()> {
+        let row = core::mem::take(&mut self.row);
+        let columns = match &self.columns {
+            Some(columns) => columns,
+            None => {
+                let mut columns = HashMap::new();
+                let mut names = Vec::new();
+                for cell in &row {
+                    if !columns.contains_key(&cell.column) {
+                        columns.insert(cell.column.clone(), names.len());
+                        names.push(cell.column.as_str());
+                    }
+                }
+                if self.options.has_headers {
+                    self.write_row(names.iter().map(|name| Some((*name, false))))?;
+                }
+                self.columns.insert(columns)
+            }
+        };
+
+        let mut cells = vec![None; columns.len()];
+        for cell in &row {
+            let Some(&index) = columns.get(&cell.column) else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "column `{}` isn't one of the first row, which the columns come from",
+                        cell.column
+                    ),
+                ));
+            };
+            cells[index] = Some((cell.text.as_str(), cell.numeric));
         }
+        // Columns that nothing in this row has, like items past the end of a shorter spread
+        // list, are left empty
+        self.write_row(cells)
     }
 
-    /// Conditionally suffix the value with the required newline
-    fn end_value(&mut self) -> Result<(), io::Error> {
-        if self.pos == self.n_fields - 1 {
-            // Reset the position to zero
-            self.pos = 0;
-            self.writer.write_all(self.newline)
-        } else {
-            // Increment the position
-            self.pos += 1;
-            // no suffix
-            Ok(())
+    /// Writes the cells of a row, as text and whether it's a number, with `None` for empty
+    /// ones.
+    fn write_row<'a>(
+        &mut self,
+        cells: impl IntoIterator<Item = Option<(&'a str, bool)>>,
+    ) -> io::Result<()> {
+        for (i, cell) in cells.into_iter().enumerate() {
+            if i > 0 {
+                self.writer.write_all(&[self.options.delimiter])?;
+            }
+            let (text, numeric) = cell.unwrap_or(("", false));
+            write_cell(&mut self.writer, &self.options, text, numeric)?;
+        }
+        self.writer.write_all(self.newline)
+    }
+
+    /// Adds an empty cell for each column a value of type `shape` would take, for a `None`.
+    fn write_none(&mut self, shape: &Shape) {
+        let mut columns = Vec::new();
+        nested_columns(shape, &self.options, &mut Vec::new(), &mut columns);
+        for column in columns {
+            let mut path = self.path.clone();
+            path.extend(column);
+            self.row.push(Cell {
+                column: path.join(self.options.path_separator),
+                text: String::new(),
+                numeric: false,
+            });
         }
     }
 }
 
-impl<'shape, W> Serializer<'shape> for CsvSerializer<W>
+/// The columns a value of type `shape` takes, as the names of the fields they're for, after
+/// the `path` of the value. A scalar takes a single column, named `path`.
+fn nested_columns(
+    shape: &Shape,
+    options: &CsvOptions,
+    path: &mut Vec<String>,
+    columns: &mut Vec<Vec<String>>,
+) {
+    match (shape.def, shape.ty) {
+        (Def::Option(option_def), _) => nested_columns(option_def.t(), options, path, columns),
+        (Def::Scalar(_), _) => columns.push(path.clone()),
+        (Def::Array(array_def), _) if options.lists == ListStrategy::Spread => {
+            for index in 0..array_def.n {
+                path.push(index.to_string());
+                nested_columns(array_def.t(), options, path, columns);
+                path.pop();
+            }
+        }
+        // How many items there are, or which keys, isn't known without a value
+        (Def::List(_) | Def::Slice(_) | Def::Set(_), _)
+            if options.lists == ListStrategy::Spread => {}
+        (Def::Map(_), _) => {}
+        (_, Type::User(UserType::Struct(struct_type)))
+            if struct_type.kind == StructKind::Struct =>
+        {
+            for field in struct_type.fields {
+                if field.flags.contains(FieldFlags::SKIP_SERIALIZING) {
+                    continue;
+                }
+                path.push(field.name.to_string());
+                nested_columns(field.shape(), options, path, columns);
+                path.pop();
+            }
+        }
+        // Tuples are lists too
+        (
+            _,
+            Type::User(UserType::Struct(StructType {
+                kind: StructKind::Tuple | StructKind::TupleStruct,
+                fields,
+                ..
+            })),
+        )
+        | (_, Type::Sequence(SequenceType::Tuple(TupleType { fields, .. })))
+            if options.lists == ListStrategy::Spread =>
+        {
+            for (index, field) in fields.iter().enumerate() {
+                path.push(index.to_string());
+                nested_columns(field.shape(), options, path, columns);
+                path.pop();
+            }
+        }
+        _ => columns.push(path.clone()),
+    }
+}
+
+/// Writes a cell, quoted if `options` say so.
+fn write_cell<W: Write>(
+    writer: &mut W,
+    options: &CsvOptions,
+    text: &str,
+    numeric: bool,
+) -> io::Result<()> {
+    let quote = match options.quoting {
+        Quoting::Always => true,
+        Quoting::Never => false,
+        Quoting::NonNumeric => !numeric,
+        Quoting::Necessary => text
+            .bytes()
+            .any(|b| b == options.delimiter || b == b'"' || b == b'\n' || b == b'\r'),
+    };
+    if quote {
+        write!(writer, "\"{}\"", text.replace('"', "\"\""))
+    } else {
+        writer.write_all(text.as_bytes())
+    }
+}
+
+impl<'shape, W> Serializer<'shape> for CsvSerializer<'shape, W>
 where
     W: Write,
 {
     type Error = io::Error;
 
-    fn start_object(&mut self, len: Option<usize>) -> Result<(), Self::Error> {
-        self.set_n_fields(len.expect("Must know the length of the object for CSV"));
+    fn start_object(&mut self, _len: Option<usize>) -> Result<(), Self::Error> {
+        self.field = None;
+        // Each struct at the top, or in the list of rows, is a row
+        let row = matches!(self.stack.last(), None | Some(Frame::Rows));
+        if !row {
+            self.start_value();
+        }
+        self.stack.push(Frame::Object {
+            base: self.path.len(),
+            row,
+        });
         Ok(())
     }
 
     fn end_object(&mut self) -> Result<(), Self::Error> {
+        if let Some(Frame::Object { base, row }) = self.stack.pop() {
+            self.path.truncate(base);
+            if row {
+                self.end_row()?;
+            }
+        }
         Ok(())
     }
 
     fn start_array(&mut self, _len: Option<usize>) -> Result<(), Self::Error> {
-        unimplemented!("Arrays are not implemented yet in CSV")
+        self.field = None;
+        // A list at the top holds the rows, any other is a value in a row
+        if self.stack.is_empty() {
+            self.stack.push(Frame::Rows);
+            return Ok(());
+        }
+        if matches!(self.options.lists, ListStrategy::Join(_))
+            && matches!(self.stack.last(), Some(Frame::List { .. }))
+        {
+            // Lists in joined lists are joined in with them
+            return Ok(());
+        }
+        self.start_value();
+        self.stack.push(Frame::List {
+            base: self.path.len(),
+            next: 0,
+            items: Vec::new(),
+        });
+        Ok(())
     }
 
     fn end_array(&mut self) -> Result<(), Self::Error> {
-        unimplemented!("Arrays are not implemented yet in CSV")
+        match self.stack.last() {
+            Some(Frame::List { .. }) => {}
+            Some(Frame::Rows) => {
+                self.stack.pop();
+                return Ok(());
+            }
+            // The end of a list joined in with the one it's in
+            _ => return Ok(()),
+        }
+        if let Some(Frame::List { base, items, .. }) = self.stack.pop() {
+            self.path.truncate(base);
+            if let ListStrategy::Join(separator) = self.options.lists {
+                let text = items.join(separator);
+                self.row.push(Cell {
+                    column: self.path.join(self.options.path_separator),
+                    text,
+                    numeric: false,
+                });
+            }
+        }
+        Ok(())
     }
 
-    fn start_map(&mut self, _len: Option<usize>) -> Result<(), Self::Error> {
-        unimplemented!("Maps are not implemented yet in CSV")
+    fn start_map(&mut self, len: Option<usize>) -> Result<(), Self::Error> {
+        // The keys of maps name columns, like the fields of structs
+        self.start_object(len)
     }
 
     fn end_map(&mut self) -> Result<(), Self::Error> {
-        unimplemented!("Maps are not implemented yet in CSV")
+        self.end_object()
+    }
+
+    fn begin_map_key(&mut self) -> Result<(), Self::Error> {
+        self.field = None;
+        self.in_map_key = true;
+        Ok(())
+    }
+
+    fn end_map_key(&mut self) -> Result<(), Self::Error> {
+        self.in_map_key = false;
+        Ok(())
     }
 
-    fn serialize_field_name(&mut self, _name: &str) -> Result<(), Self::Error> {
-        // field names are not serialized in CSV
+    fn begin_field(&mut self, field: Field<'shape>) -> Result<(), Self::Error> {
+        self.field = Some(field.shape());
+        Ok(())
+    }
+
+    fn serialize_field_name(&mut self, name: &str) -> Result<(), Self::Error> {
+        if let Some(Frame::Object { base, .. }) = self.stack.last() {
+            self.path.truncate(*base);
+        }
+        self.path.push(name.to_string());
         Ok(())
     }
 
     fn serialize_unit_variant(
         &mut self,
         _variant_index: usize,
-        _variant_name: &str,
+        variant_name: &str,
     ) -> Result<(), Self::Error> {
-        // unit variants should not serialize to anything
-        Ok(())
+        self.write_value(variant_name.to_string(), false)
     }
 
     fn serialize_u8(&mut self, value: u8) -> Result<(), Self::Error> {
-        self.start_value()?;
-        write!(self.writer, "{}", value)?;
-        self.end_value()
+        self.write_value(value.to_string(), true)
     }
 
     fn serialize_u16(&mut self, value: u16) -> Result<(), Self::Error> {
-        self.start_value()?;
-        write!(self.writer, "{}", value)?;
-        self.end_value()
+        self.write_value(value.to_string(), true)
     }
 
     fn serialize_u32(&mut self, value: u32) -> Result<(), Self::Error> {
-        self.start_value()?;
-        write!(self.writer, "{}", value)?;
-        self.end_value()
+        self.write_value(value.to_string(), true)
     }
 
     fn serialize_u64(&mut self, value: u64) -> Result<(), Self::Error> {
-        self.start_value()?;
-        write!(self.writer, "{}", value)?;
-        self.end_value()
+        self.write_value(value.to_string(), true)
     }
 
     fn serialize_u128(&mut self, value: u128) -> Result<(), Self::Error> {
-        self.start_value()?;
-        write!(self.writer, "{}", value)?;
-        self.end_value()
+        self.write_value(value.to_string(), true)
     }
 
     fn serialize_usize(&mut self, value: usize) -> Result<(), Self::Error> {
-        self.start_value()?;
-        write!(self.writer, "{}", value)?;
-        self.end_value()
+        self.write_value(value.to_string(), true)
     }
 
     fn serialize_i8(&mut self, value: i8) -> Result<(), Self::Error> {
-        self.start_value()?;
-        write!(self.writer, "{}", value)?;
-        self.end_value()
+        self.write_value(value.to_string(), true)
     }
 
     fn serialize_i16(&mut self, value: i16) -> Result<(), Self::Error> {
-        self.start_value()?;
-        write!(self.writer, "{}", value)?;
-        self.end_value()
+        self.write_value(value.to_string(), true)
     }
 
     fn serialize_i32(&mut self, value: i32) -> Result<(), Self::Error> {
-        self.start_value()?;
-        write!(self.writer, "{}", value)?;
-        self.end_value()
+        self.write_value(value.to_string(), true)
     }
 
     fn serialize_i64(&mut self, value: i64) -> Result<(), Self::Error> {
-        self.start_value()?;
-        write!(self.writer, "{}", value)?;
-        self.end_value()
+        self.write_value(value.to_string(), true)
     }
 
     fn serialize_i128(&mut self, value: i128) -> Result<(), Self::Error> {
-        self.start_value()?;
-        write!(self.writer, "{}", value)?;
-        self.end_value()
+        self.write_value(value.to_string(), true)
     }
 
     fn serialize_isize(&mut self, value: isize) -> Result<(), Self::Error> {
-        self.start_value()?;
-        write!(self.writer, "{}", value)?;
-        self.end_value()
+        self.write_value(value.to_string(), true)
     }
 
     fn serialize_f32(&mut self, value: f32) -> Result<(), Self::Error> {
-        self.start_value()?;
-        write!(self.writer, "{}", value)?;
-        self.end_value()
+        self.write_value(value.to_string(), true)
     }
 
     fn serialize_f64(&mut self, value: f64) -> Result<(), Self::Error> {
-        self.start_value()?;
-        write!(self.writer, "{}", value)?;
-        self.end_value()
+        self.write_value(value.to_string(), true)
     }
 
     fn serialize_bool(&mut self, value: bool) -> Result<(), Self::Error> {
-        self.start_value()?;
-        write!(self.writer, "{}", if value { "true" } else { "false" })?;
-        self.end_value()
+        self.write_value(value.to_string(), false)
     }
 
     fn serialize_char(&mut self, value: char) -> Result<(), Self::Error> {
-        self.start_value()?;
-        write!(self.writer, "{}", value)?;
-        self.end_value()
+        self.write_value(value.to_string(), false)
     }
 
     fn serialize_str(&mut self, value: &str) -> Result<(), Self::Error> {
-        self.start_value()?;
-        write!(self.writer, "{}", value)?;
-        self.end_value()
+        self.write_value(value.to_string(), false)
     }
 
     fn serialize_bytes(&mut self, _value: &[u8]) -> Result<(), Self::Error> {
@@ -246,14 +517,17 @@ where
     }
 
     fn serialize_none(&mut self) -> Result<(), Self::Error> {
-        self.start_value()?;
-        // skip empty columns
-        self.end_value()
+        // A field holding a struct takes a column per field of it, which are all empty
+        if let Some(shape) = self.field.take() {
+            self.write_none(shape);
+            return Ok(());
+        }
+        // An empty cell
+        self.write_value(String::new(), false)
     }
 
     fn serialize_unit(&mut self) -> Result<(), Self::Error> {
-        self.start_value()?;
-        // skip empty columns
-        self.end_value()
+        // An empty cell
+        self.write_value(String::new(), false)
     }
 }
@@ -0,0 +1,153 @@
+use facet::Facet;
+use facet_csv::CsvOptions;
+use facet_testhelpers::test;
+
+#[derive(Facet, Debug, PartialEq)]
+struct Payment {
+    account: String,
+    #[facet(rename = "Amount (EUR)")]
+    amount: f64,
+    reference: Option<String>,
+    settled: bool,
+}
+
+#[test]
+fn test_header_row() {
+    let input = "account,Amount (EUR),reference,settled\nACC-1,12.5,INV-7,true\nACC-2,-3,,false\n";
+    let payments: Vec<Payment> = facet_csv::from_str(input)?;
+    assert_eq!(
+        payments,
+        [
+            Payment {
+                account: "ACC-1".into(),
+                amount: 12.5,
+                reference: Some("INV-7".into()),
+                settled: true,
+            },
+            Payment {
+                account: "ACC-2".into(),
+                amount: -3.0,
+                reference: None,
+                settled: false,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_columns_in_any_order() {
+    // Columns no field is named after are left out
+    let input = "settled,notes,reference,Amount (EUR),account\nfalse,late,,7,ACC-3";
+    let payments: Vec<Payment> = facet_csv::from_str(input)?;
+    assert_eq!(
+        payments,
+        [Payment {
+            account: "ACC-3".into(),
+            amount: 7.0,
+            reference: None,
+            settled: false,
+        }]
+    );
+}
+
+#[test]
+fn test_quoting() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Row {
+        name: String,
+        note: String,
+        count: u32,
+    }
+
+    let input = "name,note,count\r\n\"Doe, Jane\",\"said \"\"hi\"\"\nthen left\",\"3\"\r\n\r\n";
+    let rows: Vec<Row> = facet_csv::from_str(input)?;
+    assert_eq!(
+        rows,
+        [Row {
+            name: "Doe, Jane".into(),
+            note: "said \"hi\"\nthen left".into(),
+            count: 3,
+        }]
+    );
+}
+
+#[test]
+fn test_borrowed_cells() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Row<'a> {
+        name: &'a str,
+    }
+
+    let rows: Vec<Row> = facet_csv::from_str("name\nalice\n\"bob\"\n")?;
+    assert_eq!(rows, [Row { name: "alice" }, Row { name: "bob" }]);
+}
+
+#[test]
+fn test_headerless_with_delimiter() {
+    let input = "\u{feff}ACC-1;12.5;;true\nACC-2;4;X;false\n";
+    let payments: Vec<Payment> = CsvOptions::new()
+        .delimiter(b';')
+        .has_headers(false)
+        .from_str(input)?;
+    assert_eq!(payments.len(), 2);
+    assert_eq!(payments[0].account, "ACC-1");
+    assert_eq!(payments[0].reference, None);
+    assert_eq!(payments[1].reference.as_deref(), Some("X"));
+}
+
+#[test]
+fn test_too_many_cells() {
+    let input = "ACC-1,12.5,,true,extra\n";
+    let res = CsvOptions::new()
+        .has_headers(false)
+        .from_str::<Payment>(input);
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_rows_go_on_after_errors() {
+    let input = "account,Amount (EUR),reference,settled\nACC-1,12.5,,true\nACC-2,lots,,true\nACC-3,1,,maybe\nACC-4,2,,false\n";
+    let rows: Vec<_> = facet_csv::rows::<Payment>(input).collect();
+    assert_eq!(rows.len(), 4);
+    assert!(rows[0].is_ok());
+    assert!(rows[3].is_ok());
+
+    // Each error points at its own row
+    let second_row = input.find("lots").unwrap();
+    let err = rows[1].as_ref().unwrap_err();
+    assert_eq!(err.span.start, second_row);
+    let err = rows[2].as_ref().unwrap_err();
+    assert!(
+        input[err.span.start..].starts_with("maybe"),
+        "{:?}",
+        err.span
+    );
+}
+
+#[test]
+fn test_missing_cell() {
+    let input = "account,Amount (EUR),reference,settled\nACC-1,12.5\n";
+    assert!(facet_csv::from_str::<Payment>(input).is_err());
+}
+
+#[test]
+fn test_unterminated_quote() {
+    let input = "account,Amount (EUR),reference,settled\n\"ACC-1,12.5,,true\n";
+    let err = facet_csv::from_str::<Payment>(input).unwrap_err();
+    assert!(matches!(
+        err.kind,
+        facet_csv::DeserErrorKind::UnexpectedEof { .. }
+    ));
+}
+
+#[test]
+fn test_from_reader() {
+    let input = "account,Amount (EUR),reference,settled\nACC-1,12.5,\"INV, 7\",true\n";
+    let payments: Vec<Payment> = facet_csv::from_reader(input.as_bytes())?;
+    assert_eq!(payments[0].reference.as_deref(), Some("INV, 7"));
+
+    // Errors still show the input
+    let input = "account,Amount (EUR),reference,settled\nACC-1,lots,,true\n";
+    let err = facet_csv::from_reader::<_, Payment>(input.as_bytes()).unwrap_err();
+    assert_eq!(&err.input[err.span.start..][..4], b"lots");
+}
@@ -0,0 +1,229 @@
+use std::collections::BTreeMap;
+
+use facet::Facet;
+use facet_csv::{CsvOptions, ListStrategy, Quoting};
+use facet_testhelpers::test;
+
+#[derive(Facet)]
+struct Address {
+    city: String,
+    zip: Option<u32>,
+}
+
+#[derive(Facet)]
+#[repr(u8)]
+enum Role {
+    Admin,
+    Guest,
+}
+
+#[derive(Facet)]
+struct User {
+    name: String,
+    address: Address,
+    role: Role,
+    tags: Vec<String>,
+}
+
+fn users() -> Vec<User> {
+    vec![
+        User {
+            name: "Ada".to_string(),
+            address: Address {
+                city: "London".to_string(),
+                zip: Some(12345),
+            },
+            role: Role::Admin,
+            tags: vec!["math".to_string(), "code".to_string()],
+        },
+        User {
+            name: "Bob".to_string(),
+            address: Address {
+                city: "Paris".to_string(),
+                zip: None,
+            },
+            role: Role::Guest,
+            tags: vec!["art".to_string()],
+        },
+    ]
+}
+
+#[test]
+fn test_flattened_rows() {
+    assert_eq!(
+        CsvOptions::new().to_string(&users()),
+        "name,address.city,address.zip,role,tags\n\
+         Ada,London,12345,Admin,math;code\n\
+         Bob,Paris,,Guest,art\n"
+    );
+}
+
+#[test]
+fn test_single_row() {
+    let user = &users()[0];
+    assert_eq!(
+        CsvOptions::new().has_headers(false).to_string(user),
+        "Ada,London,12345,Admin,math;code\n"
+    );
+}
+
+#[test]
+fn test_spread_lists() {
+    assert_eq!(
+        CsvOptions::new()
+            .lists(ListStrategy::Spread)
+            .path_separator("_")
+            .to_string(&users()),
+        "name,address_city,address_zip,role,tags_0,tags_1\n\
+         Ada,London,12345,Admin,math,code\n\
+         Bob,Paris,,Guest,art,\n"
+    );
+}
+
+#[test]
+fn test_missing_nested_struct() {
+    #[derive(Facet)]
+    struct Contact {
+        name: String,
+        address: Option<Address>,
+        email: String,
+    }
+
+    let contacts = vec![
+        Contact {
+            name: "Ada".to_string(),
+            address: None,
+            email: "ada@example.com".to_string(),
+        },
+        Contact {
+            name: "Bob".to_string(),
+            address: Some(Address {
+                city: "Paris".to_string(),
+                zip: Some(75001),
+            }),
+            email: "bob@example.com".to_string(),
+        },
+    ];
+    assert_eq!(
+        CsvOptions::new().to_string(&contacts),
+        "name,address.city,address.zip,email\n\
+         Ada,,,ada@example.com\n\
+         Bob,Paris,75001,bob@example.com\n"
+    );
+}
+
+#[test]
+fn test_columns_come_from_first_row() {
+    let mut users = users();
+    users.reverse();
+    let mut out = Vec::new();
+    let err = CsvOptions::new()
+        .lists(ListStrategy::Spread)
+        .to_writer(&users, &mut out)
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_joined_lists() {
+    assert_eq!(
+        CsvOptions::new()
+            .lists(ListStrategy::Join("|"))
+            .has_headers(false)
+            .to_string(&users()),
+        "Ada,London,12345,Admin,math|code\nBob,Paris,,Guest,art\n"
+    );
+}
+
+#[test]
+fn test_maps() {
+    #[derive(Facet)]
+    struct Scores {
+        player: String,
+        rounds: BTreeMap<String, u32>,
+    }
+
+    let scores = vec![Scores {
+        player: "p1".to_string(),
+        rounds: BTreeMap::from([("first".to_string(), 3), ("second".to_string(), 5)]),
+    }];
+    assert_eq!(
+        CsvOptions::new().to_string(&scores),
+        "player,rounds.first,rounds.second\np1,3,5\n"
+    );
+}
+
+#[test]
+fn test_quoting() {
+    #[derive(Facet)]
+    struct Note {
+        id: u32,
+        text: String,
+    }
+
+    let note = |id, text: &str| Note {
+        id,
+        text: text.to_string(),
+    };
+    let notes = vec![note(1, "plain"), note(2, "a, \"quoted\"\nline")];
+    assert_eq!(
+        CsvOptions::new().has_headers(false).to_string(&notes),
+        "1,plain\n2,\"a, \"\"quoted\"\"\nline\"\n"
+    );
+    assert_eq!(
+        CsvOptions::new()
+            .quoting(Quoting::Always)
+            .to_string(&vec![note(1, "plain")]),
+        "\"id\",\"text\"\n\"1\",\"plain\"\n"
+    );
+    assert_eq!(
+        CsvOptions::new()
+            .quoting(Quoting::NonNumeric)
+            .has_headers(false)
+            .to_string(&vec![note(1, "plain")]),
+        "1,\"plain\"\n"
+    );
+    assert_eq!(
+        CsvOptions::new()
+            .quoting(Quoting::Never)
+            .has_headers(false)
+            .to_string(&vec![note(2, "a, \"quoted\"\nline")]),
+        "2,a, \"quoted\"\nline\n"
+    );
+}
+
+#[test]
+fn test_delimiter() {
+    assert_eq!(
+        CsvOptions::new()
+            .delimiter(b'\t')
+            .to_string(&users().split_off(1)),
+        "name\taddress.city\taddress.zip\trole\ttags\nBob\tParis\t\tGuest\tart\n"
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Row {
+        id: u32,
+        label: Option<String>,
+        note: String,
+    }
+
+    let rows = vec![
+        Row {
+            id: 1,
+            label: Some("one".to_string()),
+            note: "with, comma".to_string(),
+        },
+        Row {
+            id: 2,
+            label: None,
+            note: "with \"quotes\"".to_string(),
+        },
+    ];
+    let csv = CsvOptions::new().to_string(&rows);
+    let read: Vec<Row> = facet_csv::from_str(&csv).map_err(|e| e.into_owned())?;
+    assert_eq!(read, rows);
+}
@@ -77,6 +77,11 @@ pub enum PFacetAttr {
     /// `#[facet(rename_all = "rule")]` — rename all fields following a rule
     RenameAll { rule: RenameRule },
 
+    /// Valid in field, enum variant
+    /// `#[facet(alias = "name")]` — an alternative name accepted when deserializing.
+    /// Can be repeated.
+    Alias { name: String },
+
     /// Valid in field, enum variant, or container
     /// `#[facet(skip_serializing)]` — skip serializing this field. Like serde.
     SkipSerializing,
@@ -84,6 +89,44 @@ pub enum PFacetAttr {
     /// Valid in field, enum variant, or container
     /// `#[facet(skip_serializing_if = "func")]` — skip serializing if the function returns true.
     SkipSerializingIf { expr: TokenStream },
+
+    /// Valid in field
+    /// `#[facet(skip_deserializing)]` — never read this field from the input, always use its
+    /// default.
+    SkipDeserializing,
+
+    /// Valid in field
+    /// `#[facet(skip)]` — expands to `SkipSerializing` and `SkipDeserializing` for fields.
+    /// Kept as an arbitrary attribute on enum variants.
+    Skip,
+
+    /// Valid in field
+    /// `#[facet(serialize_with = func)]` — serialize the value returned by `func(&field)`
+    /// instead of the field
+    SerializeWith { expr: TokenStream },
+
+    /// Valid in field
+    /// `#[facet(deserialize_with = func)]` — deserialize another value instead of the field,
+    /// then build the field with `func(value)`, which returns a `Result`
+    DeserializeWith { expr: TokenStream },
+
+    /// Valid in field
+    /// `#[facet(proxy = Type)]` — (de)serialize the field as `Type`, converting with
+    /// `From<&Field> for Type` and `TryFrom<Type> for Field`
+    Proxy { ty: TokenStream },
+
+    /// Valid in container (enums)
+    /// `#[facet(tag = "type")]` — the variant name is stored under this key,
+    /// next to the variant's fields (or under `content`, if specified)
+    Tag { tag: String },
+
+    /// Valid in container (enums)
+    /// `#[facet(content = "value")]` — with `tag`, the variant's content is stored under this key
+    Content { content: String },
+
+    /// Valid in container (enums)
+    /// `#[facet(untagged)]` — the variant name is not serialized at all
+    Untagged,
 }
 
 impl PFacetAttr {
@@ -116,6 +159,9 @@ impl PFacetAttr {
                 FacetInner::Rename(rename) => {
                     *display_name = rename.value.as_str().to_string();
                 }
+                FacetInner::Alias(alias) => dest.push(PFacetAttr::Alias {
+                    name: alias.value.as_str().to_string(),
+                }),
                 FacetInner::RenameAll(rename_all) => {
                     let rule_str = rename_all.value.as_str();
                     if let Some(rule) = RenameRule::from_str(rule_str) {
@@ -132,11 +178,37 @@ impl PFacetAttr {
                 FacetInner::SkipSerializing(_) => {
                     dest.push(PFacetAttr::SkipSerializing);
                 }
+                FacetInner::SkipDeserializing(_) => {
+                    dest.push(PFacetAttr::SkipDeserializing);
+                }
+                FacetInner::Skip(_) => {
+                    dest.push(PFacetAttr::Skip);
+                }
                 FacetInner::SkipSerializingIf(skip_if) => {
                     dest.push(PFacetAttr::SkipSerializingIf {
                         expr: skip_if.expr.to_token_stream(),
                     });
                 }
+                FacetInner::SerializeWith(serialize_with) => {
+                    dest.push(PFacetAttr::SerializeWith {
+                        expr: serialize_with.expr.to_token_stream(),
+                    });
+                }
+                FacetInner::DeserializeWith(deserialize_with) => {
+                    dest.push(PFacetAttr::DeserializeWith {
+                        expr: deserialize_with.expr.to_token_stream(),
+                    });
+                }
+                FacetInner::Proxy(proxy) => dest.push(PFacetAttr::Proxy {
+                    ty: proxy.ty.to_token_stream(),
+                }),
+                FacetInner::Tag(tag) => dest.push(PFacetAttr::Tag {
+                    tag: tag.value.as_str().to_string(),
+                }),
+                FacetInner::Content(content) => dest.push(PFacetAttr::Content {
+                    content: content.value.as_str().to_string(),
+                }),
+                FacetInner::Untagged(_) => dest.push(PFacetAttr::Untagged),
             }
         }
     }
@@ -582,7 +654,19 @@ impl PStructField {
         let mut display_name = initial_display_name.clone();
 
         // Parse attributes for the field
-        let attrs = PAttrs::parse(attrs, &mut display_name);
+        let mut attrs = PAttrs::parse(attrs, &mut display_name);
+
+        // A skipped field is neither serialized nor deserialized
+        if let Some(index) = attrs
+            .facet
+            .iter()
+            .position(|attr| matches!(attr, PFacetAttr::Skip))
+        {
+            attrs.facet.splice(
+                index..=index,
+                [PFacetAttr::SkipSerializing, PFacetAttr::SkipDeserializing],
+            );
+        }
 
         // Name resolution:
         // Precedence:
@@ -25,7 +25,60 @@ pub(crate) fn process_enum(parsed: Enum) -> TokenStream {
         doc_lines => quote! { .doc(&[#(#doc_lines),*]) },
     };
 
+    let facet_attrs = &pe.container.attrs.facet;
+    let has_tag = facet_attrs
+        .iter()
+        .any(|attr| matches!(attr, PFacetAttr::Tag { .. }));
+    let has_content = facet_attrs
+        .iter()
+        .any(|attr| matches!(attr, PFacetAttr::Content { .. }));
+
+    // Internally tagged variants are written as objects, with the tag next to their fields:
+    // only unit variants, struct variants and newtypes over structs with named fields fit.
+    let mut tag_assertions: Vec<TokenStream> = Vec::new();
+    if has_tag && !has_content {
+        for pv in &pe.variants {
+            let PVariantKind::Tuple { fields } = &pv.kind else {
+                continue;
+            };
+            let [field] = &fields[..] else {
+                panic!(
+                    "internally tagged enum variant {} cannot be a tuple variant, add #[facet(content = ...)] or make it a struct variant",
+                    pv.name.effective
+                )
+            };
+            let ty = &field.ty;
+            let message = format!(
+                "internally tagged enum variant {} must contain a struct with named fields",
+                pv.name.effective
+            );
+            tag_assertions.push(quote! {
+                assert!(
+                    matches!(
+                        <#ty as ::facet::Facet<'__facet>>::SHAPE.ty,
+                        ::facet::Type::User(::facet::UserType::Struct(::facet::StructType {
+                            kind: ::facet::StructKind::Struct,
+                            ..
+                        }))
+                    ),
+                    #message
+                );
+            });
+        }
+    }
+
     let container_attributes_tokens = {
+        if !has_tag && has_content {
+            panic!("#[facet(content = ...)] requires #[facet(tag = ...)]")
+        }
+        if has_tag
+            && facet_attrs
+                .iter()
+                .any(|attr| matches!(attr, PFacetAttr::Untagged))
+        {
+            panic!("#[facet(tag = ...)] and #[facet(untagged)] are mutually exclusive")
+        }
+
         let mut attribute_tokens: Vec<TokenStream> = Vec::new();
         for attr in &pe.container.attrs.facet {
             match attr {
@@ -45,6 +98,15 @@ pub(crate) fn process_enum(parsed: Enum) -> TokenStream {
                     // Maybe panic or warn here? For now, ignoring.
                     panic!("Invariants are not supported on enums")
                 }
+                PFacetAttr::Tag { tag } => {
+                    attribute_tokens.push(quote! { ::facet::ShapeAttribute::Tag(#tag) });
+                }
+                PFacetAttr::Content { content } => {
+                    attribute_tokens.push(quote! { ::facet::ShapeAttribute::Content(#content) });
+                }
+                PFacetAttr::Untagged => {
+                    attribute_tokens.push(quote! { ::facet::ShapeAttribute::Untagged });
+                }
                 // Opaque, Transparent, SkipSerializing/If, Default/Equals are not relevant/valid for enum containers.
                 _ => {}
             }
@@ -164,10 +226,16 @@ pub(crate) fn process_enum(parsed: Enum) -> TokenStream {
                     } else {
                         let mut attrs_list = Vec::new();
                         for attr in &pv.attrs.facet {
-                            if let PFacetAttr::Arbitrary { content } = attr {
-                                attrs_list.push(
+                            match attr {
+                                PFacetAttr::Arbitrary { content } => attrs_list.push(
                                     quote! { ::facet::VariantAttribute::Arbitrary(#content) },
-                                );
+                                ),
+                                PFacetAttr::Alias { name } => attrs_list
+                                    .push(quote! { ::facet::VariantAttribute::Alias(#name) }),
+                                // Variants keep `skip` as an arbitrary attribute
+                                PFacetAttr::Skip => attrs_list
+                                    .push(quote! { ::facet::VariantAttribute::Arbitrary("skip") }),
+                                _ => {}
                             }
                         }
                         if attrs_list.is_empty() {
@@ -380,10 +448,16 @@ pub(crate) fn process_enum(parsed: Enum) -> TokenStream {
                     } else {
                         let mut attrs_list = Vec::new();
                         for attr in &pv.attrs.facet {
-                            if let PFacetAttr::Arbitrary { content } = attr {
-                                attrs_list.push(
+                            match attr {
+                                PFacetAttr::Arbitrary { content } => attrs_list.push(
                                     quote! { ::facet::VariantAttribute::Arbitrary(#content) },
-                                );
+                                ),
+                                PFacetAttr::Alias { name } => attrs_list
+                                    .push(quote! { ::facet::VariantAttribute::Alias(#name) }),
+                                // Variants keep `skip` as an arbitrary attribute
+                                PFacetAttr::Skip => attrs_list
+                                    .push(quote! { ::facet::VariantAttribute::Arbitrary("skip") }),
+                                _ => {}
                             }
                         }
                         if attrs_list.is_empty() {
@@ -570,6 +644,7 @@ pub(crate) fn process_enum(parsed: Enum) -> TokenStream {
 
             const SHAPE: &'static ::facet::Shape<'static> = &const {
                 #(#shadow_struct_defs)*
+                #(#tag_assertions)*
 
                 let __facet_variants: &'static [::facet::Variant] = &const {[
                     #(#variant_expressions),*
@@ -94,6 +94,9 @@ pub(crate) fn gen_field_from_pfield(
             PFacetAttr::Arbitrary { content } => {
                 attribute_list.push(quote! { ::facet::FieldAttribute::Arbitrary(#content) });
             }
+            PFacetAttr::Alias { name } => {
+                attribute_list.push(quote! { ::facet::FieldAttribute::Alias(#name) });
+            }
             PFacetAttr::SkipSerializing => {
                 if flags_empty {
                     flags_empty = false;
@@ -102,6 +105,24 @@ pub(crate) fn gen_field_from_pfield(
                     flags = quote! { #flags.union(::facet::FieldFlags::SKIP_SERIALIZING) };
                 }
             }
+            PFacetAttr::SkipDeserializing => {
+                if flags_empty {
+                    flags_empty = false;
+                    flags = quote! { ::facet::FieldFlags::SKIP_DESERIALIZING };
+                } else {
+                    flags = quote! { #flags.union(::facet::FieldFlags::SKIP_DESERIALIZING) };
+                }
+                if !field
+                    .attrs
+                    .facet
+                    .iter()
+                    .any(|attr| matches!(attr, PFacetAttr::DefaultEquals { .. }))
+                {
+                    asserts.push(quote! {
+                        ::facet::static_assertions::assert_impl_all!(#field_type_static: ::core::default::Default);
+                    })
+                }
+            }
             PFacetAttr::SkipSerializingIf { expr } => {
                 let predicate = expr;
                 let field_ty = field_type;
@@ -109,11 +130,45 @@ pub(crate) fn gen_field_from_pfield(
                     .skip_serializing_if(unsafe { ::core::mem::transmute((#predicate) as fn(&#field_ty) -> bool) })
                 });
             }
+            PFacetAttr::SerializeWith { expr } => {
+                let field_ty = field_type;
+                vtable_items.push(quote! {
+                    .serialize_with(::facet::SerializeWith::new(
+                        ::facet::shape_of_serialize_with::<#field_ty, _>(#expr),
+                        |field, target| unsafe {
+                            ::facet::call_serialize_with::<#field_ty, _>(#expr, field, target)
+                        },
+                    ))
+                });
+            }
+            PFacetAttr::DeserializeWith { expr } => {
+                let field_ty = field_type;
+                vtable_items.push(quote! {
+                    .deserialize_with(::facet::DeserializeWith::new(
+                        ::facet::shape_of_deserialize_with::<#field_ty, _, _>(#expr),
+                        |source, target, error| unsafe {
+                            ::facet::call_deserialize_with::<#field_ty, _, _>(#expr, source, target, error)
+                        },
+                    ))
+                });
+            }
+            PFacetAttr::Proxy { ty } => {
+                let field_ty = field_type;
+                vtable_items.push(quote! {
+                    .serialize_with(::facet::SerializeWith::proxy::<#field_ty, #ty>())
+                    .deserialize_with(::facet::DeserializeWith::proxy::<#field_ty, #ty>())
+                });
+            }
             // These are handled by PName or are container-level, so ignore them for field attributes.
             PFacetAttr::RenameAll { .. } => {} // Explicitly ignore rename attributes here
             PFacetAttr::Transparent
             | PFacetAttr::Invariants { .. }
-            | PFacetAttr::DenyUnknownFields => {}
+            | PFacetAttr::DenyUnknownFields
+            | PFacetAttr::Tag { .. }
+            | PFacetAttr::Content { .. }
+            | PFacetAttr::Untagged => {}
+            // Expanded into SkipSerializing and SkipDeserializing when the field is parsed
+            PFacetAttr::Skip => {}
         }
     }
 
@@ -264,8 +319,17 @@ pub(crate) fn process_struct(parsed: Struct) -> TokenStream {
                 | PFacetAttr::Invariants { .. }
                 | PFacetAttr::SkipSerializing
                 | PFacetAttr::SkipSerializingIf { .. }
+                | PFacetAttr::SkipDeserializing
+                | PFacetAttr::Skip
+                | PFacetAttr::SerializeWith { .. }
+                | PFacetAttr::DeserializeWith { .. }
+                | PFacetAttr::Proxy { .. }
                 | PFacetAttr::Flatten
-                | PFacetAttr::Child => {}
+                | PFacetAttr::Child
+                | PFacetAttr::Alias { .. }
+                | PFacetAttr::Tag { .. }
+                | PFacetAttr::Content { .. }
+                | PFacetAttr::Untagged => {}
             }
         }
         if items.is_empty() {
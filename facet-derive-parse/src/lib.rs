@@ -42,6 +42,8 @@ keyword! {
     pub KRename = "rename";
     /// The "rename_all" keyword.
     pub KRenameAll = "rename_all";
+    /// The "alias" keyword.
+    pub KAlias = "alias";
     /// The "flatten" keyword
     pub KFlatten = "flatten";
     /// The "child" keyword
@@ -50,6 +52,22 @@ keyword! {
     pub KSkipSerializing = "skip_serializing";
     /// The "skip_serializing_if" keyword.
     pub KSkipSerializingIf = "skip_serializing_if";
+    /// The "skip_deserializing" keyword.
+    pub KSkipDeserializing = "skip_deserializing";
+    /// The "skip" keyword.
+    pub KSkip = "skip";
+    /// The "serialize_with" keyword.
+    pub KSerializeWith = "serialize_with";
+    /// The "deserialize_with" keyword.
+    pub KDeserializeWith = "deserialize_with";
+    /// The "proxy" keyword.
+    pub KProxy = "proxy";
+    /// The "tag" keyword.
+    pub KTag = "tag";
+    /// The "content" keyword.
+    pub KContent = "content";
+    /// The "untagged" keyword.
+    pub KUntagged = "untagged";
 }
 
 operator! {
@@ -147,6 +165,8 @@ unsynn! {
         RenameAll(RenameAllInner),
         /// A rename attribute that specifies a custom name for a field/variant (#[facet(rename = "custom_name")])
         Rename(RenameInner),
+        /// An alias attribute that specifies an alternative name accepted for a field/variant when deserializing (#[facet(alias = "old_name")])
+        Alias(AliasInner),
         /// A flatten attribute that marks a field to be flattened into the parent structure
         Flatten(FlattenInner),
         /// A child attribute that marks a field as a child node
@@ -155,6 +175,22 @@ unsynn! {
         SkipSerializing(SkipSerializingInner),
         /// A skip_serializing_if attribute that specifies a condition for skipping serialization.
         SkipSerializingIf(SkipSerializingIfInner),
+        /// A skip_deserializing attribute that specifies a field is never read from the input and always gets its default value.
+        SkipDeserializing(SkipDeserializingInner),
+        /// A skip attribute, shorthand for both skip_serializing and skip_deserializing.
+        Skip(SkipInner),
+        /// A serialize_with attribute that specifies a function converting a field into the value serialized in its place (#[facet(serialize_with = to_seconds)])
+        SerializeWith(SerializeWithInner),
+        /// A deserialize_with attribute that specifies a function building a field from the value deserialized in its place (#[facet(deserialize_with = from_seconds)])
+        DeserializeWith(DeserializeWithInner),
+        /// A proxy attribute that specifies a type a field is converted to and from when (de)serializing (#[facet(proxy = Seconds)])
+        Proxy(ProxyInner),
+        /// A tag attribute that specifies the key holding the variant name of an enum (#[facet(tag = "type")])
+        Tag(TagInner),
+        /// A content attribute that specifies the key holding the variant content of an enum (#[facet(content = "value")])
+        Content(ContentInner),
+        /// An untagged attribute for enums whose variant name is not serialized
+        Untagged(KUntagged),
         /// Any other attribute represented as a sequence of token trees.
         Arbitrary(VerbatimUntil<Comma>),
     }
@@ -177,6 +213,18 @@ unsynn! {
         pub _kw_skip_serializing: KSkipSerializing,
     }
 
+    /// Inner value for #[facet(skip_deserializing)]
+    pub struct SkipDeserializingInner {
+        /// The "skip_deserializing" keyword.
+        pub _kw_skip_deserializing: KSkipDeserializing,
+    }
+
+    /// Inner value for #[facet(skip)]
+    pub struct SkipInner {
+        /// The "skip" keyword.
+        pub _kw_skip: KSkip,
+    }
+
     /// Inner value for #[facet(skip_serializing_if = ...)]
     pub struct SkipSerializingIfInner {
         /// The "skip_serializing_if" keyword.
@@ -187,6 +235,36 @@ unsynn! {
         pub expr: VerbatimUntil<Comma>,
     }
 
+    /// Inner value for #[facet(serialize_with = ...)]
+    pub struct SerializeWithInner {
+        /// The "serialize_with" keyword.
+        pub _kw_serialize_with: KSerializeWith,
+        /// The equals sign '='.
+        pub _eq: Eq,
+        /// The conversion function as verbatim until comma.
+        pub expr: VerbatimUntil<Comma>,
+    }
+
+    /// Inner value for #[facet(deserialize_with = ...)]
+    pub struct DeserializeWithInner {
+        /// The "deserialize_with" keyword.
+        pub _kw_deserialize_with: KDeserializeWith,
+        /// The equals sign '='.
+        pub _eq: Eq,
+        /// The conversion function as verbatim until comma.
+        pub expr: VerbatimUntil<Comma>,
+    }
+
+    /// Inner value for #[facet(proxy = ...)]
+    pub struct ProxyInner {
+        /// The "proxy" keyword.
+        pub _kw_proxy: KProxy,
+        /// The equals sign '='.
+        pub _eq: Eq,
+        /// The proxy type as verbatim until comma.
+        pub ty: VerbatimUntil<Comma>,
+    }
+
     /// Inner value for #[facet(default = ...)]
     pub struct DefaultEqualsInner {
         /// The "default" keyword.
@@ -207,6 +285,16 @@ unsynn! {
         pub value: LiteralString,
     }
 
+    /// Inner value for #[facet(alias = ...)]
+    pub struct AliasInner {
+        /// The "alias" keyword.
+        pub _kw_alias: KAlias,
+        /// The equals sign '='.
+        pub _eq: Eq,
+        /// The value assigned, as a literal string.
+        pub value: LiteralString,
+    }
+
     /// Inner value for #[facet(rename_all = ...)]
     pub struct RenameAllInner {
         /// The "rename_all" keyword.
@@ -217,6 +305,26 @@ unsynn! {
         pub value: LiteralString,
     }
 
+    /// Inner value for #[facet(tag = ...)]
+    pub struct TagInner {
+        /// The "tag" keyword.
+        pub _kw_tag: KTag,
+        /// The equals sign '='.
+        pub _eq: Eq,
+        /// The value assigned, as a literal string.
+        pub value: LiteralString,
+    }
+
+    /// Inner value for #[facet(content = ...)]
+    pub struct ContentInner {
+        /// The "content" keyword.
+        pub _kw_content: KContent,
+        /// The equals sign '='.
+        pub _eq: Eq,
+        /// The value assigned, as a literal string.
+        pub value: LiteralString,
+    }
+
     /// Represents invariants for a type.
     pub struct InvariantInner {
         /// The "invariants" keyword.
@@ -2,6 +2,7 @@
 use ariadne::{Color, Config, IndexType, Label, Report, ReportKind, Source};
 
 use alloc::string::String;
+use alloc::{vec, vec::Vec};
 
 use facet_core::{Shape, Type, UserType};
 use facet_reflect::{ReflectError, VariantError};
@@ -86,7 +87,10 @@ pub enum DeserErrorKind<'shape> {
     },
 
     /// A required struct field was missing at the end of JSON input.
-    MissingField(&'static str),
+    MissingField(&'shape str),
+
+    /// Reading the input failed.
+    Io(String),
 
     /// A number is out of range.
     NumberOutOfRange(f64),
@@ -106,6 +110,10 @@ pub enum DeserErrorKind<'shape> {
     /// A string that could not be built into valid UTF-8 Unicode
     InvalidUtf8(String),
 
+    /// The input isn't valid syntax for the format, as reported by the parser the format
+    /// relies on. Holds the parser's message.
+    InvalidSyntax(String),
+
     /// An error occurred while reflecting a type.
     ReflectError(ReflectError<'shape>),
 
@@ -132,6 +140,21 @@ pub enum DeserErrorKind<'shape> {
 
     /// An error occurred when reflecting an enum variant (index) from a user type.
     VariantError(VariantError),
+
+    /// The key holding the variant name of an internally or adjacently tagged enum was not found.
+    MissingTag {
+        /// The name of the tag key
+        tag: &'shape str,
+
+        /// The enum shape definition that requires the tag
+        enum_shape: &'shape Shape<'shape>,
+    },
+
+    /// None of the variants of an untagged enum matched the input.
+    NoMatchingVariant {
+        /// The enum shape definition whose variants were tried
+        enum_shape: &'shape Shape<'shape>,
+    },
 }
 
 impl<'input, 'shape> DeserError<'input, 'shape> {
@@ -231,6 +254,7 @@ impl core::fmt::Display for DeserErrorMessage<'_, '_> {
                 write!(f, "Missing {} for {}", expected.red(), field.yellow())
             }
             DeserErrorKind::MissingField(fld) => write!(f, "Missing required field: {}", fld.red()),
+            DeserErrorKind::Io(e) => write!(f, "Failed to read input: {}", e.red()),
             DeserErrorKind::NumberOutOfRange(n) => {
                 write!(f, "Number out of range: {}", n.red())
             }
@@ -246,6 +270,7 @@ impl core::fmt::Display for DeserErrorMessage<'_, '_> {
                 )
             }
             DeserErrorKind::InvalidUtf8(e) => write!(f, "Invalid UTF-8 encoding: {}", e.red()),
+            DeserErrorKind::InvalidSyntax(e) => write!(f, "Invalid syntax: {}", e.red()),
             DeserErrorKind::ReflectError(e) => write!(f, "{e}"),
             DeserErrorKind::Unimplemented(s) => {
                 write!(f, "Feature not yet implemented: {}", s.yellow())
@@ -291,6 +316,21 @@ impl core::fmt::Display for DeserErrorMessage<'_, '_> {
             DeserErrorKind::VariantError(e) => {
                 write!(f, "Variant error: {e}")
             }
+            DeserErrorKind::MissingTag { tag, enum_shape } => {
+                write!(
+                    f,
+                    "Missing tag {} for enum {}",
+                    tag.red(),
+                    enum_shape.yellow()
+                )
+            }
+            DeserErrorKind::NoMatchingVariant { enum_shape } => {
+                write!(
+                    f,
+                    "Data did not match any variant of untagged enum {}",
+                    enum_shape.yellow()
+                )
+            }
         }
     }
 }
@@ -308,6 +348,10 @@ impl core::fmt::Display for DeserError<'_, '_> {
         let Ok(input_str) = core::str::from_utf8(&self.input[..]) else {
             return write!(f, "(JSON input was invalid UTF-8)");
         };
+        if self.span.end() > input_str.len() {
+            // The input was read incrementally and is gone
+            return write!(f, "{} at byte {}", self.message(), self.span.start());
+        }
 
         let source_id = self.source_id;
         let span_start = self.span.start();
@@ -322,63 +366,172 @@ impl core::fmt::Display for DeserError<'_, '_> {
 
         report = report.with_label(label);
 
-        let source = Source::from(input_str);
+        write_report(f, report.finish(), source_id, input_str)
+    }
+}
 
-        struct FmtWriter<'a, 'b: 'a> {
-            f: &'a mut core::fmt::Formatter<'b>,
-            error: Option<core::fmt::Error>,
-        }
+/// Writes an ariadne report about `input_str` to a formatter.
+#[cfg(feature = "rich-diagnostics")]
+fn write_report(
+    f: &mut core::fmt::Formatter<'_>,
+    report: Report<'_, (&'static str, core::ops::Range<usize>)>,
+    source_id: &'static str,
+    input_str: &str,
+) -> core::fmt::Result {
+    let source = Source::from(input_str);
+
+    struct FmtWriter<'a, 'b: 'a> {
+        f: &'a mut core::fmt::Formatter<'b>,
+        error: Option<core::fmt::Error>,
+    }
 
-        impl core::fmt::Write for FmtWriter<'_, '_> {
-            fn write_str(&mut self, s: &str) -> core::fmt::Result {
-                if self.error.is_some() {
-                    // Already failed, do nothing
-                    return Err(core::fmt::Error);
-                }
-                if let Err(e) = self.f.write_str(s) {
-                    self.error = Some(e);
-                    Err(core::fmt::Error)
-                } else {
-                    Ok(())
-                }
+    impl core::fmt::Write for FmtWriter<'_, '_> {
+        fn write_str(&mut self, s: &str) -> core::fmt::Result {
+            if self.error.is_some() {
+                // Already failed, do nothing
+                return Err(core::fmt::Error);
+            }
+            if let Err(e) = self.f.write_str(s) {
+                self.error = Some(e);
+                Err(core::fmt::Error)
+            } else {
+                Ok(())
             }
         }
+    }
 
-        struct IoWriter<'a, 'b: 'a> {
-            inner: FmtWriter<'a, 'b>,
-        }
+    struct IoWriter<'a, 'b: 'a> {
+        inner: FmtWriter<'a, 'b>,
+    }
 
-        impl std::io::Write for IoWriter<'_, '_> {
-            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-                match core::str::from_utf8(buf) {
-                    Ok(s) => match core::fmt::Write::write_str(&mut self.inner, s) {
-                        Ok(()) => Ok(buf.len()),
-                        Err(_) => Err(std::io::ErrorKind::Other.into()),
-                    },
-                    Err(_) => Err(std::io::ErrorKind::InvalidData.into()),
-                }
-            }
-            fn flush(&mut self) -> std::io::Result<()> {
-                Ok(())
+    impl std::io::Write for IoWriter<'_, '_> {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            match core::str::from_utf8(buf) {
+                Ok(s) => match core::fmt::Write::write_str(&mut self.inner, s) {
+                    Ok(()) => Ok(buf.len()),
+                    Err(_) => Err(std::io::ErrorKind::Other.into()),
+                },
+                Err(_) => Err(std::io::ErrorKind::InvalidData.into()),
             }
         }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
 
-        let cache = (source_id, &source);
+    let cache = (source_id, &source);
 
-        let fmt_writer = FmtWriter { f, error: None };
-        let mut io_writer = IoWriter { inner: fmt_writer };
+    let fmt_writer = FmtWriter { f, error: None };
+    let mut io_writer = IoWriter { inner: fmt_writer };
 
-        if report.finish().write(cache, &mut io_writer).is_err() {
-            return write!(f, "Error formatting with ariadne");
-        }
+    if report.write(cache, &mut io_writer).is_err() {
+        return write!(io_writer.inner.f, "Error formatting with ariadne");
+    }
 
-        // Check if our adapter ran into a formatting error
-        if io_writer.inner.error.is_some() {
-            return write!(f, "Error writing ariadne output to fmt::Formatter");
+    // Check if our adapter ran into a formatting error
+    if io_writer.inner.error.is_some() {
+        return write!(
+            io_writer.inner.f,
+            "Error writing ariadne output to fmt::Formatter"
+        );
+    }
+
+    Ok(())
+}
+
+impl core::error::Error for DeserError<'_, '_> {}
+
+/// All the errors found while deserializing an input, as returned by
+/// [`deserialize_collecting`](crate::deserialize_collecting).
+#[derive(Debug)]
+pub struct DeserErrors<'input, 'shape> {
+    errors: Vec<DeserError<'input, 'shape>>,
+}
+
+impl<'input, 'shape> DeserErrors<'input, 'shape> {
+    /// Bundles errors about the same input, sorting them by where they occurred.
+    pub fn new(mut errors: Vec<DeserError<'input, 'shape>>) -> Self {
+        errors.sort_by_key(|err| err.span.start());
+        Self { errors }
+    }
+
+    /// The errors, in the order they occur in the input.
+    pub fn errors(&self) -> &[DeserError<'input, 'shape>] {
+        &self.errors
+    }
+
+    /// Returns the errors, in the order they occur in the input.
+    pub fn into_errors(self) -> Vec<DeserError<'input, 'shape>> {
+        self.errors
+    }
+
+    /// Converts the errors into owned errors.
+    pub fn into_owned(self) -> DeserErrors<'static, 'shape> {
+        DeserErrors {
+            errors: self
+                .errors
+                .into_iter()
+                .map(DeserError::into_owned)
+                .collect(),
         }
+    }
+}
+
+impl<'input, 'shape> From<DeserError<'input, 'shape>> for DeserErrors<'input, 'shape> {
+    fn from(err: DeserError<'input, 'shape>) -> Self {
+        Self { errors: vec![err] }
+    }
+}
+
+impl<'input, 'shape> IntoIterator for DeserErrors<'input, 'shape> {
+    type Item = DeserError<'input, 'shape>;
+    type IntoIter = alloc::vec::IntoIter<DeserError<'input, 'shape>>;
 
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.into_iter()
+    }
+}
+
+#[cfg(not(feature = "rich-diagnostics"))]
+impl core::fmt::Display for DeserErrors<'_, '_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (index, err) in self.errors.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{err}")?;
+        }
         Ok(())
     }
 }
 
-impl core::error::Error for DeserError<'_, '_> {}
+#[cfg(feature = "rich-diagnostics")]
+impl core::fmt::Display for DeserErrors<'_, '_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let Some(first) = self.errors.first() else {
+            return Ok(());
+        };
+        let Ok(input_str) = core::str::from_utf8(&first.input[..]) else {
+            return write!(f, "(JSON input was invalid UTF-8)");
+        };
+
+        let source_id = first.source_id;
+        let mut report = Report::build(
+            ReportKind::Error,
+            (source_id, first.span.start()..first.span.end()),
+        )
+        .with_config(Config::new().with_index_type(IndexType::Byte))
+        .with_message(format_args!("Found {} errors", self.errors.len()));
+
+        for err in &self.errors {
+            let label = Label::new((source_id, err.span.start()..err.span.end()))
+                .with_message(err.message())
+                .with_color(Color::Red);
+            report = report.with_label(label);
+        }
+
+        write_report(f, report.finish(), source_id, input_str)
+    }
+}
+
+impl core::error::Error for DeserErrors<'_, '_> {}
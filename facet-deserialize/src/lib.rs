@@ -17,13 +17,20 @@ pub use debug::InputDebug;
 
 pub use error::*;
 
+mod recovery;
+#[doc(hidden)]
+pub use recovery::{Entry, Recovery, Slot};
+
 mod span;
+
+mod tape;
 use facet_core::{
-    Characteristic, Def, Facet, FieldFlags, PointerType, ScalarAffinity, SequenceType, StructKind,
-    Type, UserType,
+    Characteristic, Def, EnumTagging, Facet, Field, FieldFlags, PointerType, ScalarAffinity,
+    SequenceType, Shape, StructKind, Type, UserType,
 };
 use owo_colors::OwoColorize;
 pub use span::*;
+pub use tape::Tape;
 
 use facet_reflect::{HeapValue, ReflectError, Wip};
 use log::trace;
@@ -46,9 +53,11 @@ pub enum Scalar<'input> {
     Bool(bool),
     /// Null scalar (e.g. for formats supporting explicit null).
     Null,
+    /// Owned or borrowed bytes, for formats that have a type for them.
+    Bytes(Cow<'input, [u8]>),
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Copy)]
 /// Expected next input token or structure during deserialization.
 pub enum Expectation {
     /// Accept a value.
@@ -107,6 +116,7 @@ impl fmt::Display for Scalar<'_> {
             Scalar::F64(val) => write!(f, "f64 {}", val),
             Scalar::Bool(val) => write!(f, "bool {}", val),
             Scalar::Null => write!(f, "null"),
+            Scalar::Bytes(bytes) => write!(f, "{} bytes", bytes.len()),
         }
     }
 }
@@ -122,6 +132,7 @@ impl Outcome<'_> {
                     Scalar::F64(val) => Scalar::F64(val),
                     Scalar::Bool(val) => Scalar::Bool(val),
                     Scalar::Null => Scalar::Null,
+                    Scalar::Bytes(cow) => Scalar::Bytes(Cow::Owned(cow.into_owned())),
                 };
                 Outcome::Scalar(owned_scalar)
             }
@@ -139,8 +150,14 @@ pub struct NextData<'input: 'facet, 'facet, 'shape, I: ?Sized + 'input = [u8]> {
     /// The offset we're supposed to start parsing from
     start: usize,
 
-    /// Controls the parsing flow and stack state.
-    runner: StackRunner<'input, I>,
+    /// The input being parsed.
+    input: &'input I,
+
+    /// The offset before which the input won't be read again
+    keep_from: usize,
+
+    /// How many outcomes and skipped values were read before this one
+    reads: usize,
 
     /// Holds the intermediate representation of the value being built.
     pub wip: Wip<'facet, 'shape>,
@@ -149,13 +166,28 @@ pub struct NextData<'input: 'facet, 'facet, 'shape, I: ?Sized + 'input = [u8]> {
 impl<'input: 'facet, 'facet, 'shape, I: ?Sized + 'input> NextData<'input, 'facet, 'shape, I> {
     /// Returns the input (from the start! not from the current position)
     pub fn input(&self) -> &'input I {
-        self.runner.input
+        self.input
     }
 
     /// Returns the parsing start offset.
     pub fn start(&self) -> usize {
         self.start
     }
+
+    /// Returns the offset before which the input won't be read again, even when looking
+    /// ahead. Formats that read their input incrementally can let go of anything before it.
+    pub fn keep_from(&self) -> usize {
+        self.keep_from
+    }
+
+    /// Returns how many outcomes and skipped values were read before this one.
+    ///
+    /// It goes back along with the start offset whenever the deserializer reads something
+    /// again, so formats whose outcomes don't each stand for their own part of the input can
+    /// tell where to resume.
+    pub fn reads(&self) -> usize {
+        self.reads
+    }
 }
 
 /// The result of advancing the parser: updated state and parse outcome or error.
@@ -206,6 +238,49 @@ pub trait Format {
         'shape: 'input;
 }
 
+impl<F: Format + ?Sized> Format for &mut F {
+    type Input<'input> = F::Input<'input>;
+
+    fn source(&self) -> &'static str {
+        (**self).source()
+    }
+
+    fn next<'input, 'facet, 'shape>(
+        &mut self,
+        nd: NextData<'input, 'facet, 'shape, Self::Input<'input>>,
+        expectation: Expectation,
+    ) -> NextResult<
+        'input,
+        'facet,
+        'shape,
+        Spanned<Outcome<'input>>,
+        Spanned<DeserErrorKind<'shape>>,
+        Self::Input<'input>,
+    >
+    where
+        'shape: 'input,
+    {
+        (**self).next(nd, expectation)
+    }
+
+    fn skip<'input, 'facet, 'shape>(
+        &mut self,
+        nd: NextData<'input, 'facet, 'shape, Self::Input<'input>>,
+    ) -> NextResult<
+        'input,
+        'facet,
+        'shape,
+        Span,
+        Spanned<DeserErrorKind<'shape>>,
+        Self::Input<'input>,
+    >
+    where
+        'shape: 'input,
+    {
+        (**self).skip(nd)
+    }
+}
+
 /// Instructions guiding the parsing flow, indicating the next expected action or token.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Instruction {
@@ -219,6 +294,11 @@ pub enum Instruction {
     ObjectKeyOrObjectClose,
     /// Expect a list item or the end of a list.
     ListItemOrListClose,
+    /// Look ahead in the object that was just started to find the tag of an internally
+    /// or adjacently tagged enum, and select the corresponding variant.
+    SelectTaggedVariant,
+    /// Deserialize the upcoming value into an untagged enum, trying each variant in turn.
+    UntaggedValue,
 }
 
 /// Reasons for expecting a value, reflecting the current parse context.
@@ -253,7 +333,7 @@ pub fn deserialize<'input, 'facet, 'shape, T, F>(
 ) -> Result<T, DeserError<'input, 'shape>>
 where
     T: Facet<'facet>,
-    F: Format + 'shape,
+    F: Format,
     F::Input<'input>: InputDebug,
     'input: 'facet,
     'shape: 'input,
@@ -269,51 +349,138 @@ where
 /// Deserializes a working-in-progress value into a fully materialized heap value.
 /// This function drives the parsing loop until the entire input is consumed and the value is complete.
 pub fn deserialize_wip<'input, 'facet, 'shape, F>(
-    mut wip: Wip<'facet, 'shape>,
+    wip: Wip<'facet, 'shape>,
     input: &'input F::Input<'input>,
     mut format: F,
 ) -> Result<HeapValue<'facet, 'shape>, DeserError<'input, 'shape>>
 where
-    F: Format + 'shape,
+    F: Format,
     F::Input<'input>: InputDebug,
     'input: 'facet,
     'shape: 'input,
 {
     // This struct is just a bundle of the state that we need to pass around all the time.
-    let mut runner = StackRunner {
-        original_input: input,
-        input,
-        stack: vec![
-            Instruction::Pop(PopReason::TopLevel),
-            Instruction::Value(ValueReason::TopLevel),
-        ],
-        last_span: Span::new(0, 0),
-        format_source: format.source(),
-    };
+    let mut runner = StackRunner::new(input, format.source());
 
-    macro_rules! next {
-        ($runner:ident, $wip:ident, $expectation:expr, $method:ident) => {{
+    run(&mut runner, wip, &mut format).map(|(value, _span)| value)
+}
+
+/// Deserialize a value of type `T` like [`deserialize`] does, but instead of stopping at the
+/// first error, keep going and report every unknown field, missing field and invalid value
+/// found in the input.
+///
+/// Values that fail to deserialize are skipped, so that the rest of the input can still be
+/// checked in the same pass. Errors in the syntax of the input can't be recovered from, and
+/// end the report.
+pub fn deserialize_collecting<'input, 'facet, 'shape, T, F>(
+    input: &'input F::Input<'input>,
+    mut format: F,
+) -> Result<T, DeserErrors<'input, 'shape>>
+where
+    T: Facet<'facet>,
+    F: Format,
+    F::Input<'input>: InputDebug,
+    'input: 'facet,
+    'shape: 'input,
+{
+    let source = format.source();
+    let wip = Wip::alloc_shape(T::SHAPE)
+        .map_err(|e| DeserError::new_reflect(e, input, Span { start: 0, len: 0 }, source))?;
+    let mut runner = StackRunner::new(input, source);
+    runner.recovery = Some(Recovery::new(0));
+
+    let res = run(&mut runner, wip, &mut format);
+    let recovery = runner.recovery.take().unwrap();
+    let mut failures = recovery.errors;
+    match res {
+        Ok((value, _span)) if failures.is_empty() => {
+            return value
+                .materialize()
+                .map_err(|e| DeserError::new_reflect(e, input, Span::new(0, 0), source).into());
+        }
+        Ok(_) => {}
+        // Fields were skipped or missing, that's already reported
+        Err(_) if recovery.building && !failures.is_empty() => {}
+        Err(err) => failures.push(err),
+    }
+    Err(DeserErrors::new(failures))
+}
+
+/// Drives the parsing loop until the runner's stack pops its top level, returning the built
+/// value along with the span of the last token that was consumed.
+fn run<'input, 'facet, 'shape, F>(
+    runner: &mut StackRunner<'input, 'shape, F::Input<'input>>,
+    mut wip: Wip<'facet, 'shape>,
+    format: &mut F,
+) -> Result<(HeapValue<'facet, 'shape>, Span), DeserError<'input, 'shape>>
+where
+    F: Format,
+    F::Input<'input>: InputDebug,
+    'input: 'facet,
+    'shape: 'input,
+{
+    macro_rules! next_outcome {
+        ($runner:ident, $wip:ident, $expectation:expr) => {{
             let nd = NextData {
                 start: $runner.last_span.end(), // or supply the appropriate start value if available
-                runner: $runner,
+                input: $runner.input,
+                keep_from: $runner.keep_from(),
+                reads: $runner.reads,
                 wip: $wip,
             };
             let (nd, res) = format.next(nd, $expectation);
-            $runner = nd.runner;
             $wip = nd.wip;
             let outcome = res.map_err(|span_kind| {
                 $runner.last_span = span_kind.span;
-                $runner.err(span_kind.node)
+                $runner.format_err(span_kind.node)
             })?;
             $runner.last_span = outcome.span;
+            $runner.reads += 1;
+            outcome
+        }};
+    }
+
+    macro_rules! next {
+        ($runner:ident, $wip:ident, $expectation:expr, $method:ident) => {{
+            let outcome = next_outcome!($runner, $wip, $expectation);
             $wip = $runner.$method($wip, outcome)?;
         }};
     }
 
+    macro_rules! skip {
+        ($runner:ident, $wip:ident) => {{
+            // Call F::skip to skip over the next value in the input
+            let nd = NextData {
+                start: $runner.last_span.end(),
+                input: $runner.input,
+                keep_from: $runner.keep_from(),
+                reads: $runner.reads,
+                wip: $wip,
+            };
+            let (nd, res) = format.skip(nd);
+            $wip = nd.wip;
+            // Only propagate error, don't modify wip, since skip just advances input
+            let span = res.map_err(|span_kind| {
+                $runner.last_span = span_kind.span;
+                $runner.format_err(span_kind.node)
+            })?;
+            // do the actual skip
+            $runner.last_span = span;
+            $runner.reads += 1;
+        }};
+    }
+
     loop {
+        // An item or map value that's deserialized on its own only gets its frame once it's
+        // there, but its pop is already on the stack
+        let pending_frame = runner
+            .recovery
+            .as_ref()
+            .and_then(|r| r.entry.as_ref())
+            .is_some_and(|entry| !matches!(entry.slot, Slot::Field { .. }));
         let frame_count = wip.frames_count();
         debug_assert!(
-            frame_count
+            frame_count + usize::from(pending_frame)
                 >= runner
                     .stack
                     .iter()
@@ -328,17 +495,31 @@ where
 
         trace!("[{frame_count}] Instruction {:?}", insn.yellow());
 
+        if let Some(recovery) = &mut runner.recovery {
+            // Only the pop right after an object is closed gets to check its fields
+            let closed_object = recovery.closed_object.take();
+            recovery.closing_object = closed_object.filter(|_| matches!(insn, Instruction::Pop(_)));
+        }
+
         match insn {
             Instruction::Pop(reason) => {
                 wip = runner.pop(wip, reason)?;
 
                 if reason == PopReason::TopLevel {
-                    return wip.build().map_err(|e| runner.reflect_err(e));
+                    if let Some(recovery) = &mut runner.recovery {
+                        recovery.building = true;
+                    }
+                    let value = wip.build().map_err(|e| runner.reflect_err(e))?;
+                    return Ok((value, runner.last_span));
                 } else {
                     wip = wip.pop().map_err(|e| runner.reflect_err(e))?;
                 }
             }
             Instruction::Value(_why) => {
+                if let Some(entry) = runner.recovery.as_mut().and_then(|r| r.entry.take()) {
+                    wip = entry_value(runner, wip, entry, format)?;
+                    continue;
+                }
                 let expectation = match _why {
                     ValueReason::TopLevel => Expectation::Value,
                     ValueReason::ObjectVal => Expectation::ObjectVal,
@@ -360,35 +541,327 @@ where
                     Expectation::ListItemOrListClose,
                     list_item_or_list_close
                 );
+                if let Some(entry) = runner.recovery.as_mut().and_then(|r| r.entry.take()) {
+                    wip = entry_value(runner, wip, entry, format)?;
+                }
             }
             Instruction::SkipValue => {
-                // Call F::skip to skip over the next value in the input
-                let nd = NextData {
-                    start: runner.last_span.end(),
-                    runner,
-                    wip,
+                skip!(runner, wip);
+            }
+            Instruction::SelectTaggedVariant => {
+                let enum_shape = wip.shape();
+                let (tag, content) = match enum_shape.enum_tagging() {
+                    EnumTagging::Internal { tag } => (tag, None),
+                    EnumTagging::Adjacent { tag, content } => (tag, Some(content)),
+                    _ => unreachable!("only tagged enums look ahead for their tag"),
+                };
+
+                // Scan the whole object for the tag, then rewind so the fields
+                // can be deserialized once we know which variant they belong to.
+                let object_start = runner.last_span;
+                let object_reads = runner.reads;
+                let anchor = runner.anchor;
+                runner.anchor = Some(anchor.unwrap_or(object_start.end()));
+                let mut variant_name = None;
+                loop {
+                    let key = next_outcome!(runner, wip, Expectation::ObjectKeyOrObjectClose);
+                    match key.node {
+                        Outcome::Scalar(Scalar::String(name)) if name == tag => {
+                            runner.tag_keys.push(key.span.start());
+                            let value = next_outcome!(runner, wip, Expectation::ObjectVal);
+                            match value.node {
+                                Outcome::Scalar(Scalar::String(name)) => {
+                                    variant_name = Some((name.to_string(), value.span));
+                                }
+                                other => {
+                                    return Err(runner.err(DeserErrorKind::UnexpectedOutcome {
+                                        got: other.into_owned(),
+                                        wanted: "variant name",
+                                    }));
+                                }
+                            }
+                        }
+                        Outcome::Scalar(Scalar::String(name)) => {
+                            if content == Some(&*name) {
+                                runner.content_keys.push(key.span.start());
+                            }
+                            skip!(runner, wip);
+                        }
+                        Outcome::ObjectEnded => break,
+                        other => {
+                            return Err(runner.err(DeserErrorKind::UnexpectedOutcome {
+                                got: other.into_owned(),
+                                wanted: "scalar or object close",
+                            }));
+                        }
+                    }
+                }
+
+                runner.anchor = anchor;
+
+                let Some((variant_name, variant_span)) = variant_name else {
+                    return Err(runner.err(DeserErrorKind::MissingTag { tag, enum_shape }));
+                };
+                runner.last_span = variant_span;
+                let Some((index, variant)) = wip.find_variant(&variant_name) else {
+                    return Err(runner.err(DeserErrorKind::NoSuchVariant {
+                        name: variant_name,
+                        enum_shape,
+                    }));
+                };
+                trace!(
+                    "Selecting tagged variant {}::{}",
+                    enum_shape.blue(),
+                    variant.name.yellow(),
+                );
+                wip = wip.variant(index).map_err(|e| runner.reflect_err(e))?;
+                runner.last_span = object_start;
+                runner.reads = object_reads;
+
+                if content.is_none()
+                    && matches!(variant.data.kind, StructKind::Tuple)
+                    && variant.data.fields.len() == 1
+                {
+                    // The fields of the newtype's struct sit next to the tag: fill it in
+                    // directly, and pop it once the object is closed.
+                    wip = wip.field(0).map_err(|e| runner.reflect_err(e))?;
+                    let object_insn = runner.stack.pop();
+                    runner.stack.push(Instruction::Pop(PopReason::ObjectVal));
+                    runner.stack.extend(object_insn);
+                }
+            }
+            Instruction::UntaggedValue => {
+                let enum_shape = wip.shape();
+                let Type::User(UserType::Enum(ed)) = enum_shape.ty else {
+                    unreachable!("only enums can be untagged")
                 };
-                let (nd, res) = format.skip(nd);
-                runner = nd.runner;
-                wip = nd.wip;
-                // Only propagate error, don't modify wip, since skip just advances input
-                let span = res.map_err(|span_kind| {
-                    runner.last_span = span_kind.span;
-                    runner.err(span_kind.node)
-                })?;
-                // do the actual skip
-                runner.last_span = span;
+
+                let value_start = runner.last_span;
+                let mut matched = None;
+                for (index, variant) in ed.variants.iter().enumerate() {
+                    match try_untagged_variant(runner, enum_shape, index, format) {
+                        Ok(res) => {
+                            trace!("Untagged value matched variant {}", variant.name.yellow());
+                            matched = Some(res);
+                            break;
+                        }
+                        Err(e) => {
+                            trace!(
+                                "Untagged value did not match variant {}: {}",
+                                variant.name.yellow(),
+                                e.message()
+                            );
+                        }
+                    }
+                }
+
+                match matched {
+                    Some((value, span, reads)) => {
+                        wip = wip
+                            .put_heap_value(value)
+                            .map_err(|e| runner.reflect_err(e))?;
+                        runner.last_span = span;
+                        runner.reads = reads;
+                    }
+                    None => {
+                        // Point the error at the whole value if we can
+                        let nd = NextData {
+                            start: value_start.end(),
+                            input: runner.input,
+                            keep_from: runner.keep_from(),
+                            reads: runner.reads,
+                            wip,
+                        };
+                        let (_, res) = format.skip(nd);
+                        if let Ok(span) = res {
+                            runner.last_span =
+                                Span::new(value_start.end(), span.end() - value_start.end());
+                        }
+                        return Err(runner.err(DeserErrorKind::NoMatchingVariant { enum_shape }));
+                    }
+                }
             }
         }
     }
 }
 
+/// Deserializes the value of an entry with a runner and a `Wip` of its own, and puts it in
+/// its slot. If that fails, the error is collected, the value is skipped, and `runner`
+/// carries on with what's after it.
+fn entry_value<'input, 'facet, 'shape, F>(
+    runner: &mut StackRunner<'input, 'shape, F::Input<'input>>,
+    mut wip: Wip<'facet, 'shape>,
+    entry: Entry<'input, 'shape>,
+    format: &mut F,
+) -> Result<Wip<'facet, 'shape>, DeserError<'input, 'shape>>
+where
+    F: Format,
+    F::Input<'input>: InputDebug,
+    'input: 'facet,
+    'shape: 'input,
+{
+    // Where the value starts, to skip it from there
+    let (start, start_reads) = match &entry.first {
+        Some(first) => (Span::new(first.span.start(), 0), runner.reads - 1),
+        None => (runner.last_span, runner.reads),
+    };
+    let depth = runner.recovery.as_ref().map_or(0, |r| r.depth);
+    let mut entry_runner = StackRunner {
+        stack: vec![Instruction::Pop(PopReason::TopLevel)],
+        last_span: runner.last_span,
+        reads: runner.reads,
+        anchor: Some(runner.anchor.unwrap_or(start.end())),
+        recovery: Some(Recovery::new(depth + 1)),
+        ..StackRunner::new(runner.input, runner.format_source)
+    };
+    let shape = match &entry.slot {
+        Slot::Field { .. } => wip.shape(),
+        Slot::Item(shape) | Slot::MapValue { shape, .. } => shape,
+    };
+    let res = Wip::alloc_shape(shape)
+        .map_err(|e| runner.reflect_err(e))
+        .and_then(|entry_wip| match entry.first {
+            Some(first) => entry_runner.value(entry_wip, first),
+            None => {
+                entry_runner
+                    .stack
+                    .push(Instruction::Value(ValueReason::ObjectVal));
+                Ok(entry_wip)
+            }
+        })
+        .and_then(|entry_wip| run(&mut entry_runner, entry_wip, format));
+
+    let entry_recovery = entry_runner.recovery.take().unwrap();
+    let recovery = runner.recovery.as_mut().unwrap();
+    let reported = !entry_recovery.errors.is_empty();
+    recovery.errors.extend(entry_recovery.errors);
+    let err = match res {
+        Ok((value, _span)) => {
+            runner.last_span = entry_runner.last_span;
+            runner.reads = entry_runner.reads;
+            let wip = match entry.slot {
+                Slot::Field { .. } => wip.put_heap_value(value),
+                Slot::Item(_) => wip.push().and_then(|wip| wip.put_heap_value(value)),
+                Slot::MapValue { key, .. } => wip
+                    .push_map_key()
+                    .and_then(|wip| wip.put(key.into_owned()))
+                    .and_then(|wip| wip.push_map_value())
+                    .and_then(|wip| wip.put_heap_value(value)),
+            };
+            return wip.map_err(|e| runner.reflect_err(e));
+        }
+        Err(err) => err,
+    };
+    if entry_recovery.format_failed {
+        recovery.format_failed = true;
+        return Err(err);
+    }
+    if !(entry_recovery.building && reported) {
+        recovery.errors.push(err);
+    }
+    if let Slot::Field { depth, index } = entry.slot {
+        recovery.skipped_fields.push((depth, index));
+    }
+
+    trace!("Skipping the entry at {}", start.end().yellow());
+    runner.last_span = start;
+    runner.reads = start_reads;
+    let nd = NextData {
+        start: runner.last_span.end(),
+        input: runner.input,
+        keep_from: runner.keep_from(),
+        reads: runner.reads,
+        wip,
+    };
+    let (nd, res) = format.skip(nd);
+    wip = nd.wip;
+    runner.last_span = res.map_err(|span_kind| {
+        runner.last_span = span_kind.span;
+        runner.format_err(span_kind.node)
+    })?;
+    runner.reads += 1;
+
+    // The value never made it into its slot, which goes without being popped the usual way.
+    // Items and map values don't have a frame yet.
+    let pop = runner.stack.pop();
+    debug_assert!(matches!(pop, Some(Instruction::Pop(_))));
+    if let Slot::Field { .. } = entry.slot {
+        wip = wip.pop().map_err(|e| runner.reflect_err(e))?;
+    }
+    Ok(wip)
+}
+
+/// Tries to deserialize the upcoming value as the given variant of an untagged enum,
+/// without advancing `runner`. Returns the value along with where reading it stopped.
+fn try_untagged_variant<'input, 'facet, 'shape, F>(
+    runner: &StackRunner<'input, 'shape, F::Input<'input>>,
+    enum_shape: &'shape Shape<'shape>,
+    variant_index: usize,
+    format: &mut F,
+) -> Result<(HeapValue<'facet, 'shape>, Span, usize), DeserError<'input, 'shape>>
+where
+    F: Format,
+    F::Input<'input>: InputDebug,
+    'input: 'facet,
+    'shape: 'input,
+{
+    let mut runner = StackRunner {
+        stack: vec![Instruction::Pop(PopReason::TopLevel)],
+        last_span: runner.last_span,
+        reads: runner.reads,
+        anchor: Some(runner.keep_from()),
+        ..StackRunner::new(runner.input, runner.format_source)
+    };
+    let mut wip = Wip::alloc_shape(enum_shape)
+        .and_then(|wip| wip.variant(variant_index))
+        .map_err(|e| runner.reflect_err(e))?;
+    let variant = wip.selected_variant().unwrap();
+
+    match variant.data.kind {
+        StructKind::Unit => {
+            // Unit variants are represented as null
+            let nd = NextData {
+                start: runner.last_span.end(),
+                input: runner.input,
+                keep_from: runner.keep_from(),
+                reads: runner.reads,
+                wip,
+            };
+            let (nd, res) = format.next(nd, Expectation::Value);
+            wip = nd.wip;
+            let outcome = res.map_err(|span_kind| {
+                runner.last_span = span_kind.span;
+                runner.err(span_kind.node)
+            })?;
+            runner.last_span = outcome.span;
+            runner.reads += 1;
+            if outcome.node != Outcome::Scalar(Scalar::Null) {
+                return Err(runner.err(DeserErrorKind::UnexpectedOutcome {
+                    got: outcome.node.into_owned(),
+                    wanted: "null",
+                }));
+            }
+            let value = wip.build().map_err(|e| runner.reflect_err(e))?;
+            return Ok((value, runner.last_span, runner.reads));
+        }
+        StructKind::Tuple if variant.data.fields.len() == 1 => {
+            wip = wip.field(0).map_err(|e| runner.reflect_err(e))?;
+            runner.stack.push(Instruction::Pop(PopReason::ObjectVal));
+        }
+        _ => {}
+    }
+    runner.stack.push(Instruction::Value(ValueReason::TopLevel));
+
+    let (value, span) = run(&mut runner, wip, format)?;
+    Ok((value, span, runner.reads))
+}
+
 #[doc(hidden)]
 /// Maintains the parsing state and context necessary to drive deserialization.
 ///
 /// This struct tracks what the parser expects next, manages input position,
 /// and remembers the span of the last processed token to provide accurate error reporting.
-pub struct StackRunner<'input, I: ?Sized + 'input = [u8]> {
+pub struct StackRunner<'input, 'shape, I: ?Sized + 'input = [u8]> {
     /// A version of the input that doesn't advance as we parse.
     pub original_input: &'input I,
 
@@ -401,14 +874,55 @@ pub struct StackRunner<'input, I: ?Sized + 'input = [u8]> {
     /// Span of the last processed token, for accurate error reporting.
     pub last_span: Span,
 
+    /// How many outcomes and skipped values were read so far. Going back to read something
+    /// again sets it back along with `last_span`.
+    pub reads: usize,
+
     /// Format source identifier for error reporting
     pub format_source: &'static str,
+
+    /// Offsets of the tag keys found while looking ahead in tagged enums,
+    /// whose values must be skipped when the object is actually deserialized.
+    pub tag_keys: Vec<usize>,
+
+    /// Offsets of the content keys found while looking ahead in adjacently tagged enums.
+    pub content_keys: Vec<usize>,
+
+    /// Offset the runner will rewind to once it's done looking ahead, if it is.
+    pub anchor: Option<usize>,
+
+    /// Set when collecting errors rather than stopping at the first one.
+    pub recovery: Option<Recovery<'input, 'shape>>,
 }
 
-impl<'input, 'shape, I: ?Sized + 'input> StackRunner<'input, I>
+impl<'input, 'shape, I: ?Sized + 'input> StackRunner<'input, 'shape, I>
 where
     I: InputDebug,
 {
+    /// Creates a runner that deserializes a single top-level value from `input`.
+    fn new(input: &'input I, format_source: &'static str) -> Self {
+        Self {
+            original_input: input,
+            input,
+            stack: vec![
+                Instruction::Pop(PopReason::TopLevel),
+                Instruction::Value(ValueReason::TopLevel),
+            ],
+            last_span: Span::new(0, 0),
+            reads: 0,
+            format_source,
+            tag_keys: Vec::new(),
+            content_keys: Vec::new(),
+            anchor: None,
+            recovery: None,
+        }
+    }
+
+    /// The offset before which the input won't be read again.
+    fn keep_from(&self) -> usize {
+        self.anchor.unwrap_or(self.last_span.end())
+    }
+
     /// Convenience function to create a DeserError using the original input and last_span.
     fn err(&self, kind: DeserErrorKind<'shape>) -> DeserError<'input, 'shape> {
         DeserError::new(
@@ -419,6 +933,15 @@ where
         )
     }
 
+    /// Creates a DeserError for an error reported by the format, which there's no
+    /// skipping past.
+    fn format_err(&mut self, kind: DeserErrorKind<'shape>) -> DeserError<'input, 'shape> {
+        if let Some(recovery) = &mut self.recovery {
+            recovery.format_failed = true;
+        }
+        self.err(kind)
+    }
+
     /// Convenience function to create a DeserError from a ReflectError,
     /// using the original input and last_span for context.
     fn reflect_err(&self, err: ReflectError<'shape>) -> DeserError<'input, 'shape> {
@@ -464,7 +987,7 @@ where
                                         },
                                     ));
                                 }
-                                wip = wip.put_default().map_err(|e| self.reflect_err(e))?;
+                                wip = wip.put_field_default().map_err(|e| self.reflect_err(e))?;
                                 trace!(
                                     "Field #{} {} @ {} was set to default value (via default impl)",
                                     index.yellow(),
@@ -485,6 +1008,10 @@ where
                     }
                 }
 
+                if has_unset && !container_shape.has_default_attr() {
+                    self.report_missing_fields(&wip, sd.fields);
+                }
+
                 if has_unset && container_shape.has_default_attr() {
                     // let's allocate and build a default value
                     let default_val = Wip::alloc_shape(container_shape)
@@ -549,7 +1076,9 @@ where
                                                 },
                                             ));
                                         }
-                                        wip = wip.put_default().map_err(|e| self.reflect_err(e))?;
+                                        wip = wip
+                                            .put_field_default()
+                                            .map_err(|e| self.reflect_err(e))?;
                                         trace!(
                                             "Field #{} @ {} in variant {} was set to default value (via default impl)",
                                             index.yellow(),
@@ -570,6 +1099,10 @@ where
                             }
                         }
 
+                        if has_unset && !container_shape.has_default_attr() {
+                            self.report_missing_fields(&wip, variant.data.fields);
+                        }
+
                         if has_unset && container_shape.has_default_attr() {
                             trace!("Enum has DEFAULT attr but variant has uninitialized fields");
                             // Handle similar to struct, allocate and build default value for variant
@@ -652,6 +1185,60 @@ where
         Ok(wip)
     }
 
+    /// When collecting errors, reports the fields of the object that was just closed
+    /// which were neither set nor defaulted.
+    fn report_missing_fields(&mut self, wip: &Wip<'_, 'shape>, fields: &'shape [Field<'shape>]) {
+        let Some(recovery) = &mut self.recovery else {
+            return;
+        };
+        let depth = wip.frames_count();
+        if let Some(object_span) = recovery.closing_object {
+            for (index, field) in fields.iter().enumerate() {
+                if field.flags.contains(FieldFlags::DEFAULT)
+                    || field.should_skip_deserializing()
+                    || recovery.is_skipped_field(depth, index)
+                    || wip.is_field_set(index).unwrap_or(true)
+                {
+                    continue;
+                }
+                recovery.errors.push(DeserError::new(
+                    DeserErrorKind::MissingField(field.name),
+                    self.original_input,
+                    object_span,
+                    self.format_source,
+                ));
+            }
+        }
+        recovery.skipped_fields.retain(|&(d, _)| d < depth);
+    }
+
+    /// Reports a field that isn't part of `shape`, which doesn't stop deserialization
+    /// when collecting errors.
+    fn unknown_field(
+        &mut self,
+        field_name: &str,
+        shape: &'shape Shape<'shape>,
+    ) -> Result<(), DeserError<'input, 'shape>> {
+        let err = self.err(DeserErrorKind::UnknownField {
+            field_name: field_name.to_string(),
+            shape,
+        });
+        match &mut self.recovery {
+            Some(recovery) => {
+                recovery.errors.push(err);
+                Ok(())
+            }
+            None => Err(err),
+        }
+    }
+
+    /// Whether `wip` is an untagged enum whose variant is yet to be determined.
+    fn is_untagged_enum(wip: &Wip<'_, 'shape>) -> bool {
+        matches!(wip.shape().ty, Type::User(UserType::Enum(_)))
+            && wip.shape().has_untagged_attr()
+            && wip.selected_variant().is_none()
+    }
+
     /// Internal common handler for GotScalar outcome, to deduplicate code.
     fn handle_scalar<'facet>(
         &self,
@@ -691,6 +1278,11 @@ where
                             Cow::Owned(s) => wip.put(s).map_err(|e| self.reflect_err(e)),
                         }
                     }
+                    // A single character, for formats that write `char`s as strings
+                    _ if wip.innermost_shape().is_type::<char>() && cow.chars().count() == 1 => {
+                        let c = cow.chars().next().unwrap();
+                        wip.put(c).map_err(|e| self.reflect_err(e))
+                    }
                     _ => wip.put(cow.to_string()).map_err(|e| self.reflect_err(e)),
                 }
             }
@@ -699,6 +1291,24 @@ where
             Scalar::F64(value) => wip.put(value).map_err(|e| self.reflect_err(e)),
             Scalar::Bool(value) => wip.put(value).map_err(|e| self.reflect_err(e)),
             Scalar::Null => wip.put_default().map_err(|e| self.reflect_err(e)),
+            Scalar::Bytes(bytes) => {
+                if wip.innermost_shape().is_type::<Vec<u8>>() {
+                    return wip.put(bytes.into_owned()).map_err(|e| self.reflect_err(e));
+                }
+                // Other lists of bytes get them one by one
+                let mut wip = wip;
+                if let Def::List(_) = wip.innermost_shape().def {
+                    wip = wip.put_default().map_err(|e| self.reflect_err(e))?;
+                }
+                for &byte in bytes.iter() {
+                    wip = wip
+                        .push()
+                        .and_then(|wip| wip.put(byte))
+                        .and_then(|wip| wip.pop())
+                        .map_err(|e| self.reflect_err(e))?;
+                }
+                Ok(wip)
+            }
         }
     }
 
@@ -718,7 +1328,7 @@ where
         );
 
         match outcome.node {
-            Outcome::Scalar(Scalar::Null) => {
+            Outcome::Scalar(Scalar::Null) if !Self::is_untagged_enum(&wip) => {
                 return wip.put_default().map_err(|e| self.reflect_err(e));
             }
             _ => {
@@ -731,6 +1341,15 @@ where
             }
         }
 
+        if Self::is_untagged_enum(&wip) {
+            // Rewind, so that each variant can be tried against the whole value
+            trace!("Value for untagged enum {}", wip.shape().blue());
+            self.last_span = Span::new(outcome.span.start(), 0);
+            self.reads -= 1;
+            self.stack.push(Instruction::UntaggedValue);
+            return Ok(wip);
+        }
+
         match outcome.node {
             Outcome::Scalar(s) => {
                 wip = self.handle_scalar(wip, s)?;
@@ -750,6 +1369,9 @@ where
                         trace!("Array starting for list ({})!", shape.blue());
                         wip = wip.put_default().map_err(|e| self.reflect_err(e))?;
                     }
+                    Def::Set(_) => {
+                        trace!("Array starting for set ({})!", shape.blue());
+                    }
                     Def::Scalar(sd) => {
                         if matches!(sd.affinity, ScalarAffinity::Empty(_)) {
                             trace!("Empty tuple/scalar, nice");
@@ -797,15 +1419,23 @@ where
                         }
                     }
                 }
-                trace!("Beginning pushback");
                 self.stack.push(Instruction::ListItemOrListClose);
-                wip = wip.begin_pushback().map_err(|e| self.reflect_err(e))?;
+                if let Def::Set(_) = shape.def {
+                    trace!("Beginning set insertion");
+                    wip = wip.begin_set_insert().map_err(|e| self.reflect_err(e))?;
+                } else {
+                    trace!("Beginning pushback");
+                    wip = wip.begin_pushback().map_err(|e| self.reflect_err(e))?;
+                }
             }
             Outcome::ListEnded => {
                 trace!("List closing");
                 wip = wip.pop().map_err(|e| self.reflect_err(e))?;
             }
             Outcome::ObjectStarted => {
+                if let Some(recovery) = &mut self.recovery {
+                    recovery.objects.push(outcome.span.start());
+                }
                 let shape = wip.innermost_shape();
                 match shape.def {
                     Def::Map(_md) => {
@@ -818,7 +1448,14 @@ where
                             match user_ty {
                                 UserType::Enum(_) => {
                                     trace!("Object starting for enum value ({})!", shape.blue());
-                                    // nothing to do here
+                                    if wip.selected_variant().is_none()
+                                        && shape.enum_tagging() != EnumTagging::External
+                                    {
+                                        // The tag might be anywhere in the object, so we look for it first
+                                        self.stack.push(Instruction::ObjectKeyOrObjectClose);
+                                        self.stack.push(Instruction::SelectTaggedVariant);
+                                        return Ok(wip);
+                                    }
                                 }
                                 UserType::Struct(_) => {
                                     trace!("Object starting for struct value ({})!", shape.blue());
@@ -870,16 +1507,44 @@ where
                 trace!("Parsed object key: {}", key.cyan());
 
                 let mut ignore = false;
+                // Where the value goes, for values deserialized on their own
+                let mut entry = None;
                 let mut needs_pop = true;
                 let mut handled_by_flatten = false;
 
                 let shape = wip.innermost_shape();
+                let is_content_key = self.content_keys.contains(&outcome.span.start());
                 match shape.ty {
+                    _ if self.tag_keys.contains(&outcome.span.start()) => {
+                        trace!("It's the tag of an enum, which we already handled");
+                        ignore = true;
+                    }
+                    Type::User(UserType::Enum(_)) if is_content_key => {
+                        let variant = wip.selected_variant().unwrap();
+                        trace!(
+                            "Content of variant {}::{}",
+                            wip.shape().blue(),
+                            variant.name.yellow(),
+                        );
+                        if variant.data.fields.is_empty() {
+                            ignore = true;
+                        } else if matches!(variant.data.kind, StructKind::Tuple)
+                            && variant.data.fields.len() == 1
+                        {
+                            wip = wip.field(0).map_err(|e| self.reflect_err(e))?;
+                            self.stack.push(Instruction::Pop(PopReason::ObjectVal));
+                        }
+                        needs_pop = false;
+                    }
                     Type::User(UserType::Struct(sd)) => {
                         // First try to find a direct field match
-                        if let Some(index) = wip.field_index(&key) {
+                        if let Some(index) = wip.deserializable_field_index(&key) {
                             trace!("It's a struct field");
                             wip = wip.field(index).map_err(|e| self.reflect_err(e))?;
+                            entry = Some(Slot::Field {
+                                depth: wip.frames_count() - 1,
+                                index,
+                            });
                         } else {
                             // Check for flattened fields
                             let mut found_in_flatten = false;
@@ -890,11 +1555,17 @@ where
                                     wip = wip.field(index).map_err(|e| self.reflect_err(e))?;
 
                                     // Check if this flattened field has the requested key
-                                    if let Some(subfield_index) = wip.field_index(&key) {
+                                    if let Some(subfield_index) =
+                                        wip.deserializable_field_index(&key)
+                                    {
                                         trace!("Found key {} in flattened field", key);
                                         wip = wip
                                             .field(subfield_index)
                                             .map_err(|e| self.reflect_err(e))?;
+                                        entry = Some(Slot::Field {
+                                            depth: wip.frames_count() - 1,
+                                            index: subfield_index,
+                                        });
                                         found_in_flatten = true;
                                         handled_by_flatten = true;
                                         break;
@@ -919,10 +1590,8 @@ where
                                     trace!(
                                         "It's not a struct field AND we're denying unknown fields"
                                     );
-                                    return Err(self.err(DeserErrorKind::UnknownField {
-                                        field_name: key.to_string(),
-                                        shape: wip.shape(),
-                                    }));
+                                    self.unknown_field(&key, wip.shape())?;
+                                    ignore = true;
                                 } else {
                                     trace!(
                                         "It's not a struct field and we're ignoring unknown fields"
@@ -933,7 +1602,7 @@ where
                         }
                     }
                     Type::User(UserType::Enum(_ed)) => match wip.find_variant(&key) {
-                        Some((index, variant)) => {
+                        Some((index, variant)) if shape.enum_tagging() == EnumTagging::External => {
                             trace!(
                                 "Selecting variant {}::{}",
                                 wip.shape().blue(),
@@ -956,7 +1625,7 @@ where
 
                             needs_pop = false;
                         }
-                        None => {
+                        _ => {
                             if let Some(_variant_index) = wip.selected_variant() {
                                 trace!(
                                     "Already have a variant selected, treating {} as struct field of {}::{}",
@@ -965,15 +1634,17 @@ where
                                     wip.selected_variant().unwrap().name.yellow(),
                                 );
                                 // Try to find the field index of the key within the selected variant
-                                if let Some(index) = wip.field_index(&key) {
+                                if let Some(index) = wip.deserializable_field_index(&key) {
                                     trace!("Found field {} in selected variant", key.blue());
                                     wip = wip.field(index).map_err(|e| self.reflect_err(e))?;
+                                    entry = Some(Slot::Field {
+                                        depth: wip.frames_count() - 1,
+                                        index,
+                                    });
                                 } else if wip.shape().has_deny_unknown_fields_attr() {
                                     trace!("Unknown field in variant and denying unknown fields");
-                                    return Err(self.err(DeserErrorKind::UnknownField {
-                                        field_name: key.to_string(),
-                                        shape: wip.shape(),
-                                    }));
+                                    self.unknown_field(&key, wip.shape())?;
+                                    ignore = true;
                                 } else {
                                     trace!(
                                         "Ignoring unknown field '{}' in variant '{}::{}'",
@@ -993,10 +1664,19 @@ where
                     },
                     _ => {
                         // Check if it's a map
-                        if let Def::Map(_) = shape.def {
-                            wip = wip.push_map_key().map_err(|e| self.reflect_err(e))?;
-                            wip = wip.put(key.to_string()).map_err(|e| self.reflect_err(e))?;
-                            wip = wip.push_map_value().map_err(|e| self.reflect_err(e))?;
+                        if let Def::Map(md) = shape.def {
+                            if self.recovery.as_ref().is_some_and(|r| r.can_enter()) {
+                                // The key and value frames are pushed once the value is there,
+                                // see `entry_value`
+                                entry = Some(Slot::MapValue {
+                                    key: key.clone(),
+                                    shape: md.v(),
+                                });
+                            } else {
+                                wip = wip.push_map_key().map_err(|e| self.reflect_err(e))?;
+                                wip = wip.put(key.to_string()).map_err(|e| self.reflect_err(e))?;
+                                wip = wip.push_map_value().map_err(|e| self.reflect_err(e))?;
+                            }
                         } else {
                             return Err(self.err(DeserErrorKind::Unimplemented(
                                 "object key for non-struct/map",
@@ -1019,12 +1699,22 @@ where
                         self.stack.push(Instruction::Pop(PopReason::ObjectVal));
                         self.stack.push(Instruction::Pop(PopReason::ObjectVal));
                     }
+                    match (entry, &mut self.recovery) {
+                        (Some(slot), Some(recovery)) if recovery.can_enter() => {
+                            recovery.entry = Some(Entry { first: None, slot });
+                        }
+                        _ => {}
+                    }
                     self.stack.push(Instruction::Value(ValueReason::ObjectVal));
                 }
                 Ok(wip)
             }
             Outcome::ObjectEnded => {
                 trace!("Object closing");
+                if let Some(recovery) = &mut self.recovery {
+                    let start = recovery.objects.pop().unwrap_or(outcome.span.start());
+                    recovery.closed_object = Some(Span::new(start, outcome.span.end() - start));
+                }
                 Ok(wip)
             }
             _ => Err(self.err(DeserErrorKind::UnexpectedOutcome {
@@ -1051,6 +1741,18 @@ where
                 self.stack.push(Instruction::ListItemOrListClose);
                 self.stack.push(Instruction::Pop(PopReason::ListVal));
 
+                if let Some(recovery) = &mut self.recovery {
+                    if let (true, Ok(shape)) = (recovery.can_enter(), wip.element_shape()) {
+                        // The item is deserialized on its own, and pushed once it's there,
+                        // see `entry_value`
+                        recovery.entry = Some(Entry {
+                            first: Some(outcome),
+                            slot: Slot::Item(shape),
+                        });
+                        return Ok(wip);
+                    }
+                }
+
                 trace!(
                     "Expecting list item, doing a little push before doing value with outcome {}",
                     outcome.magenta()
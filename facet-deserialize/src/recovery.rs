@@ -0,0 +1,104 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+use facet_core::Shape;
+
+use crate::{DeserError, Outcome, Span, Spanned};
+
+/// How many entries deep values get a runner of their own. Errors in entries nested deeper
+/// skip the entry at this depth that they're in, rather than taking a stack frame per level.
+const MAX_DEPTH: usize = 64;
+
+/// What the [`StackRunner`](crate::StackRunner) keeps track of when it collects errors instead
+/// of stopping at the first one.
+///
+/// A runner can't get its [`Wip`](facet_reflect::Wip) back once an operation on it failed,
+/// so the values of object entries and list items are deserialized by a runner and a `Wip`
+/// of their own. When one fails, its value is skipped, and the runner it's in carries on
+/// with the next entry.
+#[doc(hidden)]
+pub struct Recovery<'input, 'shape> {
+    /// Errors that didn't stop the current runner, like unknown or missing fields.
+    pub errors: Vec<DeserError<'input, 'shape>>,
+
+    /// How many entries the value of the current runner is nested in.
+    pub depth: usize,
+
+    /// The entry whose value is deserialized next, by a runner of its own.
+    pub entry: Option<Entry<'input, 'shape>>,
+
+    /// Struct fields whose entry was skipped, which shouldn't be reported as missing:
+    /// the wip depth of their struct, and their index.
+    pub skipped_fields: Vec<(usize, usize)>,
+
+    /// Start offsets of the objects being deserialized.
+    pub objects: Vec<usize>,
+
+    /// Span of the object that was just closed, until the next instruction runs.
+    pub closed_object: Option<Span>,
+
+    /// Span of the object whose value is being popped, if the pop closes it.
+    pub closing_object: Option<Span>,
+
+    /// Whether the error that stopped the runner came from the format itself, in which case
+    /// there's no telling where the next entry starts.
+    pub format_failed: bool,
+
+    /// Whether the error that stopped the runner came from building its value.
+    pub building: bool,
+}
+
+/// An object entry or a list item, whose value gets a runner of its own.
+#[doc(hidden)]
+pub struct Entry<'input, 'shape> {
+    /// The first outcome of the value, for list items, which is read to know there's one.
+    pub first: Option<Spanned<Outcome<'input>>>,
+
+    /// Where the value goes once it's deserialized.
+    pub slot: Slot<'input, 'shape>,
+}
+
+/// Where the value of an [`Entry`] goes. Items and map values only get a frame once their
+/// value is there, since a frame that's dropped empty isn't freed.
+#[doc(hidden)]
+pub enum Slot<'input, 'shape> {
+    /// The field `index` of the struct at wip depth `depth`, whose frame the wip is at.
+    Field { depth: usize, index: usize },
+
+    /// A new item of the list or set the wip is at.
+    Item(&'shape Shape<'shape>),
+
+    /// The value for `key` in the map the wip is at.
+    MapValue {
+        key: Cow<'input, str>,
+        shape: &'shape Shape<'shape>,
+    },
+}
+
+impl<'input, 'shape> Recovery<'input, 'shape> {
+    /// Starts collecting errors for a value nested in `depth` entries.
+    pub fn new(depth: usize) -> Self {
+        Self {
+            errors: Vec::new(),
+            depth,
+            entry: None,
+            skipped_fields: Vec::new(),
+            objects: Vec::new(),
+            closed_object: None,
+            closing_object: None,
+            format_failed: false,
+            building: false,
+        }
+    }
+
+    /// Whether the value deserialized next can be an entry of its own, which it can't once
+    /// entries are nested too deep.
+    pub fn can_enter(&self) -> bool {
+        self.depth < MAX_DEPTH
+    }
+
+    /// Whether the field `index` of the struct at `depth` had its entry skipped.
+    pub fn is_skipped_field(&self, depth: usize, index: usize) -> bool {
+        self.skipped_fields.contains(&(depth, index))
+    }
+}
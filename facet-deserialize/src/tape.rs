@@ -0,0 +1,151 @@
+use alloc::{borrow::Cow, string::String, vec, vec::Vec};
+
+use crate::{
+    DeserErrorKind, Expectation, Format, NextData, NextResult, Outcome, Scalar, Span, Spannable,
+    Spanned,
+};
+
+/// A format made of outcomes worked out ahead of time, handed out in order.
+///
+/// Formats that can only tell what their input stands for once they've read all of it, or
+/// that lay it out following the shape of the value, fill a tape and deserialize from it.
+/// When the deserializer goes back, to look for the tag of an enum or to try the variants of
+/// an untagged one, the tape picks up from the outcome it read at that point.
+///
+/// Strings and bytes written as is in the input can be put on the tape by where they are, so
+/// that they're borrowed from it.
+pub struct Tape {
+    source: &'static str,
+    items: Vec<Spanned<Item>>,
+    /// Where the input ends
+    end: usize,
+    /// Where on the tape each read started, by how many were read before it
+    positions: Vec<usize>,
+}
+
+enum Item {
+    Outcome(Outcome<'static>),
+    /// A string that's in the input as is, at this span
+    Str(Span),
+    /// Bytes that are in the input as is, at this span
+    Bytes(Span),
+}
+
+impl Tape {
+    /// Creates an empty tape for `source`, whose input is `len` bytes long.
+    pub fn new(source: &'static str, len: usize) -> Self {
+        Self {
+            source,
+            items: Vec::new(),
+            end: len,
+            positions: vec![0],
+        }
+    }
+
+    /// Adds an outcome, for the part of the input at `span`.
+    pub fn push(&mut self, outcome: Outcome<'static>, span: Span) {
+        self.items.push(Item::Outcome(outcome).with_span(span));
+    }
+
+    /// Adds the string at `text` in the input, for the part of the input at `span`.
+    pub fn push_str(&mut self, text: Span, span: Span) {
+        self.items.push(Item::Str(text).with_span(span));
+    }
+
+    /// Adds the bytes at `bytes` in the input, for the part of the input at `span`.
+    pub fn push_bytes(&mut self, bytes: Span, span: Span) {
+        self.items.push(Item::Bytes(bytes).with_span(span));
+    }
+
+    /// Returns how many outcomes are on the tape.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns whether the tape has no outcomes.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Where on the tape to read from, after `reads` reads. Those after it won't be come back
+    /// to.
+    fn resume(&mut self, reads: usize) -> usize {
+        self.positions.truncate(reads + 1);
+        self.positions[reads]
+    }
+
+    fn eof<'shape>(&self) -> Spanned<DeserErrorKind<'shape>> {
+        DeserErrorKind::UnexpectedEof {
+            wanted: "any value (got EOF)",
+        }
+        .with_span(Span::new(self.end, 0))
+    }
+}
+
+impl Format for Tape {
+    type Input<'input> = [u8];
+
+    fn source(&self) -> &'static str {
+        self.source
+    }
+
+    fn next<'input, 'facet, 'shape>(
+        &mut self,
+        nd: NextData<'input, 'facet, 'shape>,
+        _expectation: Expectation,
+    ) -> NextResult<'input, 'facet, 'shape, Spanned<Outcome<'input>>, Spanned<DeserErrorKind<'shape>>>
+    where
+        'shape: 'input,
+    {
+        let position = self.resume(nd.reads());
+        let Some(item) = self.items.get(position) else {
+            return (nd, Err(self.eof()));
+        };
+        let input = nd.input();
+        let outcome = match &item.node {
+            Item::Outcome(outcome) => outcome.clone(),
+            Item::Str(text) => Outcome::Scalar(Scalar::String(String::from_utf8_lossy(
+                &input[text.start()..text.end()],
+            ))),
+            Item::Bytes(bytes) => Outcome::Scalar(Scalar::Bytes(Cow::Borrowed(
+                &input[bytes.start()..bytes.end()],
+            ))),
+        };
+        let span = item.span;
+        self.positions.push(position + 1);
+        (nd, Ok(outcome.with_span(span)))
+    }
+
+    fn skip<'input, 'facet, 'shape>(
+        &mut self,
+        nd: NextData<'input, 'facet, 'shape>,
+    ) -> NextResult<'input, 'facet, 'shape, Span, Spanned<DeserErrorKind<'shape>>>
+    where
+        'shape: 'input,
+    {
+        let mut position = self.resume(nd.reads());
+        let Some(first) = self.items.get(position) else {
+            return (nd, Err(self.eof()));
+        };
+        let start = first.span.start();
+        let mut depth = 0usize;
+        loop {
+            let Some(item) = self.items.get(position) else {
+                return (nd, Err(self.eof()));
+            };
+            position += 1;
+            match item.node {
+                Item::Outcome(Outcome::ObjectStarted | Outcome::ListStarted) => depth += 1,
+                Item::Outcome(Outcome::ObjectEnded | Outcome::ListEnded) => {
+                    depth = depth.saturating_sub(1)
+                }
+                _ => {}
+            }
+            if depth == 0 {
+                let span = Span::new(start, item.span.end().saturating_sub(start));
+                self.positions.push(position);
+                return (nd, Ok(span));
+            }
+        }
+    }
+}
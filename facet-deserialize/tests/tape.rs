@@ -0,0 +1,114 @@
+use facet::Facet;
+use facet_deserialize::{DeserError, Outcome, Scalar, Span, Tape, deserialize};
+use facet_testhelpers::test;
+
+/// Lays out words separated by spaces on a tape: braces and brackets start and end objects
+/// and lists, numbers are numbers, words starting with `b:` are the bytes after it, and
+/// other words are strings.
+fn from_words<'input, 'facet, 'shape, T: Facet<'facet>>(
+    input: &'input str,
+) -> Result<T, DeserError<'input, 'shape>>
+where
+    'input: 'facet,
+{
+    let mut tape = Tape::new("words", input.len());
+    let mut start = 0;
+    for word in input.split(' ') {
+        let span = Span::new(start, word.len());
+        start += word.len() + 1;
+        match word {
+            "{" => tape.push(Outcome::ObjectStarted, span),
+            "}" => tape.push(Outcome::ObjectEnded, span),
+            "[" => tape.push(Outcome::ListStarted, span),
+            "]" => tape.push(Outcome::ListEnded, span),
+            _ if word.starts_with("b:") => {
+                tape.push_bytes(Span::new(span.start() + 2, word.len() - 2), span)
+            }
+            _ => match word.parse() {
+                Ok(n) => tape.push(Outcome::Scalar(Scalar::U64(n)), span),
+                Err(_) => tape.push_str(span, span),
+            },
+        }
+    }
+    deserialize(input.as_bytes(), tape)
+}
+
+#[test]
+fn test_borrowed_strings_and_skips() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Crate<'a> {
+        name: &'a str,
+        version: u64,
+    }
+
+    let krate: Crate = from_words("{ name facet unknown { a [ 1 2 ] } version 3 }")?;
+    assert_eq!(
+        krate,
+        Crate {
+            name: "facet",
+            version: 3
+        }
+    );
+}
+
+#[test]
+fn test_rewinds() {
+    #[derive(Facet, Debug, PartialEq)]
+    #[facet(tag = "type")]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Shape {
+        Circle { radius: u64 },
+        Square { side: u64 },
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[facet(untagged)]
+    #[repr(u8)]
+    enum Value {
+        Pair(Vec<u64>),
+        Number(u64),
+        Text(String),
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Drawing {
+        shape: Shape,
+        values: Vec<Value>,
+    }
+
+    // The tag comes last, and each value is tried against the variants in turn
+    let drawing: Drawing =
+        from_words("{ shape { side 2 type Square } values [ [ 1 2 ] 3 four ] }")?;
+    assert_eq!(
+        drawing,
+        Drawing {
+            shape: Shape::Square { side: 2 },
+            values: vec![
+                Value::Pair(vec![1, 2]),
+                Value::Number(3),
+                Value::Text("four".to_string()),
+            ],
+        }
+    );
+}
+
+#[test]
+fn test_bytes() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Blobs {
+        vec: Vec<u8>,
+        array: [u8; 2],
+        option: Option<Vec<u8>>,
+    }
+
+    let blobs: Blobs = from_words("{ vec b:abc array b:xy option b:z }")?;
+    assert_eq!(
+        blobs,
+        Blobs {
+            vec: b"abc".to_vec(),
+            array: *b"xy",
+            option: Some(b"z".to_vec()),
+        }
+    );
+}
@@ -1,13 +1,13 @@
 use alloc::{borrow::Cow, format};
 
 use facet_core::Facet;
-pub use facet_deserialize::{DeserError, DeserErrorKind};
+pub use facet_deserialize::{DeserError, DeserErrorKind, DeserErrors};
 use facet_deserialize::{
     Expectation, Format, NextData, NextResult, Outcome, Scalar, Span, Spannable, Spanned,
 };
 use log::trace;
 
-use crate::tokenizer::{Token, TokenError, TokenErrorKind, Tokenizer};
+use crate::tokenizer::{Token, TokenError, TokenErrorKind, TokenizeResult, Tokenizer};
 
 pub(crate) fn from_slice<'input, 'facet, 'shape, T: Facet<'facet>>(
     input: &'input [u8],
@@ -18,6 +18,15 @@ where
     facet_deserialize::deserialize(input, crate::Json)
 }
 
+pub(crate) fn from_slice_collecting<'input, 'facet, 'shape, T: Facet<'facet>>(
+    input: &'input [u8],
+) -> Result<T, DeserErrors<'input, 'shape>>
+where
+    'input: 'facet,
+{
+    facet_deserialize::deserialize_collecting(input, crate::Json)
+}
+
 impl Format for crate::Json {
     type Input<'input> = [u8];
 
@@ -28,133 +37,15 @@ impl Format for crate::Json {
     fn next<'input, 'facet, 'shape>(
         &mut self,
         nd: NextData<'input, 'facet, 'shape>,
-        mut expectation: Expectation,
+        expectation: Expectation,
     ) -> NextResult<'input, 'facet, 'shape, Spanned<Outcome<'input>>, Spanned<DeserErrorKind<'shape>>>
     where
         'shape: 'input,
     {
         let input = &nd.input()[nd.start()..];
         let mut tokenizer = Tokenizer::new(input);
-
-        loop {
-            let token = match tokenizer.next_token() {
-                Ok(token) => token,
-                Err(err) => {
-                    trace!("Tokenizer error in next: {:?}", err.kind);
-                    return (nd, Err(convert_token_error(err)));
-                }
-            };
-
-            // Adjust token span to be relative to the beginning of the overall input
-            let token_offset = nd.start();
-            let span = Span::new(token.span.start() + token_offset, token.span.len());
-
-            let res = match token.node {
-                Token::String(s) => Ok(Spanned {
-                    node: Outcome::Scalar(Scalar::String(Cow::Owned(s))),
-                    span,
-                }),
-                Token::F64(n) => Ok(Spanned {
-                    node: Outcome::Scalar(Scalar::F64(n)),
-                    span,
-                }),
-                Token::I64(n) => Ok(Spanned {
-                    node: Outcome::Scalar(Scalar::I64(n)),
-                    span,
-                }),
-                Token::U64(n) => Ok(Spanned {
-                    node: Outcome::Scalar(Scalar::U64(n)),
-                    span,
-                }),
-                Token::True => Ok(Spanned {
-                    node: Outcome::Scalar(Scalar::Bool(true)),
-                    span,
-                }),
-                Token::False => Ok(Spanned {
-                    node: Outcome::Scalar(Scalar::Bool(false)),
-                    span,
-                }),
-                Token::Null => Ok(Spanned {
-                    node: Outcome::Scalar(Scalar::Null),
-                    span,
-                }),
-                Token::LBrace => Ok(Spanned {
-                    node: Outcome::ObjectStarted,
-                    span,
-                }),
-                Token::RBrace => {
-                    if expectation == Expectation::ObjectKeyOrObjectClose {
-                        Ok(Spanned {
-                            node: Outcome::ObjectEnded,
-                            span,
-                        })
-                    } else {
-                        trace!("Did not expect closing brace, expected {:?}", expectation);
-                        Err(DeserErrorKind::UnexpectedChar {
-                            got: '}',
-                            wanted: "a value",
-                        }
-                        .with_span(span))
-                    }
-                }
-                Token::LBracket => Ok(Spanned {
-                    node: Outcome::ListStarted,
-                    span,
-                }),
-                Token::RBracket => {
-                    if expectation == Expectation::ListItemOrListClose {
-                        Ok(Spanned {
-                            node: Outcome::ListEnded,
-                            span,
-                        })
-                    } else {
-                        Err(DeserErrorKind::UnexpectedChar {
-                            got: ']',
-                            wanted: "a value",
-                        }
-                        .with_span(span))
-                    }
-                }
-                Token::Colon => {
-                    if expectation == Expectation::ObjectVal {
-                        expectation = Expectation::Value;
-                        continue;
-                    } else {
-                        trace!("Did not expect ObjectValue, expected {:?}", expectation);
-                        Err(DeserErrorKind::UnexpectedChar {
-                            got: ':',
-                            wanted: "a value, not a colon",
-                        }
-                        .with_span(span))
-                    }
-                }
-                Token::Comma => match expectation {
-                    Expectation::ListItemOrListClose | Expectation::ObjectKeyOrObjectClose => {
-                        expectation = Expectation::Value;
-                        continue;
-                    }
-                    other => {
-                        trace!("Did not expect comma, expected {:?}", other);
-                        Err(DeserErrorKind::UnexpectedChar {
-                            got: ',',
-                            wanted: "<value or key>",
-                        }
-                        .with_span(span))
-                    }
-                },
-                Token::Eof => {
-                    return (
-                        nd,
-                        Err(DeserErrorKind::UnexpectedEof {
-                            wanted: "any value (got EOF)",
-                        }
-                        .with_span(span)),
-                    );
-                }
-            };
-
-            return (nd, res);
-        }
+        let res = next_outcome(|| tokenizer.next_token(), nd.start(), expectation);
+        (nd, res)
     }
 
     fn skip<'input, 'facet, 'shape>(
@@ -167,88 +58,218 @@ impl Format for crate::Json {
         trace!("Starting skip at offset {}", nd.start());
         let input = &nd.input()[nd.start()..];
         let mut tokenizer = Tokenizer::new(input);
+        let res = skip_value(|| tokenizer.next_token(), nd.start());
+        (nd, res)
+    }
+}
 
-        loop {
-            let token = match tokenizer.next_token() {
-                Ok(token) => token,
-                Err(err) => {
-                    trace!("Tokenizer error on initial token: {:?}", err.kind);
-                    return (nd, Err(convert_token_error(err)));
-                }
-            };
+/// Reads the outcome the deserializer expects from JSON tokens whose spans are relative
+/// to `offset`.
+pub(crate) fn next_outcome<'input, 'shape>(
+    mut next_token: impl FnMut() -> TokenizeResult,
+    offset: usize,
+    mut expectation: Expectation,
+) -> Result<Spanned<Outcome<'input>>, Spanned<DeserErrorKind<'shape>>> {
+    loop {
+        let token = match next_token() {
+            Ok(token) => token,
+            Err(err) => {
+                trace!("Tokenizer error in next: {:?}", err.kind);
+                return Err(convert_token_error(err, offset));
+            }
+        };
 
-            let res = match token.node {
-                Token::LBrace | Token::LBracket => {
-                    let mut depth = 1;
-                    let mut last_span = token.span;
-                    while depth > 0 {
-                        let token = match tokenizer.next_token() {
-                            Ok(token) => token,
-                            Err(err) => {
-                                trace!("Tokenizer error while skipping container: {:?}", err.kind);
-                                return (nd, Err(convert_token_error(err)));
-                            }
-                        };
+        // Adjust token span to be relative to the beginning of the overall input
+        let span = Span::new(token.span.start() + offset, token.span.len());
 
-                        match token.node {
-                            Token::LBrace | Token::LBracket => {
-                                depth += 1;
-                                last_span = token.span;
-                            }
-                            Token::RBrace | Token::RBracket => {
-                                depth -= 1;
-                                last_span = token.span;
-                            }
-                            _ => {
-                                last_span = token.span;
-                            }
-                        }
+        return match token.node {
+            Token::String(s) => Ok(Spanned {
+                node: Outcome::Scalar(Scalar::String(Cow::Owned(s))),
+                span,
+            }),
+            Token::F64(n) => Ok(Spanned {
+                node: Outcome::Scalar(Scalar::F64(n)),
+                span,
+            }),
+            Token::I64(n) => Ok(Spanned {
+                node: Outcome::Scalar(Scalar::I64(n)),
+                span,
+            }),
+            Token::U64(n) => Ok(Spanned {
+                node: Outcome::Scalar(Scalar::U64(n)),
+                span,
+            }),
+            Token::True => Ok(Spanned {
+                node: Outcome::Scalar(Scalar::Bool(true)),
+                span,
+            }),
+            Token::False => Ok(Spanned {
+                node: Outcome::Scalar(Scalar::Bool(false)),
+                span,
+            }),
+            Token::Null => Ok(Spanned {
+                node: Outcome::Scalar(Scalar::Null),
+                span,
+            }),
+            Token::LBrace => Ok(Spanned {
+                node: Outcome::ObjectStarted,
+                span,
+            }),
+            Token::RBrace => {
+                if expectation == Expectation::ObjectKeyOrObjectClose {
+                    Ok(Spanned {
+                        node: Outcome::ObjectEnded,
+                        span,
+                    })
+                } else {
+                    trace!("Did not expect closing brace, expected {:?}", expectation);
+                    Err(DeserErrorKind::UnexpectedChar {
+                        got: '}',
+                        wanted: "a value",
                     }
-                    (nd, Ok(last_span))
+                    .with_span(span))
                 }
-                Token::String(_)
-                | Token::F64(_)
-                | Token::I64(_)
-                | Token::U64(_)
-                | Token::True
-                | Token::False
-                | Token::Null => (nd, Ok(token.span)),
-                Token::Colon => {
-                    // Skip colon token
+            }
+            Token::LBracket => Ok(Spanned {
+                node: Outcome::ListStarted,
+                span,
+            }),
+            Token::RBracket => {
+                if expectation == Expectation::ListItemOrListClose {
+                    Ok(Spanned {
+                        node: Outcome::ListEnded,
+                        span,
+                    })
+                } else {
+                    Err(DeserErrorKind::UnexpectedChar {
+                        got: ']',
+                        wanted: "a value",
+                    }
+                    .with_span(span))
+                }
+            }
+            Token::Colon => {
+                if expectation == Expectation::ObjectVal {
+                    expectation = Expectation::Value;
+                    continue;
+                } else {
+                    trace!("Did not expect ObjectValue, expected {:?}", expectation);
+                    Err(DeserErrorKind::UnexpectedChar {
+                        got: ':',
+                        wanted: "a value, not a colon",
+                    }
+                    .with_span(span))
+                }
+            }
+            Token::Comma => match expectation {
+                Expectation::ListItemOrListClose | Expectation::ObjectKeyOrObjectClose => {
+                    expectation = Expectation::Value;
                     continue;
                 }
-                other => (
-                    nd,
+                other => {
+                    trace!("Did not expect comma, expected {:?}", other);
                     Err(DeserErrorKind::UnexpectedChar {
-                        got: format!("{:?}", other).chars().next().unwrap_or('?'),
-                        wanted: "value",
+                        got: ',',
+                        wanted: "<value or key>",
                     }
-                    .with_span(Span::new(token.span.start(), token.span.len()))),
-                ),
-            };
-            let (nd, mut span) = res;
-            if let Ok(valid_span) = &mut span {
-                let offset = nd.start();
-                valid_span.start += offset;
+                    .with_span(span))
+                }
+            },
+            Token::Eof => Err(DeserErrorKind::UnexpectedEof {
+                wanted: "any value (got EOF)",
             }
-            return (nd, span);
-        }
+            .with_span(span)),
+        };
+    }
+}
+
+/// Skips the next JSON value, given tokens whose spans are relative to `offset`, and
+/// returns the span of its last token.
+pub(crate) fn skip_value<'shape>(
+    mut next_token: impl FnMut() -> TokenizeResult,
+    offset: usize,
+) -> Result<Span, Spanned<DeserErrorKind<'shape>>> {
+    loop {
+        let token = match next_token() {
+            Ok(token) => token,
+            Err(err) => {
+                trace!("Tokenizer error on initial token: {:?}", err.kind);
+                return Err(convert_token_error(err, offset));
+            }
+        };
+
+        let span = match token.node {
+            Token::LBrace | Token::LBracket => {
+                let mut depth = 1;
+                let mut last_span = token.span;
+                while depth > 0 {
+                    let token = match next_token() {
+                        Ok(token) => token,
+                        Err(err) => {
+                            trace!("Tokenizer error while skipping container: {:?}", err.kind);
+                            return Err(convert_token_error(err, offset));
+                        }
+                    };
+
+                    match token.node {
+                        Token::LBrace | Token::LBracket => {
+                            depth += 1;
+                            last_span = token.span;
+                        }
+                        Token::RBrace | Token::RBracket => {
+                            depth -= 1;
+                            last_span = token.span;
+                        }
+                        Token::Eof => {
+                            return Err(DeserErrorKind::UnexpectedEof {
+                                wanted: "the end of the value being skipped",
+                            }
+                            .with_span(Span::new(token.span.start() + offset, 0)));
+                        }
+                        _ => {
+                            last_span = token.span;
+                        }
+                    }
+                }
+                last_span
+            }
+            Token::String(_)
+            | Token::F64(_)
+            | Token::I64(_)
+            | Token::U64(_)
+            | Token::True
+            | Token::False
+            | Token::Null => token.span,
+            Token::Colon => {
+                // Skip colon token
+                continue;
+            }
+            other => {
+                return Err(DeserErrorKind::UnexpectedChar {
+                    got: format!("{:?}", other).chars().next().unwrap_or('?'),
+                    wanted: "value",
+                }
+                .with_span(Span::new(token.span.start() + offset, token.span.len())));
+            }
+        };
+        return Ok(Span::new(span.start() + offset, span.len()));
     }
 }
 
-fn convert_token_error(err: TokenError) -> Spanned<DeserErrorKind<'static>> {
+fn convert_token_error(err: TokenError, offset: usize) -> Spanned<DeserErrorKind<'static>> {
+    let span = Span::new(err.span.start() + offset, err.span.len());
     match err.kind {
         TokenErrorKind::UnexpectedCharacter(c) => DeserErrorKind::UnexpectedChar {
             got: c,
             wanted: "valid JSON character",
         }
-        .with_span(err.span),
+        .with_span(span),
         TokenErrorKind::UnexpectedEof(why) => {
-            DeserErrorKind::UnexpectedEof { wanted: why }.with_span(err.span)
+            DeserErrorKind::UnexpectedEof { wanted: why }.with_span(span)
         }
-        TokenErrorKind::InvalidUtf8(s) => DeserErrorKind::InvalidUtf8(s).with_span(err.span),
+        TokenErrorKind::InvalidUtf8(s) => DeserErrorKind::InvalidUtf8(s).with_span(span),
         TokenErrorKind::NumberOutOfRange(number) => {
-            DeserErrorKind::NumberOutOfRange(number).with_span(err.span)
+            DeserErrorKind::NumberOutOfRange(number).with_span(span)
         }
     }
 }
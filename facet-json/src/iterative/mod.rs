@@ -5,3 +5,6 @@ pub use serialize::*;
 
 mod deserialize;
 pub use deserialize::*;
+
+mod reader;
+pub use reader::*;
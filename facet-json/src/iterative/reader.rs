@@ -0,0 +1,308 @@
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use std::io::{self, Read};
+
+use facet_core::Facet;
+use facet_deserialize::{
+    DeserError, DeserErrorKind, Expectation, Format, NextData, NextResult, Outcome, Span,
+    Spannable, Spanned,
+};
+use log::trace;
+
+use super::{next_outcome, skip_value};
+use crate::tokenizer::{Token, TokenError, TokenErrorKind, TokenizeResult, Tokenizer};
+
+/// How many bytes to read at once, at least.
+const CHUNK_SIZE: usize = 8 * 1024;
+
+pub(crate) fn from_reader<'facet, R: Read, T: Facet<'facet>>(
+    reader: R,
+) -> Result<T, DeserError<'static, 'static>> {
+    facet_deserialize::deserialize(&b""[..], JsonReader::new(reader))
+}
+
+/// JSON read incrementally from an [`io::Read`], only keeping in memory what the
+/// deserializer may still look at.
+///
+/// Offsets passed to and from the deserializer are relative to `base`, so that each
+/// value of a stream can be deserialized on its own.
+struct JsonReader<R> {
+    reader: R,
+
+    /// Bytes read so far, starting at `buf_offset` in the whole input
+    buf: Vec<u8>,
+
+    /// Offset of the first byte of `buf` in the whole input
+    buf_offset: usize,
+
+    /// Whether the reader has no more bytes to give
+    eof: bool,
+
+    /// Offset in the whole input of the value being deserialized
+    base: usize,
+
+    /// Whether to keep all of the value being deserialized in memory, so that the next
+    /// line can be found if it fails
+    keep_value: bool,
+
+    /// Offset in the whole input before which bytes can be let go of
+    keep_from: usize,
+
+    /// Offset in the whole input where the last token read ends
+    consumed: usize,
+}
+
+impl<R: Read> JsonReader<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: Vec::new(),
+            buf_offset: 0,
+            eof: false,
+            base: 0,
+            keep_value: false,
+            keep_from: 0,
+            consumed: 0,
+        }
+    }
+
+    /// Reads more bytes, letting go of the ones before `keep_from`.
+    fn fill(&mut self) -> io::Result<()> {
+        let unneeded = (self.keep_from.saturating_sub(self.buf_offset)).min(self.buf.len());
+        self.buf.drain(..unneeded);
+        self.buf_offset += unneeded;
+
+        // Read at least as much as we have, so that tokens spanning several reads
+        // don't get tokenized over and over again.
+        let len = self.buf.len();
+        self.buf.resize(len + CHUNK_SIZE.max(len), 0);
+        let res = loop {
+            match self.reader.read(&mut self.buf[len..]) {
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                res => break res,
+            }
+        };
+        let read = *res.as_ref().unwrap_or(&0);
+        self.buf.truncate(len + read);
+        self.eof = read == 0;
+        trace!("Read {} bytes, {} buffered", read, self.buf.len());
+        res.map(|_| ())
+    }
+
+    /// Returns the offset in the whole input of the first byte at or after `from` that
+    /// isn't whitespace, if there's one.
+    fn skip_whitespace(&mut self, mut from: usize) -> io::Result<Option<usize>> {
+        loop {
+            while let Some(&b) = self.buf.get(from - self.buf_offset) {
+                if !b.is_ascii_whitespace() {
+                    return Ok(Some(from));
+                }
+                from += 1;
+            }
+            if self.eof {
+                return Ok(None);
+            }
+            self.keep_from = from;
+            self.fill()?;
+        }
+    }
+
+    /// Returns the offset in the whole input right after the first newline at or after
+    /// `from`, if there's one.
+    fn skip_line(&mut self, mut from: usize) -> io::Result<Option<usize>> {
+        loop {
+            while let Some(&b) = self.buf.get(from - self.buf_offset) {
+                from += 1;
+                if b == b'\n' {
+                    return Ok(Some(from));
+                }
+            }
+            if self.eof {
+                return Ok(None);
+            }
+            self.keep_from = from;
+            self.fill()?;
+        }
+    }
+
+    /// Runs `parse` over the tokens starting at `start`, relative to `base`, reading more
+    /// input for as long as the tokens it looks at may be cut short.
+    fn parse<T>(
+        &mut self,
+        start: usize,
+        keep_from: usize,
+        parse: impl Fn(
+            &mut dyn FnMut() -> TokenizeResult,
+        ) -> Result<T, Spanned<DeserErrorKind<'static>>>,
+    ) -> Result<T, Spanned<DeserErrorKind<'static>>> {
+        self.keep_from = if self.keep_value {
+            self.base
+        } else {
+            self.base + keep_from
+        };
+        loop {
+            let start_in_buf = self.base + start - self.buf_offset;
+            let window = self.buf.get(start_in_buf..).unwrap_or_default();
+            let mut tokenizer = Tokenizer::new(window);
+            let mut truncated = false;
+            let mut end = 0;
+            let res = parse(&mut || {
+                let res = tokenizer.next_token();
+                if !self.eof && may_be_truncated(&res, window.len()) {
+                    truncated = true;
+                    return Err(TokenError {
+                        kind: TokenErrorKind::UnexpectedEof("in buffered input"),
+                        span: Span::new(window.len(), 0),
+                    });
+                }
+                if let Ok(token) = &res {
+                    end = token.span.end();
+                }
+                res
+            });
+            if !truncated {
+                self.consumed = self.consumed.max(self.base + start + end);
+                return res;
+            }
+            if let Err(e) = self.fill() {
+                return Err(DeserErrorKind::Io(e.to_string())
+                    .with_span(Span::new(self.buf_offset + self.buf.len() - self.base, 0)));
+            }
+        }
+    }
+}
+
+/// Whether a token read at the end of what's been read so far may continue past it.
+fn may_be_truncated(res: &TokenizeResult, len: usize) -> bool {
+    match res {
+        Ok(token) => match token.node {
+            Token::Eof
+            | Token::F64(_)
+            | Token::I64(_)
+            | Token::U64(_)
+            | Token::True
+            | Token::False
+            | Token::Null => token.span.end() >= len,
+            _ => false,
+        },
+        Err(err) => matches!(err.kind, TokenErrorKind::UnexpectedEof(_)) || err.span.end() >= len,
+    }
+}
+
+impl<R: Read> Format for JsonReader<R> {
+    type Input<'input> = [u8];
+
+    fn source(&self) -> &'static str {
+        "json"
+    }
+
+    fn next<'input, 'facet, 'shape>(
+        &mut self,
+        nd: NextData<'input, 'facet, 'shape>,
+        expectation: Expectation,
+    ) -> NextResult<'input, 'facet, 'shape, Spanned<Outcome<'input>>, Spanned<DeserErrorKind<'shape>>>
+    where
+        'shape: 'input,
+    {
+        let (start, keep_from) = (nd.start(), nd.keep_from());
+        let res = self.parse(start, keep_from, |next_token| {
+            next_outcome(next_token, start, expectation)
+        });
+        (nd, res)
+    }
+
+    fn skip<'input, 'facet, 'shape>(
+        &mut self,
+        nd: NextData<'input, 'facet, 'shape>,
+    ) -> NextResult<'input, 'facet, 'shape, Span, Spanned<DeserErrorKind<'shape>>>
+    where
+        'shape: 'input,
+    {
+        trace!("Starting skip at offset {}", nd.start());
+        let (start, keep_from) = (nd.start(), nd.keep_from());
+        let res = self.parse(start, keep_from, |next_token| skip_value(next_token, start));
+        (nd, res)
+    }
+}
+
+/// An iterator over the JSON values read from an [`io::Read`], separated by whitespace,
+/// as in [JSON Lines](https://jsonlines.org/) or NDJSON.
+///
+/// A value that fails to deserialize is reported as an error, and the iterator picks
+/// up again at the next line. Spans of errors are relative to the start of the stream.
+pub struct StreamDeserializer<R, T> {
+    format: JsonReader<R>,
+    done: bool,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<R: Read, T> StreamDeserializer<R, T> {
+    /// Creates an iterator over the JSON values in `reader`.
+    pub fn new(reader: R) -> Self {
+        Self {
+            format: JsonReader {
+                keep_value: true,
+                ..JsonReader::new(reader)
+            },
+            done: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the offset right after the last value read.
+    pub fn byte_offset(&self) -> usize {
+        self.format.consumed
+    }
+
+    fn io_error(&mut self, err: io::Error) -> DeserError<'static, 'static> {
+        self.done = true;
+        let offset = self.format.buf_offset + self.format.buf.len();
+        DeserError::new(
+            DeserErrorKind::Io(err.to_string()),
+            &b""[..],
+            Span::new(offset, 0),
+            "json",
+        )
+    }
+}
+
+impl<'facet, R: Read, T: Facet<'facet>> Iterator for StreamDeserializer<R, T> {
+    type Item = Result<T, DeserError<'static, 'static>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let start = match self.format.skip_whitespace(self.format.consumed) {
+            Ok(Some(start)) => start,
+            Ok(None) => {
+                self.done = true;
+                return None;
+            }
+            Err(err) => return Some(Err(self.io_error(err))),
+        };
+        trace!("Deserializing value at offset {}", start);
+        self.format.base = start;
+        self.format.consumed = start;
+
+        let res = facet_deserialize::deserialize(&b""[..], &mut self.format);
+        self.format.base = 0;
+        match res {
+            Ok(value) => Some(Ok(value)),
+            Err(err) => {
+                if let DeserErrorKind::Io(_) = err.kind {
+                    self.done = true;
+                }
+                let span = Span::new(start + err.span.start(), err.span.len());
+                let err = err.with_span(span);
+                // Values are one per line, so the next one starts on the next line
+                match self.format.skip_line(start) {
+                    Ok(Some(next_line)) => self.format.consumed = next_line,
+                    Ok(None) => self.done = true,
+                    Err(_) => self.done = true,
+                }
+                Some(Err(err))
+            }
+        }
+    }
+}
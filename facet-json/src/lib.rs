@@ -9,7 +9,7 @@
 use std::io::{self, Write};
 
 use facet_core::Facet;
-pub use facet_deserialize::{DeserError, DeserErrorKind, DeserErrorMessage};
+pub use facet_deserialize::{DeserError, DeserErrorKind, DeserErrorMessage, DeserErrors};
 use facet_reflect::Peek;
 
 extern crate alloc;
@@ -17,6 +17,8 @@ extern crate alloc;
 #[cfg(feature = "std")]
 mod iterative;
 #[cfg(feature = "std")]
+pub use iterative::StreamDeserializer;
+#[cfg(feature = "std")]
 mod recursive;
 mod tokenizer;
 
@@ -44,6 +46,40 @@ where
     recursive::from_str(input, 0)
 }
 
+/// Deserialize JSON from a given byte slice, reporting every error in the input rather than
+/// just the first one: unknown fields, missing fields and values of the wrong type.
+#[cfg(feature = "std")]
+pub fn from_slice_collecting<'input, 'facet, 'shape, T: Facet<'facet>>(
+    input: &'input [u8],
+) -> Result<T, DeserErrors<'input, 'shape>>
+where
+    'input: 'facet,
+{
+    iterative::from_slice_collecting(input)
+}
+
+/// Deserialize JSON from a given string, reporting every error in the input rather than
+/// just the first one: unknown fields, missing fields and values of the wrong type.
+#[cfg(feature = "std")]
+pub fn from_str_collecting<'input, 'facet, 'shape, T: Facet<'facet>>(
+    input: &'input str,
+) -> Result<T, DeserErrors<'input, 'shape>>
+where
+    'input: 'facet,
+{
+    from_slice_collecting(input.as_bytes())
+}
+
+/// Deserialize JSON from a reader, reading it incrementally rather than all at once.
+///
+/// Strings are always copied out of the input, so `T` can't borrow from it.
+#[cfg(feature = "std")]
+pub fn from_reader<'facet, R: std::io::Read, T: Facet<'facet>>(
+    reader: R,
+) -> Result<T, DeserError<'static, 'static>> {
+    iterative::from_reader(reader)
+}
+
 /// Serializes a value to JSON
 #[cfg(feature = "std")]
 pub fn to_string<'input, 'facet, T: Facet<'facet>>(value: &'input T) -> String
@@ -1,5 +1,6 @@
 use std::io::{self, Write};
 
+use facet_core::EnumTagging;
 use facet_core::Facet;
 use facet_core::Field;
 use facet_core::PointerType;
@@ -10,7 +11,7 @@ use facet_core::StructKind;
 use facet_core::Type;
 use facet_core::UserType;
 use facet_reflect::HasFields;
-use facet_reflect::{Peek, ScalarType};
+use facet_reflect::{Peek, PeekEnum, ScalarType};
 use log::debug;
 
 /// Serializes a value to JSON
@@ -63,6 +64,15 @@ where
     'mem: 'facet,
 {
     use facet_core::Def::*;
+    if let Some(converted) = maybe_field.and_then(|field| peek.custom_serialization(field)) {
+        debug!(
+            "Field converts {} to {} for serialization",
+            peek.shape(),
+            converted.shape()
+        );
+        return peek_to_writer(converted.peek(), None, recursion_depth + 1, output);
+    }
+
     if recursion_depth > crate::MAX_RECURSION_DEPTH {
         return crate::iterative::peek_to_writer(peek, output);
     }
@@ -167,8 +177,21 @@ where
             }
             write!(output, "}}")
         }
-        (List(_) | Array(_) | Slice(_) | Set(_), _) => {
-            let set_peek = peek.into_list_like().unwrap();
+        (List(_) | Array(_) | Slice(_), _) => {
+            let list_peek = peek.into_list_like().unwrap();
+            write!(output, "[")?;
+            let mut first = true;
+            for value in list_peek.iter() {
+                if !first {
+                    write!(output, ",")?;
+                }
+                first = false;
+                peek_to_writer(value, None, recursion_depth + 1, output)?;
+            }
+            write!(output, "]")
+        }
+        (Set(_), _) => {
+            let set_peek = peek.into_set().unwrap();
             write!(output, "[")?;
             let mut first = true;
             for value in set_peek.iter() {
@@ -282,6 +305,7 @@ where
             }
         }
         (_, Type::User(UserType::Enum(_))) => {
+            let tagging = peek.shape().enum_tagging();
             let peek_enum = peek.into_enum().unwrap();
             let variant = peek_enum
                 .active_variant()
@@ -295,59 +319,87 @@ where
             // );
             let flattened = maybe_field.map(|f| f.flattened).unwrap_or_default();
 
-            if variant.data.fields.is_empty() {
-                // Unit variant
-                crate::write_json_string(output, variant.name)
-            } else {
-                if !flattened {
-                    // For now, treat all enum variants with data as objects
-                    write!(output, "{{")?;
-                    crate::write_json_string(output, variant.name)?;
-                    write!(output, ":")?;
-                }
+            match tagging {
+                EnumTagging::External => {
+                    if variant.data.fields.is_empty() {
+                        // Unit variant
+                        crate::write_json_string(output, variant.name)
+                    } else {
+                        if !flattened {
+                            // For now, treat all enum variants with data as objects
+                            write!(output, "{{")?;
+                            crate::write_json_string(output, variant.name)?;
+                            write!(output, ":")?;
+                        }
 
-                if crate::variant_is_newtype_like(variant) {
-                    // Newtype variant - serialize the inner value directly
-                    let (field, field_peek) = peek_enum.fields_for_serialize().next().unwrap();
-                    peek_to_writer(field_peek, Some(&field), recursion_depth + 1, output)?;
-                } else if variant.data.kind == StructKind::Tuple
-                    || variant.data.kind == StructKind::TupleStruct
-                {
-                    // Tuple variant - serialize as array
-                    write!(output, "[")?;
+                        write_variant_content(&peek_enum, recursion_depth, output)?;
 
-                    // Push fields in reverse order for tuple variant
-                    let mut first = true;
-                    for (field, field_peek) in peek_enum.fields_for_serialize() {
-                        if !first {
-                            write!(output, ",")?;
+                        if !flattened {
+                            write!(output, "}}")?;
                         }
-                        first = false;
-                        peek_to_writer(field_peek, Some(&field), recursion_depth + 1, output)?;
+                        Ok(())
                     }
-                    write!(output, "]")?;
-                } else {
-                    // Struct variant - serialize as object
+                }
+                EnumTagging::Internal { tag } => {
                     write!(output, "{{")?;
+                    crate::write_json_string(output, tag)?;
+                    write!(output, ":")?;
+                    crate::write_json_string(output, variant.name)?;
 
-                    // Push fields in reverse order for struct variant
-                    let mut first = true;
-                    for (field, field_peek) in peek_enum.fields_for_serialize() {
-                        if !first {
-                            write!(output, ",")?;
+                    let fields = if variant.data.fields.is_empty() {
+                        Vec::new()
+                    } else if crate::variant_is_newtype_like(variant) {
+                        // Newtype variant - the inner struct's fields go next to the tag
+                        let (_, field_peek) = peek_enum.fields_for_serialize().next().unwrap();
+                        let field_shape = field_peek.shape();
+                        match field_peek.innermost_peek().into_struct() {
+                            Ok(ps) if ps.ty().kind == StructKind::Struct => {
+                                ps.fields_for_serialize().collect()
+                            }
+                            _ => {
+                                return Err(io::Error::other(format!(
+                                    "internally tagged enum variant {} must contain a struct, not a {}",
+                                    variant.name, field_shape
+                                )));
+                            }
                         }
-                        first = false;
+                    } else if variant.data.kind == StructKind::Struct {
+                        peek_enum.fields_for_serialize().collect()
+                    } else {
+                        return Err(io::Error::other(format!(
+                            "internally tagged enum variant {} cannot be a tuple variant",
+                            variant.name
+                        )));
+                    };
+
+                    for (field, field_peek) in fields {
+                        write!(output, ",")?;
                         crate::write_json_string(output, field.name)?;
                         write!(output, ":")?;
                         peek_to_writer(field_peek, Some(&field), recursion_depth + 1, output)?;
                     }
-                    write!(output, "}}")?;
+                    write!(output, "}}")
                 }
-
-                if !flattened {
-                    write!(output, "}}")?;
+                EnumTagging::Adjacent { tag, content } => {
+                    write!(output, "{{")?;
+                    crate::write_json_string(output, tag)?;
+                    write!(output, ":")?;
+                    crate::write_json_string(output, variant.name)?;
+                    if !variant.data.fields.is_empty() {
+                        write!(output, ",")?;
+                        crate::write_json_string(output, content)?;
+                        write!(output, ":")?;
+                        write_variant_content(&peek_enum, recursion_depth, output)?;
+                    }
+                    write!(output, "}}")
+                }
+                EnumTagging::Untagged => {
+                    if variant.data.fields.is_empty() {
+                        write!(output, "null")
+                    } else {
+                        write_variant_content(&peek_enum, recursion_depth, output)
+                    }
                 }
-                Ok(())
             }
         }
         (_, Type::Pointer(pointer_type)) => {
@@ -373,6 +425,55 @@ where
     }
 }
 
+/// Writes the content of a non-unit enum variant, without the variant name
+fn write_variant_content<'mem, 'facet, 'shape, W: Write>(
+    peek_enum: &PeekEnum<'mem, 'facet, 'shape>,
+    recursion_depth: usize,
+    output: &mut W,
+) -> io::Result<()>
+where
+    'mem: 'facet,
+{
+    let variant = peek_enum
+        .active_variant()
+        .expect("Failed to get active variant");
+
+    if crate::variant_is_newtype_like(variant) {
+        // Newtype variant - serialize the inner value directly
+        let (field, field_peek) = peek_enum.fields_for_serialize().next().unwrap();
+        peek_to_writer(field_peek, Some(&field), recursion_depth + 1, output)
+    } else if variant.data.kind == StructKind::Tuple || variant.data.kind == StructKind::TupleStruct
+    {
+        // Tuple variant - serialize as array
+        write!(output, "[")?;
+
+        let mut first = true;
+        for (field, field_peek) in peek_enum.fields_for_serialize() {
+            if !first {
+                write!(output, ",")?;
+            }
+            first = false;
+            peek_to_writer(field_peek, Some(&field), recursion_depth + 1, output)?;
+        }
+        write!(output, "]")
+    } else {
+        // Struct variant - serialize as object
+        write!(output, "{{")?;
+
+        let mut first = true;
+        for (field, field_peek) in peek_enum.fields_for_serialize() {
+            if !first {
+                write!(output, ",")?;
+            }
+            first = false;
+            crate::write_json_string(output, field.name)?;
+            write!(output, ":")?;
+            peek_to_writer(field_peek, Some(&field), recursion_depth + 1, output)?;
+        }
+        write!(output, "}}")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
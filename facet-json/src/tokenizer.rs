@@ -198,11 +198,13 @@ impl<'input> Tokenizer<'input> {
         self.pos += 1;
         let mut buf = Vec::new();
         let content_start = self.pos;
+        let mut closed = false;
 
         while let Some(&b) = self.input.get(self.pos) {
             match b {
                 b'"' => {
                     self.pos += 1;
+                    closed = true;
                     break;
                 }
                 b'\\' => {
@@ -293,9 +295,7 @@ impl<'input> Tokenizer<'input> {
         }
 
         // Check if we reached the end without finding a closing quote
-        if self.pos > self.input.len()
-            || (self.pos == self.input.len() && self.input[self.pos - 1] != b'"')
-        {
+        if !closed {
             return Err(TokenError {
                 kind: TokenErrorKind::UnexpectedEof("in string literal"),
                 span: Span::new(start, self.pos - start),
@@ -0,0 +1,85 @@
+use facet::Facet;
+use facet_json::{from_str, to_string};
+use facet_testhelpers::test;
+
+/// A field can be read under its alias
+#[test]
+fn test_field_alias() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct User {
+        #[facet(alias = "username")]
+        name: String,
+        age: u32,
+    }
+
+    let user: User = from_str(r#"{"username":"ferris","age":8}"#)?;
+    assert_eq!(
+        user,
+        User {
+            name: "ferris".to_string(),
+            age: 8
+        }
+    );
+
+    // The canonical name keeps working
+    let user: User = from_str(r#"{"name":"ferris","age":8}"#)?;
+    assert_eq!(user.name, "ferris");
+}
+
+/// A field can have several aliases, and serializes under its canonical name
+#[test]
+fn test_field_multiple_aliases() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Config {
+        #[facet(alias = "colour", alias = "colr")]
+        color: String,
+    }
+
+    let config: Config = from_str(r#"{"colour":"red"}"#)?;
+    assert_eq!(config.color, "red");
+
+    let config: Config = from_str(r#"{"colr":"blue"}"#)?;
+    assert_eq!(config.color, "blue");
+
+    assert_eq!(to_string(&config), r#"{"color":"blue"}"#);
+}
+
+/// Aliases combine with `rename`
+#[test]
+fn test_field_alias_with_rename() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Point {
+        #[facet(rename = "X", alias = "x")]
+        x: i32,
+    }
+
+    let point: Point = from_str(r#"{"X":1}"#)?;
+    assert_eq!(point.x, 1);
+
+    let point: Point = from_str(r#"{"x":2}"#)?;
+    assert_eq!(point.x, 2);
+}
+
+/// A variant can be read under its alias
+#[test]
+fn test_variant_alias() {
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    enum Shape {
+        #[facet(alias = "Round")]
+        Circle { radius: f64 },
+        #[facet(alias = "Box")]
+        Square,
+    }
+
+    let shape: Shape = from_str(r#"{"Round":{"radius":1.5}}"#)?;
+    assert_eq!(shape, Shape::Circle { radius: 1.5 });
+
+    let shape: Shape = from_str(r#"{"Circle":{"radius":2.5}}"#)?;
+    assert_eq!(shape, Shape::Circle { radius: 2.5 });
+
+    assert_eq!(to_string(&shape), r#"{"Circle":{"radius":2.5}}"#);
+
+    let shape: Shape = from_str(r#""Box""#)?;
+    assert_eq!(shape, Shape::Square);
+}
@@ -0,0 +1,131 @@
+use facet::Facet;
+use facet_json::{DeserErrorKind, from_str_collecting};
+use facet_testhelpers::test;
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(deny_unknown_fields)]
+struct Server {
+    host: String,
+    port: u16,
+    workers: u32,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Config {
+    name: String,
+    servers: Vec<Server>,
+    #[facet(default)]
+    verbose: bool,
+}
+
+#[test]
+fn valid_input_deserializes() {
+    let json = r#"{"name":"prod","servers":[{"host":"a","port":80,"workers":4}]}"#;
+    let config: Config = from_str_collecting(json)?;
+    assert_eq!(
+        config,
+        Config {
+            name: "prod".to_string(),
+            servers: vec![Server {
+                host: "a".to_string(),
+                port: 80,
+                workers: 4,
+            }],
+            verbose: false,
+        }
+    );
+}
+
+#[test]
+fn reports_every_error() {
+    let json = r#"{
+    "servers": [
+        {"host": "a", "port": "eighty", "workers": 4},
+        {"host": "b", "port": 81, "workers": 2, "threads": 8},
+        {"host": "c", "port": 82}
+    ],
+    "verbose": 12
+}"#;
+    let errors = from_str_collecting::<Config>(json).unwrap_err();
+
+    let found: Vec<_> = errors
+        .errors()
+        .iter()
+        .map(|err| (&json[err.span.start()..err.span.end()], err.kind.clone()))
+        .collect();
+    assert_eq!(found.len(), 5);
+
+    // Errors come out in the order they start in the input
+    assert_eq!(found[0], (json, DeserErrorKind::MissingField("name")));
+    assert!(matches!(
+        found[1],
+        ("\"eighty\"", DeserErrorKind::ReflectError(_))
+    ));
+    assert!(matches!(
+        &found[2],
+        ("\"threads\"", DeserErrorKind::UnknownField { field_name, .. }) if field_name == "threads"
+    ));
+    assert_eq!(
+        found[3],
+        (
+            r#"{"host": "c", "port": 82}"#,
+            DeserErrorKind::MissingField("workers")
+        )
+    );
+    assert!(matches!(found[4], ("12", DeserErrorKind::ReflectError(_))));
+
+    #[cfg(not(miri))]
+    insta::assert_snapshot!(errors);
+}
+
+#[test]
+fn stops_at_syntax_errors() {
+    let json = r#"{"servers": [{"host": "a", "port": "eighty"}], "name": }"#;
+    let errors = from_str_collecting::<Config>(json).unwrap_err();
+
+    // The skipped port isn't reported as missing, and the parse ends at the syntax error
+    let kinds: Vec<_> = errors.errors().iter().map(|err| &err.kind).collect();
+    assert_eq!(kinds.len(), 3);
+    assert_eq!(*kinds[0], DeserErrorKind::MissingField("workers"));
+    assert!(matches!(kinds[1], DeserErrorKind::ReflectError(_)));
+    assert!(matches!(
+        kinds[2],
+        DeserErrorKind::UnexpectedChar { got: '}', .. }
+    ));
+}
+
+#[test]
+fn single_error_matches_from_str() {
+    let json = r#"{"name": "prod", "servers": 3}"#;
+    let errors = from_str_collecting::<Config>(json).unwrap_err();
+    let err = facet_json::from_str::<Config>(json).unwrap_err();
+
+    assert_eq!(errors.errors().len(), 1);
+    assert_eq!(errors.errors()[0].span, err.span);
+    assert_eq!(errors.errors()[0].kind, err.kind);
+}
+
+#[test]
+fn recovers_inside_nested_values() {
+    use std::collections::HashMap;
+
+    #[derive(Facet, Debug)]
+    struct Limits {
+        sizes: [u8; 2],
+        pair: (u16, bool),
+        by_name: HashMap<String, u32>,
+    }
+
+    let json = r#"{
+    "sizes": [1, 300],
+    "pair": [-1, true],
+    "by_name": {"a": 1, "b": "two", "c": 3}
+}"#;
+    let errors = from_str_collecting::<Limits>(json).unwrap_err();
+    let found: Vec<_> = errors
+        .errors()
+        .iter()
+        .map(|err| &json[err.span.start()..err.span.end()])
+        .collect();
+    assert_eq!(found, ["300", "-1", "\"two\""]);
+}
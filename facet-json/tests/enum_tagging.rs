@@ -0,0 +1,186 @@
+use facet::Facet;
+use facet_json::{from_str, to_string};
+use facet_testhelpers::test;
+
+#[derive(Facet, Debug, PartialEq)]
+struct Circle {
+    radius: u32,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(tag = "type")]
+#[repr(u8)]
+enum InternallyTagged {
+    Empty,
+    Circle(Circle),
+    Rect { w: u32, h: u32 },
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(tag = "t", content = "c")]
+#[repr(u8)]
+enum AdjacentlyTagged {
+    Empty,
+    Count(u32),
+    Pair(u32, String),
+    Rect { w: u32, h: u32 },
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(untagged)]
+#[repr(u8)]
+enum Untagged {
+    Nothing,
+    Number(u64),
+    Text(String),
+    Pair(u32, bool),
+    Rect { w: u32, h: u32 },
+    Named { name: String },
+}
+
+#[test]
+fn internally_tagged_serialize() {
+    assert_eq!(to_string(&InternallyTagged::Empty), r#"{"type":"Empty"}"#);
+    assert_eq!(
+        to_string(&InternallyTagged::Circle(Circle { radius: 3 })),
+        r#"{"type":"Circle","radius":3}"#
+    );
+    assert_eq!(
+        to_string(&InternallyTagged::Rect { w: 1, h: 2 }),
+        r#"{"type":"Rect","w":1,"h":2}"#
+    );
+}
+
+#[test]
+fn internally_tagged_deserialize() {
+    let v: InternallyTagged = from_str(r#"{"type":"Empty"}"#)?;
+    assert_eq!(v, InternallyTagged::Empty);
+
+    let v: InternallyTagged = from_str(r#"{"radius":3,"type":"Circle"}"#)?;
+    assert_eq!(v, InternallyTagged::Circle(Circle { radius: 3 }));
+
+    let v: InternallyTagged = from_str(r#"{"w":1,"type":"Rect","h":2}"#)?;
+    assert_eq!(v, InternallyTagged::Rect { w: 1, h: 2 });
+}
+
+#[test]
+fn internally_tagged_in_struct() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Wrapper {
+        shapes: Vec<InternallyTagged>,
+        after: bool,
+    }
+
+    let value = Wrapper {
+        shapes: vec![
+            InternallyTagged::Rect { w: 1, h: 2 },
+            InternallyTagged::Empty,
+            InternallyTagged::Circle(Circle { radius: 5 }),
+        ],
+        after: true,
+    };
+    let json = to_string(&value);
+    let back: Wrapper = from_str(&json).unwrap();
+    assert_eq!(back, value);
+}
+
+#[test]
+fn internally_tagged_missing_tag() {
+    let err = from_str::<InternallyTagged>(r#"{"w":1,"h":2}"#).unwrap_err();
+    assert!(err.to_string().contains("Missing tag"), "{err}");
+}
+
+#[test]
+fn adjacently_tagged_serialize() {
+    assert_eq!(to_string(&AdjacentlyTagged::Empty), r#"{"t":"Empty"}"#);
+    assert_eq!(
+        to_string(&AdjacentlyTagged::Count(3)),
+        r#"{"t":"Count","c":3}"#
+    );
+    assert_eq!(
+        to_string(&AdjacentlyTagged::Pair(3, "x".to_string())),
+        r#"{"t":"Pair","c":[3,"x"]}"#
+    );
+    assert_eq!(
+        to_string(&AdjacentlyTagged::Rect { w: 1, h: 2 }),
+        r#"{"t":"Rect","c":{"w":1,"h":2}}"#
+    );
+}
+
+#[test]
+fn adjacently_tagged_deserialize() {
+    let v: AdjacentlyTagged = from_str(r#"{"t":"Empty"}"#)?;
+    assert_eq!(v, AdjacentlyTagged::Empty);
+
+    let v: AdjacentlyTagged = from_str(r#"{"c":3,"t":"Count"}"#)?;
+    assert_eq!(v, AdjacentlyTagged::Count(3));
+
+    let v: AdjacentlyTagged = from_str(r#"{"t":"Pair","c":[3,"x"]}"#)?;
+    assert_eq!(v, AdjacentlyTagged::Pair(3, "x".to_string()));
+
+    let v: AdjacentlyTagged = from_str(r#"{"c":{"w":1,"h":2},"t":"Rect"}"#)?;
+    assert_eq!(v, AdjacentlyTagged::Rect { w: 1, h: 2 });
+}
+
+#[test]
+fn untagged_serialize() {
+    assert_eq!(to_string(&Untagged::Nothing), "null");
+    assert_eq!(to_string(&Untagged::Number(7)), "7");
+    assert_eq!(to_string(&Untagged::Text("hi".to_string())), r#""hi""#);
+    assert_eq!(to_string(&Untagged::Pair(1, true)), "[1,true]");
+    assert_eq!(
+        to_string(&Untagged::Rect { w: 1, h: 2 }),
+        r#"{"w":1,"h":2}"#
+    );
+}
+
+#[test]
+fn untagged_deserialize() {
+    let v: Untagged = from_str("null")?;
+    assert_eq!(v, Untagged::Nothing);
+
+    let v: Untagged = from_str("7")?;
+    assert_eq!(v, Untagged::Number(7));
+
+    let v: Untagged = from_str(r#""hi""#)?;
+    assert_eq!(v, Untagged::Text("hi".to_string()));
+
+    let v: Untagged = from_str("[1, true]")?;
+    assert_eq!(v, Untagged::Pair(1, true));
+
+    let v: Untagged = from_str(r#"{"h":2,"w":1}"#)?;
+    assert_eq!(v, Untagged::Rect { w: 1, h: 2 });
+
+    let v: Untagged = from_str(r#"{"name":"bob"}"#)?;
+    assert_eq!(
+        v,
+        Untagged::Named {
+            name: "bob".to_string()
+        }
+    );
+}
+
+#[test]
+fn untagged_in_list() {
+    let v: Vec<Untagged> = from_str(r#"[1, "two", {"name":"three"}, null]"#)?;
+    assert_eq!(
+        v,
+        vec![
+            Untagged::Number(1),
+            Untagged::Text("two".to_string()),
+            Untagged::Named {
+                name: "three".to_string()
+            },
+            Untagged::Nothing,
+        ]
+    );
+}
+
+#[test]
+fn untagged_no_matching_variant() {
+    let err = from_str::<Untagged>("true").unwrap_err();
+    assert!(
+        err.to_string().contains("did not match any variant"),
+        "{err}"
+    );
+}
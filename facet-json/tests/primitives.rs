@@ -34,6 +34,18 @@ fn test_str_escaped() {
     assert_eq!(ok.foo, "\"\\abc");
 }
 
+#[test]
+fn test_char() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct S {
+        c: char,
+    }
+
+    let ok: S = from_str(r#"{"c":"é"}"#).unwrap();
+    assert_eq!(ok, S { c: 'é' });
+    assert!(from_str::<S>(r#"{"c":"ab"}"#).is_err());
+}
+
 #[test]
 fn test_bool_serialization() {
     #[derive(Debug, Facet)]
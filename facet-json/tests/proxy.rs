@@ -0,0 +1,164 @@
+use std::time::Duration;
+
+use facet::Facet;
+use facet_json::{from_str, to_string};
+use facet_testhelpers::test;
+
+#[allow(clippy::ptr_arg)]
+fn to_hex(bytes: &Vec<u8>) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn from_hex(hex: String) -> Result<Vec<u8>, String> {
+    if hex.len() % 2 != 0 {
+        return Err(format!("odd number of hex digits in {hex:?}"));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|e| e.to_string()))
+        .collect()
+}
+
+/// A field can be converted by functions, on either side
+#[test]
+fn test_serialize_with_and_deserialize_with() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Blob {
+        name: String,
+        #[facet(serialize_with = to_hex, deserialize_with = from_hex)]
+        data: Vec<u8>,
+    }
+
+    let blob = Blob {
+        name: "key".to_string(),
+        data: vec![0xde, 0xad, 0xbe, 0xef],
+    };
+
+    let json = to_string(&blob);
+    assert_eq!(json, r#"{"name":"key","data":"deadbeef"}"#);
+
+    let roundtrip: Blob = from_str(&json).unwrap();
+    assert_eq!(roundtrip, blob);
+}
+
+/// The error of a failed conversion is reported
+#[test]
+fn test_deserialize_with_error() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Blob {
+        #[facet(deserialize_with = from_hex)]
+        data: Vec<u8>,
+    }
+
+    let err = from_str::<Blob>(r#"{"data":"abc"}"#).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains(r#"odd number of hex digits in "abc""#),
+        "{err}"
+    );
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(transparent)]
+struct LegacyNumber(String);
+
+impl From<&u64> for LegacyNumber {
+    fn from(value: &u64) -> Self {
+        LegacyNumber(value.to_string())
+    }
+}
+
+impl TryFrom<LegacyNumber> for u64 {
+    type Error = std::num::ParseIntError;
+
+    fn try_from(value: LegacyNumber) -> Result<Self, Self::Error> {
+        value.0.parse()
+    }
+}
+
+/// A field can be converted through a proxy type
+#[test]
+fn test_proxy() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Account {
+        #[facet(proxy = LegacyNumber)]
+        balance: u64,
+    }
+
+    let account = Account { balance: 1234 };
+    let json = to_string(&account);
+    assert_eq!(json, r#"{"balance":"1234"}"#);
+
+    let roundtrip: Account = from_str(&json).unwrap();
+    assert_eq!(roundtrip, account);
+
+    assert!(from_str::<Account>(r#"{"balance":"12a"}"#).is_err());
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(transparent)]
+struct Seconds(u64);
+
+impl From<&Duration> for Seconds {
+    fn from(value: &Duration) -> Self {
+        Seconds(value.as_secs())
+    }
+}
+
+impl TryFrom<Seconds> for Duration {
+    type Error = std::convert::Infallible;
+
+    fn try_from(value: Seconds) -> Result<Self, Self::Error> {
+        Ok(Duration::from_secs(value.0))
+    }
+}
+
+/// A proxy lets types that don't implement `Facet` be serialized
+#[test]
+fn test_proxy_for_opaque_field() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Config {
+        #[facet(opaque, proxy = Seconds)]
+        timeout: Duration,
+        retries: u8,
+    }
+
+    let config = Config {
+        timeout: Duration::from_secs(30),
+        retries: 3,
+    };
+    let json = to_string(&config);
+    assert_eq!(json, r#"{"timeout":30,"retries":3}"#);
+
+    let roundtrip: Config = from_str(&json).unwrap();
+    assert_eq!(roundtrip, config);
+}
+
+/// Proxies work in enum variants and with defaults
+#[test]
+fn test_proxy_in_variant_and_default() {
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    enum Payment {
+        Card {
+            #[facet(proxy = LegacyNumber)]
+            amount: u64,
+            #[facet(proxy = LegacyNumber, default = 1)]
+            installments: u64,
+        },
+    }
+
+    let payment: Payment = from_str(r#"{"Card":{"amount":"100"}}"#)?;
+    assert_eq!(
+        payment,
+        Payment::Card {
+            amount: 100,
+            installments: 1
+        }
+    );
+
+    assert_eq!(
+        to_string(&payment),
+        r#"{"Card":{"amount":"100","installments":"1"}}"#
+    );
+}
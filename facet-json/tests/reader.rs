@@ -0,0 +1,173 @@
+use std::io::{self, Read};
+
+use facet::Facet;
+use facet_json::{DeserErrorKind, StreamDeserializer, from_reader};
+use facet_testhelpers::test;
+
+/// A reader that hands out its bytes one at a time, so tokens get cut at every position.
+struct Trickle<'a>(&'a [u8]);
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let Some((first, rest)) = self.0.split_first() else {
+            return Ok(0);
+        };
+        buf[0] = *first;
+        self.0 = rest;
+        Ok(1)
+    }
+}
+
+/// A reader that fails once its bytes run out.
+struct Broken<'a>(&'a [u8]);
+
+impl Read for Broken<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.0.is_empty() {
+            return Err(io::Error::other("connection reset"));
+        }
+        self.0.read(buf)
+    }
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Entry {
+    level: String,
+    message: String,
+    code: Option<i64>,
+    ratio: f64,
+    ok: bool,
+    tags: Vec<String>,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(tag = "type")]
+#[repr(u8)]
+enum Shape {
+    Square { side: u32 },
+    Rect { w: u32, h: u32 },
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(untagged)]
+#[repr(u8)]
+enum Id {
+    Number(u64),
+    Named { name: String },
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Line {
+    id: u32,
+}
+
+#[test]
+fn from_reader_one_byte_at_a_time() {
+    let json = r#"
+    {
+        "level": "warn",
+        "message": "disk \"sda\" at 93% é",
+        "code": -12345678,
+        "ratio": 1.5e-3,
+        "ok": false,
+        "tags": ["a", "", "ccc"]
+    }"#;
+    let entry: Entry = from_reader(Trickle(json.as_bytes()))?;
+    assert_eq!(
+        entry,
+        Entry {
+            level: "warn".to_string(),
+            message: "disk \"sda\" at 93% é".to_string(),
+            code: Some(-12345678),
+            ratio: 1.5e-3,
+            ok: false,
+            tags: vec!["a".to_string(), String::new(), "ccc".to_string()],
+        }
+    );
+}
+
+#[test]
+fn from_reader_looks_ahead() {
+    let json = br#"[{"h": 2, "w": 1, "type": "Rect"}, {"type": "Square", "side": 3}]"#;
+    let shapes: Vec<Shape> = from_reader(Trickle(json))?;
+    assert_eq!(
+        shapes,
+        vec![Shape::Rect { w: 1, h: 2 }, Shape::Square { side: 3 }]
+    );
+
+    let json = br#"[12345, {"name": "twelve"}]"#;
+    let ids: Vec<Id> = from_reader(Trickle(json))?;
+    assert_eq!(
+        ids,
+        vec![
+            Id::Number(12345),
+            Id::Named {
+                name: "twelve".to_string()
+            }
+        ]
+    );
+}
+
+#[test]
+fn from_reader_large_input() {
+    let numbers: Vec<u64> = (0..20_000).collect();
+    let json = facet_json::to_string(&numbers);
+    let read: Vec<u64> = from_reader(json.as_bytes())?;
+    assert_eq!(read, numbers);
+}
+
+#[test]
+fn from_reader_error_spans() {
+    let json = b"[1, 2, tru]";
+    let err = from_reader::<_, Vec<u32>>(Trickle(json)).unwrap_err();
+    assert_eq!(err.span.start(), 7);
+
+    let err = from_reader::<_, Vec<u32>>(Trickle(b"[1, 2")).unwrap_err();
+    assert!(matches!(err.kind, DeserErrorKind::UnexpectedEof { .. }));
+    assert_eq!(err.span.start(), 5);
+}
+
+#[test]
+fn from_reader_io_error() {
+    let err = from_reader::<_, Vec<u32>>(Broken(b"[1, 2")).unwrap_err();
+    assert_eq!(err.kind, DeserErrorKind::Io("connection reset".to_string()));
+    assert_eq!(err.span.start(), 5);
+}
+
+#[test]
+fn stream_lines() {
+    let ndjson = b"{\"id\": 1}\n{\"id\": 2}\n\n  {\"id\": 3}\n";
+    let lines: Vec<Line> = StreamDeserializer::new(Trickle(ndjson)).collect::<Result<_, _>>()?;
+    assert_eq!(lines, vec![Line { id: 1 }, Line { id: 2 }, Line { id: 3 }]);
+
+    let mut stream = StreamDeserializer::<_, Line>::new(&b""[..]);
+    assert!(stream.next().is_none());
+}
+
+#[test]
+fn stream_picks_up_after_errors() {
+    let ndjson = "{\"id\": 1}\n{\"id\": \"two\"}\n{\"id\": 3\n{\"id\": 4}";
+    let mut stream = StreamDeserializer::<_, Line>::new(Trickle(ndjson.as_bytes()));
+
+    assert_eq!(stream.next().unwrap()?, Line { id: 1 });
+
+    let err = stream.next().unwrap().unwrap_err();
+    assert_eq!(&ndjson[err.span.start()..err.span.end()], "\"two\"");
+
+    // The unclosed object only fails once the next line starts
+    let err = stream.next().unwrap().unwrap_err();
+    assert_eq!(err.span.start(), ndjson.rfind('{').unwrap());
+
+    assert_eq!(stream.next().unwrap()?, Line { id: 4 });
+    assert!(stream.next().is_none());
+    assert_eq!(stream.byte_offset(), ndjson.len());
+}
+
+#[test]
+fn stream_stops_at_io_errors() {
+    let mut stream = StreamDeserializer::<_, Line>::new(Broken(b"{\"id\": 1}\n{\"id\""));
+    assert_eq!(stream.next().unwrap()?, Line { id: 1 });
+    let err = stream.next().unwrap().unwrap_err();
+    assert!(matches!(err.kind, DeserErrorKind::Io(_)));
+    assert!(stream.next().is_none());
+}
@@ -0,0 +1,45 @@
+use std::collections::{BTreeSet, HashSet};
+
+use facet::Facet;
+use facet_json::{from_str, to_string};
+use facet_testhelpers::test;
+
+#[test]
+fn json_read_hashset() {
+    let s: HashSet<String> = from_str(r#"["a", "b", "a", "c"]"#)?;
+    assert_eq!(
+        s,
+        HashSet::from(["a".to_string(), "b".to_string(), "c".to_string()])
+    );
+}
+
+#[test]
+fn json_read_empty_btreeset() {
+    let s: BTreeSet<u32> = from_str("[]")?;
+    assert!(s.is_empty());
+}
+
+#[test]
+fn json_write_btreeset() {
+    let s = BTreeSet::from([3, 1, 2]);
+    assert_eq!(to_string(&s), "[1,2,3]");
+}
+
+#[test]
+fn json_roundtrip_set_in_struct() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Tagged {
+        name: String,
+        tags: BTreeSet<String>,
+    }
+
+    let value = Tagged {
+        name: "post".to_string(),
+        tags: BTreeSet::from(["rust".to_string(), "facet".to_string()]),
+    };
+    let json = to_string(&value);
+    assert_eq!(json, r#"{"name":"post","tags":["facet","rust"]}"#);
+
+    let back: Tagged = from_str(&json).unwrap();
+    assert_eq!(back, value);
+}
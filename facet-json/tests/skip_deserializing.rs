@@ -0,0 +1,119 @@
+#![cfg(feature = "std")]
+
+use facet::Facet;
+use facet_json::{from_str, to_string};
+use facet_testhelpers::test;
+
+#[test]
+fn test_skip_deserializing() {
+    #[derive(Debug, PartialEq, Facet)]
+    struct Connection {
+        host: String,
+        #[facet(skip_deserializing)]
+        retries: u32,
+        #[facet(skip_deserializing, default = 8080)]
+        port: u16,
+    }
+
+    // Skipped fields get their default, and their keys are ignored
+    let connection: Connection = from_str(r#"{"host":"localhost","retries":3,"port":1234}"#)?;
+    assert_eq!(
+        connection,
+        Connection {
+            host: "localhost".to_string(),
+            retries: 0,
+            port: 8080,
+        }
+    );
+
+    // They're still serialized
+    assert_eq!(
+        to_string(&connection),
+        r#"{"host":"localhost","retries":0,"port":8080}"#
+    );
+}
+
+#[test]
+fn test_skip_deserializing_deny_unknown_fields() {
+    #[derive(Debug, PartialEq, Facet)]
+    #[facet(deny_unknown_fields)]
+    struct Connection {
+        host: String,
+        #[facet(skip_deserializing)]
+        retries: u32,
+    }
+
+    let connection: Connection = from_str(r#"{"host":"localhost"}"#)?;
+    assert_eq!(connection.retries, 0);
+
+    assert!(from_str::<Connection>(r#"{"host":"localhost","retries":3}"#).is_err());
+}
+
+#[test]
+fn test_skip() {
+    #[derive(Debug, PartialEq, Facet)]
+    struct Document {
+        title: String,
+        #[facet(skip)]
+        cache: Vec<String>,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Library {
+        documents: Vec<Document>,
+        main: Document,
+    }
+
+    let library = Library {
+        documents: vec![Document {
+            title: "a".to_string(),
+            cache: vec!["cached".to_string()],
+        }],
+        main: Document {
+            title: "b".to_string(),
+            cache: vec!["cached".to_string()],
+        },
+    };
+    let json = to_string(&library);
+    assert_eq!(
+        json,
+        r#"{"documents":[{"title":"a"}],"main":{"title":"b"}}"#
+    );
+
+    let library: Library = from_str(&json).unwrap();
+    assert_eq!(
+        library,
+        Library {
+            documents: vec![Document {
+                title: "a".to_string(),
+                cache: vec![],
+            }],
+            main: Document {
+                title: "b".to_string(),
+                cache: vec![],
+            },
+        }
+    );
+}
+
+#[test]
+fn test_skip_in_enum() {
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    enum Shape {
+        Circle {
+            radius: f64,
+            #[facet(skip)]
+            area: Option<f64>,
+        },
+    }
+
+    let shape: Shape = from_str(r#"{"Circle":{"radius":1.5,"area":7.0}}"#)?;
+    assert_eq!(
+        shape,
+        Shape::Circle {
+            radius: 1.5,
+            area: None
+        }
+    );
+}
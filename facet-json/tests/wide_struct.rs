@@ -0,0 +1,105 @@
+use facet::Facet;
+use facet_json::from_str;
+use facet_testhelpers::test;
+
+#[derive(Facet, Debug, PartialEq)]
+struct Wide {
+    f0: u32,
+    f1: u32,
+    f2: u32,
+    f3: u32,
+    f4: u32,
+    f5: u32,
+    f6: u32,
+    f7: u32,
+    f8: u32,
+    f9: u32,
+    f10: u32,
+    f11: u32,
+    f12: u32,
+    f13: u32,
+    f14: u32,
+    f15: u32,
+    f16: u32,
+    f17: u32,
+    f18: u32,
+    f19: u32,
+    f20: u32,
+    f21: u32,
+    f22: u32,
+    f23: u32,
+    f24: u32,
+    f25: u32,
+    f26: u32,
+    f27: u32,
+    f28: u32,
+    f29: u32,
+    f30: u32,
+    f31: u32,
+    f32: u32,
+    f33: u32,
+    f34: u32,
+    f35: u32,
+    f36: u32,
+    f37: u32,
+    f38: u32,
+    f39: u32,
+    f40: u32,
+    f41: u32,
+    f42: u32,
+    f43: u32,
+    f44: u32,
+    f45: u32,
+    f46: u32,
+    f47: u32,
+    f48: u32,
+    f49: u32,
+    f50: u32,
+    f51: u32,
+    f52: u32,
+    f53: u32,
+    f54: u32,
+    f55: u32,
+    f56: u32,
+    f57: u32,
+    f58: u32,
+    f59: u32,
+    f60: u32,
+    f61: u32,
+    f62: u32,
+    f63: u32,
+    f64: u32,
+    f65: u32,
+    f66: u32,
+    f67: u32,
+    f68: u32,
+    f69: u32,
+}
+
+#[test]
+fn json_read_struct_with_more_than_64_fields() {
+    let json = format!(
+        "{{{}}}",
+        (0..70)
+            .map(|i| format!(r#""f{i}":{i}"#))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+    let wide: Wide = from_str(&json).unwrap();
+    assert_eq!(wide.f0, 0);
+    assert_eq!(wide.f64, 64);
+    assert_eq!(wide.f69, 69);
+}
+
+#[test]
+fn json_read_struct_with_more_than_64_fields_missing_one() {
+    let json = format!(
+        "{{{}}}",
+        (0..69)
+            .map(|i| format!(r#""f{i}":{i}"#))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+    let err = from_str::<Wide>(&json).unwrap_err();
+    assert!(err.to_string().contains("f69"), "{err}");
+}
@@ -6,7 +6,10 @@
 
 extern crate facet_core as facet;
 use facet::{PointerType, SmartPointerDef};
-use facet_core::{Def, Facet, ScalarDef, Shape, Type, UserType};
+use facet_core::{
+    Def, EnumTagging, EnumType, Facet, Field, FieldFlags, ScalarAffinity, ScalarDef, SequenceType,
+    Shape, ShapeAttribute, StructKind, StructType, Type, UserType, Variant,
+};
 
 use std::io::Write;
 
@@ -40,118 +43,306 @@ pub fn to_string<'a, T: Facet<'a>>() -> String {
     match (id.next(), id.next()) {
         (Some(_), Some(_)) => panic!("More than one id attribute found"),
         (Some(id), None) => {
-            write!(buffer, "\"$id\": \"{id}\",").unwrap();
+            write!(buffer, "\"$id\": ").unwrap();
+            write_string(id, &mut buffer).unwrap();
+            write!(buffer, ",").unwrap();
         }
         _ => {
             // No id attribute found, do nothing
         }
     }
 
-    serialize(T::SHAPE, &[], &mut buffer).unwrap();
+    let defs = Definitions::collect(T::SHAPE);
+    serialize_inline(T::SHAPE, &[], &defs, &mut buffer).unwrap();
+    serialize_defs(&defs, &mut buffer).unwrap();
     write!(buffer, "}}").unwrap();
     String::from_utf8(buffer).unwrap()
 }
 
-fn serialize<'shape, W: Write>(
+/// User types that are referred to with `$ref` rather than written out in place, because
+/// they're used more than once or contain themselves.
+struct Definitions<'shape> {
+    root: &'shape Shape<'shape>,
+
+    /// Whether the root type contains itself, in which case it's referred to as `#`
+    root_is_recursive: bool,
+
+    /// The types that go in `$defs`, along with their name there
+    defs: Vec<(&'shape Shape<'shape>, String)>,
+}
+
+impl<'shape> Definitions<'shape> {
+    fn collect(root: &'shape Shape<'shape>) -> Self {
+        let mut seen = Vec::new();
+        count_user_types(root, &mut seen);
+
+        let mut definitions = Self {
+            root,
+            root_is_recursive: false,
+            defs: Vec::new(),
+        };
+        for (shape, count) in seen {
+            if count < 2 {
+                continue;
+            }
+            if shape.id == root.id {
+                definitions.root_is_recursive = true;
+                continue;
+            }
+            // Generic types and types from different modules can share a name
+            let base = def_name(shape);
+            let mut name = base.clone();
+            let mut suffix = 2;
+            while definitions.defs.iter().any(|(_, taken)| *taken == name) {
+                name = format!("{base}{suffix}");
+                suffix += 1;
+            }
+            definitions.defs.push((shape, name));
+        }
+        definitions
+    }
+
+    /// Returns what to put in `$ref` for `shape`, if it isn't written out in place.
+    fn reference(&self, shape: &Shape) -> Option<String> {
+        if shape.id == self.root.id {
+            return self.root_is_recursive.then(|| "#".to_string());
+        }
+        self.defs
+            .iter()
+            .find(|(def, _)| def.id == shape.id)
+            .map(|(_, name)| format!("#/$defs/{name}"))
+    }
+}
+
+/// Counts how many times each struct and enum is reached from `shape`, without looking
+/// inside the ones already seen.
+fn count_user_types<'shape>(
     shape: &'shape Shape<'shape>,
-    doc: &[&str],
-    writer: &mut W,
-) -> std::io::Result<()> {
-    serialize_doc(&[shape.doc, doc].concat(), writer)?;
+    seen: &mut Vec<(&'shape Shape<'shape>, usize)>,
+) {
+    if is_definable(shape) {
+        if let Some((_, count)) = seen.iter_mut().find(|(s, _)| s.id == shape.id) {
+            *count += 1;
+            return;
+        }
+        seen.push((shape, 1));
+    }
 
-    // First check the type system (Type)
-    match &shape.ty {
-        Type::User(UserType::Struct(struct_def)) => {
-            serialize_struct(struct_def, writer)?;
-            return Ok(());
-        }
-        Type::User(UserType::Enum(_enum_def)) => {
-            todo!("Enum");
-        }
-        Type::Sequence(sequence_type) => {
-            use facet_core::SequenceType;
-            match sequence_type {
-                SequenceType::Slice(_slice_type) => {
-                    // For slices, use the Def::Slice if available
-                    if let Def::Slice(slice_def) = shape.def {
-                        serialize_slice(slice_def, writer)?;
-                        return Ok(());
-                    }
+    match shape.def {
+        Def::Map(map_def) => {
+            count_user_types(map_def.k(), seen);
+            count_user_types(map_def.v(), seen);
+        }
+        Def::Set(set_def) => count_user_types(set_def.t(), seen),
+        Def::List(list_def) => count_user_types(list_def.t(), seen),
+        Def::Array(array_def) => count_user_types(array_def.t(), seen),
+        Def::Slice(slice_def) => count_user_types(slice_def.t(), seen),
+        Def::Option(option_def) => count_user_types(option_def.t(), seen),
+        Def::SmartPointer(SmartPointerDef {
+            pointee: Some(inner_shape),
+            ..
+        }) => count_user_types(inner_shape(), seen),
+        Def::Undefined => match &shape.ty {
+            Type::User(UserType::Struct(struct_type)) => {
+                for field in deserializable_fields(struct_type) {
+                    count_user_types(field_shape(field), seen);
                 }
-                SequenceType::Array(_array_type) => {
-                    // For arrays, use the Def::Array if available
-                    if let Def::Array(array_def) = shape.def {
-                        serialize_array(array_def, writer)?;
-                        return Ok(());
+            }
+            Type::User(UserType::Enum(enum_type)) => {
+                for variant in enum_type.variants {
+                    for field in deserializable_fields(&variant.data) {
+                        count_user_types(field_shape(field), seen);
                     }
                 }
-                _ => {} // Handle other sequence types
             }
+            Type::Sequence(SequenceType::Tuple(tuple_type)) => {
+                for field in tuple_type.fields {
+                    count_user_types(field.shape(), seen);
+                }
+            }
+            Type::Pointer(PointerType::Reference(pt) | PointerType::Raw(pt)) => {
+                count_user_types((pt.target)(), seen)
+            }
+            _ => {}
+        },
+        _ => {}
+    }
+}
+
+/// Whether `shape` is a struct or enum that can go in `$defs`.
+fn is_definable(shape: &Shape) -> bool {
+    matches!(shape.def, Def::Undefined)
+        && matches!(
+            shape.ty,
+            Type::User(UserType::Struct(_) | UserType::Enum(_))
+        )
+}
+
+/// The name of `shape` in `$defs`: its type name, with generic parameters joined by `_`.
+fn def_name(shape: &Shape) -> String {
+    shape
+        .to_string()
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+fn serialize_defs<W: Write>(defs: &Definitions, writer: &mut W) -> std::io::Result<()> {
+    if defs.defs.is_empty() {
+        return Ok(());
+    }
+    write!(writer, ",\"$defs\": {{")?;
+    for (i, (shape, name)) in defs.defs.iter().enumerate() {
+        if i > 0 {
+            write!(writer, ",")?;
         }
-        _ => {} // Continue to check the def system
+        write!(writer, "\"{name}\": {{")?;
+        serialize_inline(shape, &[], defs, writer)?;
+        write!(writer, "}}")?;
+    }
+    write!(writer, "}}")?;
+    Ok(())
+}
+
+/// The fields read when deserializing, which are the ones the schema describes.
+fn deserializable_fields<'a, 'shape>(
+    struct_type: &'a StructType<'shape>,
+) -> impl Iterator<Item = &'a Field<'shape>> {
+    struct_type
+        .fields
+        .iter()
+        .filter(|field| !field.should_skip_deserializing())
+}
+
+/// The shape of the value expected in place of `field`, which differs from the field's
+/// own when it's deserialized through another type.
+fn field_shape<'shape>(field: &Field<'shape>) -> &'shape Shape<'shape> {
+    match field.vtable.deserialize_with {
+        Some(deserialize_with) => deserialize_with.shape,
+        None => field.shape(),
+    }
+}
+
+fn serialize<'shape, W: Write>(
+    shape: &'shape Shape<'shape>,
+    doc: &[&str],
+    defs: &Definitions<'shape>,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    if let Some(reference) = defs.reference(shape) {
+        serialize_doc(doc, writer)?;
+        write!(writer, "\"$ref\": \"{reference}\"")?;
+        return Ok(());
     }
+    serialize_inline(shape, doc, defs, writer)
+}
+
+/// Writes the schema of `shape` in place, even if it has an entry in `$defs`.
+fn serialize_inline<'shape, W: Write>(
+    shape: &'shape Shape<'shape>,
+    doc: &[&str],
+    defs: &Definitions<'shape>,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    serialize_doc(&[shape.doc, doc].concat(), writer)?;
 
-    // Then check the def system (Def)
+    // The def system comes first: `Option` is an enum as far as the type system goes
     match shape.def {
-        Def::Scalar(ref scalar_def) => serialize_scalar(scalar_def, writer)?,
-        Def::Map(_map_def) => todo!("Map"),
-        Def::List(list_def) => serialize_list(list_def, writer)?,
-        Def::Slice(slice_def) => serialize_slice(slice_def, writer)?,
-        Def::Array(array_def) => serialize_array(array_def, writer)?,
-        Def::Option(option_def) => serialize_option(option_def, writer)?,
+        Def::Scalar(ref scalar_def) => return serialize_scalar(scalar_def, writer),
+        Def::Map(map_def) => return serialize_map(map_def, defs, writer),
+        Def::Set(set_def) => return serialize_set(set_def, defs, writer),
+        Def::List(list_def) => return serialize_list(list_def, defs, writer),
+        Def::Slice(slice_def) => return serialize_slice(slice_def, defs, writer),
+        Def::Array(array_def) => return serialize_array(array_def, defs, writer),
+        Def::Option(option_def) => return serialize_option(option_def, defs, writer),
         Def::SmartPointer(SmartPointerDef {
             pointee: Some(inner_shape),
             ..
-        }) => serialize(inner_shape(), &[], writer)?,
-        Def::Undefined => {
+        }) => return serialize(inner_shape(), &[], defs, writer),
+        _ => {}
+    }
+
+    // Then check the type system (Type)
+    match &shape.ty {
+        Type::User(UserType::Struct(struct_def)) => {
+            if shape.attributes.contains(&ShapeAttribute::Transparent) {
+                if let Some(inner) = struct_def.fields.first() {
+                    return serialize(field_shape(inner), &[], defs, writer);
+                }
+            }
+            serialize_struct(shape, struct_def, defs, writer)
+        }
+        Type::User(UserType::Enum(enum_def)) => serialize_enum(shape, enum_def, defs, writer),
+        Type::Sequence(SequenceType::Tuple(tuple_type)) => {
+            serialize_tuple(tuple_type.fields, defs, writer)
+        }
+        Type::Primitive(primitive) => {
             // Handle the case when not yet migrated to the Type enum
             // For primitives, we can try to infer the type
-            match &shape.ty {
-                Type::Primitive(primitive) => {
-                    use facet_core::{NumericType, PrimitiveType, TextualType};
-                    match primitive {
-                        PrimitiveType::Numeric(NumericType::Float) => {
-                            write!(writer, "\"type\": \"number\", \"format\": \"double\"")?;
-                        }
-                        PrimitiveType::Boolean => {
-                            write!(writer, "\"type\": \"boolean\"")?;
-                        }
-                        PrimitiveType::Textual(TextualType::Str) => {
-                            write!(writer, "\"type\": \"string\"")?;
-                        }
-                        _ => {
-                            write!(writer, "\"type\": \"unknown\"")?;
-                        }
-                    }
-                }
-                Type::Pointer(PointerType::Reference(pt) | PointerType::Raw(pt)) => {
-                    serialize((pt.target)(), &[], writer)?
-                }
-                _ => {
-                    write!(writer, "\"type\": \"unknown\"")?;
+            use facet_core::{NumericType, PrimitiveType, TextualType};
+            match primitive {
+                PrimitiveType::Numeric(NumericType::Float) => {
+                    write!(writer, "\"type\": \"number\", \"format\": \"double\"")
                 }
+                PrimitiveType::Boolean => write!(writer, "\"type\": \"boolean\""),
+                PrimitiveType::Textual(TextualType::Str) => write!(writer, "\"type\": \"string\""),
+                _ => write!(writer, "\"type\": \"unknown\""),
             }
         }
-        _ => {
-            write!(writer, "\"type\": \"unknown\"")?;
+        Type::Pointer(PointerType::Reference(pt) | PointerType::Raw(pt)) => {
+            serialize((pt.target)(), &[], defs, writer)
         }
+        _ => write!(writer, "\"type\": \"unknown\""),
     }
-
-    Ok(())
 }
 
 fn serialize_doc<W: Write>(doc: &[&str], writer: &mut W) -> Result<(), std::io::Error> {
     if !doc.is_empty() {
         let doc = doc.join("\n");
-        write!(writer, "\"description\": \"{}\",", doc.trim())?;
+        write!(writer, "\"description\": ")?;
+        write_string(doc.trim(), writer)?;
+        write!(writer, ",")?;
     }
     Ok(())
 }
 
+/// Writes `s` as a JSON string, with quotes and escapes.
+fn write_string<W: Write>(s: &str, writer: &mut W) -> std::io::Result<()> {
+    write!(writer, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(writer, "\\\"")?,
+            '\\' => write!(writer, "\\\\")?,
+            '\n' => write!(writer, "\\n")?,
+            '\r' => write!(writer, "\\r")?,
+            '\t' => write!(writer, "\\t")?,
+            c if c.is_control() => write!(writer, "\\u{:04x}", c as u32)?,
+            c => write!(writer, "{c}")?,
+        }
+    }
+    write!(writer, "\"")
+}
+
+/// Writes `items` as a JSON array of strings.
+fn write_string_array<'a, W: Write>(
+    items: impl IntoIterator<Item = &'a str>,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    write!(writer, "[")?;
+    for (i, item) in items.into_iter().enumerate() {
+        if i > 0 {
+            write!(writer, ",")?;
+        }
+        write_string(item, writer)?;
+    }
+    write!(writer, "]")
+}
+
 /// Serialize a scalar definition to JSON schema format.
 fn serialize_scalar<W: Write>(scalar_def: &ScalarDef, writer: &mut W) -> std::io::Result<()> {
     match scalar_def.affinity {
-        facet_core::ScalarAffinity::Number(number_affinity) => {
+        ScalarAffinity::Number(number_affinity) => {
             match number_affinity.bits {
                 facet_core::NumberBits::Integer { bits, sign } => {
                     write!(writer, "\"type\": \"integer\"")?;
@@ -169,93 +360,416 @@ fn serialize_scalar<W: Write>(scalar_def: &ScalarDef, writer: &mut W) -> std::io
                     write!(writer, "\"type\": \"number\"")?;
                     write!(writer, ", \"format\": \"double\"")?;
                 }
-                _ => unimplemented!(),
+                _ => {
+                    // Fixed-point and decimal numbers
+                    write!(writer, "\"type\": \"number\"")?;
+                }
             }
             Ok(())
         }
-        facet_core::ScalarAffinity::String(_) => {
+        ScalarAffinity::String(_)
+        | ScalarAffinity::Path(_)
+        | ScalarAffinity::Time(_)
+        | ScalarAffinity::ULID(_)
+        | ScalarAffinity::IpAddr(_)
+        | ScalarAffinity::SocketAddr(_) => {
             write!(writer, "\"type\": \"string\"")?;
             Ok(())
         }
-        facet_core::ScalarAffinity::Boolean(_) => {
+        ScalarAffinity::UUID(_) => {
+            write!(writer, "\"type\": \"string\", \"format\": \"uuid\"")?;
+            Ok(())
+        }
+        ScalarAffinity::Url(_) => {
+            write!(writer, "\"type\": \"string\", \"format\": \"uri\"")?;
+            Ok(())
+        }
+        ScalarAffinity::Char(_) => {
+            write!(
+                writer,
+                "\"type\": \"string\", \"minLength\": 1, \"maxLength\": 1"
+            )?;
+            Ok(())
+        }
+        ScalarAffinity::Boolean(_) => {
             write!(writer, "\"type\": \"boolean\"")?;
             Ok(())
         }
+        ScalarAffinity::Empty(_) => {
+            write!(writer, "\"type\": \"null\"")?;
+            Ok(())
+        }
         _ => Err(std::io::Error::other(format!(
-            "facet-jsonschema: nsupported scalar type: {scalar_def:#?}"
+            "facet-jsonschema: unsupported scalar type: {scalar_def:#?}"
         ))),
     }
 }
 
-fn serialize_struct<W: Write>(
-    struct_type: &facet_core::StructType,
+fn serialize_struct<'shape, W: Write>(
+    shape: &'shape Shape<'shape>,
+    struct_type: &'shape StructType<'shape>,
+    defs: &Definitions<'shape>,
     writer: &mut W,
 ) -> std::io::Result<()> {
-    write!(writer, "\"type\": \"object\",")?;
-    let required = struct_type
-        .fields
+    match struct_type.kind {
+        StructKind::Unit => write!(writer, "\"type\": \"null\""),
+        StructKind::Tuple | StructKind::TupleStruct => {
+            serialize_tuple(struct_type.fields, defs, writer)
+        }
+        _ => {
+            let mut properties = Properties::default();
+            properties.add_fields(struct_type, !shape.has_default_attr());
+            properties.serialize(shape.has_deny_unknown_fields_attr(), defs, writer)
+        }
+    }
+}
+
+/// The properties of an object, gathered from the fields of a struct or struct variant,
+/// and those of the structs flattened into it.
+#[derive(Default)]
+struct Properties<'shape> {
+    /// For internally tagged variants: the tag, and the name of the variant it holds
+    tag: Option<(&'shape str, &'shape str)>,
+
+    fields: Vec<&'shape Field<'shape>>,
+    required: Vec<&'shape str>,
+
+    /// Flattened fields that aren't structs, whose schema must hold alongside the
+    /// properties
+    flattened: Vec<&'shape Shape<'shape>>,
+}
+
+impl<'shape> Properties<'shape> {
+    /// Adds the fields of `struct_type`, which can only be left out of the input if
+    /// `required` is false or they have a default.
+    fn add_fields(&mut self, struct_type: &'shape StructType<'shape>, required: bool) {
+        for field in deserializable_fields(struct_type) {
+            let required = required && !field.flags.contains(FieldFlags::DEFAULT);
+            if field.flags.contains(FieldFlags::FLATTEN) {
+                let shape = field_shape(field);
+                match shape.ty {
+                    Type::User(UserType::Struct(ref inner)) if inner.kind == StructKind::Struct => {
+                        self.add_fields(inner, required && !shape.has_default_attr())
+                    }
+                    _ => self.flattened.push(shape),
+                }
+                continue;
+            }
+            if required {
+                self.required.push(field.name);
+            }
+            self.fields.push(field);
+        }
+    }
+
+    fn serialize<W: Write>(
+        &self,
+        deny_unknown_fields: bool,
+        defs: &Definitions<'shape>,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        write!(writer, "\"type\": \"object\",")?;
+        write!(writer, "\"required\": ")?;
+        let tag = self.tag.map(|(tag, _)| tag);
+        write_string_array(tag.into_iter().chain(self.required.iter().copied()), writer)?;
+        write!(writer, ",")?;
+        write!(writer, "\"properties\": {{")?;
+        let mut first = true;
+        if let Some((tag, name)) = self.tag {
+            first = false;
+            write_string(tag, writer)?;
+            write!(writer, ": {{\"const\": ")?;
+            write_string(name, writer)?;
+            write!(writer, "}}")?;
+        }
+        for field in &self.fields {
+            if !first {
+                write!(writer, ",")?;
+            }
+            first = false;
+            write_string(field.name, writer)?;
+            write!(writer, ": {{")?;
+            serialize(field_shape(field), field.doc, defs, writer)?;
+            write!(writer, "}}")?;
+        }
+        write!(writer, "}}")?;
+        if !self.flattened.is_empty() {
+            write!(writer, ",\"allOf\": [")?;
+            for (i, shape) in self.flattened.iter().enumerate() {
+                if i > 0 {
+                    write!(writer, ",")?;
+                }
+                write!(writer, "{{")?;
+                serialize(shape, &[], defs, writer)?;
+                write!(writer, "}}")?;
+            }
+            write!(writer, "]")?;
+        }
+        if deny_unknown_fields {
+            write!(writer, ",\"additionalProperties\": false")?;
+        }
+        Ok(())
+    }
+}
+
+/// Serialize the fields of a tuple or tuple struct to JSON schema format, as an array
+/// with one item per field.
+fn serialize_tuple<'shape, W: Write>(
+    fields: &'shape [Field<'shape>],
+    defs: &Definitions<'shape>,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    write!(writer, "\"type\": \"array\",")?;
+    write!(writer, "\"minItems\": {},", fields.len())?;
+    write!(writer, "\"maxItems\": {},", fields.len())?;
+    write!(writer, "\"prefixItems\": [")?;
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            write!(writer, ",")?;
+        }
+        write!(writer, "{{")?;
+        serialize(field_shape(field), field.doc, defs, writer)?;
+        write!(writer, "}}")?;
+    }
+    write!(writer, "]")?;
+    Ok(())
+}
+
+/// Serialize an enum to JSON schema format.
+///
+/// Enums with only unit variants that are written as their name become a string `enum`.
+/// Others become a `oneOf` with one schema per variant, following the enum's tagging. Since
+/// untagged variants may overlap, and the first one that matches wins, they use `anyOf`.
+fn serialize_enum<'shape, W: Write>(
+    shape: &'shape Shape<'shape>,
+    enum_type: &'shape EnumType<'shape>,
+    defs: &Definitions<'shape>,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    let tagging = shape.enum_tagging();
+    let unit_only = enum_type
+        .variants
         .iter()
-        .map(|f| format!("\"{}\"", f.name))
-        .collect::<Vec<_>>()
-        .join(",");
-    write!(writer, "\"required\": [{required}],")?;
-    write!(writer, "\"properties\": {{")?;
-    let mut first = true;
-    for field in struct_type.fields {
-        if !first {
+        .all(|variant| variant.data.fields.is_empty());
+    if unit_only && tagging == EnumTagging::External {
+        write!(writer, "\"type\": \"string\",")?;
+        write!(writer, "\"enum\": ")?;
+        write_string_array(enum_type.variants.iter().map(|v| v.name), writer)?;
+        return Ok(());
+    }
+
+    let keyword = match tagging {
+        EnumTagging::Untagged => "anyOf",
+        _ => "oneOf",
+    };
+    write!(writer, "\"{keyword}\": [")?;
+    for (i, variant) in enum_type.variants.iter().enumerate() {
+        if i > 0 {
             write!(writer, ",")?;
         }
-        first = false;
-        write!(writer, "\"{}\": {{", field.name)?;
-        serialize(field.shape(), field.doc, writer)?;
+        write!(writer, "{{")?;
+        serialize_variant(shape, variant, tagging, defs, writer)?;
         write!(writer, "}}")?;
     }
+    write!(writer, "]")?;
+    Ok(())
+}
+
+/// Serialize one variant of an enum to JSON schema format, including its tag.
+fn serialize_variant<'shape, W: Write>(
+    shape: &'shape Shape<'shape>,
+    variant: &'shape Variant<'shape>,
+    tagging: EnumTagging<'shape>,
+    defs: &Definitions<'shape>,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    serialize_doc(variant.doc, writer)?;
+    let has_content = !variant.data.fields.is_empty();
+    match tagging {
+        EnumTagging::External if !has_content => {
+            write!(writer, "\"const\": ")?;
+            write_string(variant.name, writer)?;
+        }
+        EnumTagging::External => {
+            write!(writer, "\"type\": \"object\",")?;
+            write!(writer, "\"required\": ")?;
+            write_string_array([variant.name], writer)?;
+            write!(writer, ",\"properties\": {{")?;
+            write_string(variant.name, writer)?;
+            write!(writer, ": {{")?;
+            serialize_variant_content(shape, variant, defs, writer)?;
+            write!(writer, "}}}},\"additionalProperties\": false")?;
+        }
+        EnumTagging::Internal { tag } => {
+            if variant.data.kind == StructKind::Struct {
+                // The fields go next to the tag
+                let mut properties = Properties {
+                    tag: Some((tag, variant.name)),
+                    ..Default::default()
+                };
+                properties.add_fields(&variant.data, !shape.has_default_attr());
+                properties.serialize(shape.has_deny_unknown_fields_attr(), defs, writer)?;
+            } else if has_content {
+                // Newtype variant: the fields of the inner struct go next to the tag
+                write_tag_property(tag, variant.name, writer)?;
+                write!(writer, ",\"allOf\": [{{")?;
+                serialize_variant_content(shape, variant, defs, writer)?;
+                write!(writer, "}}]")?;
+            } else {
+                let properties = Properties {
+                    tag: Some((tag, variant.name)),
+                    ..Default::default()
+                };
+                properties.serialize(shape.has_deny_unknown_fields_attr(), defs, writer)?;
+            }
+        }
+        EnumTagging::Adjacent { tag, content } => {
+            write!(writer, "\"type\": \"object\",")?;
+            write!(writer, "\"required\": ")?;
+            if has_content {
+                write_string_array([tag, content], writer)?;
+            } else {
+                write_string_array([tag], writer)?;
+            }
+            write!(writer, ",\"properties\": {{")?;
+            write_string(tag, writer)?;
+            write!(writer, ": {{\"const\": ")?;
+            write_string(variant.name, writer)?;
+            write!(writer, "}}")?;
+            if has_content {
+                write!(writer, ",")?;
+                write_string(content, writer)?;
+                write!(writer, ": {{")?;
+                serialize_variant_content(shape, variant, defs, writer)?;
+                write!(writer, "}}")?;
+            }
+            write!(writer, "}}")?;
+        }
+        EnumTagging::Untagged => {
+            if has_content {
+                serialize_variant_content(shape, variant, defs, writer)?;
+            } else {
+                write!(writer, "\"type\": \"null\"")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes an object schema whose only requirement is that `tag` holds `name`.
+fn write_tag_property<W: Write>(tag: &str, name: &str, writer: &mut W) -> std::io::Result<()> {
+    write!(writer, "\"type\": \"object\",")?;
+    write!(writer, "\"required\": ")?;
+    write_string_array([tag], writer)?;
+    write!(writer, ",\"properties\": {{")?;
+    write_string(tag, writer)?;
+    write!(writer, ": {{\"const\": ")?;
+    write_string(name, writer)?;
+    write!(writer, "}}}}")?;
+    Ok(())
+}
+
+/// Serialize what a non-unit variant holds, without its name: newtype variants as their
+/// inner value, tuple variants as arrays and struct variants as objects.
+fn serialize_variant_content<'shape, W: Write>(
+    shape: &'shape Shape<'shape>,
+    variant: &'shape Variant<'shape>,
+    defs: &Definitions<'shape>,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    let fields = variant.data.fields;
+    match variant.data.kind {
+        StructKind::Tuple | StructKind::TupleStruct if fields.len() == 1 => {
+            serialize(field_shape(&fields[0]), fields[0].doc, defs, writer)
+        }
+        StructKind::Tuple | StructKind::TupleStruct => serialize_tuple(fields, defs, writer),
+        _ => {
+            let mut properties = Properties::default();
+            properties.add_fields(&variant.data, !shape.has_default_attr());
+            properties.serialize(shape.has_deny_unknown_fields_attr(), defs, writer)
+        }
+    }
+}
+
+/// Serialize a map definition to JSON schema format.
+fn serialize_map<'shape, W: Write>(
+    map_def: facet_core::MapDef<'shape>,
+    defs: &Definitions<'shape>,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    write!(writer, "\"type\": \"object\",")?;
+    write!(writer, "\"additionalProperties\": {{")?;
+    serialize(map_def.v(), &[], defs, writer)?;
+    write!(writer, "}}")?;
+    Ok(())
+}
+
+/// Serialize a set definition to JSON schema format.
+fn serialize_set<'shape, W: Write>(
+    set_def: facet_core::SetDef<'shape>,
+    defs: &Definitions<'shape>,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    write!(writer, "\"type\": \"array\",")?;
+    write!(writer, "\"uniqueItems\": true,")?;
+    write!(writer, "\"items\": {{")?;
+    serialize(set_def.t(), &[], defs, writer)?;
     write!(writer, "}}")?;
     Ok(())
 }
 
 /// Serialize a list definition to JSON schema format.
-fn serialize_list<W: Write>(list_def: facet_core::ListDef, writer: &mut W) -> std::io::Result<()> {
+fn serialize_list<'shape, W: Write>(
+    list_def: facet_core::ListDef<'shape>,
+    defs: &Definitions<'shape>,
+    writer: &mut W,
+) -> std::io::Result<()> {
     write!(writer, "\"type\": \"array\",")?;
     write!(writer, "\"items\": {{")?;
-    serialize(list_def.t(), &[], writer)?;
+    serialize(list_def.t(), &[], defs, writer)?;
     write!(writer, "}}")?;
     Ok(())
 }
 
 /// Serialize a slice definition to JSON schema format.
-fn serialize_slice<W: Write>(
-    slice_def: facet_core::SliceDef,
+fn serialize_slice<'shape, W: Write>(
+    slice_def: facet_core::SliceDef<'shape>,
+    defs: &Definitions<'shape>,
     writer: &mut W,
 ) -> std::io::Result<()> {
     write!(writer, "\"type\": \"array\",")?;
     write!(writer, "\"items\": {{")?;
-    serialize(slice_def.t(), &[], writer)?;
+    serialize(slice_def.t(), &[], defs, writer)?;
     write!(writer, "}}")?;
     Ok(())
 }
 
 /// Serialize an array definition to JSON schema format.
-fn serialize_array<W: Write>(
-    array_def: facet_core::ArrayDef,
+fn serialize_array<'shape, W: Write>(
+    array_def: facet_core::ArrayDef<'shape>,
+    defs: &Definitions<'shape>,
     writer: &mut W,
 ) -> std::io::Result<()> {
     write!(writer, "\"type\": \"array\",")?;
     write!(writer, "\"minItems\": {},", array_def.n)?;
     write!(writer, "\"maxItems\": {},", array_def.n)?;
     write!(writer, "\"items\": {{")?;
-    serialize(array_def.t(), &[], writer)?;
+    serialize(array_def.t(), &[], defs, writer)?;
     write!(writer, "}}")?;
     Ok(())
 }
 
-/// Serialize an option definition to JSON schema format.
-fn serialize_option<W: Write>(
-    _option_def: facet_core::OptionDef,
+/// Serialize an option definition to JSON schema format, as either `null` or the inner
+/// value.
+fn serialize_option<'shape, W: Write>(
+    option_def: facet_core::OptionDef<'shape>,
+    defs: &Definitions<'shape>,
     writer: &mut W,
 ) -> std::io::Result<()> {
-    write!(writer, "\"type\": \"[]\",")?;
-    unimplemented!("serialize_option");
+    write!(writer, "\"anyOf\": [{{\"type\": \"null\"}},{{")?;
+    serialize(option_def.t(), &[], defs, writer)?;
+    write!(writer, "}}]")?;
+    Ok(())
 }
 
 #[cfg(test)]
@@ -303,4 +817,141 @@ mod tests {
         let schema = to_string::<TestStruct>();
         assert_snapshot!(schema);
     }
+
+    #[test]
+    fn test_enums() {
+        #[derive(Facet)]
+        #[repr(u8)]
+        #[allow(dead_code)]
+        enum Level {
+            Low,
+            #[facet(rename = "mid")]
+            Medium,
+            High,
+        }
+
+        #[derive(Facet)]
+        #[repr(u8)]
+        #[allow(dead_code)]
+        enum Shape {
+            /// No shape at all
+            Empty,
+            Circle(f64),
+            Point(i32, i32),
+            Rect {
+                width: u32,
+                height: u32,
+            },
+        }
+
+        #[derive(Facet)]
+        #[facet(tag = "type")]
+        #[repr(u8)]
+        #[allow(dead_code)]
+        enum Internal {
+            Ping,
+            Move { x: i32 },
+        }
+
+        #[derive(Facet)]
+        #[facet(tag = "t", content = "c")]
+        #[repr(u8)]
+        #[allow(dead_code)]
+        enum Adjacent {
+            Ping,
+            Text(String),
+        }
+
+        #[derive(Facet)]
+        #[facet(untagged)]
+        #[repr(u8)]
+        #[allow(dead_code)]
+        enum Untagged {
+            Number(u64),
+            Named { name: String },
+        }
+
+        #[derive(Facet)]
+        struct Enums {
+            level: Level,
+            shape: Shape,
+            internal: Internal,
+            adjacent: Adjacent,
+            untagged: Untagged,
+        }
+
+        let schema = to_string::<Enums>();
+        assert_snapshot!(schema);
+    }
+
+    #[test]
+    fn test_collections() {
+        use alloc::collections::BTreeSet;
+        use std::collections::HashMap;
+
+        #[derive(Facet)]
+        struct Collections {
+            map: HashMap<String, u8>,
+            set: BTreeSet<String>,
+            tuple: (u8, String),
+            option: Option<bool>,
+            unit: (),
+        }
+
+        let schema = to_string::<Collections>();
+        assert_snapshot!(schema);
+    }
+
+    #[test]
+    fn test_defs() {
+        /// A node in a tree
+        #[derive(Facet)]
+        struct Node {
+            name: String,
+            children: Vec<Arc<Node>>,
+            color: Option<Box<Color>>,
+        }
+
+        #[derive(Facet)]
+        struct Color(u8, u8, u8);
+
+        #[derive(Facet)]
+        struct Tree {
+            root: Node,
+            background: Color,
+        }
+
+        let schema = to_string::<Tree>();
+        assert_snapshot!(schema);
+
+        // The root refers to itself as `#`
+        let schema = to_string::<Node>();
+        assert_snapshot!(schema);
+    }
+
+    #[test]
+    fn test_field_attributes() {
+        #[derive(Facet)]
+        struct Limits {
+            max: u32,
+        }
+
+        #[derive(Facet)]
+        #[facet(deny_unknown_fields, rename_all = "camelCase")]
+        struct Settings {
+            /// The "display" name
+            display_name: String,
+            #[facet(rename = "TTL")]
+            ttl: u32,
+            #[facet(default = false)]
+            verbose: bool,
+            #[facet(skip_deserializing, default = Vec::new())]
+            cache: Vec<u8>,
+            #[facet(flatten)]
+            limits: Limits,
+        }
+
+        let schema = to_string::<Settings>();
+        assert_snapshot!(schema);
+    }
 }
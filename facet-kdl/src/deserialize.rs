@@ -0,0 +1,955 @@
+//! Read KDL documents into Rust values.
+
+use std::{borrow::Cow, ops::Range};
+
+use facet_core::{
+    Def, EnumTagging, Facet, Field, FieldAttribute, FieldFlags, Shape, StructKind, Type, UserType,
+    Variant,
+};
+use facet_deserialize::{
+    DeserError, DeserErrorKind, Expectation, Format, NextData, NextResult, Outcome, Scalar, Span,
+    Spannable, Spanned,
+};
+use facet_reflect::Wip;
+use kdl::{KdlDocument, KdlEntry, KdlError, KdlIdentifier, KdlNode, KdlValue};
+use log::trace;
+
+/// Deserialize a value of type `T` from a KDL string.
+///
+/// `T` must be a struct: the nodes of the document are its fields. In each node, arguments
+/// go to the fields marked `#[facet(argument)]` in the order they're declared, and then to
+/// the list in the field marked `#[facet(arguments)]`. Properties and child nodes go to the
+/// fields they're named after, with every node of a list field being one of its items.
+///
+/// Errors point at where in the input they come from, and strings written without escapes
+/// can be borrowed as `&str`.
+///
+/// # Example
+/// ```ignore
+/// #[derive(Facet)]
+/// struct Config {
+///     #[facet(child)]
+///     server: Server,
+/// }
+///
+/// #[derive(Facet)]
+/// struct Server {
+///     #[facet(argument)]
+///     host: String,
+///     port: u16,
+/// }
+///
+/// let config: Config = from_str("server localhost port=8080")?;
+/// ```
+pub fn from_str<'input, 'facet, 'shape, T: Facet<'facet>>(
+    kdl: &'input str,
+) -> Result<T, DeserError<'input, 'shape>>
+where
+    'input: 'facet,
+{
+    log::trace!("Entering `from_str` function");
+
+    let input = kdl.as_bytes();
+    let document: KdlDocument = match kdl.parse() {
+        Ok(document) => document,
+        Err(err) => {
+            let err = parse_error(err, kdl);
+            return Err(DeserError::new(err.node, input, err.span, "kdl"));
+        }
+    };
+    log::trace!("KDL parsed");
+
+    facet_deserialize::deserialize(
+        input,
+        Kdl {
+            document: Document::new(kdl, &document),
+            cursors: vec![Cursor::default()],
+        },
+    )
+}
+
+/// The error for a document `kdl-rs` failed to parse, pointing at its first problem.
+fn parse_error(err: KdlError, kdl: &str) -> Spanned<DeserErrorKind<'static>> {
+    let Some(diagnostic) = err.diagnostics.into_iter().next() else {
+        return DeserErrorKind::InvalidSyntax("invalid KDL document".to_owned())
+            .with_span(Span::new(0, kdl.len()));
+    };
+    let message = diagnostic
+        .message
+        .or(diagnostic.label)
+        .unwrap_or_else(|| "invalid KDL document".to_owned());
+    let span = Span::new(diagnostic.span.offset(), diagnostic.span.len());
+    DeserErrorKind::InvalidSyntax(message).with_span(span)
+}
+
+/// The KDL format.
+///
+/// Where the entries of a node go depends on the fields they're deserialized into, so each
+/// outcome is worked out when the deserializer asks for it, from what it expects and the
+/// shape of the value it's building. The cursor into the document is kept for every read, so
+/// that the deserializer can go back to any of them.
+struct Kdl {
+    document: Document,
+    /// The cursor before each read, by how many were read before it
+    cursors: Vec<Cursor>,
+}
+
+impl Kdl {
+    /// The cursor to read from, after `reads` reads. Those after it won't be come back to.
+    fn resume(&mut self, reads: usize) -> Cursor {
+        self.cursors.truncate(reads + 1);
+        self.cursors[reads].clone()
+    }
+}
+
+impl Format for Kdl {
+    type Input<'input> = [u8];
+
+    fn source(&self) -> &'static str {
+        "kdl"
+    }
+
+    fn next<'input, 'facet, 'shape>(
+        &mut self,
+        nd: NextData<'input, 'facet, 'shape>,
+        expectation: Expectation,
+    ) -> NextResult<'input, 'facet, 'shape, Spanned<Outcome<'input>>, Spanned<DeserErrorKind<'shape>>>
+    where
+        'shape: 'input,
+    {
+        let mut cursor = self.resume(nd.reads());
+        let res = cursor.next(&self.document, nd.input(), &nd.wip, expectation);
+        self.cursors.push(cursor);
+        (nd, res)
+    }
+
+    fn skip<'input, 'facet, 'shape>(
+        &mut self,
+        nd: NextData<'input, 'facet, 'shape>,
+    ) -> NextResult<'input, 'facet, 'shape, Span, Spanned<DeserErrorKind<'shape>>>
+    where
+        'shape: 'input,
+    {
+        let mut cursor = self.resume(nd.reads());
+        let res = cursor.skip(&self.document, nd.input());
+        self.cursors.push(cursor);
+        (nd, res)
+    }
+}
+
+/// The nodes and entries of a document, each laid out next to its siblings.
+struct Document {
+    nodes: Vec<Node>,
+    entries: Vec<Entry>,
+    /// The top-level nodes, in `nodes`
+    top: Range<usize>,
+    /// The length of the input
+    len: usize,
+}
+
+/// A node, with where its parts are in the input.
+struct Node {
+    name: Text,
+    name_span: Span,
+    /// Its arguments and properties, in [`Document::entries`]
+    entries: Range<usize>,
+    /// Its child nodes, in [`Document::nodes`], if it has a children block
+    children: Option<Range<usize>>,
+    /// Where it ends, leaving out the whitespace after it
+    end: usize,
+}
+
+/// An argument, or a property when it has a name.
+struct Entry {
+    name: Option<(Text, Span)>,
+    value: Value,
+    /// Where the value is, leaving out the name of a property
+    span: Span,
+}
+
+enum Value {
+    String(Text),
+    Integer(i128),
+    Float(f64),
+    Bool(bool),
+    Null,
+}
+
+/// A string of the document: where it's written as is in the input, or what it reads as
+/// once its escapes are resolved.
+enum Text {
+    Input(Span),
+    Owned(String),
+}
+
+impl Text {
+    /// The text `value`, written at `span` of `kdl` as an identifier or a string.
+    fn new(kdl: &str, span: Span, value: &str) -> Self {
+        let written = kdl
+            .get(span.start()..span.end())
+            .unwrap_or_default()
+            .trim_start_matches('#')
+            .trim_end_matches('#');
+        let quoted = written
+            .strip_prefix('"')
+            .and_then(|written| written.strip_suffix('"'));
+        match quoted {
+            Some(inner) if inner == value => {
+                let start = span.start() + kdl[span.start()..].find('"').unwrap_or(0) + 1;
+                Text::Input(Span::new(start, value.len()))
+            }
+            None if written == value => Text::Input(span),
+            _ => Text::Owned(value.to_owned()),
+        }
+    }
+
+    fn read<'input>(&self, input: &'input [u8]) -> Cow<'input, str> {
+        match self {
+            Text::Input(span) => String::from_utf8_lossy(&input[span.start()..span.end()]),
+            Text::Owned(text) => Cow::Owned(text.clone()),
+        }
+    }
+
+    fn is(&self, other: &Text, input: &[u8]) -> bool {
+        self.read(input) == other.read(input)
+    }
+}
+
+impl Document {
+    fn new(kdl: &str, document: &KdlDocument) -> Self {
+        let mut this = Document {
+            nodes: Vec::new(),
+            entries: Vec::new(),
+            top: 0..0,
+            len: kdl.len(),
+        };
+        // Siblings go next to each other, and their children after them
+        let mut pending: Vec<(&[KdlNode], Option<usize>)> = vec![(document.nodes(), None)];
+        while let Some((nodes, parent)) = pending.pop() {
+            let start = this.nodes.len();
+            for node in nodes {
+                let entries_start = this.entries.len();
+                for entry in node.entries() {
+                    this.entries.push(Entry::new(kdl, entry));
+                }
+                let name_span = identifier_span(node.name());
+                this.nodes.push(Node {
+                    name: Text::new(kdl, name_span, node.name().value()),
+                    name_span,
+                    entries: entries_start..this.entries.len(),
+                    children: None,
+                    end: node_end(kdl, node),
+                });
+                if let Some(children) = node.children() {
+                    pending.push((children.nodes(), Some(this.nodes.len() - 1)));
+                }
+            }
+            let range = start..this.nodes.len();
+            match parent {
+                Some(parent) => this.nodes[parent].children = Some(range),
+                None => this.top = range,
+            }
+        }
+        this
+    }
+
+    /// The entries among `entries` that are arguments rather than properties.
+    fn arguments(&self, entries: Range<usize>) -> impl Iterator<Item = usize> + '_ {
+        entries.filter(|&index| self.entries[index].name.is_none())
+    }
+}
+
+impl Entry {
+    fn new(kdl: &str, entry: &KdlEntry) -> Self {
+        let span = value_span(kdl, entry);
+        let value = match entry.value() {
+            KdlValue::String(s) => Value::String(Text::new(kdl, span, s)),
+            KdlValue::Integer(n) => Value::Integer(*n),
+            KdlValue::Float(n) => Value::Float(*n),
+            KdlValue::Bool(b) => Value::Bool(*b),
+            KdlValue::Null => Value::Null,
+        };
+        let name = entry.name().map(|name| {
+            let name_span = identifier_span(name);
+            (Text::new(kdl, name_span, name.value()), name_span)
+        });
+        Entry { name, value, span }
+    }
+}
+
+/// Where the deserializer is in the document.
+#[derive(Clone, Default)]
+struct Cursor {
+    /// The objects and lists it's in, innermost last
+    frames: Vec<Frame>,
+    /// The value of the key that was just read
+    pending: Option<Pending>,
+    /// Whether the document was started
+    started: bool,
+}
+
+#[derive(Clone)]
+enum Frame {
+    /// The entries and children of a node, or the nodes of the document, as the fields of a
+    /// struct or enum variant. With `map`, only its children count, as the entries of a map.
+    Object {
+        /// The node, unless it's the document
+        node: Option<usize>,
+        map: bool,
+        /// The next entry
+        entry: usize,
+        /// How many arguments were read
+        arguments: usize,
+        /// Whether the key of the `#[facet(arguments)]` field was read
+        rest: bool,
+        /// The next child
+        child: usize,
+    },
+    /// The variant of an externally tagged enum, as the only child of `node`.
+    Variant { node: usize, keyed: bool },
+    /// Arguments, from `entry` on, as the items of a list.
+    Arguments { entries: Range<usize>, entry: usize },
+    /// The nodes among `siblings` named like `first`, as the items of a list.
+    Nodes {
+        siblings: Range<usize>,
+        first: usize,
+        next: usize,
+    },
+}
+
+/// A value, once its key is read.
+#[derive(Clone)]
+enum Pending {
+    Entry(usize),
+    /// The arguments from `entry` on, as the items of the `#[facet(arguments)]` field
+    Rest {
+        node: usize,
+        entry: usize,
+    },
+    /// A child node, along with its siblings named the same when it's a list field
+    Child {
+        siblings: Range<usize>,
+        node: usize,
+    },
+    /// The node holding the fields of a variant
+    Variant(usize),
+}
+
+type KdlResult<'shape, T> = Result<T, Spanned<DeserErrorKind<'shape>>>;
+
+impl Cursor {
+    fn next<'input, 'shape>(
+        &mut self,
+        doc: &Document,
+        input: &'input [u8],
+        wip: &Wip<'_, 'shape>,
+        expectation: Expectation,
+    ) -> KdlResult<'shape, Spanned<Outcome<'input>>>
+    where
+        'shape: 'input,
+    {
+        match expectation {
+            Expectation::ObjectKeyOrObjectClose => self.key_or_close(doc, input, wip),
+            Expectation::ListItemOrListClose => self.item_or_close(doc, input, wip),
+            Expectation::Value | Expectation::ObjectVal => self.value(doc, input, wip),
+        }
+    }
+
+    /// The value of the key just read, or the next item of the list it's in.
+    fn value<'input, 'shape>(
+        &mut self,
+        doc: &Document,
+        input: &'input [u8],
+        wip: &Wip<'_, 'shape>,
+    ) -> KdlResult<'shape, Spanned<Outcome<'input>>> {
+        if let Some(pending) = self.pending.take() {
+            return match pending {
+                Pending::Entry(entry) => scalar(&doc.entries[entry], input),
+                Pending::Rest { node, entry } => {
+                    let entries = entry..doc.nodes[node].entries.end;
+                    let start = doc
+                        .entries
+                        .get(entry)
+                        .map_or_else(|| arguments_end(doc, node), |entry| entry.span.start());
+                    self.frames.push(Frame::Arguments { entries, entry });
+                    Ok(Outcome::ListStarted.with_span(Span::new(start, 0)))
+                }
+                Pending::Child { siblings, node } if is_list(wip.shape()) => {
+                    let start = doc.nodes[node].name_span.start();
+                    self.frames.push(Frame::Nodes {
+                        siblings,
+                        first: node,
+                        next: node,
+                    });
+                    Ok(Outcome::ListStarted.with_span(Span::new(start, 0)))
+                }
+                Pending::Child { node, .. } => {
+                    self.node(doc, input, node, wip.shape(), wip.selected_variant())
+                }
+                Pending::Variant(node) => self.variant(doc, input, node, wip),
+            };
+        }
+
+        match self.frames.last_mut() {
+            // Going back to an item, as for untagged enums, reads it as a value
+            Some(Frame::Arguments { .. } | Frame::Nodes { .. }) => {
+                match self.item(doc, input, wip.shape(), wip.selected_variant())? {
+                    Some(outcome) => Ok(outcome),
+                    None => Err(eof(doc)),
+                }
+            }
+            Some(_) => Err(eof(doc)),
+            None if self.started => Err(eof(doc)),
+            None => {
+                self.started = true;
+                let shape = wip.shape();
+                let Type::User(UserType::Struct(_)) = shape.ty else {
+                    return Err(DeserErrorKind::UnsupportedType {
+                        got: shape,
+                        wanted: "struct",
+                    }
+                    .with_span(Span::new(0, 0)));
+                };
+                self.frames.push(Frame::Object {
+                    node: None,
+                    map: false,
+                    entry: 0,
+                    arguments: 0,
+                    rest: true,
+                    child: doc.top.start,
+                });
+                Ok(Outcome::ObjectStarted.with_span(Span::new(0, 0)))
+            }
+        }
+    }
+
+    /// A node, as a value of type `shape`.
+    fn node<'input, 'shape>(
+        &mut self,
+        doc: &Document,
+        input: &'input [u8],
+        index: usize,
+        shape: &'shape Shape<'shape>,
+        selected: Option<Variant<'shape>>,
+    ) -> KdlResult<'shape, Spanned<Outcome<'input>>> {
+        let node = &doc.nodes[index];
+        let shape = match shape.def {
+            Def::Option(option_def) => {
+                if node.entries.is_empty() && node.children.is_none() {
+                    return Ok(Outcome::Scalar(Scalar::Null).with_span(node.name_span));
+                }
+                option_def.t()
+            }
+            _ => shape,
+        };
+
+        if is_list(shape) {
+            return Ok(self.arguments(doc, index));
+        }
+        if let Def::Map(_) = shape.def {
+            return Ok(self.object(doc, index, true));
+        }
+        match shape.ty {
+            Type::User(UserType::Struct(struct_type)) => match struct_type.kind {
+                StructKind::Tuple | StructKind::TupleStruct => Ok(self.arguments(doc, index)),
+                _ => Ok(self.object(doc, index, false)),
+            },
+            Type::User(UserType::Enum(_)) if shape.enum_tagging() == EnumTagging::External => {
+                match node
+                    .children
+                    .as_ref()
+                    .filter(|children| !children.is_empty())
+                {
+                    Some(children) => {
+                        let child = children.start;
+                        self.frames.push(Frame::Variant {
+                            node: index,
+                            keyed: false,
+                        });
+                        Ok(Outcome::ObjectStarted.with_span(doc.nodes[child].name_span))
+                    }
+                    // A unit variant, by name
+                    None => argument(doc, input, index, shape),
+                }
+            }
+            // The variant of other enums is found from their fields, until it's selected
+            Type::User(UserType::Enum(_)) => match selected.map(|variant| variant.data.kind) {
+                Some(StructKind::Unit) => {
+                    Ok(Outcome::Scalar(Scalar::Null).with_span(node.name_span))
+                }
+                Some(StructKind::Tuple | StructKind::TupleStruct) => Ok(self.arguments(doc, index)),
+                _ => Ok(self.object(doc, index, false)),
+            },
+            _ => argument(doc, input, index, shape),
+        }
+    }
+
+    /// The only child of the node at `index`, as the content of the variant it's named
+    /// after.
+    fn variant<'input, 'shape>(
+        &mut self,
+        doc: &Document,
+        input: &'input [u8],
+        index: usize,
+        wip: &Wip<'_, 'shape>,
+    ) -> KdlResult<'shape, Spanned<Outcome<'input>>> {
+        let child = doc.nodes[index]
+            .children
+            .as_ref()
+            .map_or(index, |c| c.start);
+        match wip.selected_variant().map(|variant| variant.data.kind) {
+            Some(StructKind::Unit) => {
+                Ok(Outcome::Scalar(Scalar::Null).with_span(doc.nodes[child].name_span))
+            }
+            Some(StructKind::Tuple | StructKind::TupleStruct) => Ok(self.arguments(doc, child)),
+            Some(_) => Ok(self.object(doc, child, false)),
+            // Newtype variants are their field
+            None => self.node(doc, input, child, wip.shape(), None),
+        }
+    }
+
+    /// Starts reading a node as a struct, or as a map with `map`.
+    fn object<'input>(
+        &mut self,
+        doc: &Document,
+        index: usize,
+        map: bool,
+    ) -> Spanned<Outcome<'input>> {
+        self.frames.push(Frame::Object {
+            node: Some(index),
+            map,
+            entry: 0,
+            arguments: 0,
+            rest: false,
+            child: 0,
+        });
+        Outcome::ObjectStarted.with_span(doc.nodes[index].name_span)
+    }
+
+    /// Starts reading the arguments of a node as a list.
+    fn arguments<'input>(&mut self, doc: &Document, index: usize) -> Spanned<Outcome<'input>> {
+        let entries = doc.nodes[index].entries.clone();
+        let start = doc.arguments(entries.clone()).next().map_or_else(
+            || arguments_end(doc, index),
+            |entry| doc.entries[entry].span.start(),
+        );
+        self.frames.push(Frame::Arguments {
+            entry: entries.start,
+            entries,
+        });
+        Outcome::ListStarted.with_span(Span::new(start, 0))
+    }
+
+    /// The next key of the object being read, or its end.
+    fn key_or_close<'input, 'shape>(
+        &mut self,
+        doc: &Document,
+        input: &'input [u8],
+        wip: &Wip<'_, 'shape>,
+    ) -> KdlResult<'shape, Spanned<Outcome<'input>>>
+    where
+        'shape: 'input,
+    {
+        let shape = wip.shape();
+        let fields = match shape.ty {
+            Type::User(UserType::Struct(struct_type)) => struct_type.fields,
+            Type::User(UserType::Enum(_)) => wip
+                .selected_variant()
+                .map_or(&[][..], |variant| variant.data.fields),
+            _ => &[],
+        };
+
+        match self.frames.last_mut() {
+            Some(Frame::Object {
+                node,
+                map,
+                entry,
+                arguments,
+                rest,
+                child,
+            }) => {
+                let (entries, children, end) = match *node {
+                    Some(index) => {
+                        let node = &doc.nodes[index];
+                        let entries = if *map { 0..0 } else { node.entries.clone() };
+                        (entries, node.children.clone(), node.end)
+                    }
+                    None => (0..0, Some(doc.top.clone()), doc.len),
+                };
+
+                // Its entries come first: properties by name, arguments by position
+                let argument_fields = if *map {
+                    Vec::new()
+                } else {
+                    argument_fields_of(fields)
+                };
+                let arguments_field = arguments_field_of(fields).filter(|_| !*map);
+                *entry = (*entry).max(entries.start);
+                while *entry < entries.end {
+                    let index = *entry;
+                    *entry += 1;
+                    let item = &doc.entries[index];
+                    if let Some((name, name_span)) = &item.name {
+                        self.pending = Some(Pending::Entry(index));
+                        return Ok(key(name.read(input), *name_span));
+                    }
+                    let position = *arguments;
+                    *arguments += 1;
+                    let value_start = Span::new(item.span.start(), 0);
+                    if let Some(field) = argument_fields.get(position) {
+                        self.pending = Some(Pending::Entry(index));
+                        return Ok(key(Cow::Borrowed(field.name), value_start));
+                    }
+                    match arguments_field {
+                        // The others go along with the first
+                        Some(_) if *rest => {}
+                        Some(field) => {
+                            *rest = true;
+                            self.pending = Some(Pending::Rest {
+                                node: node.unwrap_or_default(),
+                                entry: index,
+                            });
+                            return Ok(key(Cow::Borrowed(field.name), value_start));
+                        }
+                        None if shape.has_deny_unknown_fields_attr() => {
+                            return Err(DeserErrorKind::UnknownField {
+                                field_name: format!("argument {}", position + 1),
+                                shape,
+                            }
+                            .with_span(item.span));
+                        }
+                        None => trace!("Ignoring argument {} of {}", position + 1, shape),
+                    }
+                }
+                if let (Some(field), Some(index), false) = (arguments_field, *node, *rest) {
+                    // There are no arguments left over, but the list is still there
+                    *rest = true;
+                    self.pending = Some(Pending::Rest {
+                        node: index,
+                        entry: entries.end,
+                    });
+                    let start = arguments_end(doc, index);
+                    return Ok(key(Cow::Borrowed(field.name), Span::new(start, 0)));
+                }
+
+                // And then its children, the nodes of list fields all at once
+                if let Some(children) = children {
+                    *child = (*child).max(children.start);
+                    while *child < children.end {
+                        let index = *child;
+                        *child += 1;
+                        let item = &doc.nodes[index];
+                        let listed = !*map
+                            && find_field(fields, &item.name.read(input))
+                                .is_none_or(|field| is_list(field.shape()));
+                        if listed
+                            && (children.start..index)
+                                .any(|sibling| doc.nodes[sibling].name.is(&item.name, input))
+                        {
+                            continue;
+                        }
+                        self.pending = Some(Pending::Child {
+                            siblings: children.clone(),
+                            node: index,
+                        });
+                        return Ok(key(item.name.read(input), item.name_span));
+                    }
+                }
+
+                self.frames.pop();
+                Ok(Outcome::ObjectEnded.with_span(Span::new(end, 0)))
+            }
+            Some(Frame::Variant { node, keyed }) => {
+                let node = *node;
+                if *keyed {
+                    self.frames.pop();
+                    return Ok(Outcome::ObjectEnded.with_span(Span::new(doc.nodes[node].end, 0)));
+                }
+                *keyed = true;
+                let child = doc.nodes[node].children.as_ref().map_or(node, |c| c.start);
+                self.pending = Some(Pending::Variant(node));
+                let child = &doc.nodes[child];
+                Ok(key(child.name.read(input), child.name_span))
+            }
+            _ => Err(eof(doc)),
+        }
+    }
+
+    /// The next item of the list being read, or its end.
+    fn item_or_close<'input, 'shape>(
+        &mut self,
+        doc: &Document,
+        input: &'input [u8],
+        wip: &Wip<'_, 'shape>,
+    ) -> KdlResult<'shape, Spanned<Outcome<'input>>> {
+        let item_shape = item_shape(wip.shape()).unwrap_or(wip.shape());
+        if let Some(outcome) = self.item(doc, input, item_shape, None)? {
+            return Ok(outcome);
+        }
+        let end = match self.frames.pop() {
+            Some(Frame::Arguments { entries, .. }) => doc
+                .arguments(entries)
+                .last()
+                .map_or(0, |entry| doc.entries[entry].span.end()),
+            Some(Frame::Nodes {
+                siblings, first, ..
+            }) => siblings
+                .rev()
+                .find(|&sibling| doc.nodes[sibling].name.is(&doc.nodes[first].name, input))
+                .map_or(0, |sibling| doc.nodes[sibling].end),
+            _ => return Err(eof(doc)),
+        };
+        Ok(Outcome::ListEnded.with_span(Span::new(end, 0)))
+    }
+
+    /// The next item of the list being read, as a value of type `shape`, if there is one.
+    fn item<'input, 'shape>(
+        &mut self,
+        doc: &Document,
+        input: &'input [u8],
+        shape: &'shape Shape<'shape>,
+        selected: Option<Variant<'shape>>,
+    ) -> KdlResult<'shape, Option<Spanned<Outcome<'input>>>> {
+        match self.frames.last_mut() {
+            Some(Frame::Arguments { entries, entry }) => {
+                let Some(index) = doc.arguments(*entry..entries.end).next() else {
+                    return Ok(None);
+                };
+                *entry = index + 1;
+                scalar(&doc.entries[index], input).map(Some)
+            }
+            Some(Frame::Nodes {
+                siblings,
+                first,
+                next,
+            }) => {
+                let name = &doc.nodes[*first].name;
+                let Some(index) =
+                    (*next..siblings.end).find(|&sibling| doc.nodes[sibling].name.is(name, input))
+                else {
+                    return Ok(None);
+                };
+                *next = index + 1;
+                self.node(doc, input, index, shape, selected).map(Some)
+            }
+            _ => Err(eof(doc)),
+        }
+    }
+
+    /// Skips the value of the key just read, or the next item of the list being read.
+    fn skip<'shape>(&mut self, doc: &Document, input: &[u8]) -> KdlResult<'shape, Span> {
+        let span = match self.pending.take() {
+            Some(Pending::Entry(entry)) => doc.entries[entry].span,
+            Some(Pending::Rest { node, entry }) => {
+                let start = doc
+                    .entries
+                    .get(entry)
+                    .map_or(arguments_end(doc, node), |entry| entry.span.start());
+                Span::new(start, arguments_end(doc, node).saturating_sub(start))
+            }
+            Some(Pending::Child { node, .. } | Pending::Variant(node)) => node_span(doc, node),
+            None => match self.frames.last_mut() {
+                Some(Frame::Arguments { entries, entry }) => {
+                    let Some(index) = doc.arguments(*entry..entries.end).next() else {
+                        return Err(eof(doc));
+                    };
+                    *entry = index + 1;
+                    doc.entries[index].span
+                }
+                Some(Frame::Nodes {
+                    siblings,
+                    first,
+                    next,
+                }) => {
+                    let name = &doc.nodes[*first].name;
+                    let Some(index) = (*next..siblings.end)
+                        .find(|&sibling| doc.nodes[sibling].name.is(name, input))
+                    else {
+                        return Err(eof(doc));
+                    };
+                    *next = index + 1;
+                    node_span(doc, index)
+                }
+                _ => return Err(eof(doc)),
+            },
+        };
+        Ok(span)
+    }
+}
+
+fn key<'input>(name: Cow<'input, str>, span: Span) -> Spanned<Outcome<'input>> {
+    Outcome::Scalar(Scalar::String(name)).with_span(span)
+}
+
+/// The value of an argument or property.
+fn scalar<'input, 'shape>(
+    entry: &Entry,
+    input: &'input [u8],
+) -> KdlResult<'shape, Spanned<Outcome<'input>>> {
+    let scalar = match &entry.value {
+        Value::String(text) => Scalar::String(text.read(input)),
+        Value::Integer(n) => {
+            if let Ok(n) = u64::try_from(*n) {
+                Scalar::U64(n)
+            } else if let Ok(n) = i64::try_from(*n) {
+                Scalar::I64(n)
+            } else {
+                return Err(DeserErrorKind::NumberOutOfRange(*n as f64).with_span(entry.span));
+            }
+        }
+        Value::Float(n) => Scalar::F64(*n),
+        Value::Bool(b) => Scalar::Bool(*b),
+        Value::Null => Scalar::Null,
+    };
+    Ok(Outcome::Scalar(scalar).with_span(entry.span))
+}
+
+/// A node holding a single value, as its first argument.
+fn argument<'input, 'shape>(
+    doc: &Document,
+    input: &'input [u8],
+    index: usize,
+    shape: &'shape Shape<'shape>,
+) -> KdlResult<'shape, Spanned<Outcome<'input>>> {
+    let node = &doc.nodes[index];
+    match doc.arguments(node.entries.clone()).next() {
+        Some(entry) => scalar(&doc.entries[entry], input),
+        None => Err(DeserErrorKind::MissingValue {
+            expected: "argument",
+            field: format!("{} ({shape})", node.name.read(input)),
+        }
+        .with_span(node.name_span)),
+    }
+}
+
+fn eof<'shape>(doc: &Document) -> Spanned<DeserErrorKind<'shape>> {
+    DeserErrorKind::UnexpectedEof {
+        wanted: "any value (got EOF)",
+    }
+    .with_span(Span::new(doc.len, 0))
+}
+
+/// Where the arguments of a node end: after the last one, or after its name.
+fn arguments_end(doc: &Document, index: usize) -> usize {
+    let node = &doc.nodes[index];
+    doc.arguments(node.entries.clone())
+        .last()
+        .map_or(node.name_span.end(), |entry| doc.entries[entry].span.end())
+}
+
+/// The whole of a node, from its name on.
+fn node_span(doc: &Document, index: usize) -> Span {
+    let node = &doc.nodes[index];
+    let start = node.name_span.start();
+    Span::new(start, node.end.saturating_sub(start))
+}
+
+fn identifier_span(identifier: &KdlIdentifier) -> Span {
+    let span = identifier.span();
+    Span::new(span.offset(), span.len())
+}
+
+/// Where a node ends, leaving out the whitespace after it.
+fn node_end(kdl: &str, node: &KdlNode) -> usize {
+    let span = node.span();
+    let end = (span.offset() + span.len()).min(kdl.len());
+    span.offset() + kdl[span.offset()..end].trim_end().len()
+}
+
+/// Where the value of an entry is, leaving out the whitespace around it and the name of a
+/// property.
+fn value_span(kdl: &str, entry: &KdlEntry) -> Span {
+    let span = entry.span();
+    let mut start = span.offset();
+    let end = (span.offset() + span.len()).min(kdl.len());
+    if let Some(name) = entry.name() {
+        let name_end = name.span().offset() + name.span().len();
+        if let Some(eq) = kdl[name_end.min(end)..end].find('=') {
+            start = name_end + eq + 1;
+        }
+    }
+    let text = &kdl[start..end];
+    let trimmed = text.trim_start();
+    start += text.len() - trimmed.len();
+    Span::new(start, trimmed.trim_end().len())
+}
+
+/// Whether values of type `shape` are lists of items, which in KDL are a node per item.
+fn is_list(shape: &Shape) -> bool {
+    matches!(shape.def, Def::List(_) | Def::Set(_) | Def::Array(_))
+}
+
+/// The type of the items of the list `shape`.
+fn item_shape<'shape>(shape: &'shape Shape<'shape>) -> Option<&'shape Shape<'shape>> {
+    match shape.def {
+        Def::List(list_def) => Some(list_def.t()),
+        Def::Set(set_def) => Some(set_def.t()),
+        Def::Array(array_def) => Some(array_def.t()),
+        _ => None,
+    }
+}
+
+/// The field named `name`, looking into flattened structs too.
+fn find_field<'shape>(
+    fields: &'shape [Field<'shape>],
+    name: &str,
+) -> Option<&'shape Field<'shape>> {
+    fields
+        .iter()
+        .find(|field| field.is_named(name) && !field.should_skip_deserializing())
+        .or_else(|| {
+            fields
+                .iter()
+                .filter(|field| field.flags.contains(FieldFlags::FLATTEN))
+                .find_map(|field| match field.shape().ty {
+                    Type::User(UserType::Struct(struct_type)) => {
+                        find_field(struct_type.fields, name)
+                    }
+                    _ => None,
+                })
+        })
+}
+
+/// The fields that take the arguments of a node one by one, in the order they're declared.
+fn argument_fields_of<'shape>(fields: &'shape [Field<'shape>]) -> Vec<&'shape Field<'shape>> {
+    let mut out = Vec::new();
+    let mut pending = vec![fields.iter()];
+    while let Some(fields) = pending.last_mut() {
+        let Some(field) = fields.next() else {
+            pending.pop();
+            continue;
+        };
+        if field.flags.contains(FieldFlags::FLATTEN) {
+            if let Type::User(UserType::Struct(struct_type)) = field.shape().ty {
+                pending.push(struct_type.fields.iter());
+            }
+        } else if field
+            .attributes
+            .contains(&FieldAttribute::Arbitrary("argument"))
+            && !field.should_skip_deserializing()
+        {
+            out.push(field);
+        }
+    }
+    out
+}
+
+/// The field that takes the arguments left over once the argument fields are filled.
+fn arguments_field_of<'shape>(fields: &'shape [Field<'shape>]) -> Option<&'shape Field<'shape>> {
+    fields.iter().find_map(|field| {
+        if field.flags.contains(FieldFlags::FLATTEN) {
+            match field.shape().ty {
+                Type::User(UserType::Struct(struct_type)) => arguments_field_of(struct_type.fields),
+                _ => None,
+            }
+        } else if field
+            .attributes
+            .contains(&FieldAttribute::Arbitrary("arguments"))
+            && !field.should_skip_deserializing()
+        {
+            Some(field)
+        } else {
+            None
+        }
+    })
+}
@@ -0,0 +1,429 @@
+//! Create KDL documents from Rust values.
+
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+use facet_core::{Facet, Field, FieldAttribute, FieldFlags};
+use facet_reflect::Peek;
+use facet_serialize::{Serializer, serialize_iterative};
+use kdl::{KdlDocument, KdlEntry, KdlNode, KdlValue};
+
+/// Error type for KDL serialization.
+#[derive(Debug)]
+pub enum KdlSerError {
+    /// The value being serialized isn't a struct, whose fields become the nodes of the document.
+    InvalidDocumentShape,
+    /// A list, struct or map is in a field that's an argument or a property, which can only
+    /// hold a single value. Such fields need `#[facet(child)]`.
+    NotAScalar {
+        /// The name of the field
+        field: String,
+    },
+    /// A number is too large for KDL, which stores them as `i128`.
+    NumberOutOfRange,
+    /// KDL has no byte arrays.
+    UnsupportedByteArray,
+}
+
+impl Display for KdlSerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KdlSerError::InvalidDocumentShape => {
+                write!(f, "a KDL document can only be serialized from a struct")
+            }
+            KdlSerError::NotAScalar { field } => write!(
+                f,
+                "field `{field}` holds more than a single value, so it needs `#[facet(child)]`"
+            ),
+            KdlSerError::NumberOutOfRange => write!(f, "number is too large for KDL"),
+            KdlSerError::UnsupportedByteArray => write!(f, "KDL doesn't support byte arrays"),
+        }
+    }
+}
+impl Error for KdlSerError {}
+
+/// A value, as the events of [`Serializer`] describe it. It's laid out as KDL once complete,
+/// since where a value goes depends on the field it's in, not only on the value itself.
+#[derive(Debug)]
+enum Value<'shape> {
+    Scalar(KdlValue),
+    /// `None`, or `()`
+    Nothing,
+    /// The fields of a struct. Those without a [`Field`] are the variant names of enums.
+    Object(Vec<(&'shape str, Option<Field<'shape>>, Value<'shape>)>),
+    List(Vec<Value<'shape>>),
+    Map(Vec<(String, Value<'shape>)>),
+}
+
+/// A value that's still being serialized.
+enum Partial<'shape> {
+    Object {
+        fields: Vec<(&'shape str, Option<Field<'shape>>, Value<'shape>)>,
+        /// The name of the field the next value goes to, and the field itself
+        next: Option<(&'shape str, Option<Field<'shape>>)>,
+    },
+    List(Vec<Value<'shape>>),
+    Map {
+        entries: Vec<(String, Value<'shape>)>,
+        /// The key the next value goes to
+        key: Option<String>,
+        in_key: bool,
+    },
+}
+
+/// Where a field goes in the node of the struct it's in.
+enum Placement {
+    /// In a child node named after it, from `#[facet(child)]`
+    Child,
+    /// As the next argument, from `#[facet(argument)]`
+    Argument,
+    /// With each of its items as an argument, from `#[facet(arguments)]`
+    Arguments,
+    /// As a `name=value` property, which is the default
+    Property,
+}
+
+fn placement(field: Option<Field>) -> Placement {
+    // Fields without a `Field` name enum variants, whose content is a node of its own
+    let Some(field) = field else {
+        return Placement::Child;
+    };
+    if field.flags.contains(FieldFlags::CHILD) {
+        Placement::Child
+    } else if field
+        .attributes
+        .contains(&FieldAttribute::Arbitrary("argument"))
+    {
+        Placement::Argument
+    } else if field
+        .attributes
+        .contains(&FieldAttribute::Arbitrary("arguments"))
+    {
+        Placement::Arguments
+    } else {
+        Placement::Property
+    }
+}
+
+/// Serializer for KDL documents.
+///
+/// The fields of the top-level struct are the nodes of the document. In each node, fields
+/// with `#[facet(child)]` are child nodes, those with `#[facet(argument)]` are arguments
+/// in the order they're declared, and the others are `name=value` properties. A list in a
+/// child field is a node per item, `None` leaves its node or property out, and maps have a
+/// child node per key.
+pub struct KdlSerializer<'shape> {
+    /// The values being serialized, innermost last
+    stack: Vec<Partial<'shape>>,
+    /// The field whose name comes next
+    field: Option<Field<'shape>>,
+    /// The value, once complete
+    root: Option<Value<'shape>>,
+}
+
+impl<'shape> KdlSerializer<'shape> {
+    /// Create a new serializer.
+    pub fn new() -> Self {
+        Self {
+            stack: Vec::new(),
+            field: None,
+            root: None,
+        }
+    }
+
+    /// Get the serialized KDL document.
+    pub fn into_document(self) -> Result<KdlDocument, KdlSerError> {
+        let Some(Value::Object(fields)) = self.root else {
+            return Err(KdlSerError::InvalidDocumentShape);
+        };
+        let mut document = KdlDocument::new();
+        // There's no node for arguments or properties to go in, so every field is a node
+        for (name, _, value) in fields {
+            push_nodes(&mut document, name, value)?;
+        }
+        document.autoformat();
+        Ok(document)
+    }
+
+    /// Get the serialized KDL string.
+    pub fn into_string(self) -> Result<String, KdlSerError> {
+        Ok(self.into_document()?.to_string())
+    }
+
+    /// Put a complete value where it goes in the one it's in.
+    fn push_value(&mut self, value: Value<'shape>) -> Result<(), KdlSerError> {
+        match self.stack.last_mut() {
+            None => self.root = Some(value),
+            Some(Partial::Object { fields, next }) => {
+                let (name, field) = next.take().unwrap_or_default();
+                fields.push((name, field, value));
+            }
+            Some(Partial::List(items)) => items.push(value),
+            Some(Partial::Map {
+                entries,
+                key,
+                in_key,
+            }) => {
+                if *in_key {
+                    *key = Some(match value {
+                        Value::Scalar(KdlValue::String(key)) => key,
+                        Value::Scalar(other) => other.to_string(),
+                        _ => String::new(),
+                    });
+                } else {
+                    entries.push((key.take().unwrap_or_default(), value));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn push_scalar(&mut self, value: impl Into<KdlValue>) -> Result<(), KdlSerError> {
+        self.push_value(Value::Scalar(value.into()))
+    }
+}
+
+impl Default for KdlSerializer<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Adds the nodes for a child field: a node for its value, or one per item for a list.
+fn push_nodes(document: &mut KdlDocument, name: &str, value: Value<'_>) -> Result<(), KdlSerError> {
+    match value {
+        Value::Nothing => {}
+        Value::List(items) => {
+            for item in items {
+                push_nodes(document, name, item)?;
+            }
+        }
+        value => {
+            let mut node = KdlNode::new(name);
+            fill_node(&mut node, value)?;
+            document.nodes_mut().push(node);
+        }
+    }
+    Ok(())
+}
+
+/// Adds a value to a node: its fields for a struct, or itself as an argument.
+fn fill_node(node: &mut KdlNode, value: Value<'_>) -> Result<(), KdlSerError> {
+    match value {
+        Value::Object(fields) => {
+            for (name, field, value) in fields {
+                match placement(field) {
+                    Placement::Child => push_nodes(node.ensure_children(), name, value)?,
+                    Placement::Argument => {
+                        node.push(KdlEntry::new(scalar(name, value)?));
+                    }
+                    Placement::Arguments => match value {
+                        Value::List(items) => {
+                            for item in items {
+                                node.push(KdlEntry::new(scalar(name, item)?));
+                            }
+                        }
+                        value => node.push(KdlEntry::new(scalar(name, value)?)),
+                    },
+                    Placement::Property => {
+                        if !matches!(value, Value::Nothing) {
+                            node.push(KdlEntry::new_prop(name, scalar(name, value)?));
+                        }
+                    }
+                }
+            }
+        }
+        Value::Map(entries) => {
+            for (key, value) in entries {
+                push_nodes(node.ensure_children(), &key, value)?;
+            }
+        }
+        Value::List(items) => {
+            for item in items {
+                node.push(KdlEntry::new(scalar(node.name().value(), item)?));
+            }
+        }
+        value => node.push(KdlEntry::new(scalar(node.name().value(), value)?)),
+    }
+    Ok(())
+}
+
+/// The value of an argument or property.
+fn scalar(name: &str, value: Value<'_>) -> Result<KdlValue, KdlSerError> {
+    match value {
+        Value::Scalar(value) => Ok(value),
+        Value::Nothing => Ok(KdlValue::Null),
+        _ => Err(KdlSerError::NotAScalar {
+            field: name.to_owned(),
+        }),
+    }
+}
+
+impl<'shape> Serializer<'shape> for KdlSerializer<'shape> {
+    type Error = KdlSerError;
+
+    fn serialize_u64(&mut self, value: u64) -> Result<(), Self::Error> {
+        self.push_scalar(value as i128)
+    }
+
+    fn serialize_u128(&mut self, value: u128) -> Result<(), Self::Error> {
+        let value = i128::try_from(value).map_err(|_| KdlSerError::NumberOutOfRange)?;
+        self.push_scalar(value)
+    }
+
+    fn serialize_i64(&mut self, value: i64) -> Result<(), Self::Error> {
+        self.push_scalar(value as i128)
+    }
+
+    fn serialize_i128(&mut self, value: i128) -> Result<(), Self::Error> {
+        self.push_scalar(value)
+    }
+
+    fn serialize_f64(&mut self, value: f64) -> Result<(), Self::Error> {
+        self.push_scalar(value)
+    }
+
+    fn serialize_bool(&mut self, value: bool) -> Result<(), Self::Error> {
+        self.push_scalar(value)
+    }
+
+    fn serialize_char(&mut self, value: char) -> Result<(), Self::Error> {
+        self.push_scalar(value.to_string())
+    }
+
+    fn serialize_str(&mut self, value: &str) -> Result<(), Self::Error> {
+        self.push_scalar(value)
+    }
+
+    fn serialize_bytes(&mut self, _value: &[u8]) -> Result<(), Self::Error> {
+        Err(KdlSerError::UnsupportedByteArray)
+    }
+
+    fn serialize_none(&mut self) -> Result<(), Self::Error> {
+        self.push_value(Value::Nothing)
+    }
+
+    fn serialize_unit(&mut self) -> Result<(), Self::Error> {
+        self.push_value(Value::Nothing)
+    }
+
+    fn serialize_unit_variant(
+        &mut self,
+        _variant_index: usize,
+        variant_name: &'shape str,
+    ) -> Result<(), Self::Error> {
+        self.push_scalar(variant_name)
+    }
+
+    fn start_object(&mut self, _len: Option<usize>) -> Result<(), Self::Error> {
+        self.stack.push(Partial::Object {
+            fields: Vec::new(),
+            next: None,
+        });
+        Ok(())
+    }
+
+    fn end_object(&mut self) -> Result<(), Self::Error> {
+        match self.stack.pop() {
+            Some(Partial::Object { fields, .. }) => self.push_value(Value::Object(fields)),
+            _ => unreachable!("end_object without start_object"),
+        }
+    }
+
+    fn begin_field(&mut self, field: Field<'shape>) -> Result<(), Self::Error> {
+        self.field = Some(field);
+        Ok(())
+    }
+
+    fn serialize_field_name(&mut self, name: &'shape str) -> Result<(), Self::Error> {
+        // Enum variant names come without `begin_field`
+        let field = self.field.take();
+        if let Some(Partial::Object { next, .. }) = self.stack.last_mut() {
+            *next = Some((name, field));
+        }
+        Ok(())
+    }
+
+    fn start_array(&mut self, _len: Option<usize>) -> Result<(), Self::Error> {
+        self.stack.push(Partial::List(Vec::new()));
+        Ok(())
+    }
+
+    fn end_array(&mut self) -> Result<(), Self::Error> {
+        match self.stack.pop() {
+            Some(Partial::List(items)) => self.push_value(Value::List(items)),
+            _ => unreachable!("end_array without start_array"),
+        }
+    }
+
+    fn start_map(&mut self, _len: Option<usize>) -> Result<(), Self::Error> {
+        self.stack.push(Partial::Map {
+            entries: Vec::new(),
+            key: None,
+            in_key: false,
+        });
+        Ok(())
+    }
+
+    fn end_map(&mut self) -> Result<(), Self::Error> {
+        match self.stack.pop() {
+            Some(Partial::Map { entries, .. }) => self.push_value(Value::Map(entries)),
+            _ => unreachable!("end_map without start_map"),
+        }
+    }
+
+    fn begin_map_key(&mut self) -> Result<(), Self::Error> {
+        if let Some(Partial::Map { in_key, .. }) = self.stack.last_mut() {
+            *in_key = true;
+        }
+        Ok(())
+    }
+
+    fn end_map_key(&mut self) -> Result<(), Self::Error> {
+        if let Some(Partial::Map { in_key, .. }) = self.stack.last_mut() {
+            *in_key = false;
+        }
+        Ok(())
+    }
+}
+
+/// Serialize a value of type `T` to a KDL string.
+///
+/// `T` must be a struct: its fields are the nodes of the document.
+///
+/// # Example
+/// ```ignore
+/// #[derive(Facet)]
+/// struct Config {
+///     #[facet(child)]
+///     server: Server,
+/// }
+///
+/// #[derive(Facet)]
+/// struct Server {
+///     #[facet(argument)]
+///     host: String,
+///     port: u16,
+/// }
+///
+/// let config = Config { server: Server { host: "localhost".into(), port: 8080 } };
+/// let kdl = to_string(&config)?;
+/// // server localhost port=8080
+/// ```
+pub fn to_string<'a, T: Facet<'a>>(value: &'a T) -> Result<String, KdlSerError> {
+    peek_to_string(Peek::new(value))
+}
+
+/// Serialize a `Peek` to a KDL string.
+pub fn peek_to_string<'mem, 'facet, 'shape>(
+    peek: Peek<'mem, 'facet, 'shape>,
+) -> Result<String, KdlSerError>
+where
+    'mem: 'facet,
+{
+    let mut serializer = KdlSerializer::new();
+    serialize_iterative(peek, &mut serializer)?;
+    serializer.into_string()
+}
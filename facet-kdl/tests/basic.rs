@@ -3,7 +3,6 @@ use facet_testhelpers::test;
 use indoc::indoc;
 
 #[test]
-#[ignore]
 fn basic_node() {
     // QUESTION: I don't know when this would be particularly good practice, but it could be nice if `facet` shipped
     // some sort of macro that allowed libraries to rename the Facet trait / attributes? This might make it clearer
@@ -32,7 +31,6 @@ fn basic_node() {
         title "Hello, World"
     "#};
 
-    dbg!(Basic::SHAPE);
-
-    let _basic: Basic = facet_kdl::from_str(kdl)?;
+    let basic: Basic = facet_kdl::from_str(kdl)?;
+    assert_eq!(basic.title.title, "Hello, World");
 }
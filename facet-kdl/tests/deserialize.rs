@@ -0,0 +1,378 @@
+use std::collections::BTreeMap;
+
+use facet::Facet;
+use facet_kdl::DeserErrorKind;
+use facet_testhelpers::test;
+use indoc::indoc;
+
+#[derive(Facet, Debug, PartialEq)]
+struct Config {
+    #[facet(child)]
+    server: Server,
+    #[facet(child)]
+    route: Vec<Route>,
+    #[facet(child)]
+    log_level: Level,
+    #[facet(child, default)]
+    comment: Option<String>,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Server {
+    #[facet(argument)]
+    host: String,
+    port: u16,
+    #[facet(default)]
+    tls: Option<bool>,
+    #[facet(child)]
+    limits: Limits,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Limits {
+    #[facet(arguments)]
+    sizes: Vec<u32>,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Route {
+    #[facet(argument)]
+    path: String,
+    #[facet(argument)]
+    target: String,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[repr(u8)]
+enum Level {
+    Info,
+    Debug,
+}
+
+#[test]
+fn test_arguments_properties_and_children() {
+    let kdl = indoc! {r#"
+        server "localhost" port=8080 tls=#true {
+            limits 16 32 64
+        }
+        route "/" "index"
+        log_level "Debug"
+        route "/api" "backend"
+    "#};
+
+    let config: Config = facet_kdl::from_str(kdl)?;
+    assert_eq!(
+        config,
+        Config {
+            server: Server {
+                host: "localhost".to_string(),
+                port: 8080,
+                tls: Some(true),
+                limits: Limits {
+                    sizes: vec![16, 32, 64],
+                },
+            },
+            route: vec![
+                Route {
+                    path: "/".to_string(),
+                    target: "index".to_string(),
+                },
+                Route {
+                    path: "/api".to_string(),
+                    target: "backend".to_string(),
+                },
+            ],
+            log_level: Level::Debug,
+            comment: None,
+        }
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    let config = Config {
+        server: Server {
+            host: "example.com".to_string(),
+            port: 443,
+            tls: None,
+            limits: Limits { sizes: vec![1, 2] },
+        },
+        route: vec![Route {
+            path: "/".to_string(),
+            target: "home".to_string(),
+        }],
+        log_level: Level::Info,
+        comment: Some("hello".to_string()),
+    };
+
+    let kdl = facet_kdl::to_string(&config)?;
+    let back: Config = facet_kdl::from_str(&kdl).map_err(|e| e.into_owned())?;
+    assert_eq!(back, config);
+}
+
+#[test]
+fn test_defaults_and_unknown_fields() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Document {
+        #[facet(child)]
+        window: Window,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Window {
+        #[facet(default)]
+        title: String,
+        #[facet(default = 640)]
+        width: u32,
+    }
+
+    // Unknown properties and nodes are skipped
+    let kdl = indoc! {r#"
+        window title="Main" height=480 {
+            decorations #false
+        }
+        theme "dark"
+    "#};
+    let document: Document = facet_kdl::from_str(kdl)?;
+    assert_eq!(
+        document.window,
+        Window {
+            title: "Main".to_string(),
+            width: 640,
+        }
+    );
+
+    #[derive(Facet, Debug)]
+    #[facet(deny_unknown_fields)]
+    struct Strict {
+        #[facet(child)]
+        window: StrictWindow,
+    }
+
+    #[derive(Facet, Debug)]
+    #[facet(deny_unknown_fields)]
+    struct StrictWindow {
+        title: String,
+    }
+
+    let kdl = r#"window height=480 title="Main""#;
+    let err = facet_kdl::from_str::<Strict>(kdl).unwrap_err();
+    assert!(matches!(
+        err.kind,
+        DeserErrorKind::UnknownField { ref field_name, .. } if field_name == "height"
+    ));
+    assert_eq!(&kdl[err.span.start()..err.span.end()], "height");
+
+    let kdl = r#"window "Main" title="Main""#;
+    let err = facet_kdl::from_str::<Strict>(kdl).unwrap_err();
+    assert!(matches!(err.kind, DeserErrorKind::UnknownField { .. }));
+    assert_eq!(&kdl[err.span.start()..err.span.end()], r#""Main""#);
+}
+
+#[test]
+fn test_maps_and_variants() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Document {
+        #[facet(child)]
+        env: BTreeMap<String, String>,
+        #[facet(child)]
+        shape: Vec<Shape>,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    enum Shape {
+        Circle { radius: f64 },
+        Label(String),
+        Point,
+    }
+
+    let kdl = indoc! {r#"
+        env {
+            HOME "/root"
+            SHELL "/bin/sh"
+        }
+        shape {
+            Circle radius=2.5
+        }
+        shape {
+            Label "origin"
+        }
+        shape "Point"
+    "#};
+    let document: Document = facet_kdl::from_str(kdl)?;
+    assert_eq!(
+        document,
+        Document {
+            env: BTreeMap::from([
+                ("HOME".to_string(), "/root".to_string()),
+                ("SHELL".to_string(), "/bin/sh".to_string()),
+            ]),
+            shape: vec![
+                Shape::Circle { radius: 2.5 },
+                Shape::Label("origin".to_string()),
+                Shape::Point,
+            ],
+        }
+    );
+}
+
+#[test]
+fn test_tagged_and_untagged_enums() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Document {
+        #[facet(child)]
+        shape: Shape,
+        #[facet(child)]
+        value: Vec<Value>,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[facet(tag = "type")]
+    #[allow(dead_code)]
+    enum Shape {
+        Circle { radius: f64 },
+        Square { side: f64 },
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[facet(untagged)]
+    enum Value {
+        Number(u32),
+        Text(String),
+        Pair { a: u32, b: u32 },
+    }
+
+    // The tag can come after the fields
+    let kdl = indoc! {r#"
+        shape side=2.0 type="Square"
+        value 3
+        value "three"
+        value a=1 b=2
+    "#};
+    let document: Document = facet_kdl::from_str(kdl)?;
+    assert_eq!(
+        document,
+        Document {
+            shape: Shape::Square { side: 2.0 },
+            value: vec![
+                Value::Number(3),
+                Value::Text("three".to_string()),
+                Value::Pair { a: 1, b: 2 },
+            ],
+        }
+    );
+}
+
+#[test]
+fn test_flatten() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Document {
+        #[facet(child)]
+        user: User,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct User {
+        #[facet(argument)]
+        name: String,
+        #[facet(flatten)]
+        contact: Contact,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Contact {
+        email: String,
+        #[facet(child)]
+        phone: Vec<String>,
+    }
+
+    let kdl = indoc! {r#"
+        user "alice" email="alice@example.com" {
+            phone "555-1234"
+            phone "555-5678"
+        }
+    "#};
+    let document: Document = facet_kdl::from_str(kdl)?;
+    assert_eq!(
+        document.user,
+        User {
+            name: "alice".to_string(),
+            contact: Contact {
+                email: "alice@example.com".to_string(),
+                phone: vec!["555-1234".to_string(), "555-5678".to_string()],
+            },
+        }
+    );
+}
+
+#[test]
+fn test_borrowed_strings() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Document<'a> {
+        #[facet(child)]
+        book: Book<'a>,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Book<'a> {
+        #[facet(argument)]
+        title: &'a str,
+        author: &'a str,
+        #[facet(child)]
+        tag: Vec<&'a str>,
+        #[facet(child)]
+        quote: String,
+    }
+
+    let kdl = indoc! {r##"
+        book "Dune" author=#"Frank "Herbert""# {
+            tag classic
+            tag "sci-fi"
+            quote "spice\nmust flow"
+        }
+    "##};
+    let document: Document = facet_kdl::from_str(kdl)?;
+    assert_eq!(document.book.title, "Dune");
+    assert_eq!(document.book.author, "Frank \"Herbert\"");
+    assert_eq!(document.book.tag, ["classic", "sci-fi"]);
+    // Strings with escapes can't be borrowed, but still go into owned strings
+    assert_eq!(document.book.quote, "spice\nmust flow");
+}
+
+#[test]
+fn test_error_spans() {
+    #[derive(Facet, Debug)]
+    struct Document {
+        #[facet(child)]
+        server: Server,
+    }
+
+    // Values of the wrong type point at the value
+    let kdl = r#"server port="eighty" "localhost" { limits; }"#;
+    let err = facet_kdl::from_str::<Document>(kdl).unwrap_err();
+    assert_eq!(&kdl[err.span.start()..err.span.end()], r#""eighty""#);
+
+    // Invalid syntax points at where parsing failed
+    let kdl = "server \"localhost";
+    let err = facet_kdl::from_str::<Document>(kdl).unwrap_err();
+    assert!(matches!(err.kind, DeserErrorKind::InvalidSyntax(_)));
+
+    #[derive(Facet, Debug)]
+    struct Plot {
+        #[facet(child)]
+        point: Point,
+    }
+
+    #[derive(Facet, Debug)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    // Missing fields are reported
+    let err = facet_kdl::from_str::<Plot>("point x=1").unwrap_err();
+    assert!(matches!(err.kind, DeserErrorKind::ReflectError(_)));
+}
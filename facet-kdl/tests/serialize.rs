@@ -0,0 +1,195 @@
+use std::collections::BTreeMap;
+
+use facet::Facet;
+use facet_testhelpers::test;
+use kdl::{KdlDocument, KdlValue};
+
+#[derive(Facet)]
+struct Config {
+    #[facet(child)]
+    server: Server,
+    #[facet(child)]
+    route: Vec<Route>,
+    #[facet(child)]
+    log_level: Level,
+    #[facet(child)]
+    comment: Option<String>,
+}
+
+#[derive(Facet)]
+struct Server {
+    #[facet(argument)]
+    host: String,
+    port: u16,
+    tls: Option<bool>,
+    #[facet(child)]
+    limits: Limits,
+}
+
+#[derive(Facet)]
+struct Limits {
+    #[facet(arguments)]
+    sizes: Vec<u32>,
+}
+
+#[derive(Facet)]
+struct Route {
+    #[facet(argument)]
+    path: String,
+    #[facet(argument)]
+    target: String,
+}
+
+#[derive(Facet)]
+#[repr(u8)]
+enum Level {
+    Info,
+    #[allow(dead_code)]
+    Debug,
+}
+
+fn config() -> Config {
+    Config {
+        server: Server {
+            host: "localhost".to_string(),
+            port: 8080,
+            tls: None,
+            limits: Limits {
+                sizes: vec![16, 32],
+            },
+        },
+        route: vec![
+            Route {
+                path: "/".to_string(),
+                target: "index".to_string(),
+            },
+            Route {
+                path: "/api".to_string(),
+                target: "backend".to_string(),
+            },
+        ],
+        log_level: Level::Info,
+        comment: None,
+    }
+}
+
+fn string(value: &str) -> KdlValue {
+    KdlValue::String(value.to_string())
+}
+
+#[test]
+fn test_arguments_and_properties() {
+    let kdl = facet_kdl::to_string(&config())?;
+    let document: KdlDocument = kdl.parse()?;
+
+    let server = document.get("server").unwrap();
+    assert_eq!(server.get(0), Some(&string("localhost")));
+    assert_eq!(server.get("port"), Some(&KdlValue::Integer(8080)));
+    // `None` properties are left out
+    assert_eq!(server.get("tls"), None);
+    assert_eq!(server.entries().len(), 2);
+}
+
+#[test]
+fn test_children() {
+    let kdl = facet_kdl::to_string(&config())?;
+    let document: KdlDocument = kdl.parse()?;
+
+    let limits = document
+        .get("server")
+        .and_then(|server| server.children())
+        .and_then(|children| children.get("limits"))
+        .unwrap();
+    let sizes: Vec<_> = limits.entries().iter().map(|entry| entry.value()).collect();
+    assert_eq!(sizes, [&KdlValue::Integer(16), &KdlValue::Integer(32)]);
+
+    // A node per item of a list
+    let routes: Vec<_> = document
+        .nodes()
+        .iter()
+        .filter(|node| node.name().value() == "route")
+        .collect();
+    assert_eq!(routes.len(), 2);
+    assert_eq!(routes[1].get(0), Some(&string("/api")));
+    assert_eq!(routes[1].get(1), Some(&string("backend")));
+
+    // Unit variants are their name, and `None` leaves the node out
+    assert_eq!(
+        document.get("log_level").unwrap().get(0),
+        Some(&string("Info"))
+    );
+    assert!(document.get("comment").is_none());
+}
+
+#[test]
+fn test_maps_and_variants() {
+    #[derive(Facet)]
+    struct Document {
+        #[facet(child)]
+        env: BTreeMap<String, String>,
+        #[facet(child)]
+        shape: Shape,
+    }
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Shape {
+        Circle { radius: f64 },
+        Square { side: f64 },
+    }
+
+    let value = Document {
+        env: BTreeMap::from([("HOME".to_string(), "/root".to_string())]),
+        shape: Shape::Circle { radius: 2.5 },
+    };
+    let kdl = facet_kdl::to_string(&value)?;
+    let document: KdlDocument = kdl.parse()?;
+
+    let env = document.get("env").and_then(|env| env.children()).unwrap();
+    assert_eq!(env.get_arg("HOME"), Some(&string("/root")));
+
+    let circle = document
+        .get("shape")
+        .and_then(|shape| shape.children())
+        .and_then(|children| children.get("Circle"))
+        .unwrap();
+    assert_eq!(circle.get("radius"), Some(&KdlValue::Float(2.5)));
+}
+
+#[test]
+fn test_invalid_shapes() {
+    #[derive(Facet)]
+    struct Nested {
+        inner: Server,
+    }
+
+    // Every field of the document is a node, even without `child`
+    let value = Nested {
+        inner: config().server,
+    };
+    assert!(facet_kdl::to_string(&value).is_ok());
+
+    #[derive(Facet)]
+    struct Wrapper {
+        #[facet(child)]
+        outer: Nested,
+    }
+
+    // But a struct can't be a property
+    let value = Wrapper {
+        outer: Nested {
+            inner: config().server,
+        },
+    };
+    assert!(matches!(
+        facet_kdl::to_string(&value),
+        Err(facet_kdl::KdlSerError::NotAScalar { .. })
+    ));
+
+    // The document is made from the fields of a struct
+    assert!(matches!(
+        facet_kdl::to_string(&vec![1, 2]),
+        Err(facet_kdl::KdlSerError::InvalidDocumentShape)
+    ));
+}
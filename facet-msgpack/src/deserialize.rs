@@ -1,7 +1,7 @@
 use crate::constants::*;
 use crate::errors::Error as DecodeError;
 
-use facet_core::{Def, Facet, Type, UserType};
+use facet_core::{Def, EnumTagging, EnumType, Facet, StructKind, Type, UserType, Variant};
 use facet_reflect::{HeapValue, Wip};
 use log::trace;
 
@@ -350,6 +350,8 @@ impl<'input, 'shape> Decoder<'input> {
                 Ok(())
             }
             // Fixed integers are already handled by decode_u8
+            MSGPACK_POSFIXINT_MIN..=MSGPACK_POSFIXINT_MAX => Ok(()),
+            prefix if (prefix as i8) >= MSGPACK_NEGFIXINT_MIN => Ok(()),
 
             // Boolean and nil
             MSGPACK_NIL | MSGPACK_TRUE | MSGPACK_FALSE => Ok(()),
@@ -407,6 +409,196 @@ impl<'input, 'shape> Decoder<'input> {
         }
     }
 
+    /// Scans the map at the current offset for the string value of `tag`, and the offset of
+    /// the value of `content` if requested. The offset is left at the end of the map.
+    fn scan_tagged_map(
+        &mut self,
+        tag: &str,
+        content: Option<&str>,
+    ) -> Result<(Option<String>, Option<usize>), DecodeError<'static>> {
+        let map_len = self.decode_map_len()?;
+        let mut variant_name = None;
+        let mut content_offset = None;
+        for _ in 0..map_len {
+            let key = self.decode_string()?;
+            if key == tag {
+                variant_name = Some(self.decode_string()?);
+            } else {
+                if content == Some(key.as_str()) {
+                    content_offset = Some(self.offset);
+                }
+                self.skip_value()?;
+            }
+        }
+        Ok((variant_name, content_offset))
+    }
+
+    /// Finds the variant named by a tag and selects it.
+    fn select_tagged_variant<'facet>(
+        wip: Wip<'facet, 'shape>,
+        enum_type: &EnumType<'shape>,
+        tag: &str,
+        variant_name: Option<String>,
+    ) -> Result<(Wip<'facet, 'shape>, &'shape Variant<'shape>), DecodeError<'shape>> {
+        let variant_name = variant_name
+            .ok_or_else(|| DecodeError::InvalidEnum(format!("Missing tag: {}", tag)))?;
+        let (idx, variant) = enum_type
+            .variants
+            .iter()
+            .enumerate()
+            .find(|(_, variant)| variant.is_named(&variant_name))
+            .ok_or_else(|| {
+                DecodeError::InvalidEnum(format!("Unknown variant: {}", variant_name))
+            })?;
+        Ok((wip.variant(idx)?, variant))
+    }
+
+    /// Deserializes the fields of a map into the struct-like frame at the top of `wip`,
+    /// ignoring the key `ignored`.
+    fn deserialize_map_fields<'facet>(
+        &mut self,
+        mut wip: Wip<'facet, 'shape>,
+        ignored: Option<&str>,
+    ) -> Result<Wip<'facet, 'shape>, DecodeError<'shape>> {
+        let map_len = self.decode_map_len()?;
+        for _ in 0..map_len {
+            let key = self.decode_string()?;
+            match wip.deserializable_field_index(&key) {
+                Some(index) if ignored != Some(key.as_str()) => {
+                    wip = self.deserialize_value(wip.field(index)?)?.pop()?;
+                }
+                _ => {
+                    trace!("Skipping field: {}", key);
+                    self.skip_value()?;
+                }
+            }
+        }
+        Ok(wip)
+    }
+
+    /// Deserializes the data of a selected variant: the inner value for a newtype variant,
+    /// an array for a tuple variant and a map for a struct variant.
+    fn deserialize_variant_content<'facet>(
+        &mut self,
+        mut wip: Wip<'facet, 'shape>,
+        variant: &'shape Variant<'shape>,
+    ) -> Result<Wip<'facet, 'shape>, DecodeError<'shape>> {
+        match variant.data.kind {
+            StructKind::Unit => {
+                self.decode_nil()?;
+                Ok(wip)
+            }
+            StructKind::Tuple | StructKind::TupleStruct if variant.data.fields.len() == 1 => {
+                Ok(self.deserialize_value(wip.field(0)?)?.pop()?)
+            }
+            StructKind::Tuple | StructKind::TupleStruct => {
+                let array_len = self.decode_array_len()?;
+                if array_len != variant.data.fields.len() {
+                    return Err(DecodeError::InvalidData);
+                }
+                for index in 0..array_len {
+                    wip = self.deserialize_value(wip.field(index)?)?.pop()?;
+                }
+                Ok(wip)
+            }
+            _ => self.deserialize_map_fields(wip, None),
+        }
+    }
+
+    /// Deserializes an enum whose variant name is stored in the `tag` field of the map
+    /// holding its fields.
+    fn deserialize_internally_tagged<'facet>(
+        &mut self,
+        wip: Wip<'facet, 'shape>,
+        enum_type: &EnumType<'shape>,
+        tag: &str,
+    ) -> Result<Wip<'facet, 'shape>, DecodeError<'shape>> {
+        let start = self.offset;
+        let (variant_name, _) = self.scan_tagged_map(tag, None)?;
+        let end = self.offset;
+        let (wip, variant) = Self::select_tagged_variant(wip, enum_type, tag, variant_name)?;
+
+        self.offset = start;
+        let wip = match variant.data.kind {
+            StructKind::Unit => {
+                self.skip_value()?;
+                wip
+            }
+            StructKind::Struct => self.deserialize_map_fields(wip, Some(tag))?,
+            _ if variant.data.fields.len() == 1 => {
+                // Newtype variants hold a struct whose fields sit beside the tag
+                let wip = self.deserialize_map_fields(wip.field(0)?, Some(tag))?;
+                wip.pop()?
+            }
+            _ => {
+                return Err(DecodeError::InvalidEnum(format!(
+                    "Tuple variant {} can't be internally tagged",
+                    variant.name
+                )));
+            }
+        };
+        self.offset = end;
+        Ok(wip)
+    }
+
+    /// Deserializes an enum stored as a map with its variant name in `tag` and its data in
+    /// `content`.
+    fn deserialize_adjacently_tagged<'facet>(
+        &mut self,
+        wip: Wip<'facet, 'shape>,
+        enum_type: &EnumType<'shape>,
+        tag: &str,
+        content: &str,
+    ) -> Result<Wip<'facet, 'shape>, DecodeError<'shape>> {
+        let (variant_name, content_offset) = self.scan_tagged_map(tag, Some(content))?;
+        let end = self.offset;
+        let (wip, variant) = Self::select_tagged_variant(wip, enum_type, tag, variant_name)?;
+
+        let wip = match content_offset {
+            Some(offset) => {
+                self.offset = offset;
+                self.deserialize_variant_content(wip, variant)?
+            }
+            None if variant.data.kind == StructKind::Unit => wip,
+            None => return Err(DecodeError::MissingField(content.to_string())),
+        };
+        self.offset = end;
+        Ok(wip)
+    }
+
+    /// Deserializes an enum stored without any variant name, picking the first variant whose
+    /// data matches.
+    fn deserialize_untagged<'facet>(
+        &mut self,
+        wip: Wip<'facet, 'shape>,
+        enum_type: &EnumType<'shape>,
+    ) -> Result<Wip<'facet, 'shape>, DecodeError<'shape>> {
+        let shape = wip.shape();
+        let start = self.offset;
+        for (idx, variant) in enum_type.variants.iter().enumerate() {
+            self.offset = start;
+            let attempt = Wip::alloc_shape(shape)
+                .and_then(|variant_wip| variant_wip.variant(idx))
+                .map_err(DecodeError::ReflectError)
+                .and_then(|variant_wip| self.deserialize_variant_content(variant_wip, variant))
+                .and_then(|variant_wip| variant_wip.build().map_err(DecodeError::ReflectError));
+            match attempt {
+                Ok(value) => {
+                    trace!("Untagged value matched variant {}", variant.name);
+                    return Ok(wip.put_heap_value(value)?);
+                }
+                Err(e) => trace!(
+                    "Untagged value did not match variant {}: {}",
+                    variant.name, e
+                ),
+            }
+        }
+        Err(DecodeError::InvalidEnum(format!(
+            "Data did not match any variant of untagged enum {}",
+            shape
+        )))
+    }
+
     fn deserialize_value<'facet>(
         &mut self,
         mut wip: Wip<'facet, 'shape>,
@@ -426,7 +618,7 @@ impl<'input, 'shape> Decoder<'input> {
                 let mut wip = wip;
                 for _ in 0..map_len {
                     let key = self.decode_string()?;
-                    match wip.field_index(&key) {
+                    match wip.deserializable_field_index(&key) {
                         Some(index) => {
                             seen_fields[index] = true;
                             wip = self
@@ -444,17 +636,18 @@ impl<'input, 'shape> Decoder<'input> {
 
                 // Handle defaults for fields that weren't seen in the input
                 for (i, &seen) in seen_fields.iter().enumerate() {
-                    if !seen {
+                    // Skipped fields are filled with their default when the struct is popped
+                    if !seen && !struct_type.fields[i].should_skip_deserializing() {
                         let field = &struct_type.fields[i];
                         if field.flags.contains(facet_core::FieldFlags::DEFAULT) {
                             // Field has default attribute, so we should apply the default
                             let field_wip = wip.field(i).map_err(DecodeError::ReflectError)?;
 
-                            // Whether there's a custom default function or not, we just use put_default()
-                            // the Wip.put_default() API in the facet system will handle calling the
+                            // Whether there's a custom default function or not, we just use put_field_default()
+                            // the Wip.put_field_default() API in the facet system will handle calling the
                             // appropriate default function set in the #[facet(default = ...)] attribute
                             wip = field_wip
-                                .put_default()
+                                .put_field_default()
                                 .map_err(DecodeError::ReflectError)?
                                 .pop()
                                 .map_err(DecodeError::ReflectError)?;
@@ -495,11 +688,24 @@ impl<'input, 'shape> Decoder<'input> {
             Type::User(UserType::Enum(enum_type)) => {
                 trace!("Deserializing enum");
 
+                match shape.enum_tagging() {
+                    EnumTagging::External => {}
+                    EnumTagging::Internal { tag } => {
+                        return self.deserialize_internally_tagged(wip, enum_type, tag);
+                    }
+                    EnumTagging::Adjacent { tag, content } => {
+                        return self.deserialize_adjacently_tagged(wip, enum_type, tag, content);
+                    }
+                    EnumTagging::Untagged => {
+                        return self.deserialize_untagged(wip, enum_type);
+                    }
+                }
+
                 // Check if it's a unit variant which is represented as a string
                 if self.peek_string()? {
                     let variant_name = self.decode_string()?;
                     for (idx, variant) in enum_type.variants.iter().enumerate() {
-                        if variant.name == variant_name {
+                        if variant.is_named(&variant_name) {
                             return wip.variant(idx).map_err(DecodeError::ReflectError);
                         }
                     }
@@ -518,7 +724,7 @@ impl<'input, 'shape> Decoder<'input> {
                 let variant_name = self.decode_string()?;
 
                 for (idx, variant) in enum_type.variants.iter().enumerate() {
-                    if variant.name == variant_name {
+                    if variant.is_named(&variant_name) {
                         match &variant.data.kind {
                             // Handle unit variant
                             facet_core::StructKind::Unit => {
@@ -555,7 +761,7 @@ impl<'input, 'shape> Decoder<'input> {
                                 // Handle fields as a normal struct
                                 for _ in 0..map_len {
                                     let field_name = self.decode_string()?;
-                                    match enum_wip.field_index(&field_name) {
+                                    match enum_wip.deserializable_field_index(&field_name) {
                                         Some(field_idx) => {
                                             let field_wip = enum_wip
                                                 .field(field_idx)
@@ -699,6 +905,20 @@ impl<'input, 'shape> Decoder<'input> {
             }
 
             wip = list_wip;
+        } else if let Def::Set(_set_def) = shape.def {
+            trace!("Deserializing set");
+            let array_len = self.decode_array_len()?;
+            let mut set_wip = wip.begin_set_insert().map_err(DecodeError::ReflectError)?;
+
+            for _ in 0..array_len {
+                let item_wip = set_wip.push().map_err(DecodeError::ReflectError)?;
+                set_wip = self
+                    .deserialize_value(item_wip)?
+                    .pop()
+                    .map_err(DecodeError::ReflectError)?;
+            }
+
+            wip = set_wip;
         } else if let Def::Option(_option_def) = shape.def {
             trace!("Deserializing option");
             // Check if we have a null/nil value
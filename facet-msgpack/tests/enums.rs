@@ -1,6 +1,6 @@
 use eyre::Result;
 use facet::Facet;
-use facet_msgpack::from_slice;
+use facet_msgpack::{from_slice, to_vec};
 
 #[test]
 fn msgpack_deserialize_unit_enum_variant() -> Result<()> {
@@ -107,3 +107,104 @@ fn msgpack_deserialize_struct_variant() -> Result<()> {
 
     Ok(())
 }
+
+#[derive(Facet, Debug, PartialEq)]
+struct Circle {
+    radius: u32,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(tag = "type")]
+#[repr(u8)]
+enum InternallyTagged {
+    Empty,
+    Circle(Circle),
+    Rect { w: u32, h: u32 },
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(tag = "t", content = "c")]
+#[repr(u8)]
+enum AdjacentlyTagged {
+    Empty,
+    Count(u32),
+    Pair(u32, String),
+    Rect { w: u32, h: u32 },
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(untagged)]
+#[repr(u8)]
+enum Untagged {
+    Nothing,
+    Number(u64),
+    Text(String),
+    Rect { w: u32, h: u32 },
+}
+
+#[test]
+fn msgpack_internally_tagged_roundtrip() -> Result<()> {
+    facet_testhelpers::setup();
+
+    for value in [
+        InternallyTagged::Empty,
+        InternallyTagged::Circle(Circle { radius: 3 }),
+        InternallyTagged::Rect { w: 1, h: 2 },
+    ] {
+        let bytes = to_vec(&value);
+        let back: InternallyTagged = from_slice(&bytes)?;
+        assert_eq!(back, value);
+    }
+
+    // { "radius": 3, "type": "Circle" }
+    let data = [
+        0x82, // Map with 2 elements
+        0xa6, 0x72, 0x61, 0x64, 0x69, 0x75, 0x73, // "radius"
+        0x03, // 3
+        0xa4, 0x74, 0x79, 0x70, 0x65, // "type"
+        0xa6, 0x43, 0x69, 0x72, 0x63, 0x6c, 0x65, // "Circle"
+    ];
+    let v: InternallyTagged = from_slice(&data)?;
+    assert_eq!(v, InternallyTagged::Circle(Circle { radius: 3 }));
+
+    Ok(())
+}
+
+#[test]
+fn msgpack_adjacently_tagged_roundtrip() -> Result<()> {
+    facet_testhelpers::setup();
+
+    for value in [
+        AdjacentlyTagged::Empty,
+        AdjacentlyTagged::Count(3),
+        AdjacentlyTagged::Pair(3, "x".to_string()),
+        AdjacentlyTagged::Rect { w: 1, h: 2 },
+    ] {
+        let bytes = to_vec(&value);
+        let back: AdjacentlyTagged = from_slice(&bytes)?;
+        assert_eq!(back, value);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn msgpack_untagged_roundtrip() -> Result<()> {
+    facet_testhelpers::setup();
+
+    for value in [
+        Untagged::Nothing,
+        Untagged::Number(7),
+        Untagged::Text("hi".to_string()),
+        Untagged::Rect { w: 1, h: 2 },
+    ] {
+        let bytes = to_vec(&value);
+        let back: Untagged = from_slice(&bytes)?;
+        assert_eq!(back, value);
+    }
+
+    // true matches no variant
+    assert!(from_slice::<Untagged>(&[0xc3]).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use eyre::Result;
+use facet::Facet;
+use facet_msgpack::{from_slice, to_vec};
+use std::collections::{BTreeSet, HashSet};
+
+#[test]
+fn msgpack_deserialize_hashset() -> Result<()> {
+    facet_testhelpers::setup();
+
+    // [1, 2, 1]
+    let data = [
+        0x93, // Array with 3 elements
+        0x01, // 1
+        0x02, // 2
+        0x01, // 1
+    ];
+
+    let s: HashSet<u32> = from_slice(&data)?;
+    assert_eq!(s, HashSet::from([1, 2]));
+
+    Ok(())
+}
+
+#[test]
+fn msgpack_roundtrip_btreeset() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Tagged {
+        tags: BTreeSet<String>,
+    }
+
+    let value = Tagged {
+        tags: BTreeSet::from(["a".to_string(), "b".to_string()]),
+    };
+    let bytes = to_vec(&value);
+    let back: Tagged = from_slice(&bytes)?;
+    assert_eq!(back, value);
+
+    Ok(())
+}
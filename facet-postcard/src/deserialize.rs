@@ -0,0 +1,376 @@
+use alloc::borrow::{Cow, ToOwned};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use facet_core::{Def, Facet, SequenceType, Type, UserType};
+use facet_reflect::{ReflectError, ScalarType, Wip};
+
+use crate::varint;
+
+/// Errors when deserializing from postcard bytes
+#[derive(Debug)]
+pub enum PostcardDeserError {
+    /// Unexpected end of input
+    UnexpectedEof,
+    /// A varint that's longer than its type allows, or too large for it
+    VarintOverflow {
+        /// Position of this error in bytes
+        position: usize,
+    },
+    /// Invalid boolean
+    InvalidBoolean {
+        /// Position of this error in bytes
+        position: usize,
+    },
+    /// Invalid discriminant for optional
+    InvalidOptional {
+        /// Position of this error in bytes
+        position: usize,
+    },
+    /// Invalid enum variant index
+    InvalidVariant {
+        /// Position of this error in bytes
+        position: usize,
+    },
+    /// Invalid string
+    InvalidString {
+        /// Position of this error in bytes
+        position: usize,
+        /// Underlying UTF-8 error
+        source: core::str::Utf8Error,
+    },
+    /// A string that isn't exactly one character, where a `char` was expected
+    InvalidChar {
+        /// Position of this error in bytes
+        position: usize,
+    },
+    /// Bytes left over after the value
+    TrailingBytes {
+        /// Position of this error in bytes
+        position: usize,
+    },
+    /// A type that has no postcard representation, like a smart pointer or a borrowed value
+    UnsupportedType(String),
+    /// The value could not be built
+    ReflectError(String),
+}
+
+impl core::fmt::Display for PostcardDeserError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PostcardDeserError::UnexpectedEof => write!(f, "Unexpected end of input"),
+            PostcardDeserError::VarintOverflow { position } => {
+                write!(f, "Varint too large at byte {}", position)
+            }
+            PostcardDeserError::InvalidBoolean { position } => {
+                write!(f, "Invalid boolean at byte {}", position)
+            }
+            PostcardDeserError::InvalidOptional { position } => {
+                write!(f, "Invalid discriminant for optional at byte {}", position)
+            }
+            PostcardDeserError::InvalidVariant { position } => {
+                write!(f, "Invalid enum variant index at byte {}", position)
+            }
+            PostcardDeserError::InvalidString { position, .. } => {
+                write!(f, "Invalid string at byte {}", position)
+            }
+            PostcardDeserError::InvalidChar { position } => {
+                write!(f, "Invalid char at byte {}", position)
+            }
+            PostcardDeserError::TrailingBytes { position } => {
+                write!(f, "Trailing bytes at byte {}", position)
+            }
+            PostcardDeserError::UnsupportedType(shape) => write!(f, "Unsupported type: {}", shape),
+            PostcardDeserError::ReflectError(error) => write!(f, "Reflection error: {}", error),
+        }
+    }
+}
+
+impl core::error::Error for PostcardDeserError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            PostcardDeserError::InvalidString { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<ReflectError<'_>> for PostcardDeserError {
+    fn from(error: ReflectError<'_>) -> Self {
+        PostcardDeserError::ReflectError(error.to_string())
+    }
+}
+
+/// Deserializes postcard bytes into any Facet type. All of `input` has to be used.
+///
+/// # Example
+///
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet, Debug, PartialEq)]
+/// struct Ping {
+///     seq: u32,
+///     payload: Vec<u8>,
+/// }
+///
+/// let ping: Ping = facet_postcard::from_slice(&[0xac, 0x02, 0x01, 0x07]).unwrap();
+/// assert_eq!(ping, Ping { seq: 300, payload: vec![7] });
+/// ```
+pub fn from_slice<'facet, T: Facet<'facet>>(input: &[u8]) -> Result<T, PostcardDeserError> {
+    let (value, rest) = take_from_slice(input)?;
+    if !rest.is_empty() {
+        return Err(PostcardDeserError::TrailingBytes {
+            position: input.len() - rest.len(),
+        });
+    }
+    Ok(value)
+}
+
+/// Deserializes one value from the start of `input`, and returns it with the bytes after it,
+/// for reading several values that were written one after another.
+pub fn take_from_slice<'input, 'facet, T: Facet<'facet>>(
+    input: &'input [u8],
+) -> Result<(T, &'input [u8]), PostcardDeserError> {
+    let mut runner = PostcardDeserializerStack {
+        input,
+        pos: 0,
+        stack: alloc::vec![DeserializeTask::Value],
+    };
+    let mut wip = Wip::alloc::<T>()?;
+    while let Some(task) = runner.stack.pop() {
+        wip = runner.run(task, wip)?;
+    }
+    let value = wip.build()?.materialize::<T>()?;
+    Ok((value, &input[runner.pos..]))
+}
+
+#[derive(Debug)]
+enum DeserializeTask {
+    Value,
+    Field(usize),
+    /// Items still to push onto a list, array, set or tuple
+    Items(usize),
+    /// Entries still to insert into a map
+    Entries(usize),
+    MapValue,
+    Pop,
+}
+
+struct PostcardDeserializerStack<'input> {
+    input: &'input [u8],
+    pos: usize,
+    stack: Vec<DeserializeTask>,
+}
+
+impl<'input> PostcardDeserializerStack<'input> {
+    fn next_byte(&mut self) -> Result<u8, PostcardDeserError> {
+        let byte = *self
+            .input
+            .get(self.pos)
+            .ok_or(PostcardDeserError::UnexpectedEof)?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn next_bytes(&mut self, len: usize) -> Result<&'input [u8], PostcardDeserError> {
+        if self.input.len() - self.pos < len {
+            return Err(PostcardDeserError::UnexpectedEof);
+        }
+        self.pos += len;
+        Ok(&self.input[self.pos - len..self.pos])
+    }
+
+    /// Reads a varint that has to fit in `bits` bits.
+    fn next_varint(&mut self, bits: u32) -> Result<u128, PostcardDeserError> {
+        let position = self.pos;
+        let mut value = 0u128;
+        for index in 0..varint::max_len(bits) {
+            let byte = self.next_byte()?;
+            let shift = 7 * index as u32;
+            let group = u128::from(byte & 0x7f);
+            if shift + 7 > 128 && group >> (128 - shift) != 0 {
+                return Err(PostcardDeserError::VarintOverflow { position });
+            }
+            value |= group << shift;
+            if byte & 0x80 == 0 {
+                if bits < 128 && value >> bits != 0 {
+                    return Err(PostcardDeserError::VarintOverflow { position });
+                }
+                return Ok(value);
+            }
+        }
+        Err(PostcardDeserError::VarintOverflow { position })
+    }
+
+    fn next_signed(&mut self, bits: u32) -> Result<i128, PostcardDeserError> {
+        Ok(varint::unzigzag(self.next_varint(bits)?))
+    }
+
+    fn next_len(&mut self) -> Result<usize, PostcardDeserError> {
+        let position = self.pos;
+        usize::try_from(self.next_varint(64)?)
+            .map_err(|_| PostcardDeserError::VarintOverflow { position })
+    }
+
+    fn next_str(&mut self) -> Result<&'input str, PostcardDeserError> {
+        let position = self.pos;
+        let len = self.next_len()?;
+        core::str::from_utf8(self.next_bytes(len)?)
+            .map_err(|source| PostcardDeserError::InvalidString { position, source })
+    }
+
+    fn run<'facet, 'shape>(
+        &mut self,
+        task: DeserializeTask,
+        wip: Wip<'facet, 'shape>,
+    ) -> Result<Wip<'facet, 'shape>, PostcardDeserError> {
+        match task {
+            DeserializeTask::Value => self.next(wip),
+            DeserializeTask::Field(index) => {
+                self.stack.push(DeserializeTask::Pop);
+                self.stack.push(DeserializeTask::Value);
+                Ok(wip.field(index)?)
+            }
+            DeserializeTask::Items(0) | DeserializeTask::Entries(0) => Ok(wip),
+            DeserializeTask::Items(count) => {
+                self.stack.push(DeserializeTask::Items(count - 1));
+                self.stack.push(DeserializeTask::Pop);
+                self.stack.push(DeserializeTask::Value);
+                Ok(wip.push()?)
+            }
+            DeserializeTask::Entries(count) => {
+                self.stack.push(DeserializeTask::Entries(count - 1));
+                self.stack.push(DeserializeTask::Pop);
+                self.stack.push(DeserializeTask::Value);
+                self.stack.push(DeserializeTask::MapValue);
+                self.stack.push(DeserializeTask::Value);
+                Ok(wip.push_map_key()?)
+            }
+            DeserializeTask::MapValue => Ok(wip.push_map_value()?),
+            DeserializeTask::Pop => Ok(wip.pop()?),
+        }
+    }
+
+    fn next<'facet, 'shape>(
+        &mut self,
+        wip: Wip<'facet, 'shape>,
+    ) -> Result<Wip<'facet, 'shape>, PostcardDeserError> {
+        let shape = wip.shape();
+        match (shape.def, shape.ty) {
+            (Def::Scalar(_), _) => self.next_scalar(wip),
+            (Def::List(ld), _) if ld.t().is_type::<u8>() => {
+                let len = self.next_len()?;
+                let bytes = self.next_bytes(len)?;
+                Ok(wip.put(bytes.to_vec())?)
+            }
+            (Def::List(_), _) => {
+                let len = self.next_len()?;
+                self.stack.push(DeserializeTask::Items(len));
+                Ok(wip.begin_pushback()?)
+            }
+            (Def::Array(ad), _) => {
+                self.stack.push(DeserializeTask::Items(ad.n));
+                Ok(wip.begin_pushback()?)
+            }
+            (Def::Set(_), _) => {
+                let len = self.next_len()?;
+                self.stack.push(DeserializeTask::Items(len));
+                Ok(wip.begin_set_insert()?)
+            }
+            (Def::Map(_), _) => {
+                let len = self.next_len()?;
+                self.stack.push(DeserializeTask::Entries(len));
+                Ok(wip.begin_map_insert()?)
+            }
+            (Def::Option(_), _) => match self.next_byte()? {
+                0 => Ok(wip.put_default()?),
+                1 => {
+                    self.stack.push(DeserializeTask::Pop);
+                    self.stack.push(DeserializeTask::Value);
+                    Ok(wip.push_some()?)
+                }
+                _ => Err(PostcardDeserError::InvalidOptional {
+                    position: self.pos - 1,
+                }),
+            },
+            (_, Type::User(UserType::Struct(st))) => {
+                for index in (0..st.fields.len()).rev() {
+                    self.stack.push(DeserializeTask::Field(index));
+                }
+                Ok(wip)
+            }
+            (_, Type::User(UserType::Enum(et))) => {
+                let position = self.pos;
+                let index = self.next_varint(32)? as usize;
+                let Some(variant) = et.variants.get(index) else {
+                    return Err(PostcardDeserError::InvalidVariant { position });
+                };
+                for index in (0..variant.data.fields.len()).rev() {
+                    self.stack.push(DeserializeTask::Field(index));
+                }
+                Ok(wip.variant(index)?)
+            }
+            (_, Type::Sequence(SequenceType::Tuple(tt))) => {
+                self.stack.push(DeserializeTask::Items(tt.fields.len()));
+                Ok(wip)
+            }
+            _ => Err(PostcardDeserError::UnsupportedType(shape.to_string())),
+        }
+    }
+
+    fn next_scalar<'facet, 'shape>(
+        &mut self,
+        wip: Wip<'facet, 'shape>,
+    ) -> Result<Wip<'facet, 'shape>, PostcardDeserError> {
+        let shape = wip.innermost_shape();
+        let wip = match ScalarType::try_from_shape(shape) {
+            Some(ScalarType::Unit) => wip.put(())?,
+            Some(ScalarType::Bool) => match self.next_byte()? {
+                0 => wip.put(false)?,
+                1 => wip.put(true)?,
+                _ => {
+                    return Err(PostcardDeserError::InvalidBoolean {
+                        position: self.pos - 1,
+                    });
+                }
+            },
+            Some(ScalarType::Char) => {
+                let position = self.pos;
+                let mut chars = self.next_str()?.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => wip.put(c)?,
+                    _ => return Err(PostcardDeserError::InvalidChar { position }),
+                }
+            }
+            Some(ScalarType::String) => wip.put(self.next_str()?.to_owned())?,
+            Some(ScalarType::CowStr) => {
+                wip.put(Cow::<'static, str>::Owned(self.next_str()?.to_owned()))?
+            }
+            Some(ScalarType::F32) => {
+                let bytes = self.next_bytes(4)?;
+                wip.put(f32::from_le_bytes(bytes.try_into().unwrap()))?
+            }
+            Some(ScalarType::F64) => {
+                let bytes = self.next_bytes(8)?;
+                wip.put(f64::from_le_bytes(bytes.try_into().unwrap()))?
+            }
+            Some(ScalarType::U8) => wip.put(self.next_byte()?)?,
+            Some(ScalarType::U16) => wip.put(self.next_varint(16)? as u16)?,
+            Some(ScalarType::U32) => wip.put(self.next_varint(32)? as u32)?,
+            Some(ScalarType::U64) => wip.put(self.next_varint(64)? as u64)?,
+            Some(ScalarType::U128) => wip.put(self.next_varint(128)?)?,
+            Some(ScalarType::USize) => wip.put(self.next_varint(usize::BITS)? as usize)?,
+            Some(ScalarType::I8) => wip.put(self.next_byte()? as i8)?,
+            Some(ScalarType::I16) => wip.put(self.next_signed(16)? as i16)?,
+            Some(ScalarType::I32) => wip.put(self.next_signed(32)? as i32)?,
+            Some(ScalarType::I64) => wip.put(self.next_signed(64)? as i64)?,
+            Some(ScalarType::I128) => wip.put(self.next_signed(128)?)?,
+            Some(ScalarType::ISize) => wip.put(self.next_signed(usize::BITS)? as isize)?,
+            // Addresses, dates, UUIDs and the like are their text
+            _ if shape.vtable.parse.is_some() => wip.parse(self.next_str()?)?,
+            _ => return Err(PostcardDeserError::UnsupportedType(shape.to_string())),
+        };
+        Ok(wip)
+    }
+}
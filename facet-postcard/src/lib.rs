@@ -0,0 +1,19 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(missing_docs)]
+#![warn(clippy::std_instead_of_core)]
+#![warn(clippy::std_instead_of_alloc)]
+#![forbid(unsafe_code)]
+#![doc = include_str!("../README.md")]
+
+#[cfg(not(feature = "alloc"))]
+compile_error!("feature `alloc` is required");
+
+extern crate alloc;
+
+mod varint;
+
+mod serialize;
+pub use serialize::*;
+
+mod deserialize;
+pub use deserialize::*;
@@ -0,0 +1,218 @@
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use facet_core::{Def, Facet, SequenceType, Type, UserType};
+use facet_reflect::{HasFields, Peek, ScalarType};
+
+use crate::varint;
+
+/// Errors when serializing to postcard bytes
+#[derive(Debug)]
+pub enum PostcardSerError {
+    /// IO error
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+    /// A type that has no postcard representation, like a smart pointer or a borrowed value
+    UnsupportedType(String),
+}
+
+impl core::fmt::Display for PostcardSerError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            PostcardSerError::Io(error) => write!(f, "IO error: {}", error),
+            PostcardSerError::UnsupportedType(shape) => write!(f, "Unsupported type: {}", shape),
+        }
+    }
+}
+
+impl core::error::Error for PostcardSerError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            PostcardSerError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Serializes any Facet type to postcard bytes.
+///
+/// # Example
+///
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Ping {
+///     seq: u32,
+///     payload: Vec<u8>,
+/// }
+///
+/// let bytes = facet_postcard::to_vec(&Ping { seq: 300, payload: vec![7] }).unwrap();
+/// assert_eq!(bytes, [0xac, 0x02, 0x01, 0x07]);
+/// ```
+pub fn to_vec<'a, T: Facet<'a>>(value: &'a T) -> Result<Vec<u8>, PostcardSerError> {
+    let mut output = Vec::new();
+    serialize(Peek::new(value), &mut output)?;
+    Ok(output)
+}
+
+/// Serializes any Facet type to postcard bytes, and writes them to `writer`.
+#[cfg(feature = "std")]
+pub fn to_writer<'a, T: Facet<'a>, W: std::io::Write>(
+    value: &'a T,
+    writer: &mut W,
+) -> Result<(), PostcardSerError> {
+    let bytes = to_vec(value)?;
+    writer.write_all(&bytes).map_err(PostcardSerError::Io)
+}
+
+fn unsupported(peek: Peek<'_, '_, '_>) -> PostcardSerError {
+    PostcardSerError::UnsupportedType(peek.shape().to_string())
+}
+
+fn write_str(output: &mut Vec<u8>, value: &str) {
+    varint::write(output, value.len() as u128);
+    output.extend_from_slice(value.as_bytes());
+}
+
+fn write_signed(output: &mut Vec<u8>, value: i128) {
+    varint::write(output, varint::zigzag(value));
+}
+
+/// Writes a value's fields, items and entries in the order the shape gives them. Nothing
+/// names or delimits them: both sides know the layout from the shape.
+fn serialize<'mem, 'facet, 'shape>(
+    peek: Peek<'mem, 'facet, 'shape>,
+    output: &mut Vec<u8>,
+) -> Result<(), PostcardSerError>
+where
+    'mem: 'facet,
+{
+    let mut stack = Vec::new();
+    stack.push(peek);
+
+    while let Some(peek) = stack.pop() {
+        match (peek.shape().def, peek.shape().ty) {
+            (Def::Scalar(_), _) => {
+                let peek = peek.innermost_peek();
+                match peek.scalar_type() {
+                    Some(ScalarType::Unit) => {}
+                    Some(ScalarType::Bool) => output.push(*peek.get::<bool>().unwrap() as u8),
+                    Some(ScalarType::Char) => {
+                        let mut buf = [0; 4];
+                        write_str(output, peek.get::<char>().unwrap().encode_utf8(&mut buf))
+                    }
+                    Some(ScalarType::Str) => write_str(output, peek.get::<&str>().unwrap()),
+                    Some(ScalarType::String) => write_str(output, peek.get::<String>().unwrap()),
+                    Some(ScalarType::CowStr) => {
+                        write_str(output, peek.get::<Cow<'_, str>>().unwrap())
+                    }
+                    Some(ScalarType::F32) => {
+                        output.extend_from_slice(&peek.get::<f32>().unwrap().to_le_bytes())
+                    }
+                    Some(ScalarType::F64) => {
+                        output.extend_from_slice(&peek.get::<f64>().unwrap().to_le_bytes())
+                    }
+                    Some(ScalarType::U8) => output.push(*peek.get::<u8>().unwrap()),
+                    Some(ScalarType::U16) => {
+                        varint::write(output, (*peek.get::<u16>().unwrap()).into())
+                    }
+                    Some(ScalarType::U32) => {
+                        varint::write(output, (*peek.get::<u32>().unwrap()).into())
+                    }
+                    Some(ScalarType::U64) => {
+                        varint::write(output, (*peek.get::<u64>().unwrap()).into())
+                    }
+                    Some(ScalarType::U128) => varint::write(output, *peek.get::<u128>().unwrap()),
+                    Some(ScalarType::USize) => {
+                        varint::write(output, *peek.get::<usize>().unwrap() as u128)
+                    }
+                    Some(ScalarType::I8) => output.push(*peek.get::<i8>().unwrap() as u8),
+                    Some(ScalarType::I16) => {
+                        write_signed(output, (*peek.get::<i16>().unwrap()).into())
+                    }
+                    Some(ScalarType::I32) => {
+                        write_signed(output, (*peek.get::<i32>().unwrap()).into())
+                    }
+                    Some(ScalarType::I64) => {
+                        write_signed(output, (*peek.get::<i64>().unwrap()).into())
+                    }
+                    Some(ScalarType::I128) => write_signed(output, *peek.get::<i128>().unwrap()),
+                    Some(ScalarType::ISize) => {
+                        write_signed(output, *peek.get::<isize>().unwrap() as i128)
+                    }
+                    // Addresses, dates, UUIDs and the like are their text
+                    _ if peek.shape().vtable.display.is_some() => {
+                        write_str(output, &format!("{}", peek))
+                    }
+                    _ => return Err(unsupported(peek)),
+                }
+            }
+            // Other lists of bytes are written the same way, a byte at a time
+            (Def::List(_), _) if peek.shape().is_type::<Vec<u8>>() => {
+                let bytes = peek.get::<Vec<u8>>().unwrap();
+                varint::write(output, bytes.len() as u128);
+                output.extend_from_slice(bytes);
+            }
+            (Def::List(_) | Def::Slice(_), _) => {
+                let list = peek.into_list_like().unwrap();
+                varint::write(output, list.len() as u128);
+                let items: Vec<_> = list.iter().collect();
+                stack.extend(items.into_iter().rev());
+            }
+            (Def::Array(_), _) => {
+                // The length is part of the type, so it isn't written
+                let items: Vec<_> = peek.into_list_like().unwrap().iter().collect();
+                stack.extend(items.into_iter().rev());
+            }
+            (Def::Set(_), _) => {
+                let set = peek.into_set().unwrap();
+                varint::write(output, set.len() as u128);
+                let items: Vec<_> = set.iter().collect();
+                stack.extend(items.into_iter().rev());
+            }
+            (Def::Map(_), _) => {
+                let map = peek.into_map().unwrap();
+                varint::write(output, map.len() as u128);
+                let entries: Vec<_> = map.iter().collect();
+                for (key, value) in entries.into_iter().rev() {
+                    stack.push(value);
+                    stack.push(key);
+                }
+            }
+            (Def::Option(_), _) => match peek.into_option().unwrap().value() {
+                Some(inner) => {
+                    output.push(1);
+                    stack.push(inner);
+                }
+                None => output.push(0),
+            },
+            (_, Type::User(UserType::Struct(_))) => {
+                let fields: Vec<_> = peek.into_struct().unwrap().fields().collect();
+                stack.extend(fields.into_iter().rev().map(|(_, field)| field));
+            }
+            (_, Type::User(UserType::Enum(_))) => {
+                // Variants are numbered by their position, whatever their discriminant is
+                let peek_enum = peek.into_enum().unwrap();
+                varint::write(output, peek_enum.variant_index().unwrap() as u128);
+                let fields: Vec<_> = peek_enum.fields().collect();
+                stack.extend(fields.into_iter().rev().map(|(_, field)| field));
+            }
+            (_, Type::Sequence(SequenceType::Tuple(_))) => {
+                let fields: Vec<_> = peek.into_tuple().unwrap().fields().collect();
+                stack.extend(fields.into_iter().rev().map(|(_, field)| field));
+            }
+            (_, Type::Pointer(_)) => match peek.as_str() {
+                Some(value) => write_str(output, value),
+                None => return Err(unsupported(peek)),
+            },
+            _ => return Err(unsupported(peek)),
+        }
+    }
+
+    Ok(())
+}
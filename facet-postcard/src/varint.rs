@@ -0,0 +1,29 @@
+//! LEB128 variable-length integers, and the zigzag encoding that makes small negative numbers
+//! short too.
+
+use alloc::vec::Vec;
+
+/// Writes an unsigned integer seven bits at a time, least significant group first, with the
+/// top bit of each byte set when more follow.
+pub(crate) fn write(output: &mut Vec<u8>, mut value: u128) {
+    while value >= 0x80 {
+        output.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    output.push(value as u8);
+}
+
+/// The most bytes an integer of this many bits can take.
+pub(crate) const fn max_len(bits: u32) -> usize {
+    bits.div_ceil(7) as usize
+}
+
+/// Maps signed integers onto unsigned ones so that -1 is 1, 1 is 2, -2 is 3 and so on.
+pub(crate) fn zigzag(value: i128) -> u128 {
+    ((value << 1) ^ (value >> 127)) as u128
+}
+
+/// Undoes [`zigzag`].
+pub(crate) fn unzigzag(value: u128) -> i128 {
+    (value >> 1) as i128 ^ -((value & 1) as i128)
+}
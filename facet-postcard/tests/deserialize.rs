@@ -0,0 +1,162 @@
+use std::collections::{BTreeSet, HashMap};
+
+use facet::Facet;
+use facet_postcard::{PostcardDeserError, from_slice, take_from_slice, to_vec};
+use facet_testhelpers::test;
+
+#[derive(Facet, Debug, PartialEq)]
+#[repr(u8)]
+enum Command {
+    Stop,
+    Say(String),
+    Move { x: i32, y: i32 },
+    Pair(u8, u8),
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Message {
+    id: u64,
+    command: Command,
+    reply_to: Option<String>,
+    payload: Vec<u8>,
+    weights: [f32; 2],
+    tags: BTreeSet<String>,
+    counters: HashMap<String, i64>,
+    origin: (char, std::net::Ipv4Addr),
+}
+
+#[test]
+fn test_round_trip() {
+    let messages = vec![
+        Message {
+            id: u64::MAX,
+            command: Command::Move {
+                x: i32::MIN,
+                y: 300,
+            },
+            reply_to: Some("ipc://reply".to_string()),
+            payload: vec![0x00, 0x7f, 0xff],
+            weights: [0.5, -1.25],
+            tags: BTreeSet::from(["a".to_string(), "b".to_string()]),
+            counters: HashMap::from([("sent".to_string(), -3), ("seen".to_string(), 1 << 40)]),
+            origin: ('\u{1f980}', "127.0.0.1".parse()?),
+        },
+        Message {
+            id: 0,
+            command: Command::Stop,
+            reply_to: None,
+            payload: Vec::new(),
+            weights: [0.0, 0.0],
+            tags: BTreeSet::new(),
+            counters: HashMap::new(),
+            origin: ('x', "10.0.0.1".parse()?),
+        },
+    ];
+    let bytes = to_vec(&messages)?;
+    assert_eq!(from_slice::<Vec<Message>>(&bytes)?, messages);
+
+    for command in [Command::Say("hi".to_string()), Command::Pair(1, 2)] {
+        assert_eq!(from_slice::<Command>(&to_vec(&command)?)?, command);
+    }
+
+    for value in [i128::MIN, -1, 0, 1, i128::MAX] {
+        assert_eq!(from_slice::<i128>(&to_vec(&value)?)?, value);
+    }
+    assert_eq!(from_slice::<u128>(&to_vec(&u128::MAX)?)?, u128::MAX);
+    assert_eq!(from_slice::<isize>(&to_vec(&isize::MIN)?)?, isize::MIN);
+}
+
+#[test]
+fn test_transparent_and_unit_structs() {
+    #[derive(Facet, Debug, PartialEq)]
+    #[facet(transparent)]
+    struct Id(u32);
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Marker;
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Entry {
+        id: Id,
+        marker: Marker,
+        parent: Option<Id>,
+    }
+
+    let entry: Entry = from_slice(&[0xe8, 0x07, 0x01, 0x05])?;
+    assert_eq!(
+        entry,
+        Entry {
+            id: Id(1000),
+            marker: Marker,
+            parent: Some(Id(5)),
+        }
+    );
+}
+
+#[test]
+fn test_take_from_slice() {
+    // Two messages, one after the other
+    let mut bytes = to_vec(&Command::Say("a".to_string()))?;
+    bytes.extend(to_vec(&Command::Pair(1, 2))?);
+
+    let (first, rest) = take_from_slice::<Command>(&bytes)?;
+    assert_eq!(first, Command::Say("a".to_string()));
+    let (second, rest) = take_from_slice::<Command>(rest)?;
+    assert_eq!(second, Command::Pair(1, 2));
+    assert!(rest.is_empty());
+
+    // `from_slice` wants all of it
+    assert!(matches!(
+        from_slice::<Command>(&bytes),
+        Err(PostcardDeserError::TrailingBytes { position: 3 })
+    ));
+}
+
+#[test]
+fn test_errors() {
+    assert!(matches!(
+        from_slice::<String>(&[0x05, b'a']),
+        Err(PostcardDeserError::UnexpectedEof)
+    ));
+    assert!(matches!(
+        from_slice::<u32>(&[0xff, 0xff]),
+        Err(PostcardDeserError::UnexpectedEof)
+    ));
+
+    // Varints that don't fit their type
+    assert!(matches!(
+        from_slice::<u16>(&[0x80, 0x80, 0x04]),
+        Err(PostcardDeserError::VarintOverflow { position: 0 })
+    ));
+    assert!(matches!(
+        from_slice::<u32>(&[0xff, 0xff, 0xff, 0xff, 0xff, 0x01]),
+        Err(PostcardDeserError::VarintOverflow { position: 0 })
+    ));
+    let mut bytes = vec![0xff; 18];
+    bytes.push(0x04);
+    assert!(matches!(
+        from_slice::<u128>(&bytes),
+        Err(PostcardDeserError::VarintOverflow { position: 0 })
+    ));
+
+    assert!(matches!(
+        from_slice::<(u8, bool)>(&[0x01, 0x02]),
+        Err(PostcardDeserError::InvalidBoolean { position: 1 })
+    ));
+    assert!(matches!(
+        from_slice::<Option<u8>>(&[0x02, 0x01]),
+        Err(PostcardDeserError::InvalidOptional { position: 0 })
+    ));
+    assert!(matches!(
+        from_slice::<Command>(&[0x04]),
+        Err(PostcardDeserError::InvalidVariant { position: 0 })
+    ));
+    assert!(matches!(
+        from_slice::<String>(&[0x02, 0xc3, 0x28]),
+        Err(PostcardDeserError::InvalidString { position: 0, .. })
+    ));
+    assert!(matches!(
+        from_slice::<char>(&[0x02, b'a', b'b']),
+        Err(PostcardDeserError::InvalidChar { position: 0 })
+    ));
+}
@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+
+use facet::Facet;
+use facet_postcard::to_vec;
+use facet_testhelpers::test;
+
+#[test]
+fn test_integers() {
+    // Bytes are themselves, wider integers are varints
+    assert_eq!(to_vec(&0u8)?, [0x00]);
+    assert_eq!(to_vec(&255u8)?, [0xff]);
+    assert_eq!(to_vec(&-1i8)?, [0xff]);
+    assert_eq!(to_vec(&127u16)?, [0x7f]);
+    assert_eq!(to_vec(&128u16)?, [0x80, 0x01]);
+    assert_eq!(to_vec(&16384u32)?, [0x80, 0x80, 0x01]);
+    assert_eq!(to_vec(&u16::MAX)?, [0xff, 0xff, 0x03]);
+    assert_eq!(to_vec(&u32::MAX)?, [0xff, 0xff, 0xff, 0xff, 0x0f]);
+    assert_eq!(to_vec(&u64::MAX)?, {
+        let mut expected = vec![0xff; 9];
+        expected.push(0x01);
+        expected
+    });
+    assert_eq!(to_vec(&u128::MAX)?.len(), 19);
+    assert_eq!(to_vec(&300usize)?, [0xac, 0x02]);
+
+    // Signed integers are zigzagged first
+    assert_eq!(to_vec(&0i32)?, [0x00]);
+    assert_eq!(to_vec(&-1i32)?, [0x01]);
+    assert_eq!(to_vec(&1i32)?, [0x02]);
+    assert_eq!(to_vec(&-64i16)?, [0x7f]);
+    assert_eq!(to_vec(&64i16)?, [0x80, 0x01]);
+    assert_eq!(to_vec(&i16::MIN)?, [0xff, 0xff, 0x03]);
+    assert_eq!(to_vec(&i64::MIN)?, to_vec(&u64::MAX)?);
+    assert_eq!(to_vec(&-300isize)?, [0xd7, 0x04]);
+}
+
+#[test]
+fn test_scalars() {
+    assert_eq!(to_vec(&true)?, [0x01]);
+    assert_eq!(to_vec(&false)?, [0x00]);
+    assert_eq!(to_vec(&1.5f32)?, [0x00, 0x00, 0xc0, 0x3f]);
+    assert_eq!(to_vec(&-2.0f64)?, [0, 0, 0, 0, 0, 0, 0, 0xc0]);
+    assert_eq!(to_vec(&"hi")?, [0x02, b'h', b'i']);
+    assert_eq!(to_vec(&"\u{00fc}".to_string())?, [0x02, 0xc3, 0xbc]);
+    assert_eq!(to_vec(&'\u{00fc}')?, [0x02, 0xc3, 0xbc]);
+    assert_eq!(to_vec(&())?, []);
+    assert_eq!(
+        to_vec(&"10.0.0.1".parse::<std::net::Ipv4Addr>()?)?,
+        b"\x0810.0.0.1"
+    );
+}
+
+#[test]
+fn test_sequences() {
+    // Lengths for lists, none for arrays and tuples
+    assert_eq!(to_vec(&vec![1u8, 2, 3])?, [0x03, 0x01, 0x02, 0x03]);
+    assert_eq!(to_vec(&vec![1u16, 300])?, [0x02, 0x01, 0xac, 0x02]);
+    assert_eq!(to_vec(&Vec::<String>::new())?, [0x00]);
+    assert_eq!(to_vec(&[1u32, 2, 3])?, [0x01, 0x02, 0x03]);
+    assert_eq!(to_vec(&(1u8, -1i32, "a"))?, [0x01, 0x01, 0x01, b'a']);
+
+    let map = BTreeMap::from([("a", 1u32), ("b", 2)]);
+    assert_eq!(to_vec(&map)?, [0x02, 0x01, b'a', 0x01, 0x01, b'b', 0x02]);
+}
+
+#[test]
+fn test_structs_and_options() {
+    #[derive(Facet)]
+    struct Unit;
+
+    #[derive(Facet)]
+    struct Pair(u8, i8);
+
+    #[derive(Facet)]
+    #[facet(transparent)]
+    struct Id(u32);
+
+    #[derive(Facet)]
+    struct Header {
+        id: Id,
+        pair: Pair,
+        unit: Unit,
+        name: Option<String>,
+        next: Option<u32>,
+    }
+
+    let header = Header {
+        id: Id(1000),
+        pair: Pair(1, -1),
+        unit: Unit,
+        name: Some("x".to_string()),
+        next: None,
+    };
+    assert_eq!(
+        to_vec(&header)?,
+        [0xe8, 0x07, 0x01, 0xff, 0x01, 0x01, b'x', 0x00]
+    );
+}
+
+#[test]
+fn test_enums() {
+    #[derive(Facet)]
+    #[repr(u16)]
+    #[allow(dead_code)]
+    enum Command {
+        Stop = 10,
+        Say(String),
+        Move { x: i32, y: i32 },
+        Pair(u8, u8),
+    }
+
+    // Variants are numbered by their position, not their discriminant
+    assert_eq!(to_vec(&Command::Stop)?, [0x00]);
+    assert_eq!(
+        to_vec(&Command::Say("hi".to_string()))?,
+        [0x01, 0x02, b'h', b'i']
+    );
+    assert_eq!(to_vec(&Command::Move { x: -1, y: 1 })?, [0x02, 0x01, 0x02]);
+    assert_eq!(to_vec(&Command::Pair(3, 4))?, [0x03, 0x03, 0x04]);
+}
+
+#[test]
+fn test_to_writer() {
+    let mut output = Vec::new();
+    facet_postcard::to_writer(&vec!["a", "b"], &mut output)?;
+    assert_eq!(output, [0x02, 0x01, b'a', 0x01, b'b']);
+}
@@ -51,6 +51,7 @@ enum StackState {
 
 enum SeqKind {
     List,
+    Set,
     Tuple,
 }
 
@@ -301,6 +302,18 @@ impl PrettyPrinter {
                             item.type_depth += 1;
                             stack.push_back(item);
                         }
+                        (Def::Set(_), _) => {
+                            self.write_type_name(f, &item.value)?;
+                            item.state = StackState::ProcessSeqItem {
+                                item_index: 0,
+                                kind: SeqKind::Set,
+                            };
+                            self.write_punctuation(f, " {")?;
+                            writeln!(f)?;
+                            item.format_depth += 1;
+                            item.type_depth += 1;
+                            stack.push_back(item);
+                        }
                         (Def::Map(_), _) => {
                             let _map = item.value.into_map().unwrap();
                             // Print the map name
@@ -615,6 +628,10 @@ impl PrettyPrinter {
                             let list = item.value.into_list_like().unwrap();
                             (list.len(), list.get(item_index))
                         }
+                        SeqKind::Set => {
+                            let set = item.value.into_set().unwrap();
+                            (set.len(), set.iter().nth(item_index))
+                        }
                         SeqKind::Tuple => {
                             let tuple = item.value.into_tuple().unwrap();
                             (tuple.len(), tuple.field(item_index))
@@ -632,6 +649,7 @@ impl PrettyPrinter {
                             f,
                             match kind {
                                 SeqKind::List => "]",
+                                SeqKind::Set => "}",
                                 SeqKind::Tuple => ")",
                             },
                         )?;
@@ -183,3 +183,10 @@ fn test_u32_slice() {
     let nums = [1u32, 2u32, 3u32, 4u32];
     assert_snapshot!(printer.format(&&nums[..]));
 }
+
+#[test]
+fn test_btreeset_u32() {
+    let printer = PrettyPrinter::new().with_colors(false);
+    let nums = std::collections::BTreeSet::from([3u32, 1u32, 2u32]);
+    assert_snapshot!(printer.format(&nums));
+}
@@ -0,0 +1,521 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use facet_core::{Def, Facet, Field, Type, UserType};
+use facet_reflect::{ReflectError, ScalarType, Wip};
+
+use crate::SchemaError;
+use crate::schema::{Element, FieldKind, Messages, ProtoField, ProtoScalar, VariantContent};
+use crate::wire::{self, I32, I64, Reader, Record, VARINT, WireValue};
+
+/// Errors when deserializing from protobuf bytes
+#[derive(Debug)]
+pub enum ProtobufDeserError {
+    /// Unexpected end of input
+    UnexpectedEof {
+        /// Position of this error in bytes
+        position: usize,
+    },
+    /// A varint longer than ten bytes, or too large for 64 bits
+    VarintOverflow {
+        /// Position of this error in bytes
+        position: usize,
+    },
+    /// A wire type that doesn't exist, or a group, which are deprecated and not supported
+    InvalidWireType {
+        /// Position of this error in bytes
+        position: usize,
+        /// The wire type, as read
+        wire_type: u8,
+    },
+    /// A field number of zero, or one that doesn't fit in 32 bits
+    InvalidTag {
+        /// Position of this error in bytes
+        position: usize,
+    },
+    /// A field whose wire type isn't the one its type is written with
+    WrongWireType {
+        /// The number of the field
+        tag: u32,
+        /// Position of this error in bytes
+        position: usize,
+    },
+    /// A number that doesn't fit in the Rust type of its field
+    NumberOutOfRange {
+        /// The number of the field
+        tag: u32,
+        /// Position of this error in bytes
+        position: usize,
+    },
+    /// Invalid string
+    InvalidString {
+        /// Position of this error in bytes
+        position: usize,
+        /// Underlying UTF-8 error
+        source: core::str::Utf8Error,
+    },
+    /// A string that isn't exactly one character, where a `char` was expected
+    InvalidChar {
+        /// Position of this error in bytes
+        position: usize,
+    },
+    /// No field of a oneof was set, but the enum it stands for isn't optional
+    MissingField(String),
+    /// A repeated field with fewer or more values than the array it's read into
+    WrongLength {
+        /// The number of the field
+        tag: u32,
+        /// Position of the message the field is in
+        position: usize,
+    },
+    /// The type can't be a protobuf message
+    Schema(SchemaError),
+    /// The value could not be built
+    ReflectError(String),
+}
+
+impl fmt::Display for ProtobufDeserError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtobufDeserError::UnexpectedEof { position } => {
+                write!(f, "Unexpected end of input at byte {}", position)
+            }
+            ProtobufDeserError::VarintOverflow { position } => {
+                write!(f, "Varint too large at byte {}", position)
+            }
+            ProtobufDeserError::InvalidWireType {
+                position,
+                wire_type,
+            } => write!(f, "Invalid wire type {} at byte {}", wire_type, position),
+            ProtobufDeserError::InvalidTag { position } => {
+                write!(f, "Invalid field number at byte {}", position)
+            }
+            ProtobufDeserError::WrongWireType { tag, position } => {
+                write!(f, "Wrong wire type for field {} at byte {}", tag, position)
+            }
+            ProtobufDeserError::NumberOutOfRange { tag, position } => {
+                write!(
+                    f,
+                    "Number out of range for field {} at byte {}",
+                    tag, position
+                )
+            }
+            ProtobufDeserError::InvalidString { position, .. } => {
+                write!(f, "Invalid string at byte {}", position)
+            }
+            ProtobufDeserError::InvalidChar { position } => {
+                write!(f, "Invalid char at byte {}", position)
+            }
+            ProtobufDeserError::MissingField(name) => write!(f, "Missing field: {}", name),
+            ProtobufDeserError::WrongLength { tag, position } => write!(
+                f,
+                "Wrong number of values for field {} in the message at byte {}",
+                tag, position
+            ),
+            ProtobufDeserError::Schema(error) => write!(f, "{}", error),
+            ProtobufDeserError::ReflectError(error) => write!(f, "Reflection error: {}", error),
+        }
+    }
+}
+
+impl Error for ProtobufDeserError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ProtobufDeserError::InvalidString { source, .. } => Some(source),
+            ProtobufDeserError::Schema(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ReflectError<'_>> for ProtobufDeserError {
+    fn from(error: ReflectError<'_>) -> Self {
+        ProtobufDeserError::ReflectError(error.to_string())
+    }
+}
+
+impl From<SchemaError> for ProtobufDeserError {
+    fn from(error: SchemaError) -> Self {
+        ProtobufDeserError::Schema(error)
+    }
+}
+
+/// Deserializes the bytes of a protobuf message into a struct or an enum.
+///
+/// Fields that aren't in the message get their default value, and fields the type doesn't
+/// have are skipped, so that messages from newer or older versions of a schema can be read.
+///
+/// # Example
+///
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet, Debug, PartialEq)]
+/// struct Test1 {
+///     #[facet(proto_tag = 1)]
+///     a: i32,
+/// }
+///
+/// let test: Test1 = facet_protobuf::from_slice(&[0x08, 0x96, 0x01]).unwrap();
+/// assert_eq!(test, Test1 { a: 150 });
+/// ```
+pub fn from_slice<'facet, T: Facet<'facet>>(input: &[u8]) -> Result<T, ProtobufDeserError> {
+    let wip = Wip::alloc::<T>()?;
+    let wip = read_message(wip, &mut Messages::default(), input, 0)?;
+    Ok(wip.build()?.materialize::<T>()?)
+}
+
+/// Reads a message into the struct or enum `wip` is at. `base` is where `input` starts in
+/// the whole input.
+fn read_message<'facet, 'shape>(
+    wip: Wip<'facet, 'shape>,
+    messages: &mut Messages<'shape>,
+    input: &[u8],
+    base: usize,
+) -> Result<Wip<'facet, 'shape>, ProtobufDeserError> {
+    let shape = wip.shape();
+    let proto_fields = messages.fields(shape)?;
+    let fields = match shape.ty {
+        Type::User(UserType::Struct(struct_type)) => struct_type.fields,
+        _ => &[],
+    };
+    let records = Tagged::new(wire::records(input, base)?);
+    read_fields(wip, messages, &proto_fields, fields, &records, base)
+}
+
+/// The fields of a message, grouped by their tag, in the order they came in.
+struct Tagged<'input> {
+    records: HashMap<u32, Vec<Record<'input>>>,
+}
+
+impl<'input> Tagged<'input> {
+    fn new(records: Vec<Record<'input>>) -> Self {
+        let mut by_tag: HashMap<u32, Vec<Record<'input>>> = HashMap::new();
+        for record in records {
+            by_tag.entry(record.tag).or_default().push(record);
+        }
+        Self { records: by_tag }
+    }
+
+    fn with_tag(&self, tag: u32) -> &[Record<'input>] {
+        self.records.get(&tag).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Reads the fields of a struct or a variant, and gives the ones that are never serialized
+/// their default value.
+fn read_fields<'facet, 'shape>(
+    mut wip: Wip<'facet, 'shape>,
+    messages: &mut Messages<'shape>,
+    proto_fields: &[ProtoField<'shape>],
+    fields: &'shape [Field<'shape>],
+    records: &Tagged,
+    base: usize,
+) -> Result<Wip<'facet, 'shape>, ProtobufDeserError> {
+    for proto_field in proto_fields {
+        wip = match proto_field.field {
+            Some(_) => {
+                let wip = wip.field(proto_field.index)?;
+                read_field(wip, messages, proto_field, records, base)?.pop()?
+            }
+            // An enum that is a message of its own is its only field
+            None => read_field(wip, messages, proto_field, records, base)?,
+        };
+    }
+    for (index, field) in fields.iter().enumerate() {
+        if proto_fields.iter().any(|f| f.index == index) {
+            continue;
+        }
+        wip = wip.field(index)?;
+        wip = match field.vtable.default_fn {
+            Some(default_fn) => wip.put_from_fn(default_fn)?,
+            None => wip.put_default()?,
+        };
+        wip = wip.pop()?;
+    }
+    Ok(wip)
+}
+
+fn read_field<'facet, 'shape>(
+    wip: Wip<'facet, 'shape>,
+    messages: &mut Messages<'shape>,
+    proto_field: &ProtoField<'shape>,
+    records: &Tagged,
+    base: usize,
+) -> Result<Wip<'facet, 'shape>, ProtobufDeserError> {
+    let wip = match &proto_field.kind {
+        FieldKind::Single {
+            tag,
+            element,
+            optional,
+        } => {
+            let found = records.with_tag(*tag);
+            match optional {
+                true if found.is_empty() => wip.put_default()?,
+                true => read_value(wip.push_some()?, messages, *element, found, base)?.pop()?,
+                false => read_value(wip, messages, *element, found, base)?,
+            }
+        }
+        FieldKind::Repeated { tag, element } => {
+            let shape = wip.shape();
+            let mut wip = match shape.def {
+                Def::Set(_) => wip.begin_set_insert()?,
+                _ => wip.begin_pushback()?,
+            };
+            let mut count = 0;
+            for &record in records.with_tag(*tag) {
+                match (element, record.value) {
+                    // Numbers and booleans may be packed, whichever way they were declared
+                    (Element::Scalar(scalar), WireValue::Len(bytes)) if scalar.is_packable() => {
+                        let mut reader = Reader::new(bytes, record.position);
+                        while !reader.is_empty() {
+                            let position = reader.position();
+                            let value = match scalar.wire_type() {
+                                VARINT => WireValue::Varint(reader.varint()?),
+                                I32 => WireValue::I32(reader.fixed32()?),
+                                I64 => WireValue::I64(reader.fixed64()?),
+                                _ => unreachable!(),
+                            };
+                            let item = Record {
+                                tag: *tag,
+                                value,
+                                position,
+                            };
+                            wip = read_value(wip.push()?, messages, *element, &[item], base)?
+                                .pop()?;
+                            count += 1;
+                        }
+                    }
+                    _ => {
+                        wip =
+                            read_value(wip.push()?, messages, *element, &[record], base)?.pop()?;
+                        count += 1;
+                    }
+                }
+            }
+            if let Def::Array(array_def) = shape.def {
+                if count != array_def.n {
+                    return Err(ProtobufDeserError::WrongLength {
+                        tag: *tag,
+                        position: base,
+                    });
+                }
+            }
+            wip
+        }
+        FieldKind::Map { tag, key, value } => {
+            let mut wip = wip.begin_map_insert()?;
+            for record in records.with_tag(*tag) {
+                let WireValue::Len(bytes) = record.value else {
+                    return Err(ProtobufDeserError::WrongWireType {
+                        tag: *tag,
+                        position: record.position,
+                    });
+                };
+                let entry = Tagged::new(wire::records(bytes, record.position)?);
+                let (keys, values) = (entry.with_tag(1), entry.with_tag(2));
+                wip = read_value(
+                    wip.push_map_key()?,
+                    messages,
+                    Element::Scalar(*key),
+                    keys,
+                    base,
+                )?;
+                wip = read_value(wip.push_map_value()?, messages, *value, values, base)?.pop()?;
+            }
+            wip
+        }
+        FieldKind::Oneof {
+            enum_shape,
+            variants,
+            optional,
+        } => {
+            // The last field of a oneof in the message is the one that's set
+            let set = variants
+                .iter()
+                .filter_map(|variant| Some((variant, *records.with_tag(variant.tag).last()?)))
+                .max_by_key(|(_, record)| record.position);
+            let Some((variant, record)) = set else {
+                if *optional {
+                    return Ok(wip.put_default()?);
+                }
+                let name = match proto_field.field {
+                    Some(field) => field.name.to_owned(),
+                    None => enum_shape.to_string(),
+                };
+                return Err(ProtobufDeserError::MissingField(name));
+            };
+            let mut wip = match optional {
+                true => wip.push_some()?,
+                false => wip,
+            };
+            wip = wip.variant(variant.index)?;
+            wip = match &variant.content {
+                VariantContent::Empty => match record.value {
+                    WireValue::Len(_) => wip,
+                    _ => {
+                        return Err(ProtobufDeserError::WrongWireType {
+                            tag: variant.tag,
+                            position: record.position,
+                        });
+                    }
+                },
+                VariantContent::Newtype(element) => {
+                    let found = records.with_tag(variant.tag);
+                    read_value(wip.field(0)?, messages, *element, found, base)?.pop()?
+                }
+                VariantContent::Fields(fields) => {
+                    let WireValue::Len(bytes) = record.value else {
+                        return Err(ProtobufDeserError::WrongWireType {
+                            tag: variant.tag,
+                            position: record.position,
+                        });
+                    };
+                    let records = Tagged::new(wire::records(bytes, record.position)?);
+                    let all_fields = variant.variant.data.fields;
+                    read_fields(wip, messages, fields, all_fields, &records, record.position)?
+                }
+            };
+            match optional {
+                true => wip.pop()?,
+                false => wip,
+            }
+        }
+    };
+    Ok(wip)
+}
+
+/// Reads a single value from the fields that have its tag. The last one wins for scalars,
+/// and messages are merged. A value that isn't there is its default.
+fn read_value<'facet, 'shape>(
+    wip: Wip<'facet, 'shape>,
+    messages: &mut Messages<'shape>,
+    element: Element,
+    found: &[Record],
+    base: usize,
+) -> Result<Wip<'facet, 'shape>, ProtobufDeserError> {
+    match element {
+        Element::Scalar(_) if found.is_empty() => Ok(wip.put_default()?),
+        Element::Scalar(scalar) => put_scalar(wip, scalar, found[found.len() - 1]),
+        Element::Message(_) => {
+            let mut parts = Vec::new();
+            for record in found {
+                match record.value {
+                    WireValue::Len(bytes) => parts.push(bytes),
+                    _ => {
+                        return Err(ProtobufDeserError::WrongWireType {
+                            tag: record.tag,
+                            position: record.position,
+                        });
+                    }
+                }
+            }
+            match (found.first(), parts.as_slice()) {
+                (None, _) => read_message(wip, messages, &[], base),
+                (Some(record), [bytes]) => read_message(wip, messages, bytes, record.position),
+                // Positions are only right in the first part, but a merged message is rare
+                (Some(record), _) => read_message(wip, messages, &parts.concat(), record.position),
+            }
+        }
+    }
+}
+
+/// A scalar as it was on the wire, before it's put in its Rust type.
+enum Value<'input> {
+    Integer(i128),
+    F32(f32),
+    F64(f64),
+    Text(&'input str),
+    Bytes(&'input [u8]),
+}
+
+fn put_scalar<'facet, 'shape>(
+    wip: Wip<'facet, 'shape>,
+    scalar: ProtoScalar,
+    record: Record,
+) -> Result<Wip<'facet, 'shape>, ProtobufDeserError> {
+    let Record {
+        tag,
+        value,
+        position,
+    } = record;
+    let value = match (scalar, value) {
+        (ProtoScalar::Int32 | ProtoScalar::Int64, WireValue::Varint(n)) => {
+            Value::Integer((n as i64).into())
+        }
+        (ProtoScalar::Uint32 | ProtoScalar::Uint64 | ProtoScalar::Bool, WireValue::Varint(n)) => {
+            Value::Integer(n.into())
+        }
+        (ProtoScalar::Sint32 | ProtoScalar::Sint64, WireValue::Varint(n)) => {
+            Value::Integer(wire::unzigzag(n).into())
+        }
+        (ProtoScalar::Fixed32, WireValue::I32(bytes)) => {
+            Value::Integer(u32::from_le_bytes(bytes).into())
+        }
+        (ProtoScalar::Sfixed32, WireValue::I32(bytes)) => {
+            Value::Integer(i32::from_le_bytes(bytes).into())
+        }
+        (ProtoScalar::Fixed64, WireValue::I64(bytes)) => {
+            Value::Integer(u64::from_le_bytes(bytes).into())
+        }
+        (ProtoScalar::Sfixed64, WireValue::I64(bytes)) => {
+            Value::Integer(i64::from_le_bytes(bytes).into())
+        }
+        (ProtoScalar::Float, WireValue::I32(bytes)) => Value::F32(f32::from_le_bytes(bytes)),
+        (ProtoScalar::Double, WireValue::I64(bytes)) => Value::F64(f64::from_le_bytes(bytes)),
+        (ProtoScalar::String, WireValue::Len(bytes)) => Value::Text(
+            core::str::from_utf8(bytes)
+                .map_err(|source| ProtobufDeserError::InvalidString { position, source })?,
+        ),
+        (ProtoScalar::Bytes, WireValue::Len(bytes)) => Value::Bytes(bytes),
+        _ => return Err(ProtobufDeserError::WrongWireType { tag, position }),
+    };
+
+    fn narrow<T: TryFrom<i128>>(
+        n: i128,
+        tag: u32,
+        position: usize,
+    ) -> Result<T, ProtobufDeserError> {
+        T::try_from(n).map_err(|_| ProtobufDeserError::NumberOutOfRange { tag, position })
+    }
+
+    let shape = wip.innermost_shape();
+    let wip = match (ScalarType::try_from_shape(shape), value) {
+        (Some(ScalarType::Bool), Value::Integer(n)) => wip.put(n != 0)?,
+        (Some(ScalarType::U8), Value::Integer(n)) => wip.put(narrow::<u8>(n, tag, position)?)?,
+        (Some(ScalarType::U16), Value::Integer(n)) => wip.put(narrow::<u16>(n, tag, position)?)?,
+        (Some(ScalarType::U32), Value::Integer(n)) => wip.put(narrow::<u32>(n, tag, position)?)?,
+        (Some(ScalarType::U64), Value::Integer(n)) => wip.put(narrow::<u64>(n, tag, position)?)?,
+        (Some(ScalarType::USize), Value::Integer(n)) => {
+            wip.put(narrow::<usize>(n, tag, position)?)?
+        }
+        (Some(ScalarType::I8), Value::Integer(n)) => wip.put(narrow::<i8>(n, tag, position)?)?,
+        (Some(ScalarType::I16), Value::Integer(n)) => wip.put(narrow::<i16>(n, tag, position)?)?,
+        (Some(ScalarType::I32), Value::Integer(n)) => wip.put(narrow::<i32>(n, tag, position)?)?,
+        (Some(ScalarType::I64), Value::Integer(n)) => wip.put(narrow::<i64>(n, tag, position)?)?,
+        (Some(ScalarType::ISize), Value::Integer(n)) => {
+            wip.put(narrow::<isize>(n, tag, position)?)?
+        }
+        (Some(ScalarType::F32), Value::F32(x)) => wip.put(x)?,
+        (Some(ScalarType::F64), Value::F64(x)) => wip.put(x)?,
+        (Some(ScalarType::Char), Value::Text(text)) => {
+            let mut chars = text.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => wip.put(c)?,
+                _ => return Err(ProtobufDeserError::InvalidChar { position }),
+            }
+        }
+        (Some(ScalarType::String), Value::Text(text)) => wip.put(text.to_owned())?,
+        (Some(ScalarType::CowStr), Value::Text(text)) => {
+            wip.put(Cow::<'static, str>::Owned(text.to_owned()))?
+        }
+        // Addresses, dates, UUIDs and the like are their text
+        (_, Value::Text(text)) if shape.vtable.parse.is_some() => wip.parse(text)?,
+        (_, Value::Bytes(bytes)) => wip.put(bytes.to_vec())?,
+        // Borrowed strings can't outlive the input
+        _ => return Err(SchemaError::UnsupportedType(shape.to_string()).into()),
+    };
+    Ok(wip)
+}
@@ -0,0 +1,17 @@
+#![warn(missing_docs)]
+#![forbid(unsafe_code)]
+#![doc = include_str!("../README.md")]
+
+mod schema;
+pub use schema::SchemaError;
+
+mod wire;
+
+mod serialize;
+pub use serialize::*;
+
+mod deserialize;
+pub use deserialize::*;
+
+mod proto;
+pub use proto::*;
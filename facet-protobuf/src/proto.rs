@@ -0,0 +1,235 @@
+use core::fmt::Write;
+
+use facet_core::Shape;
+
+use crate::SchemaError;
+use crate::schema::{Element, FieldKind, ProtoField, ProtoVariant, VariantContent, message_fields};
+
+/// Generates a `.proto` file with a message for each of `shapes`, and for the structs and
+/// enums they hold, so that other languages can read and write the same messages.
+///
+/// Structs are messages, and enums are oneofs: inline when they're the field of a struct,
+/// or in a message of their own otherwise. Variants with several fields are messages named
+/// after their enum and themselves, and unit variants are `google.protobuf.Empty`. Doc
+/// comments are kept.
+///
+/// # Example
+///
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Point {
+///     #[facet(proto_tag = 1, proto_encoding = "zigzag")]
+///     x: i32,
+///     #[facet(proto_tag = 2, proto_encoding = "zigzag")]
+///     y: i32,
+/// }
+///
+/// let proto = facet_protobuf::to_proto("geometry", &[Point::SHAPE]).unwrap();
+/// assert_eq!(
+///     proto,
+///     r#"syntax = "proto3";
+///
+/// package geometry;
+///
+/// message Point {
+///   sint32 x = 1;
+///   sint32 y = 2;
+/// }
+/// "#
+/// );
+/// ```
+pub fn to_proto<'shape>(
+    package: &str,
+    shapes: &[&'shape Shape<'shape>],
+) -> Result<String, SchemaError> {
+    let mut generator = Generator::default();
+    for shape in shapes {
+        generator.message_type(shape)?;
+    }
+    // Messages add the ones they hold to the end of the queue
+    let mut next = 0;
+    while let Some(shape) = generator.shapes.get(next).copied() {
+        next += 1;
+        let fields = message_fields(shape)?;
+        generator.message(&message_name(shape), shape.doc, &fields)?;
+    }
+
+    let mut output = String::from("syntax = \"proto3\";\n");
+    if !package.is_empty() {
+        writeln!(output, "\npackage {};", package).unwrap();
+    }
+    if generator.uses_empty {
+        output.push_str("\nimport \"google/protobuf/empty.proto\";\n");
+    }
+    output.push_str(&generator.messages);
+    Ok(output)
+}
+
+#[derive(Default)]
+struct Generator<'shape> {
+    /// The structs and enums that are messages, in the order they're written
+    shapes: Vec<&'shape Shape<'shape>>,
+    /// The variants that are messages of their own
+    variants: Vec<(&'shape Shape<'shape>, usize)>,
+    /// The names of all messages so far
+    names: Vec<String>,
+    uses_empty: bool,
+    messages: String,
+}
+
+impl<'shape> Generator<'shape> {
+    /// The name of the message a struct or an enum is, which is written later.
+    fn message_type(&mut self, shape: &'shape Shape<'shape>) -> Result<String, SchemaError> {
+        let name = message_name(shape);
+        if !self.shapes.contains(&shape) {
+            self.claim(&name)?;
+            self.shapes.push(shape);
+        }
+        Ok(name)
+    }
+
+    fn claim(&mut self, name: &str) -> Result<(), SchemaError> {
+        if self.names.iter().any(|n| n == name) {
+            return Err(SchemaError::DuplicateMessage(name.to_owned()));
+        }
+        self.names.push(name.to_owned());
+        Ok(())
+    }
+
+    fn element_type(&mut self, element: Element<'shape>) -> Result<String, SchemaError> {
+        match element {
+            Element::Scalar(scalar) => Ok(scalar.name().to_owned()),
+            Element::Message(shape) => self.message_type(shape),
+        }
+    }
+
+    fn message(
+        &mut self,
+        name: &str,
+        doc: &[&str],
+        fields: &[ProtoField<'shape>],
+    ) -> Result<(), SchemaError> {
+        let mut body = String::new();
+        // Variants written after this message, since messages aren't nested
+        let mut variant_messages: Vec<(String, &ProtoVariant<'shape>)> = Vec::new();
+        for proto_field in fields {
+            let (field_name, field_doc) = match proto_field.field {
+                // Values of tuple structs and tuple variants have numbers for names
+                Some(field) if field.name.starts_with(|c: char| c.is_ascii_digit()) => {
+                    (format!("field_{}", field.name), field.doc)
+                }
+                Some(field) => (field.name.to_owned(), field.doc),
+                None => (snake_case(name), &[][..]),
+            };
+            write_doc(&mut body, "  ", field_doc);
+            match &proto_field.kind {
+                FieldKind::Single {
+                    tag,
+                    element,
+                    optional,
+                } => {
+                    // Messages are optional anyway
+                    let label = match (optional, element) {
+                        (true, Element::Scalar(_)) => "optional ",
+                        _ => "",
+                    };
+                    let ty = self.element_type(*element)?;
+                    writeln!(body, "  {}{} {} = {};", label, ty, field_name, tag).unwrap();
+                }
+                FieldKind::Repeated { tag, element } => {
+                    let ty = self.element_type(*element)?;
+                    writeln!(body, "  repeated {} {} = {};", ty, field_name, tag).unwrap();
+                }
+                FieldKind::Map { tag, key, value } => {
+                    let value = self.element_type(*value)?;
+                    writeln!(
+                        body,
+                        "  map<{}, {}> {} = {};",
+                        key.name(),
+                        value,
+                        field_name,
+                        tag
+                    )
+                    .unwrap();
+                }
+                FieldKind::Oneof {
+                    enum_shape,
+                    variants,
+                    ..
+                } => {
+                    writeln!(body, "  oneof {} {{", field_name).unwrap();
+                    for variant in variants {
+                        let ty = match &variant.content {
+                            VariantContent::Empty => {
+                                self.uses_empty = true;
+                                "google.protobuf.Empty".to_owned()
+                            }
+                            VariantContent::Newtype(element) => self.element_type(*element)?,
+                            VariantContent::Fields(_) => {
+                                let name =
+                                    format!("{}{}", message_name(enum_shape), variant.variant.name);
+                                let key = (*enum_shape, variant.index);
+                                if !self.variants.contains(&key) {
+                                    self.claim(&name)?;
+                                    self.variants.push(key);
+                                    variant_messages.push((name.clone(), variant));
+                                }
+                                name
+                            }
+                        };
+                        write_doc(&mut body, "    ", variant.variant.doc);
+                        let variant_name = snake_case(variant.variant.name);
+                        writeln!(body, "    {} {} = {};", ty, variant_name, variant.tag).unwrap();
+                    }
+                    body.push_str("  }\n");
+                }
+            }
+        }
+
+        self.messages.push('\n');
+        write_doc(&mut self.messages, "", doc);
+        match body.is_empty() {
+            true => writeln!(self.messages, "message {} {{}}", name).unwrap(),
+            false => writeln!(self.messages, "message {} {{\n{}}}", name, body).unwrap(),
+        }
+        for (name, variant) in variant_messages {
+            if let VariantContent::Fields(fields) = &variant.content {
+                self.message(&name, variant.variant.doc, fields)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The name of a type without its generic parameters' punctuation, so `Page<User>` is
+/// `PageUser`.
+fn message_name(shape: &Shape) -> String {
+    shape
+        .to_string()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+        .collect()
+}
+
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (index, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if index > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+fn write_doc(output: &mut String, indent: &str, doc: &[&str]) {
+    for line in doc {
+        writeln!(output, "{}//{}", indent, line).unwrap();
+    }
+}
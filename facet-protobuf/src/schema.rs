@@ -0,0 +1,548 @@
+//! How the fields of a Rust type map onto the fields of a protobuf message.
+
+use core::fmt;
+use std::collections::HashMap;
+use std::error::Error;
+use std::rc::Rc;
+
+use facet_core::{
+    ConstTypeId, Def, EnumType, Field, FieldAttribute, FieldFlags, Shape, StructKind, Type,
+    UserType, Variant, VariantAttribute,
+};
+use facet_reflect::ScalarType;
+
+/// Why a type can't be a protobuf message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaError {
+    /// A type with no protobuf representation, like a tuple, a list of lists or a 128-bit
+    /// integer
+    UnsupportedType(String),
+    /// A named field or an enum variant without `#[facet(proto_tag = N)]`
+    MissingTag {
+        /// The type the field or variant is in
+        shape: String,
+        /// The name of the field or variant
+        name: String,
+    },
+    /// A `proto_tag` that isn't a valid field number, or that's on an enum field, whose
+    /// variants are the ones with tags
+    InvalidTag {
+        /// The type the field or variant is in
+        shape: String,
+        /// The name of the field or variant
+        name: String,
+        /// The tag, as written
+        tag: String,
+    },
+    /// Two fields of the same message with the same tag
+    DuplicateTag {
+        /// The type of the message
+        shape: String,
+        /// The tag used twice
+        tag: u32,
+    },
+    /// A `proto_encoding` that doesn't exist, or doesn't apply to the type of the field
+    InvalidEncoding {
+        /// The type the field is in
+        shape: String,
+        /// The name of the field
+        name: String,
+        /// The encoding, as written
+        encoding: String,
+    },
+    /// Two different types that would be messages of the same name in a `.proto` file
+    DuplicateMessage(String),
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::UnsupportedType(shape) => write!(f, "Unsupported type: {shape}"),
+            SchemaError::MissingTag { shape, name } => {
+                write!(f, "Missing #[facet(proto_tag = N)] on {shape}::{name}")
+            }
+            SchemaError::InvalidTag { shape, name, tag } => {
+                write!(f, "Invalid proto_tag {tag} on {shape}::{name}")
+            }
+            SchemaError::DuplicateTag { shape, tag } => {
+                write!(f, "Tag {tag} is used more than once in {shape}")
+            }
+            SchemaError::InvalidEncoding {
+                shape,
+                name,
+                encoding,
+            } => write!(f, "Invalid proto_encoding {encoding} on {shape}::{name}"),
+            SchemaError::DuplicateMessage(name) => {
+                write!(f, "Two different types are both named {name}")
+            }
+        }
+    }
+}
+
+impl Error for SchemaError {}
+
+/// The protobuf scalar types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ProtoScalar {
+    Double,
+    Float,
+    Int32,
+    Int64,
+    Uint32,
+    Uint64,
+    Sint32,
+    Sint64,
+    Fixed32,
+    Fixed64,
+    Sfixed32,
+    Sfixed64,
+    Bool,
+    String,
+    Bytes,
+}
+
+impl ProtoScalar {
+    /// The name of the type in a `.proto` file.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            ProtoScalar::Double => "double",
+            ProtoScalar::Float => "float",
+            ProtoScalar::Int32 => "int32",
+            ProtoScalar::Int64 => "int64",
+            ProtoScalar::Uint32 => "uint32",
+            ProtoScalar::Uint64 => "uint64",
+            ProtoScalar::Sint32 => "sint32",
+            ProtoScalar::Sint64 => "sint64",
+            ProtoScalar::Fixed32 => "fixed32",
+            ProtoScalar::Fixed64 => "fixed64",
+            ProtoScalar::Sfixed32 => "sfixed32",
+            ProtoScalar::Sfixed64 => "sfixed64",
+            ProtoScalar::Bool => "bool",
+            ProtoScalar::String => "string",
+            ProtoScalar::Bytes => "bytes",
+        }
+    }
+
+    /// The wire type values of this type are written with.
+    pub(crate) fn wire_type(self) -> u8 {
+        match self {
+            ProtoScalar::Double | ProtoScalar::Fixed64 | ProtoScalar::Sfixed64 => crate::wire::I64,
+            ProtoScalar::Float | ProtoScalar::Fixed32 | ProtoScalar::Sfixed32 => crate::wire::I32,
+            ProtoScalar::String | ProtoScalar::Bytes => crate::wire::LEN,
+            _ => crate::wire::VARINT,
+        }
+    }
+
+    /// Whether repeated values of this type are packed into a single length-delimited field.
+    pub(crate) fn is_packable(self) -> bool {
+        self.wire_type() != crate::wire::LEN
+    }
+
+    /// Whether this type can be the key of a map.
+    fn is_key(self) -> bool {
+        !matches!(
+            self,
+            ProtoScalar::Double | ProtoScalar::Float | ProtoScalar::Bytes
+        )
+    }
+}
+
+/// What a single value is in a message.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Element<'shape> {
+    Scalar(ProtoScalar),
+    /// A struct or an enum, as a nested message
+    Message(&'shape Shape<'shape>),
+}
+
+/// A field of a message, and the Rust field it comes from.
+#[derive(Debug)]
+pub(crate) struct ProtoField<'shape> {
+    /// The position of the Rust field in its struct or variant
+    pub(crate) index: usize,
+    /// The Rust field, which enums standing for a message of their own don't have
+    pub(crate) field: Option<&'shape Field<'shape>>,
+    pub(crate) kind: FieldKind<'shape>,
+}
+
+#[derive(Debug)]
+pub(crate) enum FieldKind<'shape> {
+    /// One value, which an `Option` may leave out
+    Single {
+        tag: u32,
+        element: Element<'shape>,
+        optional: bool,
+    },
+    /// The items of a list, a set or an array
+    Repeated { tag: u32, element: Element<'shape> },
+    /// The entries of a map, each a message with the key as field 1 and the value as field 2
+    Map {
+        tag: u32,
+        key: ProtoScalar,
+        value: Element<'shape>,
+    },
+    /// An enum, whose variants are fields of their own, at most one of them set
+    Oneof {
+        enum_shape: &'shape Shape<'shape>,
+        variants: Vec<ProtoVariant<'shape>>,
+        optional: bool,
+    },
+}
+
+/// A variant of an enum, as a field of its oneof.
+#[derive(Debug)]
+pub(crate) struct ProtoVariant<'shape> {
+    /// The position of the variant in its enum
+    pub(crate) index: usize,
+    pub(crate) variant: &'shape Variant<'shape>,
+    pub(crate) tag: u32,
+    pub(crate) content: VariantContent<'shape>,
+}
+
+#[derive(Debug)]
+pub(crate) enum VariantContent<'shape> {
+    /// A unit variant, as an empty message
+    Empty,
+    /// A variant holding a single value, as that value
+    Newtype(Element<'shape>),
+    /// A variant with named fields or several values, as a message of them
+    Fields(Vec<ProtoField<'shape>>),
+}
+
+/// The messages met while reading or writing a value, so that the fields of each type are
+/// worked out once rather than for every message of that type.
+#[derive(Default)]
+pub(crate) struct Messages<'shape> {
+    fields: HashMap<ConstTypeId, Rc<[ProtoField<'shape>]>>,
+}
+
+impl<'shape> Messages<'shape> {
+    /// The fields of the message `shape` stands for, as [`message_fields`] gives them.
+    pub(crate) fn fields(
+        &mut self,
+        shape: &'shape Shape<'shape>,
+    ) -> Result<Rc<[ProtoField<'shape>]>, SchemaError> {
+        if let Some(fields) = self.fields.get(&shape.id) {
+            return Ok(fields.clone());
+        }
+        let fields: Rc<[ProtoField<'shape>]> = message_fields(shape)?.into();
+        self.fields.insert(shape.id, fields.clone());
+        Ok(fields)
+    }
+}
+
+/// The fields of the message a struct or an enum stands for. An enum is a message with a
+/// single oneof.
+pub(crate) fn message_fields<'shape>(
+    shape: &'shape Shape<'shape>,
+) -> Result<Vec<ProtoField<'shape>>, SchemaError> {
+    let fields = match shape.ty {
+        Type::User(UserType::Struct(struct_type)) => {
+            fields_of(shape, struct_type.fields, struct_type.kind)?
+        }
+        Type::User(UserType::Enum(enum_type)) => vec![ProtoField {
+            index: 0,
+            field: None,
+            kind: oneof(shape, enum_type, false)?,
+        }],
+        _ => return Err(unsupported(shape)),
+    };
+    check_tags(shape, &fields)?;
+    Ok(fields)
+}
+
+/// The fields of a struct or a variant, leaving out those that are never serialized.
+fn fields_of<'shape>(
+    shape: &'shape Shape<'shape>,
+    fields: &'shape [Field<'shape>],
+    kind: StructKind,
+) -> Result<Vec<ProtoField<'shape>>, SchemaError> {
+    let positional = matches!(kind, StructKind::Tuple | StructKind::TupleStruct);
+    let mut proto_fields = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        if field.flags.contains(FieldFlags::SKIP_SERIALIZING) {
+            continue;
+        }
+        let tag = match attribute(field_attributes(field), "proto_tag") {
+            Some(tag) => Some(parse_tag(shape, field.name, tag)?),
+            // Values of tuple structs and tuple variants are numbered from 1
+            None if positional => Some(index as u32 + 1),
+            None => None,
+        };
+        let encoding = attribute(field_attributes(field), "proto_encoding");
+        let kind = field_kind(shape, field, tag, encoding)?;
+        proto_fields.push(ProtoField {
+            index,
+            field: Some(field),
+            kind,
+        });
+    }
+    Ok(proto_fields)
+}
+
+fn field_kind<'shape>(
+    parent: &'shape Shape<'shape>,
+    field: &'shape Field<'shape>,
+    tag: Option<u32>,
+    encoding: Option<&str>,
+) -> Result<FieldKind<'shape>, SchemaError> {
+    let shape = field.shape();
+    let (shape, optional) = match shape.def {
+        Def::Option(option_def) => (option_def.t(), true),
+        _ => (shape, false),
+    };
+    if let Type::User(UserType::Enum(enum_type)) = shape.ty {
+        if let Some(tag) = tag {
+            // The variants are the fields, with tags of their own
+            return Err(SchemaError::InvalidTag {
+                shape: parent.to_string(),
+                name: field.name.to_owned(),
+                tag: tag.to_string(),
+            });
+        }
+        return oneof(shape, enum_type, optional);
+    }
+    let Some(tag) = tag else {
+        return Err(SchemaError::MissingTag {
+            shape: parent.to_string(),
+            name: field.name.to_owned(),
+        });
+    };
+    let invalid_encoding = |encoding: &str| SchemaError::InvalidEncoding {
+        shape: parent.to_string(),
+        name: field.name.to_owned(),
+        encoding: encoding.to_owned(),
+    };
+    let element = |shape| match element(shape, encoding) {
+        Err(SchemaError::InvalidEncoding { encoding, .. }) => Err(invalid_encoding(&encoding)),
+        result => result,
+    };
+    let kind = match shape.def {
+        Def::List(list_def) if !optional && !list_def.t().is_type::<u8>() => FieldKind::Repeated {
+            tag,
+            element: element(list_def.t())?,
+        },
+        Def::Set(set_def) if !optional => FieldKind::Repeated {
+            tag,
+            element: element(set_def.t())?,
+        },
+        Def::Array(array_def) if !optional => FieldKind::Repeated {
+            tag,
+            element: element(array_def.t())?,
+        },
+        // The encoding of a map is the one of its values
+        Def::Map(map_def) if !optional => match self::element(map_def.k(), None)? {
+            Element::Scalar(key) if key.is_key() => FieldKind::Map {
+                tag,
+                key,
+                value: element(map_def.v())?,
+            },
+            _ => return Err(unsupported(field.shape())),
+        },
+        _ => FieldKind::Single {
+            tag,
+            element: element(shape)?,
+            optional,
+        },
+    };
+    Ok(kind)
+}
+
+/// What a value of type `shape` is, when it's a single value of a field.
+pub(crate) fn element<'shape>(
+    shape: &'shape Shape<'shape>,
+    encoding: Option<&str>,
+) -> Result<Element<'shape>, SchemaError> {
+    if let Def::Option(_) = shape.def {
+        return Err(unsupported(shape));
+    }
+    // Transparent wrappers are what they wrap, as long as that's a scalar
+    if let Some(inner) = shape.inner {
+        return match element(inner(), encoding)? {
+            Element::Scalar(scalar) => Ok(Element::Scalar(scalar)),
+            Element::Message(_) => Err(unsupported(shape)),
+        };
+    }
+    match (shape.def, shape.ty) {
+        (Def::Scalar(_), _) => scalar(shape, encoding).map(Element::Scalar),
+        (Def::List(list_def), _) if list_def.t().is_type::<u8>() => {
+            no_encoding(encoding)?;
+            Ok(Element::Scalar(ProtoScalar::Bytes))
+        }
+        (_, Type::User(UserType::Struct(_) | UserType::Enum(_))) => {
+            no_encoding(encoding)?;
+            Ok(Element::Message(shape))
+        }
+        _ => Err(unsupported(shape)),
+    }
+}
+
+/// The protobuf type of a Rust scalar, with the `proto_encoding` of its field.
+fn scalar(shape: &Shape, encoding: Option<&str>) -> Result<ProtoScalar, SchemaError> {
+    let Some(scalar_type) = ScalarType::try_from_shape(shape) else {
+        // Addresses, dates, UUIDs and the like are their text
+        if shape.vtable.display.is_some() && shape.vtable.parse.is_some() {
+            no_encoding(encoding)?;
+            return Ok(ProtoScalar::String);
+        }
+        return Err(unsupported(shape));
+    };
+    let proto = match (scalar_type, encoding) {
+        (ScalarType::Bool, None | Some("varint")) => ProtoScalar::Bool,
+        (ScalarType::U8 | ScalarType::U16 | ScalarType::U32, None | Some("varint")) => {
+            ProtoScalar::Uint32
+        }
+        (ScalarType::U8 | ScalarType::U16 | ScalarType::U32, Some("fixed")) => ProtoScalar::Fixed32,
+        (ScalarType::U64 | ScalarType::USize, None | Some("varint")) => ProtoScalar::Uint64,
+        (ScalarType::U64 | ScalarType::USize, Some("fixed")) => ProtoScalar::Fixed64,
+        (ScalarType::I8 | ScalarType::I16 | ScalarType::I32, None | Some("varint")) => {
+            ProtoScalar::Int32
+        }
+        (ScalarType::I8 | ScalarType::I16 | ScalarType::I32, Some("zigzag")) => ProtoScalar::Sint32,
+        (ScalarType::I8 | ScalarType::I16 | ScalarType::I32, Some("fixed")) => {
+            ProtoScalar::Sfixed32
+        }
+        (ScalarType::I64 | ScalarType::ISize, None | Some("varint")) => ProtoScalar::Int64,
+        (ScalarType::I64 | ScalarType::ISize, Some("zigzag")) => ProtoScalar::Sint64,
+        (ScalarType::I64 | ScalarType::ISize, Some("fixed")) => ProtoScalar::Sfixed64,
+        (ScalarType::F32, None | Some("fixed")) => ProtoScalar::Float,
+        (ScalarType::F64, None | Some("fixed")) => ProtoScalar::Double,
+        (ScalarType::Char | ScalarType::Str | ScalarType::String | ScalarType::CowStr, None) => {
+            ProtoScalar::String
+        }
+        (ScalarType::U128 | ScalarType::I128 | ScalarType::Unit, _) => {
+            return Err(unsupported(shape));
+        }
+        (_, None) if shape.vtable.display.is_some() && shape.vtable.parse.is_some() => {
+            ProtoScalar::String
+        }
+        (_, Some(encoding)) => return Err(encoding_error(encoding)),
+        _ => return Err(unsupported(shape)),
+    };
+    Ok(proto)
+}
+
+fn no_encoding(encoding: Option<&str>) -> Result<(), SchemaError> {
+    match encoding {
+        Some(encoding) => Err(encoding_error(encoding)),
+        None => Ok(()),
+    }
+}
+
+/// An encoding error without its field, which [`field_kind`] fills in.
+fn encoding_error(encoding: &str) -> SchemaError {
+    SchemaError::InvalidEncoding {
+        shape: String::new(),
+        name: String::new(),
+        encoding: encoding.to_owned(),
+    }
+}
+
+/// The oneof an enum stands for, with a field for each of its variants.
+fn oneof<'shape>(
+    shape: &'shape Shape<'shape>,
+    enum_type: EnumType<'shape>,
+    optional: bool,
+) -> Result<FieldKind<'shape>, SchemaError> {
+    let mut variants = Vec::new();
+    for (index, variant) in enum_type.variants.iter().enumerate() {
+        let Some(tag) = attribute(variant_attributes(variant), "proto_tag") else {
+            return Err(SchemaError::MissingTag {
+                shape: shape.to_string(),
+                name: variant.name.to_owned(),
+            });
+        };
+        let tag = parse_tag(shape, variant.name, tag)?;
+        let fields = variant.data.fields;
+        let content = match variant.data.kind {
+            StructKind::Unit => VariantContent::Empty,
+            StructKind::Tuple | StructKind::TupleStruct
+                if fields.len() == 1 && field_attributes(&fields[0]).next().is_none() =>
+            {
+                match element(fields[0].shape(), None) {
+                    Ok(element) => VariantContent::Newtype(element),
+                    // Lists, maps and options can't be oneof fields, but can be in a message
+                    Err(_) => VariantContent::Fields(fields_of(shape, fields, variant.data.kind)?),
+                }
+            }
+            kind => VariantContent::Fields(fields_of(shape, fields, kind)?),
+        };
+        if let VariantContent::Fields(fields) = &content {
+            check_tags(shape, fields)?;
+        }
+        variants.push(ProtoVariant {
+            index,
+            variant,
+            tag,
+            content,
+        });
+    }
+    Ok(FieldKind::Oneof {
+        enum_shape: shape,
+        variants,
+        optional,
+    })
+}
+
+/// Checks that no two fields of a message, or variants of its oneofs, share a tag.
+fn check_tags(shape: &Shape, fields: &[ProtoField]) -> Result<(), SchemaError> {
+    let mut tags: Vec<u32> = Vec::new();
+    for field in fields {
+        let field_tags: Vec<u32> = match &field.kind {
+            FieldKind::Single { tag, .. }
+            | FieldKind::Repeated { tag, .. }
+            | FieldKind::Map { tag, .. } => vec![*tag],
+            FieldKind::Oneof { variants, .. } => variants.iter().map(|v| v.tag).collect(),
+        };
+        for tag in field_tags {
+            if tags.contains(&tag) {
+                return Err(SchemaError::DuplicateTag {
+                    shape: shape.to_string(),
+                    tag,
+                });
+            }
+            tags.push(tag);
+        }
+    }
+    Ok(())
+}
+
+fn parse_tag(shape: &Shape, name: &str, tag: &str) -> Result<u32, SchemaError> {
+    match tag.parse::<u32>() {
+        // Field numbers are 29 bits, and 19000 to 19999 are reserved for protobuf itself
+        Ok(tag @ 1..=0x1fff_ffff) if !(19000..=19999).contains(&tag) => Ok(tag),
+        _ => Err(SchemaError::InvalidTag {
+            shape: shape.to_string(),
+            name: name.to_owned(),
+            tag: tag.to_owned(),
+        }),
+    }
+}
+
+fn field_attributes<'shape>(field: &'shape Field<'shape>) -> impl Iterator<Item = &'shape str> {
+    field.attributes.iter().filter_map(|attr| match attr {
+        FieldAttribute::Arbitrary(attr) => Some(*attr),
+        _ => None,
+    })
+}
+
+fn variant_attributes<'shape>(
+    variant: &'shape Variant<'shape>,
+) -> impl Iterator<Item = &'shape str> {
+    variant.attributes.iter().filter_map(|attr| match attr {
+        VariantAttribute::Arbitrary(attr) => Some(*attr),
+        _ => None,
+    })
+}
+
+/// The value of the `key = value` attribute, without its quotes.
+fn attribute<'a>(mut attributes: impl Iterator<Item = &'a str>, key: &str) -> Option<&'a str> {
+    attributes.find_map(|attr| {
+        let value = attr.strip_prefix(key)?.trim_start().strip_prefix('=')?;
+        Some(value.trim().trim_matches('"'))
+    })
+}
+
+pub(crate) fn unsupported(shape: &Shape) -> SchemaError {
+    SchemaError::UnsupportedType(shape.to_string())
+}
@@ -0,0 +1,301 @@
+use std::borrow::Cow;
+
+use facet_core::{Def, Facet};
+use facet_reflect::{Peek, ScalarType};
+
+use crate::SchemaError;
+use crate::schema::{
+    Element, FieldKind, Messages, ProtoField, ProtoScalar, VariantContent, unsupported,
+};
+use crate::wire::{self, LEN};
+
+/// Serializes a struct or an enum to the bytes of a protobuf message.
+///
+/// Fields are numbered by their `#[facet(proto_tag = N)]` attribute. Fields holding their
+/// default value are left out, as proto3 does, and so are `None`s.
+///
+/// # Example
+///
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Test1 {
+///     #[facet(proto_tag = 1)]
+///     a: i32,
+/// }
+///
+/// let bytes = facet_protobuf::to_vec(&Test1 { a: 150 }).unwrap();
+/// assert_eq!(bytes, [0x08, 0x96, 0x01]);
+/// ```
+pub fn to_vec<'a, T: Facet<'a>>(value: &'a T) -> Result<Vec<u8>, SchemaError> {
+    let mut output = Vec::new();
+    write_message(Peek::new(value), &mut Messages::default(), &mut output)?;
+    Ok(output)
+}
+
+fn write_message<'shape>(
+    peek: Peek<'_, '_, 'shape>,
+    messages: &mut Messages<'shape>,
+    output: &mut Vec<u8>,
+) -> Result<(), SchemaError> {
+    let fields = messages.fields(peek.shape())?;
+    write_fields(peek, messages, &fields, output)
+}
+
+/// Writes the fields of a struct, or of the variant an enum holds.
+fn write_fields<'shape>(
+    peek: Peek<'_, '_, 'shape>,
+    messages: &mut Messages<'shape>,
+    fields: &[ProtoField],
+    output: &mut Vec<u8>,
+) -> Result<(), SchemaError> {
+    for proto_field in fields {
+        // An enum that is a message of its own is its only field
+        let value = match proto_field.field {
+            Some(_) => child(peek, proto_field.index)?,
+            None => peek,
+        };
+        match &proto_field.kind {
+            FieldKind::Single {
+                tag,
+                element,
+                optional: true,
+            } => {
+                if let Some(value) = option_value(value)? {
+                    write_element(value, messages, *tag, *element, output)?;
+                }
+            }
+            FieldKind::Single {
+                tag,
+                element: Element::Scalar(scalar),
+                optional: false,
+            } => {
+                // Zeros, empty strings and `false` are what a missing field reads as
+                let mut payload = Vec::new();
+                write_scalar(value, *scalar, &mut payload)?;
+                let is_default = match scalar.wire_type() {
+                    LEN => payload.is_empty(),
+                    _ => payload.iter().all(|byte| *byte == 0),
+                };
+                if !is_default {
+                    write_payload(output, *tag, *scalar, &payload);
+                }
+            }
+            FieldKind::Single { tag, element, .. } => {
+                write_element(value, messages, *tag, *element, output)?
+            }
+            FieldKind::Repeated { tag, element } => {
+                let items: Vec<_> = match value.shape().def {
+                    Def::Set(_) => value
+                        .into_set()
+                        .map_err(|_| unsupported(value.shape()))?
+                        .iter()
+                        .collect(),
+                    _ => value
+                        .into_list_like()
+                        .map_err(|_| unsupported(value.shape()))?
+                        .iter()
+                        .collect(),
+                };
+                match element {
+                    // Numbers and booleans are packed into a single field
+                    Element::Scalar(scalar) if scalar.is_packable() => {
+                        if items.is_empty() {
+                            continue;
+                        }
+                        let mut packed = Vec::new();
+                        for item in items {
+                            write_scalar(item, *scalar, &mut packed)?;
+                        }
+                        wire::write_key(output, *tag, LEN);
+                        wire::write_len(output, &packed);
+                    }
+                    _ => {
+                        for item in items {
+                            write_element(item, messages, *tag, *element, output)?;
+                        }
+                    }
+                }
+            }
+            FieldKind::Map { tag, key, value: v } => {
+                let map = value.into_map().map_err(|_| unsupported(value.shape()))?;
+                for (entry_key, entry_value) in map.iter() {
+                    let mut entry = Vec::new();
+                    write_element(entry_key, messages, 1, Element::Scalar(*key), &mut entry)?;
+                    write_element(entry_value, messages, 2, *v, &mut entry)?;
+                    wire::write_key(output, *tag, LEN);
+                    wire::write_len(output, &entry);
+                }
+            }
+            FieldKind::Oneof {
+                variants, optional, ..
+            } => {
+                let value = if *optional {
+                    match option_value(value)? {
+                        Some(value) => value,
+                        None => continue,
+                    }
+                } else {
+                    value
+                };
+                let shape = value.shape();
+                let peek_enum = value.into_enum().map_err(|_| unsupported(shape))?;
+                let index = peek_enum.variant_index().map_err(|_| unsupported(shape))?;
+                let Some(variant) = variants.iter().find(|v| v.index == index) else {
+                    return Err(unsupported(shape));
+                };
+                match &variant.content {
+                    VariantContent::Empty => {
+                        wire::write_key(output, variant.tag, LEN);
+                        wire::write_len(output, &[]);
+                    }
+                    VariantContent::Newtype(element) => {
+                        let Ok(Some(inner)) = peek_enum.field(0) else {
+                            return Err(unsupported(shape));
+                        };
+                        write_element(inner, messages, variant.tag, *element, output)?;
+                    }
+                    VariantContent::Fields(fields) => {
+                        let mut message = Vec::new();
+                        write_fields(value, messages, fields, &mut message)?;
+                        wire::write_key(output, variant.tag, LEN);
+                        wire::write_len(output, &message);
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The field at `index` of a struct, or of the variant an enum holds.
+fn child<'mem, 'facet, 'shape>(
+    peek: Peek<'mem, 'facet, 'shape>,
+    index: usize,
+) -> Result<Peek<'mem, 'facet, 'shape>, SchemaError> {
+    let field = match peek.into_struct() {
+        Ok(peek_struct) => peek_struct.field(index).ok(),
+        Err(_) => match peek.into_enum() {
+            Ok(peek_enum) => peek_enum.field(index).ok().flatten(),
+            Err(_) => None,
+        },
+    };
+    field.ok_or_else(|| unsupported(peek.shape()))
+}
+
+/// What an `Option` holds, if anything.
+fn option_value<'mem, 'facet, 'shape>(
+    peek: Peek<'mem, 'facet, 'shape>,
+) -> Result<Option<Peek<'mem, 'facet, 'shape>>, SchemaError> {
+    match peek.into_option() {
+        Ok(option) => Ok(option.value()),
+        Err(_) => Err(unsupported(peek.shape())),
+    }
+}
+
+/// Writes a value with its key, whatever the value is.
+fn write_element<'shape>(
+    peek: Peek<'_, '_, 'shape>,
+    messages: &mut Messages<'shape>,
+    tag: u32,
+    element: Element,
+    output: &mut Vec<u8>,
+) -> Result<(), SchemaError> {
+    match element {
+        Element::Scalar(scalar) => {
+            let mut payload = Vec::new();
+            write_scalar(peek, scalar, &mut payload)?;
+            write_payload(output, tag, scalar, &payload);
+        }
+        Element::Message(_) => {
+            let mut message = Vec::new();
+            write_message(peek, messages, &mut message)?;
+            wire::write_key(output, tag, LEN);
+            wire::write_len(output, &message);
+        }
+    }
+    Ok(())
+}
+
+fn write_payload(output: &mut Vec<u8>, tag: u32, scalar: ProtoScalar, payload: &[u8]) {
+    wire::write_key(output, tag, scalar.wire_type());
+    match scalar.wire_type() {
+        LEN => wire::write_len(output, payload),
+        _ => output.extend_from_slice(payload),
+    }
+}
+
+/// Writes a scalar without its key, nor its length when it's a string or bytes.
+fn write_scalar(
+    peek: Peek<'_, '_, '_>,
+    scalar: ProtoScalar,
+    output: &mut Vec<u8>,
+) -> Result<(), SchemaError> {
+    let peek = peek.innermost_peek();
+    match scalar {
+        ProtoScalar::String => match text(peek) {
+            Some(text) => output.extend_from_slice(text.as_bytes()),
+            None => return Err(unsupported(peek.shape())),
+        },
+        ProtoScalar::Bytes => match peek.get::<Vec<u8>>() {
+            Ok(bytes) => output.extend_from_slice(bytes),
+            Err(_) => return Err(unsupported(peek.shape())),
+        },
+        ProtoScalar::Float => output.extend_from_slice(&peek.get::<f32>().unwrap().to_le_bytes()),
+        ProtoScalar::Double => output.extend_from_slice(&peek.get::<f64>().unwrap().to_le_bytes()),
+        _ => {
+            let Some(n) = integer(peek) else {
+                return Err(unsupported(peek.shape()));
+            };
+            match scalar {
+                // Negative numbers take all ten bytes, as if they were 64 bits
+                ProtoScalar::Int32
+                | ProtoScalar::Int64
+                | ProtoScalar::Uint32
+                | ProtoScalar::Uint64
+                | ProtoScalar::Bool => wire::write_varint(output, n as u64),
+                ProtoScalar::Sint32 | ProtoScalar::Sint64 => {
+                    wire::write_varint(output, wire::zigzag(n as i64))
+                }
+                ProtoScalar::Fixed32 => output.extend_from_slice(&(n as u32).to_le_bytes()),
+                ProtoScalar::Sfixed32 => output.extend_from_slice(&(n as i32).to_le_bytes()),
+                ProtoScalar::Fixed64 => output.extend_from_slice(&(n as u64).to_le_bytes()),
+                _ => output.extend_from_slice(&(n as i64).to_le_bytes()),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A boolean or an integer of any width, as a wider one.
+fn integer(peek: Peek<'_, '_, '_>) -> Option<i128> {
+    let n = match peek.scalar_type()? {
+        ScalarType::Bool => i128::from(*peek.get::<bool>().unwrap()),
+        ScalarType::U8 => (*peek.get::<u8>().unwrap()).into(),
+        ScalarType::U16 => (*peek.get::<u16>().unwrap()).into(),
+        ScalarType::U32 => (*peek.get::<u32>().unwrap()).into(),
+        ScalarType::U64 => (*peek.get::<u64>().unwrap()).into(),
+        ScalarType::USize => *peek.get::<usize>().unwrap() as i128,
+        ScalarType::I8 => (*peek.get::<i8>().unwrap()).into(),
+        ScalarType::I16 => (*peek.get::<i16>().unwrap()).into(),
+        ScalarType::I32 => (*peek.get::<i32>().unwrap()).into(),
+        ScalarType::I64 => (*peek.get::<i64>().unwrap()).into(),
+        ScalarType::ISize => *peek.get::<isize>().unwrap() as i128,
+        _ => return None,
+    };
+    Some(n)
+}
+
+fn text(peek: Peek<'_, '_, '_>) -> Option<String> {
+    let text = match peek.scalar_type() {
+        Some(ScalarType::Char) => peek.get::<char>().unwrap().to_string(),
+        Some(ScalarType::Str) => peek.get::<&str>().unwrap().to_string(),
+        Some(ScalarType::String) => peek.get::<String>().unwrap().clone(),
+        Some(ScalarType::CowStr) => peek.get::<Cow<'_, str>>().unwrap().to_string(),
+        // Addresses, dates, UUIDs and the like are their text
+        _ if peek.shape().vtable.display.is_some() => format!("{}", peek),
+        _ => return None,
+    };
+    Some(text)
+}
@@ -0,0 +1,179 @@
+//! The protobuf wire format: fields as a key, made of their tag and wire type, followed by a
+//! value whose length the wire type gives.
+
+use crate::ProtobufDeserError;
+
+/// Integers, as LEB128 varints
+pub(crate) const VARINT: u8 = 0;
+/// 8 little-endian bytes
+pub(crate) const I64: u8 = 1;
+/// A varint length followed by that many bytes: strings, bytes, messages and packed values
+pub(crate) const LEN: u8 = 2;
+/// 4 little-endian bytes
+pub(crate) const I32: u8 = 5;
+
+/// Writes an integer seven bits at a time, least significant group first, with the top bit
+/// of each byte set when more follow.
+pub(crate) fn write_varint(output: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        output.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    output.push(value as u8);
+}
+
+pub(crate) fn write_key(output: &mut Vec<u8>, tag: u32, wire_type: u8) {
+    write_varint(output, u64::from(tag) << 3 | u64::from(wire_type));
+}
+
+/// Writes a length-delimited value.
+pub(crate) fn write_len(output: &mut Vec<u8>, bytes: &[u8]) {
+    write_varint(output, bytes.len() as u64);
+    output.extend_from_slice(bytes);
+}
+
+/// Maps signed integers onto unsigned ones so that -1 is 1, 1 is 2, -2 is 3 and so on, for
+/// `sint32` and `sint64`.
+pub(crate) fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// Undoes [`zigzag`].
+pub(crate) fn unzigzag(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+/// The value of a field, as it is on the wire.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum WireValue<'input> {
+    Varint(u64),
+    I64([u8; 8]),
+    Len(&'input [u8]),
+    I32([u8; 4]),
+}
+
+/// A field of a message, as it is on the wire.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Record<'input> {
+    pub(crate) tag: u32,
+    pub(crate) value: WireValue<'input>,
+    /// Where the value starts in the whole input, after the length of length-delimited ones
+    pub(crate) position: usize,
+}
+
+/// Reads bytes from a part of the input, keeping track of where they are in the whole of it.
+pub(crate) struct Reader<'input> {
+    input: &'input [u8],
+    pos: usize,
+    /// Where `input` starts in the whole input
+    base: usize,
+}
+
+impl<'input> Reader<'input> {
+    pub(crate) fn new(input: &'input [u8], base: usize) -> Self {
+        Self {
+            input,
+            pos: 0,
+            base,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.pos == self.input.len()
+    }
+
+    /// Where the next byte is in the whole input.
+    pub(crate) fn position(&self) -> usize {
+        self.base + self.pos
+    }
+
+    fn eof(&self) -> ProtobufDeserError {
+        ProtobufDeserError::UnexpectedEof {
+            position: self.position(),
+        }
+    }
+
+    pub(crate) fn bytes(&mut self, len: usize) -> Result<&'input [u8], ProtobufDeserError> {
+        if self.input.len() - self.pos < len {
+            return Err(self.eof());
+        }
+        self.pos += len;
+        Ok(&self.input[self.pos - len..self.pos])
+    }
+
+    pub(crate) fn varint(&mut self) -> Result<u64, ProtobufDeserError> {
+        let position = self.position();
+        let mut value = 0u64;
+        for index in 0..10 {
+            let byte = *self.input.get(self.pos).ok_or_else(|| self.eof())?;
+            self.pos += 1;
+            let group = u64::from(byte & 0x7f);
+            // The tenth byte only has room for the top bit
+            if index == 9 && group > 1 {
+                return Err(ProtobufDeserError::VarintOverflow { position });
+            }
+            value |= group << (7 * index);
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(ProtobufDeserError::VarintOverflow { position })
+    }
+
+    pub(crate) fn fixed32(&mut self) -> Result<[u8; 4], ProtobufDeserError> {
+        Ok(self.bytes(4)?.try_into().unwrap())
+    }
+
+    pub(crate) fn fixed64(&mut self) -> Result<[u8; 8], ProtobufDeserError> {
+        Ok(self.bytes(8)?.try_into().unwrap())
+    }
+
+    /// Reads the next field of a message.
+    pub(crate) fn record(&mut self) -> Result<Record<'input>, ProtobufDeserError> {
+        let key_position = self.position();
+        let key = self.varint()?;
+        let wire_type = (key & 7) as u8;
+        let tag = match u32::try_from(key >> 3) {
+            Ok(tag) if tag > 0 => tag,
+            _ => {
+                return Err(ProtobufDeserError::InvalidTag {
+                    position: key_position,
+                });
+            }
+        };
+        let mut position = self.position();
+        let value = match wire_type {
+            VARINT => WireValue::Varint(self.varint()?),
+            I64 => WireValue::I64(self.fixed64()?),
+            LEN => {
+                let len = self.varint()?;
+                let len = usize::try_from(len).map_err(|_| self.eof())?;
+                position = self.position();
+                WireValue::Len(self.bytes(len)?)
+            }
+            I32 => WireValue::I32(self.fixed32()?),
+            // Groups are deprecated, and nothing writes them anymore
+            _ => {
+                return Err(ProtobufDeserError::InvalidWireType {
+                    position: key_position,
+                    wire_type,
+                });
+            }
+        };
+        Ok(Record {
+            tag,
+            value,
+            position,
+        })
+    }
+}
+
+/// The fields of a message, in the order they're in.
+pub(crate) fn records(input: &[u8], base: usize) -> Result<Vec<Record<'_>>, ProtobufDeserError> {
+    let mut reader = Reader::new(input, base);
+    let mut records = Vec::new();
+    while !reader.is_empty() {
+        records.push(reader.record()?);
+    }
+    Ok(records)
+}
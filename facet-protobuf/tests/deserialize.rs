@@ -0,0 +1,263 @@
+use std::collections::{BTreeMap, HashSet};
+
+use facet::Facet;
+use facet_protobuf::{ProtobufDeserError, SchemaError, from_slice, to_vec};
+use facet_testhelpers::test;
+
+#[derive(Facet, Debug, PartialEq)]
+struct Test1 {
+    #[facet(proto_tag = 1)]
+    a: i32,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[repr(u8)]
+enum Command {
+    #[facet(proto_tag = 3)]
+    Stop,
+    #[facet(proto_tag = 4)]
+    Say(String),
+    #[facet(proto_tag = 5)]
+    Move {
+        #[facet(proto_tag = 1, proto_encoding = "zigzag")]
+        x: i32,
+        #[facet(proto_tag = 2, proto_encoding = "zigzag")]
+        y: i32,
+    },
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Message {
+    #[facet(proto_tag = 1)]
+    id: u64,
+    #[facet(proto_tag = 2)]
+    tags: Vec<String>,
+    command: Command,
+    #[facet(proto_tag = 6)]
+    reply_to: Option<String>,
+    #[facet(proto_tag = 7)]
+    origin: Option<Test1>,
+    #[facet(proto_tag = 8)]
+    weights: BTreeMap<u32, Test1>,
+    #[facet(proto_tag = 9, proto_encoding = "fixed")]
+    samples: [u32; 2],
+    #[facet(proto_tag = 10)]
+    seen: HashSet<u8>,
+    #[facet(proto_tag = 11)]
+    history: Vec<Command>,
+}
+
+#[test]
+fn test_round_trip() {
+    let message = Message {
+        id: 7,
+        tags: vec!["a".to_string(), "b".to_string()],
+        command: Command::Move { x: -1, y: 300 },
+        reply_to: Some(String::new()),
+        origin: Some(Test1 { a: 0 }),
+        weights: BTreeMap::from([(1, Test1 { a: -5 }), (0, Test1 { a: 0 })]),
+        samples: [0, u32::MAX],
+        seen: HashSet::from([1, 200]),
+        history: vec![Command::Stop, Command::Say("hi".to_string())],
+    };
+    let bytes = to_vec(&message)?;
+    let back: Message = from_slice(&bytes)?;
+    assert_eq!(back, message);
+}
+
+#[test]
+fn test_defaults_and_unknown_fields() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Sparse {
+        #[facet(proto_tag = 1)]
+        a: u32,
+        #[facet(proto_tag = 2)]
+        b: String,
+        #[facet(proto_tag = 3)]
+        c: Option<u32>,
+        #[facet(proto_tag = 4)]
+        d: Test1,
+        #[facet(skip_serializing, default = 42)]
+        e: u32,
+    }
+
+    // Field 5 is a varint, 6 a fixed64, 7 bytes and 8 a fixed32, none of which `Sparse` has
+    let bytes = [
+        0x28, 0x01, 0x31, 0, 0, 0, 0, 0, 0, 0, 0, 0x3a, 0x01, 0xff, 0x45, 0, 0, 0, 0,
+    ];
+    let sparse: Sparse = from_slice(&bytes)?;
+    assert_eq!(
+        sparse,
+        Sparse {
+            a: 0,
+            b: String::new(),
+            c: None,
+            d: Test1 { a: 0 },
+            e: 42,
+        }
+    );
+}
+
+#[test]
+fn test_merging() {
+    // Scalars that appear more than once take their last value
+    let test: Test1 = from_slice(&[0x08, 0x01, 0x08, 0x02])?;
+    assert_eq!(test, Test1 { a: 2 });
+
+    // Messages that appear more than once are merged
+    #[derive(Facet, Debug, PartialEq)]
+    struct Point {
+        #[facet(proto_tag = 1)]
+        x: u32,
+        #[facet(proto_tag = 2)]
+        y: u32,
+    }
+    #[derive(Facet, Debug, PartialEq)]
+    struct Wrapper {
+        #[facet(proto_tag = 1)]
+        point: Point,
+    }
+    let wrapper: Wrapper = from_slice(&[0x0a, 0x02, 0x08, 0x01, 0x0a, 0x02, 0x10, 0x02])?;
+    assert_eq!(
+        wrapper,
+        Wrapper {
+            point: Point { x: 1, y: 2 }
+        }
+    );
+
+    // The last variant of a oneof is the one that's set
+    #[derive(Facet, Debug, PartialEq)]
+    struct Holder {
+        command: Command,
+    }
+    let holder: Holder = from_slice(&[0x1a, 0x00, 0x22, 0x01, b'x'])?;
+    assert_eq!(
+        holder,
+        Holder {
+            command: Command::Say("x".to_string())
+        }
+    );
+}
+
+#[test]
+fn test_packed_and_unpacked() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Numbers {
+        #[facet(proto_tag = 4)]
+        d: Vec<i32>,
+    }
+
+    let expected = Numbers {
+        d: vec![3, 270, 86942],
+    };
+    let packed: Numbers = from_slice(&[0x22, 0x06, 0x03, 0x8e, 0x02, 0x9e, 0xa7, 0x05])?;
+    assert_eq!(packed, expected);
+    let unpacked: Numbers = from_slice(&[0x20, 0x03, 0x20, 0x8e, 0x02, 0x20, 0x9e, 0xa7, 0x05])?;
+    assert_eq!(unpacked, expected);
+}
+
+#[test]
+fn test_errors() {
+    #[derive(Facet, Debug)]
+    struct Small {
+        #[facet(proto_tag = 1)]
+        a: u8,
+    }
+    assert!(matches!(
+        from_slice::<Small>(&[0x08, 0x80, 0x02]),
+        Err(ProtobufDeserError::NumberOutOfRange {
+            tag: 1,
+            position: 1
+        })
+    ));
+    assert!(matches!(
+        from_slice::<Small>(&[0x0a, 0x00]),
+        Err(ProtobufDeserError::WrongWireType {
+            tag: 1,
+            position: 2
+        })
+    ));
+    assert!(matches!(
+        from_slice::<Small>(&[0x08]),
+        Err(ProtobufDeserError::UnexpectedEof { position: 1 })
+    ));
+    assert!(matches!(
+        from_slice::<Small>(&[0x00, 0x00]),
+        Err(ProtobufDeserError::InvalidTag { position: 0 })
+    ));
+    assert!(matches!(
+        from_slice::<Small>(&[0x0b]),
+        Err(ProtobufDeserError::InvalidWireType {
+            position: 0,
+            wire_type: 3
+        })
+    ));
+
+    #[derive(Facet, Debug)]
+    struct Holder {
+        #[allow(dead_code)]
+        command: Command,
+    }
+    assert!(matches!(
+        from_slice::<Holder>(&[]),
+        Err(ProtobufDeserError::MissingField(name)) if name == "command"
+    ));
+
+    #[derive(Facet, Debug)]
+    struct Fixed {
+        #[facet(proto_tag = 1)]
+        #[allow(dead_code)]
+        values: [u32; 2],
+    }
+    assert!(matches!(
+        from_slice::<Fixed>(&[0x0a, 0x01, 0x01]),
+        Err(ProtobufDeserError::WrongLength { tag: 1, .. })
+    ));
+
+    #[derive(Facet, Debug)]
+    struct Untagged {
+        #[allow(dead_code)]
+        a: u32,
+    }
+    assert!(matches!(
+        from_slice::<Untagged>(&[]),
+        Err(ProtobufDeserError::Schema(SchemaError::MissingTag { .. }))
+    ));
+}
+
+#[test]
+fn test_transparent_and_text() {
+    #[derive(Facet, Debug, PartialEq)]
+    #[facet(transparent)]
+    struct UserId(u64);
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Login {
+        #[facet(proto_tag = 1, proto_encoding = "fixed")]
+        user: UserId,
+        #[facet(proto_tag = 2)]
+        from: std::net::Ipv4Addr,
+        #[facet(proto_tag = 3)]
+        grade: char,
+    }
+
+    let mut bytes = vec![0x09, 2, 0, 0, 0, 0, 0, 0, 0, 0x12, 8];
+    bytes.extend_from_slice(b"10.0.0.1");
+    bytes.extend_from_slice(&[0x1a, 0x01, b'B']);
+    let login: Login = from_slice(&bytes)?;
+    assert_eq!(
+        login,
+        Login {
+            user: UserId(2),
+            from: std::net::Ipv4Addr::new(10, 0, 0, 1),
+            grade: 'B',
+        }
+    );
+
+    let len = bytes.len();
+    bytes.splice(len - 2.., [0x02, b'a', b'b']);
+    assert!(matches!(
+        from_slice::<Login>(&bytes),
+        Err(ProtobufDeserError::InvalidChar { position: 21 })
+    ));
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use facet::Facet;
+use facet_protobuf::{SchemaError, to_proto};
+use facet_testhelpers::test;
+
+/// Something that happened.
+#[derive(Facet)]
+#[repr(u8)]
+#[allow(dead_code)]
+enum Event {
+    /// Nothing, really
+    #[facet(proto_tag = 2)]
+    Ping,
+    #[facet(proto_tag = 3)]
+    Count(u32),
+    #[facet(proto_tag = 4)]
+    Move(#[facet(proto_encoding = "zigzag")] i32, i32),
+    #[facet(proto_tag = 5)]
+    Rename {
+        /// The new name
+        #[facet(proto_tag = 1)]
+        name: String,
+    },
+}
+
+#[derive(Facet)]
+struct Point {
+    #[facet(proto_tag = 1)]
+    x: f64,
+    #[facet(proto_tag = 2)]
+    y: f64,
+}
+
+/// A batch of events.
+#[derive(Facet)]
+struct Batch {
+    /// Where the batch comes from
+    #[facet(proto_tag = 10)]
+    source: String,
+    #[facet(proto_tag = 11)]
+    limit: Option<u32>,
+    #[facet(proto_tag = 12)]
+    events: Vec<Event>,
+    #[facet(proto_tag = 13)]
+    positions: HashMap<String, Point>,
+    #[facet(proto_tag = 14)]
+    origin: Option<Point>,
+    current: Option<Event>,
+}
+
+#[test]
+fn test_generated_proto() {
+    let proto = to_proto("example.v1", &[Batch::SHAPE])?;
+    assert_eq!(
+        proto,
+        r#"syntax = "proto3";
+
+package example.v1;
+
+import "google/protobuf/empty.proto";
+
+// A batch of events.
+message Batch {
+  // Where the batch comes from
+  string source = 10;
+  optional uint32 limit = 11;
+  repeated Event events = 12;
+  map<string, Point> positions = 13;
+  Point origin = 14;
+  oneof current {
+    // Nothing, really
+    google.protobuf.Empty ping = 2;
+    uint32 count = 3;
+    EventMove move = 4;
+    EventRename rename = 5;
+  }
+}
+
+message EventMove {
+  sint32 field_0 = 1;
+  int32 field_1 = 2;
+}
+
+message EventRename {
+  // The new name
+  string name = 1;
+}
+
+// Something that happened.
+message Event {
+  oneof event {
+    // Nothing, really
+    google.protobuf.Empty ping = 2;
+    uint32 count = 3;
+    EventMove move = 4;
+    EventRename rename = 5;
+  }
+}
+
+message Point {
+  double x = 1;
+  double y = 2;
+}
+"#
+    );
+}
+
+#[test]
+fn test_shapes_are_written_once() {
+    #[derive(Facet)]
+    struct Empty {}
+
+    let proto = to_proto("", &[Point::SHAPE, Empty::SHAPE, Point::SHAPE])?;
+    assert_eq!(
+        proto,
+        r#"syntax = "proto3";
+
+message Point {
+  double x = 1;
+  double y = 2;
+}
+
+message Empty {}
+"#
+    );
+
+    mod other {
+        #[derive(facet::Facet)]
+        pub struct Point {
+            #[facet(proto_tag = 1)]
+            pub x: i32,
+        }
+    }
+    assert_eq!(
+        to_proto("", &[Point::SHAPE, other::Point::SHAPE]).unwrap_err(),
+        SchemaError::DuplicateMessage("Point".to_string())
+    );
+}
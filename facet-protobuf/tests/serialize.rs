@@ -0,0 +1,291 @@
+use std::collections::BTreeMap;
+
+use facet::Facet;
+use facet_protobuf::{SchemaError, to_vec};
+use facet_testhelpers::test;
+
+#[derive(Facet)]
+struct Test1 {
+    #[facet(proto_tag = 1)]
+    a: i32,
+}
+
+#[test]
+fn test_encoding_guide_examples() {
+    // The examples of https://protobuf.dev/programming-guides/encoding/
+    assert_eq!(to_vec(&Test1 { a: 150 })?, [0x08, 0x96, 0x01]);
+
+    #[derive(Facet)]
+    struct Test2 {
+        #[facet(proto_tag = 2)]
+        b: String,
+    }
+    assert_eq!(
+        to_vec(&Test2 {
+            b: "testing".to_string()
+        })?,
+        [0x12, 0x07, 0x74, 0x65, 0x73, 0x74, 0x69, 0x6e, 0x67]
+    );
+
+    #[derive(Facet)]
+    struct Test3 {
+        #[facet(proto_tag = 3)]
+        c: Test1,
+    }
+    assert_eq!(
+        to_vec(&Test3 {
+            c: Test1 { a: 150 }
+        })?,
+        [0x1a, 0x03, 0x08, 0x96, 0x01]
+    );
+
+    #[derive(Facet)]
+    struct Test4 {
+        #[facet(proto_tag = 4)]
+        d: Vec<i32>,
+    }
+    assert_eq!(
+        to_vec(&Test4 {
+            d: vec![3, 270, 86942]
+        })?,
+        [0x22, 0x06, 0x03, 0x8e, 0x02, 0x9e, 0xa7, 0x05]
+    );
+}
+
+#[test]
+fn test_encodings() {
+    #[derive(Facet)]
+    struct Numbers {
+        #[facet(proto_tag = 1)]
+        int: i32,
+        #[facet(proto_tag = 2, proto_encoding = "zigzag")]
+        sint: i64,
+        #[facet(proto_tag = 3, proto_encoding = "fixed")]
+        fixed: u32,
+        #[facet(proto_tag = 4, proto_encoding = "fixed")]
+        sfixed: i64,
+        #[facet(proto_tag = 5)]
+        float: f32,
+        #[facet(proto_tag = 6)]
+        flag: bool,
+    }
+
+    let numbers = Numbers {
+        int: -1,
+        sint: -2,
+        fixed: 1,
+        sfixed: -1,
+        float: 1.5,
+        flag: true,
+    };
+    assert_eq!(
+        to_vec(&numbers)?,
+        [
+            0x08, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01, // 1: -1
+            0x10, 0x03, // 2: zigzag -2
+            0x1d, 0x01, 0x00, 0x00, 0x00, // 3: fixed32 1
+            0x21, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // 4: sfixed64 -1
+            0x2d, 0x00, 0x00, 0xc0, 0x3f, // 5: 1.5
+            0x30, 0x01, // 6: true
+        ]
+    );
+
+    // Default values aren't written
+    let zeros = Numbers {
+        int: 0,
+        sint: 0,
+        fixed: 0,
+        sfixed: 0,
+        float: 0.0,
+        flag: false,
+    };
+    assert_eq!(to_vec(&zeros)?, []);
+}
+
+#[test]
+fn test_presence_and_collections() {
+    #[derive(Facet)]
+    struct Record {
+        #[facet(proto_tag = 1)]
+        count: Option<u32>,
+        #[facet(proto_tag = 2)]
+        inner: Option<Test1>,
+        #[facet(proto_tag = 3)]
+        names: Vec<String>,
+        #[facet(proto_tag = 4)]
+        scores: BTreeMap<String, u32>,
+        #[facet(proto_tag = 5)]
+        data: Vec<u8>,
+        #[facet(skip_serializing)]
+        cache: u32,
+    }
+
+    let record = Record {
+        count: Some(0),
+        inner: None,
+        names: vec!["a".to_string(), String::new()],
+        scores: BTreeMap::from([("x".to_string(), 1)]),
+        data: vec![0xff],
+        cache: 7,
+    };
+    assert_eq!(
+        to_vec(&record)?,
+        [
+            0x08, 0x00, // 1: Some(0), written since it's there
+            0x1a, 0x01, b'a', // 3: "a"
+            0x1a, 0x00, // 3: "", which repeated fields keep
+            0x22, 0x05, 0x0a, 0x01, b'x', 0x10, 0x01, // 4: {"x": 1}
+            0x2a, 0x01, 0xff, // 5: bytes
+        ]
+    );
+}
+
+#[test]
+fn test_oneof() {
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Event {
+        #[facet(proto_tag = 2)]
+        Ping,
+        #[facet(proto_tag = 3)]
+        Count(u32),
+        #[facet(proto_tag = 4)]
+        Move(i32, i32),
+    }
+
+    #[derive(Facet)]
+    struct Envelope {
+        #[facet(proto_tag = 1)]
+        id: u32,
+        event: Event,
+    }
+
+    let envelope = Envelope {
+        id: 1,
+        event: Event::Ping,
+    };
+    // Variants are fields of the struct
+    assert_eq!(to_vec(&envelope)?, [0x08, 0x01, 0x12, 0x00]);
+
+    #[derive(Facet)]
+    struct Later {
+        next: Option<Event>,
+    }
+    // A variant is written even when it holds zero, since that's how it's told apart
+    assert_eq!(
+        to_vec(&Later {
+            next: Some(Event::Count(0))
+        })?,
+        [0x18, 0x00]
+    );
+    assert_eq!(to_vec(&Later { next: None })?, []);
+
+    // Elsewhere an enum is a message of its own
+    assert_eq!(
+        to_vec(&vec_of(Event::Move(1, 2)))?,
+        [0x0a, 0x06, 0x22, 0x04, 0x08, 0x01, 0x10, 0x02]
+    );
+
+    #[derive(Facet)]
+    struct Events {
+        #[facet(proto_tag = 1)]
+        events: Vec<Event>,
+    }
+    fn vec_of(event: Event) -> Events {
+        Events {
+            events: vec![event],
+        }
+    }
+}
+
+#[test]
+fn test_transparent_and_text() {
+    #[derive(Facet)]
+    #[facet(transparent)]
+    struct UserId(u64);
+
+    #[derive(Facet)]
+    struct Login {
+        #[facet(proto_tag = 1, proto_encoding = "fixed")]
+        user: UserId,
+        #[facet(proto_tag = 2)]
+        from: std::net::Ipv4Addr,
+        #[facet(proto_tag = 3)]
+        grade: char,
+    }
+
+    let login = Login {
+        user: UserId(2),
+        from: std::net::Ipv4Addr::new(10, 0, 0, 1),
+        grade: 'B',
+    };
+    let mut expected = vec![0x09, 2, 0, 0, 0, 0, 0, 0, 0, 0x12, 8];
+    expected.extend_from_slice(b"10.0.0.1");
+    expected.extend_from_slice(&[0x1a, 0x01, b'B']);
+    assert_eq!(to_vec(&login)?, expected);
+}
+
+#[test]
+fn test_schema_errors() {
+    #[derive(Facet)]
+    struct Untagged {
+        a: u32,
+    }
+    assert_eq!(
+        to_vec(&Untagged { a: 1 }).unwrap_err(),
+        SchemaError::MissingTag {
+            shape: "Untagged".to_string(),
+            name: "a".to_string(),
+        }
+    );
+
+    #[derive(Facet)]
+    struct Twice {
+        #[facet(proto_tag = 1)]
+        a: u32,
+        #[facet(proto_tag = 1)]
+        b: u32,
+    }
+    assert_eq!(
+        to_vec(&Twice { a: 1, b: 2 }).unwrap_err(),
+        SchemaError::DuplicateTag {
+            shape: "Twice".to_string(),
+            tag: 1,
+        }
+    );
+
+    #[derive(Facet)]
+    struct Reserved {
+        #[facet(proto_tag = 19000)]
+        a: u32,
+    }
+    assert!(matches!(
+        to_vec(&Reserved { a: 1 }),
+        Err(SchemaError::InvalidTag { .. })
+    ));
+
+    #[derive(Facet)]
+    struct ZigzagString {
+        #[facet(proto_tag = 1, proto_encoding = "zigzag")]
+        a: String,
+    }
+    assert_eq!(
+        to_vec(&ZigzagString { a: "x".to_string() }).unwrap_err(),
+        SchemaError::InvalidEncoding {
+            shape: "ZigzagString".to_string(),
+            name: "a".to_string(),
+            encoding: "zigzag".to_string(),
+        }
+    );
+
+    #[derive(Facet)]
+    struct Pair {
+        #[facet(proto_tag = 1)]
+        a: (u32, u32),
+    }
+    assert!(matches!(
+        to_vec(&Pair { a: (1, 2) }),
+        Err(SchemaError::UnsupportedType(_))
+    ));
+}
@@ -1,3 +1,4 @@
+use alloc::string::String;
 use facet_core::{Characteristic, EnumType, FieldError, Shape, TryFromError};
 use owo_colors::OwoColorize;
 
@@ -104,6 +105,18 @@ pub enum ReflectError<'shape> {
         inner: TryFromError<'shape>,
     },
 
+    /// A `deserialize_with` (or `proxy`) conversion failed
+    ConversionFailed {
+        /// The shape of the value being converted from.
+        src_shape: &'shape Shape<'shape>,
+
+        /// The shape of the field being built.
+        dst_shape: &'shape Shape<'shape>,
+
+        /// Why the conversion failed
+        message: String,
+    },
+
     /// A shape has a `default` attribute, but no implementation of the `Default` trait.
     DefaultAttrButNoDefaultImpl {
         /// The shape of the value that has a `default` attribute but no default implementation.
@@ -220,6 +233,19 @@ impl core::fmt::Display for ReflectError<'_> {
                     inner.red()
                 )
             }
+            ReflectError::ConversionFailed {
+                src_shape,
+                dst_shape,
+                message,
+            } => {
+                write!(
+                    f,
+                    "While converting {} into a {}: {}",
+                    src_shape.green(),
+                    dst_shape.blue(),
+                    message.red()
+                )
+            }
             ReflectError::DefaultAttrButNoDefaultImpl { shape } => write!(
                 f,
                 "Shape '{}' has a `default` attribute but no default implementation",
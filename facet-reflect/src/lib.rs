@@ -17,6 +17,9 @@ pub use wip::*;
 mod peek;
 pub use peek::*;
 
+mod poke;
+pub use poke::*;
+
 mod scalar;
 pub use scalar::*;
 
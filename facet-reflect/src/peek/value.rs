@@ -7,7 +7,8 @@ use facet_core::{
 use crate::{ReflectError, ScalarType};
 
 use super::{
-    ListLikeDef, PeekEnum, PeekList, PeekListLike, PeekMap, PeekSmartPointer, PeekStruct, PeekTuple,
+    ListLikeDef, PeekEnum, PeekList, PeekListLike, PeekMap, PeekSet, PeekSmartPointer, PeekStruct,
+    PeekTuple,
 };
 
 /// A unique identifier for a peek value
@@ -247,6 +248,18 @@ impl<'mem, 'facet, 'shape> Peek<'mem, 'facet, 'shape> {
         }
     }
 
+    /// Tries to identify this value as a set
+    pub fn into_set(self) -> Result<PeekSet<'mem, 'facet, 'shape>, ReflectError<'shape>> {
+        if let Def::Set(def) = self.shape.def {
+            Ok(PeekSet { value: self, def })
+        } else {
+            Err(ReflectError::WasNotA {
+                expected: "set",
+                actual: self.shape,
+            })
+        }
+    }
+
     /// Tries to identify this value as a list
     pub fn into_list(self) -> Result<PeekList<'mem, 'facet, 'shape>, ReflectError<'shape>> {
         if let Def::List(def) = self.shape.def {
@@ -364,6 +377,37 @@ impl<'mem, 'facet, 'shape> Peek<'mem, 'facet, 'shape> {
         }
         current_peek
     }
+
+    /// Converts this value, read from `field`, with the field's `serialize_with` conversion
+    /// (or `proxy`), returning the value to serialize in its place.
+    ///
+    /// Returns `None` if the field has no such conversion.
+    #[cfg(feature = "alloc")]
+    pub fn custom_serialization<'proxy>(
+        &self,
+        field: &facet_core::Field<'shape>,
+    ) -> Option<crate::HeapValue<'proxy, 'shape>> {
+        let serialize_with = field.vtable.serialize_with?;
+        let shape = serialize_with.shape;
+        let layout = shape
+            .layout
+            .sized_layout()
+            .expect("`SerializeWith::new` only takes sized shapes");
+        let data = shape
+            .allocate()
+            .expect("`SerializeWith::new` only takes sized shapes");
+
+        unsafe { (serialize_with.convert_fn)(self.data, data) };
+
+        Some(crate::HeapValue {
+            guard: Some(crate::Guard {
+                ptr: data.as_mut_byte_ptr(),
+                layout,
+            }),
+            shape,
+            phantom: PhantomData,
+        })
+    }
 }
 
 impl<'mem, 'facet, 'shape> core::fmt::Display for Peek<'mem, 'facet, 'shape> {
@@ -0,0 +1,199 @@
+use alloc::alloc::{Layout, alloc, dealloc, handle_alloc_error};
+use core::mem::MaybeUninit;
+
+use facet_core::{
+    Characteristic, Def, EnumRepr, EnumType, Field, FieldError, PtrMut, PtrUninit, Type, UserType,
+    Variant,
+};
+
+use crate::{Poke, ReflectError, trace};
+
+/// Writes the discriminant of a variant at the start of an enum's memory
+///
+/// # Safety
+///
+/// `data` must point to memory large enough for an enum with the given representation.
+pub(crate) unsafe fn write_discriminant(data: PtrMut<'_>, repr: EnumRepr, discriminant: i64) {
+    let data_ptr = data.as_mut_byte_ptr();
+    unsafe {
+        match repr {
+            EnumRepr::U8 => *data_ptr = discriminant as u8,
+            EnumRepr::U16 => *(data_ptr as *mut u16) = discriminant as u16,
+            EnumRepr::U32 => *(data_ptr as *mut u32) = discriminant as u32,
+            EnumRepr::U64 => *(data_ptr as *mut u64) = discriminant as u64,
+            EnumRepr::USize => *(data_ptr as *mut usize) = discriminant as usize,
+            EnumRepr::I8 => *(data_ptr as *mut i8) = discriminant as i8,
+            EnumRepr::I16 => *(data_ptr as *mut i16) = discriminant as i16,
+            EnumRepr::I32 => *(data_ptr as *mut i32) = discriminant as i32,
+            EnumRepr::I64 => *(data_ptr as *mut i64) = discriminant,
+            EnumRepr::ISize => *(data_ptr as *mut isize) = discriminant as isize,
+            EnumRepr::RustNPO => (),
+            _ => {
+                // Default to a reasonable size for other representations
+                *(data_ptr as *mut u32) = discriminant as u32;
+            }
+        }
+    }
+}
+
+impl<'mem, 'facet, 'shape> Poke<'mem, 'facet, 'shape> {
+    /// Switches an enum to the variant at the given index, dropping the current
+    /// value. The fields of the new variant are set to their default values, so
+    /// they must all have one.
+    pub fn set_variant(&mut self, index: usize) -> Result<(), ReflectError<'shape>> {
+        let ty = self.enum_type()?;
+        let variant = ty.variants.get(index).ok_or(ReflectError::FieldError {
+            shape: self.shape,
+            field_error: FieldError::IndexOutOfBounds {
+                index,
+                bound: ty.variants.len(),
+            },
+        })?;
+
+        self.switch_variant(ty, variant)
+    }
+
+    /// Switches an enum to the variant with the given name, dropping the current
+    /// value. The fields of the new variant are set to their default values, so
+    /// they must all have one.
+    pub fn set_variant_named(&mut self, name: &str) -> Result<(), ReflectError<'shape>> {
+        let ty = self.enum_type()?;
+        let variant = ty
+            .variants
+            .iter()
+            .find(|variant| variant.name == name)
+            .ok_or(ReflectError::FieldError {
+                shape: self.shape,
+                field_error: FieldError::NoSuchField,
+            })?;
+
+        self.switch_variant(ty, variant)
+    }
+
+    fn switch_variant(
+        &mut self,
+        ty: EnumType<'shape>,
+        variant: &'shape Variant<'shape>,
+    ) -> Result<(), ReflectError<'shape>> {
+        // Check everything we need before dropping the current value
+        for field in variant.data.fields {
+            if field.vtable.default_fn.is_none() && !field.shape().is(Characteristic::Default) {
+                return Err(ReflectError::MissingCharacteristic {
+                    shape: field.shape(),
+                    characteristic: Characteristic::Default,
+                });
+            }
+        }
+
+        // Only `Option` has a niche the shape tells us how to write
+        let init_none = match (ty.enum_repr, self.shape.def) {
+            (EnumRepr::RustNPO, Def::Option(option_def)) => Some(option_def.vtable.init_none_fn),
+            (EnumRepr::RustNPO, _) => {
+                return Err(ReflectError::OperationFailed {
+                    shape: self.shape,
+                    operation: "switching variants of an enum without a #[repr]",
+                });
+            }
+            _ => None,
+        };
+        let layout = self
+            .shape
+            .layout
+            .sized_layout()
+            .map_err(|_| ReflectError::Unsized { shape: self.shape })?;
+
+        trace!("Switching {} to variant {}", self.shape, variant.name);
+
+        // The new value is built on the side, so that a panicking default leaves the current
+        // one as it was
+        let mut scratch = Scratch::new(layout, variant.data.fields);
+        unsafe {
+            let data = PtrUninit::new(scratch.data);
+            match (init_none, variant.discriminant) {
+                (Some(init_none), _) if variant.data.fields.is_empty() => {
+                    init_none(data);
+                }
+                // The niche means `Some(value)` is laid out as `value` is
+                (Some(_), _) | (None, None) => {}
+                (None, Some(discriminant)) => {
+                    write_discriminant(PtrMut::new(scratch.data), ty.enum_repr, discriminant)
+                }
+            }
+            for field in variant.data.fields {
+                let field_data = data.field_uninit_at(field.offset);
+                match field.vtable.default_fn {
+                    Some(default_fn) => default_fn(field_data),
+                    None => (field.shape().vtable.default_in_place.unwrap())(field_data),
+                };
+                scratch.built += 1;
+            }
+
+            // Swap the values, then drop the old one where the new one was built
+            core::ptr::swap_nonoverlapping(
+                self.data.as_mut_byte_ptr() as *mut MaybeUninit<u8>,
+                scratch.data as *mut MaybeUninit<u8>,
+                layout.size(),
+            );
+            scratch.built = 0;
+            if let Some(drop_fn) = self.shape.vtable.drop_in_place {
+                drop_fn(PtrMut::new(scratch.data));
+            }
+        }
+
+        self.check_invariants()
+    }
+
+    fn enum_type(&self) -> Result<EnumType<'shape>, ReflectError<'shape>> {
+        match self.shape.ty {
+            Type::User(UserType::Enum(ty)) => Ok(ty),
+            _ => Err(ReflectError::WasNotA {
+                expected: "enum",
+                actual: self.shape,
+            }),
+        }
+    }
+}
+
+/// Memory for a variant being built. Dropping it drops the fields built so far and frees it,
+/// which is all there is to clean up if building the variant panics.
+struct Scratch<'shape> {
+    data: *mut u8,
+    layout: Layout,
+    fields: &'shape [Field<'shape>],
+    /// How many of `fields` are initialized
+    built: usize,
+}
+
+impl<'shape> Scratch<'shape> {
+    fn new(layout: Layout, fields: &'shape [Field<'shape>]) -> Self {
+        let data = if layout.size() == 0 {
+            core::ptr::without_provenance_mut(layout.align())
+        } else {
+            // SAFETY: the layout isn't zero-sized
+            let data = unsafe { alloc(layout) };
+            if data.is_null() {
+                handle_alloc_error(layout);
+            }
+            data
+        };
+        Self {
+            data,
+            layout,
+            fields,
+            built: 0,
+        }
+    }
+}
+
+impl Drop for Scratch<'_> {
+    fn drop(&mut self) {
+        for field in &self.fields[..self.built] {
+            if let Some(drop_fn) = field.shape().vtable.drop_in_place {
+                unsafe { drop_fn(PtrMut::new(self.data.wrapping_add(field.offset))) };
+            }
+        }
+        if self.layout.size() != 0 {
+            unsafe { dealloc(self.data, self.layout) };
+        }
+    }
+}
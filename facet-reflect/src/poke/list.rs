@@ -0,0 +1,72 @@
+use core::mem::ManuallyDrop;
+use facet_core::{Def, Facet, FieldError, ListDef, PtrMut};
+
+use crate::{Poke, ReflectError};
+
+impl<'mem, 'facet, 'shape> Poke<'mem, 'facet, 'shape> {
+    /// Appends an item to the end of a list.
+    pub fn push<T: Facet<'facet>>(&mut self, item: T) -> Result<(), ReflectError<'shape>> {
+        let def = self.list_def()?;
+        self.check_shape::<T>(def.t())?;
+
+        let mut item = ManuallyDrop::new(item);
+        unsafe {
+            (def.vtable.push)(self.data, PtrMut::new(&mut *item as *mut T));
+        }
+
+        self.check_invariants()
+    }
+
+    /// Removes the item at the given index from a list, dropping it.
+    pub fn remove(&mut self, index: usize) -> Result<(), ReflectError<'shape>> {
+        let def = self.list_def()?;
+        let Some(remove_fn) = def.vtable.remove else {
+            return Err(ReflectError::OperationFailed {
+                shape: self.shape,
+                operation: "List does not support removing items",
+            });
+        };
+
+        let len = unsafe { (def.vtable.len)(self.data.as_const()) };
+        if index >= len {
+            return Err(ReflectError::FieldError {
+                shape: self.shape,
+                field_error: FieldError::IndexOutOfBounds { index, bound: len },
+            });
+        }
+
+        unsafe {
+            remove_fn(self.data, index);
+        }
+
+        self.check_invariants()
+    }
+
+    /// Returns a `Poke` for the list item at the given index.
+    pub fn item_mut(
+        &mut self,
+        index: usize,
+    ) -> Result<Poke<'_, 'facet, 'shape>, ReflectError<'shape>> {
+        let def = self.list_def()?;
+        let Some(data) = (unsafe { (def.vtable.get_mut)(self.data, index) }) else {
+            return Err(ReflectError::FieldError {
+                shape: self.shape,
+                field_error: FieldError::IndexOutOfBounds {
+                    index,
+                    bound: unsafe { (def.vtable.len)(self.data.as_const()) },
+                },
+            });
+        };
+        Ok(self.child(data, def.t()))
+    }
+
+    fn list_def(&self) -> Result<ListDef<'shape>, ReflectError<'shape>> {
+        match self.shape.def {
+            Def::List(def) => Ok(def),
+            _ => Err(ReflectError::WasNotA {
+                expected: "list",
+                actual: self.shape,
+            }),
+        }
+    }
+}
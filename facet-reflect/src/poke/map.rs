@@ -0,0 +1,58 @@
+use core::mem::ManuallyDrop;
+use facet_core::{Def, Facet, MapDef, PtrConst, PtrMut};
+
+use crate::{Poke, ReflectError};
+
+impl<'mem, 'facet, 'shape> Poke<'mem, 'facet, 'shape> {
+    /// Inserts an entry into a map, dropping the value previously stored for that key.
+    pub fn insert<K: Facet<'facet>, V: Facet<'facet>>(
+        &mut self,
+        key: K,
+        value: V,
+    ) -> Result<(), ReflectError<'shape>> {
+        let def = self.map_def()?;
+        self.check_shape::<K>(def.k())?;
+        self.check_shape::<V>(def.v())?;
+
+        let mut key = ManuallyDrop::new(key);
+        let mut value = ManuallyDrop::new(value);
+        unsafe {
+            (def.vtable.insert_fn)(
+                self.data,
+                PtrMut::new(&mut *key as *mut K),
+                PtrMut::new(&mut *value as *mut V),
+            );
+        }
+
+        self.check_invariants()
+    }
+
+    /// Removes the entry for a key from a map, dropping it.
+    ///
+    /// Returns whether the key was present.
+    pub fn remove_key<K: Facet<'facet>>(&mut self, key: &K) -> Result<bool, ReflectError<'shape>> {
+        let def = self.map_def()?;
+        self.check_shape::<K>(def.k())?;
+        let Some(remove_fn) = def.vtable.remove_fn else {
+            return Err(ReflectError::OperationFailed {
+                shape: self.shape,
+                operation: "Map does not support removing entries",
+            });
+        };
+
+        let removed = unsafe { remove_fn(self.data, PtrConst::new(key as *const K)) };
+        self.check_invariants()?;
+
+        Ok(removed)
+    }
+
+    fn map_def(&self) -> Result<MapDef<'shape>, ReflectError<'shape>> {
+        match self.shape.def {
+            Def::Map(def) => Ok(def),
+            _ => Err(ReflectError::WasNotA {
+                expected: "map",
+                actual: self.shape,
+            }),
+        }
+    }
+}
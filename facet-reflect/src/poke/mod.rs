@@ -0,0 +1,14 @@
+//! Allows editing existing values in place
+
+mod value;
+pub use value::*;
+
+mod enum_;
+#[cfg(feature = "alloc")]
+pub(crate) use enum_::write_discriminant;
+
+mod list;
+
+mod map;
+
+mod option;
@@ -0,0 +1,57 @@
+use core::mem::ManuallyDrop;
+use facet_core::{Def, Facet, OptionDef, PtrConst, PtrUninit};
+
+use crate::{Poke, ReflectError};
+
+impl<'mem, 'facet, 'shape> Poke<'mem, 'facet, 'shape> {
+    /// Sets an option to `Some(value)`, dropping the previous value if any.
+    pub fn set_some<T: Facet<'facet>>(&mut self, value: T) -> Result<(), ReflectError<'shape>> {
+        let def = self.option_def()?;
+        self.check_shape::<T>(def.t())?;
+
+        let value = ManuallyDrop::new(value);
+        unsafe {
+            (def.vtable.replace_with_fn)(self.data, Some(PtrConst::new(&*value as *const T)));
+        }
+
+        self.check_invariants()
+    }
+
+    /// Sets an option to `None`, dropping the previous value if any.
+    pub fn set_none(&mut self) -> Result<(), ReflectError<'shape>> {
+        let def = self.option_def()?;
+
+        unsafe {
+            (def.vtable.replace_with_fn)(self.data, None);
+        }
+
+        self.check_invariants()
+    }
+
+    /// Takes the value out of an option, leaving `None` in its place.
+    pub fn take<T: Facet<'facet>>(&mut self) -> Result<Option<T>, ReflectError<'shape>> {
+        let def = self.option_def()?;
+        self.check_shape::<T>(def.t())?;
+
+        let value = unsafe {
+            let value =
+                (def.vtable.get_value_fn)(self.data.as_const()).map(|inner| inner.read::<T>());
+            // The inner value was moved out, so overwrite the option without dropping it
+            (def.vtable.init_none_fn)(PtrUninit::new(self.data.as_mut_byte_ptr()));
+            value
+        };
+
+        self.check_invariants()?;
+        Ok(value)
+    }
+
+    fn option_def(&self) -> Result<OptionDef<'shape>, ReflectError<'shape>> {
+        match self.shape.def {
+            Def::Option(def) => Ok(def),
+            _ => Err(ReflectError::WasNotA {
+                expected: "option",
+                actual: self.shape,
+            }),
+        }
+    }
+}
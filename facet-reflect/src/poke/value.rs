@@ -0,0 +1,199 @@
+use core::marker::PhantomData;
+use facet_core::{Facet, Field, FieldError, PtrMut, Shape, Type, UserType};
+
+use crate::{Peek, ReflectError, trace};
+
+/// Lets you edit a value that already exists, in place.
+///
+/// Every change drops the values it replaces or removes, then checks the
+/// [`invariants`](facet_core::ValueVTable::invariants) of the edited value and
+/// of every value it was borrowed from (via [`Poke::field_mut`] and friends).
+/// [`Poke::set`] puts the previous value back when those checks fail; the other
+/// operations keep the change and report the violation.
+pub struct Poke<'mem, 'facet, 'shape> {
+    /// Underlying data
+    pub(crate) data: PtrMut<'mem>,
+
+    /// Shape of the value
+    pub(crate) shape: &'shape Shape<'shape>,
+
+    /// The value this one is a part of, if any
+    parent: Option<&'mem Poke<'mem, 'facet, 'shape>>,
+
+    invariant: PhantomData<fn(&'facet ()) -> &'facet ()>,
+}
+
+impl core::fmt::Debug for Poke<'_, '_, '_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(&self.as_peek(), f)
+    }
+}
+
+impl<'mem, 'facet, 'shape> Poke<'mem, 'facet, 'shape> {
+    /// Creates a new `Poke` instance for a value of type `T`.
+    pub fn new<T: Facet<'facet>>(t: &'mem mut T) -> Self {
+        Self {
+            data: PtrMut::new(t as *mut T),
+            shape: T::SHAPE,
+            parent: None,
+            invariant: PhantomData,
+        }
+    }
+
+    /// Creates a new `Poke` instance without checking the type.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `data` points to an initialized value of the
+    /// given shape, and that nothing else reads or writes it for `'mem`.
+    pub unsafe fn unchecked_new(data: PtrMut<'mem>, shape: &'shape Shape<'shape>) -> Self {
+        Self {
+            data,
+            shape,
+            parent: None,
+            invariant: PhantomData,
+        }
+    }
+
+    /// Returns the shape
+    #[inline(always)]
+    pub const fn shape(&self) -> &'shape Shape<'shape> {
+        self.shape
+    }
+
+    /// Returns a read-only view of the value
+    #[inline]
+    pub fn as_peek(&self) -> Peek<'_, 'facet, 'shape> {
+        unsafe { Peek::unchecked_new(self.data.as_const(), self.shape) }
+    }
+
+    /// Read the value from memory into a Rust value.
+    pub fn get<T: Facet<'facet>>(&self) -> Result<&T, ReflectError<'shape>> {
+        self.check_shape::<T>(self.shape)?;
+        Ok(unsafe { self.data.get::<T>() })
+    }
+
+    /// Replaces the value with `value`, dropping the previous one.
+    ///
+    /// If an invariant doesn't hold with the new value, the previous value is
+    /// put back and `value` is dropped instead.
+    pub fn set<T: Facet<'facet>>(&mut self, value: T) -> Result<(), ReflectError<'shape>> {
+        self.check_shape::<T>(self.shape)?;
+
+        let ptr = self.data.as_mut_byte_ptr() as *mut T;
+        let previous = unsafe { core::ptr::replace(ptr, value) };
+        if let Err(err) = self.check_invariants() {
+            trace!(
+                "Invariants failed after setting {}, rolling back",
+                self.shape
+            );
+            drop(unsafe { core::ptr::replace(ptr, previous) });
+            return Err(err);
+        }
+        drop(previous);
+
+        Ok(())
+    }
+
+    /// Returns a `Poke` for the field at the given index, for structs and the
+    /// active variant of enums.
+    pub fn field_mut(
+        &mut self,
+        index: usize,
+    ) -> Result<Poke<'_, 'facet, 'shape>, ReflectError<'shape>> {
+        let fields = self.fields()?;
+        let field = fields.get(index).ok_or(ReflectError::FieldError {
+            shape: self.shape,
+            field_error: FieldError::IndexOutOfBounds {
+                index,
+                bound: fields.len(),
+            },
+        })?;
+
+        let data = unsafe { self.data.field(field.offset) };
+        Ok(self.child(data, field.shape()))
+    }
+
+    /// Returns a `Poke` for the field with the given name, for structs and the
+    /// active variant of enums.
+    pub fn field_mut_named(
+        &mut self,
+        name: &str,
+    ) -> Result<Poke<'_, 'facet, 'shape>, ReflectError<'shape>> {
+        let index = self
+            .fields()?
+            .iter()
+            .position(|field| field.name == name)
+            .ok_or(ReflectError::FieldError {
+                shape: self.shape,
+                field_error: FieldError::NoSuchField,
+            })?;
+        self.field_mut(index)
+    }
+
+    /// Checks the invariants of this value, then of every value it is a part of.
+    pub fn check_invariants(&self) -> Result<(), ReflectError<'shape>> {
+        let mut current = Some(self);
+        while let Some(poke) = current {
+            if let Some(invariants_fn) = poke.shape.vtable.invariants {
+                if !unsafe { invariants_fn(poke.data.as_const()) } {
+                    return Err(ReflectError::InvariantViolation {
+                        invariant: "Custom validation function returned false",
+                    });
+                }
+            }
+            current = poke.parent;
+        }
+        Ok(())
+    }
+
+    /// Returns a `Poke` for a part of this value, which checks our invariants too.
+    pub(crate) fn child<'a>(
+        &'a mut self,
+        data: PtrMut<'a>,
+        shape: &'shape Shape<'shape>,
+    ) -> Poke<'a, 'facet, 'shape> {
+        Poke {
+            data,
+            shape,
+            parent: Some(self),
+            invariant: PhantomData,
+        }
+    }
+
+    /// Makes sure `T` is the `expected` shape
+    pub(crate) fn check_shape<T: Facet<'facet>>(
+        &self,
+        expected: &'shape Shape<'shape>,
+    ) -> Result<(), ReflectError<'shape>> {
+        if expected != T::SHAPE {
+            Err(ReflectError::WrongShape {
+                expected,
+                actual: T::SHAPE,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the fields of a struct, or of the active variant of an enum
+    fn fields(&self) -> Result<&'shape [Field<'shape>], ReflectError<'shape>> {
+        match self.shape.ty {
+            Type::User(UserType::Struct(ty)) => Ok(ty.fields),
+            Type::User(UserType::Enum(_)) => {
+                let peek = unsafe { Peek::unchecked_new(self.data.as_const(), self.shape) };
+                let variant = peek.into_enum()?.active_variant().map_err(|_| {
+                    ReflectError::OperationFailed {
+                        shape: self.shape,
+                        operation: "Could not determine the active variant",
+                    }
+                })?;
+                Ok(variant.data.fields)
+            }
+            _ => Err(ReflectError::WasNotA {
+                expected: "struct or enum",
+                actual: self.shape,
+            }),
+        }
+    }
+}
@@ -3,7 +3,7 @@ use facet_core::{FieldError, Type, UserType, Variant};
 use owo_colors::OwoColorize;
 
 use crate::trace;
-use crate::{ISet, ReflectError, Wip};
+use crate::{ISet, ReflectError, Wip, write_discriminant};
 
 impl<'facet, 'shape> Wip<'facet, 'shape> {
     /// Selects a variant of an enum by index.
@@ -44,28 +44,7 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
         // Write the discriminant value based on the enum's representation
         if let Some(discriminant) = variant.discriminant {
             unsafe {
-                let data_ptr = frame.data.as_mut_byte_ptr();
-                match def.enum_repr {
-                    facet_core::EnumRepr::U8 => *data_ptr = discriminant as u8,
-                    facet_core::EnumRepr::U16 => *(data_ptr as *mut u16) = discriminant as u16,
-                    facet_core::EnumRepr::U32 => *(data_ptr as *mut u32) = discriminant as u32,
-                    facet_core::EnumRepr::U64 => *(data_ptr as *mut u64) = discriminant as u64,
-                    facet_core::EnumRepr::USize => {
-                        *(data_ptr as *mut usize) = discriminant as usize
-                    }
-                    facet_core::EnumRepr::I8 => *(data_ptr as *mut i8) = discriminant as i8,
-                    facet_core::EnumRepr::I16 => *(data_ptr as *mut i16) = discriminant as i16,
-                    facet_core::EnumRepr::I32 => *(data_ptr as *mut i32) = discriminant as i32,
-                    facet_core::EnumRepr::I64 => *(data_ptr as *mut i64) = discriminant,
-                    facet_core::EnumRepr::ISize => {
-                        *(data_ptr as *mut isize) = discriminant as isize
-                    }
-                    facet_core::EnumRepr::RustNPO => (),
-                    _ => {
-                        // Default to a reasonable size for other representations
-                        *(data_ptr as *mut u32) = discriminant as u32;
-                    }
-                }
+                write_discriminant(frame.data.assume_init(), def.enum_repr, discriminant);
             }
         } // If there's no discriminant, don't try to write one (e.g., for RustNPO)
 
@@ -83,11 +62,11 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
         Ok(self)
     }
 
-    /// Selects a variant of an enum by name.
+    /// Selects a variant of an enum by name or alias.
     ///
     /// # Arguments
     ///
-    /// * `name` - The name of the variant to select.
+    /// * `name` - The name or alias of the variant to select.
     ///
     /// # Returns
     ///
@@ -106,7 +85,7 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
         let index =
             def.variants
                 .iter()
-                .position(|v| v.name == name)
+                .position(|v| v.is_named(name))
                 .ok_or(ReflectError::FieldError {
                     shape,
                     field_error: FieldError::NoSuchField,
@@ -115,11 +94,11 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
         self.variant(index)
     }
 
-    /// Finds a variant in an enum by name.
+    /// Finds a variant in an enum by name or alias.
     ///
     /// # Arguments
     ///
-    /// * `name` - The name of the variant to find.
+    /// * `name` - The name or alias of the variant to find.
     ///
     /// # Returns
     ///
@@ -131,7 +110,7 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
             def.variants
                 .iter()
                 .enumerate()
-                .find(|(_, v)| v.name == name)
+                .find(|(_, v)| v.is_named(name))
                 .map(|(i, &v)| (i, v))
         } else {
             None
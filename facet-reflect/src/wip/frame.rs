@@ -5,7 +5,7 @@ use facet_core::{Def, PointerType, PrimitiveType, PtrUninit, SequenceType, Shape
 #[allow(unused_imports)]
 use owo_colors::OwoColorize;
 
-use crate::{ISet, Peek, ValueId};
+use crate::{ISet, Peek, ReflectError, ValueId};
 
 use super::{FrameFlags, IState};
 
@@ -40,6 +40,7 @@ fn def_kind(def: &Def) -> &'static str {
         Def::Scalar(_) => "scalar",
         Def::Map(_) => "map",
         Def::List(_) => "list",
+        Def::Set(_) => "set",
         Def::Option(_) => "option",
         Def::SmartPointer(_) => "smart_ptr",
         _ => "other",
@@ -166,6 +167,41 @@ impl<'shape> Frame<'shape> {
         self.dealloc_if_needed();
     }
 
+    /// Initializes the `skip_deserializing` fields that weren't set yet with their default
+    pub(crate) fn fill_skipped_fields(&mut self) -> Result<(), ReflectError<'shape>> {
+        let fields = match self.shape.ty {
+            Type::User(UserType::Struct(sd)) => sd.fields,
+            Type::User(UserType::Enum(_)) => match &self.istate.variant {
+                Some(variant) => variant.data.fields,
+                None => return Ok(()),
+            },
+            _ => return Ok(()),
+        };
+
+        for (index, field) in fields.iter().enumerate() {
+            if !field.should_skip_deserializing() || self.istate.fields.has(index) {
+                continue;
+            }
+
+            let default_fn = field
+                .vtable
+                .default_fn
+                .or(field.shape().vtable.default_in_place)
+                .ok_or(ReflectError::DefaultAttrButNoDefaultImpl {
+                    shape: field.shape(),
+                })?;
+            trace!(
+                "[{}] Filling skipped field {} of {} with its default",
+                self.istate.depth,
+                field.name.blue(),
+                self.shape.blue(),
+            );
+            unsafe { default_fn(self.data.field_uninit_at(field.offset)) };
+            self.istate.fields.set(index);
+        }
+        Ok(())
+    }
+
     /// Marks the frame as fully initialized
     pub(crate) unsafe fn mark_fully_initialized(&mut self) {
         trace!(
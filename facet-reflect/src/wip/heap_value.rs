@@ -30,6 +30,11 @@ impl<'facet, 'shape> HeapValue<'facet, 'shape> {
         unsafe { Peek::unchecked_new(PtrConst::new(self.guard.as_ref().unwrap().ptr), self.shape) }
     }
 
+    /// Returns the shape of the value
+    pub fn shape(&self) -> &'shape Shape<'shape> {
+        self.shape
+    }
+
     /// Turn this heapvalue into a concrete type
     pub fn materialize<T: Facet<'facet>>(mut self) -> Result<T, ReflectError<'shape>> {
         if self.shape != T::SHAPE {
@@ -1,19 +1,26 @@
+use alloc::vec::Vec;
 use facet_core::Field;
 
-/// Keeps track of which fields were initialized, up to 64 fields
-#[derive(Clone, Copy, Default, Debug)]
+/// Number of bits in a word of the set
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Keeps track of which fields were initialized, for any number of fields.
+///
+/// The first 64 fields are tracked inline, so small structs never allocate.
+#[derive(Clone, Default, Debug)]
 pub struct ISet {
+    /// Fields 0 to 63
     flags: u64,
+
+    /// Fields 64 and up, 64 per word
+    overflow: Vec<u64>,
 }
 
 impl ISet {
-    /// The maximum index that can be tracked.
-    pub const MAX_INDEX: usize = 63;
-
     /// Creates a new ISet with all (given) fields set.
     pub fn all(fields: &[Field]) -> Self {
         let mut iset = ISet::default();
-        for (i, _field) in fields.iter().enumerate() {
+        for i in 0..fields.len() {
             iset.set(i);
         }
         iset
@@ -21,44 +28,98 @@ impl ISet {
 
     /// Sets the bit at the given index.
     pub fn set(&mut self, index: usize) {
-        if index >= 64 {
-            panic!("ISet can only track up to 64 fields. Index {index} is out of bounds.");
+        if index < WORD_BITS {
+            self.flags |= 1 << index;
+            return;
+        }
+
+        let (word, bit) = Self::overflow_position(index);
+        if word >= self.overflow.len() {
+            self.overflow.resize(word + 1, 0);
         }
-        self.flags |= 1 << index;
+        self.overflow[word] |= 1 << bit;
     }
 
     /// Unsets the bit at the given index.
     pub fn unset(&mut self, index: usize) {
-        if index >= 64 {
-            panic!("ISet can only track up to 64 fields. Index {index} is out of bounds.");
+        if index < WORD_BITS {
+            self.flags &= !(1 << index);
+            return;
+        }
+
+        let (word, bit) = Self::overflow_position(index);
+        if let Some(word) = self.overflow.get_mut(word) {
+            *word &= !(1 << bit);
         }
-        self.flags &= !(1 << index);
     }
 
     /// Checks if the bit at the given index is set.
     pub fn has(&self, index: usize) -> bool {
-        if index >= 64 {
-            panic!("ISet can only track up to 64 fields. Index {index} is out of bounds.");
+        if index < WORD_BITS {
+            return (self.flags & (1 << index)) != 0;
         }
-        (self.flags & (1 << index)) != 0
+
+        let (word, bit) = Self::overflow_position(index);
+        self.overflow
+            .get(word)
+            .is_some_and(|word| (word & (1 << bit)) != 0)
     }
 
     /// Checks if all bits up to the given count are set.
     pub fn are_all_set(&self, count: usize) -> bool {
-        if count > 64 {
-            panic!("ISet can only track up to 64 fields. Count {count} is out of bounds.");
+        if count <= WORD_BITS {
+            let mask = Self::low_mask(count);
+            return self.flags & mask == mask;
+        }
+
+        if self.flags != u64::MAX {
+            return false;
         }
-        let mask = (1 << count) - 1;
-        self.flags & mask == mask
+
+        let rest = count - WORD_BITS;
+        let full_words = rest / WORD_BITS;
+        let remaining_bits = rest % WORD_BITS;
+        let words_needed = full_words + usize::from(remaining_bits != 0);
+        if self.overflow.len() < words_needed {
+            return false;
+        }
+
+        if !self.overflow[..full_words]
+            .iter()
+            .all(|word| *word == u64::MAX)
+        {
+            return false;
+        }
+
+        let mask = Self::low_mask(remaining_bits);
+        remaining_bits == 0 || self.overflow[full_words] & mask == mask
     }
 
     /// Checks if any bit in the ISet is set.
     pub fn is_any_set(&self) -> bool {
-        self.flags != 0
+        self.flags != 0 || self.overflow.iter().any(|word| *word != 0)
     }
 
     /// Clears all bits in the ISet.
     pub fn clear(&mut self) {
         self.flags = 0;
+        self.overflow.clear();
+    }
+
+    /// Returns the word in `overflow` and the bit in that word for an index of 64 or more
+    #[inline(always)]
+    fn overflow_position(index: usize) -> (usize, usize) {
+        let index = index - WORD_BITS;
+        (index / WORD_BITS, index % WORD_BITS)
+    }
+
+    /// Returns a mask with the lowest `count` bits set, `count` being at most 64
+    #[inline(always)]
+    fn low_mask(count: usize) -> u64 {
+        if count == WORD_BITS {
+            u64::MAX
+        } else {
+            (1 << count) - 1
+        }
     }
 }
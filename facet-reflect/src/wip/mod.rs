@@ -15,8 +15,8 @@ use alloc::format;
 use bitflags::bitflags;
 use core::marker::PhantomData;
 use facet_core::{
-    Def, DefaultInPlaceFn, Facet, FieldError, PtrConst, PtrUninit, ScalarAffinity, SequenceType,
-    Shape, Type, UserType, Variant,
+    Def, DefaultInPlaceFn, Facet, Field, FieldError, PtrConst, PtrUninit, ScalarAffinity,
+    SequenceType, Shape, Type, UserType, Variant,
 };
 use flat_map::FlatMap;
 
@@ -28,6 +28,8 @@ pub use iset::*;
 mod put_f64;
 mod put_shape;
 
+mod proxy;
+
 mod enum_;
 mod flat_map;
 
@@ -128,6 +130,9 @@ pub enum FrameMode {
     /// Frame represents the None variant of an option (no allocation needed)
     /// Any `put` should fail
     OptionNone,
+    /// Frame represents the value deserialized in place of a field with a
+    /// `deserialize_with` conversion (that we allocated)
+    Proxy,
 }
 
 /// A work-in-progress heap-allocated value
@@ -355,6 +360,7 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
         }
 
         // now the root frame is at index 0
+        self.frames[0].fill_skipped_fields()?;
         let root_frame = &self.frames[0];
 
         enum FrameRef<'shape> {
@@ -582,6 +588,10 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
 
     /// Selects a field of a struct or enum variant by index and pushes it onto the frame stack.
     ///
+    /// If the field has a `deserialize_with` conversion (or a `proxy`), the pushed frame is
+    /// for the value to convert from instead, and the conversion happens when it's popped.
+    /// Values of the field's own type, such as defaults, can still be put there directly.
+    ///
     /// # Arguments
     ///
     /// * `index` - The index of the field to select.
@@ -632,6 +642,11 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
             }
         };
 
+        if let Some(deserialize_with) = field.vtable.deserialize_with {
+            self.push_proxy(index, deserialize_with)?;
+            return Ok(self);
+        }
+
         let field_data = unsafe { frame.data.field_uninit_at(field_offset) };
 
         let mut frame = Frame {
@@ -674,11 +689,11 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
         Ok(self)
     }
 
-    /// Finds the index of a field in a struct or enum variant by name.
+    /// Finds the index of a field in a struct or enum variant by name or alias.
     ///
     /// # Arguments
     ///
-    /// * `name` - The name of the field to find.
+    /// * `name` - The name or alias of the field to find.
     ///
     /// # Returns
     ///
@@ -687,7 +702,7 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
     ///   or if the field doesn't exist.
     pub fn field_index(&self, name: &str) -> Option<usize> {
         fn find_field_index(fields: &[facet_core::Field], name: &str) -> Option<usize> {
-            fields.iter().position(|f| f.name == name)
+            fields.iter().position(|f| f.is_named(name))
         }
 
         let frame = self.frames.last()?;
@@ -701,7 +716,20 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
         }
     }
 
-    /// Selects a field of a struct or enum variant by name and pushes it onto the frame stack.
+    /// Like [`Self::field_index`], but returns `None` for fields marked `skip_deserializing`, so
+    /// that deserializers treat their names like unknown keys.
+    pub fn deserializable_field_index(&self, name: &str) -> Option<usize> {
+        let index = self.field_index(name)?;
+        let frame = self.frames.last()?;
+        let field = match frame.shape.ty {
+            Type::User(UserType::Struct(def)) => def.fields.get(index),
+            Type::User(UserType::Enum(_)) => frame.istate.variant.as_ref()?.data.fields.get(index),
+            _ => None,
+        }?;
+        (!field.should_skip_deserializing()).then_some(index)
+    }
+
+    /// Selects a field of a struct or enum variant by name or alias and pushes it onto the frame stack.
     ///
     /// # Arguments
     ///
@@ -773,6 +801,34 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
         res
     }
 
+    /// Moves a value that was built separately (e.g. with another [`Wip`]) into the current frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The heap value to move into the frame.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Self)` if the value was successfully put into the frame.
+    /// * `Err(ReflectError)` if there was an error putting the value into the frame.
+    pub fn put_heap_value(
+        self,
+        mut value: HeapValue<'facet, 'shape>,
+    ) -> Result<Wip<'facet, 'shape>, ReflectError<'shape>> {
+        let guard = value.guard.take().unwrap();
+        match self.put_shape(PtrConst::new(guard.ptr), value.shape) {
+            Ok(wip) => {
+                drop(guard); // free memory (but don't drop in place), ownership moved into Wip
+                Ok(wip)
+            }
+            Err(err) => {
+                // The value wasn't moved: give the guard back so it's dropped with `value`
+                value.guard = Some(guard);
+                Err(err)
+            }
+        }
+    }
+
     /// Tries to parse the current frame's value from a string
     pub fn parse<'ínput>(mut self, s: &'ínput str) -> Result<Self, ReflectError<'shape>> {
         let Some(frame) = self.frames.last_mut() else {
@@ -810,10 +866,13 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
     }
 
     /// Puts a value using a provided DefaultInPlaceFn in the current frame.
+    ///
+    /// For a field with a `deserialize_with` conversion, the function builds the field itself.
     pub fn put_from_fn(
         mut self,
         default_in_place: DefaultInPlaceFn,
     ) -> Result<Self, ReflectError<'shape>> {
+        self.bypass_proxy();
         let Some(frame) = self.frames.last_mut() else {
             return Err(ReflectError::OperationFailed {
                 shape: <()>::SHAPE,
@@ -848,6 +907,15 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
         Ok(self)
     }
 
+    /// Puts the default value of the current field, for fields missing from the input.
+    ///
+    /// Unlike [`Self::put_default`], this writes the field itself rather than the value
+    /// converted by its `deserialize_with`, if any.
+    pub fn put_field_default(mut self) -> Result<Self, ReflectError<'shape>> {
+        self.bypass_proxy();
+        self.put_default()
+    }
+
     /// Puts the default value in the current frame.
     pub fn put_default(self) -> Result<Self, ReflectError<'shape>> {
         let Some(frame) = self.frames.last() else {
@@ -874,6 +942,15 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
         shape: &'shape Shape<'shape>,
         index: Option<usize>,
     ) -> Result<(), ReflectError<'shape>> {
+        // Proxies mark their field once they're converted, when popped
+        if self
+            .frames
+            .last()
+            .is_some_and(|frame| frame.istate.mode == FrameMode::Proxy)
+        {
+            return Ok(());
+        }
+
         if let Some(index) = index {
             let parent_index = self.frames.len().saturating_sub(2);
             #[cfg(feature = "log")]
@@ -915,15 +992,16 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
         Ok(())
     }
 
-    /// Returns the shape of the element type for a list/array
+    /// Returns the shape of the element type for a list/array/set
     pub fn element_shape(&self) -> Result<&'shape Shape<'shape>, ReflectError<'shape>> {
         let frame = self.frames.last().unwrap();
         let shape = frame.shape;
 
         match shape.def {
             Def::List(list_def) => Ok(list_def.t()),
+            Def::Set(set_def) => Ok(set_def.t()),
             _ => Err(ReflectError::WasNotA {
-                expected: "list or array",
+                expected: "list, array or set",
                 actual: shape,
             }),
         }
@@ -1133,10 +1211,37 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
         Ok(self)
     }
 
-    /// Pushes a new element onto the list/array/tuple struct/tuple enum variant
+    /// Begins insertion mode for a set, allowing elements to be added one by one with `push`
+    pub fn begin_set_insert(mut self) -> Result<Self, ReflectError<'shape>> {
+        let Some(frame) = self.frames.last_mut() else {
+            return Err(ReflectError::OperationFailed {
+                shape: <()>::SHAPE,
+                operation: "tried to begin set insertion but there was no frame",
+            });
+        };
+
+        let Def::Set(set_def) = frame.shape.def else {
+            return Err(ReflectError::WasNotA {
+                expected: "set",
+                actual: frame.shape,
+            });
+        };
+
+        // Initialize an empty set if it's not already initialized
+        if !frame.istate.fields.has(0) {
+            unsafe {
+                (set_def.vtable.init_in_place_with_capacity_fn)(frame.data, 0);
+                frame.istate.fields.set(0);
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Pushes a new element onto the list/array/set/tuple struct/tuple enum variant
     ///
     /// This creates a new frame for the element. When this frame is popped,
-    /// the element will be added to the list or the corresponding tuple field will be set.
+    /// the element will be added to the list or set, or the corresponding tuple field will be set.
     pub fn push(mut self) -> Result<Self, ReflectError<'shape>> {
         // Get mutable access to the top frame early, we might need it for list_index
         let frame_len = self.frames.len();
@@ -1163,6 +1268,12 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
                     let shape = list_def.t();
                     (shape, "list")
                 }
+                (_, Def::Set(set_def)) => {
+                    if !frame.istate.fields.has(0) {
+                        return self.begin_set_insert()?.push();
+                    }
+                    (set_def.t(), "set")
+                }
                 (_, Def::Array(array_def)) => {
                     // For arrays, we need to check which index we're on and verify it's valid
                     let index = frame.istate.list_index.unwrap_or(0);
@@ -1205,12 +1316,8 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
                     if sd.kind == facet_core::StructKind::Tuple =>
                 {
                     // Handle tuple struct (requires mutable frame for list_index)
-                    let field_index = {
-                        // Borrow frame mutably (already done) to update list_index
-                        let next_idx = frame.istate.list_index.unwrap_or(0);
-                        frame.istate.list_index = Some(next_idx + 1);
-                        next_idx
-                    };
+                    let field_index =
+                        next_tuple_field_index(&mut frame.istate.list_index, sd.fields);
                     // Check if the field index is valid
                     if field_index >= sd.fields.len() {
                         return Err(ReflectError::FieldError {
@@ -1241,12 +1348,8 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
                         });
                     }
                     // Get the next field index for the tuple variant
-                    let field_index = {
-                        // Borrow frame mutably (already done) to update list_index
-                        let next_idx = frame.istate.list_index.unwrap_or(0);
-                        frame.istate.list_index = Some(next_idx + 1);
-                        next_idx
-                    };
+                    let fields = variant.data.fields;
+                    let field_index = next_tuple_field_index(&mut frame.istate.list_index, fields);
                     // Check if the field index is valid within the variant's fields
                     if field_index >= variant.data.fields.len() {
                         return Err(ReflectError::FieldError {
@@ -1270,7 +1373,7 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
                 _ => {
                     // If it's not a list, tuple struct, or enum, it's an error
                     return Err(ReflectError::WasNotA {
-                        expected: "list, array, tuple, tuple struct, or tuple enum variant",
+                        expected: "list, array, set, tuple, tuple struct, or tuple enum variant",
                         actual: seq_shape,
                     });
                 }
@@ -1682,7 +1785,7 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
                 FrameMode::Root => {
                     // Root doesn't add to the path
                 }
-                FrameMode::Field => {
+                FrameMode::Field | FrameMode::Proxy => {
                     // For struct fields, we use dot notation with field name
                     if let Some(index) = frame.field_index_in_parent {
                         // Find the parent frame to get the field name
@@ -1750,3 +1853,17 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
         }
     }
 }
+
+/// Returns the index of the next tuple field to push into, skipping `skip_deserializing`
+/// fields, and advances `list_index` past it.
+fn next_tuple_field_index(list_index: &mut Option<usize>, fields: &[Field<'_>]) -> usize {
+    let mut index = list_index.unwrap_or(0);
+    while fields
+        .get(index)
+        .is_some_and(|field| field.should_skip_deserializing())
+    {
+        index += 1;
+    }
+    *list_index = Some(index + 1);
+    index
+}
@@ -27,6 +27,9 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
     }
 
     fn pop_inner(&mut self) -> Result<Option<Frame<'shape>>, ReflectError<'shape>> {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.fill_skipped_fields()?;
+        }
         let mut frame = match self.frames.pop() {
             Some(f) => f,
             None => return Ok(None),
@@ -56,8 +59,12 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
             // 2. Mark the parent's field as initialized (if this is a field)
             #[cfg(feature = "log")]
             let num_frames = self.frames.len();
+            //    (proxies mark it once they're converted, below)
             if let Some(parent) = self.frames.last_mut() {
-                if let Some(index) = frame.field_index_in_parent {
+                if let (Some(index), false) = (
+                    frame.field_index_in_parent,
+                    frame.istate.mode == FrameMode::Proxy,
+                ) {
                     trace!(
                         "[{}] Marking field #{} in parent {} as initialized",
                         num_frames,
@@ -123,6 +130,20 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
                                 self.mark_moved_out_of(&mut frame);
                             }
                         }
+                        Def::Set(set_def) => {
+                            trace!(
+                                "[{}] Inserting element into set {}",
+                                frame_len,
+                                parent_shape.blue()
+                            );
+                            unsafe {
+                                (set_def.vtable.insert_fn)(
+                                    PtrMut::new(parent_frame.data.as_mut_byte_ptr()),
+                                    PtrMut::new(frame.data.as_mut_byte_ptr()),
+                                );
+                                self.mark_moved_out_of(&mut frame);
+                            }
+                        }
                         Def::Scalar(s) if matches!(s.affinity, ScalarAffinity::Empty(_)) => {
                             trace!(
                                 "[{}] Handling scalar empty unit type {}",
@@ -405,7 +426,7 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
                             // Unexpected parent type
                             _ => {
                                 panic!(
-                                    "FrameMode::ListElement pop expected parent to be List, Tuple, Tuple Struct, Tuple Enum Variant, or Array, but got {}",
+                                    "FrameMode::ListElement pop expected parent to be List, Set, Tuple, Tuple Struct, Tuple Enum Variant, or Array, but got {}",
                                     parent_shape
                                 );
                             }
@@ -544,6 +565,15 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
                 }
             }
 
+            // Handle frames deserialized in place of a field
+            FrameMode::Proxy => {
+                if frame.is_fully_initialized() {
+                    self.convert_proxy(&mut frame)?;
+                } else {
+                    trace!("Popping Proxy (not fully init'd)");
+                }
+            }
+
             // Map keys are just tracked, they don't need special handling when popped
             // FIXME: that's not true, we need to deallocate them at least??
             FrameMode::MapKey => {}
@@ -0,0 +1,148 @@
+use alloc::string::String;
+use facet_core::{DeserializeWith, Field, PtrMut, Type, UserType};
+#[cfg(feature = "log")]
+use owo_colors::OwoColorize;
+
+use crate::trace;
+use crate::{FrameFlags, FrameMode, IState, ReflectError, Wip};
+
+use super::Frame;
+
+impl<'facet, 'shape> Wip<'facet, 'shape> {
+    /// Pushes a frame for the value deserialized in place of the field at `index`, which
+    /// gets converted into the field when popped.
+    pub(crate) fn push_proxy(
+        &mut self,
+        index: usize,
+        deserialize_with: DeserializeWith,
+    ) -> Result<(), ReflectError<'shape>> {
+        let shape = deserialize_with.shape;
+        let data = shape
+            .allocate()
+            .map_err(|_| ReflectError::Unsized { shape })?;
+
+        trace!(
+            "[{}] Pushing proxy frame {} for field #{}",
+            self.frames.len(),
+            shape.blue(),
+            index.yellow(),
+        );
+
+        self.frames.push(Frame {
+            data,
+            shape,
+            field_index_in_parent: Some(index),
+            istate: IState::new(self.frames.len(), FrameMode::Proxy, FrameFlags::ALLOCATED),
+        });
+        Ok(())
+    }
+
+    /// Returns the field the current frame stands in for, if it's a proxy frame
+    pub(crate) fn proxied_field(&self) -> Option<Field<'shape>> {
+        let frame = self.frames.last()?;
+        if frame.istate.mode != FrameMode::Proxy {
+            return None;
+        }
+        let parent = self.frames.get(self.frames.len().checked_sub(2)?)?;
+        parent_field(parent, frame.field_index_in_parent?)
+    }
+
+    /// Replaces the current proxy frame with a frame for the field itself, so that a value
+    /// of the field's own type can be put there directly (defaults, for example).
+    pub(crate) fn bypass_proxy(&mut self) {
+        let Some(field) = self.proxied_field() else {
+            return;
+        };
+
+        let mut frame = self.frames.pop().unwrap();
+        let index = frame.field_index_in_parent;
+        trace!(
+            "[{}] Bypassing proxy {} for field {}",
+            self.frames.len(),
+            frame.shape.blue(),
+            field.name.blue(),
+        );
+        if frame.is_fully_initialized() {
+            unsafe { frame.drop_and_dealloc_if_needed() };
+        } else {
+            frame.dealloc_if_needed();
+        }
+
+        let parent = self.frames.last().unwrap();
+        self.frames.push(Frame {
+            data: unsafe { parent.data.field_uninit_at(field.offset) },
+            shape: field.shape(),
+            field_index_in_parent: index,
+            istate: IState::new(self.frames.len(), FrameMode::Field, FrameFlags::EMPTY),
+        });
+    }
+
+    /// Converts a fully initialized proxy frame, which was just popped, into the field
+    /// it stands in for.
+    pub(crate) fn convert_proxy(
+        &mut self,
+        frame: &mut Frame<'shape>,
+    ) -> Result<(), ReflectError<'shape>> {
+        let index = frame.field_index_in_parent.unwrap();
+        let parent = self.frames.last_mut().unwrap();
+        let field = parent_field(parent, index).unwrap();
+        let deserialize_with = field.vtable.deserialize_with.unwrap();
+
+        if parent.istate.fields.has(index) {
+            unsafe {
+                if let Some(drop_fn) = frame.shape.vtable.drop_in_place {
+                    drop_fn(PtrMut::new(frame.data.as_mut_byte_ptr()));
+                }
+                self.mark_moved_out_of(frame);
+            }
+            return Err(ReflectError::OperationFailed {
+                shape: field.shape(),
+                operation: "was supposed to mark a field as initialized, but the parent frame already had it marked as initialized",
+            });
+        }
+
+        trace!(
+            "Converting proxy {} into field {} ({})",
+            frame.shape.blue(),
+            field.name.blue(),
+            field.shape().green(),
+        );
+
+        let mut message = String::new();
+        let converted = unsafe {
+            (deserialize_with.convert_fn)(
+                PtrMut::new(frame.data.as_mut_byte_ptr()),
+                parent.data.field_uninit_at(field.offset),
+                &mut message,
+            )
+        };
+        if converted.is_some() {
+            parent.istate.fields.set(index);
+        }
+
+        // The proxy value was consumed by the conversion, whether it succeeded or not
+        unsafe { self.mark_moved_out_of(frame) };
+
+        match converted {
+            Some(_) => Ok(()),
+            None => Err(ReflectError::ConversionFailed {
+                src_shape: frame.shape,
+                dst_shape: field.shape(),
+                message,
+            }),
+        }
+    }
+}
+
+/// Returns the field at `index` of a struct, or of the selected variant of an enum
+fn parent_field<'shape>(parent: &Frame<'shape>, index: usize) -> Option<Field<'shape>> {
+    match parent.shape.ty {
+        Type::User(UserType::Struct(sd)) => sd.fields.get(index).copied(),
+        Type::User(UserType::Enum(_)) => parent
+            .istate
+            .variant
+            .as_ref()
+            .and_then(|variant| variant.data.fields.get(index).copied()),
+        _ => None,
+    }
+}
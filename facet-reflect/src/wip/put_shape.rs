@@ -14,6 +14,14 @@ impl<'facet, 'shape> Wip<'facet, 'shape> {
         src: PtrConst<'_>,
         src_shape: &'shape Shape<'shape>,
     ) -> Result<Wip<'facet, 'shape>, ReflectError<'shape>> {
+        // A value of the field's own type goes straight into a field with a conversion
+        if self
+            .proxied_field()
+            .is_some_and(|field| field.shape() == src_shape)
+        {
+            self.bypass_proxy();
+        }
+
         let Some(frame) = self.frames.last_mut() else {
             return Err(ReflectError::OperationFailed {
                 shape: src_shape,
@@ -1,2 +1,3 @@
 mod peek;
+mod poke;
 mod wip;
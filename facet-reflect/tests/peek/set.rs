@@ -0,0 +1,48 @@
+use facet_reflect::Peek;
+use facet_testhelpers::test;
+use std::collections::{BTreeSet, HashSet};
+
+#[test]
+fn test_peek_set_basics() {
+    let source = HashSet::from(["a", "b", "c"]);
+
+    let peek_value = Peek::new(&source);
+    let peek_set = peek_value.into_set()?;
+    assert_eq!(peek_set.len(), 3);
+    assert!(!peek_set.is_empty());
+
+    assert!(peek_set.contains(&"a"));
+    assert!(peek_set.contains(&"b"));
+    assert!(peek_set.contains(&"c"));
+    assert!(!peek_set.contains(&"d"));
+}
+
+#[test]
+fn test_peek_set_empty() {
+    let source: HashSet<i32> = HashSet::new();
+    let peek_value = Peek::new(&source);
+    let peek_set = peek_value.into_set()?;
+    assert_eq!(peek_set.len(), 0);
+    assert!(peek_set.is_empty());
+    assert!(!peek_set.contains(&1));
+    assert_eq!(peek_set.iter().count(), 0);
+}
+
+#[test]
+fn test_peek_set_iteration() {
+    let source = BTreeSet::from([3, 1, 2]);
+
+    let peek_value = Peek::new(&source);
+    let peek_set = peek_value.into_set()?;
+    let items: Vec<i32> = peek_set
+        .iter()
+        .map(|item| *item.get::<i32>().unwrap())
+        .collect();
+    assert_eq!(items, vec![1, 2, 3]);
+}
+
+#[test]
+fn test_peek_set_not_a_set() {
+    let source = vec![1, 2, 3];
+    assert!(Peek::new(&source).into_set().is_err());
+}
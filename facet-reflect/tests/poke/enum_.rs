@@ -0,0 +1,104 @@
+use facet::Facet;
+use facet_reflect::{Poke, ReflectError};
+use facet_testhelpers::test;
+
+#[derive(Facet, Debug, PartialEq)]
+#[repr(u8)]
+#[allow(dead_code)]
+enum Figure {
+    Empty,
+    Circle { radius: u32, label: String },
+    Named(String),
+    Custom(NoDefault),
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct NoDefault(u32);
+
+#[test]
+fn poke_enum_set_variant() {
+    let mut figure = Figure::Named("big".to_string());
+
+    let mut poke = Poke::new(&mut figure);
+    poke.set_variant_named("Circle")?;
+    poke.field_mut_named("radius")?.set(3u32)?;
+    assert_eq!(
+        figure,
+        Figure::Circle {
+            radius: 3,
+            label: String::new()
+        }
+    );
+
+    Poke::new(&mut figure).set_variant(0)?;
+    assert_eq!(figure, Figure::Empty);
+}
+
+#[test]
+fn poke_enum_variant_field() {
+    let mut figure = Figure::Named("big".to_string());
+
+    Poke::new(&mut figure)
+        .field_mut(0)?
+        .set("small".to_string())?;
+    assert_eq!(figure, Figure::Named("small".to_string()));
+}
+
+#[test]
+fn poke_enum_variant_needs_defaults() {
+    let mut figure = Figure::Empty;
+
+    let err = Poke::new(&mut figure)
+        .set_variant_named("Custom")
+        .unwrap_err();
+    assert!(
+        matches!(err, ReflectError::MissingCharacteristic { .. }),
+        "{err}"
+    );
+    assert_eq!(figure, Figure::Empty);
+}
+
+#[test]
+fn poke_enum_no_such_variant() {
+    let mut figure = Figure::Empty;
+
+    let mut poke = Poke::new(&mut figure);
+    assert!(poke.set_variant_named("Square").is_err());
+    assert!(poke.set_variant(4).is_err());
+}
+
+#[test]
+fn poke_option_like_enum() {
+    let mut value = Some(Box::new(5u32));
+
+    Poke::new(&mut value).set_variant_named("None")?;
+    assert_eq!(value, None);
+}
+
+#[test]
+fn poke_enum_panicking_default() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Bomb(u32);
+
+    impl Default for Bomb {
+        fn default() -> Self {
+            panic!("boom")
+        }
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Slot {
+        Text(String),
+        Armed { label: String, bomb: Bomb },
+    }
+
+    let mut slot = Slot::Text("kept".to_string());
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        Poke::new(&mut slot).set_variant_named("Armed")
+    }));
+    assert!(result.is_err());
+    // The panic happened before the current value was touched
+    assert_eq!(slot, Slot::Text("kept".to_string()));
+}
@@ -0,0 +1,83 @@
+use facet::Facet;
+use facet_reflect::{Poke, ReflectError};
+use facet_testhelpers::test;
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(invariants = Range::invariants)]
+struct Range {
+    start: u32,
+    end: u32,
+    tags: Vec<String>,
+}
+
+impl Range {
+    fn invariants(&self) -> bool {
+        self.start <= self.end && self.tags.len() < 2
+    }
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Outer {
+    range: Range,
+}
+
+#[test]
+fn poke_set_checks_invariants_and_rolls_back() {
+    let mut range = Range {
+        start: 1,
+        end: 5,
+        tags: vec![],
+    };
+
+    let mut poke = Poke::new(&mut range);
+    poke.field_mut_named("end")?.set(3u32)?;
+
+    let err = poke.field_mut_named("end")?.set(0u32).unwrap_err();
+    assert!(
+        matches!(err, ReflectError::InvariantViolation { .. }),
+        "{err}"
+    );
+    assert_eq!(range.end, 3);
+}
+
+#[test]
+fn poke_checks_invariants_of_all_parents() {
+    let mut outer = Outer {
+        range: Range {
+            start: 1,
+            end: 5,
+            tags: vec![],
+        },
+    };
+
+    let err = Poke::new(&mut outer)
+        .field_mut_named("range")?
+        .field_mut_named("start")?
+        .set(10u32)
+        .unwrap_err();
+    assert!(
+        matches!(err, ReflectError::InvariantViolation { .. }),
+        "{err}"
+    );
+    assert_eq!(outer.range.start, 1);
+}
+
+#[test]
+fn poke_structural_change_reports_violation() {
+    let mut range = Range {
+        start: 1,
+        end: 5,
+        tags: vec!["a".to_string()],
+    };
+
+    let mut poke = Poke::new(&mut range);
+    let err = poke
+        .field_mut_named("tags")?
+        .push("b".to_string())
+        .unwrap_err();
+    assert!(
+        matches!(err, ReflectError::InvariantViolation { .. }),
+        "{err}"
+    );
+    assert_eq!(range.tags.len(), 2);
+}
@@ -0,0 +1,40 @@
+use facet_reflect::{Poke, ReflectError};
+use facet_testhelpers::test;
+
+#[test]
+fn poke_list_push_and_remove() {
+    let mut list = vec!["a".to_string(), "b".to_string()];
+
+    let mut poke = Poke::new(&mut list);
+    poke.push("c".to_string())?;
+    poke.remove(0)?;
+    poke.item_mut(1)?.set("d".to_string())?;
+
+    assert_eq!(list, vec!["b".to_string(), "d".to_string()]);
+}
+
+#[test]
+fn poke_list_out_of_bounds() {
+    let mut list = vec![1u8];
+
+    let mut poke = Poke::new(&mut list);
+    assert!(poke.remove(1).is_err());
+    assert!(poke.item_mut(1).is_err());
+    assert_eq!(list, vec![1]);
+}
+
+#[test]
+fn poke_list_wrong_item_shape() {
+    let mut list = vec![1u8];
+
+    let err = Poke::new(&mut list).push(2u16).unwrap_err();
+    assert!(matches!(err, ReflectError::WrongShape { .. }), "{err}");
+}
+
+#[test]
+fn poke_not_a_list() {
+    let mut value = 1u8;
+
+    let err = Poke::new(&mut value).push(2u8).unwrap_err();
+    assert!(matches!(err, ReflectError::WasNotA { .. }), "{err}");
+}
@@ -0,0 +1,39 @@
+use std::collections::{BTreeMap, HashMap};
+
+use facet_reflect::Poke;
+use facet_testhelpers::test;
+
+#[test]
+fn poke_hashmap_insert_and_remove() {
+    let mut map = HashMap::new();
+    map.insert("a".to_string(), 1u32);
+
+    let mut poke = Poke::new(&mut map);
+    poke.insert("b".to_string(), 2u32)?;
+    poke.insert("a".to_string(), 3u32)?;
+    assert!(poke.remove_key(&"b".to_string())?);
+    assert!(!poke.remove_key(&"b".to_string())?);
+
+    assert_eq!(map, HashMap::from([("a".to_string(), 3)]));
+}
+
+#[test]
+fn poke_btreemap_insert_and_remove() {
+    let mut map = BTreeMap::new();
+    map.insert(1u8, "one".to_string());
+
+    let mut poke = Poke::new(&mut map);
+    poke.insert(2u8, "two".to_string())?;
+    assert!(poke.remove_key(&1u8)?);
+
+    assert_eq!(map, BTreeMap::from([(2, "two".to_string())]));
+}
+
+#[test]
+fn poke_map_wrong_key_shape() {
+    let mut map: HashMap<String, u32> = HashMap::new();
+
+    let mut poke = Poke::new(&mut map);
+    assert!(poke.insert(1u32, 2u32).is_err());
+    assert!(poke.remove_key(&1u32).is_err());
+}
@@ -0,0 +1,6 @@
+mod enum_;
+mod invariant;
+mod list;
+mod map;
+mod option;
+mod struct_;
@@ -0,0 +1,33 @@
+use facet_reflect::Poke;
+use facet_testhelpers::test;
+
+#[test]
+fn poke_option_set_and_take() {
+    let mut value: Option<String> = None;
+
+    let mut poke = Poke::new(&mut value);
+    poke.set_some("hello".to_string())?;
+    poke.set_some("world".to_string())?;
+    assert_eq!(poke.take::<String>()?, Some("world".to_string()));
+    assert_eq!(poke.take::<String>()?, None);
+
+    assert_eq!(value, None);
+}
+
+#[test]
+fn poke_option_set_none() {
+    let mut value = Some(vec![1u8, 2, 3]);
+
+    Poke::new(&mut value).set_none()?;
+
+    assert_eq!(value, None);
+}
+
+#[test]
+fn poke_option_wrong_shape() {
+    let mut value: Option<String> = None;
+
+    let mut poke = Poke::new(&mut value);
+    assert!(poke.set_some(1u8).is_err());
+    assert!(poke.take::<u8>().is_err());
+}
@@ -0,0 +1,84 @@
+use facet::Facet;
+use facet_reflect::{Poke, ReflectError};
+use facet_testhelpers::test;
+
+#[derive(Facet, Debug, PartialEq)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Config {
+    name: String,
+    server: Server,
+}
+
+fn config() -> Config {
+    Config {
+        name: "app".to_string(),
+        server: Server {
+            host: "localhost".to_string(),
+            port: 8080,
+        },
+    }
+}
+
+#[test]
+fn poke_set_root() {
+    let mut value = 42u32;
+    Poke::new(&mut value).set(7u32)?;
+    assert_eq!(value, 7);
+}
+
+#[test]
+fn poke_set_field_by_path() {
+    let mut config = config();
+
+    let mut poke = Poke::new(&mut config);
+    poke.field_mut_named("server")?
+        .field_mut_named("port")?
+        .set(9090u16)?;
+    poke.field_mut(0)?.set("other".to_string())?;
+
+    assert_eq!(
+        config,
+        Config {
+            name: "other".to_string(),
+            server: Server {
+                host: "localhost".to_string(),
+                port: 9090,
+            },
+        }
+    );
+}
+
+#[test]
+fn poke_get() {
+    let mut config = config();
+    let mut poke = Poke::new(&mut config);
+    let mut server = poke.field_mut_named("server")?;
+    assert_eq!(
+        *server.field_mut_named("host")?.get::<String>()?,
+        "localhost"
+    );
+}
+
+#[test]
+fn poke_set_wrong_shape() {
+    let mut config = config();
+    let err = Poke::new(&mut config)
+        .field_mut_named("name")?
+        .set(3u64)
+        .unwrap_err();
+    assert!(matches!(err, ReflectError::WrongShape { .. }), "{err}");
+    assert_eq!(config.name, "app");
+}
+
+#[test]
+fn poke_no_such_field() {
+    let mut config = config();
+    let mut poke = Poke::new(&mut config);
+    assert!(poke.field_mut_named("nope").is_err());
+    assert!(poke.field_mut(2).is_err());
+}
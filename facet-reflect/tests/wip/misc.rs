@@ -433,6 +433,47 @@ fn wip_switch_enum_variant() {
     assert_eq!(result, EnumWithData::Tuple(43, String::from("Changed")));
 }
 
+#[test]
+fn wip_field_and_variant_aliases() {
+    #[derive(Facet, PartialEq, Debug)]
+    struct Aliased {
+        #[facet(alias = "identifier")]
+        id: u32,
+    }
+
+    let wip = Wip::alloc::<Aliased>()?;
+    assert_eq!(wip.field_index("id"), Some(0));
+    assert_eq!(wip.field_index("identifier"), Some(0));
+    assert_eq!(wip.field_index("ident"), None);
+
+    let v = wip
+        .field_named("identifier")?
+        .put(7u32)?
+        .pop()?
+        .build()?
+        .materialize::<Aliased>()?;
+    assert_eq!(v, Aliased { id: 7 });
+
+    #[derive(Facet, PartialEq, Debug)]
+    #[repr(u8)]
+    enum Answer {
+        #[facet(alias = "Y")]
+        Yes,
+        No,
+    }
+
+    let wip = Wip::alloc::<Answer>()?;
+    assert_eq!(wip.find_variant("Y").map(|(index, _)| index), Some(0));
+    let v = wip.variant_named("Y")?.build()?.materialize::<Answer>()?;
+    assert_eq!(v, Answer::Yes);
+
+    let v = Wip::alloc::<Answer>()?
+        .variant_named("No")?
+        .build()?
+        .materialize::<Answer>()?;
+    assert_eq!(v, Answer::No);
+}
+
 // List tests
 
 #[test]
@@ -869,3 +910,53 @@ fn wip_build_option_none_through_default() {
     let option = hv.materialize::<Option<u32>>()?;
     assert_eq!(option, None);
 }
+
+#[test]
+fn wip_skip_deserializing_fields_get_their_default() {
+    #[derive(Facet, PartialEq, Eq, Debug)]
+    struct Handle {
+        name: String,
+        #[facet(skip_deserializing)]
+        fd: i32,
+        #[facet(skip_deserializing, default = 3)]
+        retries: u8,
+    }
+
+    let wip = Wip::alloc::<Handle>()?;
+    assert_eq!(wip.field_index("fd"), Some(1));
+    assert_eq!(wip.deserializable_field_index("fd"), None);
+
+    let handle = wip
+        .field_named("name")?
+        .put(String::from("stdin"))?
+        .pop()?
+        .build()?
+        .materialize::<Handle>()?;
+    assert_eq!(
+        handle,
+        Handle {
+            name: String::from("stdin"),
+            fd: 0,
+            retries: 3,
+        }
+    );
+
+    #[derive(Facet, PartialEq, Eq, Debug)]
+    #[repr(u8)]
+    enum Message {
+        Pair(i32, #[facet(skip)] String, i32),
+    }
+
+    // Pushing onto a tuple variant skips over skipped fields
+    let message = Wip::alloc::<Message>()?
+        .variant_named("Pair")?
+        .push()?
+        .put(1)?
+        .pop()?
+        .push()?
+        .put(2)?
+        .pop()?
+        .build()?
+        .materialize::<Message>()?;
+    assert_eq!(message, Message::Pair(1, String::new(), 2));
+}
@@ -9,6 +9,8 @@ mod misc;
 
 mod map;
 
+mod set;
+
 mod list_leak;
 
 mod map_leak;
@@ -26,3 +28,5 @@ mod put_into_tuples;
 mod variance;
 
 mod array_building;
+
+mod wide_struct;
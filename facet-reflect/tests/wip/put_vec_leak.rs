@@ -25,3 +25,17 @@ fn put_vec_leaktest3() {
     let v = w.materialize::<Vec<String>>()?;
     assert_eq!(v, vec!["a".to_string()]);
 }
+
+#[test]
+fn put_heap_value_of_wrong_shape_drops_it() {
+    let shared = std::sync::Arc::new("a".to_string());
+    let value = Wip::alloc::<std::sync::Arc<String>>()?
+        .put(shared.clone())?
+        .build()?;
+    assert_eq!(std::sync::Arc::strong_count(&shared), 2);
+
+    let w = Wip::alloc::<u32>()?;
+    assert!(w.put_heap_value(value).is_err());
+    // The value wasn't moved anywhere, so it was dropped with the error
+    assert_eq!(std::sync::Arc::strong_count(&shared), 1);
+}
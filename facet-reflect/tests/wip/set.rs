@@ -0,0 +1,96 @@
+use facet::Facet;
+use facet_reflect::Wip;
+use facet_testhelpers::test;
+use std::collections::{BTreeSet, HashSet};
+
+#[test]
+fn wip_set_trivial() {
+    let set = Wip::alloc::<HashSet<String>>()?
+        .begin_set_insert()?
+        .push()?
+        .put::<String>("one".into())?
+        .pop()?
+        .push()?
+        .put::<String>("two".into())?
+        .pop()?
+        .build()?
+        .materialize::<HashSet<String>>()?;
+
+    assert_eq!(set, HashSet::from(["one".to_string(), "two".to_string()]));
+}
+
+#[test]
+fn wip_set_empty() {
+    let set = Wip::alloc::<BTreeSet<u32>>()?
+        .begin_set_insert()?
+        .build()?
+        .materialize::<BTreeSet<u32>>()?;
+
+    assert!(set.is_empty());
+}
+
+#[test]
+fn wip_set_deduplicates() {
+    let set = Wip::alloc::<BTreeSet<String>>()?
+        .push()?
+        .put::<String>("same".into())?
+        .pop()?
+        .push()?
+        .put::<String>("same".into())?
+        .pop()?
+        .build()?
+        .materialize::<BTreeSet<String>>()?;
+
+    assert_eq!(set, BTreeSet::from(["same".to_string()]));
+}
+
+#[test]
+fn wip_set_in_struct() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Tags {
+        tags: BTreeSet<u32>,
+        name: String,
+    }
+
+    let tags = Wip::alloc::<Tags>()?
+        .field_named("tags")?
+        .begin_set_insert()?
+        .push()?
+        .put(2u32)?
+        .pop()?
+        .push()?
+        .put(1u32)?
+        .pop()?
+        .pop()?
+        .field_named("name")?
+        .put::<String>("numbers".into())?
+        .pop()?
+        .build()?
+        .materialize::<Tags>()?;
+
+    assert_eq!(
+        tags,
+        Tags {
+            tags: BTreeSet::from([1, 2]),
+            name: "numbers".to_string(),
+        }
+    );
+}
+
+#[test]
+fn wip_set_partial_leak() {
+    // Dropping a half-built set must not leak or double-free
+    let wip = Wip::alloc::<HashSet<String>>()?
+        .begin_set_insert()?
+        .push()?
+        .put::<String>("kept".into())?
+        .pop()?
+        .push()?;
+    drop(wip);
+}
+
+#[test]
+fn wip_set_element_shape() {
+    let wip = Wip::alloc::<HashSet<u8>>()?;
+    assert_eq!(wip.element_shape()?, u8::SHAPE);
+}
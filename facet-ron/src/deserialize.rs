@@ -0,0 +1,593 @@
+//! Read RON documents into Rust values.
+
+use std::borrow::Cow;
+
+use facet_core::{
+    Def, EnumTagging, Facet, Field, FieldFlags, SequenceType, Shape, StructKind, Type, UserType,
+    Variant,
+};
+use facet_deserialize::{
+    DeserError, DeserErrorKind, Outcome, Scalar, Span, Spannable, Spanned, Tape,
+};
+
+use crate::parse::{Name, Node, NodeKind, parse, too_deep};
+use crate::{MAX_DEPTH, struct_name};
+
+/// Deserialize a value of type `T` from a RON string.
+///
+/// Structs may be written with or without their name, but a name that isn't the one of the
+/// struct is an error. Enums are written the RON way, as `Variant`, `Variant(a)` or
+/// `Variant(f: a)`, whatever their `#[facet(tag = ...)]` says about other formats. `Option`s
+/// may be written as `Some(x)`, or just `x`, and may be left out of structs altogether.
+///
+/// Errors point at where in the input they come from.
+///
+/// # Example
+/// ```rust
+/// use facet::Facet;
+///
+/// #[derive(Facet, Debug, PartialEq)]
+/// struct Level {
+///     name: String,
+///     size: (u32, u32),
+///     music: Option<String>,
+/// }
+///
+/// let level: Level = facet_ron::from_str(
+///     r#"
+///     // The first level
+///     Level(
+///         name: "meadow",
+///         size: (64, 32),
+///     )
+///     "#,
+/// )
+/// .unwrap();
+/// assert_eq!(level.size, (64, 32));
+/// assert_eq!(level.music, None);
+/// ```
+pub fn from_str<'input, 'facet, 'shape, T: Facet<'facet>>(
+    ron: &'input str,
+) -> Result<T, DeserError<'input, 'shape>>
+where
+    'input: 'facet,
+{
+    log::trace!("Entering `from_str` function");
+
+    let input = ron.as_bytes();
+    let root = match parse(ron) {
+        Ok(root) => root,
+        Err(err) => return Err(DeserError::new(err.node, input, err.span, "ron")),
+    };
+    log::trace!("RON parsed");
+
+    let mut lowering = Lowering {
+        tape: Tape::new("ron", ron.len()),
+        depth: 0,
+    };
+    if let Err(err) = lowering.value(&root, T::SHAPE) {
+        return Err(DeserError::new(err.node, input, err.span, "ron"));
+    }
+
+    facet_deserialize::deserialize(input, lowering.tape)
+}
+
+/// Lays out a document as the outcomes the deserializer expects for a given shape.
+///
+/// Whether `(a, b)` is a tuple or the fields of a tuple struct, and whether `x` is an
+/// implicit `Some(x)`, depends on the type it's deserialized into, so the whole document is
+/// laid out ahead of time, following the shape of the value.
+struct Lowering {
+    tape: Tape,
+    /// How many values deep the one being lowered is
+    depth: usize,
+}
+
+type LoweringResult<'shape> = Result<(), Spanned<DeserErrorKind<'shape>>>;
+
+impl<'shape> Lowering {
+    fn push(&mut self, outcome: Outcome<'static>, span: Span) {
+        self.tape.push(outcome, span);
+    }
+
+    fn push_key(&mut self, name: &str, span: Span) {
+        self.push(
+            Outcome::Scalar(Scalar::String(Cow::Owned(name.to_owned()))),
+            span,
+        );
+    }
+
+    /// The end of a value, where the outcome closing it goes.
+    fn end(node: &Node) -> Span {
+        Span::new(node.span.end(), 0)
+    }
+
+    /// Lowers a value one level deeper, unless that's deeper than values may be nested.
+    fn nested<F>(&mut self, node: &Node, lower: F) -> LoweringResult<'shape>
+    where
+        F: FnOnce(&mut Self) -> LoweringResult<'shape>,
+    {
+        if self.depth == MAX_DEPTH {
+            return Err(too_deep().with_span(node.open));
+        }
+        self.depth += 1;
+        let res = lower(self);
+        self.depth -= 1;
+        res
+    }
+
+    /// A value, as a value of type `shape`.
+    fn value(&mut self, node: &Node, shape: &'shape Shape<'shape>) -> LoweringResult<'shape> {
+        self.nested(node, |this| this.lower_value(node, shape))
+    }
+
+    fn lower_value(&mut self, node: &Node, shape: &'shape Shape<'shape>) -> LoweringResult<'shape> {
+        let mut shape = shape;
+        if let Def::Option(option_def) = shape.def {
+            match &node.kind {
+                NodeKind::Ident(name) if name.text == "None" => {
+                    self.push(Outcome::Scalar(Scalar::Null), node.span);
+                    return Ok(());
+                }
+                NodeKind::Tuple(Some(name), items) if name.text == "Some" && items.len() == 1 => {
+                    return self.value(&items[0], option_def.t());
+                }
+                // Anything else is an implicit `Some`
+                _ => shape = option_def.t(),
+            }
+        }
+        // Transparent wrappers are deserialized as what they wrap
+        while let (Some(inner), false) = (shape.inner, matches!(shape.def, Def::Option(_))) {
+            shape = inner();
+        }
+        if let Def::Option(_) = shape.def {
+            return self.lower_value(node, shape);
+        }
+
+        if let Some(item_shape) = item_shape(shape) {
+            return match &node.kind {
+                NodeKind::List(items) | NodeKind::Tuple(None, items) => {
+                    self.items(node, items, |_| Some(item_shape))
+                }
+                _ => self.lower_generic(node),
+            };
+        }
+        if let Def::Map(map_def) = shape.def {
+            return match &node.kind {
+                NodeKind::Map(entries) => self.map(node, entries, map_def.v()),
+                _ => self.lower_generic(node),
+            };
+        }
+        match shape.ty {
+            Type::User(UserType::Struct(struct_type)) => match (struct_type.kind, &node.kind) {
+                (StructKind::Unit, NodeKind::Ident(name)) => {
+                    check_name(*name, shape)?;
+                    self.push(Outcome::ObjectStarted, node.span);
+                    self.push(Outcome::ObjectEnded, Self::end(node));
+                    Ok(())
+                }
+                (StructKind::Unit, NodeKind::Tuple(name, items)) if items.is_empty() => {
+                    if let Some(name) = name {
+                        check_name(*name, shape)?;
+                    }
+                    self.push(Outcome::ObjectStarted, node.open);
+                    self.push(Outcome::ObjectEnded, Self::end(node));
+                    Ok(())
+                }
+                (StructKind::Tuple | StructKind::TupleStruct, NodeKind::Tuple(name, items)) => {
+                    if let Some(name) = name {
+                        check_name(*name, shape)?;
+                    }
+                    self.positional(node, items, struct_type.fields)
+                }
+                (StructKind::Struct, NodeKind::Struct(name, fields)) => {
+                    if let Some(name) = name {
+                        check_name(*name, shape)?;
+                    }
+                    self.push(Outcome::ObjectStarted, node.open);
+                    self.fields(node, fields, struct_type.fields)?;
+                    self.push(Outcome::ObjectEnded, Self::end(node));
+                    Ok(())
+                }
+                // A struct whose fields are all left out
+                (StructKind::Struct, NodeKind::Tuple(name, items)) if items.is_empty() => {
+                    if let Some(name) = name {
+                        check_name(*name, shape)?;
+                    }
+                    self.push(Outcome::ObjectStarted, node.open);
+                    self.fields(node, &[], struct_type.fields)?;
+                    self.push(Outcome::ObjectEnded, Self::end(node));
+                    Ok(())
+                }
+                _ => self.lower_generic(node),
+            },
+            Type::User(UserType::Enum(enum_type)) => {
+                let (name, content) = match &node.kind {
+                    NodeKind::Ident(name) => (*name, None),
+                    NodeKind::Tuple(Some(name), _) | NodeKind::Struct(Some(name), _) => {
+                        (*name, Some(node))
+                    }
+                    _ => return self.lower_generic(node),
+                };
+                match enum_type
+                    .variants
+                    .iter()
+                    .find(|variant| variant.is_named(name.text))
+                {
+                    Some(variant) => self.variant(node, name, variant, content, shape),
+                    None => {
+                        // Left for the deserializer to report
+                        self.push(Outcome::ObjectStarted, node.open);
+                        self.push_key(name.text, name.span);
+                        match content {
+                            Some(content) => self.generic_content(content)?,
+                            None => self.push(Outcome::Scalar(Scalar::Null), name.span),
+                        }
+                        self.push(Outcome::ObjectEnded, Self::end(node));
+                        Ok(())
+                    }
+                }
+            }
+            Type::Sequence(SequenceType::Tuple(tuple_type)) => match &node.kind {
+                NodeKind::Tuple(None, items) => self.items(node, items, |index| {
+                    tuple_type.fields.get(index).map(|field| field.shape())
+                }),
+                _ => self.lower_generic(node),
+            },
+            _ => match &node.kind {
+                // The unit type
+                NodeKind::Tuple(None, items) if items.is_empty() => {
+                    self.push(Outcome::Scalar(Scalar::Null), node.span);
+                    Ok(())
+                }
+                _ => self.lower_generic(node),
+            },
+        }
+    }
+
+    /// An enum variant, `Variant`, `Variant(a)` or `Variant(f: a)`, laid out the way the
+    /// enum is tagged.
+    fn variant(
+        &mut self,
+        node: &Node,
+        name: Name,
+        variant: &'shape Variant<'shape>,
+        content: Option<&Node>,
+        shape: &'shape Shape<'shape>,
+    ) -> LoweringResult<'shape> {
+        let fields = variant.data.fields;
+        let end = Self::end(node);
+        // A bare `Variant` has no bracket, so its object starts where its name does
+        let open = match content {
+            Some(_) => node.open,
+            None => Span::new(node.span.start(), 0),
+        };
+        match shape.enum_tagging() {
+            EnumTagging::External => match content {
+                None if fields.is_empty() => {
+                    self.push_key(variant.name, name.span);
+                    Ok(())
+                }
+                _ => {
+                    self.push(Outcome::ObjectStarted, open);
+                    self.push_key(variant.name, name.span);
+                    self.variant_content(node, variant, content)?;
+                    self.push(Outcome::ObjectEnded, end);
+                    Ok(())
+                }
+            },
+            EnumTagging::Internal { tag } => {
+                self.push(Outcome::ObjectStarted, open);
+                self.push_key(tag, name.span);
+                self.push_key(variant.name, name.span);
+                match content.map(|content| &content.kind) {
+                    Some(NodeKind::Struct(_, present)) => self.fields(node, present, fields)?,
+                    None | Some(NodeKind::Tuple(_, _)) if variant.data.kind == StructKind::Unit => {
+                    }
+                    // A struct variant whose fields are all left out
+                    None => self.fields(node, &[], fields)?,
+                    Some(_) => {
+                        return Err(DeserErrorKind::InvalidSyntax(format!(
+                            "internally tagged variant `{}` must have named fields",
+                            variant.name
+                        ))
+                        .with_span(node.span));
+                    }
+                }
+                self.push(Outcome::ObjectEnded, end);
+                Ok(())
+            }
+            EnumTagging::Adjacent {
+                tag,
+                content: content_key,
+            } => {
+                self.push(Outcome::ObjectStarted, open);
+                self.push_key(tag, name.span);
+                self.push_key(variant.name, name.span);
+                if variant.data.kind != StructKind::Unit {
+                    // Keys where the tag key is are taken as the tag, and skipped
+                    self.push_key(content_key, Span::new(node.open.end(), 0));
+                    self.variant_content(node, variant, content)?;
+                }
+                self.push(Outcome::ObjectEnded, end);
+                Ok(())
+            }
+            EnumTagging::Untagged => self.variant_content(node, variant, content),
+        }
+    }
+
+    /// What's in the brackets of a variant, as its fields.
+    fn variant_content(
+        &mut self,
+        node: &Node,
+        variant: &'shape Variant<'shape>,
+        content: Option<&Node>,
+    ) -> LoweringResult<'shape> {
+        let fields = variant.data.fields;
+        let Some(content) = content else {
+            return match variant.data.kind {
+                StructKind::Unit => {
+                    self.push(Outcome::Scalar(Scalar::Null), node.span);
+                    Ok(())
+                }
+                // A struct variant whose fields are all left out
+                StructKind::Struct => {
+                    self.push(Outcome::ObjectStarted, node.open);
+                    self.fields(node, &[], fields)?;
+                    self.push(Outcome::ObjectEnded, Self::end(node));
+                    Ok(())
+                }
+                _ => Err(DeserErrorKind::MissingValue {
+                    expected: "the fields of the variant",
+                    field: variant.name.to_owned(),
+                }
+                .with_span(node.span)),
+            };
+        };
+        match (variant.data.kind, &content.kind) {
+            (StructKind::Tuple | StructKind::TupleStruct, NodeKind::Tuple(_, items))
+                if fields.len() == 1 && items.len() == 1 =>
+            {
+                self.value(&items[0], fields[0].shape())
+            }
+            (StructKind::Tuple | StructKind::TupleStruct, NodeKind::Tuple(_, items)) => {
+                self.items(content, items, |index| {
+                    fields.get(index).map(|field| field.shape())
+                })
+            }
+            (StructKind::Struct, NodeKind::Struct(_, present)) => {
+                self.push(Outcome::ObjectStarted, content.open);
+                self.fields(content, present, fields)?;
+                self.push(Outcome::ObjectEnded, Self::end(content));
+                Ok(())
+            }
+            (StructKind::Struct | StructKind::Unit, NodeKind::Tuple(_, items))
+                if items.is_empty() =>
+            {
+                self.push(Outcome::ObjectStarted, content.open);
+                self.fields(content, &[], fields)?;
+                self.push(Outcome::ObjectEnded, Self::end(content));
+                Ok(())
+            }
+            _ => self.generic_content(content),
+        }
+    }
+
+    /// Values, as the items of a list or the fields of a tuple.
+    fn items<F>(&mut self, node: &Node, items: &[Node], item_shape: F) -> LoweringResult<'shape>
+    where
+        F: Fn(usize) -> Option<&'shape Shape<'shape>>,
+    {
+        self.push(Outcome::ListStarted, node.open);
+        for (index, item) in items.iter().enumerate() {
+            match item_shape(index) {
+                Some(shape) => self.value(item, shape)?,
+                None => self.generic(item)?,
+            }
+        }
+        self.push(Outcome::ListEnded, Self::end(node));
+        Ok(())
+    }
+
+    /// Values, as the fields of a tuple struct, which are named after their position. Reading them as an object rather than a list lets the deserializer fill
+    /// them in one by one, whether the type has a default or not.
+    fn positional(
+        &mut self,
+        node: &Node,
+        items: &[Node],
+        fields: &'shape [Field<'shape>],
+    ) -> LoweringResult<'shape> {
+        if items.len() > fields.len() {
+            return Err(DeserErrorKind::InvalidSyntax(format!(
+                "expected {} values, found {}",
+                fields.len(),
+                items.len()
+            ))
+            .with_span(items[fields.len()].span));
+        }
+        self.push(Outcome::ObjectStarted, node.open);
+        for (item, field) in items.iter().zip(fields) {
+            self.push_key(field.name, Span::new(item.span.start(), 0));
+            self.value(item, field.shape())?;
+        }
+        self.push(Outcome::ObjectEnded, Self::end(node));
+        Ok(())
+    }
+
+    /// The fields of a struct or a variant, between the outcomes starting and ending it.
+    /// Fields left out are `None`, if they're optional.
+    fn fields(
+        &mut self,
+        node: &Node,
+        present: &[(Name, Node)],
+        fields: &'shape [Field<'shape>],
+    ) -> LoweringResult<'shape> {
+        let mut names = Vec::new();
+        for (name, value) in present {
+            self.push_key(name.text, name.span);
+            match find_field(fields, name.text) {
+                Some(field) => {
+                    names.push(field.name);
+                    self.value(value, field.shape())?;
+                }
+                // Left for the deserializer to skip, or to report
+                None => self.generic(value)?,
+            }
+        }
+        let end = Self::end(node);
+        for field in absent_fields(fields, &names) {
+            if matches!(field.shape().def, Def::Option(_)) {
+                self.push_key(field.name, end);
+                self.push(Outcome::Scalar(Scalar::Null), end);
+            }
+        }
+        Ok(())
+    }
+
+    /// A map, whose keys are taken as text.
+    fn map(
+        &mut self,
+        node: &Node,
+        entries: &[(Node, Node)],
+        value_shape: &'shape Shape<'shape>,
+    ) -> LoweringResult<'shape> {
+        self.push(Outcome::ObjectStarted, node.open);
+        for (key, value) in entries {
+            self.push_key(&key_text(key)?, key.span);
+            self.value(value, value_shape)?;
+        }
+        self.push(Outcome::ObjectEnded, Self::end(node));
+        Ok(())
+    }
+
+    /// A value whose shape isn't known, or isn't the one it looks like, as the value it
+    /// looks like.
+    fn generic(&mut self, node: &Node) -> LoweringResult<'shape> {
+        self.nested(node, |this| this.lower_generic(node))
+    }
+
+    fn lower_generic(&mut self, node: &Node) -> LoweringResult<'shape> {
+        match &node.kind {
+            NodeKind::Number(scalar) => self.push(Outcome::Scalar(scalar.clone()), node.span),
+            NodeKind::Str(string) => self.push_key(string, node.span),
+            NodeKind::Char(c) => self.push_key(&c.to_string(), node.span),
+            NodeKind::Bool(b) => self.push(Outcome::Scalar(Scalar::Bool(*b)), node.span),
+            NodeKind::Ident(name) => self.push_key(name.text, node.span),
+            NodeKind::List(items) | NodeKind::Tuple(_, items) => {
+                self.push(Outcome::ListStarted, node.open);
+                for item in items {
+                    self.generic(item)?;
+                }
+                self.push(Outcome::ListEnded, Self::end(node));
+            }
+            NodeKind::Struct(_, fields) => {
+                self.push(Outcome::ObjectStarted, node.open);
+                for (name, value) in fields {
+                    self.push_key(name.text, name.span);
+                    self.generic(value)?;
+                }
+                self.push(Outcome::ObjectEnded, Self::end(node));
+            }
+            NodeKind::Map(entries) => {
+                self.push(Outcome::ObjectStarted, node.open);
+                for (key, value) in entries {
+                    self.push_key(&key_text(key)?, key.span);
+                    self.generic(value)?;
+                }
+                self.push(Outcome::ObjectEnded, Self::end(node));
+            }
+        }
+        Ok(())
+    }
+
+    /// What's in the brackets of a variant whose fields aren't known: its only value, or
+    /// its values, or its fields.
+    fn generic_content(&mut self, content: &Node) -> LoweringResult<'shape> {
+        match &content.kind {
+            NodeKind::Tuple(_, items) if items.len() == 1 => self.generic(&items[0]),
+            _ => self.lower_generic(content),
+        }
+    }
+}
+
+/// Checks that a struct is written with its own name, if it's written with one.
+fn check_name<'shape>(name: Name, shape: &'shape Shape<'shape>) -> LoweringResult<'shape> {
+    let expected = struct_name(shape);
+    if name.text == expected {
+        Ok(())
+    } else {
+        Err(
+            DeserErrorKind::InvalidSyntax(format!("expected `{expected}`, found `{}`", name.text))
+                .with_span(name.span),
+        )
+    }
+}
+
+/// A map key, as the text the deserializer takes keys as.
+fn key_text(key: &Node) -> Result<String, Spanned<DeserErrorKind<'static>>> {
+    let text = match &key.kind {
+        NodeKind::Str(string) => string.clone(),
+        NodeKind::Char(c) => c.to_string(),
+        NodeKind::Bool(b) => b.to_string(),
+        NodeKind::Ident(name) => name.text.to_owned(),
+        NodeKind::Number(Scalar::U64(v)) => v.to_string(),
+        NodeKind::Number(Scalar::I64(v)) => v.to_string(),
+        NodeKind::Number(Scalar::F64(v)) => v.to_string(),
+        _ => {
+            return Err(DeserErrorKind::InvalidSyntax(
+                "map keys must be strings, characters, numbers, booleans or names".to_owned(),
+            )
+            .with_span(key.span));
+        }
+    };
+    Ok(text)
+}
+
+/// The type of the items of the list `shape`.
+fn item_shape<'shape>(shape: &'shape Shape<'shape>) -> Option<&'shape Shape<'shape>> {
+    match shape.def {
+        Def::List(list_def) => Some(list_def.t()),
+        Def::Set(set_def) => Some(set_def.t()),
+        Def::Array(array_def) => Some(array_def.t()),
+        _ => None,
+    }
+}
+
+/// The field named `name`, looking into flattened structs too.
+fn find_field<'shape>(
+    fields: &'shape [Field<'shape>],
+    name: &str,
+) -> Option<&'shape Field<'shape>> {
+    fields
+        .iter()
+        .find(|field| field.is_named(name) && !field.should_skip_deserializing())
+        .or_else(|| {
+            fields
+                .iter()
+                .filter(|field| field.flags.contains(FieldFlags::FLATTEN))
+                .find_map(|field| match field.shape().ty {
+                    Type::User(UserType::Struct(struct_type)) => {
+                        find_field(struct_type.fields, name)
+                    }
+                    _ => None,
+                })
+        })
+}
+
+/// The fields that aren't in a struct, looking into flattened structs too.
+fn absent_fields<'shape>(
+    fields: &'shape [Field<'shape>],
+    present: &[&str],
+) -> Vec<&'shape Field<'shape>> {
+    let mut absent = Vec::new();
+    for field in fields {
+        if field.flags.contains(FieldFlags::FLATTEN) {
+            if let Type::User(UserType::Struct(struct_type)) = field.shape().ty {
+                absent.extend(absent_fields(struct_type.fields, present));
+            }
+        } else if !present.contains(&field.name) && !field.should_skip_deserializing() {
+            absent.push(field);
+        }
+    }
+    absent
+}
@@ -0,0 +1,34 @@
+#![warn(missing_docs)]
+#![forbid(unsafe_code)]
+#![doc = include_str!("../README.md")]
+
+pub use facet_deserialize::{DeserError, DeserErrorKind, DeserErrorMessage};
+
+use facet_core::Shape;
+
+mod options;
+pub use options::*;
+
+mod parse;
+
+mod serialize;
+pub use serialize::*;
+
+mod deserialize;
+pub use deserialize::*;
+
+/// How deep values may be nested in a document. Reading one nested deeper is an error, rather
+/// than a stack overflow.
+const MAX_DEPTH: usize = 128;
+
+/// The name a struct of type `shape` is written with: the name of the type, without its
+/// generic parameters.
+fn struct_name(shape: &Shape) -> String {
+    let type_name = shape.to_string();
+    type_name
+        .split('<')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_owned()
+}
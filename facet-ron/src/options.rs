@@ -0,0 +1,65 @@
+/// How to write RON: on one line or laid out over several, and whether structs are written
+/// with their names.
+///
+/// ```rust
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// let ron = facet_ron::RonOptions::new()
+///     .pretty(true)
+///     .indent("  ")
+///     .struct_names(false)
+///     .to_string(&Point { x: 1, y: -2 })
+///     .unwrap();
+/// assert_eq!(ron, "(\n  x: 1,\n  y: -2,\n)");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RonOptions {
+    pub(crate) pretty: bool,
+    pub(crate) indent: &'static str,
+    pub(crate) struct_names: bool,
+}
+
+impl Default for RonOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RonOptions {
+    /// Everything on one line, with structs written with their names, like
+    /// `Point(x: 1, y: -2)`.
+    pub fn new() -> Self {
+        Self {
+            pretty: false,
+            indent: "    ",
+            struct_names: true,
+        }
+    }
+
+    /// Sets whether each field, item and entry goes on a line of its own, followed by a
+    /// comma, rather than everything on one line.
+    pub fn pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+
+    /// Sets what nested lines are indented with, per level, when writing pretty RON. Four
+    /// spaces by default.
+    pub fn indent(mut self, indent: &'static str) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Sets whether structs are written with their names, like `Point(x: 1)`, rather than
+    /// as `(x: 1)`. Enum variants always are.
+    pub fn struct_names(mut self, struct_names: bool) -> Self {
+        self.struct_names = struct_names;
+        self
+    }
+}
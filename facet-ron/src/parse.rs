@@ -0,0 +1,558 @@
+//! Parse RON documents into a tree of values, with where each of them is in the input.
+
+use facet_deserialize::{DeserErrorKind, Scalar, Span, Spannable, Spanned};
+
+use crate::MAX_DEPTH;
+
+pub(crate) type ParseResult<T> = Result<T, Spanned<DeserErrorKind<'static>>>;
+
+/// A value in a document.
+#[derive(Debug)]
+pub(crate) struct Node<'a> {
+    pub(crate) kind: NodeKind<'a>,
+    /// Where the whole value is
+    pub(crate) span: Span,
+    /// Where the value starts: for structs, tuples, lists and maps, its name and opening
+    /// bracket, and otherwise the whole value
+    pub(crate) open: Span,
+}
+
+#[derive(Debug)]
+pub(crate) enum NodeKind<'a> {
+    /// An integer or a float
+    Number(Scalar<'static>),
+    Str(String),
+    Char(char),
+    Bool(bool),
+    /// `[a, b]`, or a byte string
+    List(Vec<Node<'a>>),
+    /// `{k: v}`
+    Map(Vec<(Node<'a>, Node<'a>)>),
+    /// `(a, b)` or `Name(a, b)`, and `()` for the unit value
+    Tuple(Option<Name<'a>>, Vec<Node<'a>>),
+    /// `(f: a)` or `Name(f: a)`
+    Struct(Option<Name<'a>>, Vec<(Name<'a>, Node<'a>)>),
+    /// A bare name, like a unit struct or a unit variant
+    Ident(Name<'a>),
+}
+
+/// The name of a struct, a variant or a field.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Name<'a> {
+    pub(crate) text: &'a str,
+    pub(crate) span: Span,
+}
+
+/// Parses a whole document into the value it holds.
+pub(crate) fn parse(ron: &str) -> ParseResult<Node<'_>> {
+    let mut parser = Parser {
+        ron,
+        pos: 0,
+        depth: 0,
+    };
+    parser.extensions()?;
+    let node = parser.value()?;
+    parser.skip_whitespace()?;
+    if let Some(c) = parser.peek() {
+        return Err(DeserErrorKind::UnexpectedChar {
+            got: c,
+            wanted: "end of input",
+        }
+        .with_span(Span::new(parser.pos, c.len_utf8())));
+    }
+    Ok(node)
+}
+
+struct Parser<'a> {
+    ron: &'a str,
+    pos: usize,
+    /// How many values deep the one being parsed is
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.ron[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn span_from(&self, start: usize) -> Span {
+        Span::new(start, self.pos - start)
+    }
+
+    fn unexpected(&self, wanted: &'static str) -> Spanned<DeserErrorKind<'static>> {
+        match self.peek() {
+            Some(c) => DeserErrorKind::UnexpectedChar { got: c, wanted }
+                .with_span(Span::new(self.pos, c.len_utf8())),
+            None => DeserErrorKind::UnexpectedEof { wanted }.with_span(Span::new(self.pos, 0)),
+        }
+    }
+
+    fn expect(&mut self, c: char, wanted: &'static str) -> ParseResult<()> {
+        self.skip_whitespace()?;
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            Ok(())
+        } else {
+            Err(self.unexpected(wanted))
+        }
+    }
+
+    /// Skips whitespace and comments, `// line` ones and `/* block */` ones, which nest.
+    fn skip_whitespace(&mut self) -> ParseResult<()> {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if trimmed.starts_with("/*") {
+                let start = self.pos;
+                self.pos += 2;
+                let mut depth = 1;
+                while depth > 0 {
+                    let rest = self.rest();
+                    if rest.starts_with("/*") {
+                        depth += 1;
+                        self.pos += 2;
+                    } else if rest.starts_with("*/") {
+                        depth -= 1;
+                        self.pos += 2;
+                    } else if self.bump().is_none() {
+                        return Err(DeserErrorKind::UnexpectedEof {
+                            wanted: "end of the comment",
+                        }
+                        .with_span(self.span_from(start)));
+                    }
+                }
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Skips the `#![enable(...)]` attributes at the start of a document. Implicit `Some`
+    /// is always enabled, and there's nothing else they change for reading.
+    fn extensions(&mut self) -> ParseResult<()> {
+        loop {
+            self.skip_whitespace()?;
+            if !self.rest().starts_with("#!") {
+                return Ok(());
+            }
+            self.pos += 2;
+            self.expect('[', "`[` of an attribute")?;
+            match self.rest().find(']') {
+                Some(end) => self.pos += end + 1,
+                None => {
+                    self.pos = self.ron.len();
+                    return Err(self.unexpected("`]` closing the attribute"));
+                }
+            }
+        }
+    }
+
+    fn value(&mut self) -> ParseResult<Node<'a>> {
+        self.skip_whitespace()?;
+        if self.depth == MAX_DEPTH {
+            let len = self.peek().map_or(0, char::len_utf8);
+            return Err(too_deep().with_span(Span::new(self.pos, len)));
+        }
+        self.depth += 1;
+        let node = self.node();
+        self.depth -= 1;
+        node
+    }
+
+    fn node(&mut self) -> ParseResult<Node<'a>> {
+        let start = self.pos;
+        let rest = self.rest();
+        let Some(c) = self.peek() else {
+            return Err(self.unexpected("a value"));
+        };
+        let kind = match c {
+            '(' => return self.parenthesized(None, start),
+            '[' => return self.list(start),
+            '{' => return self.map(start),
+            '"' => NodeKind::Str(self.string()?),
+            '\'' => NodeKind::Char(self.char()?),
+            'r' if rest[1..].starts_with(['"', '#']) && !is_raw_ident(rest) => {
+                self.pos += 1;
+                NodeKind::Str(self.raw_string()?)
+            }
+            'b' if rest[1..].starts_with('"') => {
+                self.pos += 1;
+                let string_start = self.pos;
+                let bytes = self.string()?;
+                let items = bytes
+                    .chars()
+                    .map(|c| {
+                        let span = self.span_from(string_start);
+                        Node {
+                            kind: NodeKind::Number(Scalar::U64(c as u64)),
+                            span,
+                            open: span,
+                        }
+                    })
+                    .collect();
+                NodeKind::List(items)
+            }
+            '0'..='9' | '+' | '-' | '.' => NodeKind::Number(self.number()?),
+            c if is_ident_start(c) => {
+                let name = self.ident()?;
+                match name.text {
+                    "true" => NodeKind::Bool(true),
+                    "false" => NodeKind::Bool(false),
+                    "inf" => NodeKind::Number(Scalar::F64(f64::INFINITY)),
+                    "NaN" => NodeKind::Number(Scalar::F64(f64::NAN)),
+                    _ => {
+                        let end = self.pos;
+                        self.skip_whitespace()?;
+                        if self.peek() == Some('(') {
+                            return self.parenthesized(Some(name), start);
+                        }
+                        self.pos = end;
+                        NodeKind::Ident(name)
+                    }
+                }
+            }
+            _ => return Err(self.unexpected("a value")),
+        };
+        let span = self.span_from(start);
+        Ok(Node {
+            kind,
+            span,
+            open: span,
+        })
+    }
+
+    /// A struct or a tuple, after its name if it has one: `(f: a)` or `(a, b)`.
+    fn parenthesized(&mut self, name: Option<Name<'a>>, start: usize) -> ParseResult<Node<'a>> {
+        self.expect('(', "`(`")?;
+        let open = self.span_from(start);
+        let kind = if self.at_field()? {
+            let mut fields = Vec::new();
+            while self.at_field()? {
+                let field = self.ident()?;
+                self.expect(':', "`:` after a field name")?;
+                fields.push((field, self.value()?));
+                if !self.comma()? {
+                    break;
+                }
+            }
+            NodeKind::Struct(name, fields)
+        } else {
+            let items = self.items(')')?;
+            NodeKind::Tuple(name, items)
+        };
+        self.expect(')', "`)`")?;
+        Ok(Node {
+            kind,
+            span: self.span_from(start),
+            open,
+        })
+    }
+
+    /// Whether a field comes next, which is a name followed by a `:`.
+    fn at_field(&mut self) -> ParseResult<bool> {
+        self.skip_whitespace()?;
+        if !self.peek().is_some_and(is_ident_start) {
+            return Ok(false);
+        }
+        let pos = self.pos;
+        let is_field = self.ident().is_ok() && {
+            self.skip_whitespace()?;
+            self.rest().starts_with(':') && !self.rest().starts_with("::")
+        };
+        self.pos = pos;
+        Ok(is_field)
+    }
+
+    fn list(&mut self, start: usize) -> ParseResult<Node<'a>> {
+        self.pos += 1;
+        let open = self.span_from(start);
+        let items = self.items(']')?;
+        self.expect(']', "`]`")?;
+        Ok(Node {
+            kind: NodeKind::List(items),
+            span: self.span_from(start),
+            open,
+        })
+    }
+
+    fn map(&mut self, start: usize) -> ParseResult<Node<'a>> {
+        self.pos += 1;
+        let open = self.span_from(start);
+        let mut entries = Vec::new();
+        loop {
+            self.skip_whitespace()?;
+            if self.peek() == Some('}') {
+                break;
+            }
+            let key = self.value()?;
+            self.expect(':', "`:` after a map key")?;
+            entries.push((key, self.value()?));
+            if !self.comma()? {
+                break;
+            }
+        }
+        self.expect('}', "`}`")?;
+        Ok(Node {
+            kind: NodeKind::Map(entries),
+            span: self.span_from(start),
+            open,
+        })
+    }
+
+    /// Values separated by commas, up to the `close` bracket, which is left to the caller.
+    fn items(&mut self, close: char) -> ParseResult<Vec<Node<'a>>> {
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace()?;
+            if self.peek() == Some(close) {
+                return Ok(items);
+            }
+            items.push(self.value()?);
+            if !self.comma()? {
+                return Ok(items);
+            }
+        }
+    }
+
+    /// Skips the comma after a value, if there's one.
+    fn comma(&mut self) -> ParseResult<bool> {
+        self.skip_whitespace()?;
+        if self.peek() == Some(',') {
+            self.pos += 1;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// A name, or a raw one like `r#type`, which is taken without its `r#`.
+    fn ident(&mut self) -> ParseResult<Name<'a>> {
+        self.skip_whitespace()?;
+        if is_raw_ident(self.rest()) {
+            self.pos += 2;
+        }
+        let start = self.pos;
+        if !self.peek().is_some_and(is_ident_start) {
+            return Err(self.unexpected("a name"));
+        }
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !is_ident_continue(c))
+            .unwrap_or(rest.len());
+        self.pos += len;
+        Ok(Name {
+            text: &rest[..len],
+            span: self.span_from(start),
+        })
+    }
+
+    fn number(&mut self) -> ParseResult<Scalar<'static>> {
+        let start = self.pos;
+        let rest = self.rest();
+        let mut len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '+' | '-')))
+            .unwrap_or(rest.len());
+        // Past the first character, a sign is only part of an exponent, like in `1e-3`
+        for (index, c) in rest[..len].char_indices().skip(1) {
+            if matches!(c, '+' | '-') && (!rest[..index].ends_with(['e', 'E']) || is_hex(rest)) {
+                len = index;
+                break;
+            }
+        }
+        self.pos += len;
+        let text: String = rest[..len].chars().filter(|&c| c != '_').collect();
+        let span = self.span_from(start);
+        let invalid = || DeserErrorKind::InvalidSyntax(format!("invalid number `{text}`"));
+
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, text.strip_prefix('+').unwrap_or(&text)),
+        };
+        let radix = match digits.get(..2) {
+            Some("0x") => Some(16),
+            Some("0o") => Some(8),
+            Some("0b") => Some(2),
+            _ => None,
+        };
+        let integer = match radix {
+            Some(radix) => Some(
+                u128::from_str_radix(&digits[2..], radix).map_err(|_| invalid().with_span(span))?,
+            ),
+            None => digits.parse::<u128>().ok(),
+        };
+        if let Some(integer) = integer {
+            let scalar = match (negative, u64::try_from(integer), i64::try_from(integer)) {
+                (false, Ok(v), _) => Scalar::U64(v),
+                (true, _, Ok(v)) => Scalar::I64(-v),
+                (true, _, _) if integer == 1 << 63 => Scalar::I64(i64::MIN),
+                _ => {
+                    let value = if negative {
+                        -(integer as f64)
+                    } else {
+                        integer as f64
+                    };
+                    return Err(DeserErrorKind::NumberOutOfRange(value).with_span(span));
+                }
+            };
+            return Ok(scalar);
+        }
+        let value = match digits {
+            "inf" => f64::INFINITY,
+            "NaN" => f64::NAN,
+            _ if digits.starts_with(|c: char| c.is_ascii_digit() || c == '.') => digits
+                .parse::<f64>()
+                .map_err(|_| invalid().with_span(span))?,
+            _ => return Err(invalid().with_span(span)),
+        };
+        Ok(Scalar::F64(if negative { -value } else { value }))
+    }
+
+    fn string(&mut self) -> ParseResult<String> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut string = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(string),
+                Some('\\') => string.push(self.escape()?),
+                Some(c) => string.push(c),
+                None => {
+                    return Err(DeserErrorKind::UnexpectedEof {
+                        wanted: "`\"` closing the string",
+                    }
+                    .with_span(self.span_from(start)));
+                }
+            }
+        }
+    }
+
+    /// A raw string like `r#"..."#`, after its `r`.
+    fn raw_string(&mut self) -> ParseResult<String> {
+        let start = self.pos - 1;
+        let hashes = self.rest().len() - self.rest().trim_start_matches('#').len();
+        self.pos += hashes;
+        if self.peek() != Some('"') {
+            return Err(self.unexpected("`\"` opening the raw string"));
+        }
+        self.pos += 1;
+        let closing = format!("\"{}", "#".repeat(hashes));
+        match self.rest().find(&closing) {
+            Some(len) => {
+                let string = self.rest()[..len].to_owned();
+                self.pos += len + closing.len();
+                Ok(string)
+            }
+            None => {
+                self.pos = self.ron.len();
+                Err(DeserErrorKind::UnexpectedEof {
+                    wanted: "`\"` closing the raw string",
+                }
+                .with_span(self.span_from(start)))
+            }
+        }
+    }
+
+    fn char(&mut self) -> ParseResult<char> {
+        let start = self.pos;
+        self.pos += 1;
+        let c = match self.bump() {
+            Some('\\') => self.escape()?,
+            Some(c) => c,
+            None => return Err(self.unexpected("a character")),
+        };
+        if self.peek() != Some('\'') {
+            return Err(DeserErrorKind::InvalidSyntax(
+                "a character literal holds a single character".to_owned(),
+            )
+            .with_span(self.span_from(start)));
+        }
+        self.pos += 1;
+        Ok(c)
+    }
+
+    /// The character an escape sequence stands for, after its `\`.
+    fn escape(&mut self) -> ParseResult<char> {
+        let start = self.pos - 1;
+        let c = match self.bump() {
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('0') => '\0',
+            Some(c @ ('\\' | '"' | '\'')) => c,
+            Some('x') => {
+                let digits = self.rest().get(..2).unwrap_or_default();
+                self.pos += digits.len();
+                u8::from_str_radix(digits, 16)
+                    .ok()
+                    .map(char::from)
+                    .ok_or_else(|| {
+                        DeserErrorKind::InvalidSyntax(format!("invalid escape `\\x{digits}`"))
+                            .with_span(self.span_from(start))
+                    })?
+            }
+            Some('u') => {
+                let rest = self.rest();
+                let code = rest
+                    .strip_prefix('{')
+                    .and_then(|rest| rest.split_once('}'))
+                    .map(|(digits, _)| digits);
+                let Some(digits) = code else {
+                    return Err(self.unexpected("`{` of a unicode escape"));
+                };
+                self.pos += digits.len() + 2;
+                u32::from_str_radix(digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| {
+                        DeserErrorKind::InvalidSyntax(format!("invalid escape `\\u{{{digits}}}`"))
+                            .with_span(self.span_from(start))
+                    })?
+            }
+            Some(_) => {
+                return Err(
+                    DeserErrorKind::InvalidSyntax("unknown escape sequence".to_owned())
+                        .with_span(self.span_from(start)),
+                );
+            }
+            None => return Err(self.unexpected("an escape sequence")),
+        };
+        Ok(c)
+    }
+}
+
+/// The error for a value nested deeper than values may be.
+pub(crate) fn too_deep() -> DeserErrorKind<'static> {
+    DeserErrorKind::InvalidSyntax(format!("values are nested more than {MAX_DEPTH} deep"))
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+fn is_ident_continue(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Whether `rest` starts with a raw name, like `r#type`.
+fn is_raw_ident(rest: &str) -> bool {
+    rest.strip_prefix("r#")
+        .is_some_and(|rest| rest.starts_with(is_ident_start))
+}
+
+/// Whether the number `rest` starts with is hexadecimal, where `e` is a digit.
+fn is_hex(rest: &str) -> bool {
+    rest.trim_start_matches(['+', '-']).starts_with("0x")
+}
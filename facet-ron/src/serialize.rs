@@ -0,0 +1,439 @@
+//! Create RON text from Rust values.
+
+use std::{
+    borrow::Cow,
+    error::Error,
+    fmt::{self, Display, Write},
+};
+
+use facet_core::{Def, Facet, SequenceType, StructKind, Type, UserType};
+use facet_reflect::{HasFields, Peek, ScalarType};
+
+use crate::{RonOptions, struct_name};
+
+/// Error type for RON serialization.
+#[derive(Debug)]
+pub enum RonSerError {
+    /// A type that has no RON representation, like a smart pointer or a function
+    UnsupportedType(String),
+}
+
+impl Display for RonSerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RonSerError::UnsupportedType(shape) => write!(f, "Unsupported type: {shape}"),
+        }
+    }
+}
+impl Error for RonSerError {}
+
+/// Serialize a value of type `T` to RON, on one line and with struct names.
+///
+/// # Example
+/// ```rust
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// #[repr(u8)]
+/// #[allow(dead_code)]
+/// enum Shape {
+///     Circle { radius: f32 },
+///     Square(f32),
+/// }
+///
+/// #[derive(Facet)]
+/// struct Sprite {
+///     name: String,
+///     shape: Shape,
+///     tint: Option<(u8, u8, u8)>,
+/// }
+///
+/// let sprite = Sprite {
+///     name: "coin".into(),
+///     shape: Shape::Circle { radius: 0.5 },
+///     tint: Some((255, 200, 0)),
+/// };
+/// assert_eq!(
+///     facet_ron::to_string(&sprite).unwrap(),
+///     r#"Sprite(name: "coin", shape: Circle(radius: 0.5), tint: Some((255, 200, 0)))"#
+/// );
+/// ```
+pub fn to_string<'a, T: Facet<'a>>(value: &'a T) -> Result<String, RonSerError> {
+    RonOptions::new().to_string(value)
+}
+
+/// Serialize a value of type `T` to RON, with each field, item and entry on a line of its
+/// own.
+pub fn to_string_pretty<'a, T: Facet<'a>>(value: &'a T) -> Result<String, RonSerError> {
+    RonOptions::new().pretty(true).to_string(value)
+}
+
+/// Serialize a `Peek` to RON, on one line and with struct names.
+pub fn peek_to_string<'mem, 'facet, 'shape>(
+    peek: Peek<'mem, 'facet, 'shape>,
+) -> Result<String, RonSerError>
+where
+    'mem: 'facet,
+{
+    RonOptions::new().peek_to_string(peek)
+}
+
+impl RonOptions {
+    /// Serialize a value of type `T` to RON.
+    pub fn to_string<'a, T: Facet<'a>>(&self, value: &'a T) -> Result<String, RonSerError> {
+        self.peek_to_string(Peek::new(value))
+    }
+
+    /// Serialize a `Peek` to RON.
+    pub fn peek_to_string<'mem, 'facet, 'shape>(
+        &self,
+        peek: Peek<'mem, 'facet, 'shape>,
+    ) -> Result<String, RonSerError>
+    where
+        'mem: 'facet,
+    {
+        let mut writer = RonWriter {
+            out: String::new(),
+            options: self,
+            depth: 0,
+        };
+        writer.serialize(peek)?;
+        Ok(writer.out)
+    }
+}
+
+/// What's left to write of the values being serialized, last first.
+enum Task<'mem, 'facet, 'shape> {
+    Value(Peek<'mem, 'facet, 'shape>),
+    /// What goes before a field, item or entry: a comma after the one before it, or a line
+    /// break when writing pretty RON
+    Separator {
+        first: bool,
+    },
+    /// The name of a field, and its colon
+    Name(&'shape str),
+    /// The colon between the key and the value of an entry
+    Colon,
+    /// The bracket closing a struct, tuple, list or map that isn't empty
+    Close(char),
+    /// The bracket closing a newtype, which is on the same line as its content
+    CloseNewtype,
+}
+
+struct RonWriter<'a> {
+    out: String,
+    options: &'a RonOptions,
+    /// How many brackets are open
+    depth: usize,
+}
+
+impl RonWriter<'_> {
+    /// Writes a value, walking its fields, items and entries with a stack rather than by
+    /// recursing.
+    fn serialize<'mem, 'facet, 'shape>(
+        &mut self,
+        peek: Peek<'mem, 'facet, 'shape>,
+    ) -> Result<(), RonSerError>
+    where
+        'mem: 'facet,
+    {
+        let mut stack = vec![Task::Value(peek)];
+        while let Some(task) = stack.pop() {
+            let peek = match task {
+                Task::Value(peek) => peek,
+                Task::Separator { first } => {
+                    self.separator(first);
+                    continue;
+                }
+                Task::Name(name) => {
+                    self.out.push_str(name);
+                    self.out.push_str(": ");
+                    continue;
+                }
+                Task::Colon => {
+                    self.out.push_str(": ");
+                    continue;
+                }
+                Task::Close(bracket) => {
+                    self.depth -= 1;
+                    if self.options.pretty {
+                        self.out.push(',');
+                        self.newline();
+                    }
+                    self.out.push(bracket);
+                    continue;
+                }
+                Task::CloseNewtype => {
+                    self.out.push(')');
+                    continue;
+                }
+            };
+
+            // Transparent wrappers are written as what they wrap. Options have an inner
+            // shape too, but they're written as `Some(...)`
+            let is_option = matches!(peek.shape().def, Def::Option(_));
+            let peek = if peek.shape().inner.is_some() && !is_option {
+                peek.innermost_peek()
+            } else {
+                peek
+            };
+            let shape = peek.shape();
+            match (shape.def, shape.ty) {
+                (Def::Scalar(_), _) => self.scalar(peek)?,
+                (Def::List(_) | Def::Slice(_) | Def::Array(_), _) => {
+                    let items: Vec<_> = peek.into_list_like().unwrap().iter().collect();
+                    self.open('[', None, items.len(), &mut stack);
+                    push_items(&mut stack, items);
+                }
+                (Def::Set(_), _) => {
+                    let items: Vec<_> = peek.into_set().unwrap().iter().collect();
+                    self.open('[', None, items.len(), &mut stack);
+                    push_items(&mut stack, items);
+                }
+                (Def::Map(_), _) => {
+                    let entries: Vec<_> = peek.into_map().unwrap().iter().collect();
+                    self.open('{', None, entries.len(), &mut stack);
+                    for (index, (key, value)) in entries.into_iter().enumerate().rev() {
+                        stack.push(Task::Value(value));
+                        stack.push(Task::Colon);
+                        stack.push(Task::Value(key));
+                        stack.push(Task::Separator { first: index == 0 });
+                    }
+                }
+                (Def::Option(_), _) => match peek.into_option().unwrap().value() {
+                    Some(inner) => self.newtype("Some", inner, &mut stack),
+                    None => self.out.push_str("None"),
+                },
+                (_, Type::User(UserType::Struct(struct_type))) => {
+                    let name = struct_name(shape);
+                    let name = Some(name.as_str()).filter(|_| self.options.struct_names);
+                    let peek_struct = peek.into_struct().unwrap();
+                    match struct_type.kind {
+                        StructKind::Unit => self.out.push_str(name.unwrap_or("()")),
+                        StructKind::TupleStruct if struct_type.fields.len() == 1 => {
+                            let (_, item) = peek_struct.fields().next().unwrap();
+                            self.newtype(name.unwrap_or_default(), item, &mut stack);
+                        }
+                        StructKind::Tuple | StructKind::TupleStruct => {
+                            let items: Vec<_> =
+                                peek_struct.fields().map(|(_, field)| field).collect();
+                            self.open('(', name, items.len(), &mut stack);
+                            push_items(&mut stack, items);
+                        }
+                        _ => {
+                            let fields: Vec<_> = peek_struct
+                                .fields_for_serialize()
+                                .map(|(field, value)| (field.name, value))
+                                .collect();
+                            self.open('(', name, fields.len(), &mut stack);
+                            push_fields(&mut stack, fields);
+                        }
+                    }
+                }
+                (_, Type::User(UserType::Enum(_))) => {
+                    let peek_enum = peek.into_enum().unwrap();
+                    let variant = peek_enum
+                        .active_variant()
+                        .map_err(|_| unsupported(shape.to_string()))?;
+                    let name = Some(variant.name);
+                    match variant.data.kind {
+                        StructKind::Unit => self.out.push_str(variant.name),
+                        StructKind::Tuple | StructKind::TupleStruct
+                            if variant.data.fields.len() == 1 =>
+                        {
+                            let (_, item) = peek_enum.fields().next().unwrap();
+                            self.newtype(variant.name, item, &mut stack);
+                        }
+                        StructKind::Tuple | StructKind::TupleStruct => {
+                            let items: Vec<_> =
+                                peek_enum.fields().map(|(_, field)| field).collect();
+                            self.open('(', name, items.len(), &mut stack);
+                            push_items(&mut stack, items);
+                        }
+                        _ => {
+                            let fields: Vec<_> = peek_enum
+                                .fields_for_serialize()
+                                .map(|(field, value)| (field.name, value))
+                                .collect();
+                            self.open('(', name, fields.len(), &mut stack);
+                            push_fields(&mut stack, fields);
+                        }
+                    }
+                }
+                (_, Type::Sequence(SequenceType::Tuple(_))) => {
+                    let items: Vec<_> = peek
+                        .into_tuple()
+                        .unwrap()
+                        .fields()
+                        .map(|(_, field)| field)
+                        .collect();
+                    self.open('(', None, items.len(), &mut stack);
+                    push_items(&mut stack, items);
+                }
+                (_, Type::Pointer(_)) => match peek.as_str() {
+                    Some(value) => write_string(&mut self.out, value),
+                    None => return Err(unsupported(shape.to_string())),
+                },
+                _ => return Err(unsupported(shape.to_string())),
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the name and opening bracket of a struct, tuple, list or map, or the whole of
+    /// it if it's empty. Otherwise, what closes it goes on the stack, for once its content
+    /// is written.
+    fn open<'mem, 'facet, 'shape>(
+        &mut self,
+        bracket: char,
+        name: Option<&str>,
+        len: usize,
+        stack: &mut Vec<Task<'mem, 'facet, 'shape>>,
+    ) {
+        if let Some(name) = name {
+            self.out.push_str(name);
+        }
+        self.out.push(bracket);
+        let close = match bracket {
+            '[' => ']',
+            '{' => '}',
+            _ => ')',
+        };
+        if len == 0 {
+            self.out.push(close);
+        } else {
+            self.depth += 1;
+            stack.push(Task::Close(close));
+        }
+    }
+
+    /// Writes the name and opening bracket of `Some(...)`, a newtype struct or a newtype
+    /// variant, whose content stays on the same line even in pretty RON.
+    fn newtype<'mem, 'facet, 'shape>(
+        &mut self,
+        name: &str,
+        content: Peek<'mem, 'facet, 'shape>,
+        stack: &mut Vec<Task<'mem, 'facet, 'shape>>,
+    ) {
+        self.out.push_str(name);
+        self.out.push('(');
+        stack.push(Task::CloseNewtype);
+        stack.push(Task::Value(content));
+    }
+
+    fn separator(&mut self, first: bool) {
+        if self.options.pretty {
+            if !first {
+                self.out.push(',');
+            }
+            self.newline();
+        } else if !first {
+            self.out.push_str(", ");
+        }
+    }
+
+    fn newline(&mut self) {
+        self.out.push('\n');
+        for _ in 0..self.depth {
+            self.out.push_str(self.options.indent);
+        }
+    }
+
+    fn scalar(&mut self, peek: Peek<'_, '_, '_>) -> Result<(), RonSerError> {
+        let out = &mut self.out;
+        match peek.scalar_type() {
+            Some(ScalarType::Unit) => out.push_str("()"),
+            Some(ScalarType::Bool) => write_display(out, peek.get::<bool>().unwrap()),
+            Some(ScalarType::Char) => write_char(out, *peek.get::<char>().unwrap()),
+            Some(ScalarType::Str) => write_string(out, peek.get::<&str>().unwrap()),
+            Some(ScalarType::String) => write_string(out, peek.get::<String>().unwrap()),
+            Some(ScalarType::CowStr) => write_string(out, peek.get::<Cow<'_, str>>().unwrap()),
+            // `Debug` keeps the decimal point of whole numbers, so they're read back as floats
+            Some(ScalarType::F32) => write_float(out, peek.get::<f32>().unwrap()),
+            Some(ScalarType::F64) => write_float(out, peek.get::<f64>().unwrap()),
+            Some(ScalarType::U8) => write_display(out, peek.get::<u8>().unwrap()),
+            Some(ScalarType::U16) => write_display(out, peek.get::<u16>().unwrap()),
+            Some(ScalarType::U32) => write_display(out, peek.get::<u32>().unwrap()),
+            Some(ScalarType::U64) => write_display(out, peek.get::<u64>().unwrap()),
+            Some(ScalarType::U128) => write_display(out, peek.get::<u128>().unwrap()),
+            Some(ScalarType::USize) => write_display(out, peek.get::<usize>().unwrap()),
+            Some(ScalarType::I8) => write_display(out, peek.get::<i8>().unwrap()),
+            Some(ScalarType::I16) => write_display(out, peek.get::<i16>().unwrap()),
+            Some(ScalarType::I32) => write_display(out, peek.get::<i32>().unwrap()),
+            Some(ScalarType::I64) => write_display(out, peek.get::<i64>().unwrap()),
+            Some(ScalarType::I128) => write_display(out, peek.get::<i128>().unwrap()),
+            Some(ScalarType::ISize) => write_display(out, peek.get::<isize>().unwrap()),
+            // Addresses, dates, UUIDs and the like are their text
+            _ if peek.shape().vtable.display.is_some() => write_string(out, &peek.to_string()),
+            _ => return Err(unsupported(peek.shape().to_string())),
+        }
+        Ok(())
+    }
+}
+
+fn push_items<'mem, 'facet, 'shape>(
+    stack: &mut Vec<Task<'mem, 'facet, 'shape>>,
+    items: Vec<Peek<'mem, 'facet, 'shape>>,
+) {
+    for (index, item) in items.into_iter().enumerate().rev() {
+        stack.push(Task::Value(item));
+        stack.push(Task::Separator { first: index == 0 });
+    }
+}
+
+fn push_fields<'mem, 'facet, 'shape>(
+    stack: &mut Vec<Task<'mem, 'facet, 'shape>>,
+    fields: Vec<(&'shape str, Peek<'mem, 'facet, 'shape>)>,
+) {
+    for (index, (name, value)) in fields.into_iter().enumerate().rev() {
+        stack.push(Task::Value(value));
+        stack.push(Task::Name(name));
+        stack.push(Task::Separator { first: index == 0 });
+    }
+}
+
+fn unsupported(shape: String) -> RonSerError {
+    RonSerError::UnsupportedType(shape)
+}
+
+fn write_display(out: &mut String, value: impl Display) {
+    let _ = write!(out, "{value}");
+}
+
+fn write_float(out: &mut String, value: impl fmt::Debug) {
+    let _ = write!(out, "{value:?}");
+}
+
+fn write_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            c => write_escaped(out, c),
+        }
+    }
+    out.push('"');
+}
+
+fn write_char(out: &mut String, value: char) {
+    out.push('\'');
+    match value {
+        '\'' => out.push_str("\\'"),
+        c => write_escaped(out, c),
+    }
+    out.push('\'');
+}
+
+fn write_escaped(out: &mut String, c: char) {
+    match c {
+        '\\' => out.push_str("\\\\"),
+        '\n' => out.push_str("\\n"),
+        '\r' => out.push_str("\\r"),
+        '\t' => out.push_str("\\t"),
+        '\0' => out.push_str("\\0"),
+        c if c.is_control() => {
+            let _ = write!(out, "\\u{{{:x}}}", c as u32);
+        }
+        c => out.push(c),
+    }
+}
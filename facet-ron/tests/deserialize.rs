@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+
+use facet::Facet;
+use facet_ron::{DeserErrorKind, RonOptions, from_str, to_string, to_string_pretty};
+use facet_testhelpers::test;
+
+#[derive(Facet, Debug, PartialEq)]
+struct Asset {
+    name: String,
+    size: (u32, u32),
+    frames: Vec<Frame>,
+    pivot: Option<Point>,
+    tags: HashMap<String, String>,
+    animation: Animation,
+    #[facet(default)]
+    hidden: bool,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Frame(u16, f32);
+
+#[derive(Facet, Debug, PartialEq)]
+struct Point {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[repr(u8)]
+enum Animation {
+    Still,
+    Loop(f32),
+    PingPong { speed: f32, pause: Option<f32> },
+}
+
+#[test]
+fn test_hand_written_asset() {
+    let ron = r##"
+        #![enable(implicit_some)]
+        /* The player sprite,
+           /* exported by hand */ */
+        Asset(
+            name: "player", // as shown in the editor
+            size: (32, 48),
+            frames: [
+                Frame(0, 0.1),
+                (1, 0.15),
+            ],
+            pivot: (x: 16, y: 40.5),
+            tags: {"layer": "actors", r#"say "hi""#: "x"},
+            animation: PingPong(speed: 1.5),
+        )
+    "##;
+    let asset: Asset = from_str(ron)?;
+    assert_eq!(
+        asset,
+        Asset {
+            name: "player".to_string(),
+            size: (32, 48),
+            frames: vec![Frame(0, 0.1), Frame(1, 0.15)],
+            pivot: Some(Point { x: 16.0, y: 40.5 }),
+            tags: HashMap::from([
+                ("layer".to_string(), "actors".to_string()),
+                ("say \"hi\"".to_string(), "x".to_string()),
+            ]),
+            animation: Animation::PingPong {
+                speed: 1.5,
+                pause: None,
+            },
+            hidden: false,
+        }
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    let asset = Asset {
+        name: "a \"tricky\"\nname \u{1F980}".to_string(),
+        size: (0, u32::MAX),
+        frames: vec![Frame(7, -1.25)],
+        pivot: None,
+        tags: HashMap::from([("k".to_string(), "v".to_string())]),
+        animation: Animation::Loop(0.5),
+        hidden: true,
+    };
+    let ron = to_string(&asset)?;
+    let back: Asset = from_str(&ron).map_err(|e| e.into_owned())?;
+    assert_eq!(back, asset);
+    let ron = to_string_pretty(&asset)?;
+    let back: Asset = from_str(&ron).map_err(|e| e.into_owned())?;
+    assert_eq!(back, asset);
+    let ron = RonOptions::new().struct_names(false).to_string(&asset)?;
+    let back: Asset = from_str(&ron).map_err(|e| e.into_owned())?;
+    assert_eq!(back, asset);
+
+    let asset = Asset {
+        animation: Animation::Still,
+        pivot: Some(Point { x: 1.0, y: 2.0 }),
+        ..asset
+    };
+    let ron = to_string_pretty(&asset)?;
+    let back: Asset = from_str(&ron).map_err(|e| e.into_owned())?;
+    assert_eq!(back, asset);
+
+    let nested = vec![Some(vec![1i64, -2]), None, Some(vec![])];
+    let ron = to_string(&nested)?;
+    let back: Vec<Option<Vec<i64>>> = from_str(&ron).map_err(|e| e.into_owned())?;
+    assert_eq!(back, nested);
+    let value = (Some(Some('\'')), (), ['x', 'y']);
+    let ron = to_string(&value)?;
+    let back: (Option<Option<char>>, (), [char; 2]) = from_str(&ron).map_err(|e| e.into_owned())?;
+    assert_eq!(back, value);
+}
+
+#[test]
+fn test_literals() {
+    let numbers: Vec<i64> = from_str("[0x_ff, 0o17, 0b101, -42, +7, 1_000]")?;
+    assert_eq!(numbers, vec![255, 15, 5, -42, 7, 1000]);
+    let floats: Vec<f64> = from_str("[1e3, -2.5e-1, .5, 3]")?;
+    assert_eq!(floats, vec![1000.0, -0.25, 0.5, 3.0]);
+    let bytes: Vec<u8> = from_str(r#"b"hi\n""#)?;
+    assert_eq!(bytes, b"hi\n");
+    let strings: Vec<String> = from_str(r##"["tab\tend", "\u{e9}", r#"raw \n "quote""#]"##)?;
+    assert_eq!(strings, vec!["tab\tend", "é", r#"raw \n "quote""#]);
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Keyword {
+        r#type: String,
+    }
+    let keyword: Keyword = from_str(r#"(r#type: "door")"#)?;
+    assert_eq!(keyword.r#type, "door");
+}
+
+#[test]
+fn test_options() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Settings {
+        volume: Option<u8>,
+        name: Option<String>,
+        window: Option<Point>,
+    }
+
+    // Explicit, implicit and left out
+    let settings: Settings = from_str(r#"(volume: Some(3), name: "hero")"#)?;
+    assert_eq!(
+        settings,
+        Settings {
+            volume: Some(3),
+            name: Some("hero".to_string()),
+            window: None,
+        }
+    );
+    let settings: Settings = from_str("Settings(volume: None, window: Point(x: 1, y: 2))")?;
+    assert_eq!(
+        settings,
+        Settings {
+            volume: None,
+            name: None,
+            window: Some(Point { x: 1.0, y: 2.0 }),
+        }
+    );
+    let settings: Settings = from_str("()")?;
+    assert_eq!(settings.volume, None);
+}
+
+#[test]
+fn test_enum_tagging_is_ignored() {
+    #[derive(Facet, Debug, PartialEq)]
+    #[facet(tag = "kind")]
+    #[repr(u8)]
+    enum Shape {
+        Circle { radius: f32 },
+        Empty,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[facet(tag = "t", content = "c")]
+    #[repr(u8)]
+    enum Event {
+        Click(i32, i32),
+        Key(char),
+        Quit,
+    }
+
+    let shapes: Vec<Shape> = from_str("[Circle(radius: 2), Empty]")?;
+    assert_eq!(shapes, vec![Shape::Circle { radius: 2.0 }, Shape::Empty]);
+    assert_eq!(to_string(&shapes)?, "[Circle(radius: 2.0), Empty]");
+
+    let events: Vec<Event> = from_str("[Click(1, 2), Key('q'), Quit]")?;
+    assert_eq!(
+        events,
+        vec![Event::Click(1, 2), Event::Key('q'), Event::Quit]
+    );
+}
+
+#[test]
+fn test_errors() {
+    // Syntax errors point at where the problem is
+    let err = from_str::<Point>("Point(x: 1, y: 2").unwrap_err();
+    assert!(matches!(err.kind, DeserErrorKind::UnexpectedEof { .. }));
+    assert_eq!(err.span.start(), 16);
+
+    let err = from_str::<Point>("Point(x: 1 y: 2)").unwrap_err();
+    assert!(matches!(
+        err.kind,
+        DeserErrorKind::UnexpectedChar { got: 'y', .. }
+    ));
+    assert_eq!(err.span.start(), 11);
+
+    let err = from_str::<Point>("Point(x: 1, y: 2) extra").unwrap_err();
+    assert!(matches!(
+        err.kind,
+        DeserErrorKind::UnexpectedChar { got: 'e', .. }
+    ));
+
+    let err = from_str::<u8>("/* never closed").unwrap_err();
+    assert!(matches!(err.kind, DeserErrorKind::UnexpectedEof { .. }));
+
+    let err = from_str::<Vec<i64>>("[99999999999999999999]").unwrap_err();
+    assert!(matches!(err.kind, DeserErrorKind::NumberOutOfRange(_)));
+    assert_eq!(err.span.start(), 1);
+
+    // Values nested too deep to read without overflowing the stack
+    let input = "[".repeat(100_000);
+    let err = from_str::<Point>(&input).unwrap_err();
+    assert!(matches!(err.kind, DeserErrorKind::InvalidSyntax(_)));
+    assert_eq!(err.span.start(), 128);
+
+    // A struct written with another name
+    let err = from_str::<Point>("Size(x: 1, y: 2)").unwrap_err();
+    assert!(matches!(
+        &err.kind,
+        DeserErrorKind::InvalidSyntax(message) if message == "expected `Point`, found `Size`"
+    ));
+    assert_eq!((err.span.start(), err.span.len()), (0, 4));
+
+    // Errors from the deserializer point at the value too
+    let err = from_str::<Point>("(x: 1, y: \"two\")").unwrap_err();
+    assert_eq!(err.span.start(), 10);
+
+    let err = from_str::<Animation>("Bounce(1.0)").unwrap_err();
+    assert!(matches!(err.kind, DeserErrorKind::NoSuchVariant { .. }));
+
+    #[derive(Facet, Debug)]
+    #[facet(deny_unknown_fields)]
+    struct Strict {
+        id: u32,
+    }
+
+    let err = from_str::<Strict>("(id: 1, extra: [1, 2])").unwrap_err();
+    assert!(matches!(err.kind, DeserErrorKind::UnknownField { .. }));
+    assert_eq!(err.span.start(), 8);
+}
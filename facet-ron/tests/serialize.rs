@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+
+use facet::Facet;
+use facet_ron::{RonOptions, to_string, to_string_pretty};
+use facet_testhelpers::test;
+
+#[derive(Facet)]
+struct Unit;
+
+#[derive(Facet)]
+struct Meters(f32);
+
+#[derive(Facet)]
+struct Rgb(u8, u8, u8);
+
+#[derive(Facet)]
+#[repr(u8)]
+#[allow(dead_code)]
+enum Action {
+    Idle,
+    Jump(f32),
+    Move(i32, i32),
+    Say { text: String, loud: bool },
+}
+
+#[test]
+fn test_structs_and_enums() {
+    assert_eq!(to_string(&Unit)?, "Unit");
+    assert_eq!(to_string(&Meters(1.0))?, "Meters(1.0)");
+    assert_eq!(to_string(&Rgb(1, 2, 3))?, "Rgb(1, 2, 3)");
+
+    assert_eq!(to_string(&Action::Idle)?, "Idle");
+    assert_eq!(to_string(&Action::Jump(2.5))?, "Jump(2.5)");
+    assert_eq!(to_string(&Action::Move(-1, 4))?, "Move(-1, 4)");
+    assert_eq!(
+        to_string(&Action::Say {
+            text: "hi".to_string(),
+            loud: false,
+        })?,
+        r#"Say(text: "hi", loud: false)"#
+    );
+
+    // Enums keep their variant names without struct names, since that's what they are
+    let options = RonOptions::new().struct_names(false);
+    assert_eq!(options.to_string(&Unit)?, "()");
+    assert_eq!(options.to_string(&Rgb(1, 2, 3))?, "(1, 2, 3)");
+    assert_eq!(options.to_string(&Action::Move(0, 0))?, "Move(0, 0)");
+}
+
+#[test]
+fn test_scalars_and_collections() {
+    assert_eq!(
+        to_string(&"a \"quoted\"\n\u{7}line")?,
+        r#""a \"quoted\"\n\u{7}line""#
+    );
+    assert_eq!(to_string(&'\'')?, r"'\''");
+    assert_eq!(to_string(&1.0f64)?, "1.0");
+    assert_eq!(to_string(&-0.25f32)?, "-0.25");
+    assert_eq!(to_string(&u128::MAX)?, u128::MAX.to_string());
+    assert_eq!(to_string(&())?, "()");
+
+    assert_eq!(to_string(&vec![1u8, 2, 3])?, "[1, 2, 3]");
+    assert_eq!(to_string(&Vec::<u8>::new())?, "[]");
+    assert_eq!(to_string(&[true, false])?, "[true, false]");
+    assert_eq!(to_string(&(1u8, "two", 3.0f64))?, r#"(1, "two", 3.0)"#);
+    assert_eq!(to_string(&Some(Some(1u8)))?, "Some(Some(1))");
+    assert_eq!(to_string(&None::<u8>)?, "None");
+
+    let map = BTreeMap::from([("a", 1u8), ("b", 2)]);
+    assert_eq!(to_string(&map)?, r#"{"a": 1, "b": 2}"#);
+    assert_eq!(to_string(&BTreeMap::<u8, u8>::new())?, "{}");
+}
+
+#[test]
+fn test_pretty() {
+    #[derive(Facet)]
+    struct Level {
+        name: String,
+        spawn: (i32, i32),
+        actions: Vec<Action>,
+        tints: BTreeMap<String, Rgb>,
+        empty: Vec<u8>,
+        music: Option<String>,
+    }
+
+    let level = Level {
+        name: "cave".to_string(),
+        spawn: (0, -2),
+        actions: vec![Action::Idle, Action::Jump(1.5)],
+        tints: BTreeMap::from([("fog".to_string(), Rgb(10, 10, 20))]),
+        empty: Vec::new(),
+        music: None,
+    };
+    assert_eq!(
+        to_string_pretty(&level)?,
+        r#"Level(
+    name: "cave",
+    spawn: (
+        0,
+        -2,
+    ),
+    actions: [
+        Idle,
+        Jump(1.5),
+    ],
+    tints: {
+        "fog": Rgb(
+            10,
+            10,
+            20,
+        ),
+    },
+    empty: [],
+    music: None,
+)"#
+    );
+
+    let options = RonOptions::new().pretty(true).indent("\t");
+    assert_eq!(
+        options.to_string(&Action::Say {
+            text: "x".to_string(),
+            loud: true,
+        })?,
+        "Say(\n\ttext: \"x\",\n\tloud: true,\n)"
+    );
+}
+
+#[test]
+fn test_transparent_and_skipped() {
+    #[derive(Facet)]
+    #[facet(transparent)]
+    struct Id(u32);
+
+    #[derive(Facet)]
+    struct Entity {
+        id: Id,
+        parent: Option<Id>,
+        #[facet(skip_serializing)]
+        cache: u32,
+        #[facet(rename = "type")]
+        kind: String,
+    }
+
+    let entity = Entity {
+        id: Id(3),
+        parent: Some(Id(1)),
+        cache: 99,
+        kind: "door".to_string(),
+    };
+    assert_eq!(
+        to_string(&entity)?,
+        r#"Entity(id: 3, parent: Some(1), type: "door")"#
+    );
+}
@@ -5,16 +5,21 @@
 #![deny(unsafe_code)]
 #![doc = include_str!("../README.md")]
 
+#[cfg(not(feature = "alloc"))]
+compile_error!("feature `alloc` is required");
+
 extern crate alloc;
 
 use alloc::string::String;
 use alloc::vec::Vec;
 
 use facet_core::{
-    Def, Facet, Field, PointerType, ScalarAffinity, SequenceType, ShapeAttribute, StructKind, Type,
-    UserType,
+    Def, EnumTagging, Facet, Field, PointerType, ScalarAffinity, SequenceType, ShapeAttribute,
+    StructKind, Type, UserType,
+};
+use facet_reflect::{
+    HasFields, Peek, PeekEnum, PeekListLike, PeekMap, PeekSet, PeekStruct, PeekTuple, ScalarType,
 };
-use facet_reflect::{HasFields, Peek, PeekListLike, PeekMap, PeekStruct, PeekTuple, ScalarType};
 use log::{debug, trace};
 
 mod debug_serializer;
@@ -23,6 +28,56 @@ fn variant_is_newtype_like(variant: &facet_core::Variant) -> bool {
     variant.data.kind == facet_core::StructKind::Tuple && variant.data.fields.len() == 1
 }
 
+/// Serializes the content of a non-unit enum variant, without the variant name:
+/// newtype variants as their inner value, tuple variants as arrays and struct
+/// variants as objects.
+fn push_variant_content<'mem, 'facet, 'shape, S>(
+    serializer: &mut S,
+    stack: &mut Vec<SerializeTask<'mem, 'facet, 'shape>>,
+    peek_enum: &PeekEnum<'mem, 'facet, 'shape>,
+) -> Result<(), S::Error>
+where
+    S: Serializer<'shape>,
+    'mem: 'facet,
+{
+    let variant = peek_enum
+        .active_variant()
+        .expect("Failed to get active variant");
+
+    if variant_is_newtype_like(variant) {
+        // Newtype variant - serialize the inner value directly
+        let fields = peek_enum.fields_for_serialize().collect::<Vec<_>>();
+        let (field, field_peek) = fields[0];
+        // TODO: error if `skip_serialize` is set?
+        stack.push(SerializeTask::Value(field_peek, Some(field)));
+    } else if variant.data.kind == StructKind::Tuple || variant.data.kind == StructKind::TupleStruct
+    {
+        // Tuple variant - serialize as array
+        let fields = peek_enum.fields_for_serialize().count();
+        serializer.start_array(Some(fields))?;
+        stack.push(SerializeTask::EndArray);
+
+        // Push fields in reverse order for tuple variant
+        for (field, field_peek) in peek_enum.fields_for_serialize().rev() {
+            stack.push(SerializeTask::Value(field_peek, Some(field)));
+        }
+    } else {
+        // Struct variant - serialize as object
+        let fields = peek_enum.fields_for_serialize().count();
+        serializer.start_object(Some(fields))?;
+        stack.push(SerializeTask::EndObject);
+
+        // Push fields in reverse order for struct variant
+        for (field, field_peek) in peek_enum.fields_for_serialize().rev() {
+            stack.push(SerializeTask::EndField);
+            stack.push(SerializeTask::Value(field_peek, Some(field)));
+            stack.push(SerializeTask::SerializeFieldName(field));
+        }
+    }
+
+    Ok(())
+}
+
 // --- Serializer Trait Definition ---
 
 /// A trait for implementing format-specific serialization logic.
@@ -206,6 +261,14 @@ pub trait Serializer<'shape> {
         Ok(())
     }
 
+    /// Signal the start of serializing a struct field, before its name. Formats that lay
+    /// fields out by their flags or attributes, rather than by name alone, look at them here.
+    #[inline(always)]
+    fn begin_field(&mut self, field: Field<'shape>) -> Result<(), Self::Error> {
+        let _ = field;
+        Ok(())
+    }
+
     /// Signal the end of serializing a field.
     #[inline(always)]
     fn end_field(&mut self) -> Result<(), Self::Error> {
@@ -218,6 +281,19 @@ pub trait Serializer<'shape> {
         let _ = discriminant;
         Ok(())
     }
+
+    /// Serialize a scalar that's written as the text its `Display` gives, like a date or a
+    /// UUID. Formats with their own representation for its affinity, like a tagged value,
+    /// write that instead; the others write the text.
+    #[inline(always)]
+    fn serialize_affinity_str(
+        &mut self,
+        affinity: &ScalarAffinity<'shape>,
+        value: &str,
+    ) -> Result<(), Self::Error> {
+        let _ = affinity;
+        self.serialize_str(value)
+    }
 }
 
 // --- Iterative Serialization Logic ---
@@ -239,8 +315,9 @@ enum SerializeTask<'mem, 'facet, 'shape> {
     TupleStructFields(PeekStruct<'mem, 'facet, 'shape>),
     TupleFields(PeekTuple<'mem, 'facet, 'shape>),
     MapEntries(PeekMap<'mem, 'facet, 'shape>),
+    SetItems(PeekSet<'mem, 'facet, 'shape>),
     // Field-related tasks
-    SerializeFieldName(&'shape str),
+    SerializeFieldName(Field<'shape>),
     SerializeMapKey(Peek<'mem, 'facet, 'shape>),
     SerializeMapValue(Peek<'mem, 'facet, 'shape>),
 }
@@ -264,6 +341,18 @@ where
             SerializeTask::Value(mut cpeek, maybe_field) => {
                 debug!("Serializing a value, shape is {}", cpeek.shape());
 
+                if let Some(converted) =
+                    maybe_field.and_then(|field| cpeek.custom_serialization(&field))
+                {
+                    debug!(
+                        "Field converts {} to {} for serialization",
+                        cpeek.shape(),
+                        converted.shape()
+                    );
+                    serialize_iterative(converted.peek(), serializer)?;
+                    continue;
+                }
+
                 if cpeek
                     .shape()
                     .attributes
@@ -362,8 +451,10 @@ where
                                     | ScalarAffinity::UUID(_) => {
                                         if let Some(_display) = cpeek.shape().vtable.display {
                                             // Use display formatting if available
-                                            serializer
-                                                .serialize_str(&alloc::format!("{}", cpeek))?
+                                            serializer.serialize_affinity_str(
+                                                sd.affinity,
+                                                &alloc::format!("{}", cpeek),
+                                            )?
                                         } else {
                                             panic!(
                                                 "Unsupported shape (no display): {}",
@@ -427,6 +518,13 @@ where
                         stack.push(SerializeTask::EndMap);
                         stack.push(SerializeTask::MapEntries(peek_map));
                     }
+                    (Def::Set(_), _) => {
+                        let peek_set = cpeek.into_set().unwrap();
+                        let len = peek_set.len();
+                        serializer.start_array(Some(len))?;
+                        stack.push(SerializeTask::EndArray);
+                        stack.push(SerializeTask::SetItems(peek_set));
+                    }
                     (Def::Option(_), _) => {
                         let opt = cpeek.into_option().unwrap();
                         if let Some(inner_peek) = opt.value() {
@@ -526,6 +624,7 @@ where
                         }
                     }
                     (_, Type::User(UserType::Enum(_))) => {
+                        let tagging = cpeek.shape().enum_tagging();
                         let peek_enum = cpeek.into_enum().unwrap();
                         let variant = peek_enum
                             .active_variant()
@@ -544,48 +643,87 @@ where
                         serializer.start_enum_variant(discriminant)?;
                         let flattened = maybe_field.map(|f| f.flattened).unwrap_or_default();
 
-                        if variant.data.fields.is_empty() {
-                            // Unit variant
-                            serializer.serialize_unit_variant(variant_index, variant.name)?;
-                        } else {
-                            if !flattened {
-                                // For now, treat all enum variants with data as objects
-                                serializer.start_object(Some(1))?;
-                                stack.push(SerializeTask::EndObject);
+                        match tagging {
+                            EnumTagging::External => {
+                                if variant.data.fields.is_empty() {
+                                    // Unit variant
+                                    serializer
+                                        .serialize_unit_variant(variant_index, variant.name)?;
+                                } else {
+                                    if !flattened {
+                                        // For now, treat all enum variants with data as objects
+                                        serializer.start_object(Some(1))?;
+                                        stack.push(SerializeTask::EndObject);
+
+                                        // Serialize variant name as field name
+                                        serializer.serialize_field_name(variant.name)?;
+                                        stack.push(SerializeTask::EndField);
+                                    }
 
-                                // Serialize variant name as field name
-                                serializer.serialize_field_name(variant.name)?;
+                                    push_variant_content(serializer, &mut stack, &peek_enum)?;
+                                }
                             }
-
-                            if variant_is_newtype_like(variant) {
-                                // Newtype variant - serialize the inner value directly
-                                let fields = peek_enum.fields_for_serialize().collect::<Vec<_>>();
-                                let (field, field_peek) = fields[0];
-                                // TODO: error if `skip_serialize` is set?
-                                stack.push(SerializeTask::Value(field_peek, Some(field)));
-                            } else if variant.data.kind == StructKind::Tuple
-                                || variant.data.kind == StructKind::TupleStruct
-                            {
-                                // Tuple variant - serialize as array
-                                let fields = peek_enum.fields_for_serialize().count();
-                                serializer.start_array(Some(fields))?;
-                                stack.push(SerializeTask::EndArray);
-
-                                // Push fields in reverse order for tuple variant
-                                for (field, field_peek) in peek_enum.fields_for_serialize().rev() {
-                                    stack.push(SerializeTask::Value(field_peek, Some(field)));
+                            EnumTagging::Internal { tag } => {
+                                if variant.data.fields.is_empty() {
+                                    // Unit variant - just the tag
+                                    serializer.start_object(Some(1))?;
+                                    stack.push(SerializeTask::EndObject);
+                                } else if variant_is_newtype_like(variant) {
+                                    // Newtype variant - the inner struct's fields go next to the tag
+                                    let (_, field_peek) = peek_enum
+                                        .fields_for_serialize()
+                                        .next()
+                                        .expect("newtype variant has a field");
+                                    // `#[derive(Facet)]` rejects newtypes over anything else
+                                    let peek_struct = field_peek
+                                        .into_struct()
+                                        .expect("internally tagged newtype variants hold a struct");
+                                    let fields = peek_struct.fields_for_serialize().count();
+                                    serializer.start_object(Some(fields + 1))?;
+                                    stack.push(SerializeTask::EndObject);
+                                    stack.push(SerializeTask::ObjectFields(peek_struct));
+                                } else if variant.data.kind == StructKind::Struct {
+                                    // Struct variant - the fields go next to the tag
+                                    let fields = peek_enum.fields_for_serialize().count();
+                                    serializer.start_object(Some(fields + 1))?;
+                                    stack.push(SerializeTask::EndObject);
+                                    for (field, field_peek) in
+                                        peek_enum.fields_for_serialize().rev()
+                                    {
+                                        stack.push(SerializeTask::EndField);
+                                        stack.push(SerializeTask::Value(field_peek, Some(field)));
+                                        stack.push(SerializeTask::SerializeFieldName(field));
+                                    }
+                                } else {
+                                    unreachable!(
+                                        "#[derive(Facet)] rejects internally tagged tuple variants"
+                                    );
                                 }
-                            } else {
-                                // Struct variant - serialize as object
-                                let fields = peek_enum.fields_for_serialize().count();
-                                serializer.start_object(Some(fields))?;
+
+                                serializer.serialize_field_name(tag)?;
+                                serializer.serialize_str(variant.name)?;
+                                serializer.end_field()?;
+                            }
+                            EnumTagging::Adjacent { tag, content } => {
+                                let has_content = !variant.data.fields.is_empty();
+                                serializer.start_object(Some(if has_content { 2 } else { 1 }))?;
                                 stack.push(SerializeTask::EndObject);
 
-                                // Push fields in reverse order for struct variant
-                                for (field, field_peek) in peek_enum.fields_for_serialize().rev() {
+                                serializer.serialize_field_name(tag)?;
+                                serializer.serialize_str(variant.name)?;
+                                serializer.end_field()?;
+
+                                if has_content {
+                                    serializer.serialize_field_name(content)?;
                                     stack.push(SerializeTask::EndField);
-                                    stack.push(SerializeTask::Value(field_peek, Some(field)));
-                                    stack.push(SerializeTask::SerializeFieldName(field.name));
+                                    push_variant_content(serializer, &mut stack, &peek_enum)?;
+                                }
+                            }
+                            EnumTagging::Untagged => {
+                                if variant.data.fields.is_empty() {
+                                    serializer.serialize_unit()?;
+                                } else {
+                                    push_variant_content(serializer, &mut stack, &peek_enum)?;
                                 }
                             }
                         }
@@ -627,7 +765,7 @@ where
                 for (field, field_peek) in peek_struct.fields_for_serialize().rev() {
                     stack.push(SerializeTask::EndField);
                     stack.push(SerializeTask::Value(field_peek, Some(field)));
-                    stack.push(SerializeTask::SerializeFieldName(field.name));
+                    stack.push(SerializeTask::SerializeFieldName(field));
                 }
             }
             SerializeTask::TupleStructFields(peek_struct) => {
@@ -664,9 +802,18 @@ where
                 }
             }
 
+            SerializeTask::SetItems(peek_set) => {
+                // Push items in reverse order
+                let items: Vec<_> = peek_set.iter().collect();
+                for item_peek in items.into_iter().rev() {
+                    stack.push(SerializeTask::Value(item_peek, None));
+                }
+            }
+
             // --- Field name and map key/value handling ---
-            SerializeTask::SerializeFieldName(name) => {
-                serializer.serialize_field_name(name)?;
+            SerializeTask::SerializeFieldName(field) => {
+                serializer.begin_field(field)?;
+                serializer.serialize_field_name(field.name)?;
             }
             SerializeTask::SerializeMapKey(key_peek) => {
                 stack.push(SerializeTask::EndMapKey);
@@ -83,6 +83,12 @@ impl<'input, 'shape> TomlDeError<'input, 'shape> {
             TomlDeErrorKind::ParseSingleValueAsMultipleFieldStruct => {
                 "Can't parse a single value as a struct with multiple fields".to_string()
             }
+            TomlDeErrorKind::ExpectedTagWithName(tag) => {
+                format!("Expected enum tag with name '{tag}'")
+            }
+            TomlDeErrorKind::NoMatchingVariant(shape) => {
+                format!("Data did not match any variant of untagged enum '{shape}'")
+            }
         }
     }
 }
@@ -177,4 +183,8 @@ pub enum TomlDeErrorKind<'shape> {
     ExpectedExactlyOneField,
     /// Tried parsing a single value as a struct with multiple fields.
     ParseSingleValueAsMultipleFieldStruct,
+    /// Expected the tag of an internally or adjacently tagged enum with the specified name.
+    ExpectedTagWithName(&'shape str),
+    /// None of the variants of an untagged enum matched.
+    NoMatchingVariant(&'shape Shape<'shape>),
 }
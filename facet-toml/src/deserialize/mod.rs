@@ -11,7 +11,7 @@ use alloc::{
     string::{String, ToString},
 };
 pub use error::{TomlDeError, TomlDeErrorKind};
-use facet_core::{Characteristic, Def, Facet, FieldFlags, StructKind, Type, UserType};
+use facet_core::{Characteristic, Def, EnumTagging, Facet, FieldFlags, StructKind, Type, UserType};
 use facet_reflect::{ReflectError, ScalarType, Wip};
 use log::trace;
 use toml_edit::{ImDocument, Item, TomlError};
@@ -107,6 +107,7 @@ fn deserialize_item<'input, 'facet, 'shape>(
         Def::Scalar(_) => deserialize_as_scalar(toml, wip, item),
         Def::List(_) => deserialize_as_list(toml, wip, item),
         Def::Map(_) => deserialize_as_map(toml, wip, item),
+        Def::Set(_) => deserialize_as_set(toml, wip, item),
         Def::SmartPointer(_) => deserialize_as_smartpointer(toml, wip, item),
         _ => todo!(),
     }
@@ -124,6 +125,31 @@ fn deserialize_as_struct<'input, 'a, 'shape>(
         "struct".blue()
     );
 
+    // Tuple structs can be written as an array of their fields
+    if let (Some(array), StructKind::Tuple | StructKind::TupleStruct) = (item.as_array(), def.kind)
+    {
+        if array.len() != def.fields.len() {
+            return Err(TomlDeError::new(
+                toml,
+                TomlDeErrorKind::GenericReflect(ReflectError::OperationFailed {
+                    shape: wip.shape(),
+                    operation: "tuple struct field count doesn't match array length",
+                }),
+                item.span(),
+                wip.path(),
+            ));
+        }
+
+        for (index, value) in array.iter().enumerate() {
+            reflect!(wip, toml, value.span(), field(index));
+            // TODO: remove clone
+            wip = deserialize_item(toml, wip, &Item::Value(value.clone()))?;
+            reflect!(wip, toml, value.span(), pop());
+        }
+
+        return Ok(wip);
+    }
+
     // Parse as a the inner struct type if item is a single value and the struct is a unit struct
     if item.is_value() && !item.is_inline_table() {
         // Only allow unit structs
@@ -162,10 +188,17 @@ fn deserialize_as_struct<'input, 'a, 'shape>(
     })?;
 
     for field in def.fields {
+        // Skipped fields are filled with their default when the struct is popped
+        if field.should_skip_deserializing() {
+            continue;
+        }
+
         reflect!(wip, toml, item.span(), field_named(field.name));
 
-        // Find the matching TOML field
-        let field_item = table.get(field.name);
+        // Find the matching TOML field, by name or by alias
+        let field_item = table
+            .get(field.name)
+            .or_else(|| field.aliases().find_map(|alias| table.get(alias)));
         match field_item {
             Some(field_item) => wip = deserialize_item(toml, wip, field_item)?,
             None => {
@@ -177,7 +210,7 @@ fn deserialize_as_struct<'input, 'a, 'shape>(
                     if let Some(default_in_place_fn) = field.vtable.default_fn {
                         reflect!(wip, toml, item.span(), put_from_fn(default_in_place_fn));
                     } else if field.shape().is(Characteristic::Default) {
-                        reflect!(wip, toml, item.span(), put_default());
+                        reflect!(wip, toml, item.span(), put_field_default());
                     } else {
                         // Throw an error when there's a "default" attribute but no implementation for the type
                         return Err(TomlDeError::new(
@@ -224,6 +257,27 @@ fn deserialize_as_enum<'input, 'a, 'shape>(
         "enum".blue()
     );
 
+    match wip.shape().enum_tagging() {
+        EnumTagging::External => {}
+        EnumTagging::Internal { tag } => {
+            let variant_name = enum_tag(toml, &wip, tag, item)?;
+            return build_enum_from_variant_name(toml, wip, variant_name, item);
+        }
+        EnumTagging::Adjacent { tag, content } => {
+            let variant_name = enum_tag(toml, &wip, tag, item)?;
+            match item.as_table_like().and_then(|table| table.get(content)) {
+                Some(content) => {
+                    return build_enum_from_variant_name(toml, wip, variant_name, content);
+                }
+                None => {
+                    // Only unit variants can do without content
+                    return build_enum_from_variant_name(toml, wip, variant_name, &Item::None);
+                }
+            }
+        }
+        EnumTagging::Untagged => return deserialize_as_untagged_enum(toml, wip, item),
+    }
+
     let wip = match item {
         Item::None => todo!(),
 
@@ -333,8 +387,33 @@ fn build_enum_from_variant_name<'input, 'a, 'shape>(
     let is_tuple =
         variant.data.kind == StructKind::TupleStruct || variant.data.kind == StructKind::Tuple;
 
+    // A newtype variant wrapping a table-like type is that table itself, unless it's written by index
+    if is_tuple
+        && variant.data.fields.len() == 1
+        && matches!(
+            (
+                variant.data.fields[0].shape().ty,
+                variant.data.fields[0].shape().def
+            ),
+            (Type::User(UserType::Struct(_) | UserType::Enum(_)), _) | (_, Def::Map(_))
+        )
+        && item
+            .as_table_like()
+            .is_some_and(|table| !table.contains_key("0"))
+    {
+        reflect!(wip, toml, item.span(), field(0));
+        wip = deserialize_item(toml, wip, item)?;
+        reflect!(wip, toml, item.span(), pop());
+        return Ok(wip);
+    }
+
     // Push all fields
     for (index, field) in variant.data.fields.iter().enumerate() {
+        // Skipped fields are filled with their default when the enum is popped
+        if field.should_skip_deserializing() {
+            continue;
+        }
+
         reflect!(wip, toml, item.span(), field_named(field.name));
 
         // Try to get the TOML value as a table to extract the field
@@ -347,8 +426,11 @@ fn build_enum_from_variant_name<'input, 'a, 'shape>(
                 field.name
             };
 
-            // Try to get the TOML field matching the Rust name
-            match table.get(field_name) {
+            // Try to get the TOML field matching the Rust name, or one of its aliases
+            let field_item = table
+                .get(field_name)
+                .or_else(|| field.aliases().find_map(|alias| table.get(alias)));
+            match field_item {
                 // Field found, push it
                 Some(field) => {
                     wip = deserialize_item(toml, wip, field)?;
@@ -384,6 +466,101 @@ fn build_enum_from_variant_name<'input, 'a, 'shape>(
     Ok(wip)
 }
 
+/// Get the variant name of an internally or adjacently tagged enum.
+fn enum_tag<'input, 'item, 'shape>(
+    toml: &'input str,
+    wip: &Wip<'_, 'shape>,
+    tag: &'shape str,
+    item: &'item Item,
+) -> Result<&'item str, TomlDeError<'input, 'shape>> {
+    let table = item.as_table_like().ok_or_else(|| {
+        TomlDeError::new(
+            toml,
+            TomlDeErrorKind::ExpectedType {
+                expected: "table like structure",
+                got: item.type_name(),
+            },
+            item.span(),
+            wip.path(),
+        )
+    })?;
+    let tag_item = table.get(tag).ok_or_else(|| {
+        TomlDeError::new(
+            toml,
+            TomlDeErrorKind::ExpectedTagWithName(tag),
+            item.span(),
+            wip.path(),
+        )
+    })?;
+    tag_item.as_str().ok_or_else(|| {
+        TomlDeError::new(
+            toml,
+            TomlDeErrorKind::ExpectedType {
+                expected: "string",
+                got: tag_item.type_name(),
+            },
+            tag_item.span(),
+            wip.path(),
+        )
+    })
+}
+
+fn deserialize_as_untagged_enum<'input, 'a, 'shape>(
+    toml: &'input str,
+    wip: Wip<'a, 'shape>,
+    item: &Item,
+) -> Result<Wip<'a, 'shape>, TomlDeError<'input, 'shape>> {
+    let shape = wip.shape();
+    let Type::User(UserType::Enum(enum_def)) = shape.ty else {
+        unreachable!()
+    };
+
+    for (index, variant) in enum_def.variants.iter().enumerate() {
+        // TOML has no null, so unit variants can't be represented
+        if variant.data.kind == StructKind::Unit {
+            continue;
+        }
+
+        let attempt = Wip::alloc_shape(shape)
+            .and_then(|variant_wip| variant_wip.variant(index))
+            .map_err(|e| {
+                TomlDeError::new(toml, TomlDeErrorKind::GenericReflect(e), None, wip.path())
+            })
+            .and_then(|variant_wip| {
+                build_enum_from_variant_name(toml, variant_wip, variant.name, item)
+            })
+            .and_then(|variant_wip| {
+                variant_wip.build().map_err(|e| {
+                    TomlDeError::new(toml, TomlDeErrorKind::GenericReflect(e), None, wip.path())
+                })
+            });
+
+        match attempt {
+            Ok(heap_value) => {
+                trace!("Untagged value matched variant {}", variant.name.yellow());
+                let path = wip.path();
+                return wip.put_heap_value(heap_value).map_err(|e| {
+                    TomlDeError::new(toml, TomlDeErrorKind::GenericReflect(e), item.span(), path)
+                });
+            }
+            Err(e) => {
+                trace!(
+                    "Untagged value did not match variant {}: {}",
+                    variant.name.yellow(),
+                    e.message()
+                );
+            }
+        }
+    }
+
+    Err(TomlDeError::new(
+        toml,
+        TomlDeErrorKind::NoMatchingVariant(shape),
+        item.span(),
+        wip.path(),
+    ))
+}
+
 fn deserialize_as_list<'input, 'a, 'shape>(
     toml: &'input str,
     mut wip: Wip<'a, 'shape>,
@@ -439,6 +616,52 @@ fn deserialize_as_list<'input, 'a, 'shape>(
     Ok(wip)
 }
 
+fn deserialize_as_set<'input, 'a, 'shape>(
+    toml: &'input str,
+    mut wip: Wip<'a, 'shape>,
+    item: &Item,
+) -> Result<Wip<'a, 'shape>, TomlDeError<'input, 'shape>> {
+    trace!(
+        "Deserializing {} as {}",
+        item.type_name().cyan(),
+        "set".blue()
+    );
+
+    // Get the TOML item as an array
+    let Some(item) = item.as_array() else {
+        return Err(TomlDeError::new(
+            toml,
+            TomlDeErrorKind::ExpectedType {
+                expected: "array",
+                got: item.type_name(),
+            },
+            item.span(),
+            wip.path(),
+        ));
+    };
+
+    // Start the set, this also takes care of empty sets
+    reflect!(wip, toml, item.span(), begin_set_insert());
+
+    // Loop over all items in the TOML array
+    for value in item.iter() {
+        reflect!(wip, toml, value.span(), push());
+
+        wip = deserialize_item(
+            toml,
+            wip,
+            // TODO: remove clone
+            &Item::Value(value.clone()),
+        )?;
+
+        reflect!(wip, toml, value.span(), pop());
+    }
+
+    trace!("Finished deserializing {}", "set".blue());
+
+    Ok(wip)
+}
+
 fn deserialize_as_map<'input, 'a, 'shape>(
     toml: &'input str,
     mut wip: Wip<'a, 'shape>,
@@ -16,7 +16,7 @@ use core::borrow::Borrow as _;
 pub use error::TomlSerError;
 use facet_serialize::{Serialize, Serializer};
 use log::trace;
-use toml_edit::{DocumentMut, Item, Table, Value};
+use toml_edit::{Array, DocumentMut, Item, Table, Value};
 use yansi::Paint as _;
 
 /// Serializer for TOML values.
@@ -54,10 +54,14 @@ impl<'shape> TomlSerializer<'shape> {
         let value = value.into();
 
         match self.current {
-            // Write the value
-            KeyOrValue::Value => {
-                self.set_current_item(value);
-            }
+            // Write the value, or append it when we're inside an array
+            KeyOrValue::Value => match self.item_mut().as_array_mut() {
+                Some(array) => {
+                    trace!("Push array item {}", value.type_name());
+                    array.push(value);
+                }
+                None => self.set_current_item(value),
+            },
             // Push the value as a new item
             KeyOrValue::Key => {
                 let map_key = value
@@ -205,9 +209,9 @@ impl<'shape> Serializer<'shape> for TomlSerializer<'shape> {
     fn serialize_unit_variant(
         &mut self,
         _variant_index: usize,
-        _variant_name: &'shape str,
+        variant_name: &'shape str,
     ) -> Result<(), Self::Error> {
-        todo!()
+        self.write_value(variant_name)
     }
 
     fn start_object(&mut self, _len: Option<usize>) -> Result<(), Self::Error> {
@@ -221,7 +225,7 @@ impl<'shape> Serializer<'shape> for TomlSerializer<'shape> {
     }
 
     fn start_array(&mut self, _len: Option<usize>) -> Result<(), Self::Error> {
-        self.set_current_item(toml_edit::array());
+        self.set_current_item(Value::Array(Array::new()));
 
         Ok(())
     }
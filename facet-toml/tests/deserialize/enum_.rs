@@ -205,3 +205,153 @@ fn test_enum_root() {
     assert_eq!(facet_toml::from_str::<Root>("B = 2")?, Root::B(2));
     assert_eq!(facet_toml::from_str::<Root>("[C]")?, Root::C);
 }
+
+#[test]
+fn test_internally_tagged_enum() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        value: Shape,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Circle {
+        radius: u32,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[facet(tag = "type")]
+    #[repr(u8)]
+    enum Shape {
+        Empty,
+        Circle(Circle),
+        Rect { w: u32, h: u32 },
+    }
+
+    assert_eq!(
+        facet_toml::from_str::<Root>("value = { type = 'Empty' }")?,
+        Root {
+            value: Shape::Empty
+        },
+    );
+    assert_eq!(
+        facet_toml::from_str::<Root>("value = { radius = 3, type = 'Circle' }")?,
+        Root {
+            value: Shape::Circle(Circle { radius: 3 })
+        },
+    );
+    assert_eq!(
+        facet_toml::from_str::<Root>(
+            r#"
+            [value]
+            w = 1
+            type = 'Rect'
+            h = 2
+            "#
+        )?,
+        Root {
+            value: Shape::Rect { w: 1, h: 2 }
+        },
+    );
+
+    assert_eq!(
+        facet_toml::from_str::<Root>("value = { w = 1, h = 2 }")
+            .unwrap_err()
+            .kind,
+        TomlDeErrorKind::ExpectedTagWithName("type")
+    );
+}
+
+#[test]
+fn test_adjacently_tagged_enum() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        value: Message,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[facet(tag = "t", content = "c")]
+    #[repr(u8)]
+    enum Message {
+        Empty,
+        Count(u32),
+        Pair(u32, String),
+        Rect { w: u32, h: u32 },
+    }
+
+    assert_eq!(
+        facet_toml::from_str::<Root>("value = { t = 'Empty' }")?,
+        Root {
+            value: Message::Empty
+        },
+    );
+    assert_eq!(
+        facet_toml::from_str::<Root>("value = { c = 3, t = 'Count' }")?,
+        Root {
+            value: Message::Count(3)
+        },
+    );
+    assert_eq!(
+        facet_toml::from_str::<Root>("value = { t = 'Pair', c = { 0 = 3, 1 = 'x' } }")?,
+        Root {
+            value: Message::Pair(3, "x".to_string())
+        },
+    );
+    assert_eq!(
+        facet_toml::from_str::<Root>("value = { t = 'Rect', c = { w = 1, h = 2 } }")?,
+        Root {
+            value: Message::Rect { w: 1, h: 2 }
+        },
+    );
+}
+
+#[test]
+fn test_untagged_enum() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        value: Untagged,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[facet(untagged)]
+    #[repr(u8)]
+    enum Untagged {
+        Number(u64),
+        Text(String),
+        Rect { w: u32, h: u32 },
+        Named { name: String },
+    }
+
+    assert_eq!(
+        facet_toml::from_str::<Root>("value = 7")?,
+        Root {
+            value: Untagged::Number(7)
+        },
+    );
+    assert_eq!(
+        facet_toml::from_str::<Root>("value = 'hi'")?,
+        Root {
+            value: Untagged::Text("hi".to_string())
+        },
+    );
+    assert_eq!(
+        facet_toml::from_str::<Root>("value = { h = 2, w = 1 }")?,
+        Root {
+            value: Untagged::Rect { w: 1, h: 2 }
+        },
+    );
+    assert_eq!(
+        facet_toml::from_str::<Root>("value = { name = 'bob' }")?,
+        Root {
+            value: Untagged::Named {
+                name: "bob".to_string()
+            }
+        },
+    );
+
+    assert!(matches!(
+        facet_toml::from_str::<Root>("value = true")
+            .unwrap_err()
+            .kind,
+        TomlDeErrorKind::NoMatchingVariant(_)
+    ));
+}
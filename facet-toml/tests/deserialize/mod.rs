@@ -5,4 +5,5 @@ mod list;
 mod map;
 mod option;
 mod scalar;
+mod set;
 mod struct_;
@@ -0,0 +1,53 @@
+//! Tests for TOML values to sets.
+
+use std::collections::{BTreeSet, HashSet};
+
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_toml::TomlDeErrorKind;
+
+#[test]
+fn test_scalar_set() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        values: HashSet<String>,
+    }
+
+    assert_eq!(
+        facet_toml::from_str::<Root>("values = ['a', 'b', 'a']")?,
+        Root {
+            values: HashSet::from(["a".to_string(), "b".to_string()])
+        },
+    );
+    assert_eq!(
+        facet_toml::from_str::<Root>("values = []")?,
+        Root {
+            values: HashSet::new()
+        },
+    );
+
+    assert_eq!(
+        facet_toml::from_str::<Root>("values = 'a'")
+            .unwrap_err()
+            .kind,
+        TomlDeErrorKind::ExpectedType {
+            expected: "array",
+            got: "string"
+        }
+    );
+}
+
+#[test]
+fn test_ordered_set() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        values: BTreeSet<u16>,
+    }
+
+    assert_eq!(
+        facet_toml::from_str::<Root>("values = [3, 1, 2]")?,
+        Root {
+            values: BTreeSet::from([1, 2, 3])
+        },
+    );
+}
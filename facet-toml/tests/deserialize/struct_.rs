@@ -116,6 +116,29 @@ fn test_nested_unit_struct() {
     );
 }
 
+#[test]
+fn test_tuple_struct_from_array() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        point: Point,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Point(i32, i32);
+
+    assert_eq!(
+        facet_toml::from_str::<Root>("point = [1, 2]")?,
+        Root { point: Point(1, 2) },
+    );
+
+    let err = facet_toml::from_str::<Root>("point = [1, 2, 3]").unwrap_err();
+    assert!(matches!(err.kind, TomlDeErrorKind::GenericReflect(_)));
+    assert!(
+        err.message()
+            .contains("tuple struct field count doesn't match array length")
+    );
+}
+
 #[test]
 fn test_root_struct_multiple_fields() {
     #[derive(Debug, Facet, PartialEq)]
@@ -281,3 +304,74 @@ fn test_default_struct_fields() {
         },
     );
 }
+
+#[test]
+fn test_aliased_struct_fields() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        #[facet(alias = "colour")]
+        color: String,
+        #[facet(alias = "size")]
+        width: u32,
+    }
+
+    assert_eq!(
+        facet_toml::from_str::<Root>(
+            r#"
+            colour = "red"
+            width = 3
+            "#
+        )?,
+        Root {
+            color: "red".to_owned(),
+            width: 3,
+        },
+    );
+
+    assert_eq!(
+        facet_toml::from_str::<Root>(
+            r#"
+            color = "blue"
+            size = 4
+            "#
+        )?,
+        Root {
+            color: "blue".to_owned(),
+            width: 4,
+        },
+    );
+}
+
+#[test]
+fn test_proxied_struct_fields() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[facet(transparent)]
+    struct Seconds(u64);
+
+    impl TryFrom<Seconds> for std::time::Duration {
+        type Error = std::convert::Infallible;
+
+        fn try_from(value: Seconds) -> Result<Self, Self::Error> {
+            Ok(std::time::Duration::from_secs(value.0))
+        }
+    }
+
+    impl From<&std::time::Duration> for Seconds {
+        fn from(value: &std::time::Duration) -> Self {
+            Seconds(value.as_secs())
+        }
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        #[facet(opaque, proxy = Seconds)]
+        timeout: std::time::Duration,
+    }
+
+    assert_eq!(
+        facet_toml::from_str::<Root>("timeout = 30")?,
+        Root {
+            timeout: std::time::Duration::from_secs(30),
+        },
+    );
+}
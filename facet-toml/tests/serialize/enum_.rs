@@ -205,3 +205,116 @@ fn test_enum_root() {
     assert_eq!(facet_toml::from_str::<Root>("B = 2")?, Root::B(2));
     assert_eq!(facet_toml::from_str::<Root>("[C]")?, Root::C);
 }
+
+#[test]
+fn test_internally_tagged_enum() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        value: Shape,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Circle {
+        radius: u32,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[facet(tag = "type")]
+    #[repr(u8)]
+    enum Shape {
+        Empty,
+        Circle(Circle),
+        Rect { w: u32, h: u32 },
+    }
+
+    crate::assert_serialize!(
+        Root,
+        Root {
+            value: Shape::Empty
+        },
+    );
+    crate::assert_serialize!(
+        Root,
+        Root {
+            value: Shape::Circle(Circle { radius: 3 })
+        },
+    );
+    crate::assert_serialize!(
+        Root,
+        Root {
+            value: Shape::Rect { w: 1, h: 2 }
+        },
+    );
+}
+
+#[test]
+fn test_adjacently_tagged_enum() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        value: Message,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[facet(tag = "t", content = "c")]
+    #[repr(u8)]
+    enum Message {
+        Empty,
+        Count(u32),
+        Rect { w: u32, h: u32 },
+    }
+
+    crate::assert_serialize!(
+        Root,
+        Root {
+            value: Message::Empty
+        },
+    );
+    crate::assert_serialize!(
+        Root,
+        Root {
+            value: Message::Count(3)
+        },
+    );
+    crate::assert_serialize!(
+        Root,
+        Root {
+            value: Message::Rect { w: 1, h: 2 }
+        },
+    );
+}
+
+#[test]
+fn test_untagged_enum() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        value: Untagged,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[facet(untagged)]
+    #[repr(u8)]
+    enum Untagged {
+        Number(u64),
+        Text(String),
+        Rect { w: u32, h: u32 },
+    }
+
+    crate::assert_serialize!(
+        Root,
+        Root {
+            value: Untagged::Number(7)
+        },
+    );
+    crate::assert_serialize!(
+        Root,
+        Root {
+            value: Untagged::Text("hi".to_string())
+        },
+    );
+    crate::assert_serialize!(
+        Root,
+        Root {
+            value: Untagged::Rect { w: 1, h: 2 }
+        },
+    );
+}
@@ -4,6 +4,7 @@ mod list;
 mod map;
 mod option;
 mod scalar;
+mod set;
 mod struct_;
 
 /// Assert that the TOML used to serialize a value can be used to deserialize the value too.
@@ -0,0 +1,30 @@
+//! Tests for TOML values to sets.
+
+use std::collections::BTreeSet;
+
+use facet::Facet;
+
+use crate::assert_serialize;
+use facet_testhelpers::test;
+
+#[test]
+fn test_scalar_set() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        values: BTreeSet<i32>,
+    }
+
+    assert_serialize!(
+        Root,
+        Root {
+            values: BTreeSet::new()
+        },
+    );
+
+    assert_serialize!(
+        Root,
+        Root {
+            values: [3, 1, -1].into()
+        },
+    );
+}
@@ -6,6 +6,9 @@ use facet_core::{Def, Facet, Type, UserType};
 use facet_reflect::{HeapValue, Wip};
 use log::*;
 
+mod serialize;
+pub use serialize::*;
+
 #[cfg(test)]
 mod tests;
 
@@ -190,7 +193,7 @@ fn deserialize_value<'mem, 'shape>(
 
             // Process flat fields
             for key in values.keys() {
-                if let Some(index) = wip.field_index(key) {
+                if let Some(index) = wip.deserializable_field_index(key) {
                     let value = values.get(key).unwrap(); // Safe because we're iterating over keys
                     let field = wip.field(index)?;
                     wip = deserialize_scalar_field(key, value, field)?;
@@ -201,7 +204,7 @@ fn deserialize_value<'mem, 'shape>(
 
             // Process nested fields
             for key in values.nested.keys() {
-                if let Some(index) = wip.field_index(key) {
+                if let Some(index) = wip.deserializable_field_index(key) {
                     let nested_values = values.nested.get(key).unwrap(); // Safe because we're iterating over keys
                     let field = wip.field(index)?;
                     wip = deserialize_nested_field(key, nested_values, field)?;
@@ -274,7 +277,7 @@ fn deserialize_nested_field<'mem, 'shape>(
 
             // Process flat fields in the nested structure
             for nested_key in nested_values.keys() {
-                if let Some(index) = current_wip.field_index(nested_key) {
+                if let Some(index) = current_wip.deserializable_field_index(nested_key) {
                     let value = nested_values.get(nested_key).unwrap(); // Safe because we're iterating over keys
                     let field_wip = current_wip.field(index)?;
                     current_wip = deserialize_scalar_field(nested_key, value, field_wip)?
@@ -283,7 +286,7 @@ fn deserialize_nested_field<'mem, 'shape>(
 
             // Process deeper nested fields
             for nested_key in nested_values.nested.keys() {
-                if let Some(index) = current_wip.field_index(nested_key) {
+                if let Some(index) = current_wip.deserializable_field_index(nested_key) {
                     let deeper_nested = nested_values.nested.get(nested_key).unwrap(); // Safe because we're iterating over keys
                     let field_wip = current_wip.field(index)?;
                     current_wip = deserialize_nested_field(nested_key, deeper_nested, field_wip)?;
@@ -303,15 +306,15 @@ fn deserialize_nested_field<'mem, 'shape>(
     }
 }
 
-/// Errors that can occur during URL encoded form data deserialization.
+/// Errors that can occur during URL encoded form data serialization and deserialization.
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum UrlEncodedError<'shape> {
     /// The field value couldn't be parsed as a number.
     InvalidNumber(String, String),
-    /// The shape is not supported for deserialization.
+    /// The shape is not supported for serialization or deserialization.
     UnsupportedShape(String),
-    /// The type is not supported for deserialization.
+    /// The type is not supported for serialization or deserialization.
     UnsupportedType(String),
     /// Reflection error
     ReflectError(facet_reflect::ReflectError<'shape>),
@@ -0,0 +1,297 @@
+use facet_core::Facet;
+use facet_reflect::Peek;
+use facet_serialize::{Serializer, serialize_iterative};
+
+use crate::UrlEncodedError;
+
+/// Serializes a struct (or a map) into URL encoded form data, with lists written like
+/// `tags[]=a&tags[]=b`.
+///
+/// The fields of nested structs use the bracket notation [`from_str`](crate::from_str) reads,
+/// like `user[address][city]=Anytown`. `None` leaves its field out, and names and values are
+/// percent-encoded, with spaces as `+`.
+///
+/// ```
+/// use facet::Facet;
+/// use facet_urlencoded::to_string;
+///
+/// #[derive(Facet)]
+/// struct SearchParams {
+///     query: String,
+///     page: u64,
+///     lang: Option<String>,
+/// }
+///
+/// let params = SearchParams { query: "rust & facet".to_string(), page: 2, lang: None };
+/// assert_eq!(to_string(&params).unwrap(), "query=rust+%26+facet&page=2");
+/// ```
+pub fn to_string<'a, T: Facet<'a>>(value: &'a T) -> Result<String, UrlEncodedError<'static>> {
+    UrlEncodedOptions::new().to_string(value)
+}
+
+/// How to write URL encoded form data.
+///
+/// ```
+/// use facet::Facet;
+/// use facet_urlencoded::{ListStyle, UrlEncodedOptions};
+///
+/// #[derive(Facet)]
+/// struct Filter {
+///     tags: Vec<String>,
+/// }
+///
+/// let filter = Filter { tags: vec!["web".to_string(), "cli".to_string()] };
+/// let options = UrlEncodedOptions::new().lists(ListStyle::Indexed);
+/// assert_eq!(options.to_string(&filter).unwrap(), "tags[0]=web&tags[1]=cli");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct UrlEncodedOptions {
+    lists: ListStyle,
+}
+
+/// How the items of lists are named.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListStyle {
+    /// With empty brackets, like `tags[]=a&tags[]=b`. Items that aren't a single value, like
+    /// structs, still get their index, like `items[0][name]=a`, since their fields couldn't be
+    /// told apart otherwise.
+    Brackets,
+    /// With their index, like `tags[0]=a&tags[1]=b`
+    Indexed,
+}
+
+impl Default for UrlEncodedOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UrlEncodedOptions {
+    /// Lists written with empty brackets, like `tags[]=a`.
+    pub fn new() -> Self {
+        Self {
+            lists: ListStyle::Brackets,
+        }
+    }
+
+    /// Sets how the items of lists are named.
+    pub fn lists(mut self, lists: ListStyle) -> Self {
+        self.lists = lists;
+        self
+    }
+
+    /// Serializes a struct (or a map) into URL encoded form data.
+    pub fn to_string<'a, T: Facet<'a>>(
+        &self,
+        value: &'a T,
+    ) -> Result<String, UrlEncodedError<'static>> {
+        let mut serializer = UrlEncodedSerializer::new(*self);
+        serialize_iterative(Peek::new(value), &mut serializer)?;
+        Ok(serializer.output)
+    }
+}
+
+/// What the value being serialized is in.
+enum Frame {
+    /// A struct, or a map. The names of its fields come after the first `base` parts of the
+    /// name.
+    Object { base: usize },
+    /// A list, whose items come after the first `base` parts of the name
+    List { base: usize, next: usize },
+}
+
+/// Writes the `name=value` pairs of a value as the [`Serializer`] events come in.
+struct UrlEncodedSerializer {
+    options: UrlEncodedOptions,
+    /// What the value being serialized is in, innermost last
+    stack: Vec<Frame>,
+    /// The name of the next value, in parts: `user[address][city]` is `user`, `address`
+    /// and `city`
+    path: Vec<String>,
+    /// Whether the next value is the key of a map, which names the value after it
+    in_map_key: bool,
+    output: String,
+}
+
+impl UrlEncodedSerializer {
+    fn new(options: UrlEncodedOptions) -> Self {
+        Self {
+            options,
+            stack: Vec::new(),
+            path: Vec::new(),
+            in_map_key: false,
+            output: String::new(),
+        }
+    }
+
+    /// Names an item of a list, before its value. `single` is whether the item is a single
+    /// value rather than a struct or a list.
+    fn start_value(&mut self, single: bool) {
+        if let Some(Frame::List { base, next }) = self.stack.last_mut() {
+            self.path.truncate(*base);
+            if single && self.options.lists == ListStyle::Brackets {
+                self.path.push(String::new());
+            } else {
+                self.path.push(next.to_string());
+            }
+            *next += 1;
+        }
+    }
+
+    /// Writes a value as a `name=value` pair, or takes it as the name of the next value if
+    /// it's the key of a map.
+    fn write_value(&mut self, text: &str) -> Result<(), UrlEncodedError<'static>> {
+        if self.in_map_key {
+            if let Some(Frame::Object { base }) = self.stack.last() {
+                self.path.truncate(*base);
+            }
+            self.path.push(text.to_string());
+            return Ok(());
+        }
+        if self.stack.is_empty() {
+            return Err(UrlEncodedError::UnsupportedShape(
+                "Only structs and maps can be serialized as URL encoded form data".to_string(),
+            ));
+        }
+        self.start_value(true);
+
+        if !self.output.is_empty() {
+            self.output.push('&');
+        }
+        for (i, part) in self.path.iter().enumerate() {
+            if i > 0 {
+                self.output.push('[');
+            }
+            self.output
+                .extend(form_urlencoded::byte_serialize(part.as_bytes()));
+            if i > 0 {
+                self.output.push(']');
+            }
+        }
+        self.output.push('=');
+        self.output
+            .extend(form_urlencoded::byte_serialize(text.as_bytes()));
+        Ok(())
+    }
+}
+
+impl<'shape> Serializer<'shape> for UrlEncodedSerializer {
+    type Error = UrlEncodedError<'static>;
+
+    fn start_object(&mut self, _len: Option<usize>) -> Result<(), Self::Error> {
+        self.start_value(false);
+        self.stack.push(Frame::Object {
+            base: self.path.len(),
+        });
+        Ok(())
+    }
+
+    fn end_object(&mut self) -> Result<(), Self::Error> {
+        if let Some(Frame::Object { base } | Frame::List { base, .. }) = self.stack.pop() {
+            self.path.truncate(base);
+        }
+        Ok(())
+    }
+
+    fn start_array(&mut self, _len: Option<usize>) -> Result<(), Self::Error> {
+        if self.stack.is_empty() {
+            return Err(UrlEncodedError::UnsupportedShape(
+                "Only structs and maps can be serialized as URL encoded form data".to_string(),
+            ));
+        }
+        self.start_value(false);
+        self.stack.push(Frame::List {
+            base: self.path.len(),
+            next: 0,
+        });
+        Ok(())
+    }
+
+    fn end_array(&mut self) -> Result<(), Self::Error> {
+        self.end_object()
+    }
+
+    fn start_map(&mut self, len: Option<usize>) -> Result<(), Self::Error> {
+        // The keys of maps name their values, like the fields of structs
+        self.start_object(len)
+    }
+
+    fn end_map(&mut self) -> Result<(), Self::Error> {
+        self.end_object()
+    }
+
+    fn begin_map_key(&mut self) -> Result<(), Self::Error> {
+        self.in_map_key = true;
+        Ok(())
+    }
+
+    fn end_map_key(&mut self) -> Result<(), Self::Error> {
+        self.in_map_key = false;
+        Ok(())
+    }
+
+    fn serialize_field_name(&mut self, name: &'shape str) -> Result<(), Self::Error> {
+        if let Some(Frame::Object { base }) = self.stack.last() {
+            self.path.truncate(*base);
+        }
+        self.path.push(name.to_string());
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        &mut self,
+        _variant_index: usize,
+        variant_name: &'shape str,
+    ) -> Result<(), Self::Error> {
+        self.write_value(variant_name)
+    }
+
+    fn serialize_u64(&mut self, value: u64) -> Result<(), Self::Error> {
+        self.write_value(&value.to_string())
+    }
+
+    fn serialize_u128(&mut self, value: u128) -> Result<(), Self::Error> {
+        self.write_value(&value.to_string())
+    }
+
+    fn serialize_i64(&mut self, value: i64) -> Result<(), Self::Error> {
+        self.write_value(&value.to_string())
+    }
+
+    fn serialize_i128(&mut self, value: i128) -> Result<(), Self::Error> {
+        self.write_value(&value.to_string())
+    }
+
+    fn serialize_f64(&mut self, value: f64) -> Result<(), Self::Error> {
+        self.write_value(&value.to_string())
+    }
+
+    fn serialize_bool(&mut self, value: bool) -> Result<(), Self::Error> {
+        self.write_value(if value { "true" } else { "false" })
+    }
+
+    fn serialize_char(&mut self, value: char) -> Result<(), Self::Error> {
+        self.write_value(value.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(&mut self, value: &str) -> Result<(), Self::Error> {
+        self.write_value(value)
+    }
+
+    fn serialize_bytes(&mut self, _value: &[u8]) -> Result<(), Self::Error> {
+        Err(UrlEncodedError::UnsupportedType(
+            "URL encoded form data has no byte arrays".to_string(),
+        ))
+    }
+
+    fn serialize_none(&mut self) -> Result<(), Self::Error> {
+        // Left out, along with its name. Items of lists still count towards the indices of
+        // those after them.
+        self.start_value(true);
+        Ok(())
+    }
+
+    fn serialize_unit(&mut self) -> Result<(), Self::Error> {
+        self.write_value("")
+    }
+}
@@ -1,4 +1,6 @@
-use crate::from_str;
+use std::collections::BTreeMap;
+
+use crate::{ListStyle, UrlEncodedOptions, from_str, to_string};
 use facet::Facet;
 use facet_testhelpers::test;
 
@@ -216,3 +218,108 @@ fn test_deep_nesting() {
         }
     );
 }
+
+#[test]
+fn test_serialize_round_trip() {
+    let order = OrderForm {
+        product_id: "ABC 123".to_string(),
+        quantity: 2,
+        user: User {
+            name: "John Doe".to_string(),
+            age: 30,
+            address: Address {
+                street: "123 Main St".to_string(),
+                city: "Anytown".to_string(),
+                zip: "12345".to_string(),
+            },
+        },
+    };
+
+    let query_string = to_string(&order)?;
+    assert_eq!(
+        query_string,
+        "product_id=ABC+123&quantity=2&user[name]=John+Doe&user[age]=30&user[address][street]=123+Main+St&user[address][city]=Anytown&user[address][zip]=12345"
+    );
+    assert_eq!(from_str::<OrderForm>(&query_string).unwrap(), order);
+}
+
+#[test]
+fn test_serialize_percent_encoding() {
+    let params = SearchParams {
+        query: "a&b=c+d/é%".to_string(),
+        page: 1,
+    };
+
+    let query_string = to_string(&params)?;
+    assert_eq!(query_string, "query=a%26b%3Dc%2Bd%2F%C3%A9%25&page=1");
+    assert_eq!(from_str::<SearchParams>(&query_string).unwrap(), params);
+
+    let map = BTreeMap::from([("key with spaces".to_string(), "[x]".to_string())]);
+    assert_eq!(to_string(&map)?, "key+with+spaces=%5Bx%5D");
+}
+
+#[test]
+fn test_serialize_lists_and_options() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Item {
+        name: String,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    enum Sort {
+        Newest,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Filter {
+        tags: Vec<String>,
+        items: Vec<Item>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+        sort: Sort,
+        exact: bool,
+    }
+
+    let filter = Filter {
+        tags: vec!["web".to_string(), "cli".to_string()],
+        items: vec![
+            Item {
+                name: "a".to_string(),
+            },
+            Item {
+                name: "b".to_string(),
+            },
+        ],
+        limit: Some(10),
+        offset: None,
+        sort: Sort::Newest,
+        exact: false,
+    };
+
+    assert_eq!(
+        to_string(&filter)?,
+        "tags[]=web&tags[]=cli&items[0][name]=a&items[1][name]=b&limit=10&sort=Newest&exact=false"
+    );
+    assert_eq!(
+        UrlEncodedOptions::new()
+            .lists(ListStyle::Indexed)
+            .to_string(&filter)?,
+        "tags[0]=web&tags[1]=cli&items[0][name]=a&items[1][name]=b&limit=10&sort=Newest&exact=false"
+    );
+}
+
+#[test]
+fn test_serialize_unsupported_root() {
+    let result = to_string(&vec![1u32, 2]);
+    assert!(matches!(
+        result,
+        Err(crate::UrlEncodedError::UnsupportedShape(_))
+    ));
+
+    let result = to_string(&42u32);
+    assert!(matches!(
+        result,
+        Err(crate::UrlEncodedError::UnsupportedShape(_))
+    ));
+}
@@ -0,0 +1,848 @@
+//! Read XML documents into Rust values.
+
+use std::borrow::Cow;
+
+use facet_core::{
+    Def, EnumTagging, Facet, Field, FieldAttribute, FieldFlags, ScalarAffinity, ScalarDef,
+    SequenceType, Shape, StructKind, Type, UserType,
+};
+use facet_deserialize::{
+    DeserError, DeserErrorKind, Outcome, Scalar, Span, Spannable, Spanned, Tape,
+};
+use log::trace;
+use quick_xml::{
+    NsReader,
+    events::{BytesStart, Event},
+    name::ResolveResult,
+};
+
+use crate::{MAX_DEPTH, is_list, item_shape, local_name, namespaces, value_shape};
+
+/// Deserialize a value of type `T` from an XML string.
+///
+/// The root element is the value. In each element, attributes and child elements go to the
+/// fields they're named after, with every element of a list field being one of its items, and
+/// the text goes to the field marked `#[facet(text)]`. Names are matched by their namespace
+/// when the types declare the one the field's name is in, and by their local name otherwise.
+///
+/// Errors point at where in the input they come from, and strings written without escapes
+/// can be borrowed as `&str`.
+///
+/// # Example
+/// ```rust
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Server {
+///     #[facet(attribute)]
+///     host: String,
+///     port: u16,
+/// }
+///
+/// let server: Server =
+///     facet_xml::from_str(r#"<Server host="localhost"><port>8080</port></Server>"#).unwrap();
+/// assert_eq!(server.port, 8080);
+/// ```
+pub fn from_str<'input, 'facet, 'shape, T: Facet<'facet>>(
+    xml: &'input str,
+) -> Result<T, DeserError<'input, 'shape>>
+where
+    'input: 'facet,
+{
+    log::trace!("Entering `from_str` function");
+
+    let input = xml.as_bytes();
+    let root = match parse(xml) {
+        Ok(root) => root,
+        Err(err) => return Err(DeserError::new(err.node, input, err.span, "xml")),
+    };
+    log::trace!("XML parsed");
+
+    let mut lowering = Lowering {
+        xml,
+        tape: Tape::new("xml", xml.len()),
+        depth: 0,
+        namespaces: Vec::new(),
+    };
+    if let Err(err) = lowering.element(&root, T::SHAPE) {
+        return Err(DeserError::new(err.node, input, err.span, "xml"));
+    }
+
+    facet_deserialize::deserialize(input, lowering.tape)
+}
+
+/// An element of a document, with where its parts are in the input.
+#[derive(Debug)]
+struct Element<'a> {
+    name: &'a str,
+    /// The namespace its name is in
+    namespace: Option<String>,
+    name_span: Span,
+    attributes: Vec<Attribute<'a>>,
+    content: Vec<Content<'a>>,
+    /// Where the element ends, after its end tag
+    end: usize,
+}
+
+#[derive(Debug)]
+struct Attribute<'a> {
+    name: &'a str,
+    /// The namespace its name is in
+    namespace: Option<String>,
+    name_span: Span,
+    value: Cow<'a, str>,
+    value_span: Span,
+}
+
+#[derive(Debug)]
+enum Content<'a> {
+    Element(Element<'a>),
+    /// Text, or a CDATA section
+    Text(Cow<'a, str>, Span),
+}
+
+impl<'a> Element<'a> {
+    fn children(&self) -> impl Iterator<Item = &Element<'a>> {
+        self.content.iter().filter_map(|content| match content {
+            Content::Element(element) => Some(element),
+            Content::Text(..) => None,
+        })
+    }
+
+    /// The attributes, leaving out those that declare namespaces.
+    fn attributes(&self) -> impl Iterator<Item = &Attribute<'a>> {
+        self.attributes
+            .iter()
+            .filter(|attribute| attribute.name != "xmlns" && !attribute.name.starts_with("xmlns:"))
+    }
+
+    /// The text of the element, and where it is. The whitespace between child elements
+    /// is only there to lay them out, so it's left out.
+    fn text(&self) -> Option<(Cow<'a, str>, Span)> {
+        let has_children = self.children().next().is_some();
+        let mut text: Option<(Cow<'a, str>, Span)> = None;
+        for content in &self.content {
+            let Content::Text(part, span) = content else {
+                continue;
+            };
+            if has_children && part.trim().is_empty() {
+                continue;
+            }
+            match &mut text {
+                Some((text, text_span)) => {
+                    text.to_mut().push_str(part);
+                    *text_span = Span::new(text_span.start(), span.end() - text_span.start());
+                }
+                None => text = Some((part.clone(), *span)),
+            }
+        }
+        text
+    }
+
+    /// Whether there's nothing in the element, which is how `None` is written.
+    fn is_empty(&self) -> bool {
+        self.attributes().next().is_none()
+            && self.children().next().is_none()
+            && self.text().is_none_or(|(text, _)| text.is_empty())
+    }
+}
+
+/// Parses a document into its root element.
+fn parse(xml: &str) -> Result<Element<'_>, Spanned<DeserErrorKind<'static>>> {
+    let mut reader = NsReader::from_str(xml);
+    let mut open: Vec<Element> = Vec::new();
+    let mut root = None;
+    loop {
+        let start = reader.buffer_position() as usize;
+        let event = reader
+            .read_resolved_event()
+            .map(|(namespace, event)| (namespace_of(namespace), event));
+        let (namespace, event) = event.map_err(|err| {
+            let at = reader.error_position() as usize;
+            let end = (reader.buffer_position() as usize).max(at);
+            DeserErrorKind::InvalidSyntax(err.to_string()).with_span(Span::new(at, end - at))
+        })?;
+        let end = reader.buffer_position() as usize;
+        let element = match event {
+            Event::Start(tag) | Event::Empty(tag) if open.len() == MAX_DEPTH => {
+                let name = span_of(xml, tag.name().as_ref()).unwrap_or(Span::new(start, 0));
+                return Err(too_deep().with_span(name));
+            }
+            Event::Start(tag) => {
+                open.push(start_tag(xml, &reader, &tag, namespace)?);
+                continue;
+            }
+            Event::Empty(tag) => Element {
+                end,
+                ..start_tag(xml, &reader, &tag, namespace)?
+            },
+            Event::End(_) => {
+                // quick-xml checks that it matches the start tag
+                let Some(element) = open.pop() else {
+                    continue;
+                };
+                Element { end, ..element }
+            }
+            Event::Text(text) => {
+                let text = text.unescape().map_err(|err| {
+                    DeserErrorKind::InvalidSyntax(err.to_string())
+                        .with_span(Span::new(start, end - start))
+                })?;
+                let span = trimmed_span(xml, start, end);
+                match open.last_mut() {
+                    Some(parent) => parent.content.push(Content::Text(text, span)),
+                    None if text.trim().is_empty() => {}
+                    None => {
+                        return Err(DeserErrorKind::InvalidSyntax(
+                            "text outside of the root element".to_owned(),
+                        )
+                        .with_span(span));
+                    }
+                }
+                continue;
+            }
+            Event::CData(cdata) => {
+                let text = match cdata.into_inner() {
+                    Cow::Borrowed(bytes) => String::from_utf8_lossy(bytes),
+                    Cow::Owned(bytes) => Cow::Owned(String::from_utf8_lossy(&bytes).into_owned()),
+                };
+                let span = Span::new(start, end - start);
+                match open.last_mut() {
+                    Some(parent) => parent.content.push(Content::Text(text, span)),
+                    None => {
+                        return Err(DeserErrorKind::InvalidSyntax(
+                            "CDATA outside of the root element".to_owned(),
+                        )
+                        .with_span(span));
+                    }
+                }
+                continue;
+            }
+            Event::Eof => break,
+            // Declarations, comments, processing instructions and doctypes
+            _ => continue,
+        };
+        match open.last_mut() {
+            Some(parent) => parent.content.push(Content::Element(element)),
+            None if root.is_some() => {
+                return Err(DeserErrorKind::InvalidSyntax(
+                    "a document has a single root element".to_owned(),
+                )
+                .with_span(element.name_span));
+            }
+            None => root = Some(element),
+        }
+    }
+    if let Some(element) = open.last() {
+        return Err(DeserErrorKind::InvalidSyntax(format!(
+            "element `{}` is never closed",
+            element.name
+        ))
+        .with_span(element.name_span));
+    }
+    root.ok_or_else(|| {
+        DeserErrorKind::InvalidSyntax("the document has no root element".to_owned())
+            .with_span(Span::new(0, xml.len()))
+    })
+}
+
+/// An element, from its start tag.
+fn start_tag<'a>(
+    xml: &'a str,
+    reader: &NsReader<&'a [u8]>,
+    tag: &BytesStart<'a>,
+    namespace: Option<String>,
+) -> Result<Element<'a>, Spanned<DeserErrorKind<'static>>> {
+    let invalid = |message: &str| {
+        DeserErrorKind::InvalidSyntax(message.to_owned()).with_span(Span::new(0, xml.len()))
+    };
+    let name_span = span_of(xml, tag.name().into_inner())
+        .ok_or_else(|| invalid("a name that isn't in the input"))?;
+    let mut attributes = Vec::new();
+    for attribute in tag.attributes() {
+        let attribute = attribute
+            .map_err(|err| DeserErrorKind::InvalidSyntax(err.to_string()).with_span(name_span))?;
+        let attribute_span = span_of(xml, attribute.key.into_inner())
+            .ok_or_else(|| invalid("an attribute that isn't in the input"))?;
+        let value_span = span_of(xml, &attribute.value).unwrap_or(attribute_span);
+        let (namespace, _) = reader.resolve_attribute(attribute.key);
+        let namespace = namespace_of(namespace);
+        let value = attribute
+            .unescape_value()
+            .map_err(|err| DeserErrorKind::InvalidSyntax(err.to_string()).with_span(value_span))?;
+        attributes.push(Attribute {
+            name: &xml[attribute_span.start()..attribute_span.end()],
+            namespace,
+            name_span: attribute_span,
+            value: in_input(xml, value),
+            value_span,
+        });
+    }
+    Ok(Element {
+        name: &xml[name_span.start()..name_span.end()],
+        namespace,
+        name_span,
+        attributes,
+        content: Vec::new(),
+        end: name_span.end(),
+    })
+}
+
+/// Text quick-xml handed out, borrowed from the input if it's part of it.
+fn in_input<'a>(xml: &'a str, text: Cow<'_, str>) -> Cow<'a, str> {
+    match text {
+        Cow::Borrowed(text) => match span_of(xml, text.as_bytes()) {
+            Some(span) => Cow::Borrowed(&xml[span.start()..span.end()]),
+            None => Cow::Owned(text.to_owned()),
+        },
+        Cow::Owned(text) => Cow::Owned(text),
+    }
+}
+
+/// The namespace URI a name is bound to, if any.
+fn namespace_of(namespace: ResolveResult) -> Option<String> {
+    match namespace {
+        ResolveResult::Bound(namespace) => {
+            Some(String::from_utf8_lossy(namespace.into_inner()).into_owned())
+        }
+        ResolveResult::Unbound | ResolveResult::Unknown(_) => None,
+    }
+}
+
+/// Where the bytes of a name or value that quick-xml handed out are in the input, unless
+/// they aren't part of it.
+fn span_of(xml: &str, bytes: &[u8]) -> Option<Span> {
+    let start = (bytes.as_ptr() as usize).checked_sub(xml.as_ptr() as usize)?;
+    let end = start.checked_add(bytes.len())?;
+    (end <= xml.len()).then(|| Span::new(start, bytes.len()))
+}
+
+fn too_deep() -> DeserErrorKind<'static> {
+    DeserErrorKind::InvalidSyntax(format!("elements are nested more than {MAX_DEPTH} deep"))
+}
+
+/// Where some text is, leaving out the whitespace around it.
+fn trimmed_span(xml: &str, start: usize, end: usize) -> Span {
+    let text = &xml[start..end];
+    let trimmed = text.trim_start();
+    let start = start + text.len() - trimmed.len();
+    Span::new(start, trimmed.trim_end().len())
+}
+
+/// Lays out a document as the outcomes the deserializer expects for a given shape.
+///
+/// Where the attributes, children and text of an element go depends on the fields they're
+/// deserialized into, so the whole document is laid out ahead of time, following the shape of
+/// the value.
+struct Lowering<'a, 'shape> {
+    xml: &'a str,
+    tape: Tape,
+    /// How many elements deep the one being lowered is
+    depth: usize,
+    /// The namespaces declared by the types of the elements being lowered, innermost last
+    namespaces: Vec<(String, &'shape str)>,
+}
+
+type LoweringResult<'shape> = Result<(), Spanned<DeserErrorKind<'shape>>>;
+
+impl<'a, 'shape> Lowering<'a, 'shape> {
+    fn push(&mut self, outcome: Outcome<'static>, span: Span) {
+        self.tape.push(outcome, span);
+    }
+
+    /// Some text, as a value of type `shape`.
+    fn push_text(&mut self, shape: &Shape, text: &str, span: Span) {
+        if is_string(shape) {
+            self.push_str(text, span);
+        } else {
+            self.push(Outcome::Scalar(scalar(shape, text)), span);
+        }
+    }
+
+    /// Some text, as a string. It's borrowed from the input when it's written there as is.
+    fn push_str(&mut self, text: &str, span: Span) {
+        match span_of(self.xml, text.as_bytes()) {
+            Some(at) => self.tape.push_str(at, span),
+            None => self.push(
+                Outcome::Scalar(Scalar::String(Cow::Owned(text.to_owned()))),
+                span,
+            ),
+        }
+    }
+
+    fn push_key(&mut self, name: &str, span: Span) {
+        self.push(
+            Outcome::Scalar(Scalar::String(Cow::Owned(name.to_owned()))),
+            span,
+        );
+    }
+
+    /// Lowers an element one level deeper, unless that's deeper than elements may be nested.
+    fn nested<F>(&mut self, element: &Element<'a>, lower: F) -> LoweringResult<'shape>
+    where
+        F: FnOnce(&mut Self) -> LoweringResult<'shape>,
+    {
+        if self.depth == MAX_DEPTH {
+            return Err(too_deep().with_span(element.name_span));
+        }
+        self.depth += 1;
+        let res = lower(self);
+        self.depth -= 1;
+        res
+    }
+
+    /// An element, as a value of type `shape`.
+    fn element(
+        &mut self,
+        element: &Element<'a>,
+        shape: &'shape Shape<'shape>,
+    ) -> LoweringResult<'shape> {
+        self.nested(element, |this| {
+            // The namespaces its type declares are in scope for everything in it
+            let scope = this.namespaces.len();
+            this.namespaces.extend(namespaces(value_shape(shape)));
+            let res = this.element_in_scope(element, shape);
+            this.namespaces.truncate(scope);
+            res
+        })
+    }
+
+    fn element_in_scope(
+        &mut self,
+        element: &Element<'a>,
+        shape: &'shape Shape<'shape>,
+    ) -> LoweringResult<'shape> {
+        let mut shape = shape;
+        if let Def::Option(option_def) = shape.def {
+            if element.is_empty() {
+                self.push(Outcome::Scalar(Scalar::Null), element.name_span);
+                return Ok(());
+            }
+            shape = option_def.t();
+        }
+        // Transparent wrappers are deserialized as what they wrap
+        while let Some(inner) = shape.inner {
+            shape = inner();
+        }
+
+        if let Some(item_shape) = item_shape(shape) {
+            return self.items(element, |_| Some(item_shape));
+        }
+        if let Def::Map(map_def) = shape.def {
+            return self.map(element, map_def.v());
+        }
+        match shape.ty {
+            Type::User(UserType::Struct(struct_type)) => match struct_type.kind {
+                StructKind::Tuple | StructKind::TupleStruct => self.items(element, |index| {
+                    struct_type.fields.get(index).map(|field| field.shape())
+                }),
+                _ => self.object(element, shape, struct_type.fields),
+            },
+            Type::User(UserType::Enum(enum_type))
+                if shape.enum_tagging() == EnumTagging::External =>
+            {
+                let Some(child) = element.children().next() else {
+                    // A unit variant, by name
+                    return self.text(element, shape);
+                };
+                let name = local_name(child.name);
+                self.push(Outcome::ObjectStarted, child.name_span);
+                match enum_type
+                    .variants
+                    .iter()
+                    .find(|variant| variant.is_named(name))
+                {
+                    Some(variant) => {
+                        self.push_key(variant.name, child.name_span);
+                        match variant.data.kind {
+                            StructKind::Unit => {
+                                self.push(Outcome::Scalar(Scalar::Null), child.name_span)
+                            }
+                            StructKind::Tuple | StructKind::TupleStruct
+                                if variant.data.fields.len() == 1 =>
+                            {
+                                self.element(child, variant.data.fields[0].shape())?
+                            }
+                            StructKind::Tuple | StructKind::TupleStruct => self
+                                .items(child, |index| {
+                                    variant.data.fields.get(index).map(|field| field.shape())
+                                })?,
+                            _ => self.object(child, shape, variant.data.fields)?,
+                        }
+                    }
+                    None => {
+                        self.push_key(name, child.name_span);
+                        self.generic_element(child)?;
+                    }
+                }
+                self.push(Outcome::ObjectEnded, Span::new(element.end, 0));
+                Ok(())
+            }
+            Type::User(UserType::Enum(_)) => self.lower_generic(element),
+            Type::Sequence(SequenceType::Tuple(tuple_type)) => self.items(element, |index| {
+                tuple_type.fields.get(index).map(|field| field.shape())
+            }),
+            _ => self.text(element, shape),
+        }
+    }
+
+    /// An element holding a single value, as its text.
+    fn text(
+        &mut self,
+        element: &Element<'a>,
+        shape: &'shape Shape<'shape>,
+    ) -> LoweringResult<'shape> {
+        match element.text() {
+            Some((text, span)) => {
+                self.push_text(shape, &text, span);
+                Ok(())
+            }
+            None if is_string(shape) => {
+                self.push(
+                    Outcome::Scalar(Scalar::String(Cow::Borrowed(""))),
+                    Span::new(element.end, 0),
+                );
+                Ok(())
+            }
+            None => Err(DeserErrorKind::MissingValue {
+                expected: "text",
+                field: format!("{} ({shape})", element.name),
+            }
+            .with_span(element.name_span)),
+        }
+    }
+
+    /// Child elements, as the items of a list, whatever they're named.
+    fn items<F>(&mut self, element: &Element<'a>, item_shape: F) -> LoweringResult<'shape>
+    where
+        F: Fn(usize) -> Option<&'shape Shape<'shape>>,
+    {
+        self.push(Outcome::ListStarted, element.name_span);
+        for (index, child) in element.children().enumerate() {
+            match item_shape(index) {
+                Some(shape) => self.element(child, shape)?,
+                None => self.generic_element(child)?,
+            }
+        }
+        self.push(Outcome::ListEnded, Span::new(element.end, 0));
+        Ok(())
+    }
+
+    /// An element, as a struct or the fields of an enum variant: its attributes and children
+    /// go to the fields they're named after, and its text to the text field.
+    fn object(
+        &mut self,
+        element: &Element<'a>,
+        shape: &'shape Shape<'shape>,
+        fields: &'shape [Field<'shape>],
+    ) -> LoweringResult<'shape> {
+        self.push(Outcome::ObjectStarted, element.name_span);
+        let mut present = Vec::new();
+        for attribute in element.attributes() {
+            let name = local_name(attribute.name);
+            match self.find_field(fields, name, attribute.namespace.as_deref(), true) {
+                Some(field) => {
+                    present.push(field.name);
+                    self.push_key(field.name, attribute.name_span);
+                    self.push_text(field.shape(), &attribute.value, attribute.value_span);
+                }
+                None => {
+                    // Left for the deserializer to skip, or to report
+                    self.push_key(name, attribute.name_span);
+                    self.push_str(&attribute.value, attribute.value_span);
+                }
+            }
+        }
+        if let Some(field) = text_field_of(fields) {
+            let text = match element.text() {
+                Some(text) => Some(text),
+                // An empty element has empty text, unless the text is optional
+                None if is_string(field.shape())
+                    && !matches!(field.shape().def, Def::Option(_)) =>
+                {
+                    Some((Cow::Borrowed(""), Span::new(element.end, 0)))
+                }
+                None => None,
+            };
+            if let Some((text, span)) = text {
+                present.push(field.name);
+                self.push_key(field.name, Span::new(span.start(), 0));
+                self.push_text(field.shape(), &text, span);
+            }
+        } else if let Some((_, span)) = element.text() {
+            if shape.has_deny_unknown_fields_attr() {
+                return Err(DeserErrorKind::UnknownField {
+                    field_name: "text".to_owned(),
+                    shape,
+                }
+                .with_span(span));
+            }
+            trace!("Ignoring the text of {}", shape);
+        }
+        self.children(element, fields, &mut present)?;
+
+        // `None` and empty lists are written as no element at all
+        let end = Span::new(element.end, 0);
+        for field in absent_fields(fields, &present) {
+            let shape = field.shape();
+            if matches!(shape.def, Def::Option(_)) {
+                self.push_key(field.name, end);
+                self.push(Outcome::Scalar(Scalar::Null), end);
+            } else if matches!(shape.def, Def::List(_) | Def::Set(_)) {
+                self.push_key(field.name, end);
+                self.push(Outcome::ListStarted, end);
+                self.push(Outcome::ListEnded, end);
+            }
+        }
+        self.push(Outcome::ObjectEnded, end);
+        Ok(())
+    }
+
+    /// Child elements, as the fields they're named after. The elements of a list field are
+    /// gathered into one list, wherever they are.
+    fn children(
+        &mut self,
+        element: &Element<'a>,
+        fields: &'shape [Field<'shape>],
+        present: &mut Vec<&'shape str>,
+    ) -> LoweringResult<'shape> {
+        let mut listed: Vec<(&str, Option<&str>)> = Vec::new();
+        for child in element.children() {
+            let name = local_name(child.name);
+            let namespace = child.namespace.as_deref();
+            if listed.contains(&(name, namespace)) {
+                continue;
+            }
+            let same_name = || {
+                element
+                    .children()
+                    .filter(|other| {
+                        local_name(other.name) == name && other.namespace.as_deref() == namespace
+                    })
+                    .collect::<Vec<_>>()
+            };
+            match self.find_field(fields, name, namespace, false) {
+                Some(field) if is_list(value_shape(field.shape())) => {
+                    listed.push((name, namespace));
+                    present.push(field.name);
+                    self.push_key(field.name, child.name_span);
+                    self.element_list(&same_name(), field.shape())?;
+                }
+                Some(field) => {
+                    present.push(field.name);
+                    self.push_key(field.name, child.name_span);
+                    self.element(child, field.shape())?;
+                }
+                None => {
+                    // Left for the deserializer to skip, or to report
+                    listed.push((name, namespace));
+                    self.push_key(name, child.name_span);
+                    match &same_name()[..] {
+                        [child] => self.generic_element(child)?,
+                        children => {
+                            self.push(Outcome::ListStarted, child.name_span);
+                            for child in children {
+                                self.generic_element(child)?;
+                            }
+                            let end = children.last().map_or(child.end, |child| child.end);
+                            self.push(Outcome::ListEnded, Span::new(end, 0));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Elements that are each an item of a list.
+    fn element_list(
+        &mut self,
+        elements: &[&Element<'a>],
+        shape: &'shape Shape<'shape>,
+    ) -> LoweringResult<'shape> {
+        let item_shape = item_shape(value_shape(shape));
+        let start = elements
+            .first()
+            .map_or(0, |element| element.name_span.start());
+        self.push(Outcome::ListStarted, Span::new(start, 0));
+        for element in elements {
+            match item_shape {
+                Some(item_shape) => self.element(element, item_shape)?,
+                None => self.generic_element(element)?,
+            }
+        }
+        let end = elements.last().map_or(start, |element| element.end);
+        self.push(Outcome::ListEnded, Span::new(end, 0));
+        Ok(())
+    }
+
+    /// An element, as a map with an entry for each of its children.
+    fn map(
+        &mut self,
+        element: &Element<'a>,
+        value_shape: &'shape Shape<'shape>,
+    ) -> LoweringResult<'shape> {
+        self.push(Outcome::ObjectStarted, element.name_span);
+        for child in element.children() {
+            self.push_key(child.name, child.name_span);
+            self.element(child, value_shape)?;
+        }
+        self.push(Outcome::ObjectEnded, Span::new(element.end, 0));
+        Ok(())
+    }
+
+    /// An element whose shape isn't known, as the value it looks like: its text, or an
+    /// object of its attributes and children.
+    fn generic_element(&mut self, element: &Element<'a>) -> LoweringResult<'shape> {
+        self.nested(element, |this| this.lower_generic(element))
+    }
+
+    fn lower_generic(&mut self, element: &Element<'a>) -> LoweringResult<'shape> {
+        if element.attributes().next().is_none() && element.children().next().is_none() {
+            match element.text() {
+                Some((text, span)) => self.push_str(&text, span),
+                None => self.push(Outcome::Scalar(Scalar::Null), element.name_span),
+            }
+            return Ok(());
+        }
+        self.push(Outcome::ObjectStarted, element.name_span);
+        for attribute in element.attributes() {
+            self.push_key(local_name(attribute.name), attribute.name_span);
+            self.push_str(&attribute.value, attribute.value_span);
+        }
+        self.children(element, &[], &mut Vec::new())?;
+        self.push(Outcome::ObjectEnded, Span::new(element.end, 0));
+        Ok(())
+    }
+
+    /// The field an attribute or element is for, by its local name and namespace, looking
+    /// into flattened structs too.
+    fn find_field(
+        &self,
+        fields: &'shape [Field<'shape>],
+        name: &str,
+        namespace: Option<&str>,
+        attribute: bool,
+    ) -> Option<&'shape Field<'shape>> {
+        fields
+            .iter()
+            .find(|field| {
+                (field.is_named(name) || local_name(field.name) == name)
+                    && !field.should_skip_deserializing()
+                    && self
+                        .field_namespace(field, attribute)
+                        .is_none_or(|uri| namespace == Some(uri))
+            })
+            .or_else(|| {
+                fields
+                    .iter()
+                    .filter(|field| field.flags.contains(FieldFlags::FLATTEN))
+                    .find_map(|field| match field.shape().ty {
+                        Type::User(UserType::Struct(struct_type)) => {
+                            self.find_field(struct_type.fields, name, namespace, attribute)
+                        }
+                        _ => None,
+                    })
+            })
+    }
+
+    /// The namespace the name of a field is in, when the types around it declare it. Child
+    /// elements without a prefix are in the default namespace, and attributes in none.
+    fn field_namespace(&self, field: &Field<'shape>, attribute: bool) -> Option<&'shape str> {
+        let declaration = match field.name.split_once(':') {
+            Some((prefix, _)) => format!("xmlns:{prefix}"),
+            None if attribute => return None,
+            None => "xmlns".to_owned(),
+        };
+        // The type of a child element declares namespaces on the element itself
+        let own = if attribute {
+            Vec::new()
+        } else {
+            namespaces(value_shape(field.shape()))
+        };
+        own.iter()
+            .chain(self.namespaces.iter().rev())
+            .find(|(key, _)| *key == declaration)
+            .map(|(_, uri)| *uri)
+    }
+}
+
+/// Whether values of type `shape` are text, which is taken as it is.
+fn is_string(shape: &Shape) -> bool {
+    let shape = value_shape(shape);
+    shape.is_type::<&str>()
+        || matches!(
+            shape.def,
+            Def::Scalar(ScalarDef {
+                affinity: ScalarAffinity::String(_),
+                ..
+            })
+        )
+}
+
+/// The value some text stands for, as a value of type `shape`. Text is taken as it is for
+/// strings, and otherwise as the number or boolean it spells, around whitespace.
+fn scalar(shape: &Shape, text: &str) -> Scalar<'static> {
+    if is_string(shape) {
+        return Scalar::String(Cow::Owned(text.to_owned()));
+    }
+    let trimmed = text.trim();
+    if trimmed.is_empty() && matches!(shape.def, Def::Option(_)) {
+        return Scalar::Null;
+    }
+    if value_shape(shape).is_type::<bool>() {
+        match trimmed {
+            "true" | "1" => return Scalar::Bool(true),
+            "false" | "0" => return Scalar::Bool(false),
+            _ => {}
+        }
+    }
+    if let Ok(v) = trimmed.parse::<u64>() {
+        return Scalar::U64(v);
+    }
+    if let Ok(v) = trimmed.parse::<i64>() {
+        return Scalar::I64(v);
+    }
+    if let Ok(v) = trimmed.parse::<f64>() {
+        return Scalar::F64(v);
+    }
+    Scalar::String(Cow::Owned(trimmed.to_owned()))
+}
+
+/// The fields that nothing in an element went to, looking into flattened structs too.
+fn absent_fields<'shape>(
+    fields: &'shape [Field<'shape>],
+    present: &[&str],
+) -> Vec<&'shape Field<'shape>> {
+    let mut absent = Vec::new();
+    for field in fields {
+        if field.flags.contains(FieldFlags::FLATTEN) {
+            if let Type::User(UserType::Struct(struct_type)) = field.shape().ty {
+                absent.extend(absent_fields(struct_type.fields, present));
+            }
+        } else if !present.contains(&field.name) && !field.should_skip_deserializing() {
+            absent.push(field);
+        }
+    }
+    absent
+}
+
+/// The field that takes the text of an element, marked `#[facet(text)]`.
+fn text_field_of<'shape>(fields: &'shape [Field<'shape>]) -> Option<&'shape Field<'shape>> {
+    fields.iter().find_map(|field| {
+        if field.flags.contains(FieldFlags::FLATTEN) {
+            match field.shape().ty {
+                Type::User(UserType::Struct(struct_type)) => text_field_of(struct_type.fields),
+                _ => None,
+            }
+        } else if field
+            .attributes
+            .contains(&FieldAttribute::Arbitrary("text"))
+            && !field.should_skip_deserializing()
+        {
+            Some(field)
+        } else {
+            None
+        }
+    })
+}
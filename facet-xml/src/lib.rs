@@ -0,0 +1,124 @@
+#![warn(missing_docs)]
+#![forbid(unsafe_code)]
+#![doc = include_str!("../README.md")]
+
+pub use facet_deserialize::{DeserError, DeserErrorKind, DeserErrorMessage};
+
+use facet_core::{Def, Field, FieldAttribute, Shape, ShapeAttribute};
+
+mod deserialize;
+pub use deserialize::*;
+
+mod serialize;
+pub use serialize::*;
+
+/// How deep elements may be nested in a document. Reading one nested deeper is an error,
+/// rather than a stack overflow.
+const MAX_DEPTH: usize = 128;
+
+/// Where a field goes in the element of the struct it's in.
+enum Placement {
+    /// In a child element named after it, which is the default
+    Child,
+    /// As a `name="value"` attribute, from `#[facet(attribute)]`
+    Attribute,
+    /// As the text content, from `#[facet(text)]`
+    Text,
+}
+
+fn placement(field: Option<Field>) -> Placement {
+    // Fields without a `Field` name enum variants, whose content is an element of its own
+    let Some(field) = field else {
+        return Placement::Child;
+    };
+    if field
+        .attributes
+        .contains(&FieldAttribute::Arbitrary("attribute"))
+    {
+        Placement::Attribute
+    } else if field
+        .attributes
+        .contains(&FieldAttribute::Arbitrary("text"))
+    {
+        Placement::Text
+    } else {
+        Placement::Child
+    }
+}
+
+/// Whether values of type `shape` are lists of items, which in a field are an element per
+/// item.
+fn is_list(shape: &Shape) -> bool {
+    matches!(shape.def, Def::List(_) | Def::Set(_) | Def::Array(_))
+}
+
+/// The type of the items of the list `shape`.
+fn item_shape<'shape>(shape: &'shape Shape<'shape>) -> Option<&'shape Shape<'shape>> {
+    match shape.def {
+        Def::List(list_def) => Some(list_def.t()),
+        Def::Set(set_def) => Some(set_def.t()),
+        Def::Array(array_def) => Some(array_def.t()),
+        _ => None,
+    }
+}
+
+/// The type of the value held by `shape`, looking through `Option`s and transparent wrappers.
+fn value_shape<'shape>(mut shape: &'shape Shape<'shape>) -> &'shape Shape<'shape> {
+    loop {
+        shape = match (shape.def, shape.inner) {
+            (Def::Option(option_def), _) => option_def.t(),
+            (_, Some(inner)) => inner(),
+            _ => return shape,
+        }
+    }
+}
+
+/// The name of the root element for a value of type `shape`: the one given with
+/// `#[facet(name = "...")]`, or the name of the type, unless it can't be the name of an
+/// element, like that of a tuple.
+fn root_name(shape: &Shape) -> String {
+    let name = arbitrary_attributes(shape).find_map(|attr| {
+        let value = attr.strip_prefix("name")?.trim_start().strip_prefix('=')?;
+        Some(value.trim().trim_matches('"'))
+    });
+    if let Some(name) = name {
+        return name.to_owned();
+    }
+    let type_name = shape.to_string();
+    let name = type_name.split('<').next().unwrap_or_default();
+    let is_valid = name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if is_valid {
+        name.to_owned()
+    } else {
+        "value".to_owned()
+    }
+}
+
+/// The namespaces declared on `shape` with `#[facet(xmlns = "...")]` and
+/// `#[facet(xmlns:prefix = "...")]`, as the attributes that declare them.
+fn namespaces<'shape>(shape: &'shape Shape<'shape>) -> Vec<(String, &'shape str)> {
+    arbitrary_attributes(shape)
+        .filter_map(|attr| {
+            let (key, uri) = attr.split_once('=')?;
+            let key: String = key.chars().filter(|c| !c.is_whitespace()).collect();
+            let is_xmlns = key == "xmlns" || key.starts_with("xmlns:");
+            is_xmlns.then(|| (key, uri.trim().trim_matches('"')))
+        })
+        .collect()
+}
+
+fn arbitrary_attributes<'shape>(shape: &'shape Shape<'shape>) -> impl Iterator<Item = &'shape str> {
+    shape.attributes.iter().filter_map(|attr| match attr {
+        ShapeAttribute::Arbitrary(attr) => Some(*attr),
+        _ => None,
+    })
+}
+
+/// The name without its namespace prefix, which elements and attributes are matched by along
+/// with the namespace it resolves to.
+fn local_name(name: &str) -> &str {
+    name.rsplit_once(':').map_or(name, |(_, local)| local)
+}
@@ -0,0 +1,494 @@
+//! Create XML documents from Rust values.
+
+use std::{
+    error::Error,
+    fmt::{self, Display, Write},
+};
+
+use facet_core::{Facet, Field, Shape};
+use facet_reflect::Peek;
+use facet_serialize::{Serializer, serialize_iterative};
+
+use crate::{Placement, is_list, item_shape, namespaces, placement, root_name, value_shape};
+
+/// Error type for XML serialization.
+#[derive(Debug)]
+pub enum XmlSerError {
+    /// A list, struct or map is in a field that's an attribute or the text of an element,
+    /// which can only hold a single value.
+    NotAScalar {
+        /// The name of the field
+        field: String,
+    },
+    /// XML has no byte arrays.
+    UnsupportedByteArray,
+}
+
+impl Display for XmlSerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XmlSerError::NotAScalar { field } => write!(
+                f,
+                "field `{field}` holds more than a single value, so it can't be an attribute or text"
+            ),
+            XmlSerError::UnsupportedByteArray => write!(f, "XML doesn't support byte arrays"),
+        }
+    }
+}
+impl Error for XmlSerError {}
+
+/// A value, as the events of [`Serializer`] describe it. It's laid out as XML once complete,
+/// since where a value goes depends on the field it's in, not only on the value itself.
+#[derive(Debug)]
+enum Value<'shape> {
+    /// A scalar, as the text it's written as
+    Scalar(String),
+    /// `None`, or `()`
+    Nothing,
+    /// The fields of a struct. Those without a [`Field`] are the variant names of enums.
+    Object(Vec<(&'shape str, Option<Field<'shape>>, Value<'shape>)>),
+    List(Vec<Value<'shape>>),
+    Map(Vec<(String, Value<'shape>)>),
+}
+
+/// A value that's still being serialized.
+enum Partial<'shape> {
+    Object {
+        fields: Vec<(&'shape str, Option<Field<'shape>>, Value<'shape>)>,
+        /// The name of the field the next value goes to, and the field itself
+        next: Option<(&'shape str, Option<Field<'shape>>)>,
+    },
+    List(Vec<Value<'shape>>),
+    Map {
+        entries: Vec<(String, Value<'shape>)>,
+        /// The key the next value goes to
+        key: Option<String>,
+        in_key: bool,
+    },
+}
+
+/// Serializer for XML documents.
+///
+/// The value is the root element, named by `#[facet(name = "...")]` on its type, or after
+/// the type itself. In each element, fields with `#[facet(attribute)]` are attributes, the
+/// one with `#[facet(text)]` is the text content, and the others are child elements. A list
+/// in a field is an element per item, and `None` leaves its element or attribute out.
+/// Namespaces declared on a struct with `#[facet(xmlns = "...")]` or
+/// `#[facet(xmlns:prefix = "...")]` are attributes of its elements.
+pub struct XmlSerializer<'shape> {
+    /// The type of the root element
+    shape: &'shape Shape<'shape>,
+    /// The values being serialized, innermost last
+    stack: Vec<Partial<'shape>>,
+    /// The field whose name comes next
+    field: Option<Field<'shape>>,
+    /// The value, once complete
+    root: Option<Value<'shape>>,
+}
+
+impl<'shape> XmlSerializer<'shape> {
+    /// Create a new serializer for a value of type `shape`.
+    pub fn new(shape: &'shape Shape<'shape>) -> Self {
+        Self {
+            shape,
+            stack: Vec::new(),
+            field: None,
+            root: None,
+        }
+    }
+
+    /// Get the serialized XML string, on a single line.
+    pub fn into_string(self) -> Result<String, XmlSerError> {
+        self.write(None)
+    }
+
+    /// Get the serialized XML string, with each child element on a line of its own, indented
+    /// by two spaces per level.
+    pub fn into_string_pretty(self) -> Result<String, XmlSerError> {
+        self.write(Some("  "))
+    }
+
+    fn write(self, indent: Option<&str>) -> Result<String, XmlSerError> {
+        let mut writer = XmlWriter {
+            out: String::new(),
+            indent,
+            depth: 0,
+        };
+        let name = root_name(self.shape);
+        let value = self.root.unwrap_or(Value::Nothing);
+        writer.element(&name, &namespaces(self.shape), value)?;
+        if indent.is_some() {
+            writer.out.push('\n');
+        }
+        Ok(writer.out)
+    }
+
+    /// Put a complete value where it goes in the one it's in.
+    fn push_value(&mut self, value: Value<'shape>) -> Result<(), XmlSerError> {
+        match self.stack.last_mut() {
+            None => self.root = Some(value),
+            Some(Partial::Object { fields, next }) => {
+                let (name, field) = next.take().unwrap_or_default();
+                fields.push((name, field, value));
+            }
+            Some(Partial::List(items)) => items.push(value),
+            Some(Partial::Map {
+                entries,
+                key,
+                in_key,
+            }) => {
+                if *in_key {
+                    *key = Some(match value {
+                        Value::Scalar(key) => key,
+                        _ => String::new(),
+                    });
+                } else {
+                    entries.push((key.take().unwrap_or_default(), value));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn push_scalar(&mut self, value: impl Display) -> Result<(), XmlSerError> {
+        self.push_value(Value::Scalar(value.to_string()))
+    }
+}
+
+/// Writes out a complete value as elements.
+struct XmlWriter<'a> {
+    out: String,
+    /// What to indent child elements with, if they go on lines of their own
+    indent: Option<&'a str>,
+    depth: usize,
+}
+
+impl XmlWriter<'_> {
+    /// Writes the elements for a child field: one for its value, or one per item for a list.
+    fn field_elements(
+        &mut self,
+        name: &str,
+        field: Option<Field<'_>>,
+        value: Value<'_>,
+    ) -> Result<(), XmlSerError> {
+        let shape = field.map(|field| value_shape(field.shape()));
+        match value {
+            Value::Nothing if field.is_some() => {}
+            Value::List(items) if shape.is_some_and(is_list) => {
+                let namespaces = shape
+                    .and_then(item_shape)
+                    .map(|shape| namespaces(value_shape(shape)))
+                    .unwrap_or_default();
+                for item in items {
+                    self.element(name, &namespaces, item)?;
+                }
+            }
+            value => {
+                let namespaces = shape.map(namespaces).unwrap_or_default();
+                self.element(name, &namespaces, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes an element holding a value: its fields for a struct, an element per item or
+    /// entry for a list or a map, or its text for anything else.
+    fn element(
+        &mut self,
+        name: &str,
+        namespaces: &[(String, &str)],
+        value: Value<'_>,
+    ) -> Result<(), XmlSerError> {
+        if self.depth > 0 {
+            self.newline();
+        }
+        self.out.push('<');
+        self.out.push_str(name);
+        for (attribute, uri) in namespaces {
+            self.attribute(attribute, uri);
+        }
+
+        let mut text = None;
+        let mut children = Vec::new();
+        match value {
+            Value::Scalar(value) => text = Some(value),
+            Value::Nothing => {}
+            Value::Object(fields) => {
+                for (name, field, value) in fields {
+                    match placement(field) {
+                        Placement::Attribute => match value {
+                            Value::Nothing => {}
+                            value => self.attribute(name, &scalar(name, value)?),
+                        },
+                        Placement::Text => match value {
+                            Value::Nothing => {}
+                            value => text = Some(scalar(name, value)?),
+                        },
+                        Placement::Child => children.push((name.to_owned(), field, value)),
+                    }
+                }
+            }
+            Value::List(items) => {
+                children.extend(
+                    items
+                        .into_iter()
+                        .map(|item| ("item".to_owned(), None, item)),
+                );
+            }
+            Value::Map(entries) => {
+                children.extend(
+                    entries
+                        .into_iter()
+                        .filter(|(_, value)| !matches!(value, Value::Nothing))
+                        .map(|(key, value)| (key, None, value)),
+                );
+            }
+        }
+
+        self.out.push('>');
+        if let Some(text) = &text {
+            escape(&mut self.out, text, false);
+        }
+        // Text and child elements together are kept on one line, so that indenting the
+        // children doesn't add to the text
+        let indent = self.indent;
+        if text.is_some() {
+            self.indent = None;
+        }
+        let content_start = self.out.len();
+        self.depth += 1;
+        for (name, field, value) in children {
+            self.field_elements(&name, field, value)?;
+        }
+        self.depth -= 1;
+        self.indent = indent;
+        if text.is_none() && self.out.len() == content_start {
+            // Nothing in it after all
+            self.out.pop();
+            self.out.push_str("/>");
+            return Ok(());
+        }
+        if text.is_none() {
+            self.newline();
+        }
+        self.out.push_str("</");
+        self.out.push_str(name);
+        self.out.push('>');
+        Ok(())
+    }
+
+    fn attribute(&mut self, name: &str, value: &str) {
+        self.out.push(' ');
+        self.out.push_str(name);
+        self.out.push_str("=\"");
+        escape(&mut self.out, value, true);
+        self.out.push('"');
+    }
+
+    fn newline(&mut self) {
+        if let Some(indent) = self.indent {
+            self.out.push('\n');
+            for _ in 0..self.depth {
+                self.out.push_str(indent);
+            }
+        }
+    }
+}
+
+/// The text of an attribute, or of the text content of an element.
+fn scalar(name: &str, value: Value<'_>) -> Result<String, XmlSerError> {
+    match value {
+        Value::Scalar(value) => Ok(value),
+        Value::Nothing => Ok(String::new()),
+        _ => Err(XmlSerError::NotAScalar {
+            field: name.to_owned(),
+        }),
+    }
+}
+
+/// Escapes the characters that can't appear as they are in text, or in attribute values.
+fn escape(out: &mut String, text: &str, attribute: bool) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' if attribute => out.push_str("&quot;"),
+            // Line breaks and tabs in attributes would be read back as spaces
+            '\n' | '\r' | '\t' if attribute => {
+                let _ = write!(out, "&#{};", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+}
+
+impl<'shape> Serializer<'shape> for XmlSerializer<'shape> {
+    type Error = XmlSerError;
+
+    fn serialize_u64(&mut self, value: u64) -> Result<(), Self::Error> {
+        self.push_scalar(value)
+    }
+
+    fn serialize_u128(&mut self, value: u128) -> Result<(), Self::Error> {
+        self.push_scalar(value)
+    }
+
+    fn serialize_i64(&mut self, value: i64) -> Result<(), Self::Error> {
+        self.push_scalar(value)
+    }
+
+    fn serialize_i128(&mut self, value: i128) -> Result<(), Self::Error> {
+        self.push_scalar(value)
+    }
+
+    fn serialize_f64(&mut self, value: f64) -> Result<(), Self::Error> {
+        self.push_scalar(value)
+    }
+
+    fn serialize_bool(&mut self, value: bool) -> Result<(), Self::Error> {
+        self.push_scalar(value)
+    }
+
+    fn serialize_char(&mut self, value: char) -> Result<(), Self::Error> {
+        self.push_scalar(value)
+    }
+
+    fn serialize_str(&mut self, value: &str) -> Result<(), Self::Error> {
+        self.push_scalar(value)
+    }
+
+    fn serialize_bytes(&mut self, _value: &[u8]) -> Result<(), Self::Error> {
+        Err(XmlSerError::UnsupportedByteArray)
+    }
+
+    fn serialize_none(&mut self) -> Result<(), Self::Error> {
+        self.push_value(Value::Nothing)
+    }
+
+    fn serialize_unit(&mut self) -> Result<(), Self::Error> {
+        self.push_value(Value::Nothing)
+    }
+
+    fn serialize_unit_variant(
+        &mut self,
+        _variant_index: usize,
+        variant_name: &'shape str,
+    ) -> Result<(), Self::Error> {
+        self.push_scalar(variant_name)
+    }
+
+    fn start_object(&mut self, _len: Option<usize>) -> Result<(), Self::Error> {
+        self.stack.push(Partial::Object {
+            fields: Vec::new(),
+            next: None,
+        });
+        Ok(())
+    }
+
+    fn end_object(&mut self) -> Result<(), Self::Error> {
+        match self.stack.pop() {
+            Some(Partial::Object { fields, .. }) => self.push_value(Value::Object(fields)),
+            _ => unreachable!("end_object without start_object"),
+        }
+    }
+
+    fn begin_field(&mut self, field: Field<'shape>) -> Result<(), Self::Error> {
+        self.field = Some(field);
+        Ok(())
+    }
+
+    fn serialize_field_name(&mut self, name: &'shape str) -> Result<(), Self::Error> {
+        // Enum variant names come without `begin_field`
+        let field = self.field.take();
+        if let Some(Partial::Object { next, .. }) = self.stack.last_mut() {
+            *next = Some((name, field));
+        }
+        Ok(())
+    }
+
+    fn start_array(&mut self, _len: Option<usize>) -> Result<(), Self::Error> {
+        self.stack.push(Partial::List(Vec::new()));
+        Ok(())
+    }
+
+    fn end_array(&mut self) -> Result<(), Self::Error> {
+        match self.stack.pop() {
+            Some(Partial::List(items)) => self.push_value(Value::List(items)),
+            _ => unreachable!("end_array without start_array"),
+        }
+    }
+
+    fn start_map(&mut self, _len: Option<usize>) -> Result<(), Self::Error> {
+        self.stack.push(Partial::Map {
+            entries: Vec::new(),
+            key: None,
+            in_key: false,
+        });
+        Ok(())
+    }
+
+    fn end_map(&mut self) -> Result<(), Self::Error> {
+        match self.stack.pop() {
+            Some(Partial::Map { entries, .. }) => self.push_value(Value::Map(entries)),
+            _ => unreachable!("end_map without start_map"),
+        }
+    }
+
+    fn begin_map_key(&mut self) -> Result<(), Self::Error> {
+        if let Some(Partial::Map { in_key, .. }) = self.stack.last_mut() {
+            *in_key = true;
+        }
+        Ok(())
+    }
+
+    fn end_map_key(&mut self) -> Result<(), Self::Error> {
+        if let Some(Partial::Map { in_key, .. }) = self.stack.last_mut() {
+            *in_key = false;
+        }
+        Ok(())
+    }
+}
+
+/// Serialize a value of type `T` to an XML string, on a single line.
+///
+/// # Example
+/// ```rust
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Server {
+///     #[facet(attribute)]
+///     host: String,
+///     port: u16,
+/// }
+///
+/// let server = Server { host: "localhost".into(), port: 8080 };
+/// assert_eq!(
+///     facet_xml::to_string(&server).unwrap(),
+///     r#"<Server host="localhost"><port>8080</port></Server>"#
+/// );
+/// ```
+pub fn to_string<'a, T: Facet<'a>>(value: &'a T) -> Result<String, XmlSerError> {
+    peek_to_string(Peek::new(value))
+}
+
+/// Serialize a value of type `T` to an XML string, with each child element on a line of its
+/// own.
+pub fn to_string_pretty<'a, T: Facet<'a>>(value: &'a T) -> Result<String, XmlSerError> {
+    let mut serializer = XmlSerializer::new(T::SHAPE);
+    serialize_iterative(Peek::new(value), &mut serializer)?;
+    serializer.into_string_pretty()
+}
+
+/// Serialize a `Peek` to an XML string, on a single line.
+pub fn peek_to_string<'mem, 'facet, 'shape>(
+    peek: Peek<'mem, 'facet, 'shape>,
+) -> Result<String, XmlSerError>
+where
+    'mem: 'facet,
+{
+    let mut serializer = XmlSerializer::new(peek.shape());
+    serialize_iterative(peek, &mut serializer)?;
+    serializer.into_string()
+}
@@ -0,0 +1,329 @@
+use std::collections::HashMap;
+
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_xml::{DeserErrorKind, from_str, to_string, to_string_pretty};
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(name = "config")]
+struct Config {
+    #[facet(attribute)]
+    version: u32,
+    name: String,
+    server: Vec<Server>,
+    #[facet(default)]
+    debug: bool,
+    limits: Option<Limits>,
+    mode: Mode,
+    env: HashMap<String, String>,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Server {
+    #[facet(attribute)]
+    host: String,
+    #[facet(attribute)]
+    port: Option<u16>,
+    #[facet(text)]
+    label: String,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Limits {
+    connections: u32,
+    timeout: Option<f64>,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[repr(u8)]
+enum Mode {
+    Passive,
+    Proxy { upstream: String },
+    Rate(u32),
+}
+
+#[test]
+fn test_legacy_config() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- generated by hand -->
+<config version="3">
+    <name>edge &amp; co</name>
+    <server host="a.example" port="80">primary</server>
+    <limits>
+        <connections> 100 </connections>
+        <timeout></timeout>
+    </limits>
+    <server host="b.example"><![CDATA[<backup>]]></server>
+    <mode><Proxy><upstream>http://origin</upstream></Proxy></mode>
+    <env>
+        <HOME>/root</HOME>
+    </env>
+</config>
+"#;
+    let config: Config = from_str(xml)?;
+    assert_eq!(
+        config,
+        Config {
+            version: 3,
+            name: "edge & co".to_string(),
+            server: vec![
+                Server {
+                    host: "a.example".to_string(),
+                    port: Some(80),
+                    label: "primary".to_string(),
+                },
+                Server {
+                    host: "b.example".to_string(),
+                    port: None,
+                    label: "<backup>".to_string(),
+                },
+            ],
+            debug: false,
+            limits: Some(Limits {
+                connections: 100,
+                timeout: None,
+            }),
+            mode: Mode::Proxy {
+                upstream: "http://origin".to_string(),
+            },
+            env: HashMap::from([("HOME".to_string(), "/root".to_string())]),
+        }
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    let config = Config {
+        version: 1,
+        name: "  spaced  ".to_string(),
+        server: vec![Server {
+            host: "localhost".to_string(),
+            port: Some(8080),
+            label: "a < b".to_string(),
+        }],
+        debug: true,
+        limits: None,
+        mode: Mode::Rate(5),
+        env: HashMap::new(),
+    };
+    let xml = to_string(&config)?;
+    let back: Config = from_str(&xml).map_err(|e| e.into_owned())?;
+    assert_eq!(back, config);
+    let xml = to_string_pretty(&config)?;
+    let back: Config = from_str(&xml).map_err(|e| e.into_owned())?;
+    assert_eq!(back, config);
+
+    let config = Config {
+        mode: Mode::Passive,
+        server: Vec::new(),
+        ..config
+    };
+    let xml = to_string_pretty(&config)?;
+    let back: Config = from_str(&xml).map_err(|e| e.into_owned())?;
+    assert_eq!(back, config);
+
+    let nested = vec![vec![1u16, 2], vec![], vec![3]];
+    let xml = to_string(&nested)?;
+    let back: Vec<Vec<u16>> = from_str(&xml).map_err(|e| e.into_owned())?;
+    assert_eq!(back, nested);
+    let tuple = (1u8, "a".to_string(), true);
+    let xml = to_string(&tuple)?;
+    let back: (u8, String, bool) = from_str(&xml).map_err(|e| e.into_owned())?;
+    assert_eq!(back, tuple);
+}
+
+#[test]
+fn test_namespaces() {
+    #[derive(Facet, Debug, PartialEq)]
+    #[facet(
+        name = "soap:Envelope",
+        xmlns:soap = "http://schemas.xmlsoap.org/soap/envelope/"
+    )]
+    struct Envelope {
+        #[facet(rename = "soap:Body")]
+        body: Body,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Body {
+        #[facet(rename = "GetQuoteResponse")]
+        response: QuoteResponse,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[facet(xmlns = "urn:quotes")]
+    struct QuoteResponse {
+        #[facet(attribute)]
+        currency: String,
+        price: Vec<f64>,
+    }
+
+    // Prefixes don't have to be the ones the types use
+    let xml = r#"
+        <env:Envelope xmlns:env="http://schemas.xmlsoap.org/soap/envelope/">
+          <env:Body>
+            <q:GetQuoteResponse xmlns:q="urn:quotes" q:currency="EUR">
+              <q:price>1.5</q:price>
+              <q:price>2</q:price>
+            </q:GetQuoteResponse>
+          </env:Body>
+        </env:Envelope>
+    "#;
+    let envelope: Envelope = from_str(xml)?;
+    let expected = Envelope {
+        body: Body {
+            response: QuoteResponse {
+                currency: "EUR".to_string(),
+                price: vec![1.5, 2.0],
+            },
+        },
+    };
+    assert_eq!(envelope, expected);
+    let xml = to_string(&expected)?;
+    let back: Envelope = from_str(&xml).map_err(|e| e.into_owned())?;
+    assert_eq!(back, expected);
+}
+
+#[test]
+fn test_namespace_uris() {
+    #[derive(Facet, Debug, PartialEq)]
+    #[facet(xmlns:a = "urn:a", xmlns:b = "urn:b")]
+    struct Ids {
+        #[facet(rename = "a:id")]
+        first: u32,
+        #[facet(rename = "b:id", default)]
+        second: u32,
+        #[facet(attribute, rename = "b:kind", default)]
+        kind: String,
+    }
+
+    // Names are matched by the namespace their prefix stands for in the document
+    let xml =
+        r#"<Ids xmlns:x="urn:b" xmlns:y="urn:a" x:kind="pair"><x:id>2</x:id><y:id>1</y:id></Ids>"#;
+    let ids: Ids = from_str(xml)?;
+    assert_eq!(
+        ids,
+        Ids {
+            first: 1,
+            second: 2,
+            kind: "pair".to_string(),
+        }
+    );
+
+    // Elements in other namespaces aren't taken, whatever their prefix
+    let xml = r#"<Ids xmlns:a="urn:a" xmlns:b="urn:other" b:kind="pair"><a:id>1</a:id><b:id>2</b:id></Ids>"#;
+    let ids: Ids = from_str(xml)?;
+    assert_eq!(
+        ids,
+        Ids {
+            first: 1,
+            second: 0,
+            kind: String::new(),
+        }
+    );
+}
+
+#[test]
+fn test_borrowed_strings() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Link<'a> {
+        #[facet(attribute)]
+        href: &'a str,
+        title: &'a str,
+        #[facet(text)]
+        label: String,
+    }
+
+    let link: Link = from_str(r#"<Link href="/docs"><title>Docs</title>Read &amp; learn</Link>"#)?;
+    assert_eq!(
+        link,
+        Link {
+            href: "/docs",
+            title: "Docs",
+            label: "Read & learn".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_unknown_and_flattened_fields() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Common {
+        #[facet(attribute)]
+        id: u32,
+        created: String,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Record {
+        #[facet(flatten)]
+        common: Common,
+        #[facet(text)]
+        body: String,
+    }
+
+    let record: Record = from_str(
+        r#"<record id="4" extra="x"><created>today</created>body text<other><a/></other></record>"#,
+    )?;
+    assert_eq!(
+        record,
+        Record {
+            common: Common {
+                id: 4,
+                created: "today".to_string(),
+            },
+            body: "body text".to_string(),
+        }
+    );
+
+    #[derive(Facet, Debug)]
+    #[facet(deny_unknown_fields)]
+    struct Strict {
+        #[facet(attribute)]
+        id: u32,
+    }
+
+    let err = from_str::<Strict>(r#"<strict id="1" extra="x"/>"#).unwrap_err();
+    assert!(matches!(err.kind, DeserErrorKind::UnknownField { .. }));
+    assert_eq!(err.span.start(), 15);
+}
+
+#[test]
+fn test_errors() {
+    #[derive(Facet, Debug)]
+    struct Port {
+        port: u16,
+    }
+
+    // Syntax errors point at where the problem is
+    let err = from_str::<Port>("<Port><port>1</prot></Port>").unwrap_err();
+    assert!(matches!(err.kind, DeserErrorKind::InvalidSyntax(_)));
+    assert_eq!(err.span.start(), 13);
+
+    let err = from_str::<Port>("<Port><port>1</port>").unwrap_err();
+    assert!(matches!(err.kind, DeserErrorKind::InvalidSyntax(_)));
+    assert_eq!(err.span.start(), 1);
+
+    let err = from_str::<Port>("<Port/><Port/>").unwrap_err();
+    assert!(matches!(err.kind, DeserErrorKind::InvalidSyntax(_)));
+    assert_eq!(err.span.start(), 8);
+
+    // Elements nested too deep to read without overflowing the stack
+    let input = format!("<Port>{}", "<port>".repeat(100_000));
+    let err = from_str::<Port>(&input).unwrap_err();
+    assert!(matches!(err.kind, DeserErrorKind::InvalidSyntax(_)));
+    assert_eq!(err.span.start(), 6 + 127 * 6 + 1);
+
+    assert!(matches!(
+        from_str::<Port>("").unwrap_err().kind,
+        DeserErrorKind::InvalidSyntax(_)
+    ));
+
+    // So do errors about values
+    let input = "<Port><port>70000</port></Port>";
+    let err = from_str::<Port>(input).unwrap_err();
+    assert_eq!(&input[err.span.start()..err.span.end()], "70000");
+
+    let err = from_str::<Port>("<Port/>").unwrap_err();
+    assert!(matches!(err.kind, DeserErrorKind::ReflectError(_)));
+}
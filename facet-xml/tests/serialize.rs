@@ -0,0 +1,268 @@
+use std::collections::BTreeMap;
+
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_xml::{XmlSerError, to_string, to_string_pretty};
+
+#[test]
+fn test_attributes_children_and_text() {
+    #[derive(Facet)]
+    struct Price {
+        #[facet(attribute)]
+        currency: String,
+        #[facet(text)]
+        amount: f64,
+    }
+
+    #[derive(Facet)]
+    struct Item {
+        #[facet(attribute)]
+        id: u32,
+        #[facet(attribute)]
+        note: Option<String>,
+        name: String,
+        price: Price,
+        discount: Option<Price>,
+    }
+
+    let item = Item {
+        id: 7,
+        note: None,
+        name: "Widget".to_string(),
+        price: Price {
+            currency: "EUR".to_string(),
+            amount: 9.5,
+        },
+        discount: None,
+    };
+    assert_eq!(
+        to_string(&item)?,
+        r#"<Item id="7"><name>Widget</name><price currency="EUR">9.5</price></Item>"#
+    );
+}
+
+#[test]
+fn test_lists_repeat_elements() {
+    #[derive(Facet)]
+    #[facet(name = "library")]
+    struct Library {
+        book: Vec<Book>,
+        tag: Vec<String>,
+        shelf: Option<Vec<u16>>,
+    }
+
+    #[derive(Facet)]
+    struct Book {
+        #[facet(attribute)]
+        isbn: String,
+        title: String,
+    }
+
+    let library = Library {
+        book: vec![
+            Book {
+                isbn: "1".to_string(),
+                title: "Dune".to_string(),
+            },
+            Book {
+                isbn: "2".to_string(),
+                title: "Emma".to_string(),
+            },
+        ],
+        tag: Vec::new(),
+        shelf: Some(vec![1, 2]),
+    };
+    assert_eq!(
+        to_string(&library)?,
+        concat!(
+            "<library>",
+            r#"<book isbn="1"><title>Dune</title></book>"#,
+            r#"<book isbn="2"><title>Emma</title></book>"#,
+            "<shelf>1</shelf><shelf>2</shelf>",
+            "</library>"
+        )
+    );
+
+    // Lists that aren't in a field of their own have an element per item
+    assert_eq!(
+        to_string(&vec![vec![1u16, 2], vec![]])?,
+        "<Vec><item><item>1</item><item>2</item></item><item/></Vec>"
+    );
+    assert_eq!(
+        to_string(&(1u8, "a"))?,
+        "<value><item>1</item><item>a</item></value>"
+    );
+}
+
+#[test]
+fn test_namespaces() {
+    #[derive(Facet)]
+    #[facet(
+        name = "soap:Envelope",
+        xmlns:soap = "http://schemas.xmlsoap.org/soap/envelope/"
+    )]
+    struct Envelope {
+        #[facet(rename = "soap:Body")]
+        body: Body,
+    }
+
+    #[derive(Facet)]
+    struct Body {
+        #[facet(rename = "GetQuote")]
+        get_quote: Vec<GetQuote>,
+    }
+
+    #[derive(Facet)]
+    #[facet(xmlns = "urn:quotes", xmlns:q = "urn:quotes:extra")]
+    struct GetQuote {
+        #[facet(rename = "q:symbol")]
+        symbol: String,
+    }
+
+    let envelope = Envelope {
+        body: Body {
+            get_quote: vec![GetQuote {
+                symbol: "ACME".to_string(),
+            }],
+        },
+    };
+    assert_eq!(
+        to_string(&envelope)?,
+        concat!(
+            r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">"#,
+            "<soap:Body>",
+            r#"<GetQuote xmlns="urn:quotes" xmlns:q="urn:quotes:extra"><q:symbol>ACME</q:symbol></GetQuote>"#,
+            "</soap:Body>",
+            "</soap:Envelope>"
+        )
+    );
+}
+
+#[test]
+fn test_enums_and_maps() {
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Action {
+        Stop,
+        Say(String),
+        Move { x: i32, y: i32 },
+    }
+
+    #[derive(Facet)]
+    struct Script {
+        action: Vec<Action>,
+        env: BTreeMap<String, String>,
+    }
+
+    let script = Script {
+        action: vec![
+            Action::Stop,
+            Action::Say("hi".to_string()),
+            Action::Move { x: -1, y: 2 },
+        ],
+        env: BTreeMap::from([
+            ("HOME".to_string(), "/root".to_string()),
+            ("PATH".to_string(), "/bin".to_string()),
+        ]),
+    };
+    assert_eq!(
+        to_string(&script)?,
+        concat!(
+            "<Script>",
+            "<action>Stop</action>",
+            "<action><Say>hi</Say></action>",
+            "<action><Move><x>-1</x><y>2</y></Move></action>",
+            "<env><HOME>/root</HOME><PATH>/bin</PATH></env>",
+            "</Script>"
+        )
+    );
+}
+
+#[test]
+fn test_escaping() {
+    #[derive(Facet)]
+    struct Note {
+        #[facet(attribute)]
+        title: String,
+        #[facet(text)]
+        body: String,
+    }
+
+    let note = Note {
+        title: "\"Q&A\"\n<1>".to_string(),
+        body: "a < b && c > \"d\"".to_string(),
+    };
+    assert_eq!(
+        to_string(&note)?,
+        r#"<Note title="&quot;Q&amp;A&quot;&#10;&lt;1&gt;">a &lt; b &amp;&amp; c &gt; "d"</Note>"#
+    );
+}
+
+#[test]
+fn test_pretty() {
+    #[derive(Facet)]
+    #[facet(name = "config")]
+    struct Config {
+        server: Vec<Server>,
+        debug: bool,
+    }
+
+    #[derive(Facet)]
+    struct Server {
+        #[facet(attribute)]
+        host: String,
+        port: u16,
+        label: Label,
+    }
+
+    #[derive(Facet)]
+    struct Label {
+        #[facet(text)]
+        text: String,
+        emphasis: Option<String>,
+    }
+
+    let config = Config {
+        server: vec![Server {
+            host: "localhost".to_string(),
+            port: 8080,
+            label: Label {
+                text: "main ".to_string(),
+                emphasis: Some("one".to_string()),
+            },
+        }],
+        debug: true,
+    };
+    // Elements with text keep their children on the same line, so the text stays as it is
+    assert_eq!(
+        to_string_pretty(&config)?,
+        concat!(
+            "<config>\n",
+            "  <server host=\"localhost\">\n",
+            "    <port>8080</port>\n",
+            "    <label>main <emphasis>one</emphasis></label>\n",
+            "  </server>\n",
+            "  <debug>true</debug>\n",
+            "</config>\n"
+        )
+    );
+}
+
+#[test]
+fn test_errors() {
+    #[derive(Facet)]
+    struct Bad {
+        #[facet(attribute)]
+        list: Vec<u16>,
+    }
+
+    assert!(matches!(
+        to_string(&Bad { list: vec![1] }),
+        Err(XmlSerError::NotAScalar { field }) if field == "list"
+    ));
+    assert!(matches!(
+        to_string(&b"abc".to_vec()),
+        Err(XmlSerError::UnsupportedByteArray)
+    ));
+}
@@ -10,9 +10,11 @@ use alloc::{
     string::{String, ToString},
 };
 use error::AnyErr;
-use facet_core::{Characteristic, Def, Facet, FieldFlags, ScalarAffinity, Type, UserType};
+use facet_core::{
+    Characteristic, Def, EnumTagging, Facet, FieldFlags, ScalarAffinity, StructKind, Type, UserType,
+};
 use facet_reflect::Wip;
-use yaml_rust2::{Yaml, YamlLoader};
+use yaml_rust2::{Yaml, YamlLoader, yaml::Hash};
 
 /// Deserializes a YAML string into a value of type `T` that implements `Facet`.
 pub fn from_str<'input: 'facet, 'facet, T: Facet<'facet>>(yaml: &'input str) -> Result<T, AnyErr> {
@@ -103,7 +105,7 @@ fn deserialize_value<'facet, 'shape>(
                     .as_str()
                     .ok_or_else(|| AnyErr(format!("Expected string key, got: {}", yaml_type(k))))?;
                 let field_index = wip
-                    .field_index(k)
+                    .deserializable_field_index(k)
                     .ok_or_else(|| AnyErr(format!("Field '{}' not found", k)))?;
 
                 #[cfg(feature = "log")]
@@ -174,10 +176,10 @@ fn deserialize_value<'facet, 'shape>(
                                     field.name
                                 );
 
-                                wip = wip.put_default().map_err(|e| AnyErr(e.to_string()))?;
+                                wip = wip.put_field_default().map_err(|e| AnyErr(e.to_string()))?;
                             } else {
                                 // Simple default for non-struct types
-                                wip = wip.put_default().map_err(|e| AnyErr(e.to_string()))?;
+                                wip = wip.put_field_default().map_err(|e| AnyErr(e.to_string()))?;
                             }
                         }
 
@@ -224,6 +226,11 @@ fn deserialize_value<'facet, 'shape>(
         return Ok(wip);
     }
 
+    // `Option` may also be laid out as an enum, but it's handled through its def below
+    if matches!(shape.ty, Type::User(UserType::Enum(_))) && !matches!(shape.def, Def::Option(_)) {
+        return deserialize_as_enum(wip, value);
+    }
+
     // Then check the def system (Def) using innermost_shape instead of shape
     // This handles transparent types automatically by using the wrapped type
     match innermost_shape.def {
@@ -348,6 +355,12 @@ fn deserialize_value<'facet, 'shape>(
 
             wip = deserialize_as_map(wip, value)?;
         }
+        Def::Set(_) => {
+            #[cfg(feature = "log")]
+            log::debug!("Processing set type");
+
+            wip = deserialize_as_set(wip, value)?;
+        }
         // Enum has been moved to Type system
         _ => return Err(AnyErr(format!("Unsupported type: {:?}", shape))),
     }
@@ -390,6 +403,36 @@ fn deserialize_as_list<'facet, 'shape>(
     }
 }
 
+fn deserialize_as_set<'facet, 'shape>(
+    mut wip: Wip<'facet, 'shape>,
+    value: &Yaml,
+) -> Result<Wip<'facet, 'shape>, AnyErr> {
+    #[cfg(feature = "log")]
+    log::debug!("deserialize_as_set: shape={}", wip.shape());
+
+    if let Yaml::Array(array) = value {
+        // Start the set, this also takes care of empty sets
+        wip = wip.begin_set_insert().map_err(|e| AnyErr(e.to_string()))?;
+
+        // Process each element
+        for element in array.iter() {
+            #[cfg(feature = "log")]
+            log::debug!("Processing set element: {:?}", element);
+
+            wip = wip.push().map_err(|e| AnyErr(e.to_string()))?;
+            wip = deserialize_value(wip, element)?;
+            wip = wip.pop().map_err(|e| AnyErr(e.to_string()))?;
+        }
+
+        Ok(wip)
+    } else {
+        Err(AnyErr(format!(
+            "Expected a YAML array, got: {}",
+            yaml_type(value)
+        )))
+    }
+}
+
 fn deserialize_as_map<'facet, 'shape>(
     mut wip: Wip<'facet, 'shape>,
     value: &Yaml,
@@ -430,3 +473,228 @@ fn deserialize_as_map<'facet, 'shape>(
         )))
     }
 }
+
+fn deserialize_as_enum<'facet, 'shape>(
+    mut wip: Wip<'facet, 'shape>,
+    value: &Yaml,
+) -> Result<Wip<'facet, 'shape>, AnyErr> {
+    let shape = wip.shape();
+
+    #[cfg(feature = "log")]
+    log::debug!("deserialize_as_enum: shape={}", shape);
+
+    match shape.enum_tagging() {
+        EnumTagging::External => match value {
+            Yaml::String(name) => {
+                wip = select_variant(wip, name)?;
+                let variant = wip.selected_variant().unwrap();
+                if !variant.data.fields.is_empty() {
+                    return Err(AnyErr(format!(
+                        "Variant '{}' has fields, expected a hash with a single key",
+                        variant.name
+                    )));
+                }
+                Ok(wip)
+            }
+            Yaml::Hash(hash) if hash.len() == 1 => {
+                let (k, v) = hash.front().unwrap();
+                let name = k
+                    .as_str()
+                    .ok_or_else(|| AnyErr(format!("Expected string key, got: {}", yaml_type(k))))?;
+                wip = select_variant(wip, name)?;
+                deserialize_variant_content(wip, v)
+            }
+            _ => Err(AnyErr(format!(
+                "Expected a string or a hash with a single key for enum {}, got: {}",
+                shape,
+                yaml_type(value)
+            ))),
+        },
+        EnumTagging::Internal { tag } => {
+            let hash = value.as_hash().ok_or_else(|| {
+                AnyErr(format!("Expected a YAML hash, got: {}", yaml_type(value)))
+            })?;
+            wip = select_variant(wip, variant_tag(hash, tag)?)?;
+
+            // Everything but the tag belongs to the variant
+            let mut fields = hash.clone();
+            fields.remove(&Yaml::String(tag.to_string()));
+            let variant = wip.selected_variant().unwrap();
+            if variant.data.fields.is_empty() {
+                Ok(wip)
+            } else if variant.data.kind == StructKind::Struct || is_newtype(&wip) {
+                deserialize_variant_content(wip, &Yaml::Hash(fields))
+            } else {
+                Err(AnyErr(format!(
+                    "Internally tagged variant '{}' cannot be a tuple variant",
+                    variant.name
+                )))
+            }
+        }
+        EnumTagging::Adjacent { tag, content } => {
+            let hash = value.as_hash().ok_or_else(|| {
+                AnyErr(format!("Expected a YAML hash, got: {}", yaml_type(value)))
+            })?;
+            wip = select_variant(wip, variant_tag(hash, tag)?)?;
+            let variant = wip.selected_variant().unwrap();
+            if variant.data.fields.is_empty() {
+                return Ok(wip);
+            }
+            let content = hash
+                .get(&Yaml::String(content.to_string()))
+                .ok_or_else(|| AnyErr(format!("Missing content '{}'", content)))?;
+            deserialize_variant_content(wip, content)
+        }
+        EnumTagging::Untagged => {
+            let Type::User(UserType::Enum(ed)) = shape.ty else {
+                unreachable!()
+            };
+            for (index, _variant) in ed.variants.iter().enumerate() {
+                let attempt = Wip::alloc_shape(shape)
+                    .and_then(|wip| wip.variant(index))
+                    .map_err(AnyErr::from)
+                    .and_then(|variant_wip| {
+                        if variant_wip
+                            .selected_variant()
+                            .unwrap()
+                            .data
+                            .fields
+                            .is_empty()
+                        {
+                            // Unit variants are represented as null
+                            match value {
+                                Yaml::Null => Ok(variant_wip),
+                                _ => Err(AnyErr("Expected null".into())),
+                            }
+                        } else {
+                            deserialize_variant_content(variant_wip, value)
+                        }
+                    })
+                    .and_then(|variant_wip| variant_wip.build().map_err(AnyErr::from));
+                match attempt {
+                    Ok(heap_value) => {
+                        #[cfg(feature = "log")]
+                        log::debug!("Untagged value matched variant: {}", _variant.name);
+
+                        return wip
+                            .put_heap_value(heap_value)
+                            .map_err(|e| AnyErr(e.to_string()));
+                    }
+                    Err(_e) => {
+                        #[cfg(feature = "log")]
+                        log::debug!("Untagged value did not match {}: {}", _variant.name, _e);
+                    }
+                }
+            }
+            Err(AnyErr(format!(
+                "Data did not match any variant of untagged enum {}",
+                shape
+            )))
+        }
+    }
+}
+
+/// Get the variant name stored under `tag`.
+fn variant_tag<'a>(hash: &'a Hash, tag: &str) -> Result<&'a str, AnyErr> {
+    let name = hash
+        .get(&Yaml::String(tag.to_string()))
+        .ok_or_else(|| AnyErr(format!("Missing tag '{}'", tag)))?;
+    name.as_str().ok_or_else(|| {
+        AnyErr(format!(
+            "Expected string for tag '{}', got: {}",
+            tag,
+            yaml_type(name)
+        ))
+    })
+}
+
+fn select_variant<'facet, 'shape>(
+    wip: Wip<'facet, 'shape>,
+    name: &str,
+) -> Result<Wip<'facet, 'shape>, AnyErr> {
+    let (index, _) = wip.find_variant(name).ok_or_else(|| {
+        AnyErr(format!(
+            "Enum variant '{}' not found in {}",
+            name,
+            wip.shape()
+        ))
+    })?;
+    wip.variant(index).map_err(|e| AnyErr(e.to_string()))
+}
+
+fn is_newtype(wip: &Wip<'_, '_>) -> bool {
+    wip.selected_variant()
+        .is_some_and(|v| v.data.kind == StructKind::Tuple && v.data.fields.len() == 1)
+}
+
+/// Deserialize the content of the selected variant, without its name.
+fn deserialize_variant_content<'facet, 'shape>(
+    mut wip: Wip<'facet, 'shape>,
+    value: &Yaml,
+) -> Result<Wip<'facet, 'shape>, AnyErr> {
+    let variant = wip.selected_variant().unwrap();
+
+    if variant.data.fields.is_empty() {
+        return Ok(wip);
+    }
+
+    if is_newtype(&wip) {
+        wip = wip.field(0).map_err(|e| AnyErr(e.to_string()))?;
+        wip = deserialize_value(wip, value)?;
+        return wip.pop().map_err(|e| AnyErr(e.to_string()));
+    }
+
+    match variant.data.kind {
+        StructKind::Struct => {
+            let hash = value.as_hash().ok_or_else(|| {
+                AnyErr(format!("Expected a YAML hash, got: {}", yaml_type(value)))
+            })?;
+            for (k, v) in hash {
+                let k = k
+                    .as_str()
+                    .ok_or_else(|| AnyErr(format!("Expected string key, got: {}", yaml_type(k))))?;
+                let field_index = wip
+                    .deserializable_field_index(k)
+                    .ok_or_else(|| AnyErr(format!("Field '{}' not found", k)))?;
+                wip = wip
+                    .field(field_index)
+                    .map_err(|e| AnyErr(format!("Field '{}' error: {}", k, e)))?;
+                wip = deserialize_value(wip, v)?;
+                wip = wip.pop().map_err(|e| AnyErr(e.to_string()))?;
+            }
+
+            for (index, field) in variant.data.fields.iter().enumerate() {
+                let is_set = wip.is_field_set(index).map_err(|e| AnyErr(e.to_string()))?;
+                if !is_set && field.flags.contains(FieldFlags::DEFAULT) {
+                    wip = wip.field(index).map_err(|e| AnyErr(e.to_string()))?;
+                    wip = match field.vtable.default_fn {
+                        Some(default_in_place_fn) => wip.put_from_fn(default_in_place_fn),
+                        None => wip.put_field_default(),
+                    }
+                    .map_err(|e| AnyErr(e.to_string()))?;
+                    wip = wip.pop().map_err(|e| AnyErr(e.to_string()))?;
+                }
+            }
+        }
+        _ => {
+            let array = value.as_vec().ok_or_else(|| {
+                AnyErr(format!("Expected a YAML array, got: {}", yaml_type(value)))
+            })?;
+            if array.len() != variant.data.fields.len() {
+                return Err(AnyErr(format!(
+                    "Expected {} elements for variant '{}', got {}",
+                    variant.data.fields.len(),
+                    variant.name,
+                    array.len()
+                )));
+            }
+            for (index, element) in array.iter().enumerate() {
+                wip = wip.field(index).map_err(|e| AnyErr(e.to_string()))?;
+                wip = deserialize_value(wip, element)?;
+                wip = wip.pop().map_err(|e| AnyErr(e.to_string()))?;
+            }
+        }
+    }
+
+    Ok(wip)
+}
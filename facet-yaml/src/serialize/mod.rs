@@ -229,9 +229,9 @@ impl<'shape> Serializer<'shape> for YamlSerializer<'shape> {
     fn serialize_unit_variant(
         &mut self,
         _variant_index: usize,
-        _variant_name: &'shape str,
+        variant_name: &'shape str,
     ) -> Result<(), Self::Error> {
-        todo!()
+        self.write_value(Yaml::String(variant_name.to_string()))
     }
 
     fn start_object(&mut self, _len: Option<usize>) -> Result<(), Self::Error> {
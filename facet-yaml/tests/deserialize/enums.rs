@@ -0,0 +1,107 @@
+use facet::Facet;
+use facet_testhelpers::test;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[test]
+fn test_deserialize_external_enum() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    enum Shape {
+        Empty,
+        Pair(i32, String),
+        Rect { w: i32, h: i32 },
+    }
+
+    assert_eq!(facet_yaml::from_str::<Shape>("Empty")?, Shape::Empty);
+    assert_eq!(
+        facet_yaml::from_str::<Shape>("Pair: [1, one]")?,
+        Shape::Pair(1, "one".to_string())
+    );
+    assert_eq!(
+        facet_yaml::from_str::<Shape>("Rect:\n  w: 1\n  h: 2")?,
+        Shape::Rect { w: 1, h: 2 }
+    );
+    assert!(facet_yaml::from_str::<Shape>("Circle").is_err());
+}
+
+#[test]
+fn test_deserialize_internally_tagged_enum() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[facet(tag = "kind")]
+    #[repr(u8)]
+    enum Shape {
+        Empty,
+        Point(Point),
+        Rect { w: i32, h: i32 },
+    }
+
+    assert_eq!(facet_yaml::from_str::<Shape>("kind: Empty")?, Shape::Empty);
+    assert_eq!(
+        facet_yaml::from_str::<Shape>("x: 1\nkind: Point\ny: 2")?,
+        Shape::Point(Point { x: 1, y: 2 })
+    );
+    assert_eq!(
+        facet_yaml::from_str::<Shape>("kind: Rect\nw: 1\nh: 2")?,
+        Shape::Rect { w: 1, h: 2 }
+    );
+
+    let err = facet_yaml::from_str::<Shape>("w: 1\nh: 2").unwrap_err();
+    assert!(err.to_string().contains("Missing tag 'kind'"), "{err}");
+}
+
+#[test]
+fn test_deserialize_adjacently_tagged_enum() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[facet(tag = "t", content = "c")]
+    #[repr(u8)]
+    enum Shape {
+        Empty,
+        Pair(i32, String),
+        Rect { w: i32, h: i32 },
+    }
+
+    assert_eq!(facet_yaml::from_str::<Shape>("t: Empty")?, Shape::Empty);
+    assert_eq!(
+        facet_yaml::from_str::<Shape>("c: [1, one]\nt: Pair")?,
+        Shape::Pair(1, "one".to_string())
+    );
+    assert_eq!(
+        facet_yaml::from_str::<Shape>("t: Rect\nc:\n  w: 1\n  h: 2")?,
+        Shape::Rect { w: 1, h: 2 }
+    );
+}
+
+#[test]
+fn test_deserialize_untagged_enum() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[facet(untagged)]
+    #[repr(u8)]
+    enum Value {
+        Nothing,
+        Number(i64),
+        List(Vec<String>),
+        Point(Point),
+    }
+
+    assert_eq!(facet_yaml::from_str::<Value>("~")?, Value::Nothing);
+    assert_eq!(facet_yaml::from_str::<Value>("12")?, Value::Number(12));
+    assert_eq!(
+        facet_yaml::from_str::<Value>("[a, b]")?,
+        Value::List(vec!["a".to_string(), "b".to_string()])
+    );
+    assert_eq!(
+        facet_yaml::from_str::<Value>("x: 1\ny: 2")?,
+        Value::Point(Point { x: 1, y: 2 })
+    );
+
+    let err = facet_yaml::from_str::<Value>("a: b").unwrap_err();
+    assert!(
+        err.to_string().contains("did not match any variant"),
+        "{err}"
+    );
+}
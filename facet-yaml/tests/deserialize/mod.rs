@@ -1,6 +1,8 @@
 mod datetime;
 mod default;
+mod enums;
 mod from_str;
 mod lists;
 mod maps;
+mod sets;
 mod transparent;
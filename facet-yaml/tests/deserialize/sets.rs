@@ -0,0 +1,20 @@
+use facet_testhelpers::test;
+use std::collections::{BTreeSet, HashSet};
+
+#[test]
+fn test_deserialize_string_set() {
+    let yaml = r#"
+        - one
+        - two
+        - one
+    "#;
+
+    let set: HashSet<String> = facet_yaml::from_str(yaml)?;
+    assert_eq!(set, HashSet::from(["one".to_string(), "two".to_string()]));
+}
+
+#[test]
+fn test_deserialize_u64_set() {
+    let set: BTreeSet<u64> = facet_yaml::from_str("[3, 1, 2]")?;
+    assert_eq!(set, BTreeSet::from([1, 2, 3]));
+}
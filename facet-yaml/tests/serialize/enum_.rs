@@ -0,0 +1,147 @@
+//! Tests for YAML enum values.
+
+use eyre::Result;
+use facet::Facet;
+
+use crate::assert_serialize;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[test]
+fn test_external_enum() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        a: Shape,
+        b: Shape,
+        c: Shape,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    enum Shape {
+        Empty,
+        Point(Point),
+        Rect { w: i32, h: i32 },
+    }
+
+    assert_serialize!(
+        Root,
+        Root {
+            a: Shape::Empty,
+            b: Shape::Point(Point { x: 1, y: 2 }),
+            c: Shape::Rect { w: 3, h: 4 },
+        },
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_internally_tagged_enum() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        a: Shape,
+        b: Shape,
+        c: Shape,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[facet(tag = "type")]
+    #[repr(u8)]
+    enum Shape {
+        Empty,
+        Point(Point),
+        Rect { w: i32, h: i32 },
+    }
+
+    assert_eq!(
+        facet_yaml::to_string(&Shape::Rect { w: 3, h: 4 })?,
+        "---\ntype: Rect\nw: 3\nh: 4"
+    );
+    assert_serialize!(
+        Root,
+        Root {
+            a: Shape::Empty,
+            b: Shape::Point(Point { x: 1, y: 2 }),
+            c: Shape::Rect { w: 3, h: 4 },
+        },
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_adjacently_tagged_enum() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        a: Shape,
+        b: Shape,
+        c: Shape,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[facet(tag = "t", content = "c")]
+    #[repr(u8)]
+    enum Shape {
+        Empty,
+        Point(Point),
+        Rect { w: i32, h: i32 },
+    }
+
+    assert_eq!(
+        facet_yaml::to_string(&Shape::Point(Point { x: 1, y: 2 }))?,
+        "---\nt: Point\nc:\n  x: 1\n  \"y\": 2"
+    );
+    assert_serialize!(
+        Root,
+        Root {
+            a: Shape::Empty,
+            b: Shape::Point(Point { x: 1, y: 2 }),
+            c: Shape::Rect { w: 3, h: 4 },
+        },
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_untagged_enum() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        a: Value,
+        b: Value,
+        c: Value,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[facet(untagged)]
+    #[repr(u8)]
+    enum Value {
+        Number(i64),
+        Text(String),
+        Point(Point),
+    }
+
+    assert_serialize!(
+        Root,
+        Root {
+            a: Value::Number(1),
+            b: Value::Text("two".to_string()),
+            c: Value::Point(Point { x: 3, y: 4 }),
+        },
+    );
+
+    Ok(())
+}
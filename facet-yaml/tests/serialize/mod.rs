@@ -1,7 +1,9 @@
 mod basic;
+mod enum_;
 mod list;
 mod map;
 mod scalar;
+mod set;
 mod struct_;
 
 /// Assert that the YAML used to serialize a value can be used to deserialize the value too.
@@ -0,0 +1,34 @@
+//! Tests for YAML values to sets.
+
+use std::collections::BTreeSet;
+
+use eyre::Result;
+use facet::Facet;
+
+use crate::assert_serialize;
+
+#[test]
+fn test_scalar_set() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        values: BTreeSet<i32>,
+    }
+
+    assert_serialize!(
+        Root,
+        Root {
+            values: BTreeSet::new()
+        },
+    );
+
+    assert_serialize!(
+        Root,
+        Root {
+            values: [3, 1, -1].into()
+        },
+    );
+
+    Ok(())
+}
@@ -78,6 +78,18 @@ pub use facet_core::*;
 ///
 /// * `skip_serializing_if = ".."` Ignore when serializing if the function returns `true`.
 ///
+/// * `skip_deserializing` Never read when deserializing, always use the default value instead.
+///
+/// * `skip` Shorthand for `skip_serializing` and `skip_deserializing`.
+///
+/// * `alias = ".."` Also accept this name when deserializing. Can be repeated.
+///
+/// * `serialize_with = ..` Serialize the value returned by the given `fn(&T) -> U` instead.
+///
+/// * `deserialize_with = ..` Deserialize a `U` and convert it with the given `fn(U) -> Result<T, E>`.
+///
+/// * `proxy = ..` Serialize and deserialize through the given type, using `From<&T>` and `TryFrom<Proxy>`.
+///
 /// # Variant Attributes
 ///
 /// ```rust
@@ -96,6 +108,8 @@ pub use facet_core::*;
 ///
 /// * `skip_serializing_if = ".."` Ignore when serializing if the function returns `true`.
 ///
+/// * `alias = ".."` Also accept this name when deserializing. Can be repeated.
+///
 /// # Examples
 ///
 /// **TODO**.